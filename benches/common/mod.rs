@@ -0,0 +1,85 @@
+//! Synthetic feeds for the benchmarks
+//!
+//! Events cycle through the shapes real feeds mix together: TZID times with
+//! a video link in the description, UTC times, floating times, all-day
+//! events and long folded descriptions.
+
+use chrono::{Duration, TimeZone, Utc};
+use std::fmt::Write;
+
+const VTIMEZONE: &str = "BEGIN:VTIMEZONE\r
+TZID:America/New_York\r
+BEGIN:STANDARD\r
+DTSTART:19701101T020000\r
+RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU\r
+TZOFFSETFROM:-0400\r
+TZOFFSETTO:-0500\r
+TZNAME:EST\r
+END:STANDARD\r
+BEGIN:DAYLIGHT\r
+DTSTART:19700308T020000\r
+RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU\r
+TZOFFSETFROM:-0500\r
+TZOFFSETTO:-0400\r
+TZNAME:EDT\r
+END:DAYLIGHT\r
+END:VTIMEZONE\r
+";
+
+/// Start of the generated feed; events follow every 37 minutes
+pub fn feed_start() -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap()
+}
+
+/// An ICS document with `count` events
+pub fn generate_feed(count: usize) -> String {
+    let mut ics = String::from("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//OpenChime//Bench//EN\r\n");
+    ics.push_str(VTIMEZONE);
+
+    for i in 0..count {
+        let start = feed_start() + Duration::minutes(37 * i as i64);
+        let end = start + Duration::minutes(30);
+        ics.push_str("BEGIN:VEVENT\r\n");
+        write!(ics, "UID:bench-{}@example.com\r\n", i).unwrap();
+
+        match i % 5 {
+            0 => {
+                let local = start.with_timezone(&chrono_tz::America::New_York);
+                write!(ics, "SUMMARY:Standup {}\r\n", i).unwrap();
+                write!(ics, "DTSTART;TZID=America/New_York:{}\r\n", local.format("%Y%m%dT%H%M%S")).unwrap();
+                write!(ics, "DTEND;TZID=America/New_York:{}\r\n", (local + Duration::minutes(30)).format("%Y%m%dT%H%M%S")).unwrap();
+                write!(ics, "DESCRIPTION:Join Zoom Meeting\\nhttps://us02web.zoom.us/j/{}\r\n", 80000000000u64 + i as u64).unwrap();
+            }
+            1 => {
+                write!(ics, "SUMMARY:Design review {}\r\n", i).unwrap();
+                write!(ics, "DTSTART:{}\r\n", start.format("%Y%m%dT%H%M%SZ")).unwrap();
+                write!(ics, "DTEND:{}\r\n", end.format("%Y%m%dT%H%M%SZ")).unwrap();
+                ics.push_str("DESCRIPTION:https://meet.google.com/abc-defg-hij\r\n");
+            }
+            2 => {
+                write!(ics, "SUMMARY:Focus time {}\r\n", i).unwrap();
+                write!(ics, "DTSTART:{}\r\n", start.format("%Y%m%dT%H%M%S")).unwrap();
+                write!(ics, "DTEND:{}\r\n", end.format("%Y%m%dT%H%M%S")).unwrap();
+            }
+            3 => {
+                let date = start.date_naive();
+                write!(ics, "SUMMARY:Offsite {}\r\n", i).unwrap();
+                write!(ics, "DTSTART;VALUE=DATE:{}\r\n", date.format("%Y%m%d")).unwrap();
+                write!(ics, "DTEND;VALUE=DATE:{}\r\n", date.succ_opt().unwrap().format("%Y%m%d")).unwrap();
+            }
+            _ => {
+                write!(ics, "SUMMARY:Quarterly planning {}\r\n", i).unwrap();
+                write!(ics, "DTSTART:{}\r\n", start.format("%Y%m%dT%H%M%SZ")).unwrap();
+                write!(ics, "DTEND:{}\r\n", end.format("%Y%m%dT%H%M%SZ")).unwrap();
+                ics.push_str("DESCRIPTION:Agenda: review last quarter's numbers\\, agree on the roadmap \r\n");
+                ics.push_str(" and assign owners. Dial-in details are in the team handbook\\; bring \r\n");
+                ics.push_str(" questions. https://teams.microsoft.com/l/meetup-join/19%3ameeting_bench\r\n");
+            }
+        }
+
+        ics.push_str("END:VEVENT\r\n");
+    }
+
+    ics.push_str("END:VCALENDAR\r\n");
+    ics
+}
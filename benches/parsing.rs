@@ -0,0 +1,26 @@
+//! ICS parsing throughput
+//!
+//! Run with `cargo bench --bench parsing`.
+
+mod common;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use openchime::calendar::common::{parse_ics_events_with, ParseOptions};
+
+fn parse_feeds(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_ics");
+    group.sample_size(20);
+
+    for count in [1_000, 10_000, 50_000] {
+        let feed = common::generate_feed(count);
+        group.throughput(Throughput::Bytes(feed.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &feed, |b, feed| {
+            b.iter(|| parse_ics_events_with(black_box(feed), "bench", &ParseOptions::default()).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, parse_feeds);
+criterion_main!(benches);
@@ -0,0 +1,85 @@
+//! Storing synced events and reading them back for alerts
+//!
+//! Run with `cargo bench --bench sync`. Each iteration works on a fresh
+//! on-disk database so the numbers include SQLite's write path.
+
+mod common;
+
+use chrono::Duration;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use openchime::calendar::common::sync_from_source;
+use openchime::calendar::source::MockCalendarSource;
+use openchime::utils::clock::MockClock;
+use openchime::{get_upcoming_events, Account, Database};
+use std::time::Instant;
+use tempfile::TempDir;
+use tokio::runtime::Runtime;
+
+/// A database in its own temp dir, with one account to sync into
+async fn setup() -> (TempDir, Database, Account) {
+    let dir = TempDir::new().unwrap();
+    let db = Database::open(&dir.path().join("bench.db")).await.unwrap();
+    let mut account = Account::new_proton(
+        "Bench Calendar".to_string(),
+        "https://calendar.example.com/bench.ics".to_string(),
+    );
+    account.id = Some(db.add_account(&account).await.unwrap());
+    (dir, db, account)
+}
+
+fn upsert(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("sync_upsert");
+    group.sample_size(10);
+
+    for count in [1_000, 10_000] {
+        let source = MockCalendarSource::new(common::generate_feed(count));
+
+        // First sync of a feed: every event is inserted
+        group.bench_with_input(BenchmarkId::new("initial", count), &source, |b, source| {
+            b.to_async(&rt).iter_custom(|iters| async move {
+                let mut elapsed = std::time::Duration::ZERO;
+                for _ in 0..iters {
+                    let (_dir, db, account) = setup().await;
+                    let started = Instant::now();
+                    sync_from_source(source, &account, &db.pool).await.unwrap();
+                    elapsed += started.elapsed();
+                }
+                elapsed
+            })
+        });
+
+        // Routine re-sync: every event is already stored and unchanged
+        group.bench_with_input(BenchmarkId::new("unchanged", count), &source, |b, source| {
+            let (_dir, db, account) = rt.block_on(setup());
+            rt.block_on(sync_from_source(source, &account, &db.pool)).unwrap();
+            b.to_async(&rt).iter(|| async {
+                sync_from_source(source, &account, &db.pool).await.unwrap();
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn upcoming(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("get_upcoming_events");
+
+    for count in [1_000, 10_000] {
+        let (_dir, db, account) = rt.block_on(setup());
+        let source = MockCalendarSource::new(common::generate_feed(count));
+        rt.block_on(sync_from_source(&source, &account, &db.pool)).unwrap();
+
+        // Midway through the feed, so the window is surrounded by events
+        let clock = MockClock::new(common::feed_start() + Duration::minutes(37 * count as i64 / 2));
+        group.bench_function(BenchmarkId::from_parameter(count), |b| {
+            b.to_async(&rt).iter(|| get_upcoming_events(&db.pool, &clock, Duration::minutes(35)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, upsert, upcoming);
+criterion_main!(benches);
@@ -0,0 +1,54 @@
+//! Single DTSTART/DTEND values, including dates at the edges of chrono's
+//! range, DST gaps and unknown TZIDs
+#![no_main]
+
+use arbitrary::Arbitrary;
+use chrono::{DateTime, NaiveDate};
+use icalendar::{CalendarDateTime, DatePerhapsTime};
+use libfuzzer_sys::fuzz_target;
+use openchime::calendar::common::{parse_ical_datetime, parse_ical_datetime_with, FloatingTime};
+
+#[derive(Debug, Arbitrary)]
+enum Input {
+    Date { days_from_ce: i32 },
+    Utc { secs: i64 },
+    Floating { secs: i64, zone: u16 },
+    Zoned { secs: i64, zone: u16, unknown_tzid: Option<String> },
+}
+
+fn zone(index: u16) -> chrono_tz::Tz {
+    chrono_tz::TZ_VARIANTS[index as usize % chrono_tz::TZ_VARIANTS.len()]
+}
+
+fuzz_target!(|input: Input| {
+    let (value, floating) = match input {
+        Input::Date { days_from_ce } => match NaiveDate::from_num_days_from_ce_opt(days_from_ce) {
+            Some(date) => (DatePerhapsTime::Date(date), FloatingTime::Local),
+            None => return,
+        },
+        Input::Utc { secs } => match DateTime::from_timestamp(secs, 0) {
+            Some(dt) => (DatePerhapsTime::DateTime(CalendarDateTime::Utc(dt)), FloatingTime::Local),
+            None => return,
+        },
+        Input::Floating { secs, zone: index } => match DateTime::from_timestamp(secs, 0) {
+            Some(dt) => (
+                DatePerhapsTime::DateTime(CalendarDateTime::Floating(dt.naive_utc())),
+                FloatingTime::Zone(zone(index)),
+            ),
+            None => return,
+        },
+        Input::Zoned { secs, zone: index, unknown_tzid } => match DateTime::from_timestamp(secs, 0) {
+            Some(dt) => {
+                let tzid = unknown_tzid.unwrap_or_else(|| zone(index).name().to_string());
+                (
+                    DatePerhapsTime::DateTime(CalendarDateTime::WithTimezone { date_time: dt.naive_utc(), tzid }),
+                    FloatingTime::Zone(zone(index)),
+                )
+            }
+            None => return,
+        },
+    };
+
+    let _ = parse_ical_datetime(&value);
+    let _ = parse_ical_datetime_with(&value, &floating);
+});
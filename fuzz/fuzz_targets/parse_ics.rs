@@ -0,0 +1,20 @@
+//! Whole feeds: arbitrary bytes through the ICS parser and event conversion
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use openchime::calendar::common::{parse_ics_events_with, FloatingTime, ParseOptions};
+
+fuzz_target!(|data: &[u8]| {
+    // Fetched feeds reach the parser as (lossily decoded) text
+    let ics = String::from_utf8_lossy(data);
+
+    let options = ParseOptions {
+        floating: FloatingTime::Zone(chrono_tz::America::New_York),
+        ..ParseOptions::default()
+    };
+    if let Ok(events) = parse_ics_events_with(&ics, "fuzz", &options) {
+        for event in events {
+            assert!(!event.external_id.as_str().is_empty());
+        }
+    }
+});
@@ -1,6 +1,9 @@
 #![allow(dead_code)]
-use crate::{models::{CalendarEvent, Account}, calendar, AppState};
+use crate::{models::{merge_duplicates, Account, CalendarEvent, EventChanges, EventId, MAX_CALENDAR_REMINDER_MINUTES}, calendar, AppState};
 use crate::audio::AlertType;
+use crate::error::{AppError, AppResult};
+use crate::utils::clock::Clock;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::time::{sleep, Duration};
 use log::{info, error, warn, debug};
@@ -9,15 +12,41 @@ use tokio::sync::mpsc::Sender;
 
 #[derive(Debug, Clone)]
 pub enum MonitorEvent {
-    AlertTriggered(CalendarEvent),
-    SyncCompleted { added: usize, updated: usize },
+    /// An alert fired, of the given kind; carries the checklist items still
+    /// to do when it's an early warning (see `carries_checklist`)
+    AlertTriggered(Box<CalendarEvent>, AlertType, Vec<String>),
+    /// A background sync finished; carries the rows it added, changed or removed
+    SyncCompleted(EventChanges),
+    /// Accounts whose syncs are being skipped after repeated failures, sent
+    /// after every background sync (empty once they recover)
+    SyncStalled(Vec<StalledSync>),
     Error(String),
 }
 
+/// Seconds the monitor sleeps between cycles
+const CYCLE_SECS: u64 = 30;
+
+/// How much later than planned a cycle can run before the gap is taken for
+/// the machine having been asleep
+const SLEEP_GAP_MINUTES: i64 = 2;
+
+/// When the machine went to sleep, if the time between the end of the last
+/// cycle and `now` is far longer than the monitor ever sleeps. The wall clock
+/// is what shows this: the monotonic clock stops during suspend on Linux and
+/// macOS.
+pub fn resumed_from_sleep(last_cycle_end: chrono::DateTime<Utc>, now: chrono::DateTime<Utc>) -> Option<chrono::DateTime<Utc>> {
+    let expected = chrono::Duration::seconds(CYCLE_SECS as i64) + chrono::Duration::minutes(SLEEP_GAP_MINUTES);
+    (now - last_cycle_end > expected).then_some(last_cycle_end)
+}
+
 pub async fn monitor_meetings(state: Arc<AppState>, sender: Option<Sender<MonitorEvent>>) {
     info!("Starting meeting monitor loop");
 
-    let mut last_sync = Utc::now();
+    let mut schedule = SyncSchedule::new(state.clock.now());
+    let mut wake_lock = crate::wake_lock::WakeLock::default();
+    let mut connectivity = crate::connectivity::Connectivity::default();
+    let mut zone = crate::zone_watch::ZoneWatch::new(state.clock.now());
+    let mut last_cycle_end = state.clock.now();
 
     loop {
         // Check for shutdown signal
@@ -26,21 +55,39 @@ pub async fn monitor_meetings(state: Arc<AppState>, sender: Option<Sender<Monito
             break;
         }
 
-        match monitor_cycle(&state, &mut last_sync, &sender).await {
+        // After a suspend the calendars are stale and alerts may have been missed
+        let asleep_since = resumed_from_sleep(last_cycle_end, state.clock.now());
+        if let Some(since) = asleep_since {
+            info!("Resumed after {} minutes asleep; syncing and catching up on alerts", (state.clock.now() - since).num_minutes());
+            schedule.sync_all_now();
+        }
+
+        // Floating times were stored as read in the old local offset
+        if zone.update(state.clock.now()) {
+            match crate::database::accounts::forget_parsed_feeds(&state.db.pool).await {
+                Ok(()) => schedule.sync_all_now(),
+                Err(e) => warn!("Failed to schedule re-expansion after the clocks changed: {}", e),
+            }
+        }
+
+        match monitor_cycle(&state, &mut schedule, &mut wake_lock, &mut connectivity, asleep_since, &sender).await {
             Ok(_) => {
                 debug!("Monitor cycle completed successfully");
             }
             Err(e) => {
                 error!("Error in monitor cycle: {}", e);
                 if let Some(tx) = &sender {
-                    let _ = tx.send(MonitorEvent::Error(e.to_string())).await;
+                    // The UI may display this; keep database/network details in the log
+                    let _ = tx.send(MonitorEvent::Error(e.to_safe_string())).await;
                 }
             }
         }
 
-        // Sleep for 30 seconds between checks, but wake on shutdown
+        last_cycle_end = state.clock.now();
+
+        // Sleep between checks, but wake on shutdown
         tokio::select! {
-            _ = sleep(Duration::from_secs(30)) => {
+            _ = sleep(Duration::from_secs(CYCLE_SECS)) => {
                 // Normal sleep completed, continue loop
             }
             _ = state.shutdown.cancelled() => {
@@ -53,20 +100,73 @@ pub async fn monitor_meetings(state: Arc<AppState>, sender: Option<Sender<Monito
     info!("Meeting monitor loop stopped gracefully");
 }
 
-async fn monitor_cycle(state: &AppState, last_sync: &mut chrono::DateTime<Utc>, sender: &Option<Sender<MonitorEvent>>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let now = Utc::now();
+async fn monitor_cycle(
+    state: &AppState,
+    schedule: &mut SyncSchedule,
+    wake_lock: &mut crate::wake_lock::WakeLock,
+    connectivity: &mut crate::connectivity::Connectivity,
+    asleep_since: Option<chrono::DateTime<Utc>>,
+    sender: &Option<Sender<MonitorEvent>>,
+) -> AppResult<()> {
+    let now = state.clock.now();
+
+    // Re-read settings every cycle so changes (e.g. sync interval) apply without a restart
+    let settings = state.db.get_settings().await?;
+
+    // Before syncing, which can take a while: the machine mustn't doze off mid-cycle
+    if settings.keep_awake_minutes > 0 {
+        let soon = get_upcoming_events(&state.db.pool, state.clock.as_ref(), chrono::Duration::minutes(settings.keep_awake_minutes as i64)).await?;
+        match crate::wake_lock::keep_awake_until(&soon, now, settings.keep_awake_minutes) {
+            Some(until) => wake_lock.hold_until(until, now),
+            None => wake_lock.release(),
+        }
+    } else {
+        wake_lock.release();
+    }
     
-    // Check if we need to sync calendars (every 5 minutes)
-    if (now - *last_sync).num_seconds() >= 300 {
-        info!("Triggering calendar sync");
-        match sync_calendars(state).await {
-            Ok(stats) => {
-                *last_sync = now;
+    // Alerts missed while asleep are overdue already; catch up on them
+    // before syncing, which can take a while
+    if asleep_since.is_some() {
+        alert_due_events(state, &settings, asleep_since, sender).await?;
+    }
+
+    // The accounts whose interval has passed
+    let accounts = crate::database::accounts::get_syncable(&state.db.pool).await?;
+    let mut due = schedule.due(accounts.clone(), now, settings.sync_interval_duration());
+
+    // Syncing while offline would only trip the circuit breakers; once the
+    // network is back, every calendar syncs straight away. The network is
+    // only probed when there's a sync to make or while it's down, by trying
+    // the servers about to be synced.
+    if !settings.hold_syncs_offline {
+        connectivity.update(true);
+    } else if !accounts.is_empty() && connectivity.should_check(!due.is_empty()) {
+        let probed = if due.is_empty() { &accounts } else { &due };
+        if connectivity.update(crate::connectivity::check(probed).await) {
+            crate::utils::circuit_breaker::reset_all_circuit_breakers().await;
+            schedule.sync_all_now();
+            due = schedule.due(accounts, now, settings.sync_interval_duration());
+        }
+        if !connectivity.is_online() {
+            due.clear();
+        }
+    }
+    if !due.is_empty() {
+        info!("Triggering calendar sync of {} account(s)", due.len());
+        let synced = sync_accounts(state, &due).await;
+        if let Some(tx) = sender {
+            match stalled_syncs(&state.db.pool).await {
+                Ok(stalled) => {
+                    let _ = tx.send(MonitorEvent::SyncStalled(stalled)).await;
+                }
+                Err(e) => warn!("Failed to check for stalled syncs: {}", e),
+            }
+        }
+        match synced {
+            Ok(changes) => {
+                schedule.record(&due, now);
                 if let Some(tx) = sender {
-                    let _ = tx.send(MonitorEvent::SyncCompleted { 
-                        added: stats.0, 
-                        updated: stats.1 
-                    }).await;
+                    let _ = tx.send(MonitorEvent::SyncCompleted(changes)).await;
                 }
             }
             Err(e) => {
@@ -76,69 +176,286 @@ async fn monitor_cycle(state: &AppState, last_sync: &mut chrono::DateTime<Utc>,
         }
     }
     
+    alert_due_events(state, &settings, asleep_since, sender).await
+}
+
+/// Chime for every event with an alert due, or missed while the machine
+/// was asleep since `asleep_since`
+async fn alert_due_events(
+    state: &AppState,
+    settings: &crate::models::Settings,
+    asleep_since: Option<chrono::DateTime<Utc>>,
+    sender: &Option<Sender<MonitorEvent>>,
+) -> AppResult<()> {
     // Get upcoming events that need alerts
-    let events_needing_alerts = get_upcoming_events(&state.db.pool).await?;
-    let settings = state.db.get_settings().await?;
+    let events_needing_alerts = alertable_events(&state.db.pool, state.clock.as_ref(), settings).await?;
     
-    for event in events_needing_alerts {
-        if let Some((threshold, alert_type)) = check_alert_thresholds(&event, &settings) {
-            info!("Triggering {}m alert for event: {}", threshold, event.title);
+    let shared_accounts = crate::database::accounts::shared_ids(&state.db.pool).await?;
+    let team_settings = settings.for_team_calendar();
+
+    // A meeting synced from several calendars chimes once
+    for merged in merge_duplicates(&events_needing_alerts) {
+        // Team calendars only chime at the start, unless the meeting is in one of mine too
+        let event_settings = if merged.account_ids.iter().all(|id| shared_accounts.contains(id)) {
+            &team_settings
+        } else {
+            settings
+        };
+        let event = merged.event;
+        let due = next_alert(&event, event_settings, state.clock.as_ref()).or_else(|| {
+            let since = asleep_since?;
+            missed_alert(&event, event_settings, since, state.clock.as_ref())
+                .map(|(threshold, alert_type)| (AlertTrigger::Threshold(threshold), alert_type))
+        });
+        if let Some((trigger, alert_type)) = due {
+            match trigger {
+                AlertTrigger::Threshold(threshold) => info!("Triggering {}m alert for event: {}", threshold, event.title),
+                AlertTrigger::SnoozeElapsed => info!("Snooze elapsed for event: {}", event.title),
+            }
             
             // Play alert sound
             if let Err(e) = play_alert_sound(&event, &state, alert_type.clone()).await {
                 warn!("Failed to play alert sound: {}", e);
             }
             
+            // Preparation reminders ride along with the early warnings
+            let to_prepare = if carries_checklist(trigger) {
+                crate::database::checklists::unchecked(&state.db.pool, &merged.ids).await?
+            } else {
+                Vec::new()
+            };
+            if !to_prepare.is_empty() {
+                info!("Still to prepare for {}: {}", event.title, to_prepare.join("; "));
+            }
+
             // Notify UI via channel
             if let Some(tx) = sender {
-                let _ = tx.send(MonitorEvent::AlertTriggered(event.clone())).await;
+                let _ = tx.send(MonitorEvent::AlertTriggered(Box::new(event.clone()), alert_type, to_prepare)).await;
             }
             
-            // Update last_alert_threshold in DB
-            sqlx::query("UPDATE events SET last_alert_threshold = ? WHERE id = ?")
+            for event_id in merged.ids {
+                record_alert(&state.db.pool, event_id, trigger).await?;
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+// Alert state machine
+//
+// Each event's alert state is `last_alert_threshold` (lowest threshold already
+// alerted, NULL if none), `last_snoozed_at` (pending snooze, NULL if none),
+// `snoozed_until` (that snooze's end, if not `snooze_interval` after it) and
+// `is_dismissed`. Transitions:
+//
+// | From                       | Input                               | To                                                  |
+// |----------------------------|-------------------------------------|-----------------------------------------------------|
+// | last = None or T           | enabled threshold t < T reached     | alert; last = t; pending snooze cleared             |
+// | snoozed at S               | snooze_interval elapsed since S     | alert; pending snooze cleared; last unchanged       |
+// | snoozed at S until U       | U reached                           | alert; pending snooze cleared; last unchanged       |
+// | snoozed at S               | threshold t < last reached first    | alert as a threshold (snooze is superseded)         |
+// | alerted                    | user snoozes (`events::snooze`)     | last unchanged, so later thresholds stay armed      |
+// | alerted                    | user snoozes all (`snooze_all`)     | snoozed until U; events at the snooze limit skipped |
+// | any                        | user dismisses                      | dismissed: never alerts again                       |
+// | any                        | manual alert                        | sound only; no state change                         |
+
+/// Why an event is alerting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertTrigger {
+    /// The event crossed this many minutes before its start
+    Threshold(i32),
+    /// A snooze on an earlier alert ran out
+    SnoozeElapsed,
+}
+
+/// The alert an event is due for right now, if any (see the table above)
+pub fn next_alert(event: &CalendarEvent, settings: &crate::models::Settings, clock: &dyn Clock) -> Option<(AlertTrigger, AlertType)> {
+    if event.is_dismissed || !alerts_for(event, settings) {
+        return None;
+    }
+
+    if let Some((threshold, alert_type)) = check_alert_thresholds(event, settings, clock) {
+        return Some((AlertTrigger::Threshold(threshold), alert_type));
+    }
+
+    let snooze_interval = chrono::Duration::minutes(settings.snooze_interval.max(1) as i64);
+    let snooze_ends = event
+        .snoozed_until
+        .or_else(|| event.last_snoozed_at.map(|snoozed_at| snoozed_at + snooze_interval));
+    let snooze_elapsed = event.last_snoozed_at.is_some() && snooze_ends.is_some_and(|ends| clock.now() >= ends);
+    if snooze_elapsed {
+        let alert_type = if event.is_video_meeting() { AlertType::VideoMeeting } else { AlertType::Meeting };
+        return Some((AlertTrigger::SnoozeElapsed, alert_type));
+    }
+
+    None
+}
+
+/// The alert an event missed while the machine was asleep from
+/// `asleep_since`: the most recent of its thresholds that came due in that
+/// time and hasn't alerted. Meetings that started more than
+/// `LATE_ALERT_MINUTES` ago are let go.
+pub fn missed_alert(
+    event: &CalendarEvent,
+    settings: &crate::models::Settings,
+    asleep_since: chrono::DateTime<Utc>,
+    clock: &dyn Clock,
+) -> Option<(i32, AlertType)> {
+    if event.is_dismissed || !alerts_for(event, settings) {
+        return None;
+    }
+    let now = clock.now();
+    let start = event.effective_start();
+    if start < now - chrono::Duration::minutes(LATE_ALERT_MINUTES) {
+        return None;
+    }
+    event_thresholds(event, settings)
+        .into_iter()
+        .filter(|(threshold, _)| event.last_alert_threshold.is_none_or(|last| last > *threshold))
+        .rfind(|(threshold, _)| {
+            let due_at = start - chrono::Duration::minutes(*threshold as i64);
+            due_at > asleep_since && due_at <= now
+        })
+}
+
+/// How long "Snooze all" puts the current alerts off
+pub const SNOOZE_ALL_MINUTES: i64 = 10;
+
+/// Thresholds whose alerts remind about unchecked checklist items; by the
+/// last minute it's too late to prepare
+const CHECKLIST_THRESHOLDS: [i32; 2] = [30, 10];
+
+/// Whether an alert should list the event's unchecked checklist items
+pub fn carries_checklist(trigger: AlertTrigger) -> bool {
+    matches!(trigger, AlertTrigger::Threshold(threshold) if CHECKLIST_THRESHOLDS.contains(&threshold))
+}
+
+/// Persist that `trigger` fired for an event
+pub async fn record_alert(pool: &sqlx::SqlitePool, event_id: EventId, trigger: AlertTrigger) -> AppResult<()> {
+    match trigger {
+        AlertTrigger::Threshold(threshold) => {
+            sqlx::query("UPDATE events SET last_alert_threshold = ?, last_snoozed_at = NULL, snoozed_until = NULL WHERE id = ?")
                 .bind(threshold)
-                .bind(event.id)
-                .execute(&state.db.pool)
+                .bind(event_id)
+                .execute(pool)
+                .await?;
+        }
+        AlertTrigger::SnoozeElapsed => {
+            sqlx::query("UPDATE events SET last_snoozed_at = NULL, snoozed_until = NULL WHERE id = ?")
+                .bind(event_id)
+                .execute(pool)
                 .await?;
         }
     }
-    
     Ok(())
 }
 
-pub fn check_alert_thresholds(event: &CalendarEvent, settings: &crate::models::Settings) -> Option<(i32, AlertType)> {
-    let now = Utc::now();
-    let minutes_until = (event.start_time - now).num_minutes();
-    
-    // Check strict thresholds
-    let thresholds = [
+/// Minutes-before-start thresholds, whether each is enabled, and the sound
+/// it plays. Custom thresholds come after the standard ones; one that
+/// matches a standard threshold turns that on, and the rest play the
+/// generic warning.
+fn alert_thresholds(settings: &crate::models::Settings, is_video: bool) -> Vec<(i32, bool, AlertType)> {
+    let mut thresholds = vec![
         (30, settings.alert_30m, AlertType::Warning30m),
         (10, settings.alert_10m, AlertType::Warning10m),
         (5, settings.alert_5m, AlertType::Warning5m),
         (1, settings.alert_1m, AlertType::Warning1m),
-        (0, settings.alert_default, if event.is_video_meeting() { AlertType::VideoMeeting } else { AlertType::Meeting }), // 0 is "Start"
+        (0, settings.alert_default, if is_video { AlertType::VideoMeeting } else { AlertType::Meeting }), // 0 is "Start"
     ];
-    
-    for (threshold, enabled, alert_type) in thresholds {
-        if enabled {
-            // Logic:
-            // 1. We have passed the threshold (minutes_until <= threshold)
-            // 2. We are within a reasonable window (e.g. 2 minutes) so we don't alert for 30m when we are at 5m (if missed)
-            // 3. We haven't alerted for this threshold yet (implied by last_alert > threshold, OR last_alert is None)
-            //    (Since we iterate descending 30->0, if last_alert is 10, we skip 30. Correct).
-            
-            let window_ok = minutes_until <= threshold as i64 && minutes_until > (threshold as i64 - 5); // 5 minute grace window
-            
-            let not_alerted_yet = match event.last_alert_threshold {
-                Some(last) => last > threshold,
-                None => true,
-            };
-            
-            if window_ok && not_alerted_yet {
-                return Some((threshold, alert_type));
+    for minutes in settings.custom_alert_thresholds() {
+        match thresholds.iter_mut().find(|(threshold, _, _)| *threshold == minutes) {
+            Some((_, enabled, _)) => *enabled = true,
+            None => thresholds.push((minutes, true, AlertType::Warning)),
+        }
+    }
+    thresholds
+}
+
+/// Look-ahead past the largest enabled threshold, so an event is already
+/// loaded when its earliest alert comes due even if a cycle runs late
+const ALERT_HORIZON_MARGIN_MINUTES: i64 = 5;
+
+/// How far ahead the monitor has to look to catch every enabled threshold,
+/// and any reminder set in the calendar app when those are followed
+pub fn alert_horizon(settings: &crate::models::Settings) -> chrono::Duration {
+    let furthest = alert_thresholds(settings, false)
+        .into_iter()
+        .filter(|(_, enabled, _)| *enabled)
+        .map(|(threshold, _, _)| threshold)
+        .chain(settings.calendar_reminders.then_some(MAX_CALENDAR_REMINDER_MINUTES))
+        .max()
+        .unwrap_or(0);
+    chrono::Duration::minutes(furthest as i64 + ALERT_HORIZON_MARGIN_MINUTES)
+}
+
+/// The sound for a calendar-app reminder: that of the standard threshold
+/// it matches, or the generic warning
+fn reminder_alert_type(minutes: i32, is_video: bool) -> AlertType {
+    match minutes {
+        30 => AlertType::Warning30m,
+        10 => AlertType::Warning10m,
+        5 => AlertType::Warning5m,
+        1 => AlertType::Warning1m,
+        0 if is_video => AlertType::VideoMeeting,
+        0 => AlertType::Meeting,
+        _ => AlertType::Warning,
+    }
+}
+
+/// The thresholds that apply to an event, largest first: the enabled ones
+/// from settings and the reminders its calendar app set
+fn event_thresholds(event: &CalendarEvent, settings: &crate::models::Settings) -> Vec<(i32, AlertType)> {
+    let is_video = event.is_video_meeting();
+    let mut thresholds: Vec<(i32, AlertType)> = alert_thresholds(settings, is_video)
+        .into_iter()
+        .filter(|(_, enabled, _)| *enabled)
+        .map(|(threshold, _, alert_type)| (threshold, alert_type))
+        .collect();
+    if settings.calendar_reminders {
+        for minutes in event.calendar_reminders() {
+            if !thresholds.iter().any(|(threshold, _)| *threshold == minutes) {
+                thresholds.push((minutes, reminder_alert_type(minutes, is_video)));
             }
         }
     }
+    thresholds.sort_by_key(|(threshold, _)| std::cmp::Reverse(*threshold));
+    thresholds
+}
+
+/// Whether the event alerts at all: events shown as free only do when
+/// that's turned on, and all-day events only when they aren't skipped
+fn alerts_for(event: &CalendarEvent, settings: &crate::models::Settings) -> bool {
+    (!event.is_free || settings.alert_free_events) && !(event.is_all_day && settings.skip_all_day_events)
+}
+
+pub fn check_alert_thresholds(event: &CalendarEvent, settings: &crate::models::Settings, clock: &dyn Clock) -> Option<(i32, AlertType)> {
+    if event.is_dismissed || !alerts_for(event, settings) {
+        return None;
+    }
+
+    let now = clock.now();
+    let minutes_until = (event.effective_start() - now).num_minutes();
+    
+    // Check strict thresholds
+    for (threshold, alert_type) in event_thresholds(event, settings) {
+        // Logic:
+        // 1. We have passed the threshold (minutes_until <= threshold)
+        // 2. We are within a reasonable window (e.g. 2 minutes) so we don't alert for 30m when we are at 5m (if missed)
+        // 3. We haven't alerted for this threshold yet (implied by last_alert > threshold, OR last_alert is None)
+        //    (Since we iterate descending 30->0, if last_alert is 10, we skip 30. Correct).
+        
+        let window_ok = minutes_until <= threshold as i64 && minutes_until > (threshold as i64 - 5); // 5 minute grace window
+        
+        let not_alerted_yet = match event.last_alert_threshold {
+            Some(last) => last > threshold,
+            None => true,
+        };
+        
+        if window_ok && not_alerted_yet {
+            return Some((threshold, alert_type));
+        }
+    }
     
     None
 }
@@ -150,26 +467,72 @@ pub fn should_trigger_alert(event: &CalendarEvent) -> bool {
     (0..=3).contains(&minutes_until)
 }
 
-pub async fn sync_calendars(state: &AppState) -> Result<(usize, usize), Box<dyn std::error::Error + Send + Sync>> {
+/// When each account is next due a background sync
+#[derive(Debug, Clone)]
+pub struct SyncSchedule {
+    /// When the monitor started; accounts not yet synced count from here
+    started: chrono::DateTime<Utc>,
+    last_synced: HashMap<i64, chrono::DateTime<Utc>>,
+}
+
+impl SyncSchedule {
+    pub fn new(now: chrono::DateTime<Utc>) -> Self {
+        Self { started: now, last_synced: HashMap::new() }
+    }
+
+    /// The accounts whose own sync interval, or the global one, has passed
+    pub fn due(&self, accounts: Vec<Account>, now: chrono::DateTime<Utc>, default_interval: chrono::Duration) -> Vec<Account> {
+        accounts
+            .into_iter()
+            .filter(|account| {
+                let last = account.id.and_then(|id| self.last_synced.get(&id)).copied().unwrap_or(self.started);
+                now - last >= account.sync_interval_duration(default_interval)
+            })
+            .collect()
+    }
+
+    /// Make every account due at the next check
+    pub fn sync_all_now(&mut self) {
+        self.started = chrono::DateTime::<Utc>::MIN_UTC;
+        self.last_synced.clear();
+    }
+
+    /// Note that `accounts` were synced at `now`
+    pub fn record(&mut self, accounts: &[Account], now: chrono::DateTime<Utc>) {
+        for id in accounts.iter().filter_map(|account| account.id) {
+            self.last_synced.insert(id, now);
+        }
+    }
+}
+
+/// Sync every syncable account
+pub async fn sync_calendars(state: &AppState) -> AppResult<EventChanges> {
+    let accounts = crate::database::accounts::get_syncable(&state.db.pool).await?;
+    sync_accounts(state, &accounts).await
+}
+
+/// Sync `accounts` concurrently, carrying on past the ones that fail
+pub async fn sync_accounts(state: &AppState, accounts: &[Account]) -> AppResult<EventChanges> {
     info!("Starting calendar sync");
     
-    let accounts = sqlx::query_as::<_, Account>(
-        "SELECT id, provider, account_name, auth_data, refresh_token, last_synced_at FROM accounts"
-    )
-    .fetch_all(&state.db.pool)
-    .await?;
+    // Unreadable settings only cost the configured timeout, not the sync
+    let timeout = match state.db.get_settings().await {
+        Ok(settings) => settings.sync_timeout_duration(),
+        Err(e) => {
+            warn!("Failed to load settings, syncing with the default timeout: {}", e);
+            crate::models::Settings::default().sync_timeout_duration()
+        }
+    };
     
-    let mut total_added = 0;
-    let mut total_updated = 0;
+    let mut changes = EventChanges::default();
     
-    for account in accounts {
-        match calendar::sync_account(&account, &state.db.pool).await {
+    for (account, result) in calendar::sync_accounts(accounts, &state.db.pool, &calendar::cache::IcsCache::default_location(), &state.shutdown, timeout).await {
+        match result {
             Ok(sync_result) => {
-                info!("Synced account {}: {} events added, {} events updated", 
-                      account.account_name, sync_result.events_added, sync_result.events_updated);
+                info!("Synced account {}: {} events added, {} events updated, {} events deleted", 
+                      account.account_name, sync_result.events_added, sync_result.events_updated, sync_result.events_deleted);
                 
-                total_added += sync_result.events_added;
-                total_updated += sync_result.events_updated;
+                changes.merge(sync_result.changes);
 
                 // Update last_synced_at
                 sqlx::query("UPDATE accounts SET last_synced_at = CURRENT_TIMESTAMP WHERE id = ?")
@@ -178,65 +541,116 @@ pub async fn sync_calendars(state: &AppState) -> Result<(usize, usize), Box<dyn
                     .await?;
             }
             Err(e) => {
-                error!("Failed to sync account {}: {}", account.account_name, e);
+                error!("Failed to sync account {}: {:#}", account.account_name, e);
             }
         }
     }
     
     info!("Calendar sync completed");
-    Ok((total_added, total_updated))
+    Ok(changes)
+}
+
+/// An account whose last `STALLED_AFTER_FAILURES` syncs all failed
+#[derive(Debug, Clone, PartialEq)]
+pub struct StalledSync {
+    pub account_id: i64,
+    pub account_name: String,
+    pub last_synced_at: Option<chrono::DateTime<Utc>>,
+}
+
+impl StalledSync {
+    /// "Work calendar hasn't synced for 45 minutes"
+    pub fn message(&self, now: chrono::DateTime<Utc>) -> String {
+        let Some(last_synced_at) = self.last_synced_at else {
+            return format!("{} calendar hasn't synced yet", self.account_name);
+        };
+        let minutes = (now - last_synced_at).num_minutes().max(1);
+        let (count, unit) = match minutes {
+            m if m < 120 => (m, "minute"),
+            m if m < 48 * 60 => (m / 60, "hour"),
+            m => (m / (24 * 60), "day"),
+        };
+        format!("{} calendar hasn't synced for {} {}{}", self.account_name, count, unit, if count == 1 { "" } else { "s" })
+    }
+}
+
+/// Failed syncs in a row before an account counts as stalled
+pub const STALLED_AFTER_FAILURES: i64 = 3;
+
+/// Calendar accounts that have stopped syncing, going by each account's own
+/// sync history rather than its provider's circuit breaker
+pub async fn stalled_syncs(pool: &sqlx::SqlitePool) -> AppResult<Vec<StalledSync>> {
+    let failing = crate::database::sync_history::failing_accounts(pool, STALLED_AFTER_FAILURES).await?;
+    if failing.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let accounts = crate::database::accounts::get_syncable(pool).await?;
+    Ok(accounts
+        .into_iter()
+        .filter_map(|account| {
+            let account_id = account.id.filter(|id| failing.contains(id))?;
+            Some(StalledSync {
+                account_id,
+                account_name: account.account_name,
+                last_synced_at: account.last_synced_at,
+            })
+        })
+        .collect())
 }
 
-async fn play_alert_sound(event: &CalendarEvent, state: &AppState, alert_type: AlertType) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+async fn play_alert_sound(event: &CalendarEvent, state: &AppState, alert_type: AlertType) -> AppResult<()> {
     info!("Playing alert sound for event: {}", event.title);
     
     state.audio.play_alert(alert_type)
-        .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> { 
-            format!("Audio playback failed: {}", e).into() 
-        })?;
+        .map_err(|e| AppError::audio(format!("Audio playback failed: {}", e)))?;
     
     Ok(())
 }
 
-async fn show_alert_window(event: &CalendarEvent) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+async fn show_alert_window(event: &CalendarEvent) -> AppResult<()> {
     info!("Showing alert window for event: {}", event.title);
     
     // Alert window display is handled by the main application UI
     Ok(())
 }
 
-pub async fn get_upcoming_events(pool: &sqlx::SqlitePool) -> Result<Vec<CalendarEvent>, Box<dyn std::error::Error + Send + Sync>> {
-    let now = Utc::now();
-    let future = now + chrono::Duration::minutes(60); // Look ahead 60 minutes to catch 30m alerts
-    
-    let events = sqlx::query_as::<_, CalendarEvent>(
-        r#"
-        SELECT id, external_id, account_id, title, description, start_time, end_time, video_link, video_platform,
-               snooze_count, has_alerted, last_alert_threshold, is_dismissed,
-               created_at, updated_at
-        FROM events 
-        WHERE start_time BETWEEN ? AND ?
-        ORDER BY start_time ASC
-        "#
-    )
-    .bind(now - chrono::Duration::minutes(5)) // Look back 5 mins for late alerts
-    .bind(future)
-    .fetch_all(pool)
-    .await?;
+/// How long after its start a meeting can still alert
+const LATE_ALERT_MINUTES: i64 = 5;
+
+/// Undismissed events starting between `LATE_ALERT_MINUTES` ago and
+/// `horizon` from now (see `alert_horizon`)
+pub async fn get_upcoming_events(pool: &sqlx::SqlitePool, clock: &dyn Clock, horizon: chrono::Duration) -> AppResult<Vec<CalendarEvent>> {
+    let now = clock.now();
+    let past = now - chrono::Duration::minutes(LATE_ALERT_MINUTES);
+    let future = now + horizon;
+    // All-day events are stored at UTC midnight but start at local midnight,
+    // which is at most 14 hours away; widen the query for them and filter below
+    let utc_offset_margin = chrono::Duration::hours(14);
     
+    let events = crate::database::events::get_alert_candidates(pool, past, future, utc_offset_margin).await?;
+
+    Ok(events
+        .into_iter()
+        .filter(|event| (past..=future).contains(&event.effective_start()))
+        .collect())
+}
+
+/// The upcoming events that may alert: those within `alert_horizon`,
+/// less the ones declined from one of the user's `my_addresses`
+async fn alertable_events(pool: &sqlx::SqlitePool, clock: &dyn Clock, settings: &crate::models::Settings) -> AppResult<Vec<CalendarEvent>> {
+    let mut events = get_upcoming_events(pool, clock, alert_horizon(settings)).await?;
+    let declined = crate::database::attendees::declined_by(pool, &settings.my_addresses()).await?;
+    events.retain(|event| !event.id.is_some_and(|id| declined.contains(&id)));
+
     Ok(events)
 }
 
-pub async fn trigger_manual_alert(event_id: i64, state: &AppState) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+pub async fn trigger_manual_alert(event_id: EventId, state: &AppState) -> AppResult<()> {
     // Get the specific event
-    let events = sqlx::query_as::<_, CalendarEvent>(
-        "SELECT id, external_id, account_id, title, description, start_time, end_time, video_link, video_platform, snooze_count, has_alerted, last_alert_threshold, is_dismissed, created_at, updated_at FROM events WHERE id = ?"
-    )
-    .bind(event_id)
-    .fetch_all(&state.db.pool)
-    .await?;
+    let event = crate::database::events::get_by_id(&state.db.pool, event_id).await?;
     
-    if let Some(event) = events.into_iter().next() {
+    if let Some(event) = event {
         info!("Manually triggering alert for event: {}", event.title);
         
         let alert_type = if event.is_video_meeting() { AlertType::VideoMeeting } else { AlertType::Meeting };
@@ -251,7 +665,7 @@ pub async fn trigger_manual_alert(event_id: i64, state: &AppState) -> Result<(),
             error!("Failed to show alert window: {}", e);
         }
     } else {
-        return Err(format!("Event not found: {}", event_id).into());
+        return Err(AppError::not_found(format!("Event {}", event_id)));
     }
     
     Ok(())
@@ -266,16 +680,19 @@ mod tests {
     use std::sync::Arc;
     use sqlx::SqlitePool;
     use crate::AudioManager;
+    use crate::utils::clock::{MockClock, SystemClock};
+    use chrono::TimeZone;
 
     fn create_test_event(minutes_from_now: i64, has_video: bool) -> CalendarEvent {
-        let now = Utc::now();
+        create_test_event_at(Utc::now(), minutes_from_now, has_video)
+    }
+
+    fn create_test_event_at(now: chrono::DateTime<Utc>, minutes_from_now: i64, has_video: bool) -> CalendarEvent {
         CalendarEvent {
-            id: Some(1),
-            external_id: "test-event".to_string(),
-            account_id: 1,
+            id: Some(EventId(1)),
+            external_id: "test-event".into(),
             title: "Test Meeting".to_string(),
             description: Some("Test description".to_string()),
-            start_time: now + Duration::minutes(minutes_from_now),
             end_time: now + Duration::minutes(minutes_from_now + 60),
             video_link: if has_video {
                 Some("https://zoom.us/test".to_string())
@@ -287,12 +704,7 @@ mod tests {
             } else {
                 None
             },
-            snooze_count: 0,
-            has_alerted: false,
-            last_alert_threshold: None,
-            is_dismissed: false,
-            created_at: now,
-            updated_at: now,
+            ..CalendarEvent::test_at(now + Duration::minutes(minutes_from_now))
         }
     }
 
@@ -334,7 +746,7 @@ mod tests {
         let schema = include_str!("../database/schema.sql");
         sqlx::query(schema).execute(&pool).await.unwrap();
 
-        let events = get_upcoming_events(&pool).await.unwrap();
+        let events = get_upcoming_events(&pool, &SystemClock, Duration::minutes(60)).await.unwrap();
         assert!(events.is_empty());
     }
 
@@ -354,33 +766,441 @@ mod tests {
             db: std::sync::Arc::new(db),
             audio: std::sync::Arc::new(audio),
             shutdown: tokio_util::sync::CancellationToken::new(),
+            clock: Arc::new(SystemClock),
         });
 
-        let result = trigger_manual_alert(999, &state).await;
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("Event not found"));
+        let result = trigger_manual_alert(EventId(999), &state).await;
+        let err = result.unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+        assert_eq!(err.to_string(), "Not found: Event 999");
     }
 
     #[test]
     fn test_alert_info_creation() {
-        let event = create_test_event(5, true);
-        let alert_info = AlertInfo::new(event.clone());
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let event = create_test_event_at(now, 5, true);
+        let alert_info = AlertInfo::new_at(event.clone(), now);
         
         assert!(matches!(alert_info.alert_type, AlertType::VideoMeeting));
-        // Allow for 1 minute of drift (4 or 5 is acceptable)
-        assert!(alert_info.minutes_remaining >= 4 && alert_info.minutes_remaining <= 5, 
-            "Expected ~5 minutes, got {}", alert_info.minutes_remaining);
+        assert_eq!(alert_info.minutes_remaining, 5);
         assert_eq!(alert_info.event.title, "Test Meeting");
     }
 
     #[test]
     fn test_alert_info_regular_meeting() {
-        let event = create_test_event(2, false);
-        let alert_info = AlertInfo::new(event.clone());
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let event = create_test_event_at(now, 2, false);
+        let alert_info = AlertInfo::new_at(event.clone(), now);
         
         assert!(matches!(alert_info.alert_type, AlertType::Meeting));
-        let minutes = alert_info.minutes_remaining;
-        assert!(minutes >= 1 && minutes <= 3, "Expected ~2 minutes, got {}", minutes);
+        assert_eq!(alert_info.minutes_remaining, 2);
+    }
+
+    #[test]
+    fn test_check_alert_thresholds_as_clock_advances() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let clock = MockClock::new(start);
+        let mut event = create_test_event_at(start, 31, true);
+        let settings = crate::models::Settings { alert_30m: true, ..Default::default() };
+
+        // 31 minutes out: nothing yet
+        assert!(check_alert_thresholds(&event, &settings, &clock).is_none());
+
+        // Exactly 30 minutes out: 30m warning
+        clock.advance(chrono::Duration::minutes(1));
+        let (threshold, alert_type) = check_alert_thresholds(&event, &settings, &clock).unwrap();
+        assert_eq!(threshold, 30);
+        assert!(matches!(alert_type, AlertType::Warning30m));
+
+        // Once the 30m alert is recorded it doesn't fire again
+        event.last_alert_threshold = Some(30);
+        clock.advance(chrono::Duration::minutes(1));
+        assert!(check_alert_thresholds(&event, &settings, &clock).is_none());
+
+        // Start time (after the 1m warning went out): the meeting alert
+        event.last_alert_threshold = Some(1);
+        clock.set(start + chrono::Duration::minutes(31));
+        let (threshold, alert_type) = check_alert_thresholds(&event, &settings, &clock).unwrap();
+        assert_eq!(threshold, 0);
+        assert!(matches!(alert_type, AlertType::VideoMeeting));
+    }
+
+    #[test]
+    fn test_calendar_reminders_add_thresholds() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let clock = MockClock::new(start);
+        let mut event = create_test_event_at(start, 15, false);
+        event.reminder_minutes = Some("15,5".to_string());
+        let settings = crate::models::Settings { calendar_reminders: true, ..Default::default() };
+
+        // A reminder that isn't a standard threshold plays the generic warning
+        let (threshold, alert_type) = check_alert_thresholds(&event, &settings, &clock).unwrap();
+        assert_eq!(threshold, 15);
+        assert!(matches!(alert_type, AlertType::Warning));
+
+        // The 5 minute reminder is also a default threshold; it fires once
+        event.last_alert_threshold = Some(15);
+        clock.advance(chrono::Duration::minutes(10));
+        assert_eq!(check_alert_thresholds(&event, &settings, &clock).unwrap().0, 5);
+        assert_eq!(event_thresholds(&event, &settings).iter().filter(|(t, _)| *t == 5).count(), 1);
+
+        // Off by default, or on a team calendar, only the settings' thresholds apply
+        clock.set(start);
+        event.last_alert_threshold = None;
+        assert!(check_alert_thresholds(&event, &crate::models::Settings::default(), &clock).is_none());
+        assert!(check_alert_thresholds(&event, &settings.for_team_calendar(), &clock).is_none());
+    }
+
+    #[test]
+    fn test_custom_thresholds() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let clock = MockClock::new(start);
+        let mut event = create_test_event_at(start, 45, false);
+        let settings = crate::models::Settings {
+            custom_alert_minutes: "45, 30".to_string(),
+            calendar_reminders: false,
+            ..Default::default()
+        };
+
+        // A non-standard threshold plays the generic warning
+        let (threshold, alert_type) = check_alert_thresholds(&event, &settings, &clock).unwrap();
+        assert_eq!(threshold, 45);
+        assert!(matches!(alert_type, AlertType::Warning));
+
+        // One that matches a standard threshold turns it on, sound and all
+        event.last_alert_threshold = Some(45);
+        clock.advance(chrono::Duration::minutes(15));
+        let (threshold, alert_type) = check_alert_thresholds(&event, &settings, &clock).unwrap();
+        assert_eq!(threshold, 30);
+        assert!(matches!(alert_type, AlertType::Warning30m));
+        assert_eq!(event_thresholds(&event, &settings).iter().filter(|(t, _)| *t == 30).count(), 1);
+
+        assert_eq!(alert_horizon(&settings), Duration::minutes(50));
+    }
+
+    #[test]
+    fn test_free_events_alert_only_when_enabled() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let clock = MockClock::new(now);
+        let mut event = create_test_event_at(now, 5, false);
+        event.is_free = true;
+        let mut settings = crate::models::Settings::default();
+        assert!(next_alert(&event, &settings, &clock).is_none());
+
+        settings.alert_free_events = true;
+        assert_eq!(next_alert(&event, &settings, &clock).map(|(trigger, _)| trigger), Some(AlertTrigger::Threshold(5)));
+    }
+
+    #[test]
+    fn test_all_day_events_are_skipped_by_default() {
+        // Local midnight, when an all-day event starts
+        let midnight = chrono::NaiveDate::from_ymd_opt(2024, 3, 5).unwrap().and_time(chrono::NaiveTime::MIN);
+        let now = chrono::Local.from_local_datetime(&midnight).earliest().unwrap().with_timezone(&Utc);
+        let clock = MockClock::new(now);
+        let mut event = create_test_event_at(now, 0, false);
+        event.is_all_day = true;
+        event.start_time = midnight.and_utc();
+        event.end_time = event.start_time + Duration::days(1);
+        let mut settings = crate::models::Settings::default();
+        assert!(next_alert(&event, &settings, &clock).is_none());
+
+        settings.skip_all_day_events = false;
+        assert!(next_alert(&event, &settings, &clock).is_some());
+    }
+
+    #[test]
+    fn test_dismissed_events_never_alert() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let clock = MockClock::new(now);
+        let settings = crate::models::Settings::default();
+        let mut event = create_test_event_at(now, 5, false);
+        event.is_dismissed = true;
+        event.last_snoozed_at = Some(now - chrono::Duration::minutes(10));
+
+        assert!(check_alert_thresholds(&event, &settings, &clock).is_none());
+        assert!(next_alert(&event, &settings, &clock).is_none());
+    }
+
+    #[test]
+    fn test_snoozed_event_realerts_and_keeps_later_thresholds() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let clock = MockClock::new(start);
+        let settings = crate::models::Settings::default(); // 5m, 1m and start; 2 minute snooze
+        let mut event = create_test_event_at(start, 5, false);
+
+        // 5m warning fires and is recorded
+        assert_eq!(next_alert(&event, &settings, &clock).unwrap().0, AlertTrigger::Threshold(5));
+        event.last_alert_threshold = Some(5);
+
+        // User snoozes: nothing until the snooze runs out
+        event.last_snoozed_at = Some(clock.now());
+        clock.advance(chrono::Duration::minutes(1));
+        assert!(next_alert(&event, &settings, &clock).is_none());
+
+        clock.advance(chrono::Duration::minutes(1));
+        assert_eq!(next_alert(&event, &settings, &clock).unwrap().0, AlertTrigger::SnoozeElapsed);
+        event.last_snoozed_at = None;
+
+        // The 1m warning is still armed after the snooze
+        clock.advance(chrono::Duration::minutes(2));
+        assert_eq!(next_alert(&event, &settings, &clock).unwrap().0, AlertTrigger::Threshold(1));
+    }
+
+    #[test]
+    fn test_missed_alert_after_sleep() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let clock = MockClock::new(start - chrono::Duration::minutes(16));
+        let settings = crate::models::Settings { alert_30m: true, ..Default::default() };
+        let asleep_since = start - chrono::Duration::hours(1);
+        let mut event = create_test_event_at(start, 0, false);
+
+        // The 30m warning came due while asleep and is past its grace window
+        assert!(next_alert(&event, &settings, &clock).is_none());
+        assert_eq!(missed_alert(&event, &settings, asleep_since, &clock).map(|(t, _)| t), Some(30));
+
+        // Not when it had already alerted, or came due before the sleep
+        event.last_alert_threshold = Some(30);
+        assert!(missed_alert(&event, &settings, asleep_since, &clock).is_none());
+        event.last_alert_threshold = None;
+        assert!(missed_alert(&event, &settings, start - chrono::Duration::minutes(20), &clock).is_none());
+
+        // A meeting well under way is let go
+        clock.advance(chrono::Duration::minutes(30));
+        assert!(missed_alert(&event, &settings, asleep_since, &clock).is_none());
+    }
+
+    #[test]
+    fn test_resumed_from_sleep() {
+        let last = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        assert_eq!(resumed_from_sleep(last, last + chrono::Duration::seconds(31)), None);
+        assert_eq!(resumed_from_sleep(last, last + chrono::Duration::minutes(2)), None);
+        assert_eq!(resumed_from_sleep(last, last + chrono::Duration::hours(8)), Some(last));
+    }
+
+    #[test]
+    fn test_stalled_sync_message() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let stalled = |ago: Option<i64>| StalledSync {
+            account_id: 1,
+            account_name: "Work".to_string(),
+            last_synced_at: ago.map(|minutes| now - chrono::Duration::minutes(minutes)),
+        };
+
+        assert_eq!(stalled(Some(45)).message(now), "Work calendar hasn't synced for 45 minutes");
+        assert_eq!(stalled(Some(0)).message(now), "Work calendar hasn't synced for 1 minute");
+        assert_eq!(stalled(Some(180)).message(now), "Work calendar hasn't synced for 3 hours");
+        assert_eq!(stalled(Some(3 * 24 * 60)).message(now), "Work calendar hasn't synced for 3 days");
+        assert_eq!(stalled(None).message(now), "Work calendar hasn't synced yet");
+    }
+
+    #[test]
+    fn test_sync_schedule_per_account_intervals() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let account = |id: i64, sync_interval: Option<i32>| {
+            let mut account = Account::new_proton(format!("Account {}", id), "https://example.com/cal.ics".to_string());
+            account.id = Some(id);
+            account.sync_interval = sync_interval;
+            account
+        };
+        let accounts = vec![account(1, Some(120)), account(2, Some(3600)), account(3, None)];
+        let default = chrono::Duration::minutes(5);
+        let due_ids = |schedule: &SyncSchedule, minutes: i64| {
+            schedule
+                .due(accounts.clone(), start + chrono::Duration::minutes(minutes), default)
+                .iter()
+                .filter_map(|account| account.id)
+                .collect::<Vec<_>>()
+        };
+
+        let mut schedule = SyncSchedule::new(start);
+        assert!(due_ids(&schedule, 1).is_empty());
+        assert_eq!(due_ids(&schedule, 2), vec![1]);
+        schedule.record(&[accounts[0].clone()], start + chrono::Duration::minutes(2));
+
+        assert!(due_ids(&schedule, 3).is_empty());
+        assert_eq!(due_ids(&schedule, 5), vec![1, 3]);
+        schedule.record(&[accounts[0].clone(), accounts[2].clone()], start + chrono::Duration::minutes(5));
+        assert!(due_ids(&schedule, 6).is_empty());
+        assert_eq!(due_ids(&schedule, 7), vec![1]);
+        assert_eq!(due_ids(&schedule, 60), vec![1, 2, 3]);
+
+        schedule.sync_all_now();
+        assert_eq!(due_ids(&schedule, 6), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_snooze_with_its_own_end() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let clock = MockClock::new(start - chrono::Duration::minutes(30));
+        let settings = crate::models::Settings::default(); // 2 minute snooze
+        let mut event = create_test_event_at(start, 30, false);
+        event.last_alert_threshold = Some(5);
+        event.last_snoozed_at = Some(clock.now());
+        event.snoozed_until = Some(clock.now() + chrono::Duration::minutes(10));
+
+        // The usual interval doesn't apply
+        clock.advance(chrono::Duration::minutes(9));
+        assert!(next_alert(&event, &settings, &clock).is_none());
+
+        clock.advance(chrono::Duration::minutes(1));
+        assert_eq!(next_alert(&event, &settings, &clock).unwrap().0, AlertTrigger::SnoozeElapsed);
+    }
+
+    #[test]
+    fn test_threshold_supersedes_pending_snooze() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let clock = MockClock::new(start);
+        let settings = crate::models::Settings { snooze_interval: 10, ..Default::default() };
+        let mut event = create_test_event_at(start, 1, false);
+        event.last_alert_threshold = Some(5);
+        event.last_snoozed_at = Some(start - chrono::Duration::minutes(4));
+
+        assert_eq!(next_alert(&event, &settings, &clock).unwrap().0, AlertTrigger::Threshold(1));
+    }
+
+    #[tokio::test]
+    async fn test_declined_events_dont_alert() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_path = format!("sqlite:file:{}?mode=rwc", temp_file.path().to_str().unwrap());
+        let pool = SqlitePool::connect(&db_path).await.unwrap();
+        sqlx::query(include_str!("../database/schema.sql")).execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO accounts (id, provider, account_name, auth_data) VALUES (1, 'proton', 'Test', 'https://example.com/cal.ics')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let start = Utc::now() + chrono::Duration::minutes(5);
+        let event_id = sqlx::query("INSERT INTO events (external_id, account_id, title, start_time, end_time) VALUES ('review', 1, 'Review', ?, ?)")
+            .bind(start)
+            .bind(start + chrono::Duration::hours(1))
+            .execute(&pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+        sqlx::query("INSERT INTO event_attendees (event_id, email, rsvp) VALUES (?, 'me@example.com', 'DECLINED')")
+            .bind(event_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Without the user's address there's no telling the reply is theirs
+        let settings = crate::models::Settings::default();
+        assert_eq!(alertable_events(&pool, &SystemClock, &settings).await.unwrap().len(), 1);
+
+        let settings = crate::models::Settings { my_addresses: "Me@Example.com, other@example.com".to_string(), ..settings };
+        assert!(alertable_events(&pool, &SystemClock, &settings).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_record_alert_and_manual_alert_state() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_path = format!("sqlite:file:{}?mode=rwc", temp_file.path().to_str().unwrap());
+        let pool = SqlitePool::connect(&db_path).await.unwrap();
+        let schema = include_str!("../database/schema.sql");
+        sqlx::query(schema).execute(&pool).await.unwrap();
+
+        sqlx::query("INSERT INTO accounts (id, provider, account_name, auth_data) VALUES (1, 'proton', 'Test', 'https://example.com/cal.ics')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let start = Utc::now() + chrono::Duration::minutes(5);
+        let event_id = EventId(
+            sqlx::query("INSERT INTO events (external_id, account_id, title, start_time, end_time) VALUES ('standup', 1, 'Standup', ?, ?)")
+                .bind(start)
+                .bind(start + chrono::Duration::hours(1))
+                .execute(&pool)
+                .await
+                .unwrap()
+                .last_insert_rowid(),
+        );
+
+        let state = crate::AppState {
+            db: Arc::new(crate::database::Database { pool: pool.clone() }),
+            audio: Arc::new(AudioManager::new().unwrap()),
+            shutdown: tokio_util::sync::CancellationToken::new(),
+            clock: Arc::new(SystemClock),
+        };
+        let load = || async {
+            get_upcoming_events(&pool, &SystemClock, Duration::minutes(60)).await.unwrap().remove(0)
+        };
+
+        record_alert(&pool, event_id, AlertTrigger::Threshold(5)).await.unwrap();
+        state.db.snooze_event(event_id).await.unwrap();
+        let snoozed = load().await;
+        assert_eq!(snoozed.last_alert_threshold, Some(5));
+        assert!(snoozed.last_snoozed_at.is_some());
+
+        // A manual alert plays a sound but leaves threshold and snooze tracking alone
+        trigger_manual_alert(event_id, &state).await.unwrap();
+        let after_manual = load().await;
+        assert_eq!(after_manual.last_alert_threshold, Some(5));
+        assert_eq!(after_manual.last_snoozed_at, snoozed.last_snoozed_at);
+
+        record_alert(&pool, event_id, AlertTrigger::SnoozeElapsed).await.unwrap();
+        let realerted = load().await;
+        assert_eq!(realerted.last_alert_threshold, Some(5));
+        assert!(realerted.last_snoozed_at.is_none());
+
+        // Dismissed events drop out of the monitor's query entirely
+        state.db.dismiss_event(event_id).await.unwrap();
+        assert!(get_upcoming_events(&pool, &SystemClock, Duration::minutes(60)).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_alert_horizon_follows_enabled_thresholds() {
+        let mut settings = crate::models::Settings { calendar_reminders: false, ..Default::default() };
+        // Defaults: 5m, 1m and start
+        assert_eq!(alert_horizon(&settings), Duration::minutes(10));
+
+        settings.alert_30m = true;
+        assert_eq!(alert_horizon(&settings), Duration::minutes(35));
+
+        settings.alert_30m = false;
+        settings.alert_5m = false;
+        settings.alert_1m = false;
+        settings.alert_default = false;
+        assert_eq!(alert_horizon(&settings), Duration::minutes(5));
+
+        // Calendar reminders can be up to a day ahead
+        settings.calendar_reminders = true;
+        assert_eq!(alert_horizon(&settings), Duration::minutes(24 * 60 + 5));
+    }
+
+    #[test]
+    fn test_checklist_rides_along_with_early_warnings() {
+        assert!(carries_checklist(AlertTrigger::Threshold(30)));
+        assert!(carries_checklist(AlertTrigger::Threshold(10)));
+        assert!(!carries_checklist(AlertTrigger::Threshold(5)));
+        assert!(!carries_checklist(AlertTrigger::Threshold(0)));
+        assert!(!carries_checklist(AlertTrigger::SnoozeElapsed));
+    }
+
+    #[tokio::test]
+    async fn test_get_upcoming_events_uses_clock() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_path = format!("sqlite:file:{}?mode=rwc", temp_file.path().to_str().unwrap());
+        let pool = SqlitePool::connect(&db_path).await.unwrap();
+        let schema = include_str!("../database/schema.sql");
+        sqlx::query(schema).execute(&pool).await.unwrap();
+
+        sqlx::query("INSERT INTO accounts (id, provider, account_name, auth_data) VALUES (1, 'proton', 'Test', 'https://example.com/cal.ics')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let start = Utc.with_ymd_and_hms(2030, 6, 1, 12, 0, 0).unwrap();
+        sqlx::query("INSERT INTO events (external_id, account_id, title, start_time, end_time) VALUES ('future', 1, 'Future', ?, ?)")
+            .bind(start)
+            .bind(start + chrono::Duration::hours(1))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let clock = MockClock::new(start - chrono::Duration::hours(2));
+        assert!(get_upcoming_events(&pool, &clock, Duration::minutes(60)).await.unwrap().is_empty());
+
+        clock.advance(chrono::Duration::minutes(90));
+        let events = get_upcoming_events(&pool, &clock, Duration::minutes(60)).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].title, "Future");
     }
 
     #[tokio::test]
@@ -397,6 +1217,7 @@ mod tests {
             db,
             audio,
             shutdown: tokio_util::sync::CancellationToken::new(),
+            clock: Arc::new(SystemClock),
         });
 
         // This should not panic even if sound file doesn't exist
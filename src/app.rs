@@ -1,19 +1,134 @@
 use std::sync::Arc;
 use log::{info, error};
 use iced::futures::SinkExt;
-use chrono::TimeZone;
-use iced::widget::{button, column, row, text, text_input, container, scrollable, checkbox};
+use iced::widget::{button, column, row, text, text_input, container, scrollable, checkbox, pick_list};
 use iced::{Application, Command, Element, Theme, Length};
 
 use crate::database::Database;
 use crate::audio::AudioManager;
-use crate::models::{Account, Settings, CalendarEvent};
-use crate::ui_state::{UiState, View};
-use crate::messages::Message;
+use crate::models::{Account, AccountGroup, Settings, CalendarEvent};
+use crate::ui_state::{AccountForm, ReminderForm, UiState, View};
+use crate::messages::{GroupToggle, Message};
 use crate::ui::styles::*;
 use crate::calendar;
+use crate::focus::{FocusEvent, FocusPhase, FocusTimer, BREAK_MINUTE_CHOICES, WORK_MINUTE_CHOICES};
 use crate::AppState;
 
+/// Background sync intervals offered in Settings (seconds)
+const SYNC_INTERVAL_CHOICES: [i32; 5] = [60, 300, 900, 1800, 3600];
+
+/// Pick-list entry for the sync interval
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SyncIntervalChoice(i32);
+
+impl std::fmt::Display for SyncIntervalChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            s if s % 3600 == 0 && s >= 3600 => write!(f, "Every {} hour{}", s / 3600, if s == 3600 { "" } else { "s" }),
+            s if s % 60 == 0 && s >= 60 => write!(f, "Every {} minute{}", s / 60, if s == 60 { "" } else { "s" }),
+            s => write!(f, "Every {} seconds", s),
+        }
+    }
+}
+
+/// Sync intervals offered for a single account
+const ACCOUNT_SYNC_INTERVAL_CHOICES: [i32; 6] = [120, 300, 900, 1800, 3600, 21600];
+
+/// Pick-list entry for an account's sync interval; `None` follows the
+/// feed's refresh interval, if it gives one (the second field), or else the
+/// global one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AccountSyncIntervalChoice(Option<i32>, Option<i32>);
+
+impl std::fmt::Display for AccountSyncIntervalChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.0, self.1) {
+            (Some(secs), _) => SyncIntervalChoice(secs).fmt(f),
+            (None, Some(secs)) => write!(f, "{} (feed)", SyncIntervalChoice(secs)),
+            (None, None) => f.write_str("Default sync"),
+        }
+    }
+}
+
+/// Pick-list entry for the zone an account's floating times are read in;
+/// `None` follows the `floating_timezone` setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AccountTimezoneChoice(Option<chrono_tz::Tz>);
+
+impl std::fmt::Display for AccountTimezoneChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(tz) => f.write_str(tz.name()),
+            None => f.write_str("Default timezone"),
+        }
+    }
+}
+
+/// Days of past events offered for import
+const SYNC_DAYS_BACK_CHOICES: [i32; 5] = [0, 1, 7, 30, 90];
+/// Days of upcoming events offered for import
+const SYNC_DAYS_AHEAD_CHOICES: [i32; 5] = [7, 30, 90, 180, 365];
+
+/// Pick-list entry for how far back or ahead events are imported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SyncDaysChoice(i32);
+
+impl std::fmt::Display for SyncDaysChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => f.write_str("None"),
+            1 => f.write_str("1 day"),
+            days => write!(f, "{} days", days),
+        }
+    }
+}
+
+/// Feed size limits offered in Settings, in MB
+const MAX_FEED_SIZE_CHOICES: [i32; 6] = [10, 25, 50, 100, 250, crate::models::settings::MAX_FEED_SIZE_MB];
+
+/// Pick-list entry for the largest calendar feed downloaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FeedSizeChoice(i32);
+
+impl std::fmt::Display for FeedSizeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} MB", self.0)
+    }
+}
+
+/// Pick-list entry for how long before a meeting the system is kept awake
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct KeepAwakeChoice(i32);
+
+impl std::fmt::Display for KeepAwakeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => f.write_str("Off"),
+            minutes => write!(f, "{} minutes before", minutes),
+        }
+    }
+}
+
+/// Pick-list entry for an account's group; `None` is "No group"
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GroupChoice(Option<i64>, String);
+
+impl std::fmt::Display for GroupChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.1)
+    }
+}
+
+/// Pick-list entry for a focus timer phase length
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FocusMinutesChoice(i32);
+
+impl std::fmt::Display for FocusMinutesChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} minutes", self.0)
+    }
+}
+
 // Helper function to get current local time for the user's timezone
 fn get_local_now() -> chrono::DateTime<chrono::Local> {
     chrono::Local::now()
@@ -48,6 +163,54 @@ fn user_friendly_error(error: &str) -> String {
     }
 }
 
+/// Open a URL with the platform's default handler
+fn open_url(url: &str) {
+    #[cfg(target_os = "macos")]
+    let _ = std::process::Command::new("open").arg(url).spawn();
+    #[cfg(target_os = "linux")]
+    let _ = std::process::Command::new("xdg-open").arg(url).spawn();
+    #[cfg(target_os = "windows")]
+    let _ = std::process::Command::new("cmd").arg("/C").arg("start").arg(url).spawn();
+}
+
+/// Passcode and phone dial-in found in an event's invite, for display
+fn join_details_text(event: &CalendarEvent) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(passcode) = &event.meeting_passcode {
+        parts.push(format!("Passcode {}", passcode));
+    }
+    if let Some(dial_in) = event.dial_in() {
+        match &dial_in.conference_id {
+            Some(id) => parts.push(format!("Dial-in {} (ID {}#)", dial_in.number, id)),
+            None => parts.push(format!("Dial-in {}", dial_in.number)),
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+/// Meetings further off than this aren't counted down in the window title
+const TITLE_COUNTDOWN_HOURS: i64 = 12;
+
+/// "OpenChime — Standup in 7m", so the next meeting shows on the taskbar
+fn window_title(events: &[CalendarEvent], now: chrono::DateTime<chrono::Utc>) -> String {
+    match meeting_countdown(events, now) {
+        Some(countdown) => format!("OpenChime — {}", countdown),
+        None => "OpenChime".to_string(),
+    }
+}
+
+/// "Standup in 7m", for the next meeting within `TITLE_COUNTDOWN_HOURS`
+fn meeting_countdown(events: &[CalendarEvent], now: chrono::DateTime<chrono::Utc>) -> Option<String> {
+    let event = crate::models::next_meeting(events, now)?;
+    let when = match event.minutes_until_start_at(now) {
+        m if m <= 0 => "now".to_string(),
+        m if m < 60 => format!("in {}m", m),
+        m if m < TITLE_COUNTDOWN_HOURS * 60 => format!("in {}h {}m", m / 60, m % 60),
+        _ => return None,
+    };
+    Some(format!("{} {}", event.display_title(), when))
+}
+
 /// Determine calendar provider based on URL
 fn detect_provider_from_url(url: &str) -> crate::models::CalendarProvider {
     if url.contains("google.com/calendar") {
@@ -55,8 +218,19 @@ fn detect_provider_from_url(url: &str) -> crate::models::CalendarProvider {
     } else if url.contains("proton.me") {
         crate::models::CalendarProvider::Proton
     } else {
-        // Default to Proton for unknown URLs to maintain backward compatibility
-        crate::models::CalendarProvider::Proton
+        crate::models::CalendarProvider::Generic
+    }
+}
+
+/// Whether `account` syncs through the Google Calendar API, with an
+/// `auth_data` that isn't a URL to edit
+fn is_api_account(account: &Account) -> bool {
+    #[cfg(feature = "google-api")]
+    return calendar::google_api::calendar_id(account).is_some();
+    #[cfg(not(feature = "google-api"))]
+    {
+        let _ = account;
+        false
     }
 }
 
@@ -71,44 +245,78 @@ pub struct OpenChimeApp {
 
     // Data
     events: Vec<CalendarEvent>,
+    // `events` with each meeting once; kept in step by `events_changed`
+    merged_events: Vec<crate::models::MergedEvent>,
     settings: Settings,
     accounts: Vec<Account>,
+    groups: Vec<AccountGroup>,
+
+    // Focus timer; lives only as long as the window
+    focus: FocusTimer,
+
+    // Computed when the Stats view is opened
+    stats: Option<crate::database::stats::MeetingStats>,
+    sync_reliability: Option<Vec<crate::database::sync_history::AccountReliability>>,
+
+    // Remaining meetings last drawn on the window icon's badge
+    badge_count: Option<usize>,
+
+    // Tray icon, where there is one, and the countdown and count it shows
+    tray: Option<crate::tray::TrayHandle>,
+    tray_status: Option<(Option<String>, usize)>,
 }
 
 
 
+/// Startup inputs for the iced application
+pub struct AppFlags {
+    pub db: Arc<Database>,
+    pub audio: Arc<AudioManager>,
+    /// `openchime://` link the app was launched with, if any
+    pub open_link: Option<String>,
+}
+
 impl Application for OpenChimeApp {
     type Message = Message;
     type Theme = Theme;
     type Executor = iced::executor::Default;
-    type Flags = (Arc<Database>, Arc<AudioManager>);
+    type Flags = AppFlags;
 
-    fn new((db, audio): Self::Flags) -> (Self, Command<Message>) {
-        let app = OpenChimeApp {
+    fn new(AppFlags { db, audio, open_link }: Self::Flags) -> (Self, Command<Message>) {
+        let mut app = OpenChimeApp {
             db,
             audio,
             shutdown: tokio_util::sync::CancellationToken::new(),
             ui_state: UiState::new(),
             events: Vec::new(),
+            merged_events: Vec::new(),
             settings: Settings::default(),
             accounts: Vec::new(),
+            groups: Vec::new(),
+            focus: FocusTimer::new(
+                Settings::default().focus_work_duration(),
+                Settings::default().focus_break_duration(),
+            ),
+            stats: None,
+            sync_reliability: None,
+            badge_count: None,
+            tray: None,
+            tray_status: None,
         };
+
+        if let Some(link) = open_link {
+            app.apply_protocol_link(&link);
+        }
         
         // Load events and accounts on startup
         let db_clone = app.db.clone();
         let startup_command = Command::perform(async move {
-            // Load upcoming events from database (starting now to next 6 months)
+            // Load upcoming events from database (starting now to the end of the sync window)
             // Use local time for filtering to match user's timezone
             let local_now = get_local_now();
             let now_utc = local_now.with_timezone(&chrono::Utc);
-            let six_months_ahead = now_utc + chrono::Duration::days(180);
 
-            let events = match sqlx::query_as::<_, crate::models::CalendarEvent>(
-                "SELECT id, external_id, account_id, title, description, start_time, end_time, video_link, video_platform, snooze_count, has_alerted, last_alert_threshold, is_dismissed, created_at, updated_at FROM events WHERE start_time >= ? AND start_time <= ? AND is_dismissed = 0 ORDER BY start_time ASC"
-            )
-            .bind(now_utc)
-            .bind(six_months_ahead)
-            .fetch_all(&db_clone.pool)
+            let events = match crate::database::events::get_to_window_end(&db_clone.pool, now_utc)
             .await {
                 Ok(events) => {
                     log::info!("Loaded {} events on startup", events.len());
@@ -124,11 +332,7 @@ impl Application for OpenChimeApp {
             };
             
             // Load accounts
-            let accounts = match sqlx::query_as::<_, crate::models::Account>(
-                "SELECT id, provider, account_name, auth_data, refresh_token, last_synced_at FROM accounts ORDER BY created_at ASC"
-            )
-            .fetch_all(&db_clone.pool)
-            .await {
+            let accounts = match crate::database::accounts::get_all(&db_clone.pool).await {
                 Ok(accounts) => accounts,
                 Err(e) => {
                     log::error!("Failed to load accounts: {}", e);
@@ -138,12 +342,23 @@ impl Application for OpenChimeApp {
             
             (events, accounts)
         }, |(events, accounts)| Message::DataLoaded(events, accounts));
+
+        // Load persisted settings so opt-in features (like the update check) apply
+        let db_clone = app.db.clone();
+        let settings_command = Command::perform(async move {
+            crate::database::settings::get(&db_clone.pool).await.unwrap_or_else(|e| {
+                log::error!("Failed to load settings: {}", e);
+                Settings::default()
+            })
+        }, Message::SettingsUpdated);
+
+        let groups_command = app.reload_groups();
         
-        (app, startup_command)
+        (app, Command::batch(vec![startup_command, settings_command, groups_command]))
     }
 
     fn title(&self) -> String {
-        "OpenChime".to_string()
+        window_title(&self.events, chrono::Utc::now())
     }
 
     fn update(&mut self, message: Message) -> Command<Message> {
@@ -160,17 +375,49 @@ impl Application for OpenChimeApp {
                 self.ui_state.current_view = View::Alerts;
                 Command::none()
             }
+            Message::ShowFocus => {
+                self.ui_state.current_view = View::Focus;
+                Command::none()
+            }
+            Message::ShowStats => {
+                self.ui_state.current_view = View::Stats;
+                // Recomputed on every visit; it's a single query
+                let db = self.db.clone();
+                let stats = Command::perform(async move {
+                    crate::database::stats::meeting_stats(&db.pool).await
+                }, |result| Message::StatsLoaded(result.map_err(|e| e.to_string())));
+                let db = self.db.clone();
+                let reliability = Command::perform(async move {
+                    crate::database::sync_history::recent_reliability(&db.pool).await
+                }, |result| Message::SyncReliabilityLoaded(result.map_err(|e| e.to_string())));
+                Command::batch(vec![stats, reliability])
+            }
+            Message::StatsLoaded(Ok(stats)) => {
+                self.stats = Some(stats);
+                Command::none()
+            }
+            Message::StatsLoaded(Err(error)) => {
+                error!("Failed to compute statistics: {}", error);
+                self.ui_state.sync_status = user_friendly_error(&error);
+                Command::none()
+            }
+            Message::SyncReliabilityLoaded(Ok(report)) => {
+                self.sync_reliability = Some(report);
+                Command::none()
+            }
+            Message::SyncReliabilityLoaded(Err(error)) => {
+                error!("Failed to read sync history: {}", error);
+                Command::none()
+            }
             Message::SyncCalendars => {
                 self.ui_state.sync_status = "Fetching accounts...".to_string();
                 self.ui_state.loading = true;
                 let db = self.db.clone();
+                let shutdown = self.shutdown.clone();
+                let timeout = self.settings.sync_timeout_duration();
                 Command::perform(async move {
                     // Get all accounts and sync them
-                    let accounts = match sqlx::query_as::<_, crate::models::Account>(
-                        "SELECT id, provider, account_name, auth_data, refresh_token, last_synced_at FROM accounts"
-                    )
-                    .fetch_all(&db.pool)
-                    .await {
+                    let accounts = match crate::database::accounts::get_syncable(&db.pool).await {
                         Ok(accounts) => {
                             log::info!("Found {} accounts to sync", accounts.len());
                             accounts
@@ -187,15 +434,15 @@ impl Application for OpenChimeApp {
                     
                     for account in accounts.iter() {
                         log::info!("Attempting to sync account: {} ({})", account.account_name, account.provider);
-                        match crate::calendar::sync_account(account, &db.pool).await {
+                        match crate::calendar::sync_account(account, &db.pool, &crate::calendar::cache::IcsCache::default_location(), &shutdown, timeout).await {
                             Ok(sync_result) => {
                                 total_events += sync_result.events_added + sync_result.events_updated;
                                 successful_syncs += 1;
-                                log::info!("Synced account {}: {} events added, {} events updated", 
-                                          account.account_name, sync_result.events_added, sync_result.events_updated);
+                                log::info!("Synced account {}: {} events added, {} events updated, {} events deleted", 
+                                          account.account_name, sync_result.events_added, sync_result.events_updated, sync_result.events_deleted);
                             }
                             Err(e) => {
-                                log::error!("Failed to sync account {}: {}", account.account_name, e);
+                                log::error!("Failed to sync account {}: {:#}", account.account_name, e);
                                 // Continue with other accounts even if one fails
                             }
                         }
@@ -224,7 +471,46 @@ impl Application for OpenChimeApp {
                 Command::none()
             }
             Message::IcsUrlChanged(url) => {
-                self.ui_state.ics_url = url;
+                // Outlook's published page is a web page; its feed sits next to it
+                self.ui_state.ics_url = match calendar::common::outlook_feed_url(&url) {
+                    Some(feed) => {
+                        self.ui_state.sync_status = "That's an Outlook calendar page; its ICS feed will be used instead.".to_string();
+                        feed
+                    }
+                    None => url,
+                };
+                self.ui_state.discovered_feeds.clear();
+                self.ui_state.discovered_calendars.clear();
+                self.ui_state.account_preview = None;
+                self.ui_state.connection_test.clear();
+                Command::none()
+            }
+            Message::CalDavUsernameChanged(username) => {
+                self.ui_state.caldav_username = username;
+                Command::none()
+            }
+            Message::CalDavPasswordChanged(password) => {
+                self.ui_state.caldav_password = password;
+                Command::none()
+            }
+            Message::RequestHeaderChanged(header) => {
+                self.ui_state.header_input = header;
+                Command::none()
+            }
+            Message::AddRequestHeader => {
+                match calendar::source::parse_header(&self.ui_state.header_input) {
+                    Ok((name, value)) => {
+                        self.ui_state.request_headers.push(format!("{}: {}", name, value));
+                        self.ui_state.header_input.clear();
+                    }
+                    Err(e) => self.ui_state.sync_status = format!("❌ {}", e),
+                }
+                Command::none()
+            }
+            Message::RemoveRequestHeader(index) => {
+                if index < self.ui_state.request_headers.len() {
+                    self.ui_state.request_headers.remove(index);
+                }
                 Command::none()
             }
             Message::AddProtonAccount => {
@@ -232,44 +518,152 @@ impl Application for OpenChimeApp {
                     return Command::none();
                 }
 
-                let url = self.ui_state.ics_url.clone();
-                let account_name = self.ui_state.account_name.clone();
-                let provider = detect_provider_from_url(&url);
-
-                // Validate ICS URL format for Proton accounts
-                if matches!(provider, crate::models::CalendarProvider::Proton) {
-                    if let Err(e) = calendar::common::validate_ics_url_format(&url) {
-                        self.ui_state.sync_status = format!("❌ Invalid ICS URL: {}", e);
+                let url = self.ui_state.ics_url.trim().to_string();
+                let username = self.ui_state.caldav_username.trim().to_string();
+                if !username.is_empty() && calendar::caldav::well_known_url(&url).is_some() {
+                    // Just the server's hostname; find the calendars on it
+                    if self.ui_state.caldav_password.is_empty() {
+                        self.ui_state.sync_status = "❌ Enter the app password for this CalDAV account".to_string();
                         return Command::none();
                     }
+                    let credentials = calendar::caldav::CalDavCredentials {
+                        username,
+                        password: self.ui_state.caldav_password.clone(),
+                    };
+                    self.ui_state.sync_status = "Looking for calendars on the server...".to_string();
+                    return Command::perform(async move {
+                        let calendars = calendar::caldav::discover_calendars(&url, &credentials).await.map_err(|e| e.to_string());
+                        (url, calendars)
+                    }, |(url, calendars)| Message::CalendarsDiscovered(url, calendars));
+                }
+                if !username.is_empty() || calendar::discovery::looks_like_feed_url(&url) {
+                    return self.link_account();
                 }
 
-                // Create account with the correct provider
-                let account = match provider {
-                    crate::models::CalendarProvider::Google => {
-                        Account::new_google(account_name, url, None)
+                // Probably a web page rather than its feed; look for one first
+                self.ui_state.sync_status = "Looking for a calendar feed...".to_string();
+                Command::perform(async move {
+                    let feeds = calendar::discovery::discover_feeds(&url).await.map_err(|e| e.to_string());
+                    (url, feeds)
+                }, |(url, feeds)| Message::FeedsDiscovered(url, feeds))
+            }
+            Message::FeedsDiscovered(url, result) => {
+                if url != self.ui_state.ics_url.trim() {
+                    return Command::none(); // The field changed since
+                }
+                match result {
+                    Ok(feeds) if feeds == [url.clone()] => self.link_account(),
+                    Ok(feeds) if feeds.is_empty() => {
+                        self.ui_state.sync_status = "No calendar feed found on that page. Copy the calendar's ICS link instead \
+                            (for Google Calendar, the 'Secret address in iCal format').".to_string();
+                        Command::none()
+                    }
+                    Ok(feeds) => {
+                        self.ui_state.sync_status = "That's a web page; pick the calendar feed found on it below.".to_string();
+                        self.ui_state.discovered_feeds = feeds;
+                        Command::none()
+                    }
+                    // Let linking report what's wrong with the URL
+                    Err(error) => {
+                        log::warn!("Feed discovery failed: {}", error);
+                        self.link_account()
+                    }
+                }
+            }
+            Message::CalendarsDiscovered(server, result) => {
+                if server != self.ui_state.ics_url.trim() {
+                    return Command::none(); // The field changed since
+                }
+                match result {
+                    Ok(calendars) if calendars.is_empty() => {
+                        self.ui_state.sync_status = "No event calendars found for that account on the server.".to_string();
                     }
-                    crate::models::CalendarProvider::Proton => {
-                        Account::new_proton(account_name, url)
+                    Ok(calendars) => {
+                        self.ui_state.sync_status = "Pick the calendar to add below.".to_string();
+                        self.ui_state.discovered_calendars = calendars;
                     }
+                    Err(error) => {
+                        log::warn!("CalDAV discovery failed: {}", error);
+                        self.ui_state.sync_status = format!("❌ {}", error);
+                    }
+                }
+                Command::none()
+            }
+            Message::UseDiscoveredCalendar(url) => {
+                self.ui_state.ics_url = url;
+                self.ui_state.discovered_calendars.clear();
+                self.link_account()
+            }
+            Message::PreviewAccount => {
+                if self.ui_state.ics_url.trim().is_empty() {
+                    return Command::none();
+                }
+                let Some(account) = self.account_from_form() else {
+                    return Command::none();
                 };
+                self.ui_state.account_preview = None;
+                self.ui_state.sync_status = "Fetching the calendar to preview...".to_string();
 
                 let db = self.db.clone();
-                
+                let shutdown = self.shutdown.clone();
+                let timeout = self.settings.sync_timeout_duration();
+                let url = self.ui_state.ics_url.clone();
                 Command::perform(async move {
-                    // Actually save the account to database
-                    sqlx::query(
-                        "INSERT INTO accounts (provider, account_name, auth_data, created_at, updated_at) VALUES (?, ?, ?, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)"
-                    )
-                    .bind(&account.provider)
-                    .bind(&account.account_name)
-                    .bind(&account.auth_data)
-                    .execute(&db.pool)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Failed to save account: {}", e))?;
-                    
-                    Ok(account)
-                }, |result: Result<Account, anyhow::Error>| Message::AccountAdded(result.map_err(|e| e.to_string())))
+                    let result = crate::calendar::preview_account(&account, &db.pool, &shutdown, timeout).await;
+                    (url, result.map_err(|e| e.to_string()))
+                }, |(url, result)| Message::AccountPreviewed(url, result))
+            }
+            Message::TestAccount => {
+                if self.ui_state.ics_url.trim().is_empty() {
+                    return Command::none();
+                }
+                let Some(account) = self.account_from_form() else {
+                    return Command::none();
+                };
+                self.ui_state.connection_test.clear();
+                self.ui_state.sync_status = "Testing the calendar...".to_string();
+
+                let settings = self.settings.clone();
+                let url = self.ui_state.ics_url.clone();
+                Command::perform(async move {
+                    let steps = crate::calendar::test_connection(&account, &settings).await;
+                    (url, steps)
+                }, |(url, steps)| Message::AccountTested(url, steps))
+            }
+            Message::AccountTested(url, steps) => {
+                if url != self.ui_state.ics_url {
+                    return Command::none(); // The field changed since
+                }
+                self.ui_state.sync_status = match steps.iter().find(|step| step.status != crate::doctor::CheckStatus::Pass) {
+                    Some(step) => format!("Test: {} – {}", step.name, step.detail),
+                    None => "✓ Test passed; the calendar is ready to link.".to_string(),
+                };
+                self.ui_state.connection_test = steps;
+                Command::none()
+            }
+            Message::AccountPreviewed(url, result) => {
+                if url != self.ui_state.ics_url {
+                    return Command::none(); // The field changed since
+                }
+                match result {
+                    Ok(preview) => {
+                        self.ui_state.sync_status = format!(
+                            "Linking would add {} events, update {} and remove {}.",
+                            preview.events_added, preview.events_updated, preview.events_deleted
+                        );
+                        self.ui_state.account_preview = Some(preview);
+                    }
+                    Err(error) => {
+                        log::warn!("Sync preview failed: {}", error);
+                        self.ui_state.sync_status = user_friendly_error(&error);
+                    }
+                }
+                Command::none()
+            }
+            Message::UseDiscoveredFeed(feed) => {
+                self.ui_state.ics_url = feed;
+                self.ui_state.discovered_feeds.clear();
+                self.link_account()
             }
             Message::CalendarSyncResult(Ok(())) => {
                 self.ui_state.sync_status = "Sync completed successfully".to_string();
@@ -278,17 +672,11 @@ impl Application for OpenChimeApp {
                 log::info!("Sync completed successfully, reloading events...");
                 // Reload events to show updated data
                 let db = self.db.clone();
-                Command::perform(async move {
+                let reload_events = Command::perform(async move {
                     let local_now = get_local_now();
                     let now_utc = local_now.with_timezone(&chrono::Utc);
-                    let six_months_ahead = now_utc + chrono::Duration::days(180);
 
-                    sqlx::query_as::<_, crate::models::CalendarEvent>(
-                        "SELECT id, external_id, account_id, title, description, start_time, end_time, video_link, video_platform, snooze_count, has_alerted, last_alert_threshold, is_dismissed, created_at, updated_at FROM events WHERE start_time >= ? AND start_time <= ? AND is_dismissed = 0 ORDER BY start_time ASC"
-                    )
-                    .bind(now_utc)
-                    .bind(six_months_ahead)
-                    .fetch_all(&db.pool)
+                    crate::database::events::get_to_window_end(&db.pool, now_utc)
                     .await
                     .map_err(|e| anyhow::anyhow!("Failed to reload events: {}", e))
                 }, |result: Result<Vec<CalendarEvent>, anyhow::Error>| {
@@ -299,12 +687,13 @@ impl Application for OpenChimeApp {
                         }
                         Err(e) => Message::CalendarSyncResult(Err(e.to_string()))
                     }
-                })
+                });
+                Command::batch(vec![reload_events, self.reload_accounts()])
             }
             Message::CalendarSyncResult(Err(error)) => {
                 self.ui_state.sync_status = user_friendly_error(&error);
                 self.ui_state.loading = false;
-                Command::none()
+                self.reload_accounts()
             }
             Message::AudioTestResult(Ok(())) => {
                 info!("Audio test completed successfully");
@@ -320,18 +709,20 @@ impl Application for OpenChimeApp {
                 info!("Account added: {}", account.account_name);
                 self.ui_state.account_name.clear();
                 self.ui_state.ics_url.clear();
+                self.ui_state.caldav_username.clear();
+                self.ui_state.caldav_password.clear();
+                self.ui_state.discovered_calendars.clear();
+                self.ui_state.header_input.clear();
+                self.ui_state.request_headers.clear();
                 
                 // Reload accounts to show newly added account
                 let db = self.db.clone();
                 let current_events = self.events.clone();
                 
                 let reload_accounts = Command::perform(async move {
-                    sqlx::query_as::<_, crate::models::Account>(
-                        "SELECT id, provider, account_name, auth_data, refresh_token, last_synced_at FROM accounts ORDER BY created_at ASC"
-                    )
-                    .fetch_all(&db.pool)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Failed to reload accounts: {}", e))
+                    crate::database::accounts::get_all(&db.pool)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("Failed to reload accounts: {}", e))
                 }, move |result: Result<Vec<Account>, anyhow::Error>| {
                     match result {
                         Ok(accounts) => Message::DataLoaded(current_events.clone(), accounts),
@@ -353,33 +744,53 @@ impl Application for OpenChimeApp {
             Message::EventsUpdated(events) => {
                 log::info!("EventsUpdated received with {} events", events.len());
                 self.events = events;
-                Command::none()
+                self.events_changed();
+                self.refresh_badge()
             }
             Message::SettingsUpdated(settings) => {
                 self.settings = settings;
+                crate::http_config::apply_settings(&self.settings);
+                self.focus.set_durations(self.settings.focus_work_duration(), self.settings.focus_break_duration());
+                self.audio.set_sound_schedule(self.settings.sound_schedule());
+                if self.settings.check_for_updates && !self.ui_state.update_checked {
+                    self.check_for_updates()
+                } else {
+                    Command::none()
+                }
+            }
+            Message::UpdateCheckResult(Ok(update)) => {
+                if let Some(update) = &update {
+                    info!("Update available: {}", update.version);
+                }
+                self.ui_state.available_update = update;
+                Command::none()
+            }
+            Message::UpdateCheckResult(Err(error)) => {
+                // Update checks are best-effort; don't surface failures in the status card
+                log::warn!("Update check failed: {}", error);
                 Command::none()
             }
             Message::DataLoaded(events, accounts) => {
                 self.events = events.clone();
-                self.accounts = accounts.clone();
-                log::info!("Loaded {} events and {} accounts", events.len(), accounts.len());
+                self.events_changed();
+                // The built-in reminders account isn't a calendar to manage or sync
+                self.accounts = accounts.into_iter().filter(|a| !a.is_local()).collect();
+                log::info!("Loaded {} events and {} accounts", events.len(), self.accounts.len());
                 
                 // Automatically trigger sync to fetch fresh events after loading
-                if accounts.len() > 0 {
+                if !self.accounts.is_empty() {
                     log::info!("Triggering initial calendar sync");
                     self.ui_state.sync_status = "Initial sync...".to_string();
                     self.ui_state.loading = true;
-                    Command::perform(async {}, |_| Message::SyncCalendars)
+                    Command::batch(vec![self.refresh_badge(), Command::perform(async {}, |_| Message::SyncCalendars)])
                 } else {
-                    Command::none()
+                    self.refresh_badge()
                 }
             }
             Message::DeleteAccount(account_id) => {
                 let db = self.db.clone();
                 Command::perform(async move {
-                    sqlx::query("DELETE FROM accounts WHERE id = ?")
-                        .bind(account_id)
-                        .execute(&db.pool)
+                    crate::database::accounts::delete(&db.pool, account_id)
                         .await
                         .map_err(|e| anyhow::anyhow!("Failed to delete account: {}", e))?;
                     Ok(())
@@ -390,12 +801,9 @@ impl Application for OpenChimeApp {
                 let db = self.db.clone();
                 let current_events = self.events.clone();
                 Command::perform(async move {
-                    sqlx::query_as::<_, crate::models::Account>(
-                        "SELECT id, provider, account_name, auth_data, refresh_token, last_synced_at FROM accounts ORDER BY created_at ASC"
-                    )
-                    .fetch_all(&db.pool)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Failed to reload accounts: {}", e))
+                    crate::database::accounts::get_all(&db.pool)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("Failed to reload accounts: {}", e))
                 }, move |result: Result<Vec<Account>, anyhow::Error>| {
                     match result {
                         Ok(accounts) => Message::DataLoaded(current_events.clone(), accounts),
@@ -411,9 +819,23 @@ impl Application for OpenChimeApp {
             }
             Message::MonitorEventReceived(event) => {
                 match event {
-                    crate::alerts::MonitorEvent::AlertTriggered(_calendar_event) => {
+                    crate::alerts::MonitorEvent::AlertTriggered(calendar_event, alert_type, to_prepare) => {
                         // Switch to alerts view
                         self.ui_state.current_view = View::Alerts;
+
+                        if let Some(event_id) = calendar_event.id {
+                            if to_prepare.is_empty() {
+                                self.ui_state.to_prepare.remove(&event_id);
+                            } else {
+                                self.ui_state.to_prepare.insert(event_id, to_prepare);
+                            }
+                        }
+
+                        // Hold the focus timer from the start until the meeting
+                        // is over; early warnings leave it running
+                        if alert_type.is_start() {
+                            self.focus.pause_for_meeting(chrono::Utc::now(), calendar_event.end_time);
+                        }
                         
                         // Request window attention (flash taskbar/bounce dock)
                         let attention_cmd = iced::window::request_user_attention(iced::window::Id::MAIN, Some(iced::window::UserAttention::Critical));
@@ -423,14 +845,8 @@ impl Application for OpenChimeApp {
                         let reload_cmd = Command::perform(async move {
                             let local_now = get_local_now();
                             let now_utc = local_now.with_timezone(&chrono::Utc);
-                            let six_months_ahead = now_utc + chrono::Duration::days(180);
-
-                            sqlx::query_as::<_, crate::models::CalendarEvent>(
-                                "SELECT id, external_id, account_id, title, description, start_time, end_time, video_link, video_platform, snooze_count, has_alerted, last_alert_threshold, is_dismissed, created_at, updated_at FROM events WHERE start_time >= ? AND start_time <= ? AND is_dismissed = 0 ORDER BY start_time ASC"
-                            )
-                            .bind(now_utc)
-                            .bind(six_months_ahead)
-                            .fetch_all(&db.pool)
+
+                            crate::database::events::get_to_window_end(&db.pool, now_utc)
                             .await
                             .map_err(|e| anyhow::anyhow!("Failed to reload events: {}", e))
                         }, |result: Result<Vec<CalendarEvent>, anyhow::Error>| {
@@ -442,36 +858,42 @@ impl Application for OpenChimeApp {
 
                         Command::batch(vec![attention_cmd, reload_cmd])
                     }
-                    crate::alerts::MonitorEvent::SyncCompleted { added, updated } => {
-                         if added > 0 || updated > 0 {
-                            self.ui_state.last_sync_time = Some(chrono::Utc::now());
-                            self.ui_state.sync_status = format!("Auto-sync: {} added, {} updated", added, updated);
-                            
-                            // Refresh events list
-                            let db = self.db.clone();
-                            Command::perform(async move {
-                                let local_now = get_local_now();
-                                let now_utc = local_now.with_timezone(&chrono::Utc);
-                                let six_months_ahead = now_utc + chrono::Duration::days(180);
-
-                                sqlx::query_as::<_, crate::models::CalendarEvent>(
-                                    "SELECT id, external_id, account_id, title, description, start_time, end_time, video_link, video_platform, snooze_count, has_alerted, last_alert_threshold, is_dismissed, created_at, updated_at FROM events WHERE start_time >= ? AND start_time <= ? AND is_dismissed = 0 ORDER BY start_time ASC"
-                                )
-                                .bind(now_utc)
-                                .bind(six_months_ahead)
-                                .fetch_all(&db.pool)
-                                .await
-                                .map_err(|e| anyhow::anyhow!("Failed to reload events: {}", e))
-                            }, |result: Result<Vec<CalendarEvent>, anyhow::Error>| {
-                                match result {
-                                    Ok(events) => Message::EventsUpdated(events),
-                                    Err(_) => Message::EventsUpdated(Vec::new())
-                                }
-                            })
-                        } else {
-                             self.ui_state.last_sync_time = Some(chrono::Utc::now());
-                             Command::none()
+                    crate::alerts::MonitorEvent::SyncCompleted(changes) => {
+                        self.ui_state.last_sync_time = Some(chrono::Utc::now());
+                        if !changes.is_empty() {
+                            self.ui_state.sync_status = format!("Auto-sync: {} added, {} updated", changes.added.len(), changes.updated.len());
+
+                            // Patch the list in place rather than reloading it, so
+                            // large calendars don't flicker on every background sync
+                            let now_utc = get_local_now().with_timezone(&chrono::Utc);
+                            let window_end = now_utc + self.settings.sync_days_ahead_duration();
+                            changes.apply(&mut self.events, now_utc, window_end);
+                            self.events_changed();
                         }
+                        Command::batch(vec![self.refresh_badge(), self.reload_accounts()])
+                    }
+                    crate::alerts::MonitorEvent::SyncStalled(stalled) => {
+                        // Flag the window and notify only when an account newly stops syncing
+                        let now = chrono::Utc::now();
+                        let newly_stalled: Vec<String> = stalled.iter()
+                            .filter(|s| !self.ui_state.stalled_syncs.iter().any(|known| known.account_id == s.account_id))
+                            .map(|s| s.message(now))
+                            .collect();
+                        for account in &stalled {
+                            log::warn!("{}", account.message(now));
+                        }
+                        self.ui_state.stalled_syncs = stalled;
+                        if newly_stalled.is_empty() {
+                            return Command::none();
+                        }
+                        let notify = Command::perform(
+                            crate::notify::show("OpenChime can't sync".to_string(), newly_stalled.join("\n")),
+                            |_| Message::NotificationShown,
+                        );
+                        Command::batch(vec![
+                            iced::window::request_user_attention(iced::window::Id::MAIN, Some(iced::window::UserAttention::Informational)),
+                            notify,
+                        ])
                     }
                     crate::alerts::MonitorEvent::Error(e) => {
                         log::error!("Background monitor error: {}", e);
@@ -479,68 +901,823 @@ impl Application for OpenChimeApp {
                     }
                 }
             }
-            Message::JoinMeeting(url) => {
-                log::info!("Opening meeting URL: {}", url);
-                #[cfg(target_os = "macos")]
-                let _ = std::process::Command::new("open").arg(&url).spawn();
-                #[cfg(target_os = "linux")]
-                let _ = std::process::Command::new("xdg-open").arg(&url).spawn();
-                #[cfg(target_os = "windows")]
-                let _ = std::process::Command::new("cmd").arg("/C").arg("start").arg(&url).spawn();
-                
+            Message::EditEventTitle(event_id) => {
+                let current = self.events.iter()
+                    .find(|e| e.id == Some(event_id))
+                    .map(|e| e.display_title().to_string())
+                    .unwrap_or_default();
+                self.ui_state.editing_title = Some((event_id, current));
                 Command::none()
             }
-            Message::ToggleAlert30m(enabled) => {
-                self.settings.alert_30m = enabled;
-                let pool = self.db.pool.clone();
-                let settings = self.settings.clone();
+            Message::EventTitleInputChanged(title) => {
+                if let Some((_, input)) = &mut self.ui_state.editing_title {
+                    *input = title;
+                }
+                Command::none()
+            }
+            Message::CancelEditEventTitle => {
+                self.ui_state.editing_title = None;
+                Command::none()
+            }
+            Message::SaveEventTitle => {
+                let Some((event_id, input)) = self.ui_state.editing_title.take() else {
+                    return Command::none();
+                };
+                let title_override = Some(input.trim().to_string()).filter(|t| !t.is_empty());
+
+                // Rename every calendar's copy in place; the database does the same
+                if let Some(renamed) = self.events.iter().find(|e| e.id == Some(event_id)).cloned() {
+                    for event in self.events.iter_mut().filter(|e| e.is_same_meeting(&renamed)) {
+                        event.title_override = title_override.clone();
+                    }
+                    self.events_changed();
+                }
+
+                let db = self.db.clone();
                 Command::perform(async move {
-                    crate::database::settings::update(&pool, &settings).await
-                        .map_err(|e| anyhow::anyhow!("Failed to update settings: {}", e))
-                }, |res| match res {
-                    Ok(_) => Message::SettingsUpdated(Settings::default()), // Dummy message or real update logic? Ideally refetch. For now ignored.
-                    Err(e) => Message::CalendarSyncResult(Err(e.to_string())) // Reuse error handler
-                })
+                    crate::database::events::set_title_override(&db.pool, event_id, title_override.as_deref()).await
+                }, |result| Message::EventTitleSaved(result.map_err(|e| e.to_string())))
             }
-            Message::ToggleAlert10m(enabled) => {
-                self.settings.alert_10m = enabled;
-                let pool = self.db.pool.clone();
-                let settings = self.settings.clone();
+            Message::EventTitleSaved(Ok(())) => Command::none(),
+            Message::EventTitleSaved(Err(error)) => {
+                error!("Failed to rename event: {}", error);
+                self.ui_state.sync_status = format!("❌ Could not rename event: {}", error);
+                Command::none()
+            }
+            Message::SyncAccount(account_id) => {
+                let Some(account) = self.accounts.iter().find(|a| a.id == Some(account_id)) else {
+                    return Command::none();
+                };
+                self.ui_state.sync_status = format!("Syncing {}...", account.account_name);
+                self.ui_state.loading = true;
+
+                let handlers = crate::command_handlers::CalendarHandlers::new((*self.db).clone());
+                let shutdown = self.shutdown.clone();
                 Command::perform(async move {
-                    crate::database::settings::update(&pool, &settings).await
-                        .map_err(|e| anyhow::anyhow!("Failed to update settings: {}", e))
-                }, |res| match res { Ok(_) => Message::SettingsUpdated(Settings::default()), Err(e) => Message::CalendarSyncResult(Err(e.to_string())) })
+                    handlers.sync_account(account_id, &shutdown).await
+                }, move |result| Message::AccountSynced(account_id, result.map_err(|e| e.to_string())))
             }
-            Message::ToggleAlert5m(enabled) => {
-                self.settings.alert_5m = enabled;
-                let pool = self.db.pool.clone();
-                let settings = self.settings.clone();
+            Message::AccountSynced(account_id, result) => {
+                self.ui_state.loading = false;
+                let name = self.accounts.iter()
+                    .find(|a| a.id == Some(account_id))
+                    .map_or_else(|| "Account".to_string(), |a| a.account_name.clone());
+                match result {
+                    Ok(sync_result) => {
+                        self.ui_state.last_sync_time = Some(sync_result.sync_time);
+                        self.ui_state.stalled_syncs.retain(|s| s.account_id != account_id);
+                        self.ui_state.sync_status = format!(
+                            "✓ {} synced: {} added, {} updated, {} removed",
+                            name, sync_result.events_added, sync_result.events_updated, sync_result.events_deleted
+                        );
+                        let now_utc = get_local_now().with_timezone(&chrono::Utc);
+                        let window_end = now_utc + self.settings.sync_days_ahead_duration();
+                        sync_result.changes.apply(&mut self.events, now_utc, window_end);
+                        self.events_changed();
+                        Command::batch(vec![self.refresh_badge(), self.reload_accounts()])
+                    }
+                    Err(error) => {
+                        error!("Manual sync of {} failed: {}", name, error);
+                        self.ui_state.sync_status = user_friendly_error(&error);
+                        self.reload_accounts()
+                    }
+                }
+            }
+            Message::ExportEvents => {
+                let db = self.db.clone();
                 Command::perform(async move {
-                    crate::database::settings::update(&pool, &settings).await
-                        .map_err(|e| anyhow::anyhow!("Failed to update settings: {}", e))
-                }, |res| match res { Ok(_) => Message::SettingsUpdated(Settings::default()), Err(e) => Message::CalendarSyncResult(Err(e.to_string())) })
+                    let now = chrono::Utc::now();
+                    let (ics, count) = crate::calendar::export::export_upcoming(&db.pool, now).await?;
+                    let dir = dirs::download_dir().or_else(dirs::home_dir).unwrap_or_else(std::env::temp_dir);
+                    let path = dir.join(format!("openchime-agenda-{}.ics", get_local_now().format("%Y-%m-%d")));
+                    tokio::fs::write(&path, ics).await?;
+                    Ok((path, count))
+                }, |result: anyhow::Result<(std::path::PathBuf, usize)>| Message::EventsExported(result.map_err(|e| e.to_string())))
             }
-            Message::ToggleAlert1m(enabled) => {
-                self.settings.alert_1m = enabled;
-                let pool = self.db.pool.clone();
-                let settings = self.settings.clone();
+            Message::EventsExported(Ok((path, count))) => {
+                info!("Exported {} events to {}", count, path.display());
+                self.ui_state.sync_status = format!("✓ Exported {} events to {}", count, path.display());
+                Command::none()
+            }
+            Message::EventsExported(Err(error)) => {
+                error!("Export failed: {}", error);
+                self.ui_state.sync_status = format!("❌ Could not export events: {}", error);
+                Command::none()
+            }
+            Message::ImportInputChanged(input) => {
+                self.ui_state.import_input = input;
+                Command::none()
+            }
+            Message::ImportIcs => {
+                if self.ui_state.import_input.trim().is_empty() {
+                    return Command::none();
+                }
+                let input = self.ui_state.import_input.clone();
+                let db = self.db.clone();
                 Command::perform(async move {
-                    crate::database::settings::update(&pool, &settings).await
-                        .map_err(|e| anyhow::anyhow!("Failed to update settings: {}", e))
-                }, |res| match res { Ok(_) => Message::SettingsUpdated(Settings::default()), Err(e) => Message::CalendarSyncResult(Err(e.to_string())) })
+                    let ics = crate::calendar::import::read_input(&input).await?;
+                    crate::calendar::import::import_ics(&db.pool, &ics).await
+                }, |result| Message::IcsImported(result.map_err(|e| e.to_string())))
             }
-            Message::ToggleAlertDefault(enabled) => {
-                self.settings.alert_default = enabled;
-                let pool = self.db.pool.clone();
-                let settings = self.settings.clone();
+            Message::IcsImported(Ok(result)) => {
+                self.ui_state.import_input.clear();
+                self.ui_state.sync_status = format!(
+                    "✓ Imported {} new events ({} updated, {} cancelled)",
+                    result.events_added, result.events_updated, result.events_deleted
+                );
+                let now_utc = get_local_now().with_timezone(&chrono::Utc);
+                let window_end = now_utc + self.settings.sync_days_ahead_duration();
+                result.changes.apply(&mut self.events, now_utc, window_end);
+                self.events_changed();
+
+                // The Imported account may be new
+                let db = self.db.clone();
+                let events = self.events.clone();
                 Command::perform(async move {
-                    crate::database::settings::update(&pool, &settings).await
-                        .map_err(|e| anyhow::anyhow!("Failed to update settings: {}", e))
-                }, |res| match res { Ok(_) => Message::SettingsUpdated(Settings::default()), Err(e) => Message::CalendarSyncResult(Err(e.to_string())) })
+                    crate::database::accounts::get_all(&db.pool).await
+                }, move |accounts| match accounts {
+                    Ok(accounts) => Message::DataLoaded(events.clone(), accounts),
+                    Err(e) => Message::IcsImported(Err(e.to_string())),
+                })
             }
-            _ => Command::none(), // Handle other messages if needed
-        }
-    }
+            Message::IcsImported(Err(error)) => {
+                error!("ICS import failed: {}", error);
+                self.ui_state.sync_status = format!("❌ Could not import: {}", error);
+                Command::none()
+            }
+            Message::RetrySync(account_id) => {
+                let Some(account) = self.accounts.iter().find(|a| a.id == Some(account_id)).cloned() else {
+                    return Command::none();
+                };
+                self.ui_state.stalled_syncs.retain(|s| s.account_id != account_id);
+                self.ui_state.sync_status = format!("Retrying {}...", account.account_name);
+                self.ui_state.loading = true;
+
+                let db = self.db.clone();
+                let shutdown = self.shutdown.clone();
+                let timeout = self.settings.sync_timeout_duration();
+                Command::perform(async move {
+                    crate::calendar::retry_account(&account, &db.pool, &crate::calendar::cache::IcsCache::default_location(), &shutdown, timeout)
+                        .await
+                        .map(|_| ())
+                        .map_err(|e| anyhow::anyhow!("Failed to sync {}: {:#}", account.account_name, e))
+                }, |result| Message::CalendarSyncResult(result.map_err(|e| e.to_string())))
+            }
+            Message::SnoozeAll(event_ids) => {
+                let db = self.db.clone();
+                let until = chrono::Utc::now() + chrono::Duration::minutes(crate::alerts::SNOOZE_ALL_MINUTES);
+                Command::perform(async move {
+                    db.snooze_events(&event_ids, until).await
+                }, |result| Message::AlertsSnoozed(result.map_err(|e| e.to_string())))
+            }
+            Message::AlertsSnoozed(Ok(snoozed)) => {
+                self.ui_state.sync_status = format!(
+                    "😴 Snoozed {} alert{} for {} minutes",
+                    snoozed,
+                    if snoozed == 1 { "" } else { "s" },
+                    crate::alerts::SNOOZE_ALL_MINUTES
+                );
+                Command::none()
+            }
+            Message::AlertsSnoozed(Err(error)) => {
+                error!("Failed to snooze alerts: {}", error);
+                self.ui_state.sync_status = format!("❌ Could not snooze alerts: {}", error);
+                Command::none()
+            }
+            Message::DismissAll(event_ids) => {
+                // Drop every calendar's copy; the database does the same
+                let dismissed: Vec<CalendarEvent> = self.events.iter()
+                    .filter(|e| e.id.is_some_and(|id| event_ids.contains(&id)))
+                    .cloned()
+                    .collect();
+                self.events.retain(|e| !dismissed.iter().any(|d| e.is_same_meeting(d)));
+                self.events_changed();
+
+                let db = self.db.clone();
+                let dismiss = Command::perform(async move {
+                    db.dismiss_events(&event_ids).await
+                }, |result| Message::AlertsDismissed(result.map_err(|e| e.to_string())));
+                Command::batch(vec![dismiss, self.refresh_badge()])
+            }
+            Message::AlertsDismissed(Ok(())) => Command::none(),
+            Message::AlertsDismissed(Err(error)) => {
+                error!("Failed to dismiss alerts: {}", error);
+                self.ui_state.sync_status = format!("❌ Could not dismiss alerts: {}", error);
+                Command::none()
+            }
+            Message::NewReminder => {
+                self.ui_state.reminder_form = Some(ReminderForm::default());
+                Command::none()
+            }
+            Message::CancelReminder => {
+                self.ui_state.reminder_form = None;
+                Command::none()
+            }
+            Message::ReminderTitleChanged(title) => {
+                if let Some(form) = &mut self.ui_state.reminder_form {
+                    form.title = title;
+                }
+                Command::none()
+            }
+            Message::ReminderTimeChanged(time) => {
+                if let Some(form) = &mut self.ui_state.reminder_form {
+                    form.time = time;
+                }
+                Command::none()
+            }
+            Message::ReminderLinkChanged(link) => {
+                if let Some(form) = &mut self.ui_state.reminder_form {
+                    form.link = link;
+                }
+                Command::none()
+            }
+            Message::SaveReminder => {
+                let Some(form) = &mut self.ui_state.reminder_form else {
+                    return Command::none();
+                };
+                if form.title.trim().is_empty() {
+                    form.error = Some("Give the reminder a title".to_string());
+                    return Command::none();
+                }
+                let Some(start) = crate::utils::parse_reminder_time(&form.time, &get_local_now()) else {
+                    form.error = Some("Try a time like 15:00, 3pm, tomorrow 9am or in 20m".to_string());
+                    return Command::none();
+                };
+                form.error = None;
+
+                let db = self.db.clone();
+                let title = form.title.clone();
+                let link = form.link.clone();
+                Command::perform(async move {
+                    crate::database::events::add_reminder(&db.pool, &title, start.with_timezone(&chrono::Utc), Some(&link)).await
+                }, |result| Message::ReminderAdded(result.map_err(|e| e.to_string())))
+            }
+            Message::ReminderAdded(Ok(event)) => {
+                info!("Reminder added: {}", event.title);
+                self.ui_state.reminder_form = None;
+                self.ui_state.sync_status = format!(
+                    "Reminder set for {}",
+                    event.start_time.with_timezone(&chrono::Local).format("%a %I:%M %p")
+                );
+                let position = self.events.partition_point(|e| e.start_time <= event.start_time);
+                self.events.insert(position, event);
+                self.events_changed();
+                self.refresh_badge()
+            }
+            Message::ReminderAdded(Err(error)) => {
+                error!("Failed to add reminder: {}", error);
+                if let Some(form) = &mut self.ui_state.reminder_form {
+                    form.error = Some(format!("Could not save the reminder: {}", error));
+                }
+                Command::none()
+            }
+            Message::EditAccount(account_id) => {
+                let Some(account) = self.accounts.iter().find(|a| a.id == Some(account_id)) else {
+                    return Command::none();
+                };
+                self.ui_state.account_form = Some(AccountForm {
+                    account_id,
+                    name: account.account_name.clone(),
+                    url: account.auth_data.clone(),
+                    error: None,
+                });
+                Command::none()
+            }
+            Message::CancelEditAccount => {
+                self.ui_state.account_form = None;
+                Command::none()
+            }
+            Message::EditAccountNameChanged(name) => {
+                if let Some(form) = &mut self.ui_state.account_form {
+                    form.name = name;
+                }
+                Command::none()
+            }
+            Message::EditAccountUrlChanged(url) => {
+                if let Some(form) = &mut self.ui_state.account_form {
+                    form.url = url;
+                }
+                Command::none()
+            }
+            Message::SaveAccountEdit => {
+                let Some(form) = &mut self.ui_state.account_form else {
+                    return Command::none();
+                };
+                let Some(mut account) = self.accounts.iter().find(|a| a.id == Some(form.account_id)).cloned() else {
+                    self.ui_state.account_form = None;
+                    return Command::none();
+                };
+                if form.name.trim().is_empty() {
+                    form.error = Some("Give the calendar a name".to_string());
+                    return Command::none();
+                }
+                account.account_name = form.name.trim().to_string();
+                // Local and API accounts have no feed to point elsewhere
+                if !account.is_local() && !is_api_account(&account) {
+                    let url = calendar::common::normalize_feed_url(&form.url);
+                    if let Err(e) = calendar::common::validate_ics_url_format(&url) {
+                        form.error = Some(format!("Invalid ICS URL: {}", e));
+                        return Command::none();
+                    }
+                    // A feed moved to another host may have changed provider;
+                    // CalDAV accounts stay CalDAV
+                    if !matches!(account.provider(), Ok(crate::models::CalendarProvider::CalDav)) {
+                        account.provider = detect_provider_from_url(&url).as_str().to_string();
+                    }
+                    account.auth_data = url;
+                }
+                form.error = None;
+
+                let db = self.db.clone();
+                Command::perform(async move {
+                    db.update_account(&account).await.map(|_| account)
+                }, |result| Message::AccountUpdated(result.map_err(|e| e.to_string())))
+            }
+            Message::AccountUpdated(Ok(account)) => {
+                self.ui_state.account_form = None;
+                self.ui_state.sync_status = format!("✓ Saved {}", account.account_name);
+                let Some(existing) = self.accounts.iter_mut().find(|a| a.id == account.id) else {
+                    return Command::none();
+                };
+                let url_changed = existing.auth_data != account.auth_data;
+                existing.account_name = account.account_name;
+                existing.provider = account.provider;
+                existing.auth_data = account.auth_data;
+                match (url_changed, account.id) {
+                    // Read the new feed straight away
+                    (true, Some(account_id)) => {
+                        existing.last_sync_error = None;
+                        existing.last_sync_error_at = None;
+                        Command::perform(async {}, move |_| Message::SyncAccount(account_id))
+                    }
+                    _ => Command::none(),
+                }
+            }
+            Message::AccountUpdated(Err(error)) => {
+                error!("Failed to update account: {}", error);
+                if let Some(form) = &mut self.ui_state.account_form {
+                    form.error = Some(format!("Could not save the calendar: {}", error));
+                }
+                Command::none()
+            }
+            Message::JoinMeeting(url) => {
+                log::info!("Opening meeting URL: {}", url);
+                open_url(&url);
+                Command::none()
+            }
+            Message::OpenUrl(url) => {
+                log::info!("Opening URL: {}", url);
+                open_url(&url);
+                Command::none()
+            }
+            Message::ToggleAlert30m(enabled) => {
+                self.settings.alert_30m = enabled;
+                self.save_settings()
+            }
+            Message::ToggleAlert10m(enabled) => {
+                self.settings.alert_10m = enabled;
+                self.save_settings()
+            }
+            Message::ToggleAlert5m(enabled) => {
+                self.settings.alert_5m = enabled;
+                self.save_settings()
+            }
+            Message::ToggleAlert1m(enabled) => {
+                self.settings.alert_1m = enabled;
+                self.save_settings()
+            }
+            Message::ToggleAlertDefault(enabled) => {
+                self.settings.alert_default = enabled;
+                self.save_settings()
+            }
+            Message::ToggleCalendarReminders(enabled) => {
+                self.settings.calendar_reminders = enabled;
+                self.save_settings()
+            }
+            Message::ToggleAlertFreeEvents(enabled) => {
+                self.settings.alert_free_events = enabled;
+                self.save_settings()
+            }
+            Message::ToggleSkipAllDayEvents(enabled) => {
+                self.settings.skip_all_day_events = enabled;
+                self.save_settings()
+            }
+            Message::ToggleHoldSyncsOffline(enabled) => {
+                // The monitor loop picks this up on its next cycle
+                self.settings.hold_syncs_offline = enabled;
+                self.save_settings()
+            }
+            Message::SyncIntervalChanged(seconds) => {
+                // The monitor loop picks this up on its next cycle
+                self.settings.sync_interval = seconds;
+                self.save_settings()
+            }
+            Message::SyncDaysBackChanged(days) => {
+                // Applies from the next sync
+                self.settings.sync_days_back = days;
+                self.save_settings()
+            }
+            Message::SyncDaysAheadChanged(days) => {
+                self.settings.sync_days_ahead = days;
+                self.save_settings()
+            }
+            Message::ToggleUpdateCheck(enabled) => {
+                self.settings.check_for_updates = enabled;
+                if !enabled {
+                    self.ui_state.available_update = None;
+                }
+                // SettingsUpdated kicks off the first check once the setting is saved
+                self.save_settings()
+            }
+            Message::FocusWorkMinutesChanged(minutes) => {
+                self.settings.focus_work_minutes = minutes;
+                self.save_settings()
+            }
+            Message::FocusBreakMinutesChanged(minutes) => {
+                self.settings.focus_break_minutes = minutes;
+                self.save_settings()
+            }
+            Message::KeepAwakeMinutesChanged(minutes) => {
+                self.settings.keep_awake_minutes = minutes;
+                self.save_settings()
+            }
+            Message::MaxFeedSizeChanged(megabytes) => {
+                self.settings.max_feed_size_mb = megabytes;
+                self.save_settings()
+            }
+            Message::FocusStart => {
+                self.focus.start(chrono::Utc::now());
+                Command::none()
+            }
+            Message::FocusPause => {
+                self.focus.pause(chrono::Utc::now());
+                Command::none()
+            }
+            Message::FocusResume => {
+                self.focus.resume(chrono::Utc::now());
+                Command::none()
+            }
+            Message::FocusStop => {
+                self.focus.stop();
+                Command::none()
+            }
+            Message::FocusTick => {
+                match self.focus.tick(chrono::Utc::now()) {
+                    Some(FocusEvent::PhaseStarted(phase)) => {
+                        let alert_type = match phase {
+                            FocusPhase::Break => crate::audio::AlertType::FocusBreak,
+                            FocusPhase::Work => crate::audio::AlertType::FocusWork,
+                        };
+                        let audio = self.audio.clone();
+                        Command::perform(async move {
+                            audio.play_alert(alert_type).map_err(|e| e.to_string())
+                        }, Message::FocusChimePlayed)
+                    }
+                    Some(FocusEvent::Resumed) => {
+                        info!("Meeting over, focus timer resumed");
+                        Command::none()
+                    }
+                    None => Command::none(),
+                }
+            }
+            Message::OpenChecklist(event_id) => {
+                let db = self.db.clone();
+                Command::perform(async move {
+                    crate::database::checklists::get_for_event(&db.pool, event_id).await
+                }, move |result| Message::ChecklistLoaded(event_id, result.map_err(|e| e.to_string())))
+            }
+            Message::CloseChecklist => {
+                self.ui_state.checklist = None;
+                Command::none()
+            }
+            Message::ChecklistInputChanged(input) => {
+                if let Some(editor) = &mut self.ui_state.checklist {
+                    editor.new_item = input;
+                }
+                Command::none()
+            }
+            Message::AddChecklistItem => {
+                let Some(editor) = &mut self.ui_state.checklist else {
+                    return Command::none();
+                };
+                let text = std::mem::take(&mut editor.new_item);
+                if text.trim().is_empty() {
+                    return Command::none();
+                }
+                let (pool, event_id) = (self.db.pool.clone(), editor.event_id);
+                self.reload_checklist_after(event_id, async move {
+                    crate::database::checklists::add(&pool, event_id, &text).await.map(|_| ())
+                })
+            }
+            Message::ToggleChecklistItem(item_id, done) => {
+                let Some(editor) = &self.ui_state.checklist else {
+                    return Command::none();
+                };
+                let pool = self.db.pool.clone();
+                self.reload_checklist_after(editor.event_id, async move {
+                    crate::database::checklists::set_done(&pool, item_id, done).await
+                })
+            }
+            Message::RemoveChecklistItem(item_id) => {
+                let Some(editor) = &self.ui_state.checklist else {
+                    return Command::none();
+                };
+                let pool = self.db.pool.clone();
+                self.reload_checklist_after(editor.event_id, async move {
+                    crate::database::checklists::remove(&pool, item_id).await
+                })
+            }
+            Message::ChecklistLoaded(event_id, Ok(items)) => {
+                // Ticking an item off also clears it from the alert card
+                if let Some(to_prepare) = self.ui_state.to_prepare.get_mut(&event_id) {
+                    to_prepare.retain(|text| items.iter().any(|item| !item.is_done && &item.text == text));
+                }
+                let new_item = match self.ui_state.checklist.take() {
+                    Some(editor) if editor.event_id == event_id => editor.new_item,
+                    _ => String::new(),
+                };
+                self.ui_state.checklist = Some(crate::ui_state::ChecklistEditor { event_id, items, new_item });
+                Command::none()
+            }
+            Message::ChecklistLoaded(_, Err(error)) => {
+                error!("Checklist update failed: {}", error);
+                self.ui_state.sync_status = user_friendly_error(&error);
+                Command::none()
+            }
+            Message::OpenAttendees(event_id) => {
+                let db = self.db.clone();
+                Command::perform(async move {
+                    crate::database::attendees::get_for_event(&db.pool, event_id).await
+                }, move |result| Message::AttendeesLoaded(event_id, result.map_err(|e| e.to_string())))
+            }
+            Message::CloseAttendees => {
+                self.ui_state.attendees = None;
+                Command::none()
+            }
+            Message::AttendeesLoaded(event_id, Ok(attendees)) => {
+                self.ui_state.attendees = Some((event_id, attendees));
+                Command::none()
+            }
+            Message::AttendeesLoaded(_, Err(error)) => {
+                error!("Failed to load attendees: {}", error);
+                self.ui_state.sync_status = user_friendly_error(&error);
+                Command::none()
+            }
+            Message::WorldClocksChanged(input) => {
+                self.ui_state.world_clocks_draft = Some(input);
+                Command::none()
+            }
+            Message::SaveWorldClocks => {
+                let Some(input) = self.ui_state.world_clocks_draft.take() else {
+                    return Command::none();
+                };
+                let names: Vec<&str> = input.split(',').map(str::trim).filter(|n| !n.is_empty()).collect();
+                if let Some(unknown) = names.iter().find(|name| name.parse::<chrono_tz::Tz>().is_err()) {
+                    self.ui_state.sync_status = format!("Unknown timezone '{}' (use names like Europe/London)", unknown);
+                    self.ui_state.world_clocks_draft = Some(input);
+                    return Command::none();
+                }
+                self.settings.world_clocks = names.join(", ");
+                self.save_settings()
+            }
+            Message::CustomAlertsChanged(input) => {
+                self.ui_state.custom_alerts_draft = Some(input);
+                Command::none()
+            }
+            Message::SaveCustomAlerts => {
+                let Some(input) = self.ui_state.custom_alerts_draft.take() else {
+                    return Command::none();
+                };
+                let entries: Vec<&str> = input.split(',').map(str::trim).filter(|m| !m.is_empty()).collect();
+                let valid = |m: &str| m.parse::<i32>().is_ok_and(|m| (1..=crate::models::MAX_CALENDAR_REMINDER_MINUTES).contains(&m));
+                if let Some(bad) = entries.iter().find(|m| !valid(m)) {
+                    self.ui_state.sync_status = format!("'{}' isn't a number of minutes between 1 and 1440", bad);
+                    self.ui_state.custom_alerts_draft = Some(input);
+                    return Command::none();
+                }
+                self.settings.custom_alert_minutes = entries.join(", ");
+                self.save_settings()
+            }
+            Message::MyAddressesChanged(input) => {
+                self.ui_state.my_addresses_draft = Some(input);
+                Command::none()
+            }
+            Message::SaveMyAddresses => {
+                let Some(input) = self.ui_state.my_addresses_draft.take() else {
+                    return Command::none();
+                };
+                let addresses: Vec<&str> = input.split(',').map(str::trim).filter(|a| !a.is_empty()).collect();
+                if let Some(bad) = addresses.iter().find(|a| !a.contains('@')) {
+                    self.ui_state.sync_status = format!("'{}' isn't an email address", bad);
+                    self.ui_state.my_addresses_draft = Some(input);
+                    return Command::none();
+                }
+                self.settings.my_addresses = addresses.join(", ");
+                self.save_settings()
+            }
+            Message::ProxyUrlChanged(input) => {
+                self.ui_state.proxy_url_draft = Some(input);
+                Command::none()
+            }
+            Message::SaveProxyUrl => {
+                let Some(input) = self.ui_state.proxy_url_draft.take() else {
+                    return Command::none();
+                };
+                if let Err(error) = crate::http_config::validate_proxy_setting(&input) {
+                    self.ui_state.sync_status = error;
+                    self.ui_state.proxy_url_draft = Some(input);
+                    return Command::none();
+                }
+                self.settings.proxy_url = input.trim().to_string();
+                self.save_settings()
+            }
+            Message::SoundProfilesChanged(input) => {
+                self.ui_state.sound_profiles_draft = Some(input);
+                Command::none()
+            }
+            Message::SaveSoundProfiles => {
+                let Some(input) = self.ui_state.sound_profiles_draft.take() else {
+                    return Command::none();
+                };
+                match input.parse::<crate::models::SoundSchedule>() {
+                    Ok(schedule) => {
+                        self.settings.sound_profiles = schedule.to_string();
+                        self.save_settings()
+                    }
+                    Err(error) => {
+                        self.ui_state.sync_status = error;
+                        self.ui_state.sound_profiles_draft = Some(input);
+                        Command::none()
+                    }
+                }
+            }
+            Message::GroupNameChanged(name) => {
+                self.ui_state.group_name = name;
+                Command::none()
+            }
+            Message::AddGroup => {
+                let name = std::mem::take(&mut self.ui_state.group_name);
+                let db = self.db.clone();
+                Command::perform(async move {
+                    crate::database::account_groups::add(&db.pool, &name).await?;
+                    crate::database::account_groups::get_all(&db.pool).await
+                }, |result| Message::GroupsLoaded(result.map_err(|e| e.to_string())))
+            }
+            Message::SetGroupToggle(group_id, toggle, on) => {
+                let Some(group) = self.groups.iter_mut().find(|g| g.id == group_id) else {
+                    return Command::none();
+                };
+                match toggle {
+                    GroupToggle::Sync => group.sync_enabled = on,
+                    GroupToggle::Alerts => group.alerts_enabled = on,
+                    GroupToggle::Visible => group.visible = on,
+                }
+                let group = group.clone();
+                let db = self.db.clone();
+                let save = Command::perform(async move {
+                    crate::database::account_groups::update(&db.pool, &group).await?;
+                    crate::database::account_groups::get_all(&db.pool).await
+                }, |result| Message::GroupsLoaded(result.map_err(|e| e.to_string())));
+
+                // Catch up on what the group missed while paused
+                if toggle == GroupToggle::Sync && on {
+                    Command::batch(vec![save, Command::perform(async {}, |_| Message::SyncCalendars)])
+                } else {
+                    save
+                }
+            }
+            Message::DeleteGroup(group_id) => {
+                for account in self.accounts.iter_mut().filter(|a| a.group_id == Some(group_id)) {
+                    account.group_id = None;
+                }
+                let db = self.db.clone();
+                Command::perform(async move {
+                    crate::database::account_groups::delete(&db.pool, group_id).await?;
+                    crate::database::account_groups::get_all(&db.pool).await
+                }, |result| Message::GroupsLoaded(result.map_err(|e| e.to_string())))
+            }
+            Message::AssignAccountGroup(account_id, group_id) => {
+                if let Some(account) = self.accounts.iter_mut().find(|a| a.id == Some(account_id)) {
+                    account.group_id = group_id;
+                }
+                let db = self.db.clone();
+                Command::perform(async move {
+                    crate::database::account_groups::assign(&db.pool, account_id, group_id).await?;
+                    crate::database::account_groups::get_all(&db.pool).await
+                }, |result| Message::GroupsLoaded(result.map_err(|e| e.to_string())))
+            }
+            Message::SetAccountShared(account_id, is_shared) => {
+                if let Some(account) = self.accounts.iter_mut().find(|a| a.id == Some(account_id)) {
+                    account.is_shared = is_shared;
+                }
+                let db = self.db.clone();
+                Command::perform(async move {
+                    crate::database::accounts::set_shared(&db.pool, account_id, is_shared).await
+                }, |result| Message::AccountSaved(result.map_err(|e| e.to_string())))
+            }
+            Message::SetAccountInformational(account_id, is_informational) => {
+                if let Some(account) = self.accounts.iter_mut().find(|a| a.id == Some(account_id)) {
+                    account.is_informational = is_informational;
+                }
+                let db = self.db.clone();
+                Command::perform(async move {
+                    crate::database::accounts::set_informational(&db.pool, account_id, is_informational).await
+                }, |result| Message::AccountSaved(result.map_err(|e| e.to_string())))
+            }
+            Message::SetAccountAlertsEnabled(account_id, alerts_enabled) => {
+                if let Some(account) = self.accounts.iter_mut().find(|a| a.id == Some(account_id)) {
+                    account.alerts_enabled = alerts_enabled;
+                }
+                let db = self.db.clone();
+                Command::perform(async move {
+                    crate::database::accounts::set_alerts_enabled(&db.pool, account_id, alerts_enabled).await
+                }, |result| Message::AccountSaved(result.map_err(|e| e.to_string())))
+            }
+            Message::SetAccountSyncInterval(account_id, sync_interval) => {
+                if let Some(account) = self.accounts.iter_mut().find(|a| a.id == Some(account_id)) {
+                    account.sync_interval = sync_interval;
+                }
+                let db = self.db.clone();
+                Command::perform(async move {
+                    crate::database::accounts::set_sync_interval(&db.pool, account_id, sync_interval).await
+                }, |result| Message::AccountSaved(result.map_err(|e| e.to_string())))
+            }
+            Message::SetAccountTimezone(account_id, timezone) => {
+                if let Some(account) = self.accounts.iter_mut().find(|a| a.id == Some(account_id)) {
+                    account.timezone = timezone.clone();
+                }
+                let db = self.db.clone();
+                Command::perform(async move {
+                    crate::database::accounts::set_timezone(&db.pool, account_id, timezone.as_deref()).await
+                }, |result| Message::AccountSaved(result.map_err(|e| e.to_string())))
+            }
+            Message::AccountSaved(Ok(())) => Command::none(),
+            Message::AccountSaved(Err(error)) => {
+                error!("Failed to save account: {}", error);
+                self.ui_state.sync_status = user_friendly_error(&error);
+                Command::none()
+            }
+            Message::AccountsLoaded(Ok(accounts)) => {
+                self.accounts = accounts.into_iter().filter(|a| !a.is_local()).collect();
+                Command::none()
+            }
+            Message::AccountsLoaded(Err(error)) => {
+                log::warn!("Failed to reload accounts: {}", error);
+                Command::none()
+            }
+            Message::GroupsLoaded(Ok(groups)) => {
+                self.groups = groups;
+                Command::none()
+            }
+            Message::GroupsLoaded(Err(error)) => {
+                error!("Account group update failed: {}", error);
+                self.ui_state.sync_status = user_friendly_error(&error);
+                Command::none()
+            }
+            // Redraws the clocks; meetings that end drop off the badge
+            Message::ClockTick => self.refresh_badge(),
+            Message::Tray(crate::tray::TrayEvent::Ready(tray)) => {
+                self.tray = Some(tray);
+                self.tray_status = None;
+                self.refresh_badge()
+            }
+            Message::Tray(crate::tray::TrayEvent::Action(action)) => match action {
+                crate::tray::TrayAction::Show => Command::batch(vec![
+                    iced::window::change_mode(iced::window::Id::MAIN, iced::window::Mode::Windowed),
+                    iced::window::minimize(iced::window::Id::MAIN, false),
+                    iced::window::gain_focus(iced::window::Id::MAIN),
+                ]),
+                crate::tray::TrayAction::SnoozeAll | crate::tray::TrayAction::DismissAll => {
+                    let event_ids: Vec<crate::models::EventId> = self.alert_center_events(chrono::Utc::now())
+                        .iter()
+                        .filter_map(|event| event.id)
+                        .collect();
+                    if event_ids.is_empty() {
+                        self.ui_state.sync_status = "No alerts to snooze or dismiss".to_string();
+                        return Command::none();
+                    }
+                    match action {
+                        crate::tray::TrayAction::SnoozeAll => Command::perform(async {}, move |_| Message::SnoozeAll(event_ids)),
+                        _ => Command::perform(async {}, move |_| Message::DismissAll(event_ids)),
+                    }
+                }
+                crate::tray::TrayAction::SyncNow => Command::perform(async {}, |_| Message::SyncCalendars),
+                crate::tray::TrayAction::Quit => iced::window::close(iced::window::Id::MAIN),
+            },
+            Message::TrayUpdated => Command::none(),
+            Message::NotificationShown => Command::none(),
+            // Alerts keep coming with the window hidden; the tray brings it back
+            Message::WindowCloseRequested => match self.tray {
+                Some(_) => iced::window::change_mode(iced::window::Id::MAIN, iced::window::Mode::Hidden),
+                None => iced::window::close(iced::window::Id::MAIN),
+            },
+            Message::FocusChimePlayed(Err(error)) => {
+                error!("Focus chime failed: {}", error);
+                Command::none()
+            }
+            _ => Command::none(), // Handle other messages if needed
+        }
+    }
 
     fn subscription(&self) -> iced::Subscription<Message> {
         struct MonitorLoop;
@@ -549,7 +1726,7 @@ impl Application for OpenChimeApp {
         let audio = self.audio.clone();
         let shutdown = self.shutdown.clone();
 
-        iced::subscription::channel(
+        let monitor = iced::subscription::channel(
             std::any::TypeId::of::<MonitorLoop>(),
             100,
             move |mut output| {
@@ -557,6 +1734,7 @@ impl Application for OpenChimeApp {
                     db: db.clone(),
                     audio: audio.clone(),
                     shutdown: shutdown.clone(),
+                    clock: Arc::new(crate::utils::clock::SystemClock),
                 });
 
                 async move {
@@ -575,7 +1753,34 @@ impl Application for OpenChimeApp {
                      }
                 }
             }
-        )
+        );
+
+        struct TrayIcon;
+        let tray = iced::subscription::channel(std::any::TypeId::of::<TrayIcon>(), 10, |mut output| async move {
+            let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+            if let Some(tray) = crate::tray::spawn(sender).await {
+                let _ = output.send(Message::Tray(crate::tray::TrayEvent::Ready(tray))).await;
+            }
+            // Without a tray the sender is gone and this ends straight away
+            while let Some(event) = receiver.recv().await {
+                let _ = output.send(Message::Tray(event)).await;
+            }
+            std::future::pending().await
+        });
+
+        let close_requests = iced::event::listen_with(|event, _| match event {
+            iced::Event::Window(_, iced::window::Event::CloseRequested) => Some(Message::WindowCloseRequested),
+            _ => None,
+        });
+
+        let mut subscriptions = vec![monitor, tray, close_requests];
+        // Only tick while there's a focus session to count down
+        if !self.focus.is_idle() {
+            subscriptions.push(iced::time::every(std::time::Duration::from_secs(1)).map(|_| Message::FocusTick));
+        }
+        // Keeps the window title's countdown and the world clocks current
+        subscriptions.push(iced::time::every(std::time::Duration::from_secs(30)).map(|_| Message::ClockTick));
+        iced::Subscription::batch(subscriptions)
     }
 
     fn view(&self) -> Element<'_, Message> {
@@ -605,11 +1810,17 @@ impl Application for OpenChimeApp {
                 column![
                     nav_button("Calendar", View::Calendar, self.ui_state.current_view.clone(), Message::ShowCalendar),
                     nav_button("Alerts", View::Alerts, self.ui_state.current_view.clone(), Message::ShowAlerts),
+                    nav_button("Focus", View::Focus, self.ui_state.current_view.clone(), Message::ShowFocus),
+                    nav_button("Stats", View::Stats, self.ui_state.current_view.clone(), Message::ShowStats),
                     nav_button("Settings", View::Settings, self.ui_state.current_view.clone(), Message::ShowSettings),
                 ]
                 .spacing(5),
                 
                 iced::widget::vertical_space(),
+
+                self.view_sync_warnings(),
+
+                self.view_update_banner(),
                 
                 container(
                     column![
@@ -644,6 +1855,8 @@ impl Application for OpenChimeApp {
                 View::Calendar => self.view_calendar(),
                 View::Settings => self.view_settings(),
                 View::Alerts => self.view_alerts(),
+                View::Focus => self.view_focus(),
+                View::Stats => self.view_stats(),
             }
         )
         .width(Length::Fill)
@@ -668,7 +1881,552 @@ impl Application for OpenChimeApp {
 }
 
 impl OpenChimeApp {
+    /// Pre-fill the Add Calendar form from an `openchime://add` link
+    fn apply_protocol_link(&mut self, link: &str) {
+        self.ui_state.current_view = View::Settings;
+
+        match crate::protocol::parse_protocol_url(link) {
+            Ok(crate::protocol::ProtocolAction::AddCalendar { ics_url, name }) => {
+                info!("Opened via {} link", crate::protocol::SCHEME);
+                self.ui_state.sync_status = match calendar::common::validate_ics_url_format(&ics_url) {
+                    Ok(()) => "Calendar link received. Review and click Link Account.".to_string(),
+                    Err(e) => format!("❌ Invalid ICS URL: {}", e),
+                };
+                self.ui_state.ics_url = calendar::common::normalize_feed_url(&ics_url);
+                if let Some(name) = name {
+                    self.ui_state.account_name = name;
+                }
+            }
+            Err(e) => {
+                error!("Ignoring {} link: {}", crate::protocol::SCHEME, e);
+                self.ui_state.sync_status = format!("❌ {}", e);
+            }
+        }
+    }
+
+    /// Persist the current settings and echo them back once saved
+    fn save_settings(&self) -> Command<Message> {
+        let pool = self.db.pool.clone();
+        let settings = self.settings.clone();
+        Command::perform(async move {
+            crate::database::settings::update(&pool, &settings).await
+                .map(|_| settings)
+                .map_err(|e| anyhow::anyhow!("Failed to update settings: {}", e))
+        }, |res| match res {
+            Ok(settings) => Message::SettingsUpdated(settings),
+            Err(e) => Message::CalendarSyncResult(Err(e.to_string())) // Reuse error handler
+        })
+    }
+
+    /// Apply a checklist change, then reload that event's checklist
+    fn reload_checklist_after(
+        &self,
+        event_id: crate::models::EventId,
+        change: impl std::future::Future<Output = anyhow::Result<()>> + Send + 'static,
+    ) -> Command<Message> {
+        let pool = self.db.pool.clone();
+        Command::perform(async move {
+            change.await?;
+            crate::database::checklists::get_for_event(&pool, event_id).await
+        }, move |result| Message::ChecklistLoaded(event_id, result.map_err(|e| e.to_string())))
+    }
+
+    /// The account the Add New Calendar form describes, or `None` (with the
+    /// problem in the status line) when it isn't valid
+    fn account_from_form(&mut self) -> Option<Account> {
+        let url = calendar::common::normalize_feed_url(&self.ui_state.ics_url);
+        let account_name = self.ui_state.account_name.clone();
+        let username = self.ui_state.caldav_username.trim().to_string();
+        // A sign-in with a feed URL is HTTP Basic auth on the feed itself
+        let feed_login = !username.is_empty() && calendar::discovery::looks_like_feed_url(&url);
+        let provider = if username.is_empty() || feed_login {
+            detect_provider_from_url(&url)
+        } else {
+            crate::models::CalendarProvider::CalDav
+        };
+
+        if let Err(e) = calendar::common::validate_ics_url_format(&url) {
+            self.ui_state.sync_status = format!("❌ Invalid ICS URL: {}", e);
+            return None;
+        }
+        if matches!(provider, crate::models::CalendarProvider::CalDav) && self.ui_state.caldav_password.is_empty() {
+            self.ui_state.sync_status = "❌ Enter the app password for this CalDAV account".to_string();
+            return None;
+        }
+
+        // Create account with the correct provider
+        let mut account = match provider {
+            crate::models::CalendarProvider::Google => {
+                Account::new_google(account_name, url, None)
+            }
+            crate::models::CalendarProvider::CalDav => {
+                let credentials = calendar::caldav::CalDavCredentials {
+                    username: username.clone(),
+                    password: self.ui_state.caldav_password.clone(),
+                };
+                Account::new_caldav(account_name, url, &credentials)
+            }
+            crate::models::CalendarProvider::Proton => Account::new_proton(account_name, url),
+            // URLs never detect as local; treat it like any ICS feed
+            crate::models::CalendarProvider::Generic | crate::models::CalendarProvider::Local => {
+                Account::new_generic(account_name, url)
+            }
+        };
+        if feed_login {
+            account = account.with_basic_auth(username, self.ui_state.caldav_password.clone());
+        }
+        Some(account.with_request_headers(self.ui_state.request_headers.join("\n")))
+    }
+
+    /// Save the account in the Add New Calendar form
+    fn link_account(&mut self) -> Command<Message> {
+        let Some(account) = self.account_from_form() else {
+            return Command::none();
+        };
+        self.ui_state.account_preview = None;
+        self.ui_state.connection_test.clear();
+
+        let db = self.db.clone();
+        
+        Command::perform(async move {
+            // Actually save the account to database
+            crate::database::accounts::add(&db.pool, &account)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to save account: {}", e))?;
+
+            Ok(account)
+        }, |result: Result<Account, anyhow::Error>| Message::AccountAdded(result.map_err(|e| e.to_string())))
+    }
+
+    /// Each step of testing the calendar in the add form, and how it went
+    fn view_connection_test(&self) -> Element<'_, Message> {
+        column(self.ui_state.connection_test.iter().map(|step| {
+            let (mark, color) = match step.status {
+                crate::doctor::CheckStatus::Pass => ("✓", ZEN_ACCENT),
+                crate::doctor::CheckStatus::Warn => ("⚠", ZEN_SUBTEXT),
+                crate::doctor::CheckStatus::Fail => ("✗", ZEN_DESTRUCTIVE),
+            };
+            text(format!("{} {}: {}", mark, step.name, step.detail))
+                .size(12)
+                .style(iced::theme::Text::Color(color))
+                .into()
+        }).collect::<Vec<_>>())
+        .spacing(4)
+        .into()
+    }
+
+    /// The events a previewed account would bring in, soonest first
+    fn view_account_preview(&self) -> Element<'_, Message> {
+        const SHOWN: usize = 8;
+        let Some(preview) = &self.ui_state.account_preview else {
+            return column![].into();
+        };
+
+        let mut events: Vec<&CalendarEvent> = preview.changes.added.iter().chain(&preview.changes.updated).collect();
+        events.sort_by_key(|event| event.start_time);
+        let mut list = column(events.iter().take(SHOWN).map(|event| {
+            let start = event.start_time.with_timezone(&chrono::Local);
+            text(format!("{}  {}", start.format("%a %b %-d, %H:%M"), event.title))
+                .size(12)
+                .style(iced::theme::Text::Color(ZEN_SUBTEXT))
+                .into()
+        }).collect::<Vec<_>>()).spacing(4);
+        if events.len() > SHOWN {
+            list = list.push(
+                text(format!("...and {} more", events.len() - SHOWN))
+                    .size(12)
+                    .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+            );
+        }
+        list.into()
+    }
+
+    fn reload_groups(&self) -> Command<Message> {
+        let db = self.db.clone();
+        Command::perform(async move {
+            crate::database::account_groups::get_all(&db.pool).await
+        }, |result| Message::GroupsLoaded(result.map_err(|e| e.to_string())))
+    }
+
+    /// Meetings for the calendar, without those only in accounts of hidden groups
+    fn visible_events(&self) -> Vec<&crate::models::MergedEvent> {
+        let hidden: Vec<i64> = self.accounts.iter()
+            .filter(|a| a.group_id.is_some_and(|id| self.groups.iter().any(|g| g.id == id && !g.visible)))
+            .filter_map(|a| a.id)
+            .collect();
+        self.merged_events.iter().filter(|m| m.account_ids.iter().any(|id| !hidden.contains(id))).collect()
+    }
+
+    /// Re-merge duplicates after `events` changes, rather than on every redraw
+    fn events_changed(&mut self) {
+        self.merged_events = crate::models::merge_duplicates(&self.events);
+    }
+
+    /// Whether a meeting comes only from shared team calendars, and is dimmed
+    fn is_team_only(&self, account_ids: &[i64]) -> bool {
+        account_ids.iter().all(|id| self.accounts.iter().any(|a| a.id == Some(*id) && a.is_shared))
+    }
+
+    /// The color of the first of a meeting's calendars that has one
+    fn calendar_color(&self, account_ids: &[i64]) -> Option<iced::Color> {
+        account_ids
+            .iter()
+            .find_map(|id| self.accounts.iter().find(|a| a.id == Some(*id))?.calendar_rgb())
+            .map(|[r, g, b]| iced::Color::from_rgb8(r, g, b))
+    }
+
+    /// A strip in the calendar's color, or a gap the same width without one
+    fn view_calendar_strip(&self, account_ids: &[i64]) -> Element<'_, Message> {
+        let color = self.calendar_color(account_ids).unwrap_or(iced::Color::TRANSPARENT);
+        container(iced::widget::Space::new(4, 36))
+            .style(iced::theme::Container::Custom(Box::new(CalendarColorStyle(color))))
+            .into()
+    }
+
+    /// Redraw the window icon's badge when today's remaining meeting count changed
+    fn refresh_badge(&mut self) -> Command<Message> {
+        let count = crate::models::remaining_today(&self.events, chrono::Utc::now(), &chrono::Local);
+        let tray = self.refresh_tray(count);
+        if self.badge_count == Some(count) {
+            return tray;
+        }
+        self.badge_count = Some(count);
+
+        let size = crate::ui::badge::ICON_SIZE;
+        match iced::window::icon::from_rgba(crate::ui::badge::icon_rgba(count), size, size) {
+            Ok(icon) => Command::batch(vec![tray, iced::window::change_icon(iced::window::Id::MAIN, icon)]),
+            Err(e) => {
+                log::warn!("Could not draw the meeting badge: {}", e);
+                tray
+            }
+        }
+    }
+
+    /// Give the tray icon the next meeting's countdown and today's count,
+    /// when they've changed
+    fn refresh_tray(&mut self, remaining: usize) -> Command<Message> {
+        let Some(tray) = self.tray.clone() else {
+            return Command::none();
+        };
+        let status = (meeting_countdown(&self.events, chrono::Utc::now()), remaining);
+        if self.tray_status.as_ref() == Some(&status) {
+            return Command::none();
+        }
+        self.tray_status = Some(status.clone());
+        Command::perform(async move { tray.update(status.0, status.1).await }, |_| Message::TrayUpdated)
+    }
+
+    /// The name and color the feed gives its calendar, when it names one
+    fn view_calendar_name(&self, account: &Account) -> Element<'_, Message> {
+        let Some(name) = &account.calendar_name else {
+            return column![].into();
+        };
+        let swatch: Element<Message> = match account.calendar_rgb() {
+            Some([r, g, b]) => container(iced::widget::Space::new(10, 10))
+                .style(iced::theme::Container::Custom(Box::new(CalendarColorStyle(iced::Color::from_rgb8(r, g, b)))))
+                .into(),
+            None => iced::widget::Space::with_width(0).into(),
+        };
+        row![
+            swatch,
+            text(format!("Calendar: {}", name)).size(12).style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+        ]
+        .spacing(6)
+        .align_items(iced::Alignment::Center)
+        .into()
+    }
+
+    /// Why the account's last sync failed, if it did
+    fn view_sync_error(&self, account: &Account) -> Element<'_, Message> {
+        let Some(error) = &account.last_sync_error else {
+            return column![].into();
+        };
+        let when = account.last_sync_error_at
+            .map(|at| format!(" at {}", at.with_timezone(&chrono::Local).format("%b %-d %H:%M")))
+            .unwrap_or_default();
+        text(format!("⚠ Sync failed{}: {}", when, user_friendly_error(error)))
+            .size(12)
+            .style(iced::theme::Text::Color(ZEN_DESTRUCTIVE))
+            .into()
+    }
+
+    /// Re-read the accounts, whose sync errors change as they sync
+    fn reload_accounts(&self) -> Command<Message> {
+        let db = self.db.clone();
+        Command::perform(async move { db.get_accounts().await }, |result| {
+            Message::AccountsLoaded(result.map_err(|e| e.to_string()))
+        })
+    }
+
+    /// Run the opt-in GitHub release check once per session
+    fn check_for_updates(&mut self) -> Command<Message> {
+        self.ui_state.update_checked = true;
+        Command::perform(crate::updates::check_for_update(), |result| {
+            Message::UpdateCheckResult(result.map_err(|e| e.to_safe_string()))
+        })
+    }
+
+    /// Current time in each configured world clock zone
+    fn view_world_clocks(&self) -> Element<'_, Message> {
+        let now = chrono::Utc::now();
+        let clocks: Vec<Element<Message>> = self.settings.world_clock_zones().into_iter().map(|tz| {
+            let there = now.with_timezone(&tz);
+            // The city is the most recognisable part of "America/New_York"
+            let city = tz.name().rsplit('/').next().unwrap_or(tz.name()).replace('_', " ");
+            container(
+                column![
+                    text(city)
+                        .size(11)
+                        .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                    text(there.format("%I:%M %p").to_string())
+                        .size(14)
+                        .style(iced::theme::Text::Color(ZEN_TEXT)),
+                ]
+                .spacing(2)
+            )
+            .padding([6, 12])
+            .style(iced::theme::Container::Custom(Box::new(CardStyle)))
+            .into()
+        }).collect();
+
+        row(clocks).spacing(10).into()
+    }
+
+    /// An event's title in the calendar list, or the rename field while it's being renamed
+    fn view_event_title(&self, event: &CalendarEvent, dimmed: bool) -> Element<'_, Message> {
+        match (&self.ui_state.editing_title, event.id) {
+            (Some((editing_id, input)), Some(id)) if *editing_id == id => row![
+                text_input(&event.title, input)
+                    .size(16)
+                    .padding(6)
+                    .on_input(Message::EventTitleInputChanged)
+                    .on_submit(Message::SaveEventTitle),
+                button("Save")
+                    .style(iced::theme::Button::Custom(Box::new(PrimaryButtonStyle)))
+                    .padding([6, 12])
+                    .on_press(Message::SaveEventTitle),
+                button("Cancel")
+                    .style(iced::theme::Button::Custom(Box::new(NavStyle)))
+                    .padding([6, 12])
+                    .on_press(Message::CancelEditEventTitle),
+            ]
+            .spacing(8)
+            .align_items(iced::Alignment::Center)
+            .into(),
+            (_, id) => row![
+                text(event.display_title().to_string())
+                    .size(16)
+                    .style(iced::theme::Text::Color(if dimmed { ZEN_SUBTEXT } else { ZEN_TEXT })),
+                button(text("Rename").size(12))
+                    .style(iced::theme::Button::Custom(Box::new(NavStyle)))
+                    .padding([2, 8])
+                    .on_press_maybe(id.map(Message::EditEventTitle)),
+                button(text("Checklist").size(12))
+                    .style(iced::theme::Button::Custom(Box::new(NavStyle)))
+                    .padding([2, 8])
+                    .on_press_maybe(id.map(Message::OpenChecklist)),
+                button(text("People").size(12))
+                    .style(iced::theme::Button::Custom(Box::new(NavStyle)))
+                    .padding([2, 8])
+                    .on_press_maybe(id.map(Message::OpenAttendees)),
+            ]
+            .spacing(8)
+            .align_items(iced::Alignment::Center)
+            .into(),
+        }
+    }
+
+    /// An event's preparation checklist, while it's open for editing
+    fn view_checklist(&self, event: &CalendarEvent) -> Element<'_, Message> {
+        let Some(editor) = self.ui_state.checklist.as_ref().filter(|editor| Some(editor.event_id) == event.id) else {
+            return column![].into();
+        };
+
+        let items: Vec<Element<Message>> = editor.items.iter().map(|item| {
+            let item_id = item.id;
+            row![
+                checkbox(&item.text, item.is_done)
+                    .on_toggle(move |done| Message::ToggleChecklistItem(item_id, done))
+                    .size(16)
+                    .text_size(13),
+                iced::widget::horizontal_space(),
+                button(text("Remove").size(11))
+                    .style(iced::theme::Button::Custom(Box::new(NavStyle)))
+                    .padding([2, 8])
+                    .on_press(Message::RemoveChecklistItem(item_id)),
+            ]
+            .align_items(iced::Alignment::Center)
+            .into()
+        }).collect();
+
+        column![
+            column(items).spacing(4),
+            row![
+                text_input("Add an item, e.g. prepare slides", &editor.new_item)
+                    .size(13)
+                    .padding(6)
+                    .on_input(Message::ChecklistInputChanged)
+                    .on_submit(Message::AddChecklistItem),
+                button("Add")
+                    .style(iced::theme::Button::Custom(Box::new(PrimaryButtonStyle)))
+                    .padding([6, 12])
+                    .on_press(Message::AddChecklistItem),
+                button("Done")
+                    .style(iced::theme::Button::Custom(Box::new(NavStyle)))
+                    .padding([6, 12])
+                    .on_press(Message::CloseChecklist),
+            ]
+            .spacing(8)
+            .align_items(iced::Alignment::Center),
+            text("Unchecked items are repeated in the 30 and 10 minute alerts")
+                .size(11)
+                .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+        ]
+        .spacing(8)
+        .padding([6, 0])
+        .into()
+    }
+
+    /// Who's invited to an event and how they replied, while the list is open
+    fn view_attendees(&self, event: &CalendarEvent) -> Element<'_, Message> {
+        let Some((_, attendees)) = self.ui_state.attendees.as_ref().filter(|(event_id, _)| Some(*event_id) == event.id) else {
+            return column![].into();
+        };
+
+        let people: Vec<Element<Message>> = if attendees.is_empty() {
+            vec![text("The calendar doesn't list anyone for this event")
+                .size(12)
+                .style(iced::theme::Text::Color(ZEN_SUBTEXT))
+                .into()]
+        } else {
+            attendees.iter().map(|attendee| {
+                let name = match (&attendee.name, attendee.is_organizer) {
+                    (Some(name), true) => format!("{} <{}> (organizer)", name, attendee.email),
+                    (Some(name), false) => format!("{} <{}>", name, attendee.email),
+                    (None, true) => format!("{} (organizer)", attendee.email),
+                    (None, false) => attendee.email.clone(),
+                };
+                row![
+                    text(name).size(13),
+                    iced::widget::horizontal_space(),
+                    text(attendee.rsvp.label())
+                        .size(12)
+                        .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                ]
+                .align_items(iced::Alignment::Center)
+                .into()
+            }).collect()
+        };
+
+        column![
+            column(people).spacing(4),
+            button("Done")
+                .style(iced::theme::Button::Custom(Box::new(NavStyle)))
+                .padding([6, 12])
+                .on_press(Message::CloseAttendees),
+        ]
+        .spacing(8)
+        .padding([6, 0])
+        .into()
+    }
+
+    /// The form editing `account`, in place of its row under Linked Accounts
+    fn view_account_form<'a>(&self, account: &Account, form: &'a AccountForm) -> Element<'a, Message> {
+        let mut fields = column![
+            text("Name").size(12).style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+            text_input("e.g., Work Calendar", &form.name)
+                .padding(8)
+                .on_input(Message::EditAccountNameChanged)
+                .on_submit(Message::SaveAccountEdit),
+        ]
+        .spacing(5);
+        if !account.is_local() && !is_api_account(account) {
+            fields = fields.push(text("URL").size(12).style(iced::theme::Text::Color(ZEN_SUBTEXT))).push(
+                text_input("https://...", &form.url)
+                    .padding(8)
+                    .on_input(Message::EditAccountUrlChanged)
+                    .on_submit(Message::SaveAccountEdit),
+            );
+        }
+
+        column![
+            fields,
+            text(form.error.clone().unwrap_or_default())
+                .size(12)
+                .style(iced::theme::Text::Color(ZEN_DESTRUCTIVE)),
+            row![
+                iced::widget::horizontal_space(),
+                button(text("Cancel").size(12))
+                    .style(iced::theme::Button::Custom(Box::new(NavStyle)))
+                    .padding([6, 12])
+                    .on_press(Message::CancelEditAccount),
+                button(text("Save").size(12))
+                    .style(iced::theme::Button::Custom(Box::new(PrimaryButtonStyle)))
+                    .padding([6, 12])
+                    .on_press(Message::SaveAccountEdit),
+            ]
+            .spacing(10),
+        ]
+        .spacing(8)
+        .into()
+    }
+
+    /// The new reminder form, while it's open
+    fn view_reminder_form(&self) -> Option<Element<'_, Message>> {
+        let form = self.ui_state.reminder_form.as_ref()?;
+
+        let field = |label, placeholder, value, on_input: fn(String) -> Message| {
+            column![
+                text(label)
+                    .size(12)
+                    .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                text_input(placeholder, value)
+                    .padding(10)
+                    .on_input(on_input)
+                    .on_submit(Message::SaveReminder),
+            ]
+            .spacing(5)
+        };
+
+        Some(
+            container(
+                column![
+                    text("New Reminder")
+                        .size(18)
+                        .style(iced::theme::Text::Color(ZEN_TEXT)),
+                    field("What", "e.g., Call the dentist", &form.title, Message::ReminderTitleChanged),
+                    field("When", "15:00, 3pm, tomorrow 9am, in 20m", &form.time, Message::ReminderTimeChanged),
+                    field("Meeting link (optional)", "https://...", &form.link, Message::ReminderLinkChanged),
+                    text(form.error.clone().unwrap_or_default())
+                        .size(12)
+                        .style(iced::theme::Text::Color(ZEN_DESTRUCTIVE)),
+                    row![
+                        iced::widget::horizontal_space(),
+                        button("Cancel")
+                            .style(iced::theme::Button::Custom(Box::new(NavStyle)))
+                            .padding([8, 16])
+                            .on_press(Message::CancelReminder),
+                        button("Add Reminder")
+                            .style(iced::theme::Button::Custom(Box::new(PrimaryButtonStyle)))
+                            .padding([8, 16])
+                            .on_press(Message::SaveReminder),
+                    ]
+                    .spacing(10),
+                ]
+                .spacing(12)
+            )
+            .width(Length::Fill)
+            .padding(20)
+            .style(iced::theme::Container::Custom(Box::new(CardStyle)))
+            .into()
+        )
+    }
+
     fn view_calendar(&self) -> Element<'_, Message> {
+        if let Some(form) = self.view_reminder_form() {
+            return scrollable(form).height(Length::Fill).into();
+        }
+
         if self.events.is_empty() {
             container(
                 column![
@@ -679,10 +2437,17 @@ impl OpenChimeApp {
                         .size(16)
                         .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
                     
-                    button("Go to Settings")
-                        .style(iced::theme::Button::Custom(Box::new(PrimaryButtonStyle)))
-                        .padding(12)
-                        .on_press(Message::ShowSettings)
+                    row![
+                        button("Go to Settings")
+                            .style(iced::theme::Button::Custom(Box::new(PrimaryButtonStyle)))
+                            .padding(12)
+                            .on_press(Message::ShowSettings),
+                        button("New Reminder")
+                            .style(iced::theme::Button::Custom(Box::new(NavStyle)))
+                            .padding(12)
+                            .on_press(Message::NewReminder),
+                    ]
+                    .spacing(10)
                 ]
                 .spacing(16)
                 .align_items(iced::Alignment::Center)
@@ -699,6 +2464,11 @@ impl OpenChimeApp {
                     .size(28)
                     .style(iced::theme::Text::Color(ZEN_TEXT))
                     .width(Length::Fill),
+
+                button("New Reminder")
+                    .style(iced::theme::Button::Custom(Box::new(NavStyle)))
+                    .padding([8, 16])
+                    .on_press(Message::NewReminder),
                 
                 button(if self.ui_state.loading { "Syncing..." } else { "Sync Now" })
                     .style(if self.ui_state.loading { 
@@ -709,22 +2479,22 @@ impl OpenChimeApp {
                     .padding([8, 16])
                     .on_press(Message::SyncCalendars)
             ]
+            .spacing(10)
             .align_items(iced::Alignment::Center);
 
-            // Group events by date
-            let mut events_by_date: std::collections::BTreeMap<String, Vec<&CalendarEvent>> = std::collections::BTreeMap::new();
-            for event in &self.events {
-                // Convert UTC to local timezone for proper date grouping
-                let local_time = chrono::Local.from_utc_datetime(&event.start_time.naive_utc());
-                let date = local_time.format("%Y-%m-%d").to_string();
-                events_by_date.entry(date).or_default().push(event);
+            // Group events by date, showing a meeting found in several calendars once
+            let mut events_by_date: std::collections::BTreeMap<String, Vec<&crate::models::MergedEvent>> = std::collections::BTreeMap::new();
+            for merged in self.visible_events() {
+                // Group by local date; all-day events keep their own date
+                let date = merged.event.local_date().format("%Y-%m-%d").to_string();
+                events_by_date.entry(date).or_default().push(merged);
             }
             
             let mut event_cards = Vec::new();
             
             for (date_str, mut day_events) in events_by_date {
-                // Sort events chronologically within each day
-                day_events.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+                // All-day events first, then chronologically within each day
+                day_events.sort_by_key(|m| (!m.event.is_all_day, m.event.start_time));
                 // Parse date to show friendly format
                 let date_parsed = chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d").unwrap_or_default();
                 let friendly_date = date_parsed.format("%A, %B %d").to_string();
@@ -746,14 +2516,21 @@ impl OpenChimeApp {
                 .spacing(10)
                 .align_items(iced::Alignment::Center);
                 
-                let event_rows: Vec<Element<Message>> = day_events.iter().map(|event| {
-                    let time_str = event.start_time.with_timezone(&chrono::Local).format("%I:%M %p").to_string();
+                let event_rows: Vec<Element<Message>> = day_events.iter().map(|merged| {
+                    let event = &merged.event;
+                    let time_str = if event.is_all_day {
+                        "All day".to_string()
+                    } else {
+                        event.start_time.with_timezone(&chrono::Local).format("%I:%M %p").to_string()
+                    };
                     let is_video = event.video_link.is_some();
+                    let is_team = self.is_team_only(&merged.account_ids);
                     
                     row![
+                        self.view_calendar_strip(&merged.account_ids),
                         text(time_str)
                             .size(14)
-                            .style(iced::theme::Text::Color(ZEN_ACCENT))
+                            .style(iced::theme::Text::Color(if is_team { ZEN_SUBTEXT } else { ZEN_ACCENT }))
                             .width(80),
                         
                         text(if is_video { "📹" } else { "" })
@@ -761,16 +2538,34 @@ impl OpenChimeApp {
                             .width(30),
                             
                         column![
-                            text(&event.title)
-                                .size(16)
-                                .style(iced::theme::Text::Color(ZEN_TEXT)),
+                            self.view_event_title(event, is_team),
                             if let Some(desc) = &event.description {
                                 text(desc.lines().next().unwrap_or(""))
                                     .size(12)
                                     .style(iced::theme::Text::Color(ZEN_SUBTEXT))
                             } else {
                                 text("")
-                            }
+                            },
+                            text(event.display_location().map(|location| format!("📍 {}", location)).unwrap_or_default())
+                                .size(12)
+                                .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                            if merged.account_ids.len() > 1 {
+                                text(format!("In {} calendars", merged.account_ids.len()))
+                                    .size(12)
+                                    .style(iced::theme::Text::Color(ZEN_SUBTEXT))
+                            } else {
+                                text("")
+                            },
+                            text(join_details_text(event).unwrap_or_default())
+                                .size(12)
+                                .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                            text(event.origin_start().map(|there| {
+                                format!("{} in {} (organizer's time)", there.format("%a %I:%M %p"), there.timezone().name())
+                            }).unwrap_or_default())
+                                .size(12)
+                                .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                            self.view_checklist(event),
+                            self.view_attendees(event),
                         ]
                     ]
                     .spacing(10)
@@ -797,6 +2592,7 @@ impl OpenChimeApp {
             
             column![
                 header,
+                self.view_world_clocks(),
                 scrollable(
                     column(event_cards).spacing(20)
                 )
@@ -815,7 +2611,12 @@ impl OpenChimeApp {
                         .size(18)
                         .style(iced::theme::Text::Color(ZEN_TEXT))
                         .width(Length::Fill),
-                ],
+                    button(text("Export to ICS").size(12))
+                        .on_press(Message::ExportEvents)
+                        .padding([6, 12])
+                        .style(iced::theme::Button::Custom(Box::new(NavStyle))),
+                ]
+                .align_items(iced::Alignment::Center),
                 
                 if self.accounts.is_empty() {
                     Element::from(
@@ -825,6 +2626,9 @@ impl OpenChimeApp {
                 } else {
                     column(
                         self.accounts.iter().map(|account| {
+                            if let Some(form) = self.ui_state.account_form.as_ref().filter(|f| account.id == Some(f.account_id)) {
+                                return self.view_account_form(account, form);
+                            }
                             row![
                                 column![
                                      text(&account.account_name)
@@ -832,9 +2636,31 @@ impl OpenChimeApp {
                                         .style(iced::theme::Text::Color(ZEN_TEXT)),
                                      text(format!("Provider: {}", account.provider))
                                         .size(12)
-                                        .style(iced::theme::Text::Color(ZEN_SUBTEXT))
+                                        .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                                     self.view_calendar_name(account),
+                                     self.view_sync_error(account)
                                 ],
                                 iced::widget::horizontal_space(),
+                                checkbox("Team calendar", account.is_shared)
+                                    .text_size(12)
+                                    .on_toggle_maybe(account.id.map(|id| move |on| Message::SetAccountShared(id, on))),
+                                checkbox("Informational", account.is_informational)
+                                    .text_size(12)
+                                    .on_toggle_maybe(account.id.map(|id| move |on| Message::SetAccountInformational(id, on))),
+                                checkbox("Alerts", account.alerts_enabled)
+                                    .text_size(12)
+                                    .on_toggle_maybe(account.id.map(|id| move |on| Message::SetAccountAlertsEnabled(id, on))),
+                                self.view_sync_interval_picker(account),
+                                self.view_timezone_picker(account),
+                                self.view_group_picker(account),
+                                button(text("Sync now").size(12))
+                                    .on_press_maybe(account.id.filter(|_| !self.ui_state.loading).map(Message::SyncAccount))
+                                    .padding([6, 12])
+                                    .style(iced::theme::Button::Custom(Box::new(NavStyle))),
+                                button(text("Edit").size(12))
+                                    .on_press_maybe(account.id.map(Message::EditAccount))
+                                    .padding([6, 12])
+                                    .style(iced::theme::Button::Custom(Box::new(NavStyle))),
                                 button("Unlink")
                                     .on_press(Message::DeleteAccount(account.id.unwrap_or(0)))
                                     .padding([6, 12])
@@ -868,12 +2694,95 @@ impl OpenChimeApp {
                 ].spacing(5),
 
                 column![
-                    text("ICS Feed URL")
+                    text("ICS Feed, CalDAV Calendar URL or CalDAV Server Hostname")
                         .size(12)
                         .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
                     text_input("https://...", &self.ui_state.ics_url)
                         .padding(10)
                         .on_input(Message::IcsUrlChanged),
+                    column(self.ui_state.discovered_feeds.iter().map(|feed| {
+                        row![
+                            text(feed)
+                                .size(12)
+                                .style(iced::theme::Text::Color(ZEN_SUBTEXT))
+                                .width(Length::Fill),
+                            button(text("Use this feed").size(12))
+                                .on_press(Message::UseDiscoveredFeed(feed.clone()))
+                                .padding([4, 10])
+                                .style(iced::theme::Button::Custom(Box::new(PrimaryButtonStyle))),
+                        ]
+                        .spacing(10)
+                        .align_items(iced::Alignment::Center)
+                        .into()
+                    }).collect::<Vec<_>>()).spacing(6),
+                    column(self.ui_state.discovered_calendars.iter().map(|calendar| {
+                        row![
+                            column![
+                                text(&calendar.name)
+                                    .size(14)
+                                    .style(iced::theme::Text::Color(ZEN_TEXT)),
+                                text(&calendar.url)
+                                    .size(12)
+                                    .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                            ]
+                            .width(Length::Fill),
+                            button(text("Add this calendar").size(12))
+                                .on_press(Message::UseDiscoveredCalendar(calendar.url.clone()))
+                                .padding([4, 10])
+                                .style(iced::theme::Button::Custom(Box::new(PrimaryButtonStyle))),
+                        ]
+                        .spacing(10)
+                        .align_items(iced::Alignment::Center)
+                        .into()
+                    }).collect::<Vec<_>>()).spacing(6),
+                ].spacing(5),
+
+                column![
+                    text("Sign-in (CalDAV servers such as Nextcloud or Fastmail, or a feed behind a login)")
+                        .size(12)
+                        .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                    row![
+                        text_input("Username", &self.ui_state.caldav_username)
+                            .padding(10)
+                            .on_input(Message::CalDavUsernameChanged),
+                        text_input("App password", &self.ui_state.caldav_password)
+                            .padding(10)
+                            .secure(true)
+                            .on_input(Message::CalDavPasswordChanged),
+                    ].spacing(10),
+                ].spacing(5),
+
+                column![
+                    text("Request Headers (optional, for feeds behind a corporate gateway)")
+                        .size(12)
+                        .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                    column(self.ui_state.request_headers.iter().enumerate().map(|(index, header)| {
+                        // Only the name: values are usually tokens
+                        let name = header.split(':').next().unwrap_or_default();
+                        row![
+                            text(format!("{}: ••••", name))
+                                .size(12)
+                                .style(iced::theme::Text::Color(ZEN_SUBTEXT))
+                                .width(Length::Fill),
+                            button(text("Remove").size(12))
+                                .on_press(Message::RemoveRequestHeader(index))
+                                .padding([4, 10])
+                                .style(iced::theme::Button::Custom(Box::new(NavStyle))),
+                        ]
+                        .spacing(10)
+                        .align_items(iced::Alignment::Center)
+                        .into()
+                    }).collect::<Vec<_>>()).spacing(6),
+                    row![
+                        text_input("e.g., X-Api-Key: abc123", &self.ui_state.header_input)
+                            .padding(10)
+                            .on_input(Message::RequestHeaderChanged)
+                            .on_submit(Message::AddRequestHeader),
+                        button(text("Add").size(12))
+                            .on_press(Message::AddRequestHeader)
+                            .padding([10, 14])
+                            .style(iced::theme::Button::Custom(Box::new(NavStyle))),
+                    ].spacing(10),
                 ].spacing(5),
 
                  row![
@@ -883,13 +2792,27 @@ impl OpenChimeApp {
                         .style(iced::theme::Button::Custom(Box::new(NavStyle))), // Subtle style
                     
                     iced::widget::horizontal_space(),
+
+                    button("Test")
+                        .on_press(Message::TestAccount)
+                        .padding([10, 20])
+                        .style(iced::theme::Button::Custom(Box::new(NavStyle))),
+
+                    button("Preview")
+                        .on_press(Message::PreviewAccount)
+                        .padding([10, 20])
+                        .style(iced::theme::Button::Custom(Box::new(NavStyle))),
                     
                     button("Link Account")
                         .on_press(Message::AddProtonAccount)
                         .padding([10, 20])
                         .style(iced::theme::Button::Custom(Box::new(PrimaryButtonStyle))),
                 ]
-                .align_items(iced::Alignment::Center)
+                .spacing(10)
+                .align_items(iced::Alignment::Center),
+
+                self.view_connection_test(),
+                self.view_account_preview(),
             ]
             .spacing(15)
         )
@@ -935,6 +2858,156 @@ impl OpenChimeApp {
                     .on_toggle(Message::ToggleAlert1m),
                 checkbox("Alert at start time", self.settings.alert_default)
                     .on_toggle(Message::ToggleAlertDefault),
+                column![
+                    text("Extra alerts (minutes before)")
+                        .size(14)
+                        .style(iced::theme::Text::Color(ZEN_TEXT)),
+                    text_input(
+                        "e.g. 45, 15, 2",
+                        self.ui_state.custom_alerts_draft.as_deref().unwrap_or(&self.settings.custom_alert_minutes),
+                    )
+                    .on_input(Message::CustomAlertsChanged)
+                    .on_submit(Message::SaveCustomAlerts)
+                    .padding(8),
+                    text("Times other than 30, 10, 5 and 1 play a plain chime. Press Enter to save.")
+                        .size(12)
+                        .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                ]
+                .spacing(6),
+                checkbox("Also alert at reminders set in the calendar", self.settings.calendar_reminders)
+                    .on_toggle(Message::ToggleCalendarReminders),
+                column![
+                    text("My email addresses")
+                        .size(14)
+                        .style(iced::theme::Text::Color(ZEN_TEXT)),
+                    text_input(
+                        "e.g. me@example.com, me@work.example",
+                        self.ui_state.my_addresses_draft.as_deref().unwrap_or(&self.settings.my_addresses),
+                    )
+                    .on_input(Message::MyAddressesChanged)
+                    .on_submit(Message::SaveMyAddresses)
+                    .padding(8),
+                    text("Meetings you've declined from these addresses don't alert. Press Enter to save.")
+                        .size(12)
+                        .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                ]
+                .spacing(6),
+                checkbox("Alert for events shown as free", self.settings.alert_free_events)
+                    .on_toggle(Message::ToggleAlertFreeEvents),
+                checkbox("Don't alert for all-day events", self.settings.skip_all_day_events)
+                    .on_toggle(Message::ToggleSkipAllDayEvents),
+                checkbox("Check for new versions on startup", self.settings.check_for_updates)
+                    .on_toggle(Message::ToggleUpdateCheck),
+                checkbox("Hold syncs while calendar servers can't be reached", self.settings.hold_syncs_offline)
+                    .on_toggle(Message::ToggleHoldSyncsOffline),
+                row![
+                    text("Sync calendars")
+                        .size(14)
+                        .style(iced::theme::Text::Color(ZEN_TEXT)),
+                    iced::widget::horizontal_space(),
+                    pick_list(
+                        SYNC_INTERVAL_CHOICES.map(SyncIntervalChoice).to_vec(),
+                        Some(SyncIntervalChoice(self.settings.sync_interval)),
+                        |choice| Message::SyncIntervalChanged(choice.0),
+                    ),
+                ]
+                .align_items(iced::Alignment::Center),
+                row![
+                    text("Import past events")
+                        .size(14)
+                        .style(iced::theme::Text::Color(ZEN_TEXT)),
+                    iced::widget::horizontal_space(),
+                    pick_list(
+                        SYNC_DAYS_BACK_CHOICES.map(SyncDaysChoice).to_vec(),
+                        Some(SyncDaysChoice(self.settings.sync_days_back)),
+                        |choice| Message::SyncDaysBackChanged(choice.0),
+                    ),
+                ]
+                .align_items(iced::Alignment::Center),
+                row![
+                    text("Import upcoming events")
+                        .size(14)
+                        .style(iced::theme::Text::Color(ZEN_TEXT)),
+                    iced::widget::horizontal_space(),
+                    pick_list(
+                        SYNC_DAYS_AHEAD_CHOICES.map(SyncDaysChoice).to_vec(),
+                        Some(SyncDaysChoice(self.settings.sync_days_ahead)),
+                        |choice| Message::SyncDaysAheadChanged(choice.0),
+                    ),
+                ]
+                .align_items(iced::Alignment::Center),
+                row![
+                    text("Keep the computer awake")
+                        .size(14)
+                        .style(iced::theme::Text::Color(ZEN_TEXT)),
+                    iced::widget::horizontal_space(),
+                    pick_list(
+                        crate::wake_lock::KEEP_AWAKE_MINUTE_CHOICES.map(KeepAwakeChoice).to_vec(),
+                        Some(KeepAwakeChoice(self.settings.keep_awake_minutes)),
+                        |choice| Message::KeepAwakeMinutesChanged(choice.0),
+                    ),
+                ]
+                .align_items(iced::Alignment::Center),
+                row![
+                    text("Largest calendar feed")
+                        .size(14)
+                        .style(iced::theme::Text::Color(ZEN_TEXT)),
+                    iced::widget::horizontal_space(),
+                    pick_list(
+                        MAX_FEED_SIZE_CHOICES.map(FeedSizeChoice).to_vec(),
+                        Some(FeedSizeChoice(self.settings.max_feed_size_mb)),
+                        |choice| Message::MaxFeedSizeChanged(choice.0),
+                    ),
+                ]
+                .align_items(iced::Alignment::Center),
+                column![
+                    text("World clocks")
+                        .size(14)
+                        .style(iced::theme::Text::Color(ZEN_TEXT)),
+                    text_input(
+                        "e.g. America/New_York, Asia/Tokyo",
+                        self.ui_state.world_clocks_draft.as_deref().unwrap_or(&self.settings.world_clocks),
+                    )
+                    .on_input(Message::WorldClocksChanged)
+                    .on_submit(Message::SaveWorldClocks)
+                    .padding(8),
+                    text("Shown above the calendar. Press Enter to save.")
+                        .size(12)
+                        .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                ]
+                .spacing(6),
+                column![
+                    text("Sound profiles")
+                        .size(14)
+                        .style(iced::theme::Text::Color(ZEN_TEXT)),
+                    text_input(
+                        "e.g. 07:00-10:00 gentle, 18:00-07:00 silent",
+                        self.ui_state.sound_profiles_draft.as_deref().unwrap_or(&self.settings.sound_profiles),
+                    )
+                    .on_input(Message::SoundProfilesChanged)
+                    .on_submit(Message::SaveSoundProfiles)
+                    .padding(8),
+                    text("Gentle alerts play quieter; silent ones only show here. Press Enter to save.")
+                        .size(12)
+                        .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                ]
+                .spacing(6),
+                column![
+                    text("Proxy")
+                        .size(14)
+                        .style(iced::theme::Text::Color(ZEN_TEXT)),
+                    text_input(
+                        "e.g. http://proxy.example.com:3128 or socks5://127.0.0.1:1080",
+                        self.ui_state.proxy_url_draft.as_deref().unwrap_or(&self.settings.proxy_url),
+                    )
+                    .on_input(Message::ProxyUrlChanged)
+                    .on_submit(Message::SaveProxyUrl)
+                    .padding(8),
+                    text("Leave empty to use HTTPS_PROXY from the environment, or enter none to connect directly. Press Enter to save.")
+                        .size(12)
+                        .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                ]
+                .spacing(6),
             ]
             .spacing(15)
         )
@@ -948,8 +3021,10 @@ impl OpenChimeApp {
                     .size(28)
                     .style(iced::theme::Text::Color(ZEN_TEXT)),
                 accounts_card,
+                self.view_groups_card(),
                 alerts_card,
                 add_account_card,
+                self.view_import_card(),
                 audio_card
             ]
             .spacing(20)
@@ -957,16 +3032,409 @@ impl OpenChimeApp {
         .into()
     }
     
-    fn view_alerts(&self) -> Element<'_, Message> {
-        // Show upcoming events that need alerts
+    /// Sync interval picker for an account's row
+    fn view_sync_interval_picker(&self, account: &Account) -> Element<'_, Message> {
+        let Some(account_id) = account.id else {
+            return iced::widget::Space::with_width(0).into();
+        };
+        let feed = account.feed_refresh_interval;
+        let mut choices = vec![AccountSyncIntervalChoice(None, feed)];
+        choices.extend(ACCOUNT_SYNC_INTERVAL_CHOICES.map(|secs| AccountSyncIntervalChoice(Some(secs), feed)));
+
+        pick_list(choices, Some(AccountSyncIntervalChoice(account.sync_interval, feed)), move |choice| {
+            Message::SetAccountSyncInterval(account_id, choice.0)
+        })
+        .text_size(12)
+        .into()
+    }
+
+    /// Timezone picker for an account's row, for feeds whose times carry no
+    /// zone of their own
+    fn view_timezone_picker(&self, account: &Account) -> Element<'_, Message> {
+        let Some(account_id) = account.id.filter(|_| !account.is_local()) else {
+            return iced::widget::Space::with_width(0).into();
+        };
+        let mut choices = vec![AccountTimezoneChoice(None)];
+        choices.extend(chrono_tz::TZ_VARIANTS.iter().map(|tz| AccountTimezoneChoice(Some(*tz))));
+        let selected = account.timezone.as_deref().and_then(|tz| tz.parse().ok());
+
+        pick_list(choices, Some(AccountTimezoneChoice(selected)), move |choice| {
+            Message::SetAccountTimezone(account_id, choice.0.map(|tz| tz.name().to_string()))
+        })
+        .text_size(12)
+        .into()
+    }
+
+    /// Group picker for an account's row; nothing until a group exists
+    fn view_group_picker(&self, account: &Account) -> Element<'_, Message> {
+        let (Some(account_id), false) = (account.id, self.groups.is_empty()) else {
+            return iced::widget::Space::with_width(0).into();
+        };
+        let mut choices = vec![GroupChoice(None, "No group".to_string())];
+        choices.extend(self.groups.iter().map(|g| GroupChoice(Some(g.id), g.name.clone())));
+        let selected = choices.iter().find(|c| c.0 == account.group_id).cloned();
+
+        pick_list(choices, selected, move |choice| Message::AssignAccountGroup(account_id, choice.0))
+            .text_size(12)
+            .into()
+    }
+
+    /// Groups with their sync, alert and visibility switches
+    fn view_groups_card(&self) -> Element<'_, Message> {
+        let groups: Element<Message> = if self.groups.is_empty() {
+            text("Group accounts (Work, Personal) to switch them on and off together.")
+                .size(12)
+                .style(iced::theme::Text::Color(ZEN_SUBTEXT))
+                .into()
+        } else {
+            column(self.groups.iter().map(|group| {
+                let id = group.id;
+                row![
+                    text(&group.name)
+                        .size(16)
+                        .style(iced::theme::Text::Color(ZEN_TEXT))
+                        .width(Length::Fill),
+                    checkbox("Sync", group.sync_enabled)
+                        .on_toggle(move |on| Message::SetGroupToggle(id, GroupToggle::Sync, on)),
+                    checkbox("Alerts", group.alerts_enabled)
+                        .on_toggle(move |on| Message::SetGroupToggle(id, GroupToggle::Alerts, on)),
+                    checkbox("Show", group.visible)
+                        .on_toggle(move |on| Message::SetGroupToggle(id, GroupToggle::Visible, on)),
+                    button("Delete")
+                        .on_press(Message::DeleteGroup(id))
+                        .padding([6, 12])
+                        .style(iced::theme::Button::Custom(Box::new(DestructiveButtonStyle)))
+                ]
+                .spacing(12)
+                .align_items(iced::Alignment::Center)
+                .into()
+            }).collect::<Vec<_>>())
+            .spacing(10)
+            .into()
+        };
+
+        container(
+            column![
+                text("Account Groups")
+                    .size(18)
+                    .style(iced::theme::Text::Color(ZEN_TEXT)),
+                groups,
+                row![
+                    text_input("e.g., Work", &self.ui_state.group_name)
+                        .padding(10)
+                        .on_input(Message::GroupNameChanged)
+                        .on_submit(Message::AddGroup),
+                    button("Add Group")
+                        .on_press(Message::AddGroup)
+                        .padding([10, 20])
+                        .style(iced::theme::Button::Custom(Box::new(PrimaryButtonStyle))),
+                ]
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+            ]
+            .spacing(15)
+        )
+        .padding(20)
+        .width(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(CardStyle)))
+        .into()
+    }
+
+    /// Pasting an invite's ICS text (or the path to its .ics file) to add
+    /// its events
+    fn view_import_card(&self) -> Element<'_, Message> {
+        container(
+            column![
+                text("Import ICS")
+                    .size(18)
+                    .style(iced::theme::Text::Color(ZEN_TEXT)),
+                text("Add the events of an invite that came as an .ics attachment. They go into the \"Imported\" account.")
+                    .size(12)
+                    .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                row![
+                    text_input("Paste ICS text, or the path to an .ics file", &self.ui_state.import_input)
+                        .padding(10)
+                        .on_input(Message::ImportInputChanged)
+                        .on_submit(Message::ImportIcs),
+                    button("Import")
+                        .on_press(Message::ImportIcs)
+                        .padding([10, 20])
+                        .style(iced::theme::Button::Custom(Box::new(PrimaryButtonStyle))),
+                ]
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+            ]
+            .spacing(15)
+        )
+        .padding(20)
+        .width(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(CardStyle)))
+        .into()
+    }
+
+    /// A card per account that has stopped syncing, each with a retry button
+    fn view_sync_warnings(&self) -> Element<'_, Message> {
         let now = chrono::Utc::now();
-        let upcoming_events: Vec<_> = self.events.iter()
+        let warnings: Vec<Element<Message>> = self.ui_state.stalled_syncs.iter().map(|stalled| {
+            container(
+                column![
+                    text(stalled.message(now))
+                        .size(12)
+                        .style(iced::theme::Text::Color(ZEN_DESTRUCTIVE)),
+                    button(text("Retry Now").size(11))
+                        .on_press(Message::RetrySync(stalled.account_id))
+                        .padding([4, 8])
+                        .style(iced::theme::Button::Custom(Box::new(NavStyle)))
+                ]
+                .spacing(6)
+            )
+            .padding(10)
+            .width(Length::Fill)
+            .style(iced::theme::Container::Custom(Box::new(CardStyle)))
+            .into()
+        }).collect();
+
+        column(warnings).spacing(10).into()
+    }
+
+    fn view_update_banner(&self) -> Element<'_, Message> {
+        let Some(update) = &self.ui_state.available_update else {
+            return column![].into();
+        };
+
+        container(
+            column![
+                text(format!("Version {} is available", update.version))
+                    .size(12)
+                    .style(iced::theme::Text::Color(ZEN_ACCENT)),
+                button(text("Release notes").size(11))
+                    .on_press(Message::OpenUrl(update.release_url.clone()))
+                    .padding([4, 8])
+                    .style(iced::theme::Button::Custom(Box::new(NavStyle)))
+            ]
+            .spacing(6)
+        )
+        .padding(10)
+        .width(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(CardStyle)))
+        .into()
+    }
+
+    fn view_focus(&self) -> Element<'_, Message> {
+        let now = chrono::Utc::now();
+        let remaining = self.focus.remaining(now).unwrap_or(match self.focus.phase() {
+            FocusPhase::Work => self.settings.focus_work_duration(),
+            FocusPhase::Break => self.settings.focus_break_duration(),
+        });
+        let seconds = remaining.num_seconds().max(0);
+
+        let (phase_label, status) = if self.focus.is_idle() {
+            ("Ready to focus", "Work and break chimes use your alert volume".to_string())
+        } else if self.focus.is_paused_for_meeting() {
+            ("Paused for a meeting", "Resumes automatically when the meeting ends".to_string())
+        } else {
+            let label = match self.focus.phase() {
+                FocusPhase::Work => "Focus",
+                FocusPhase::Break => "Break",
+            };
+            let sessions = self.focus.completed_sessions();
+            (label, format!("{} session{} completed", sessions, if sessions == 1 { "" } else { "s" }))
+        };
+
+        let control = |label: &'static str, msg: Message| {
+            button(label)
+                .on_press(msg)
+                .padding([8, 16])
+                .style(iced::theme::Button::Custom(Box::new(PrimaryButtonStyle)))
+        };
+        let controls = if self.focus.is_idle() {
+            row![control("Start", Message::FocusStart)]
+        } else if self.focus.is_running() {
+            row![control("Pause", Message::FocusPause), control("Stop", Message::FocusStop)]
+        } else {
+            row![control("Resume", Message::FocusResume), control("Stop", Message::FocusStop)]
+        }
+        .spacing(10);
+
+        let timer_card = container(
+            column![
+                text(phase_label)
+                    .size(18)
+                    .style(iced::theme::Text::Color(ZEN_ACCENT)),
+                text(format!("{:02}:{:02}", seconds / 60, seconds % 60))
+                    .size(48)
+                    .style(iced::theme::Text::Color(ZEN_TEXT)),
+                text(status)
+                    .size(12)
+                    .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                controls,
+            ]
+            .spacing(15)
+            .align_items(iced::Alignment::Center)
+        )
+        .padding(30)
+        .width(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(CardStyle)));
+
+        let lengths_card = container(
+            column![
+                row![
+                    text("Work session")
+                        .size(14)
+                        .style(iced::theme::Text::Color(ZEN_TEXT)),
+                    iced::widget::horizontal_space(),
+                    pick_list(
+                        WORK_MINUTE_CHOICES.map(FocusMinutesChoice).to_vec(),
+                        Some(FocusMinutesChoice(self.settings.focus_work_minutes)),
+                        |choice| Message::FocusWorkMinutesChanged(choice.0),
+                    ),
+                ]
+                .align_items(iced::Alignment::Center),
+                row![
+                    text("Break")
+                        .size(14)
+                        .style(iced::theme::Text::Color(ZEN_TEXT)),
+                    iced::widget::horizontal_space(),
+                    pick_list(
+                        BREAK_MINUTE_CHOICES.map(FocusMinutesChoice).to_vec(),
+                        Some(FocusMinutesChoice(self.settings.focus_break_minutes)),
+                        |choice| Message::FocusBreakMinutesChanged(choice.0),
+                    ),
+                ]
+                .align_items(iced::Alignment::Center),
+            ]
+            .spacing(15)
+        )
+        .padding(20)
+        .width(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(CardStyle)));
+
+        column![
+            text("Focus")
+                .size(28)
+                .style(iced::theme::Text::Color(ZEN_TEXT)),
+            timer_card,
+            lengths_card,
+        ]
+        .spacing(20)
+        .into()
+    }
+
+    fn view_stats(&self) -> Element<'_, Message> {
+        use crate::ui::charts::{BarChart, RatioBar};
+
+        let header = text("Stats")
+            .size(28)
+            .style(iced::theme::Text::Color(ZEN_TEXT));
+
+        let Some(stats) = &self.stats else {
+            return column![
+                header,
+                text("Crunching the numbers...").style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+            ]
+            .spacing(20)
+            .into();
+        };
+
+        let card = |title: String, body: Element<'static, Message>| {
+            container(
+                column![
+                    text(title)
+                        .size(16)
+                        .style(iced::theme::Text::Color(ZEN_TEXT)),
+                    body,
+                ]
+                .spacing(12)
+            )
+            .padding(20)
+            .width(Length::Fill)
+            .style(iced::theme::Container::Custom(Box::new(CardStyle)))
+        };
+
+        let weekly = BarChart {
+            bars: stats.hours_per_week.iter().map(|(monday, hours)| (monday.format("%b %d").to_string(), *hours)).collect(),
+            format: |hours| format!("{:.1}h", hours),
+        };
+        let weekdays = BarChart {
+            bars: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+                .iter()
+                .zip(stats.meetings_per_weekday)
+                .map(|(day, count)| (day.to_string(), count as f64))
+                .collect(),
+            format: |count| format!("{:.0}", count),
+        };
+        let video_share = RatioBar {
+            left: ("Video".to_string(), stats.video_meetings),
+            right: ("In person".to_string(), stats.in_person_meetings),
+        };
+
+        fn chart<P: iced::widget::canvas::Program<Message> + 'static>(program: P, height: f32) -> Element<'static, Message> {
+            iced::widget::canvas(program).width(Length::Fill).height(height).into()
+        }
+
+        scrollable(
+            column![
+                header,
+                card(format!("Meeting hours per week (last {} weeks)", stats.hours_per_week.len()), chart(weekly, 180.0)),
+                card("Busiest days".to_string(), chart(weekdays, 160.0)),
+                row![
+                    card(format!("Video vs in person ({} meetings)", stats.total_meetings()), chart(video_share, 42.0)),
+                    card(
+                        "Average snoozes".to_string(),
+                        text(format!("{:.1} per meeting", stats.average_snoozes))
+                            .size(24)
+                            .style(iced::theme::Text::Color(ZEN_ACCENT))
+                            .into(),
+                    ),
+                ]
+                .spacing(20),
+                card("Sync reliability (past week)".to_string(), self.view_sync_reliability()),
+            ]
+            .spacing(20)
+        )
+        .into()
+    }
+
+    /// Each account's success rate, sync time and last error, flaky ones
+    /// highlighted
+    fn view_sync_reliability(&self) -> Element<'static, Message> {
+        let Some(report) = &self.sync_reliability else {
+            return text("Loading...").style(iced::theme::Text::Color(ZEN_SUBTEXT)).into();
+        };
+        if report.is_empty() {
+            return text("No syncs recorded yet").style(iced::theme::Text::Color(ZEN_SUBTEXT)).into();
+        }
+
+        let rows: Vec<Element<'static, Message>> = report.iter().map(|reliability| {
+            let color = if reliability.is_flaky() { ZEN_DESTRUCTIVE } else { ZEN_SUBTEXT };
+            column![
+                text(reliability.account_name.clone()).size(14).style(iced::theme::Text::Color(ZEN_TEXT)),
+                text(reliability.summary()).size(12).style(iced::theme::Text::Color(color)),
+            ]
+            .spacing(2)
+            .into()
+        }).collect();
+        column(rows).spacing(10).into()
+    }
+
+    /// The meetings the Alerts Center shows: starting within the hour, or
+    /// started in the last five minutes, one per meeting
+    fn alert_center_events(&self, now: chrono::DateTime<chrono::Utc>) -> Vec<CalendarEvent> {
+        self.merged_events
+            .iter()
+            .map(|merged| &merged.event)
             .filter(|event| {
-                let minutes_until = (event.start_time - now).num_minutes();
+                let minutes_until = (event.effective_start() - now).num_minutes();
                 (-5..=60).contains(&minutes_until) // Show active events too
             })
-            // Sort primarily by urgency (happening soonest)
-            .collect();
+            .cloned()
+            .collect()
+    }
+
+    fn view_alerts(&self) -> Element<'_, Message> {
+        // Show upcoming events that need alerts
+        let now = chrono::Utc::now();
+        let upcoming_events = self.alert_center_events(now);
 
         let header = text("Alerts Center")
                 .size(28)
@@ -996,7 +3464,7 @@ impl OpenChimeApp {
             .into()
         } else {
             let alert_cards: Vec<Element<Message>> = upcoming_events.iter().map(|event| {
-                let minutes_until = (event.start_time - now).num_minutes();
+                let minutes_until = (event.effective_start() - now).num_minutes();
                 let is_video = event.video_link.is_some();
                 
                 // Dynamic styling based on urgency
@@ -1026,7 +3494,7 @@ impl OpenChimeApp {
                         
                         // Info Column
                         column![
-                             text(&event.title)
+                             text(event.display_title())
                                  .size(18)
                                  .style(iced::theme::Text::Color(ZEN_TEXT)),
                              if is_video {
@@ -1037,11 +3505,34 @@ impl OpenChimeApp {
                                  text("In Person / No Link")
                                      .size(12)
                                      .style(iced::theme::Text::Color(ZEN_SUBTEXT))
-                             }
+                             },
+                             text(event.display_location().map(|location| format!("📍 {}", location)).unwrap_or_default())
+                                 .size(12)
+                                 .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                             text(join_details_text(event).unwrap_or_default())
+                                 .size(12)
+                                 .style(iced::theme::Text::Color(ZEN_SUBTEXT)),
+                             text(event.id
+                                 .and_then(|id| self.ui_state.to_prepare.get(&id))
+                                 .filter(|items| !items.is_empty())
+                                 .map(|items| format!("To prepare: {}", items.join(" · ")))
+                                 .unwrap_or_default())
+                                 .size(12)
+                                 .style(iced::theme::Text::Color(ZEN_DESTRUCTIVE))
                         ]
                         .padding([0, 10]),
                         
                         iced::widget::horizontal_space(),
+
+                        // Join by phone
+                        if let Some(dial_in) = event.dial_in() {
+                             Element::from(button("Dial In")
+                                .padding([10, 20])
+                                .style(iced::theme::Button::Custom(Box::new(NavStyle)))
+                                .on_press(Message::OpenUrl(dial_in.tel_uri())))
+                        } else {
+                             Element::from(text(""))
+                        },
                         
                         // Action Button
                         if let Some(url) = &event.video_link {
@@ -1061,6 +3552,28 @@ impl OpenChimeApp {
                 .into()
             }).collect();
             
+            // Batch actions once there's more than one alert to deal with
+            let event_ids: Vec<crate::models::EventId> = upcoming_events.iter().filter_map(|event| event.id).collect();
+            let header: Element<Message> = if event_ids.len() > 1 {
+                row![
+                    header,
+                    iced::widget::horizontal_space(),
+                    button(text(format!("Snooze all {}m", crate::alerts::SNOOZE_ALL_MINUTES)))
+                        .padding([8, 16])
+                        .style(iced::theme::Button::Custom(Box::new(NavStyle)))
+                        .on_press(Message::SnoozeAll(event_ids.clone())),
+                    button("Dismiss all")
+                        .padding([8, 16])
+                        .style(iced::theme::Button::Custom(Box::new(DestructiveButtonStyle)))
+                        .on_press(Message::DismissAll(event_ids)),
+                ]
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+                .into()
+            } else {
+                header.into()
+            };
+
             column![
                 header,
                 scrollable(
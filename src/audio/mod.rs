@@ -11,6 +11,7 @@ use anyhow::{Result, Context};
 pub struct AudioManager {
     volume: Arc<Mutex<f32>>,
     sound_files: Arc<Mutex<SoundFiles>>,
+    sound_schedule: Arc<Mutex<SoundSchedule>>,
 }
 
 #[derive(Debug, Clone)]
@@ -25,6 +26,28 @@ pub struct SoundFiles {
 }
 
 pub use crate::models::AlertType;
+use crate::models::{SoundProfile, SoundSchedule};
+
+impl SoundFiles {
+    /// The file played for `alert_type`
+    pub fn for_alert(&self, alert_type: &AlertType) -> &Path {
+        match alert_type {
+            AlertType::Meeting => &self.meeting_alert,
+            AlertType::VideoMeeting => &self.video_meeting_alert,
+            AlertType::SnoozeReminder => &self.meeting_alert, // Use meeting sound for snooze
+            AlertType::Test => &self.test_sound,
+            AlertType::Warning30m => &self.alert_30m,
+            AlertType::Warning10m => &self.alert_10m,
+            AlertType::Warning5m => &self.alert_5m,
+            AlertType::Warning1m => &self.alert_1m,
+            // Custom thresholds get the plain chime, not a spoken time
+            AlertType::Warning => &self.test_sound,
+            // The focus timer reuses the gentler warning chimes
+            AlertType::FocusBreak => &self.alert_5m,
+            AlertType::FocusWork => &self.alert_1m,
+        }
+    }
+}
 
 impl AudioManager {
     pub fn new() -> Result<Self> {
@@ -36,6 +59,7 @@ impl AudioManager {
         Ok(AudioManager {
             volume,
             sound_files,
+            sound_schedule: Arc::new(Mutex::new(SoundSchedule::default())),
         })
     }
     
@@ -55,6 +79,7 @@ impl AudioManager {
                 alert_5m: PathBuf::new(),
                 alert_1m: PathBuf::new(),
             })),
+            sound_schedule: Arc::new(Mutex::new(SoundSchedule::default())),
         }
     }
     
@@ -107,21 +132,28 @@ impl AudioManager {
         Ok(())
     }
     
+    /// Replace the time-of-day profiles `play_alert` applies
+    pub fn set_sound_schedule(&self, schedule: SoundSchedule) {
+        *self.sound_schedule.lock().unwrap() = schedule;
+    }
+
+    /// Profile for an alert played now; test sounds always play normally
+    fn profile_for(&self, alert_type: &AlertType) -> SoundProfile {
+        if matches!(alert_type, AlertType::Test) {
+            return SoundProfile::Standard;
+        }
+        self.sound_schedule.lock().unwrap().profile_at(chrono::Local::now().time())
+    }
+
     pub fn play_alert(&self, alert_type: AlertType) -> Result<()> {
-        let sound_files = self.sound_files.lock().unwrap();
-        let sound_path = match alert_type {
-            AlertType::Meeting => &sound_files.meeting_alert,
-            AlertType::VideoMeeting => &sound_files.video_meeting_alert,
-            AlertType::SnoozeReminder => &sound_files.meeting_alert, // Use meeting sound for snooze
-            AlertType::Test => &sound_files.test_sound,
-            AlertType::Warning30m => &sound_files.alert_30m,
-            AlertType::Warning10m => &sound_files.alert_10m,
-            AlertType::Warning5m => &sound_files.alert_5m,
-            AlertType::Warning1m => &sound_files.alert_1m,
-        };
-        
-        let volume = *self.volume.lock().unwrap();
-        let sound_path = sound_path.clone();
+        let profile = self.profile_for(&alert_type);
+        if profile == SoundProfile::Silent {
+            debug!("Not playing {:?}: silent sound profile", alert_type);
+            return Ok(());
+        }
+
+        let sound_path = self.sound_files.lock().unwrap().for_alert(&alert_type).to_path_buf();
+        let volume = *self.volume.lock().unwrap() * profile.volume_factor();
         
         tokio::task::spawn_blocking(move || {
             if let Err(e) = Self::play_sound_file(&sound_path, volume) {
@@ -169,7 +201,10 @@ impl AudioManager {
     
     fn play_default_sound(stream_handle: &OutputStreamHandle, volume: f32) -> Result<()> {
         warn!("Playing default sine wave tone (no sound file found)");
-        
+        Self::play_sine_tone(stream_handle, volume)
+    }
+
+    fn play_sine_tone(stream_handle: &OutputStreamHandle, volume: f32) -> Result<()> {
         // Generate a simple sine wave as fallback using rodio's SineWave
         let source = rodio::source::SineWave::new(440.0) // A4 note
             .take_duration(Duration::from_millis(500))
@@ -187,6 +222,17 @@ impl AudioManager {
         info!("Testing audio system");
         self.play_alert(AlertType::Test)
     }
+
+    /// Play a short sine tone on the default output device and wait for it to finish.
+    ///
+    /// Unlike `play_alert`, this runs synchronously and returns any device error,
+    /// so callers (e.g. `openchime doctor`) can report whether audio actually works.
+    pub fn play_test_tone(&self) -> Result<()> {
+        let volume = self.get_volume();
+        let (_stream, stream_handle) = OutputStream::try_default()
+            .context("Failed to open default audio output device")?;
+        Self::play_sine_tone(&stream_handle, volume)
+    }
     
     pub fn ensure_sound_directory() -> Result<PathBuf> {
         let app_data_dir = dirs::data_dir()
@@ -349,6 +395,18 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_sound_schedule_spares_test_sounds() {
+        let manager = AudioManager::new().unwrap();
+        assert_eq!(manager.profile_for(&AlertType::Meeting), SoundProfile::Standard);
+
+        // A range that starts where it ends covers the whole day
+        manager.set_sound_schedule("00:00-00:00 silent".parse().unwrap());
+        assert_eq!(manager.profile_for(&AlertType::Meeting), SoundProfile::Silent);
+        assert_eq!(manager.profile_for(&AlertType::Warning5m), SoundProfile::Silent);
+        assert_eq!(manager.profile_for(&AlertType::Test), SoundProfile::Standard);
+    }
+
     #[tokio::test]
     async fn test_test_audio() {
         let manager = AudioManager::new().unwrap();
@@ -0,0 +1,326 @@
+//! On-disk cache for ICS feed downloads
+//!
+//! Stores the last successful body per feed URL together with the freshness
+//! lifetime the server advertised (`Cache-Control: max-age` / `Expires`).
+//! Fresh entries are served without touching the network, and when a fetch
+//! fails the last good copy is used instead so a flaky feed doesn't leave
+//! alerts running off whatever happened to be in the database.
+//!
+//! The response's `ETag`/`Last-Modified` are kept with the body so a fresh
+//! copy can be recognised as the one a sync already parsed. Feed URLs are
+//! secrets, so entries are named by a hash of the URL and the sign-in sent
+//! with it, and only the user can read them.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::header::{HeaderMap, HeaderName, CACHE_CONTROL, ETAG, EXPIRES, LAST_MODIFIED};
+use serde::{Deserialize, Serialize};
+
+use crate::calendar::source::FeedAuth;
+use crate::models::FeedValidators;
+use crate::utils::fnv::fnv1a64;
+
+/// Cached feeds older than this are never used as a fallback
+pub const MAX_STALE_AGE_DAYS: i64 = 7;
+
+/// What the response headers allow us to do with a body
+#[derive(Debug, Clone, PartialEq)]
+pub enum CachePolicy {
+    /// `Cache-Control: no-store` – never write the body to disk
+    NoStore,
+    /// Store the body; it may be reused without revalidation until `expires_at`
+    Store { expires_at: Option<DateTime<Utc>> },
+}
+
+/// A cached feed body and when it was fetched
+#[derive(Debug, Clone)]
+pub struct CachedFeed {
+    pub body: String,
+    pub fetched_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub validators: FeedValidators,
+}
+
+impl CachedFeed {
+    /// Still within the lifetime the server advertised
+    pub fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires| now < expires)
+    }
+
+    /// Recent enough to use when the live fetch fails
+    pub fn is_usable_fallback(&self, now: DateTime<Utc>) -> bool {
+        now - self.fetched_at <= Duration::days(MAX_STALE_AGE_DAYS)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheMetadata {
+    fetched_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    validators: FeedValidators,
+}
+
+/// The validators to send back on the next fetch of the same feed
+pub fn validators_from_headers(headers: &HeaderMap) -> FeedValidators {
+    let header = |name: HeaderName| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+    FeedValidators {
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    }
+}
+
+/// Work out the cache policy from response headers
+pub fn cache_policy_from_headers(headers: &HeaderMap, now: DateTime<Utc>) -> CachePolicy {
+    if let Some(cache_control) = headers.get(CACHE_CONTROL).and_then(|v| v.to_str().ok()) {
+        let mut max_age = None;
+        for directive in cache_control.split(',').map(|d| d.trim().to_ascii_lowercase()) {
+            if directive == "no-store" {
+                return CachePolicy::NoStore;
+            }
+            if directive == "no-cache" {
+                // Stored for fallback, but must be revalidated on every sync
+                max_age = Some(0);
+            } else if let Some(seconds) = directive.strip_prefix("max-age=") {
+                if let Ok(seconds) = seconds.trim_matches('"').parse::<i64>() {
+                    max_age = Some(max_age.map_or(seconds, |current: i64| current.min(seconds)));
+                }
+            }
+        }
+
+        if let Some(seconds) = max_age {
+            return CachePolicy::Store { expires_at: Some(now + Duration::seconds(seconds.max(0))) };
+        }
+    }
+
+    let expires_at = headers
+        .get(EXPIRES)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+        .map(|dt| dt.with_timezone(&Utc));
+
+    CachePolicy::Store { expires_at }
+}
+
+/// Directory-backed cache keyed by feed URL and sign-in
+#[derive(Debug, Clone)]
+pub struct IcsCache {
+    dir: PathBuf,
+}
+
+impl IcsCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Cache in the platform cache directory (e.g. `~/.cache/openchime/ics`)
+    pub fn default_location() -> Self {
+        let dir = dirs::cache_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("openchime")
+            .join("ics");
+        Self::new(dir)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The entry for `url` fetched with `auth`: a feed behind a login is
+    /// cached apart for each sign-in, so one never reads another's copy
+    fn key(url: &str, auth: Option<&FeedAuth>) -> String {
+        let mut identity = url.as_bytes().to_vec();
+        if let Some(auth) = auth {
+            if let Some((username, password)) = &auth.basic {
+                identity.extend_from_slice(format!("\0basic\0{}\0{}", username, password).as_bytes());
+            }
+            for (name, value) in &auth.headers {
+                identity.extend_from_slice(format!("\0header\0{}\0{}", name.to_ascii_lowercase(), value).as_bytes());
+            }
+        }
+        format!("{:016x}", fnv1a64(&identity))
+    }
+
+    fn paths(&self, url: &str, auth: Option<&FeedAuth>) -> (PathBuf, PathBuf) {
+        let key = Self::key(url, auth);
+        (self.dir.join(format!("{}.ics", key)), self.dir.join(format!("{}.json", key)))
+    }
+
+    /// Load the cached body for `url` fetched with `auth`, if any
+    pub fn get(&self, url: &str, auth: Option<&FeedAuth>) -> Option<CachedFeed> {
+        let (body_path, meta_path) = self.paths(url, auth);
+
+        let metadata: CacheMetadata = serde_json::from_str(&std::fs::read_to_string(meta_path).ok()?).ok()?;
+        let body = std::fs::read_to_string(body_path).ok()?;
+        Some(CachedFeed {
+            body,
+            fetched_at: metadata.fetched_at,
+            expires_at: metadata.expires_at,
+            validators: metadata.validators,
+        })
+    }
+
+    /// Store a freshly fetched body for `url` fetched with `auth`
+    pub fn put(
+        &self,
+        url: &str,
+        auth: Option<&FeedAuth>,
+        body: &str,
+        fetched_at: DateTime<Utc>,
+        expires_at: Option<DateTime<Utc>>,
+        validators: &FeedValidators,
+    ) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create cache directory {}", self.dir.display()))?;
+
+        let (body_path, meta_path) = self.paths(url, auth);
+        let metadata = CacheMetadata {
+            fetched_at,
+            expires_at,
+            validators: validators.clone(),
+        };
+
+        write_private(&body_path, body).context("Failed to write cached feed")?;
+        write_private(&meta_path, &serde_json::to_string(&metadata)?).context("Failed to write cache metadata")?;
+        Ok(())
+    }
+
+    /// Drop the cached body for `url` fetched with `auth` (e.g. when the
+    /// server sends `no-store`)
+    pub fn remove(&self, url: &str, auth: Option<&FeedAuth>) {
+        let (body_path, meta_path) = self.paths(url, auth);
+        let _ = std::fs::remove_file(body_path);
+        let _ = std::fs::remove_file(meta_path);
+    }
+}
+
+/// Write `contents` to `path` readable by the user alone: feeds hold the
+/// details of private meetings
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(contents.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+    use tempfile::TempDir;
+
+    fn headers(pairs: &[(reqwest::header::HeaderName, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(name.clone(), HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_cache_policy_max_age() {
+        let now = Utc::now();
+        let policy = cache_policy_from_headers(&headers(&[(CACHE_CONTROL, "public, max-age=600")]), now);
+        assert_eq!(policy, CachePolicy::Store { expires_at: Some(now + Duration::seconds(600)) });
+    }
+
+    #[test]
+    fn test_cache_policy_no_store_and_no_cache() {
+        let now = Utc::now();
+        assert_eq!(
+            cache_policy_from_headers(&headers(&[(CACHE_CONTROL, "private, no-store")]), now),
+            CachePolicy::NoStore
+        );
+        assert_eq!(
+            cache_policy_from_headers(&headers(&[(CACHE_CONTROL, "no-cache")]), now),
+            CachePolicy::Store { expires_at: Some(now) }
+        );
+    }
+
+    #[test]
+    fn test_cache_policy_expires_header() {
+        let now = Utc::now();
+        let policy = cache_policy_from_headers(&headers(&[(EXPIRES, "Wed, 21 Oct 2015 07:28:00 GMT")]), now);
+        let expected = DateTime::parse_from_rfc3339("2015-10-21T07:28:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(policy, CachePolicy::Store { expires_at: Some(expected) });
+
+        // max-age wins over Expires
+        let policy = cache_policy_from_headers(
+            &headers(&[(CACHE_CONTROL, "max-age=60"), (EXPIRES, "Wed, 21 Oct 2015 07:28:00 GMT")]),
+            now,
+        );
+        assert_eq!(policy, CachePolicy::Store { expires_at: Some(now + Duration::seconds(60)) });
+
+        // Nothing advertised: store for fallback only
+        assert_eq!(cache_policy_from_headers(&HeaderMap::new(), now), CachePolicy::Store { expires_at: None });
+    }
+
+    #[test]
+    fn test_validators_from_headers() {
+        let validators = validators_from_headers(&headers(&[(ETAG, "W/\"abc\""), (LAST_MODIFIED, "Wed, 21 Oct 2015 07:28:00 GMT")]));
+        assert_eq!(validators.etag.as_deref(), Some("W/\"abc\""));
+        assert_eq!(validators.last_modified.as_deref(), Some("Wed, 21 Oct 2015 07:28:00 GMT"));
+        assert!(validators_from_headers(&HeaderMap::new()).is_empty());
+    }
+
+    #[test]
+    fn test_put_get_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let cache = IcsCache::new(dir.path());
+        let url = "https://calendar.example.com/feed.ics";
+        let now = Utc::now();
+
+        assert!(cache.get(url, None).is_none());
+
+        let validators = FeedValidators { etag: Some("\"v1\"".to_string()), last_modified: None };
+        cache.put(url, None, "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n", now, Some(now + Duration::minutes(5)), &validators).unwrap();
+        let cached = cache.get(url, None).unwrap();
+        assert!(cached.body.starts_with("BEGIN:VCALENDAR"));
+        assert_eq!(cached.validators, validators);
+        assert!(cached.is_fresh(now));
+        assert!(!cached.is_fresh(now + Duration::minutes(6)));
+        assert!(cached.is_usable_fallback(now + Duration::days(1)));
+        assert!(!cached.is_usable_fallback(now + Duration::days(MAX_STALE_AGE_DAYS + 1)));
+
+        assert!(cache.get("https://calendar.example.com/other.ics", None).is_none());
+
+        // Only the hash of the URL is written to disk, for the user alone
+        let (body_path, meta_path) = cache.paths(url, None);
+        assert!(!std::fs::read_to_string(&meta_path).unwrap().contains("calendar.example.com"));
+        #[cfg(unix)]
+        for path in [&body_path, &meta_path] {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        cache.remove(url, None);
+        assert!(cache.get(url, None).is_none());
+    }
+
+    #[test]
+    fn test_each_sign_in_has_its_own_entry() {
+        let dir = TempDir::new().unwrap();
+        let cache = IcsCache::new(dir.path());
+        let url = "https://dav.example.com/team.ics";
+        let alice = FeedAuth { basic: Some(("alice".to_string(), "s3cret".to_string())), headers: Vec::new() };
+        let bob = FeedAuth { basic: Some(("bob".to_string(), "hunter2".to_string())), headers: Vec::new() };
+        let token = FeedAuth { basic: None, headers: vec![("X-Api-Key".to_string(), "abc123".to_string())] };
+        let now = Utc::now();
+
+        cache.put(url, Some(&alice), "BEGIN:VCALENDAR\r\nX-WR-CALNAME:Alice\r\nEND:VCALENDAR\r\n", now, None, &FeedValidators::default()).unwrap();
+        assert!(cache.get(url, Some(&alice)).unwrap().body.contains("Alice"));
+        assert!(cache.get(url, Some(&bob)).is_none());
+        assert!(cache.get(url, Some(&token)).is_none());
+        assert!(cache.get(url, None).is_none());
+    }
+
+    #[test]
+    fn test_entry_without_lifetime_is_never_fresh() {
+        let cached = CachedFeed { body: String::new(), fetched_at: Utc::now(), expires_at: None, validators: FeedValidators::default() };
+        assert!(!cached.is_fresh(Utc::now()));
+    }
+}
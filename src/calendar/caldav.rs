@@ -0,0 +1,574 @@
+// file: src/calendar/caldav.rs
+//! CalDAV calendars (Nextcloud, Radicale, Fastmail, ...) signed in with a
+//! username and app password, for calendars that aren't published as a
+//! public ICS feed.
+//!
+//! The account's `auth_data` is the calendar collection URL; it signs in
+//! with HTTP Basic auth like any feed behind a login, its `username` on the
+//! account and the app password in the keyring. Each sync asks the
+//! collection for the events in the sync window with a `calendar-query`
+//! REPORT and hands the calendar data to the usual ICS pipeline.
+//!
+//! Given just the server's hostname, `discover_calendars` finds the
+//! collections to choose from the RFC 6764 way: `/.well-known/caldav` leads
+//! to the user's principal, the principal to their calendar home, and the
+//! home lists the calendars.
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use quick_xml::events::{BytesStart, Event};
+use sqlx::SqlitePool;
+use std::future::Future;
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+use crate::calendar::common::{read_body_limited, sync_from_source_with, SyncMode, SyncWindow};
+use crate::calendar::source::{CalendarSource, FeedAuth};
+use crate::http_config::{HttpClientFactory, HttpConfig};
+use crate::models::{Account, SyncResult};
+use crate::utils;
+
+/// Username and app password for a CalDAV account
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalDavCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+/// A CalDAV calendar collection, read with a `calendar-query` REPORT
+#[derive(Debug, Clone)]
+pub struct CalDavSource {
+    pub url: String,
+    /// The sign-in, and any extra request headers stored on the account
+    pub auth: FeedAuth,
+    /// Only events overlapping this are asked for
+    pub window: SyncWindow,
+    /// Aborts an in-flight fetch (e.g. on shutdown)
+    pub cancel: CancellationToken,
+}
+
+impl CalDavSource {
+    pub async fn from_account(account: &Account, cancel: CancellationToken) -> Result<Self> {
+        let auth = FeedAuth::from_account(account)
+            .await?
+            .filter(|auth| auth.basic.is_some())
+            .ok_or_else(|| anyhow!("No CalDAV credentials stored for {}", account.account_name))?;
+        Ok(Self {
+            url: account.auth_data.clone(),
+            auth,
+            window: SyncWindow::around(Utc::now()),
+            cancel,
+        })
+    }
+
+    /// Ask for events overlapping `window` rather than the default one
+    pub fn with_window(mut self, window: SyncWindow) -> Self {
+        self.window = window;
+        self
+    }
+}
+
+impl CalendarSource for CalDavSource {
+    fn id_prefix(&self) -> &str {
+        "caldav"
+    }
+
+    fn fetch_ics(&self) -> impl Future<Output = Result<String>> + Send {
+        let source = self.clone();
+        async move {
+            let client = HttpClientFactory::shared_ics_client().map_err(|e| anyhow!("Failed to build client: {}", e))?;
+            let http_config = HttpConfig::ics_fetch();
+            let max_bytes = http_config.max_response_bytes;
+            let body = calendar_query(source.window);
+
+            utils::retry::retry_with_cancellation(&http_config.to_retry_config(), &source.cancel, || {
+                let client = client.clone();
+                let source = source.clone();
+                let body = body.clone();
+                async move {
+                    utils::rate_limiter::acquire_for_url(&source.url).await;
+
+                    let report = reqwest::Method::from_bytes(b"REPORT").expect("valid method");
+                    let response = source
+                        .auth
+                        .apply(client.request(report, &source.url))
+                        .header("Depth", "1")
+                        .header(reqwest::header::CONTENT_TYPE, "application/xml; charset=utf-8")
+                        .body(body)
+                        .send()
+                        .await
+                        .map_err(|e| anyhow!("Request failed: {}", e))?;
+
+                    match response.status().as_u16() {
+                        401 | 403 => return Err(anyhow!("The CalDAV server rejected the username or app password")),
+                        404 => return Err(anyhow!("No calendar found at {}; check the calendar's CalDAV URL", source.url)),
+                        status if status != 207 && !(200..300).contains(&status) => {
+                            let retry_after = response
+                                .headers()
+                                .get(reqwest::header::RETRY_AFTER)
+                                .and_then(|v| v.to_str().ok())
+                                .and_then(|v| utils::retry::parse_retry_after(v, Utc::now()));
+                            let text = response.text().await.unwrap_or_default();
+                            return Err(utils::retry::HttpStatusError { status, retry_after, body: text }.into());
+                        }
+                        _ => {}
+                    }
+
+                    let multistatus = read_body_limited(response, max_bytes).await?;
+                    calendar_from_multistatus(&multistatus)
+                }
+            })
+            .await
+        }
+    }
+}
+
+/// The REPORT body asking for every event overlapping the sync window
+fn calendar_query(window: SyncWindow) -> String {
+    let format = |t: chrono::DateTime<Utc>| t.format("%Y%m%dT%H%M%SZ").to_string();
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop><c:calendar-data/></d:prop>
+  <c:filter>
+    <c:comp-filter name="VCALENDAR">
+      <c:comp-filter name="VEVENT">
+        <c:time-range start="{}" end="{}"/>
+      </c:comp-filter>
+    </c:comp-filter>
+  </c:filter>
+</c:calendar-query>"#,
+        format(window.start),
+        format(window.end)
+    )
+}
+
+/// One ICS document holding the calendar data of every resource in a
+/// REPORT response. Each resource is a whole VCALENDAR of its own; their
+/// contents (events and the timezones they use) go into a single one.
+pub fn calendar_from_multistatus(xml: &str) -> Result<String> {
+    let multistatus = XmlElement::parse(xml)?;
+    let mut calendar = String::from("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//OpenChime//CalDAV//EN\r\n");
+    for data in multistatus.descendants("calendar-data") {
+        for line in data.text.lines().map(|l| l.trim_end_matches('\r')) {
+            if matches!(line, "BEGIN:VCALENDAR" | "END:VCALENDAR") || line.starts_with("VERSION:") || line.starts_with("PRODID:") {
+                continue;
+            }
+            if !line.trim().is_empty() {
+                calendar.push_str(line);
+                calendar.push_str("\r\n");
+            }
+        }
+    }
+    calendar.push_str("END:VCALENDAR\r\n");
+    Ok(calendar)
+}
+
+/// An element of a WebDAV response, named without its namespace prefix
+/// (servers pick their own: `d:`, `D:`, `cal:`, none)
+#[derive(Debug, Default)]
+struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlElement>,
+    /// Its text and CDATA, with entity and character references resolved
+    text: String,
+}
+
+impl XmlElement {
+    /// The root element of `xml`
+    fn parse(xml: &str) -> Result<Self> {
+        let unreadable = |e: &dyn std::fmt::Display| anyhow!("Unreadable response from the CalDAV server: {}", e);
+        let mut reader = quick_xml::Reader::from_str(xml);
+        // The document itself, then the elements open inside it
+        let mut open = vec![XmlElement::default()];
+        loop {
+            let event = reader.read_event().map_err(|e| unreadable(&e))?;
+            let current = open.last_mut().ok_or_else(|| unreadable(&"closing tag without an opening one"))?;
+            match event {
+                Event::Start(start) => {
+                    let element = Self::from_tag(&start).map_err(|e| unreadable(&e))?;
+                    open.push(element);
+                }
+                Event::Empty(start) => {
+                    let element = Self::from_tag(&start).map_err(|e| unreadable(&e))?;
+                    current.children.push(element);
+                }
+                Event::End(_) => {
+                    let element = open.pop().filter(|_| !open.is_empty()).ok_or_else(|| unreadable(&"unbalanced tags"))?;
+                    if let Some(parent) = open.last_mut() {
+                        parent.children.push(element);
+                    }
+                }
+                Event::Text(text) => current.text.push_str(&text.xml10_content().map_err(|e| unreadable(&e))?),
+                Event::CData(data) => current.text.push_str(&data.decode().map_err(|e| unreadable(&e))?),
+                Event::GeneralRef(reference) => match reference.resolve_char_ref().map_err(|e| unreadable(&e))? {
+                    Some(c) => current.text.push(c),
+                    None => {
+                        let name = reference.decode().map_err(|e| unreadable(&e))?;
+                        let resolved = quick_xml::escape::resolve_predefined_entity(&name)
+                            .ok_or_else(|| unreadable(&format!("unknown entity &{};", name)))?;
+                        current.text.push_str(resolved);
+                    }
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        match open.pop() {
+            Some(document) if open.is_empty() => document.children.into_iter().next().ok_or_else(|| unreadable(&"no root element")),
+            _ => Err(unreadable(&"unclosed tags")),
+        }
+    }
+
+    fn from_tag(tag: &BytesStart) -> std::result::Result<Self, quick_xml::Error> {
+        let local = |name: &[u8]| String::from_utf8_lossy(name).into_owned();
+        let mut attributes = Vec::new();
+        for attribute in tag.attributes() {
+            let attribute = attribute.map_err(quick_xml::Error::from)?;
+            let value = attribute.normalized_value(quick_xml::XmlVersion::Implicit1_0)?;
+            attributes.push((local(attribute.key.local_name().as_ref()), value.into_owned()));
+        }
+        Ok(Self { name: local(tag.local_name().as_ref()), attributes, ..Default::default() })
+    }
+
+    /// The first child called `name`
+    fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// The children called `name`
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> + 'a {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Every element called `name` inside this one, in document order
+    fn descendants<'a>(&'a self, name: &'a str) -> Box<dyn Iterator<Item = &'a XmlElement> + 'a> {
+        Box::new(self.children.iter().flat_map(move |child| {
+            let this = (child.name == name).then_some(child);
+            this.into_iter().chain(child.descendants(name))
+        }))
+    }
+}
+
+/// A calendar collection found on a CalDAV server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredCalendar {
+    /// The collection URL, what a CalDAV account's `auth_data` holds
+    pub url: String,
+    /// Its display name, or the last part of its path when it has none
+    pub name: String,
+}
+
+/// Where discovery starts for a server given by hostname alone
+/// (`cloud.example.com`, optionally with a scheme or port). `None` for a
+/// URL with a path, which is taken to be the calendar itself.
+pub fn well_known_url(server: &str) -> Option<String> {
+    let server = server.trim();
+    if server.is_empty() || server.contains(char::is_whitespace) {
+        return None;
+    }
+    let with_scheme = if server.contains("://") { server.to_string() } else { format!("https://{}", server) };
+    let mut url = Url::parse(&with_scheme).ok()?;
+    if !matches!(url.scheme(), "https" | "http") || url.host_str().is_none() || url.path() != "/" || url.query().is_some() {
+        return None;
+    }
+    // Credentials only ever go over TLS
+    url.set_scheme("https").ok()?;
+    url.set_path("/.well-known/caldav");
+    Some(url.to_string())
+}
+
+/// The calendars on `server` that hold events, found from its hostname
+pub async fn discover_calendars(server: &str, credentials: &CalDavCredentials) -> Result<Vec<DiscoveredCalendar>> {
+    let start = well_known_url(server).ok_or_else(|| anyhow!("Enter just the server's hostname, e.g. cloud.example.com"))?;
+    let client = HttpClientFactory::shared_ics_client().map_err(|e| anyhow!("Failed to build client: {}", e))?;
+    let not_found = || anyhow!("Couldn't find calendars on {}; paste the calendar's CalDAV URL instead", server.trim());
+
+    let (base, xml) = propfind(&client, &start, credentials, "0", "<d:current-user-principal/>").await?;
+    let principal = href_in(&xml, "current-user-principal")?.and_then(|href| base.join(&href).ok()).ok_or_else(not_found)?;
+
+    let (base, xml) = propfind(&client, principal.as_str(), credentials, "0", "<c:calendar-home-set/>").await?;
+    let home = href_in(&xml, "calendar-home-set")?.and_then(|href| base.join(&href).ok()).ok_or_else(not_found)?;
+
+    let props = "<d:resourcetype/><d:displayname/><c:supported-calendar-component-set/>";
+    let (base, xml) = propfind(&client, home.as_str(), credentials, "1", props).await?;
+    calendars_in_multistatus(&base, &xml)
+}
+
+/// A PROPFIND for `props`, returning the multistatus and the URL it came
+/// from (to resolve its hrefs against)
+async fn propfind(client: &reqwest::Client, url: &str, credentials: &CalDavCredentials, depth: &str, props: &str) -> Result<(Url, String)> {
+    let body = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop>{}</d:prop>
+</d:propfind>"#,
+        props
+    );
+
+    let mut target = url.to_string();
+    // A 301/302 is followed as a GET; ask again where it led
+    for _ in 0..2 {
+        utils::rate_limiter::acquire_for_url(&target).await;
+        let method = reqwest::Method::from_bytes(b"PROPFIND").expect("valid method");
+        let response = client
+            .request(method, &target)
+            .basic_auth(&credentials.username, Some(&credentials.password))
+            .header("Depth", depth)
+            .header(reqwest::header::CONTENT_TYPE, "application/xml; charset=utf-8")
+            .body(body.clone())
+            .send()
+            .await
+            .map_err(|e| anyhow!("Request failed: {}", e))?;
+        let landed = response.url().clone();
+
+        match response.status().as_u16() {
+            207 => return Ok((landed, read_body_limited(response, HttpConfig::ics_fetch().max_response_bytes).await?)),
+            401 | 403 => return Err(anyhow!("The CalDAV server rejected the username or app password")),
+            _ if landed.as_str() != target => target = landed.to_string(),
+            status => return Err(anyhow!("HTTP {} from {}", status, target)),
+        }
+    }
+    Err(anyhow!("Too many redirects from {}", url))
+}
+
+/// The href inside the first `prop` element (`current-user-principal`,
+/// `calendar-home-set`) of a multistatus
+fn href_in(xml: &str, prop: &str) -> Result<Option<String>> {
+    let multistatus = XmlElement::parse(xml)?;
+    let href = multistatus.descendants(prop).next().and_then(|prop| prop.child("href")).map(|href| href.text.trim());
+    Ok(href.filter(|href| !href.is_empty()).map(str::to_string))
+}
+
+/// The event calendars listed in a calendar home's multistatus. Other
+/// collections (the home itself, task lists, address books) are left out.
+pub fn calendars_in_multistatus(base: &Url, xml: &str) -> Result<Vec<DiscoveredCalendar>> {
+    let multistatus = XmlElement::parse(xml)?;
+    let mut calendars: Vec<DiscoveredCalendar> = Vec::new();
+    for response in multistatus.children_named("response") {
+        let Some(url) = response.child("href").and_then(|href| base.join(href.text.trim()).ok()) else {
+            continue;
+        };
+        // Properties the server couldn't give come back under a non-200 status
+        let props: Vec<&XmlElement> = response
+            .children_named("propstat")
+            .filter(|propstat| propstat.child("status").is_none_or(|status| status.text.contains(" 200 ")))
+            .filter_map(|propstat| propstat.child("prop"))
+            .collect();
+        let prop = |name: &str| props.iter().find_map(|prop| prop.child(name));
+
+        let is_calendar = prop("resourcetype").is_some_and(|types| types.child("calendar").is_some());
+        // Servers that don't say which components a calendar holds allow all
+        let has_events = prop("supported-calendar-component-set").is_none_or(|components| {
+            components
+                .children_named("comp")
+                .any(|comp| comp.attribute("name").is_some_and(|name| name.eq_ignore_ascii_case("VEVENT")))
+        });
+        if !is_calendar || !has_events || calendars.iter().any(|c| c.url == url.as_str()) {
+            continue;
+        }
+
+        let name = prop("displayname")
+            .map(|name| name.text.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .or_else(|| url.path_segments()?.rfind(|s| !s.is_empty()).map(|s| s.to_string()))
+            .unwrap_or_else(|| url.to_string());
+        calendars.push(DiscoveredCalendar { url: url.to_string(), name });
+    }
+    Ok(calendars)
+}
+
+pub async fn sync_caldav_calendar(account: &Account, db: &SqlitePool, cancel: &CancellationToken) -> Result<SyncResult> {
+    sync_caldav_calendar_with(account, db, cancel, SyncMode::Apply).await
+}
+
+/// `sync_caldav_calendar` in either mode
+pub async fn sync_caldav_calendar_with(account: &Account, db: &SqlitePool, cancel: &CancellationToken, mode: SyncMode) -> Result<SyncResult> {
+    let settings = crate::database::settings::get(db).await?;
+    let source = CalDavSource::from_account(account, cancel.clone()).await?.with_window(SyncWindow::from_settings(&settings, Utc::now()));
+    sync_from_source_with(&source, account, db, mode).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_credentials_are_basic_auth() {
+        let credentials = CalDavCredentials { username: "alice".to_string(), password: "app-pass \"1\"".to_string() };
+        let account = Account::new_caldav(
+            "Nextcloud".to_string(),
+            "https://cloud.example.com/remote.php/dav/calendars/alice/personal/".to_string(),
+            &credentials,
+        );
+        assert_eq!(account.provider, "caldav");
+        let source = CalDavSource::from_account(&account, CancellationToken::new()).await.unwrap();
+        assert_eq!(source.auth.basic, Some((credentials.username, credentials.password)));
+
+        let signed_out = Account { username: None, password: None, ..account };
+        assert!(CalDavSource::from_account(&signed_out, CancellationToken::new()).await.is_err());
+    }
+
+    #[test]
+    fn test_calendar_from_multistatus() {
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav">
+  <d:response>
+    <d:href>/calendars/alice/personal/standup.ics</d:href>
+    <d:propstat><d:prop><cal:calendar-data>BEGIN:VCALENDAR&#13;
+VERSION:2.0&#13;
+PRODID:-//Nextcloud//EN&#13;
+BEGIN:VEVENT&#13;
+UID:standup&#13;
+SUMMARY:Standup &amp; planning&#13;
+DTSTART:20240304T090000Z&#13;
+END:VEVENT&#13;
+END:VCALENDAR&#13;
+</cal:calendar-data></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/calendars/alice/personal/review.ics</d:href>
+    <d:propstat><d:prop><C:calendar-data xmlns:C="urn:ietf:params:xml:ns:caldav"><![CDATA[BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:review
+SUMMARY:Review <draft>
+DTSTART:20240305T140000Z
+END:VEVENT
+END:VCALENDAR]]></C:calendar-data></d:prop></d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+        let calendar = calendar_from_multistatus(xml).unwrap();
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(calendar.matches("BEGIN:VCALENDAR").count(), 1);
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 2);
+        assert!(calendar.contains("SUMMARY:Standup & planning\r\n"));
+        assert!(calendar.contains("SUMMARY:Review <draft>\r\n"));
+        assert!(!calendar.contains("Nextcloud"));
+
+        assert!(calendar_from_multistatus("<d:multistatus xmlns:d=\"DAV:\"><d:response>").is_err());
+    }
+
+    #[test]
+    fn test_empty_calendar_data_does_not_swallow_the_next_response() {
+        let xml = r#"<d:multistatus xmlns:d="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <d:response>
+    <d:href>/calendars/alice/personal/deleted.ics</d:href>
+    <d:propstat><d:prop><C:calendar-data/></d:prop><d:status>HTTP/1.1 404 Not Found</d:status></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/calendars/alice/personal/lunch.ics</d:href>
+    <d:propstat><d:prop><C:calendar-data><![CDATA[BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:lunch
+SUMMARY:Lunch & learn <b>
+DTSTART:20240306T120000Z
+END:VEVENT
+END:VCALENDAR]]></C:calendar-data></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+        let calendar = calendar_from_multistatus(xml).unwrap();
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 1);
+        assert!(calendar.contains("UID:lunch\r\n"));
+        assert!(calendar.contains("SUMMARY:Lunch & learn <b>\r\n"));
+        assert!(!calendar.contains("deleted"));
+    }
+
+    #[test]
+    fn test_calendar_query_window() {
+        let now = chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 3, 4, 12, 0, 0).unwrap();
+        let query = calendar_query(SyncWindow::around(now));
+        assert!(query.contains(r#"<c:time-range start="20240203T120000Z" end="20250304T120000Z"/>"#));
+    }
+
+    #[test]
+    fn test_well_known_url() {
+        assert_eq!(well_known_url("cloud.example.com").as_deref(), Some("https://cloud.example.com/.well-known/caldav"));
+        assert_eq!(well_known_url(" http://cloud.example.com:8443/ ").as_deref(), Some("https://cloud.example.com:8443/.well-known/caldav"));
+        assert_eq!(well_known_url("https://cloud.example.com/remote.php/dav/calendars/alice/personal/"), None);
+        assert_eq!(well_known_url("my calendar"), None);
+        assert_eq!(well_known_url(""), None);
+    }
+
+    #[test]
+    fn test_href_in() {
+        let xml = r#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>/.well-known/caldav</d:href>
+            <d:propstat><d:prop><d:current-user-principal>
+              <d:href>/principals/users/alice%40example.com/</d:href>
+            </d:current-user-principal></d:prop></d:propstat></d:response></d:multistatus>"#;
+        assert_eq!(href_in(xml, "current-user-principal").unwrap().as_deref(), Some("/principals/users/alice%40example.com/"));
+        assert_eq!(href_in(xml, "calendar-home-set").unwrap(), None);
+    }
+
+    #[test]
+    fn test_calendars_in_multistatus() {
+        let base = Url::parse("https://cloud.example.com/remote.php/dav/calendars/alice/").unwrap();
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav" xmlns:cs="http://calendarserver.org/ns/">
+  <d:response>
+    <d:href>/remote.php/dav/calendars/alice/</d:href>
+    <d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/remote.php/dav/calendars/alice/personal/</d:href>
+    <d:propstat><d:prop>
+      <d:resourcetype><d:collection/><cal:calendar/></d:resourcetype>
+      <d:displayname>Work &amp; life</d:displayname>
+      <cal:supported-calendar-component-set><cal:comp name="VEVENT"/><cal:comp name="VTODO"/></cal:supported-calendar-component-set>
+    </d:prop></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/remote.php/dav/calendars/alice/tasks/</d:href>
+    <d:propstat><d:prop>
+      <d:resourcetype><d:collection/><cal:calendar/></d:resourcetype>
+      <d:displayname>Tasks</d:displayname>
+      <cal:supported-calendar-component-set><cal:comp name="VTODO"/></cal:supported-calendar-component-set>
+    </d:prop></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/remote.php/dav/calendars/alice/inbox/</d:href>
+    <d:propstat><d:prop><d:resourcetype><d:collection/><cal:schedule-inbox/></d:resourcetype></d:prop></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>family/</d:href>
+    <d:propstat><d:prop><d:resourcetype><d:collection/><C:calendar xmlns:C="urn:ietf:params:xml:ns:caldav" /></d:resourcetype><d:displayname/></d:prop></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/remote.php/dav/calendars/alice/team/</d:href>
+    <d:propstat>
+      <d:prop><d:resourcetype><d:collection/><cal:calendar/></d:resourcetype><d:displayname>Team</d:displayname></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+    <d:propstat>
+      <d:prop><cal:supported-calendar-component-set/></d:prop>
+      <d:status>HTTP/1.1 404 Not Found</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+        assert_eq!(
+            calendars_in_multistatus(&base, xml).unwrap(),
+            vec![
+                DiscoveredCalendar {
+                    url: "https://cloud.example.com/remote.php/dav/calendars/alice/personal/".to_string(),
+                    name: "Work & life".to_string(),
+                },
+                DiscoveredCalendar {
+                    url: "https://cloud.example.com/remote.php/dav/calendars/alice/family/".to_string(),
+                    name: "family".to_string(),
+                },
+                DiscoveredCalendar {
+                    url: "https://cloud.example.com/remote.php/dav/calendars/alice/team/".to_string(),
+                    name: "Team".to_string(),
+                },
+            ]
+        );
+    }
+}
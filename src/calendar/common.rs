@@ -1,15 +1,127 @@
 #![allow(dead_code)]
 use crate::utils::circuit_breaker::get_circuit_breaker;
-use crate::utils::retry::RetryConfig;
+use crate::calendar::cache::{cache_policy_from_headers, validators_from_headers, CachePolicy, IcsCache};
+use crate::calendar::ics_stream;
+use crate::calendar::recurrence::{self, RawProperty, RecurrenceRule};
+use crate::calendar::source::{CalendarSource, FeedAuth, FeedFetch, IcsUrlSource};
+use crate::calendar::vtimezone::{self, VTimezone};
+use crate::http_config::{HttpClientFactory, HttpConfig};
+use crate::models::{Account, Attendee, CalendarEvent, EventChanges, EventId, FeedValidators, Rsvp, SyncResult, VideoMeetingInfo, MAX_CALENDAR_REMINDER_MINUTES};
 use crate::utils;
+use crate::utils::logging;
 use anyhow::{anyhow, Result};
-use chrono::{DateTime, Utc, TimeZone, Datelike};
+use chrono::{DateTime, Duration, Utc, TimeZone};
+use icalendar::{Calendar as IcsCalendar, Component, Event as IcsEvent, EventLike};
 use reqwest::Client;
+use sqlx::{FromRow, Row, SqlitePool};
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
 use url::Url;
 
-/// Validates an ICS URL for security and format correctness
+/// The `https://` form of a feed URL. Calendar apps hand out subscriptions
+/// as `webcal://` (or `webcals://`), which is plain HTTPS underneath, and
+/// an Outlook published-calendar page becomes its ICS feed.
+pub fn normalize_feed_url(url: &str) -> String {
+    let url = url.trim();
+    for scheme in ["webcals://", "webcal://"] {
+        if url.len() >= scheme.len() && url[..scheme.len()].eq_ignore_ascii_case(scheme) {
+            return format!("https://{}", &url[scheme.len()..]);
+        }
+    }
+    outlook_feed_url(url).or_else(|| google_embed_feed_url(url)).unwrap_or_else(|| url.to_string())
+}
+
+/// Outlook hosts on which calendars are published
+const OUTLOOK_HOSTS: [&str; 3] = ["outlook.office365.com", "outlook.live.com", "outlook.office.com"];
+
+/// The ICS feed behind an Outlook published-calendar page. Publishing a
+/// calendar gives an HTML link (`.../calendar.html`) and an ICS link
+/// (`.../calendar.ics`) that differ only in the extension, and the HTML
+/// one is the one people tend to copy.
+pub fn outlook_feed_url(url: &str) -> Option<String> {
+    let mut parsed = Url::parse(url.trim()).ok()?;
+    let host = parsed.host_str()?.to_ascii_lowercase();
+    if !OUTLOOK_HOSTS.contains(&host.as_str()) || !parsed.path().to_ascii_lowercase().starts_with("/owa/calendar/") {
+        return None;
+    }
+    let path = parsed.path();
+    let page = path.len().checked_sub(".html".len()).filter(|&at| path[at..].eq_ignore_ascii_case(".html"))?;
+    let feed = format!("{}.ics", &path[..page]);
+    parsed.set_path(&feed);
+    Some(parsed.to_string())
+}
+
+/// Where Google Calendar lives, and serves its feeds from
+const GOOGLE_CALENDAR_HOST: &str = "calendar.google.com";
+
+/// How to find a Google calendar's private feed, for the errors below
+const GOOGLE_SECRET_ADDRESS_STEPS: &str = "In Google Calendar open Settings, pick the calendar under \
+    'Settings for my calendars' and copy 'Secret address in iCal format' from 'Integrate calendar'";
+
+/// The public feed for a Google Calendar embed link
+/// (`calendar.google.com/calendar/embed?src=<calendar id>`, or the older
+/// `htmlembed`). An embedded calendar is a public one, so its public feed
+/// shows the same events.
+pub fn google_embed_feed_url(url: &str) -> Option<String> {
+    let url = Url::parse(url.trim()).ok()?;
+    let path = url.path();
+    if url.host_str() != Some(GOOGLE_CALENDAR_HOST) || !(path.contains("/embed") || path.contains("/htmlembed")) {
+        return None;
+    }
+    let (_, calendar_id) = url.query_pairs().find(|(key, _)| key == "src")?;
+    let mut feed = Url::parse("https://calendar.google.com/calendar/ical/").ok()?;
+    feed.path_segments_mut().ok()?.pop_if_empty().extend([calendar_id.as_ref(), "public", "basic.ics"]);
+    Some(feed.to_string())
+}
+
+/// What's wrong with a Google Calendar URL that isn't one of its feeds
+/// (`/calendar/ical/<calendar id>/<public or private-key>/basic.ics`):
+/// the address of the web app, a sharing link, or a feed address cut short
+/// or in its HTML form
+fn google_url_problem(url: &Url) -> Option<String> {
+    if url.host_str() != Some(GOOGLE_CALENDAR_HOST) {
+        return None;
+    }
+    let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+    match segments.as_slice() {
+        ["calendar", "ical", _, access, file] if *access == "public" || access.starts_with("private") => {
+            (!file.to_ascii_lowercase().ends_with(".ics")).then(|| {
+                format!("This Google Calendar address is for viewing the calendar, not syncing it; use the iCal version, which ends in .ics. {}.", GOOGLE_SECRET_ADDRESS_STEPS)
+            })
+        }
+        ["calendar", "ical", ..] => Some(format!("This Google Calendar address is incomplete. {}.", GOOGLE_SECRET_ADDRESS_STEPS)),
+        _ if url.query_pairs().any(|(key, _)| key == "cid") => Some(format!(
+            "This is a Google Calendar sharing link, which adds the calendar in Google rather than handing out a feed. {}.",
+            GOOGLE_SECRET_ADDRESS_STEPS
+        )),
+        _ => Some(format!("This is the address of the Google Calendar website, not a calendar feed. {}.", GOOGLE_SECRET_ADDRESS_STEPS)),
+    }
+}
+
+/// Why a Google feed that wasn't found is probably missing: public
+/// addresses only work for calendars made public, and most aren't
+pub fn google_public_feed_hint(url: &str) -> Option<String> {
+    let url = Url::parse(url.trim()).ok()?;
+    let segments: Vec<&str> = url.path_segments()?.collect();
+    (url.host_str() == Some(GOOGLE_CALENDAR_HOST) && matches!(segments.as_slice(), ["calendar", "ical", _, "public", _])).then(|| {
+        format!(
+            "this is the calendar's public address, which only works once the calendar is made public. {} instead.",
+            GOOGLE_SECRET_ADDRESS_STEPS
+        )
+    })
+}
+
+/// Validates an ICS URL for security and format correctness.
+/// `webcal://` URLs are checked as the `https://` URLs they stand for.
 pub fn validate_ics_url_format(ics_url: &str) -> Result<()> {
+    let normalized = normalize_feed_url(ics_url);
+    let ics_url = normalized.as_str();
+
     // Check for empty or whitespace-only URL
     if ics_url.trim().is_empty() {
         return Err(anyhow!(
@@ -59,6 +171,11 @@ pub fn validate_ics_url_format(ics_url: &str) -> Result<()> {
         ));
     }
 
+    // Google's web app and sharing links are the usual wrong copy
+    if let Some(problem) = google_url_problem(&parsed_url) {
+        return Err(anyhow!(problem));
+    }
+
     // Validate that a path is present (ICS URLs should have a path component)
     let path = parsed_url.path();
     if path.is_empty() || path == "/" {
@@ -79,115 +196,1492 @@ pub fn validate_ics_url_format(ics_url: &str) -> Result<()> {
     Ok(())
 }
 
-/// Fetch ICS data from URL with retry logic and circuit breaker
-pub async fn fetch_ics_data(ics_url: &str, circuit_breaker_name: &str) -> Result<String> {
-    let retry_config = RetryConfig {
-        max_attempts: 3,
-        base_delay: std::time::Duration::from_millis(1000),
-        max_delay: std::time::Duration::from_secs(20),
-        backoff_multiplier: 2.0,
+/// Fetch ICS data from URL with retry logic, circuit breaker and on-disk cache
+///
+/// Uses the shared ICS client and retry policy from `HttpConfig::ics_fetch()`.
+/// A cached copy is returned without a request while it is still fresh, and
+/// as a fallback (up to `cache::MAX_STALE_AGE_DAYS` old) when the fetch fails.
+pub async fn fetch_ics_data(ics_url: &str, circuit_breaker_name: &str, auth: Option<&FeedAuth>) -> Result<String> {
+    fetch_ics_data_cancellable(ics_url, circuit_breaker_name, auth, &CancellationToken::new()).await
+}
+
+/// `fetch_ics_data` that aborts (even mid-download) once `cancel` fires
+pub async fn fetch_ics_data_cancellable(
+    ics_url: &str,
+    circuit_breaker_name: &str,
+    auth: Option<&FeedAuth>,
+    cancel: &CancellationToken,
+) -> Result<String> {
+    fetch_ics_data_if_modified(ics_url, circuit_breaker_name, auth, &IcsCache::default_location(), cancel, None).await?.into_body()
+}
+
+/// `fetch_ics_data_cancellable` with the feed kept in `cache`, that asks the
+/// server to skip the download when the feed hasn't changed since the
+/// response `validators` came from
+pub async fn fetch_ics_data_if_modified(
+    ics_url: &str,
+    circuit_breaker_name: &str,
+    auth: Option<&FeedAuth>,
+    cache: &IcsCache,
+    cancel: &CancellationToken,
+    validators: Option<&FeedValidators>,
+) -> Result<FeedFetch> {
+    let client = HttpClientFactory::shared_ics_client()
+        .map_err(|e| anyhow!("Failed to build client: {}", e))?;
+    fetch_ics_data_cached_if_modified(&client, cache, ics_url, circuit_breaker_name, auth, cancel, validators).await
+}
+
+/// Cache-aware fetch with a caller-supplied client and cache
+pub async fn fetch_ics_data_cached(
+    client: &Client,
+    cache: &IcsCache,
+    ics_url: &str,
+    circuit_breaker_name: &str,
+    auth: Option<&FeedAuth>,
+    cancel: &CancellationToken,
+) -> Result<String> {
+    fetch_ics_data_cached_if_modified(client, cache, ics_url, circuit_breaker_name, auth, cancel, None).await?.into_body()
+}
+
+/// Cache-aware conditional fetch. A fresh cached copy carrying the same
+/// validators counts as unchanged too.
+pub async fn fetch_ics_data_cached_if_modified(
+    client: &Client,
+    cache: &IcsCache,
+    ics_url: &str,
+    circuit_breaker_name: &str,
+    auth: Option<&FeedAuth>,
+    cancel: &CancellationToken,
+    validators: Option<&FeedValidators>,
+) -> Result<FeedFetch> {
+    let now = Utc::now();
+    let cached = cache.get(ics_url, auth);
+    let validators = validators.filter(|v| !v.is_empty());
+
+    if let Some(cached) = cached.as_ref().filter(|c| c.is_fresh(now)) {
+        log::debug!("Using cached ICS feed (fetched {}, fresh until {:?})", cached.fetched_at, cached.expires_at);
+        if validators == Some(&cached.validators) {
+            return Ok(FeedFetch::NotModified);
+        }
+        return Ok(FeedFetch::Modified { body: cached.body.clone(), validators: cached.validators.clone() });
+    }
+
+    match fetch_ics_response(client, ics_url, circuit_breaker_name, auth, cancel, validators).await {
+        Ok((fetch, policy)) => {
+            match (&fetch, policy) {
+                (_, CachePolicy::NoStore) => cache.remove(ics_url, auth),
+                (FeedFetch::Modified { body, validators }, CachePolicy::Store { expires_at }) => {
+                    if let Err(e) = cache.put(ics_url, auth, body, now, expires_at, validators) {
+                        log::warn!("Failed to cache ICS feed: {}", e);
+                    }
+                }
+                // Still current: the cached copy lives on under the new lifetime
+                (FeedFetch::NotModified, CachePolicy::Store { expires_at }) => {
+                    if let Some(cached) = &cached {
+                        if let Err(e) = cache.put(ics_url, auth, &cached.body, now, expires_at, &cached.validators) {
+                            log::warn!("Failed to cache ICS feed: {}", e);
+                        }
+                    }
+                }
+            }
+            Ok(fetch)
+        }
+        Err(e) if e.is::<utils::retry::OperationCancelled>() => Err(e),
+        Err(e) => match cached.filter(|c| c.is_usable_fallback(now)) {
+            Some(cached) => {
+                log::warn!(
+                    "ICS fetch failed, using cached feed from {} ({} minutes old): {}",
+                    cached.fetched_at,
+                    (now - cached.fetched_at).num_minutes(),
+                    e
+                );
+                Ok(FeedFetch::Modified { body: cached.body, validators: FeedValidators::default() })
+            }
+            None => Err(e),
+        },
+    }
+}
+
+/// Fetch ICS data with a caller-supplied client, always going to the network
+pub async fn fetch_ics_data_with_client(
+    client: &Client,
+    ics_url: &str,
+    circuit_breaker_name: &str,
+    auth: Option<&FeedAuth>,
+) -> Result<String> {
+    fetch_ics_response(client, ics_url, circuit_breaker_name, auth, &CancellationToken::new(), None)
+        .await?
+        .0
+        .into_body()
+}
+
+/// Read a response body, refusing to buffer more than `max_bytes`
+///
+/// The limit applies to the decompressed body, so a small gzip bomb is
+/// caught too. Servers that announce an oversized `Content-Length` are
+/// rejected before any of the body is read.
+pub async fn read_body_limited(mut response: reqwest::Response, max_bytes: usize) -> Result<String> {
+    let too_large = || {
+        anyhow!(
+            "Calendar feed is too large (over {} MB). Try a feed that covers a shorter date range, or raise \"Largest calendar feed\" in Settings.",
+            max_bytes / (1024 * 1024)
+        )
     };
+
+    if response.content_length().is_some_and(|len| len > max_bytes as u64) {
+        return Err(too_large());
+    }
+
+    // Sized up front where the server says, rather than doubling as it arrives
+    let mut body = Vec::with_capacity(response.content_length().map_or(0, |len| len as usize));
+    while let Some(chunk) = response.chunk().await
+        .map_err(|e| anyhow!("Failed to read response body: {}", e))? {
+        if body.len() + chunk.len() > max_bytes {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    // RFC 5545 mandates UTF-8; be lenient with the odd stray byte. Folding
+    // counts octets, so a fold can split a character: unfold before decoding.
+    Ok(String::from_utf8(body).unwrap_or_else(|e| String::from_utf8_lossy(&unfold_bytes(e.as_bytes())).into_owned()))
+}
+
+/// Join folded content lines (CRLF or LF followed by a space or tab)
+fn unfold_bytes(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        let fold_len = match &data[i..] {
+            [b'\r', b'\n', b' ' | b'\t', ..] => 3,
+            [b'\n', b' ' | b'\t', ..] => 2,
+            _ => 0,
+        };
+        if fold_len > 0 {
+            i += fold_len;
+        } else {
+            out.push(data[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Decode RFC 5545 TEXT escapes: `\n`/`\N` become newlines and `\\`, `\,`
+/// and `\;` their literal character. Any other backslash is kept as written.
+pub fn unescape_text(value: &str) -> Cow<'_, str> {
+    if !value.contains('\\') {
+        return Cow::Borrowed(value);
+    }
+
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.clone().next() {
+            Some('n' | 'N') => {
+                chars.next();
+                out.push('\n');
+            }
+            Some(escaped @ ('\\' | ',' | ';')) => {
+                chars.next();
+                out.push(escaped);
+            }
+            _ => out.push('\\'),
+        }
+    }
+    Cow::Owned(out)
+}
+
+async fn fetch_ics_response(
+    client: &Client,
+    ics_url: &str,
+    circuit_breaker_name: &str,
+    auth: Option<&FeedAuth>,
+    cancel: &CancellationToken,
+    validators: Option<&FeedValidators>,
+) -> Result<(FeedFetch, CachePolicy)> {
+    let http_config = HttpConfig::ics_fetch();
+    let retry_config = http_config.to_retry_config();
+    let max_bytes = http_config.max_response_bytes;
     
     let circuit_breaker = get_circuit_breaker(circuit_breaker_name).await;
-    let ics_url_str = ics_url.to_string();
+    let ics_url_str = normalize_feed_url(ics_url);
+    let client = client.clone();
+    let cancel = cancel.clone();
+    let auth = auth.cloned();
+    let validators = validators.cloned();
     
     circuit_breaker.execute(move || {
         let config = retry_config.clone();
         let url = ics_url_str.clone();
+        let client = client.clone();
+        let cancel = cancel.clone();
+        let auth = auth.clone();
+        let validators = validators.clone();
         
-        async move {
-            utils::retry::retry_with_exponential_backoff(&config, move || {
-                let inner_url = url.clone();
-                Box::pin(async move {
-                    let client = Client::builder()
-                        .user_agent("OpenChime/1.0")
-                        .timeout(std::time::Duration::from_secs(30))
-                        .build()
-                        .map_err(|e| anyhow!("Failed to build client: {}", e))?;
-                    
-                    let response = client.get(&inner_url).send().await
-                        .map_err(|e| anyhow!("Request failed: {}", e))?;
-                    
-                    if !response.status().is_success() {
-                        let status = response.status();
-                        let text = response.text().await.unwrap_or_else(|_| "Unable to read error response".to_string());
-                        return Err(anyhow!("HTTP {}: {}", status, text));
-                    }
-                    
-                    let content = response.text().await
-                        .map_err(|e| anyhow!("Failed to read response body: {}", e))?;
-                        
-                    // TODO: For very large ICS files, consider streaming the response
-                    // instead of loading the entire body into memory.
-                    // Current icalendar crate requires full string, so this would need a streaming parser.
-                        
-                    // Basic validation to catch HTML responses
-                    if content.trim().starts_with("<!DOCTYPE") || content.trim().starts_with("<html") {
-                        return Err(anyhow!("Invalid ICS URL: The server returned HTML instead of a calendar file. Please ensure you are using the 'Secret address in iCal format' from your calendar settings, not the web browser URL."));
-                    }
-                    
-                    // Basic verification of ICS header
-                    if !content.contains("BEGIN:VCALENDAR") {
-                         log::warn!("Content does not contain BEGIN:VCALENDAR.");
-                    }
-                    
-                    Ok(content)
-                })
-            }).await
-        }
+        async move { fetch_with_retries(client, url, auth, validators, &config, &cancel, max_bytes).await }
+    }).await
+}
+
+/// Fetch a feed straight from the server: not from or into the cache, and
+/// outside its provider's circuit breaker, so a preview leaves no trace
+pub async fn fetch_ics_data_uncached(ics_url: &str, auth: Option<&FeedAuth>, cancel: &CancellationToken) -> Result<String> {
+    let client = HttpClientFactory::shared_ics_client().map_err(|e| anyhow!("Failed to build client: {}", e))?;
+    let http_config = HttpConfig::ics_fetch();
+    let url = normalize_feed_url(ics_url);
+    fetch_with_retries(client, url, auth.cloned(), None, &http_config.to_retry_config(), cancel, http_config.max_response_bytes)
+        .await?
+        .0
+        .into_body()
+}
+
+/// Request the feed, retrying as `config` says
+async fn fetch_with_retries(
+    client: Client,
+    url: String,
+    auth: Option<FeedAuth>,
+    validators: Option<FeedValidators>,
+    config: &utils::retry::RetryConfig,
+    cancel: &CancellationToken,
+    max_bytes: usize,
+) -> Result<(FeedFetch, CachePolicy)> {
+    utils::retry::retry_with_cancellation(config, cancel, move || {
+        let inner_url = url.clone();
+        let client = client.clone();
+        let auth = auth.clone();
+        let validators = validators.clone().unwrap_or_default();
+        Box::pin(async move {
+            // Shared per-host budget so many accounts (or repeated
+            // "Sync now" clicks) can't trip provider throttling
+            utils::rate_limiter::acquire_for_url(&inner_url).await;
+
+            let mut request = client.get(&inner_url);
+            if let Some(auth) = &auth {
+                request = auth.apply(request);
+            }
+            if let Some(etag) = &validators.etag {
+                request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &validators.last_modified {
+                request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+            }
+            let response = request.send().await
+                .map_err(|e| anyhow!("Request failed: {}", e))?;
+
+            if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+                log::debug!("ICS feed unchanged since the last sync");
+                return Ok((FeedFetch::NotModified, cache_policy_from_headers(response.headers(), Utc::now())));
+            }
+            
+            if matches!(response.status().as_u16(), 401 | 403) {
+                return Err(match auth {
+                    Some(FeedAuth { basic: Some(_), .. }) => anyhow!("The calendar server rejected the username or password"),
+                    Some(_) => anyhow!("The calendar server rejected the request headers set for this calendar"),
+                    None => anyhow!("The calendar server asks for a username and password; add them to this calendar"),
+                });
+            }
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let retry_after = response.headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| utils::retry::parse_retry_after(v, Utc::now()));
+                let text = response.text().await.unwrap_or_else(|_| "Unable to read error response".to_string());
+                return Err(utils::retry::HttpStatusError {
+                    status: status.as_u16(),
+                    retry_after,
+                    body: text,
+                }.into());
+            }
+
+            let policy = cache_policy_from_headers(response.headers(), Utc::now());
+            let validators = validators_from_headers(response.headers());
+            
+            let content = read_body_limited(response, max_bytes).await?;
+                
+            // Basic validation to catch HTML responses
+            if content.trim().starts_with("<!DOCTYPE") || content.trim().starts_with("<html") {
+                return Err(anyhow!("Invalid ICS URL: The server returned HTML instead of a calendar file. Please ensure you are using the 'Secret address in iCal format' from your calendar settings, not the web browser URL."));
+            }
+            
+            // Basic verification of ICS header
+            if !content.contains("BEGIN:VCALENDAR") {
+                 log::warn!("Content does not contain BEGIN:VCALENDAR.");
+            }
+            
+            Ok((FeedFetch::Modified { body: content, validators }, policy))
+        })
     }).await
 }
 
-/// Parse ICS datetime with proper timezone conversion
+/// How to read DATE-TIME values that carry neither a `Z` suffix nor a TZID.
+///
+/// RFC 5545 calls these "floating": the same wall-clock time wherever the
+/// viewer is. By default they're read in the system timezone; the
+/// `floating_timezone` setting can pin them to a fixed zone instead.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum FloatingTime {
+    #[default]
+    Local,
+    Zone(chrono_tz::Tz),
+}
+
+impl FloatingTime {
+    /// Read the `floating_timezone` setting: `local` or an IANA zone name
+    pub fn from_setting(value: &str) -> Self {
+        let value = value.trim();
+        if value.is_empty() || value.eq_ignore_ascii_case("local") {
+            return Self::Local;
+        }
+        match chrono_tz::Tz::from_str(value) {
+            Ok(tz) => Self::Zone(tz),
+            Err(_) => {
+                log::warn!("Unknown floating_timezone '{}', using local time", value);
+                Self::Local
+            }
+        }
+    }
+
+    fn to_utc(&self, naive: &chrono::NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Local => resolve_local_time(&chrono::Local, naive),
+            Self::Zone(tz) => resolve_local_time(tz, naive),
+        }
+    }
+}
+
+/// Convert a wall-clock time in `tz` to UTC the way RFC 5545 (3.3.5) asks,
+/// including around DST transitions:
+///
+/// - a time repeated when clocks fall back means its first occurrence;
+/// - a time skipped when clocks spring forward is read with the UTC offset
+///   in force before the transition (02:30 on a 02:00→03:00 night is 03:30).
+pub fn resolve_local_time<Tz: TimeZone>(tz: &Tz, naive: &chrono::NaiveDateTime) -> Option<DateTime<Utc>> {
+    use chrono::{LocalResult, Offset};
+
+    match tz.from_local_datetime(naive) {
+        LocalResult::Single(dt) => Some(dt.with_timezone(&Utc)),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest.with_timezone(&Utc)),
+        LocalResult::None => {
+            // Transitions are at least weeks apart, so a day earlier is safely
+            // on the other side of this one
+            let day_before = naive.checked_sub_signed(Duration::days(1))?;
+            let before = tz.from_local_datetime(&day_before).earliest()?;
+            let offset = before.offset().fix();
+            naive
+                .checked_sub_signed(Duration::seconds(offset.local_minus_utc() as i64))
+                .map(|utc| utc.and_utc())
+        }
+    }
+}
+
+/// The stretch of time events are imported from, and recurring events are
+/// expanded over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncWindow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl SyncWindow {
+    /// The default window: a month back, a year ahead
+    pub fn around(now: DateTime<Utc>) -> Self {
+        Self::from_settings(&crate::models::Settings::default(), now)
+    }
+
+    /// The window the sync settings ask for
+    pub fn from_settings(settings: &crate::models::Settings, now: DateTime<Utc>) -> Self {
+        Self {
+            start: now - settings.sync_days_back_duration(),
+            end: now + settings.sync_days_ahead_duration(),
+        }
+    }
+
+    /// Whether any of `event` falls inside the window
+    pub fn overlaps(&self, event: &CalendarEvent) -> bool {
+        event.end_time > self.start && event.start_time < self.end
+    }
+}
+
+/// Options that affect how ICS data is turned into events
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    pub floating: FloatingTime,
+    pub window: SyncWindow,
+    /// Zones the feed defines in VTIMEZONE blocks, by TZID
+    pub timezones: HashMap<String, VTimezone>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            floating: FloatingTime::default(),
+            window: SyncWindow::around(Utc::now()),
+            timezones: HashMap::new(),
+        }
+    }
+}
+
+impl ParseOptions {
+    pub fn from_settings(settings: &crate::models::Settings) -> Self {
+        Self {
+            floating: FloatingTime::from_setting(&settings.floating_timezone),
+            window: SyncWindow::from_settings(settings, Utc::now()),
+            ..Self::default()
+        }
+    }
+
+    /// `from_settings`, with floating times read in `account`'s own
+    /// timezone when it has one
+    pub fn for_account(settings: &crate::models::Settings, account: &Account) -> Self {
+        Self {
+            floating: FloatingTime::from_setting(floating_timezone(settings, account)),
+            ..Self::from_settings(settings)
+        }
+    }
+
+    /// The zone a TZID names: an IANA zone, or failing that one the feed
+    /// defines
+    fn zone(&self, tzid: &str) -> Option<NamedZone<'_>> {
+        match chrono_tz::Tz::from_str(tzid) {
+            Ok(tz) => Some(NamedZone::Iana(tz)),
+            Err(_) => self.timezones.get(tzid).map(NamedZone::Defined),
+        }
+    }
+}
+
+/// A zone named by a TZID
+#[derive(Debug, Clone)]
+enum NamedZone<'a> {
+    Iana(chrono_tz::Tz),
+    Defined(&'a VTimezone),
+}
+
+impl NamedZone<'_> {
+    fn to_utc(&self, naive: &chrono::NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Iana(tz) => resolve_local_time(tz, naive),
+            Self::Defined(zone) => zone.to_utc(naive),
+        }
+    }
+}
+
+/// Parse ICS datetime with proper timezone conversion, reading floating
+/// times in the system timezone
 pub fn parse_ical_datetime(dt: &icalendar::DatePerhapsTime) -> Option<DateTime<Utc>> {
+    parse_ical_datetime_with(dt, &FloatingTime::Local)
+}
+
+/// Parse ICS datetime with proper timezone conversion.
+///
+/// DATE values (all-day) come back as UTC midnight of that date: they name a
+/// calendar day, not an instant, so they must not be shifted by the viewer's
+/// offset. See `CalendarEvent::is_all_day`.
+pub fn parse_ical_datetime_with(dt: &icalendar::DatePerhapsTime, floating: &FloatingTime) -> Option<DateTime<Utc>> {
+    parse_ical_datetime_in(dt, &ParseOptions { floating: floating.clone(), ..ParseOptions::default() })
+}
+
+/// `parse_ical_datetime_with` that also knows the feed's own VTIMEZONEs
+/// (`ParseOptions::timezones`), for TZIDs that aren't IANA names
+pub fn parse_ical_datetime_in(dt: &icalendar::DatePerhapsTime, options: &ParseOptions) -> Option<DateTime<Utc>> {
+    let floating = &options.floating;
     match dt {
         icalendar::DatePerhapsTime::DateTime(dt) => {
             match dt {
                 // Already in UTC - no conversion needed
                 icalendar::CalendarDateTime::Utc(dt) => Some(dt.naive_utc().and_utc()),
 
-                // Floating time (no timezone specified) - interpret as local system time
-                icalendar::CalendarDateTime::Floating(naive_dt) => {
-                    chrono::Local
-                        .from_local_datetime(naive_dt)
-                        .single()
-                        .map(|local| local.with_timezone(&Utc))
-                }
+                // Floating time (no timezone specified)
+                icalendar::CalendarDateTime::Floating(naive_dt) => floating.to_utc(naive_dt),
 
                 // Time with explicit timezone - convert to UTC properly
                 icalendar::CalendarDateTime::WithTimezone { date_time, tzid } => {
-                    // Try to parse the timezone using chrono-tz
-                    if let Ok(tz) = chrono_tz::Tz::from_str(tzid) {
-                        tz.from_local_datetime(date_time)
-                            .single()
-                            .map(|zoned| zoned.with_timezone(&Utc))
+                    // An IANA name, or a zone the feed defines
+                    if let Some(zone) = options.zone(tzid) {
+                        zone.to_utc(date_time)
                     } else {
-                        // Fallback: if timezone not recognized, log warning and treat as local
-                        log::warn!("Unrecognized timezone '{}', treating as local time", tzid);
-                        chrono::Local
-                            .from_local_datetime(date_time)
-                            .single()
-                            .map(|local| local.with_timezone(&Utc))
+                        // Fallback: if timezone not recognized, log warning and treat as floating
+                        log::warn!("Unrecognized timezone '{}', treating as floating time", tzid);
+                        floating.to_utc(date_time)
                     }
                 }
             }
         }
         icalendar::DatePerhapsTime::Date(date) => {
-            // For date-only events, assume start of day in local timezone, then convert to UTC
-            chrono::Local
-                .with_ymd_and_hms(date.year(), date.month(), date.day(), 0, 0, 0)
-                .single()
-                .map(|local| local.with_timezone(&Utc))
+            Some(date.and_time(chrono::NaiveTime::MIN).and_utc())
         }
     }
 }
 
+/// What `store_event` did with an event; changed rows are returned as stored
+#[derive(Debug, Clone)]
+pub enum StoreOutcome {
+    Added(CalendarEvent),
+    Updated(CalendarEvent),
+    Unchanged,
+}
+
+/// Whether a sync stores what it finds or only reports it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyncMode {
+    #[default]
+    Apply,
+    /// Fetch and parse the feed and report what would be added, updated and
+    /// removed, leaving the database as it was
+    Preview,
+}
+
+/// Fetch, parse and store an ICS-backed account.
+///
+/// This is the single sync pipeline shared by every ICS provider; providers
+/// only differ in the circuit breaker they use and the prefix for generated
+/// event IDs. Downloaded feeds are kept in `cache`.
+pub async fn sync_ics_account(
+    account: &Account,
+    pool: &SqlitePool,
+    circuit_breaker_name: &str,
+    id_prefix: &str,
+    cache: &IcsCache,
+    cancel: &CancellationToken,
+) -> Result<SyncResult> {
+    sync_ics_account_with(account, pool, circuit_breaker_name, id_prefix, cache, cancel, SyncMode::Apply).await
+}
+
+/// `sync_ics_account` in either mode; a preview leaves `cache` alone
+pub async fn sync_ics_account_with(
+    account: &Account,
+    pool: &SqlitePool,
+    circuit_breaker_name: &str,
+    id_prefix: &str,
+    cache: &IcsCache,
+    cancel: &CancellationToken,
+    mode: SyncMode,
+) -> Result<SyncResult> {
+    let mut source = IcsUrlSource::new(account.auth_data.clone(), circuit_breaker_name, id_prefix)
+        .with_auth(FeedAuth::from_account(account).await?)
+        .with_cache(cache.clone())
+        .with_cancellation(cancel.clone());
+    if mode == SyncMode::Preview {
+        source = source.direct();
+    }
+    sync_from_source_with(&source, account, pool, mode).await
+}
+
+/// How long an unchanged feed can go without being parsed again
+const REPARSE_AFTER_HOURS: i64 = 24;
+
+/// Sync an account from any `CalendarSource`
+///
+/// Sends the validators of the last response along, so a feed the server
+/// reports unchanged (304) is neither parsed nor written. A feed downloaded
+/// again with the same content as last time is skipped the same way.
+pub async fn sync_from_source<S: CalendarSource>(source: &S, account: &Account, pool: &SqlitePool) -> Result<SyncResult> {
+    sync_from_source_with(source, account, pool, SyncMode::Apply).await
+}
+
+/// `sync_from_source` in either mode. A preview always downloads the feed
+/// and compares it with what's stored without writing anything (see
+/// `preview_changes`); `account` needn't be saved yet.
+pub async fn sync_from_source_with<S: CalendarSource>(source: &S, account: &Account, pool: &SqlitePool, mode: SyncMode) -> Result<SyncResult> {
+    let start_time = Instant::now();
+    let account_id = account.id.unwrap_or(0);
+    log::info!("Starting calendar sync for account: {} ({:?})", account.account_name, mode);
+
+    // Validators are only sent for a parse from the last day, so recurring
+    // events keep rolling into the sync window
+    let since = Utc::now() - Duration::hours(REPARSE_AFTER_HOURS);
+    let validators = match mode {
+        SyncMode::Apply => crate::database::accounts::feed_validators(pool, account_id, since).await?,
+        SyncMode::Preview => None,
+    };
+    let (ics_data, validators) = match source.fetch_ics_if_modified(validators.as_ref()).await? {
+        FeedFetch::Modified { body, validators } => (body, validators),
+        FeedFetch::NotModified => {
+            log::info!("Feed for {} unchanged since the last sync", account.account_name);
+            return Ok(SyncResult::success(account_id));
+        }
+    };
+    log::info!("Fetched {} bytes of ICS data", ics_data.len());
+
+    let settings = crate::database::settings::get(pool).await?;
+    // Many servers send no validators, or new ones every time
+    let hash = feed_hash(&ics_data, &settings, account);
+    if mode == SyncMode::Apply
+        && crate::database::accounts::feed_hash(pool, account_id, since).await?.as_deref() == Some(hash.as_str())
+    {
+        log::info!("Feed for {} has the same content as at the last sync", account.account_name);
+        return Ok(SyncResult::success(account_id));
+    }
+    let options = ParseOptions::for_account(&settings, account);
+    // Parsing a large feed takes a while; keep it off the async workers
+    let (id_prefix, parse_options) = (source.id_prefix().to_string(), options.clone());
+    let mut feed = tokio::task::spawn_blocking(move || parse_ics_feed(&ics_data, &id_prefix, &parse_options))
+        .await
+        .map_err(|e| anyhow!("ICS parsing failed: {}", e))??;
+    let current: HashSet<String> = feed.events.iter().map(|e| e.external_id.as_str().to_string()).collect();
+
+    if mode == SyncMode::Preview {
+        let changes = preview_changes(pool, account.id, &feed, &options.window).await?;
+        let result = SyncResult::with_changes(account_id, changes);
+        log::info!(
+            "Sync preview for {}: {} events would be added, {} updated, {} deleted",
+            account.account_name, result.events_added, result.events_updated, result.events_deleted
+        );
+        return Ok(result);
+    }
+
+    let mut changes = EventChanges::default();
+    // One transaction per account: a sync lands all at once or not at all
+    let (_turn, mut tx) = crate::database::begin_store(pool).await?;
+    store_feed_events(&mut tx, account_id, std::mem::take(&mut feed.events), &feed.attendees, &feed.occurrence_series, &feed.cancelled, &mut changes).await?;
+
+    // Events deleted upstream, and occurrences a series no longer has (it
+    // moved or got shorter). Only the window just parsed is compared: past
+    // events are kept for history, and ones beyond it (the window shrank)
+    // weren't looked for. An empty feed is more likely a server hiccup than
+    // an empty calendar. Events the feed has but that couldn't be read
+    // aren't missing either.
+    if current.is_empty() {
+        log::warn!("Feed for {} has no events; keeping the stored ones", account.account_name);
+    } else {
+        let stored: Vec<(EventId, String, Option<String>)> = sqlx::query_as(
+            "SELECT id, external_id, series_uid FROM events WHERE account_id = ? AND end_time > ? AND start_time < ? AND removed_at IS NULL",
+        )
+        .bind(account_id)
+        .bind(options.window.start)
+        .bind(options.window.end)
+        .fetch_all(&mut *tx)
+        .await?;
+        for (event_id, external_id, series_uid) in stored {
+            if !current.contains(&external_id) && !feed.may_hold(&external_id, series_uid.as_deref()) {
+                crate::database::events::remove_from_feed(&mut tx, event_id).await?;
+                changes.removed.push(event_id);
+            }
+        }
+    }
+    tx.commit().await?;
+    crate::database::accounts::set_calendar_metadata(pool, account_id, feed.calendar_name.as_deref(), feed.calendar_color.as_deref()).await?;
+    crate::database::accounts::set_feed_refresh_interval(pool, account_id, feed.refresh_interval).await?;
+    // A feed with events that didn't store is downloaded and parsed again
+    // next time, whatever the server or its hash says
+    let (hash, validators) = match changes.failed {
+        0 => (Some(hash.as_str()), validators),
+        failed => {
+            log::warn!("{} events of {} couldn't be stored; the feed will be parsed again", failed, account.account_name);
+            (None, FeedValidators::default())
+        }
+    };
+    crate::database::accounts::set_feed_hash(pool, account_id, hash).await?;
+    crate::database::accounts::set_feed_validators(pool, account_id, &validators).await?;
+
+    let result = SyncResult::with_changes(account_id, changes);
+    let duration = start_time.elapsed();
+    logging::log_calendar_sync(&account.account_name, result.events_added + result.events_updated, duration.as_millis() as u64);
+    log::info!(
+        "Calendar sync completed: {} events added, {} updated, {} deleted",
+        result.events_added, result.events_updated, result.events_deleted
+    );
+
+    Ok(result)
+}
+
+/// What storing `feed` under `account_id` would change, worked out from a
+/// read of the stored events with the comparisons `store_event` and the
+/// prune make, so a preview never takes the database's write lock
+async fn preview_changes(pool: &SqlitePool, account_id: Option<i64>, feed: &ParsedFeed, window: &SyncWindow) -> Result<EventChanges> {
+    let stored: HashMap<String, CalendarEvent> = match account_id {
+        Some(account_id) => sqlx::query_as::<_, CalendarEvent>(&format!(
+            "SELECT {} FROM events WHERE account_id = ? AND removed_at IS NULL",
+            crate::database::events::EVENT_COLUMNS
+        ))
+        .bind(account_id)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|event| (event.external_id.as_str().to_string(), event))
+        .collect(),
+        None => HashMap::new(),
+    };
+    let series: HashMap<String, String> = match account_id {
+        Some(account_id) => sqlx::query_as("SELECT external_id, series_uid FROM events WHERE account_id = ? AND series_uid IS NOT NULL AND removed_at IS NULL")
+            .bind(account_id)
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect(),
+        None => HashMap::new(),
+    };
+
+    let mut changes = EventChanges::default();
+    for event in &feed.events {
+        match stored.get(event.external_id.as_str()) {
+            None => changes.added.push(event.clone()),
+            Some(row) if !row.same_synced_fields(event) => {
+                changes.updated.push(CalendarEvent { id: row.id, title_override: row.title_override.clone(), ..event.clone() })
+            }
+            Some(_) => {}
+        }
+    }
+    let current: HashSet<&str> = feed.events.iter().map(|e| e.external_id.as_str()).collect();
+    for (external_id, row) in &stored {
+        let cancelled = feed.cancelled.contains(external_id);
+        let missing = !current.is_empty()
+            && window.overlaps(row)
+            && !current.contains(external_id.as_str())
+            && !feed.may_hold(external_id, series.get(external_id).map(String::as_str));
+        if cancelled || missing {
+            changes.removed.extend(row.id);
+        }
+    }
+    changes.removed.sort();
+
+    Ok(changes)
+}
+
+/// Bump when parsing a feed stores something it didn't before, so feeds
+/// that haven't changed are parsed again after an upgrade
+const PARSER_VERSION: u32 = 1;
+
+/// Identifies a feed body together with the parser and the settings that
+/// change how it parses, so an upgrade or changing the sync window or
+/// floating timezone re-parses it. Floating times read in local time also
+/// depend on the computer's zone. The hash is stored, so it has to be
+/// stable across builds.
+fn feed_hash(ics_data: &str, settings: &crate::models::Settings, account: &Account) -> String {
+    let mut hasher = crate::utils::fnv::Fnv1a::default();
+    hasher.write(&PARSER_VERSION.to_le_bytes());
+    hasher.write_str(ics_data);
+    let floating = floating_timezone(settings, account);
+    hasher.write_str(floating);
+    hasher.write(&settings.sync_days_back.to_le_bytes());
+    hasher.write(&settings.sync_days_ahead.to_le_bytes());
+    if FloatingTime::from_setting(floating) == FloatingTime::Local {
+        hasher.write(&crate::zone_watch::local_rules(Utc::now()).to_le_bytes());
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+/// The zone `account`'s floating times are read in: its own, or the
+/// `floating_timezone` setting
+fn floating_timezone<'a>(settings: &'a crate::models::Settings, account: &'a Account) -> &'a str {
+    account.timezone.as_deref().unwrap_or(&settings.floating_timezone)
+}
+
+/// Store parsed events (with their tags and attendees) under `account_id`
+/// and take the `cancelled` ones out, noting what changed in `changes`. An
+/// event that can't be stored is logged, skipped and counted in
+/// `changes.failed`, unless the error ends the transaction.
+pub(crate) async fn store_feed_events(
+    conn: &mut sqlx::SqliteConnection,
+    account_id: i64,
+    events: Vec<CalendarEvent>,
+    attendees: &HashMap<String, Vec<Attendee>>,
+    occurrence_series: &HashMap<String, String>,
+    cancelled: &[String],
+    changes: &mut EventChanges,
+) -> Result<()> {
+    let stored_attendees = crate::database::attendees::get_for_account(conn, account_id).await?;
+    for mut event in events {
+        event.account_id = account_id;
+        let people = attendees.get(event.external_id.as_str()).map(Vec::as_slice).unwrap_or_default();
+        let outcome = store_event(&event, &mut *conn).await;
+        if let (Ok(_), Some(series_uid)) = (&outcome, occurrence_series.get(event.external_id.as_str())) {
+            if let Err(e) = set_series_uid(conn, &event, series_uid).await {
+                log::warn!("Failed to store the series of {}: {}", event.title, e);
+            }
+        }
+        let (stored, list) = match outcome {
+            Ok(StoreOutcome::Added(stored)) => (stored, &mut changes.added),
+            Ok(StoreOutcome::Updated(stored)) => (stored, &mut changes.updated),
+            // Replies come in without the event itself changing
+            Ok(StoreOutcome::Unchanged) => {
+                let known = stored_attendees.get(event.external_id.as_str()).map(Vec::as_slice).unwrap_or_default();
+                if known == people {
+                    continue;
+                }
+                if let Err(e) = update_attendees(conn, &event, people).await {
+                    log::warn!("Failed to store attendees of {}: {}", event.title, e);
+                }
+                continue;
+            }
+            // The whole transaction is lost, not just this event
+            Err(e) if crate::database::ends_transaction(&e) => return Err(e),
+            Err(e) => {
+                log::warn!("Failed to store event {}: {}", event.title, e);
+                changes.failed += 1;
+                continue;
+            }
+        };
+
+        if let Some(event_id) = stored.id {
+            let tags = crate::utils::categorize_event(&stored.title, stored.description.as_deref());
+            if let Err(e) = crate::database::tags::set_auto_tags(conn, event_id, &tags).await {
+                log::warn!("Failed to tag event {}: {}", stored.title, e);
+            }
+            if let Err(e) = crate::database::attendees::set_for_event(conn, event_id, people).await {
+                log::warn!("Failed to store attendees of {}: {}", stored.title, e);
+            }
+        }
+        list.push(stored);
+    }
+
+    // Events called off since they were stored
+    for external_id in cancelled {
+        let removed: Option<EventId> = sqlx::query_scalar("SELECT id FROM events WHERE account_id = ? AND external_id = ? AND removed_at IS NULL")
+            .bind(account_id)
+            .bind(external_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+        if let Some(event_id) = removed {
+            crate::database::events::remove_from_feed(&mut *conn, event_id).await?;
+            changes.removed.push(event_id);
+        }
+    }
+
+    Ok(())
+}
+
+/// Note the series an occurrence belongs to; rows stored before the
+/// column existed get it on their next sync
+async fn set_series_uid(conn: &mut sqlx::SqliteConnection, event: &CalendarEvent, series_uid: &str) -> Result<()> {
+    sqlx::query("UPDATE events SET series_uid = ? WHERE account_id = ? AND external_id = ? AND series_uid IS NOT ?")
+        .bind(series_uid)
+        .bind(event.account_id)
+        .bind(&event.external_id)
+        .bind(series_uid)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Replace the attendees of an event `store_event` left alone, whose
+/// replies changed
+async fn update_attendees(conn: &mut sqlx::SqliteConnection, event: &CalendarEvent, attendees: &[Attendee]) -> Result<()> {
+    let event_id: Option<EventId> = sqlx::query_scalar("SELECT id FROM events WHERE account_id = ? AND external_id = ?")
+        .bind(event.account_id)
+        .bind(&event.external_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    if let Some(event_id) = event_id {
+        crate::database::attendees::set_for_event(conn, event_id, attendees).await?;
+    }
+
+    Ok(())
+}
+
+/// Parse ICS data into calendar events.
+///
+/// Events that can't be converted (e.g. no DTSTART) are skipped rather than
+/// failing the whole feed. `account_id` is left at 0 for the caller to set.
+pub fn parse_ics_events(ics_data: &str, id_prefix: &str) -> Result<Vec<CalendarEvent>> {
+    parse_ics_events_with(ics_data, id_prefix, &ParseOptions::default())
+}
+
+/// `parse_ics_events` with explicit parse options
+pub fn parse_ics_events_with(ics_data: &str, id_prefix: &str, options: &ParseOptions) -> Result<Vec<CalendarEvent>> {
+    parse_ics_feed(ics_data, id_prefix, options).map(|feed| feed.events)
+}
+
+/// Events parsed from a feed, with recurring series expanded
+#[derive(Debug, Clone, Default)]
+pub struct ParsedFeed {
+    /// Single events, and each occurrence of a series inside the window
+    pub events: Vec<CalendarEvent>,
+    /// IDs of the recurring series the feed holds
+    pub series: Vec<String>,
+    /// The series each occurrence in `events` belongs to, by external ID;
+    /// stored alongside it, since a UID can look like an occurrence's ID
+    pub occurrence_series: HashMap<String, String>,
+    /// IDs of events and occurrences the organizer cancelled
+    pub cancelled: Vec<String>,
+    /// Who's invited to each event in `events`, by external ID
+    pub attendees: HashMap<String, Vec<Attendee>>,
+    /// The calendar's name (X-WR-CALNAME, or NAME from RFC 7986)
+    pub calendar_name: Option<String>,
+    /// The calendar's color as `#rrggbb` (X-APPLE-CALENDAR-COLOR, or a hex
+    /// COLOR)
+    pub calendar_color: Option<String>,
+    /// Seconds the feed asks to be left between fetches (REFRESH-INTERVAL
+    /// from RFC 7986, or X-PUBLISHED-TTL)
+    pub refresh_interval: Option<i32>,
+    /// UIDs of the events that couldn't be read, so their stored copies
+    /// aren't taken for deleted; `None` for one without a UID
+    pub unreadable: Vec<Option<String>>,
+}
+
+impl ParsedFeed {
+    /// Whether a stored event may be missing from `events` only because
+    /// the feed's copy of it (or of its series, `series_uid`) couldn't be read
+    pub fn may_hold(&self, external_id: &str, series_uid: Option<&str>) -> bool {
+        self.unreadable.iter().any(|uid| match uid {
+            Some(uid) => uid == external_id || series_uid == Some(uid.as_str()),
+            None => true,
+        })
+    }
+}
+
+/// Parse a feed, expanding each recurring event into its occurrences
+/// within `options.window` (see `recurrence`)
+///
+/// The feed is read an event at a time (see `ics_stream`), though the body
+/// itself has already been downloaded whole; an event the ICS parser can't
+/// read is skipped rather than failing the feed, and noted in `unreadable`.
+pub fn parse_ics_feed(ics_data: &str, id_prefix: &str, options: &ParseOptions) -> Result<ParsedFeed> {
+    if !ics_data.trim().is_empty() && !ics_stream::content_lines(ics_data).any(|line| line.trim().eq_ignore_ascii_case("BEGIN:VCALENDAR")) {
+        return Err(anyhow!("Failed to parse ICS data: not an iCalendar feed"));
+    }
+    let options = &ParseOptions { timezones: vtimezone::parse_all(ics_data), ..options.clone() };
+
+    let mut feed = ParsedFeed::default();
+    let header = ics_stream::calendar_properties(ics_data);
+    let header_value = |names: &[&str]| {
+        names.iter().find_map(|name| header.iter().find(|p| p.name == *name)).map(|p| unescape_text(&p.value).into_owned())
+    };
+    feed.calendar_name = header_value(&["X-WR-CALNAME", "NAME"]).map(|name| name.trim().to_string()).filter(|name| !name.is_empty());
+    feed.calendar_color = header_value(&["X-APPLE-CALENDAR-COLOR", "COLOR"]).and_then(|color| hex_color(&color));
+    feed.refresh_interval = header_value(&["REFRESH-INTERVAL", "X-PUBLISHED-TTL"])
+        .and_then(|value| recurrence::parse_duration(&value))
+        .filter(|interval| *interval > Duration::zero())
+        .and_then(|interval| i32::try_from(interval.num_seconds()).ok());
+    struct Override {
+        event: CalendarEvent,
+        recurrence_id: RawProperty,
+        own_start: Option<icalendar::DatePerhapsTime>,
+        cancelled: bool,
+        people: Vec<Attendee>,
+    }
+    let mut overrides = Vec::new();
+    let mut series_starts = HashMap::new();
+    for block in ics_stream::event_blocks(ics_data) {
+        let raw = &block.properties;
+        let mut skip = |problem: &dyn std::fmt::Display| {
+            let uid = raw.iter().find(|p| p.name == "UID").map(|p| p.value.trim().to_string());
+            log::warn!("Skipping event {} that couldn't be read: {}", uid.as_deref().unwrap_or("without a UID"), problem);
+            feed.unreadable.push(uid);
+        };
+        let calendar = match IcsCalendar::from_str(&block.as_calendar()) {
+            Ok(calendar) => calendar,
+            Err(e) => {
+                skip(&e);
+                continue;
+            }
+        };
+        let Some(ics_event) = calendar.components.iter().find_map(|component| component.as_event()) else { continue };
+        let Some(mut event) = convert_ics_event(ics_event, id_prefix, options) else {
+            skip(&"no usable start time");
+            continue;
+        };
+        let recurring = raw.iter().any(|p| p.name == "RRULE" || p.name == "RDATE");
+        event.reminder_minutes = reminder_minutes(&block.alarms, &event, recurring);
+        let cancelled = is_cancelled(ics_event);
+        let people = attendees(raw);
+        // Overrides are placed once every series' DTSTART is known
+        if let Some(recurrence_id) = raw.iter().find(|p| p.name == "RECURRENCE-ID") {
+            overrides.push(Override { event, recurrence_id: recurrence_id.clone(), own_start: ics_event.get_start(), cancelled, people });
+            continue;
+        }
+        if let Some(start) = ics_event.get_start() {
+            series_starts.insert(event.external_id.as_str().to_string(), start);
+        }
+        match expand_series(&event, ics_event, raw, options) {
+            // A cancelled series keeps no occurrences, so the stored ones are pruned
+            Some(occurrences) => {
+                feed.series.push(event.external_id.as_str().to_string());
+                if !cancelled {
+                    for occurrence in &occurrences {
+                        feed.occurrence_series.insert(occurrence.external_id.as_str().to_string(), event.external_id.as_str().to_string());
+                        feed.attendees.insert(occurrence.external_id.as_str().to_string(), people.clone());
+                    }
+                    feed.events.extend(occurrences);
+                }
+            }
+            None if cancelled => feed.cancelled.push(event.external_id.as_str().to_string()),
+            None => {
+                feed.attendees.insert(event.external_id.as_str().to_string(), people);
+                feed.events.push(event);
+            }
+        }
+    }
+    // A changed occurrence takes the place of the one the series generated.
+    // Its RECURRENCE-ID is read like the series' DTSTART, whose zone the
+    // override's own DTSTART needn't share.
+    for Override { mut event, recurrence_id, own_start, cancelled, people } in overrides {
+        let series_start = series_starts.get(event.external_id.as_str()).or(own_start.as_ref());
+        let Some((original, series_start)) = series_start.and_then(|start| Some((overridden_occurrence(&recurrence_id, start, options)?, start))) else {
+            // One that can't be placed is kept as an event of its own
+            if !cancelled {
+                feed.attendees.insert(event.external_id.as_str().to_string(), people);
+                feed.events.push(event);
+            }
+            continue;
+        };
+        let series = event.external_id.as_str().to_string();
+        let is_all_day = matches!(series_start, icalendar::DatePerhapsTime::Date(_));
+        event.external_id = recurrence::occurrence_id(&series, original, is_all_day).into();
+        if cancelled {
+            feed.cancelled.push(event.external_id.as_str().to_string());
+            continue;
+        }
+        feed.events.retain(|e| e.external_id.as_str() != event.external_id.as_str());
+        feed.occurrence_series.insert(event.external_id.as_str().to_string(), series);
+        feed.attendees.insert(event.external_id.as_str().to_string(), people);
+        feed.events.push(event);
+    }
+    feed.events.retain(|e| !feed.cancelled.iter().any(|id| id == e.external_id.as_str()));
+    // Long-past and far-off events aren't imported
+    feed.events.retain(|e| options.window.overlaps(e));
+    let kept: HashSet<&str> = feed.events.iter().map(|e| e.external_id.as_str()).collect();
+    feed.attendees.retain(|id, people| !people.is_empty() && kept.contains(id.as_str()));
+    feed.occurrence_series.retain(|id, _| kept.contains(id.as_str()));
+    if feed.events.is_empty() && !ics_data.is_empty() {
+        log::warn!("Parsed 0 events. ICS data size: {} bytes. First 100 chars: {:?}",
+            ics_data.len(),
+            ics_data.chars().take(100).collect::<String>());
+    } else {
+        log::info!("Parsed {} events from ICS data", feed.events.len());
+    }
+
+    Ok(feed)
+}
+
+/// A `#rrggbb` or `#rrggbbaa` color as `#rrggbb`; CSS color names (which
+/// RFC 7986 COLOR also allows) aren't read
+fn hex_color(value: &str) -> Option<String> {
+    let hex = value.trim().strip_prefix('#')?;
+    if !matches!(hex.len(), 6 | 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(format!("#{}", hex[..6].to_ascii_lowercase()))
+}
+
+/// The occurrences of a recurring event inside the window, or `None` if
+/// it doesn't recur (or its RRULE can't be read, in which case it is kept
+/// as a single event). RDATEs add occurrences and EXDATEs remove them.
+fn expand_series(event: &CalendarEvent, ics_event: &IcsEvent, raw: &[RawProperty], options: &ParseOptions) -> Option<Vec<CalendarEvent>> {
+    let rule = match raw.iter().find(|p| p.name == "RRULE").map(|p| p.value.parse::<RecurrenceRule>()) {
+        Some(Ok(rule)) => Some(rule),
+        Some(Err(e)) => {
+            log::debug!("Not expanding '{}': {}", event.title, e);
+            return None;
+        }
+        None => None,
+    };
+    let listed = |name: &str| raw.iter().filter(|p| p.name == name).flat_map(recurrence::date_list).collect::<Vec<_>>();
+    let (rdates, exdates) = (listed("RDATE"), listed("EXDATE"));
+    if rule.is_none() && rdates.is_empty() {
+        return None;
+    }
+
+    let start = ics_event.get_start()?;
+    let (first, to_utc) = wall_clock(&start, options);
+    let listed_to_utc = |listed: &icalendar::DatePerhapsTime| listed_time(listed, options, &to_utc);
+
+    let mut starts: Vec<DateTime<Utc>> = match &rule {
+        Some(rule) => rule.occurrences(first, &to_utc, options.window.end).into_iter().filter_map(&to_utc).collect(),
+        None => to_utc(first).into_iter().collect(),
+    };
+    starts.extend(rdates.iter().filter_map(listed_to_utc));
+    let excluded: HashSet<DateTime<Utc>> = exdates.iter().filter_map(listed_to_utc).collect();
+    starts.retain(|start| !excluded.contains(start));
+    starts.sort();
+    starts.dedup();
+
+    let duration = event.end_time - event.start_time;
+    let series = event.external_id.as_str();
+    let occurrences = starts
+        .into_iter()
+        .filter(|start| *start + duration > options.window.start && *start <= options.window.end)
+        .map(|start| CalendarEvent {
+            external_id: recurrence::occurrence_id(series, start, event.is_all_day).into(),
+            start_time: start,
+            end_time: start + duration,
+            ..event.clone()
+        })
+        .collect();
+    Some(occurrences)
+}
+
+/// Whether the organizer called the event off (STATUS:CANCELLED)
+fn is_cancelled(ics_event: &IcsEvent) -> bool {
+    ics_event.property_value("STATUS").is_some_and(|status| status.trim().eq_ignore_ascii_case("CANCELLED"))
+}
+
+/// The minutes before the start at which the event's VALARMs go off, as
+/// stored in `reminder_minutes`. Email alarms, alarms after the start and
+/// ones more than `MAX_CALENDAR_REMINDER_MINUTES` ahead are left out, as
+/// are alarms at a fixed time on a `recurring` event: those belong to one
+/// occurrence, not the whole series.
+fn reminder_minutes(alarms: &[Vec<RawProperty>], event: &CalendarEvent, recurring: bool) -> Option<String> {
+    use icalendar::{CalendarDateTime, DatePerhapsTime};
+
+    let mut minutes: Vec<i64> = alarms
+        .iter()
+        .filter(|alarm| {
+            let action = alarm.iter().find(|p| p.name == "ACTION").map(|p| p.value.trim().to_ascii_uppercase());
+            !matches!(action.as_deref(), Some("EMAIL" | "NONE"))
+        })
+        .filter_map(|alarm| {
+            let trigger = alarm.iter().find(|p| p.name == "TRIGGER")?;
+            let goes_off = if trigger.param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE-TIME")) {
+                if recurring {
+                    return None;
+                }
+                match recurrence::date_list(trigger).into_iter().next()? {
+                    DatePerhapsTime::DateTime(CalendarDateTime::Utc(at)) => at,
+                    _ => return None,
+                }
+            } else {
+                let related_to_end = trigger.param("RELATED").is_some_and(|r| r.eq_ignore_ascii_case("END"));
+                let anchor = if related_to_end { event.end_time } else { event.start_time };
+                anchor + recurrence::parse_duration(&trigger.value)?
+            };
+            Some((event.start_time - goes_off).num_minutes())
+        })
+        .filter(|minutes| (0..=MAX_CALENDAR_REMINDER_MINUTES as i64).contains(minutes))
+        .collect();
+    minutes.sort_unstable_by(|a, b| b.cmp(a));
+    minutes.dedup();
+
+    (!minutes.is_empty()).then(|| minutes.iter().map(i64::to_string).collect::<Vec<_>>().join(","))
+}
+
+/// The event's ORGANIZER and ATTENDEEs, organizer first and the rest by
+/// address. An organizer who is also listed as an attendee appears once.
+fn attendees(raw: &[RawProperty]) -> Vec<Attendee> {
+    let mut people: Vec<Attendee> = Vec::new();
+    for property in raw.iter().filter(|p| p.name == "ORGANIZER" || p.name == "ATTENDEE") {
+        let address = property.value.trim();
+        let address = match address.get(..7) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("mailto:") => &address[7..],
+            _ => address,
+        };
+        if address.is_empty() {
+            continue;
+        }
+        let email = address.to_lowercase();
+        let name = property.param("CN").map(|cn| unescape_text(cn).trim().to_string()).filter(|cn| !cn.is_empty());
+        let is_organizer = property.name == "ORGANIZER";
+        match people.iter_mut().find(|person| person.email == email) {
+            Some(person) => {
+                person.is_organizer |= is_organizer;
+                person.name = person.name.take().or(name);
+                if let Some(partstat) = property.param("PARTSTAT") {
+                    person.rsvp = Rsvp::from_partstat(partstat);
+                }
+            }
+            None => people.push(Attendee {
+                email,
+                name,
+                rsvp: property.param("PARTSTAT").map(Rsvp::from_partstat).unwrap_or_default(),
+                is_organizer,
+            }),
+        }
+    }
+    people.sort_by(|a, b| b.is_organizer.cmp(&a.is_organizer).then_with(|| a.email.cmp(&b.email)));
+    people
+}
+
+/// The original start, in UTC, of the occurrence a RECURRENCE-ID instance
+/// replaces, in a series starting at `series_start`
+fn overridden_occurrence(recurrence_id: &RawProperty, series_start: &icalendar::DatePerhapsTime, options: &ParseOptions) -> Option<DateTime<Utc>> {
+    let original = recurrence::date_list(recurrence_id).into_iter().next()?;
+    let (_, to_utc) = wall_clock(series_start, options);
+    listed_time(&original, options, to_utc)
+}
+
+/// An EXDATE, RDATE or RECURRENCE-ID value in UTC. Dates, floating times
+/// and unknown zones are read in the series' own zone (`series_to_utc`), as
+/// the RFC has them match DTSTART.
+fn listed_time(
+    listed: &icalendar::DatePerhapsTime,
+    options: &ParseOptions,
+    series_to_utc: impl Fn(chrono::NaiveDateTime) -> Option<DateTime<Utc>>,
+) -> Option<DateTime<Utc>> {
+    use icalendar::{CalendarDateTime, DatePerhapsTime};
+
+    match listed {
+        DatePerhapsTime::DateTime(CalendarDateTime::Utc(dt)) => Some(*dt),
+        DatePerhapsTime::DateTime(CalendarDateTime::WithTimezone { date_time, tzid }) => match options.zone(tzid) {
+            Some(zone) => zone.to_utc(date_time),
+            None => series_to_utc(*date_time),
+        },
+        DatePerhapsTime::DateTime(CalendarDateTime::Floating(naive)) => series_to_utc(*naive),
+        DatePerhapsTime::Date(date) => series_to_utc(date.and_time(chrono::NaiveTime::MIN)),
+    }
+}
+
+/// A start time as wall-clock time in its own zone, and the way back to UTC
+/// for other wall-clock times in that zone
+fn wall_clock<'a>(
+    start: &icalendar::DatePerhapsTime,
+    options: &'a ParseOptions,
+) -> (chrono::NaiveDateTime, impl Fn(chrono::NaiveDateTime) -> Option<DateTime<Utc>> + 'a) {
+    use icalendar::{CalendarDateTime, DatePerhapsTime};
+
+    enum Zone<'a> {
+        Date,
+        Utc,
+        Named(NamedZone<'a>),
+        Floating,
+    }
+    let (naive, zone) = match start {
+        DatePerhapsTime::Date(date) => (date.and_time(chrono::NaiveTime::MIN), Zone::Date),
+        DatePerhapsTime::DateTime(CalendarDateTime::Utc(dt)) => (dt.naive_utc(), Zone::Utc),
+        DatePerhapsTime::DateTime(CalendarDateTime::Floating(naive)) => (*naive, Zone::Floating),
+        // An unknown zone is read as floating, as for the first occurrence
+        DatePerhapsTime::DateTime(CalendarDateTime::WithTimezone { date_time, tzid }) => match options.zone(tzid) {
+            Some(zone) => (*date_time, Zone::Named(zone)),
+            None => (*date_time, Zone::Floating),
+        },
+    };
+    let to_utc = move |naive: chrono::NaiveDateTime| match &zone {
+        Zone::Date | Zone::Utc => Some(naive.and_utc()),
+        Zone::Named(zone) => zone.to_utc(&naive),
+        Zone::Floating => options.floating.to_utc(&naive),
+    };
+    (naive, to_utc)
+}
+
+/// Convert a single VEVENT into a `CalendarEvent`.
+///
+/// Date-only events are kept and marked `is_all_day`. A missing DTEND
+/// defaults to one day for date-only events and one hour otherwise. Returns
+/// `None` if the event has no usable start time.
+pub fn convert_ics_event(ics_event: &IcsEvent, id_prefix: &str, options: &ParseOptions) -> Option<CalendarEvent> {
+    let title = ics_event.get_summary()
+        .map(|summary| unescape_text(summary).into_owned())
+        .unwrap_or_else(|| "Untitled Event".to_string());
+
+    let description = ics_event.get_description().map(|d| utils::html::html_to_text(&unescape_text(d)));
+    let location = ics_event.get_location().map(|l| unescape_text(l).into_owned());
+
+    let start = ics_event.get_start();
+    let parse = |dt: &icalendar::DatePerhapsTime| parse_ical_datetime_in(dt, options);
+    let start_time = match start.as_ref().and_then(parse) {
+        Some(start_time) => start_time,
+        None => {
+            log::debug!("Skipping event '{}' without a valid start time", title);
+            return None;
+        }
+    };
+
+    let is_all_day = matches!(start, Some(icalendar::DatePerhapsTime::Date(_)));
+    // Shown as free time: out-of-office blocks, holidays, reminders to self
+    let is_free = ics_event.property_value("TRANSP").is_some_and(|transp| transp.trim().eq_ignore_ascii_case("TRANSPARENT"));
+    // Kept so the event can be shown in the organizer's time as well as ours
+    let source_timezone = match &start {
+        Some(icalendar::DatePerhapsTime::DateTime(icalendar::CalendarDateTime::WithTimezone { tzid, .. })) => {
+            chrono_tz::Tz::from_str(tzid).ok().map(|tz| tz.name().to_string())
+        }
+        _ => None,
+    };
+    let default_duration = if is_all_day { Duration::days(1) } else { Duration::hours(1) };
+    let end_time = ics_event.get_end()
+        .as_ref()
+        .and_then(parse)
+        .or_else(|| start_time.checked_add_signed(default_duration))
+        .unwrap_or(start_time);
+
+    // Generate unique ID from UID, falling back to a hash of title and start time
+    let external_id = ics_event.get_uid()
+        .map(|uid| uid.to_string())
+        .unwrap_or_else(|| {
+            let mut hasher = DefaultHasher::new();
+            format!("{}{}", title, start_time.timestamp()).hash(&mut hasher);
+            format!("{}-{:x}", id_prefix, hasher.finish())
+        });
+
+    // Phone-only meetings still have a dial-in and passcode worth keeping
+    let invite_text = format!("{} {}", description.as_deref().unwrap_or(""), location.as_deref().unwrap_or(""));
+    // A meeting link in the URL property is the event's own; one in the
+    // description may just be mentioned there
+    let video = ics_event
+        .property_value("URL")
+        .and_then(|url| utils::extract_video_link(Some(url.trim()), None))
+        .map(|info| VideoMeetingInfo {
+            password: info.password.or_else(|| utils::extract_meeting_password(&invite_text)),
+            dial_in: info.dial_in.or_else(|| utils::extract_dial_in(&invite_text)),
+            ..info
+        })
+        .or_else(|| utils::extract_video_link(description.as_deref(), location.as_deref()));
+    let (meeting_id, passcode, dial_in) = match &video {
+        Some(info) => (info.meeting_id.clone(), info.password.clone(), info.dial_in.clone()),
+        None => (None, utils::extract_meeting_password(&invite_text), utils::extract_dial_in(&invite_text)),
+    };
+
+    Some(CalendarEvent {
+        id: None,
+        external_id: external_id.into(),
+        account_id: 0,
+        title,
+        description,
+        start_time,
+        end_time,
+        video_link: video.as_ref().map(|info| info.url.clone()),
+        video_platform: video.map(|info| info.platform),
+        snooze_count: 0,
+        has_alerted: false,
+        last_alert_threshold: None,
+        is_dismissed: false,
+        last_snoozed_at: None,
+        is_all_day,
+        meeting_id,
+        meeting_passcode: passcode,
+        dial_in_number: dial_in.as_ref().map(|d| d.number.clone()),
+        dial_in_conference_id: dial_in.and_then(|d| d.conference_id),
+        title_override: None,
+        source_timezone,
+        snoozed_until: None,
+        location,
+        reminder_minutes: None,
+        is_free,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    })
+}
+
+/// Insert or update an event, keyed by `(external_id, account_id)`.
+///
+/// A single upsert: rows whose synced fields already match are left alone,
+/// so `updated_at` only moves when the feed actually changed. Previews
+/// compare with `CalendarEvent::same_synced_fields`, kept in step with it.
+///
+/// Alert state belongs to the meeting as scheduled. A rename or a new room
+/// keeps it, so a dismissed event stays quiet; a move to another start
+/// time clears it (dismissal, alerted thresholds and snoozes), so the new
+/// time gets its alerts.
+pub async fn store_event<'c, E>(event: &CalendarEvent, executor: E) -> Result<StoreOutcome>
+where
+    E: sqlx::Executor<'c, Database = sqlx::Sqlite>,
+{
+    let now = Utc::now();
+    let row = sqlx::query(
+        "INSERT INTO events (external_id, title, description, start_time, end_time,
+         video_link, video_platform, is_all_day, meeting_id, meeting_passcode,
+         dial_in_number, dial_in_conference_id, source_timezone, location, reminder_minutes, is_free, account_id, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(external_id, account_id) DO UPDATE SET
+            title = excluded.title,
+            description = excluded.description,
+            start_time = excluded.start_time,
+            end_time = excluded.end_time,
+            video_link = excluded.video_link,
+            video_platform = excluded.video_platform,
+            is_all_day = excluded.is_all_day,
+            meeting_id = excluded.meeting_id,
+            meeting_passcode = excluded.meeting_passcode,
+            dial_in_number = excluded.dial_in_number,
+            dial_in_conference_id = excluded.dial_in_conference_id,
+            source_timezone = excluded.source_timezone,
+            location = excluded.location,
+            reminder_minutes = excluded.reminder_minutes,
+            is_free = excluded.is_free,
+            has_alerted = CASE WHEN events.start_time IS excluded.start_time THEN events.has_alerted ELSE 0 END,
+            last_alert_threshold = CASE WHEN events.start_time IS excluded.start_time THEN events.last_alert_threshold END,
+            is_dismissed = CASE WHEN events.start_time IS excluded.start_time THEN events.is_dismissed ELSE 0 END,
+            snooze_count = CASE WHEN events.start_time IS excluded.start_time THEN events.snooze_count ELSE 0 END,
+            last_snoozed_at = CASE WHEN events.start_time IS excluded.start_time THEN events.last_snoozed_at END,
+            snoozed_until = CASE WHEN events.start_time IS excluded.start_time THEN events.snoozed_until END,
+            removed_at = NULL,
+            updated_at = excluded.updated_at
+         WHERE events.title IS NOT excluded.title
+            OR events.description IS NOT excluded.description
+            OR events.start_time IS NOT excluded.start_time
+            OR events.end_time IS NOT excluded.end_time
+            OR events.video_link IS NOT excluded.video_link
+            OR events.video_platform IS NOT excluded.video_platform
+            OR events.is_all_day IS NOT excluded.is_all_day
+            OR events.meeting_id IS NOT excluded.meeting_id
+            OR events.meeting_passcode IS NOT excluded.meeting_passcode
+            OR events.dial_in_number IS NOT excluded.dial_in_number
+            OR events.dial_in_conference_id IS NOT excluded.dial_in_conference_id
+            OR events.source_timezone IS NOT excluded.source_timezone
+            OR events.location IS NOT excluded.location
+            OR events.reminder_minutes IS NOT excluded.reminder_minutes
+            OR events.is_free IS NOT excluded.is_free
+            OR events.removed_at IS NOT NULL
+         RETURNING id, external_id, account_id, title, description, start_time, end_time, video_link, video_platform,
+                   snooze_count, has_alerted, last_alert_threshold, is_dismissed, last_snoozed_at, is_all_day,
+                   meeting_id, meeting_passcode, dial_in_number, dial_in_conference_id, title_override,
+                   source_timezone, snoozed_until, location, reminder_minutes, is_free, created_at, updated_at,
+                   created_at = ? AS inserted"
+    )
+    .bind(&event.external_id)
+    .bind(&event.title)
+    .bind(&event.description)
+    .bind(event.start_time)
+    .bind(event.end_time)
+    .bind(&event.video_link)
+    .bind(&event.video_platform)
+    .bind(event.is_all_day)
+    .bind(&event.meeting_id)
+    .bind(&event.meeting_passcode)
+    .bind(&event.dial_in_number)
+    .bind(&event.dial_in_conference_id)
+    .bind(&event.source_timezone)
+    .bind(&event.location)
+    .bind(&event.reminder_minutes)
+    .bind(event.is_free)
+    .bind(event.account_id)
+    .bind(now)
+    .bind(now)
+    .bind(now)
+    // Step the statement to completion: a RETURNING row read on its own leaves
+    // the write's implicit transaction open until the connection is reused
+    .fetch_all(executor)
+    .await?
+    .pop();
+
+    // No row back means the conflict update was skipped by its WHERE clause;
+    // a fresh insert is the only way created_at can equal this call's `now`
+    let outcome = match row {
+        None => {
+            log::debug!("Event unchanged: {}", event.title);
+            StoreOutcome::Unchanged
+        }
+        Some(row) => {
+            let stored = CalendarEvent::from_row(&row)?;
+            if row.try_get::<bool, _>("inserted")? {
+                log::debug!("Added new event: {}", event.title);
+                StoreOutcome::Added(stored)
+            } else {
+                log::debug!("Updated event: {}", event.title);
+                StoreOutcome::Updated(stored)
+            }
+        }
+    };
+    Ok(outcome)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::{TimeZone, NaiveDate, NaiveDateTime, NaiveTime};
+    use chrono::{TimeZone, NaiveDate, NaiveDateTime};
     use icalendar::{DatePerhapsTime, CalendarDateTime};
 
+    #[test]
+    fn test_unreadable_events_are_noted() {
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:ok\r\nDTSTART:20240301T090000Z\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:standup\r\nDTSTART:never\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let options = ParseOptions { window: SyncWindow::around(Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap()), ..Default::default() };
+        let feed = parse_ics_feed(ics, "test", &options).unwrap();
+        assert_eq!(feed.events.len(), 1);
+        assert_eq!(feed.unreadable, vec![Some("standup".to_string())]);
+
+        let occurrence = recurrence::occurrence_id("standup", Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap(), false);
+        assert!(feed.may_hold("standup", None) && feed.may_hold(&occurrence, Some("standup")));
+        assert!(!feed.may_hold("ok", None) && !feed.may_hold("retro", None));
+        // A UID that only looks like one of the series' occurrences isn't held
+        assert!(!feed.may_hold(&occurrence, None));
+        assert!(ParsedFeed { unreadable: vec![None], ..Default::default() }.may_hold("retro", None));
+    }
+
     #[test]
     fn test_validate_ics_url_format_valid() {
         let url = "https://calendar.google.com/calendar/ical/user/private/basic.ics";
@@ -202,6 +1696,60 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("HTTPS"));
     }
 
+    #[test]
+    fn test_webcal_urls_are_https() {
+        assert_eq!(normalize_feed_url(" webcal://example.com/cal.ics "), "https://example.com/cal.ics");
+        assert_eq!(normalize_feed_url("WEBCALS://example.com/cal.ics"), "https://example.com/cal.ics");
+        assert_eq!(normalize_feed_url("https://example.com/cal.ics"), "https://example.com/cal.ics");
+        assert_eq!(normalize_feed_url("http://example.com/cal.ics"), "http://example.com/cal.ics");
+
+        assert!(validate_ics_url_format("webcal://p01-calendars.icloud.com/published/2/abc").is_ok());
+        assert!(validate_ics_url_format("webcals://calendar.example.com/basic.ics").is_ok());
+        assert!(validate_ics_url_format("webcal://localhost/calendar.ics").is_err());
+    }
+
+    #[test]
+    fn test_outlook_pages_become_their_feeds() {
+        let page = "https://outlook.office365.com/owa/calendar/0a1b2c@example.com/d3e4f5/calendar.html";
+        let feed = "https://outlook.office365.com/owa/calendar/0a1b2c@example.com/d3e4f5/calendar.ics";
+        assert_eq!(outlook_feed_url(page).as_deref(), Some(feed));
+        assert_eq!(normalize_feed_url(page), feed);
+        assert_eq!(normalize_feed_url(feed), feed);
+        assert_eq!(
+            normalize_feed_url("https://outlook.live.com/owa/calendar/00000000-0000-0000-0000-000000000000/a1b2/cid-C3D4/calendar.html"),
+            "https://outlook.live.com/owa/calendar/00000000-0000-0000-0000-000000000000/a1b2/cid-C3D4/calendar.ics"
+        );
+        assert_eq!(
+            normalize_feed_url("webcal://outlook.office365.com/owa/calendar/0a1b2c@example.com/d3e4f5/calendar.ics"),
+            feed
+        );
+
+        assert_eq!(outlook_feed_url("https://example.com/owa/calendar/abc/calendar.html"), None);
+        assert_eq!(outlook_feed_url("https://outlook.office365.com/mail/inbox"), None);
+    }
+
+    #[test]
+    fn test_google_url_mistakes() {
+        let public = "https://calendar.google.com/calendar/ical/team%40group.calendar.google.com/public/basic.ics";
+        assert_eq!(normalize_feed_url("https://calendar.google.com/calendar/embed?src=team%40group.calendar.google.com&ctz=UTC"), public.replace("%40", "@"));
+        assert_eq!(normalize_feed_url("https://calendar.google.com/calendar/htmlembed?src=team%40group.calendar.google.com"), public.replace("%40", "@"));
+
+        let secret = "https://calendar.google.com/calendar/ical/me%40gmail.com/private-0123abcd/basic.ics";
+        assert!(validate_ics_url_format(secret).is_ok());
+        assert!(validate_ics_url_format(public).is_ok());
+
+        let problem = |url: &str| validate_ics_url_format(url).unwrap_err().to_string();
+        assert!(problem("https://calendar.google.com/calendar/u/0/r").contains("Google Calendar website"));
+        assert!(problem("https://calendar.google.com/calendar/u/0?cid=bWVAZ21haWwuY29t").contains("sharing link"));
+        assert!(problem("https://calendar.google.com/calendar/ical/me%40gmail.com/public/basic.html").contains("ends in .ics"));
+        assert!(problem("https://calendar.google.com/calendar/ical/me%40gmail.com").contains("incomplete"));
+        assert!(problem(secret.trim_end_matches("/basic.ics")).contains("Secret address in iCal format"));
+
+        assert!(google_public_feed_hint(public).is_some_and(|hint| hint.contains("made public")));
+        assert_eq!(google_public_feed_hint(secret), None);
+        assert_eq!(google_public_feed_hint("https://example.com/calendar/ical/a/public/basic.ics"), None);
+    }
+
     #[test]
     fn test_validate_ics_url_format_empty() {
         let url = "   ";
@@ -226,6 +1774,13 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("local network"));
     }
 
+    #[test]
+    fn test_unfold_bytes() {
+        // A fold in the middle of a multi-byte character
+        let bytes = b"SUMMARY:Caf\xc3\r\n \xa9\r\n";
+        assert_eq!(unfold_bytes(bytes), "SUMMARY:Café\r\n".as_bytes());
+    }
+
     #[test]
     fn test_parse_ical_datetime_utc() {
         let naive = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap().and_hms_opt(12, 0, 0).unwrap();
@@ -267,7 +1822,690 @@ mod tests {
         let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
         let dt = DatePerhapsTime::Date(date);
         
+        // A date names a day, not an instant: no shift by the local offset
         let result = parse_ical_datetime(&dt);
-        assert!(result.is_some());
+        assert_eq!(result, Some(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_resolve_local_time_across_dst() {
+        let ny = chrono_tz::America::New_York;
+        let at = |y, m, d, h, min| NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, min, 0).unwrap();
+
+        // Spring forward (2024-03-10 02:00 EST -> 03:00 EDT): 02:30 doesn't
+        // exist and is read with the EST offset
+        assert_eq!(resolve_local_time(&ny, &at(2024, 3, 10, 2, 30)), Some(Utc.with_ymd_and_hms(2024, 3, 10, 7, 30, 0).unwrap()));
+        // Fall back (2024-11-03 02:00 EDT -> 01:00 EST): 01:30 happens twice; take the first
+        assert_eq!(resolve_local_time(&ny, &at(2024, 11, 3, 1, 30)), Some(Utc.with_ymd_and_hms(2024, 11, 3, 5, 30, 0).unwrap()));
+        // Either side of the switch keeps the same wall-clock time
+        assert_eq!(resolve_local_time(&ny, &at(2024, 3, 9, 9, 0)), Some(Utc.with_ymd_and_hms(2024, 3, 9, 14, 0, 0).unwrap()));
+        assert_eq!(resolve_local_time(&ny, &at(2024, 3, 11, 9, 0)), Some(Utc.with_ymd_and_hms(2024, 3, 11, 13, 0, 0).unwrap()));
+
+        // Southern hemisphere: Sydney leaves DST on 2024-04-07 (03:00 AEDT -> 02:00 AEST)
+        let sydney = chrono_tz::Australia::Sydney;
+        assert_eq!(resolve_local_time(&sydney, &at(2024, 4, 7, 2, 30)), Some(Utc.with_ymd_and_hms(2024, 4, 6, 15, 30, 0).unwrap()));
+        // A 30-minute shift: Lord Howe Island springs forward 02:00 -> 02:30
+        let lord_howe = chrono_tz::Australia::Lord_Howe;
+        assert_eq!(resolve_local_time(&lord_howe, &at(2024, 10, 6, 2, 15)), Some(Utc.with_ymd_and_hms(2024, 10, 5, 15, 45, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_resolve_local_time_at_the_edges_of_the_calendar() {
+        // Hostile feeds can name any date; out-of-range times are dropped, not panicked on
+        for tz in [chrono_tz::America::New_York, chrono_tz::Asia::Tokyo] {
+            let _ = resolve_local_time(&tz, &NaiveDateTime::MIN);
+            let _ = resolve_local_time(&tz, &NaiveDateTime::MAX);
+        }
+
+        let dt = DatePerhapsTime::DateTime(CalendarDateTime::Floating(NaiveDateTime::MAX));
+        let _ = parse_ical_datetime_with(&dt, &FloatingTime::Zone(chrono_tz::America::New_York));
+    }
+
+    #[test]
+    fn test_parse_ical_datetime_floating_in_configured_zone() {
+        let naive = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap().and_hms_opt(12, 0, 0).unwrap();
+        let dt = DatePerhapsTime::DateTime(CalendarDateTime::Floating(naive));
+
+        let floating = FloatingTime::from_setting("Europe/Berlin");
+        assert_eq!(floating, FloatingTime::Zone(chrono_tz::Europe::Berlin));
+        let result = parse_ical_datetime_with(&dt, &floating);
+        assert_eq!(result, Some(Utc.with_ymd_and_hms(2023, 1, 1, 11, 0, 0).unwrap()));
+
+        assert_eq!(FloatingTime::from_setting("local"), FloatingTime::Local);
+        assert_eq!(FloatingTime::from_setting("Not/AZone"), FloatingTime::Local);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_serves_fresh_cache_without_network() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = IcsCache::new(dir.path());
+        // Nothing listens here, so any network access would fail
+        let url = "https://127.0.0.1:9/fresh.ics";
+        let now = Utc::now();
+        cache.put(url, None, "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n", now, Some(now + chrono::Duration::minutes(10)), &FeedValidators::default()).unwrap();
+
+        let client = Client::new();
+        let body = fetch_ics_data_cached(&client, &cache, url, "test_cache_fresh", None, &CancellationToken::new()).await.unwrap();
+        assert!(body.starts_with("BEGIN:VCALENDAR"));
+    }
+
+    #[tokio::test]
+    async fn test_fresh_cache_with_same_validators_is_unchanged() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = IcsCache::new(dir.path());
+        let url = "https://127.0.0.1:9/validated.ics";
+        let now = Utc::now();
+        let validators = FeedValidators { etag: Some("\"v1\"".to_string()), last_modified: None };
+        cache.put(url, None, "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n", now, Some(now + chrono::Duration::minutes(10)), &validators).unwrap();
+
+        let client = Client::new();
+        let cancel = CancellationToken::new();
+        let fetch = fetch_ics_data_cached_if_modified(&client, &cache, url, "test_cache_validated", None, &cancel, Some(&validators)).await.unwrap();
+        assert_eq!(fetch, FeedFetch::NotModified);
+
+        let older = FeedValidators { etag: Some("\"v0\"".to_string()), last_modified: None };
+        let fetch = fetch_ics_data_cached_if_modified(&client, &cache, url, "test_cache_validated", None, &cancel, Some(&older)).await.unwrap();
+        assert!(matches!(fetch, FeedFetch::Modified { validators, .. } if validators.etag.as_deref() == Some("\"v1\"")));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_falls_back_to_stale_cache_on_failure() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = IcsCache::new(dir.path());
+        let url = "https://127.0.0.1:9/stale.ics";
+        let fetched_at = Utc::now() - chrono::Duration::hours(2);
+        cache.put(url, None, "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n", fetched_at, Some(fetched_at), &FeedValidators::default()).unwrap();
+
+        let client = Client::new();
+        let body = fetch_ics_data_cached(&client, &cache, url, "test_cache_fallback", None, &CancellationToken::new()).await.unwrap();
+        assert!(body.starts_with("BEGIN:VCALENDAR"));
+
+        // Without a cached copy the failure is returned
+        let result = fetch_ics_data_cached(&client, &cache, "https://127.0.0.1:9/missing.ics", "test_cache_fallback", None, &CancellationToken::new()).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_fetch_skips_cache_fallback() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = IcsCache::new(dir.path());
+        let url = "https://127.0.0.1:9/cancelled.ics";
+        let fetched_at = Utc::now() - chrono::Duration::hours(2);
+        cache.put(url, None, "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n", fetched_at, Some(fetched_at), &FeedValidators::default()).unwrap();
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let err = fetch_ics_data_cached(&Client::new(), &cache, url, "test_cache_cancelled", None, &cancel).await.unwrap_err();
+        assert!(err.is::<utils::retry::OperationCancelled>());
+    }
+
+    /// Serve one raw HTTP response on a local port and return its URL
+    async fn serve_once(response: Vec<u8>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket.write_all(&response).await;
+        });
+        format!("http://{}/feed.ics", addr)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_sends_credentials_and_headers() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/feed.ics", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let read = socket.read(&mut buf).await.unwrap();
+            let body = "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n";
+            let response = format!("HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n{}", body.len(), body);
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&buf[..read]).to_string()
+        });
+
+        let auth = FeedAuth {
+            basic: Some(("alice".to_string(), "s3cret".to_string())),
+            headers: vec![("X-Api-Key".to_string(), "abc123".to_string())],
+        };
+        fetch_ics_data_with_client(&Client::new(), &url, "test_feed_auth", Some(&auth)).await.unwrap();
+        let request = server.await.unwrap().to_ascii_lowercase();
+        assert!(request.contains("authorization: basic ywxpy2u6cznjcmv0"), "{}", request);
+        assert!(request.contains("x-api-key: abc123"), "{}", request);
+    }
+
+    #[tokio::test]
+    async fn test_read_body_limited_rejects_large_content_length() {
+        let url = serve_once(b"HTTP/1.1 200 OK\r\ncontent-length: 5000000\r\n\r\n".to_vec()).await;
+        let response = Client::new().get(url).send().await.unwrap();
+
+        let err = read_body_limited(response, 1024 * 1024).await.unwrap_err();
+        assert!(err.to_string().contains("too large"));
+    }
+
+    #[tokio::test]
+    async fn test_read_body_limited_stops_streaming_at_limit() {
+        let mut raw = b"HTTP/1.1 200 OK\r\nconnection: close\r\n\r\n".to_vec();
+        raw.extend(std::iter::repeat_n(b'X', 4096));
+        let url = serve_once(raw).await;
+        let response = Client::new().get(url).send().await.unwrap();
+
+        let err = read_body_limited(response, 1024).await.unwrap_err();
+        assert!(err.to_string().contains("too large"));
+    }
+
+    #[tokio::test]
+    async fn test_read_body_limited_accepts_small_body() {
+        let body = "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n";
+        let raw = format!("HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n{}", body.len(), body);
+        let url = serve_once(raw.into_bytes()).await;
+        let response = Client::new().get(url).send().await.unwrap();
+
+        assert_eq!(read_body_limited(response, 1024).await.unwrap(), body);
+    }
+
+    const SAMPLE_ICS: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//OpenChime//Test//EN\r
+BEGIN:VEVENT\r
+UID:standup@example.com\r
+SUMMARY:Standup\r
+DESCRIPTION:Join Zoom Meeting https://zoom.us/j/123456789 and bring notes\r
+DTSTART:20240115T150000Z\r
+DTEND:20240115T151500Z\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Offsite\r
+DTSTART;VALUE=DATE:20240120\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:no-start@example.com\r
+SUMMARY:Broken\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    /// Parse options whose window covers the 2024 dates the samples use
+    fn in_2024() -> ParseOptions {
+        ParseOptions {
+            window: SyncWindow {
+                start: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+                end: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            },
+            ..ParseOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_ics_events_converts_and_skips() {
+        let events = parse_ics_events_with(SAMPLE_ICS, "test", &in_2024()).unwrap();
+        assert_eq!(events.len(), 2, "event without DTSTART should be skipped");
+
+        let standup = &events[0];
+        assert_eq!(standup.external_id.as_str(), "standup@example.com");
+        assert_eq!(standup.title, "Standup");
+        assert_eq!(standup.start_time, Utc.with_ymd_and_hms(2024, 1, 15, 15, 0, 0).unwrap());
+        assert_eq!(standup.end_time, Utc.with_ymd_and_hms(2024, 1, 15, 15, 15, 0).unwrap());
+        // Only the URL is taken from the description, not the whole text
+        assert_eq!(standup.video_link.as_deref(), Some("https://zoom.us/j/123456789"));
+        assert_eq!(standup.video_platform.as_deref(), Some("Zoom"));
+    }
+
+    #[test]
+    fn test_parse_ics_events_keeps_date_only_events() {
+        let events = parse_ics_events_with(SAMPLE_ICS, "test", &in_2024()).unwrap();
+        let offsite = &events[1];
+        assert_eq!(offsite.title, "Offsite");
+        assert!(offsite.external_id.as_str().starts_with("test-"));
+        assert_eq!(offsite.end_time - offsite.start_time, Duration::days(1));
+        assert!(offsite.is_all_day);
+        assert!(!events[0].is_all_day);
+        assert!(offsite.video_link.is_none());
+    }
+
+    #[test]
+    fn test_generated_ids_are_stable() {
+        let first = parse_ics_events_with(SAMPLE_ICS, "test", &in_2024()).unwrap();
+        let second = parse_ics_events_with(SAMPLE_ICS, "test", &in_2024()).unwrap();
+        assert_eq!(first[1].external_id, second[1].external_id);
+    }
+
+    #[test]
+    fn test_events_outside_the_window_are_skipped() {
+        let options = ParseOptions {
+            window: SyncWindow {
+                start: Utc.with_ymd_and_hms(2024, 1, 16, 0, 0, 0).unwrap(),
+                end: Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap(),
+            },
+            ..ParseOptions::default()
+        };
+        let events = parse_ics_events_with(SAMPLE_ICS, "test", &options).unwrap();
+        let titles: Vec<_> = events.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["Offsite"]);
+    }
+
+    #[tokio::test]
+    async fn test_store_event_add_update_unchanged() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = crate::database::Database::open(&dir.path().join("test.db")).await.unwrap();
+        let account_id = sqlx::query("INSERT INTO accounts (provider, account_name, auth_data) VALUES ('proton', 'Test', 'https://example.com/cal.ics')")
+            .execute(&db.pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+
+        let mut event = parse_ics_events_with(SAMPLE_ICS, "test", &in_2024()).unwrap().remove(0);
+        event.account_id = account_id;
+
+        let added = match store_event(&event, &db.pool).await.unwrap() {
+            StoreOutcome::Added(stored) => stored,
+            other => panic!("expected Added, got {:?}", other),
+        };
+        assert!(added.id.is_some());
+        assert!(matches!(store_event(&event, &db.pool).await.unwrap(), StoreOutcome::Unchanged));
+
+        event.title = "Standup (moved)".to_string();
+        match store_event(&event, &db.pool).await.unwrap() {
+            StoreOutcome::Updated(stored) => {
+                assert_eq!(stored.id, added.id);
+                assert_eq!(stored.title, "Standup (moved)");
+            }
+            other => panic!("expected Updated, got {:?}", other),
+        }
+
+        // A room change is a change too
+        event.location = Some("Room 4".to_string());
+        match store_event(&event, &db.pool).await.unwrap() {
+            StoreOutcome::Updated(stored) => assert_eq!(stored.location.as_deref(), Some("Room 4")),
+            other => panic!("expected Updated, got {:?}", other),
+        }
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM events")
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_alert_state_survives_updates_until_the_start_moves() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = crate::database::Database::open(&dir.path().join("test.db")).await.unwrap();
+        let account_id = sqlx::query("INSERT INTO accounts (provider, account_name, auth_data) VALUES ('proton', 'Test', 'https://example.com/cal.ics')")
+            .execute(&db.pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+
+        let mut event = parse_ics_events_with(SAMPLE_ICS, "test", &in_2024()).unwrap().remove(0);
+        event.account_id = account_id;
+        let Ok(StoreOutcome::Added(added)) = store_event(&event, &db.pool).await else { panic!("expected Added") };
+        sqlx::query("UPDATE events SET is_dismissed = 1, has_alerted = 1, last_alert_threshold = 5, snooze_count = 2, last_snoozed_at = ? WHERE id = ?")
+            .bind(Utc::now())
+            .bind(added.id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        // Renamed: still dismissed
+        event.title = "Standup (renamed)".to_string();
+        let Ok(StoreOutcome::Updated(stored)) = store_event(&event, &db.pool).await else { panic!("expected Updated") };
+        assert!(stored.is_dismissed && stored.has_alerted);
+        assert_eq!((stored.last_alert_threshold, stored.snooze_count), (Some(5), 2));
+        assert!(stored.last_snoozed_at.is_some());
+
+        // Moved: alerts start over for the new time
+        event.start_time += Duration::hours(1);
+        event.end_time += Duration::hours(1);
+        let Ok(StoreOutcome::Updated(stored)) = store_event(&event, &db.pool).await else { panic!("expected Updated") };
+        assert!(!stored.is_dismissed && !stored.has_alerted);
+        assert_eq!((stored.last_alert_threshold, stored.snooze_count), (None, 0));
+        assert!(stored.last_snoozed_at.is_none() && stored.snoozed_until.is_none());
+    }
+
+    #[test]
+    fn test_calendar_name_and_color() {
+        let ics = "BEGIN:VCALENDAR\r\nX-WR-CALNAME:Platform\\, Infra\r\nX-APPLE-CALENDAR-COLOR:#1BADF8FF\r\nEND:VCALENDAR\r\n";
+        let feed = parse_ics_feed(ics, "test", &ParseOptions::default()).unwrap();
+        assert_eq!(feed.calendar_name.as_deref(), Some("Platform, Infra"));
+        assert_eq!(feed.calendar_color.as_deref(), Some("#1badf8"));
+
+        let feed = parse_ics_feed("BEGIN:VCALENDAR\r\nNAME:Club\r\nCOLOR:turquoise\r\nEND:VCALENDAR\r\n", "test", &ParseOptions::default()).unwrap();
+        assert_eq!(feed.calendar_name.as_deref(), Some("Club"));
+        assert_eq!(feed.calendar_color, None);
+        assert_eq!(hex_color("#12345"), None);
+    }
+
+    #[test]
+    fn test_feed_refresh_interval() {
+        let feed = |header: &str| {
+            let ics = format!("BEGIN:VCALENDAR\r\n{}END:VCALENDAR\r\n", header);
+            parse_ics_feed(&ics, "test", &ParseOptions::default()).unwrap().refresh_interval
+        };
+        assert_eq!(feed("REFRESH-INTERVAL;VALUE=DURATION:P1W\r\n"), Some(7 * 86_400));
+        assert_eq!(feed("X-PUBLISHED-TTL:PT12H\r\n"), Some(12 * 3600));
+        assert_eq!(feed("REFRESH-INTERVAL;VALUE=DURATION:PT4H\r\nX-PUBLISHED-TTL:PT1H\r\n"), Some(4 * 3600));
+        assert_eq!(feed("X-PUBLISHED-TTL:PT0S\r\n"), None);
+        assert_eq!(feed("X-PUBLISHED-TTL:soon\r\n"), None);
+        assert_eq!(feed(""), None);
+    }
+
+    #[test]
+    fn test_unescape_text() {
+        assert_eq!(unescape_text("Plain title"), "Plain title");
+        assert_eq!(unescape_text(r"Sync\, then lunch\; bring snacks"), "Sync, then lunch; bring snacks");
+        assert_eq!(unescape_text(r"Line one\nLine two\NLine three"), "Line one\nLine two\nLine three");
+        assert_eq!(unescape_text(r"C:\\Users\\alex"), r"C:\Users\alex");
+        assert_eq!(unescape_text(r"Odd \x escape\"), r"Odd \x escape\");
+    }
+
+    #[test]
+    fn test_convert_unescapes_text_fields() {
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:escaped@example.com\r\n\
+            SUMMARY:Design review\\, Q3\\; final\r\n\
+            DTSTART:20240115T150000Z\r\nDTEND:20240115T160000Z\r\n\
+            DESCRIPTION:Agenda\\nJoin: https://us02web.zoom.us/j/8501234\r\n\t5678\\nPasscode: 482910\r\n\
+            LOCATION:Room 4\\, Building B\r\n\
+            END:VEVENT\r\nEND:VCALENDAR\r\n";
+
+        let events = parse_ics_events_with(ics, "test", &in_2024()).unwrap();
+        let event = &events[0];
+        assert_eq!(event.title, "Design review, Q3; final");
+        assert_eq!(
+            event.description.as_deref(),
+            Some("Agenda\nJoin: https://us02web.zoom.us/j/85012345678\nPasscode: 482910")
+        );
+        assert_eq!(event.video_link.as_deref(), Some("https://us02web.zoom.us/j/85012345678"));
+        assert_eq!(event.meeting_passcode.as_deref(), Some("482910"));
+        assert_eq!(event.location.as_deref(), Some("Room 4, Building B"));
+        assert!(!event.is_free);
+
+        let free = parse_ics_events_with(&ics.replace("END:VEVENT", "TRANSP:TRANSPARENT\r\nEND:VEVENT"), "test", &in_2024()).unwrap();
+        assert!(free[0].is_free);
+    }
+
+    #[test]
+    fn test_url_property_is_the_preferred_join_link() {
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:url@example.com\r\n\
+            SUMMARY:Planning\r\nDTSTART:20240115T150000Z\r\n\
+            URL:https://meet.google.com/abc-defg-hij\r\n\
+            DESCRIPTION:Last time's recording: https://us02web.zoom.us/j/85012345678\\nPIN: 1234\r\n\
+            END:VEVENT\r\nEND:VCALENDAR\r\n";
+
+        let event = &parse_ics_events_with(ics, "test", &in_2024()).unwrap()[0];
+        assert_eq!(event.video_link.as_deref(), Some("https://meet.google.com/abc-defg-hij"));
+        assert_eq!(event.video_platform.as_deref(), Some("Google Meet"));
+        assert_eq!(event.meeting_passcode.as_deref(), Some("1234"));
+
+        // A URL that isn't a meeting link leaves the description's
+        let ics = ics.replace("https://meet.google.com/abc-defg-hij", "https://example.com/events/planning");
+        let event = &parse_ics_events_with(&ics, "test", &in_2024()).unwrap()[0];
+        assert_eq!(event.video_link.as_deref(), Some("https://us02web.zoom.us/j/85012345678"));
+    }
+
+    #[test]
+    fn test_convert_keeps_source_timezone() {
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            BEGIN:VEVENT\r\nUID:ny@example.com\r\nSUMMARY:NY standup\r\n\
+            DTSTART;TZID=America/New_York:20240315T090000\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:utc@example.com\r\nSUMMARY:UTC sync\r\n\
+            DTSTART:20240315T090000Z\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:unknown@example.com\r\nSUMMARY:Custom zone\r\n\
+            DTSTART;TZID=Customized Time Zone:20240315T090000\r\nEND:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+
+        let events = parse_ics_events_with(ics, "test", &in_2024()).unwrap();
+        let zone = |uid: &str| events.iter().find(|e| e.external_id.as_str() == uid).unwrap().source_timezone.clone();
+        assert_eq!(zone("ny@example.com").as_deref(), Some("America/New_York"));
+        assert_eq!(zone("utc@example.com"), None);
+        assert_eq!(zone("unknown@example.com"), None);
+    }
+
+    #[test]
+    fn test_feed_defined_timezone() {
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            BEGIN:VTIMEZONE\r\nTZID:Pacific Standard Time\r\n\
+            BEGIN:STANDARD\r\nDTSTART:16010101T020000\r\nTZOFFSETFROM:-0700\r\nTZOFFSETTO:-0800\r\n\
+            RRULE:FREQ=YEARLY;BYDAY=1SU;BYMONTH=11\r\nEND:STANDARD\r\n\
+            BEGIN:DAYLIGHT\r\nDTSTART:16010101T020000\r\nTZOFFSETFROM:-0800\r\nTZOFFSETTO:-0700\r\n\
+            RRULE:FREQ=YEARLY;BYDAY=2SU;BYMONTH=3\r\nEND:DAYLIGHT\r\nEND:VTIMEZONE\r\n\
+            BEGIN:VEVENT\r\nUID:winter@example.com\r\nSUMMARY:Winter sync\r\n\
+            DTSTART;TZID=Pacific Standard Time:20240115T090000\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:summer@example.com\r\nSUMMARY:Summer sync\r\n\
+            DTSTART;TZID=Pacific Standard Time:20240715T090000\r\nEND:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+
+        let events = parse_ics_events_with(ics, "test", &ParseOptions { floating: FloatingTime::Zone(chrono_tz::UTC), ..in_2024() }).unwrap();
+        let start = |uid: &str| events.iter().find(|e| e.external_id.as_str() == uid).unwrap().start_time;
+        assert_eq!(start("winter@example.com"), Utc.with_ymd_and_hms(2024, 1, 15, 17, 0, 0).unwrap());
+        assert_eq!(start("summer@example.com"), Utc.with_ymd_and_hms(2024, 7, 15, 16, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_recurring_events_expand_within_window() {
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            BEGIN:VEVENT\r\nUID:standup@example.com\r\nSUMMARY:Standup\r\n\
+            DTSTART;TZID=America/New_York:20240226T090000\r\nDTEND;TZID=America/New_York:20240226T091500\r\n\
+            RRULE:FREQ=WEEKLY;BYDAY=MO\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:once@example.com\r\nSUMMARY:Planning\r\n\
+            DTSTART:20240305T150000Z\r\nEND:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let options = ParseOptions {
+            window: SyncWindow {
+                start: Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap(),
+                end: Utc.with_ymd_and_hms(2024, 3, 19, 0, 0, 0).unwrap(),
+            },
+            ..ParseOptions::default()
+        };
+
+        let feed = parse_ics_feed(ics, "test", &options).unwrap();
+        assert_eq!(feed.series, vec!["standup@example.com".to_string()]);
+        assert!(feed.events.iter().filter(|e| e.title == "Standup").all(|e| {
+            feed.occurrence_series.get(e.external_id.as_str()).map(String::as_str) == Some("standup@example.com")
+        }));
+
+        let standups: Vec<_> = feed.events.iter().filter(|e| e.title == "Standup").collect();
+        let starts: Vec<_> = standups.iter().map(|e| e.start_time).collect();
+        // 9:00 in New York on both sides of the DST change on March 10
+        assert_eq!(
+            starts,
+            vec![
+                Utc.with_ymd_and_hms(2024, 3, 4, 14, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 3, 11, 13, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 3, 18, 13, 0, 0).unwrap(),
+            ]
+        );
+        assert!(standups.iter().all(|e| e.end_time - e.start_time == Duration::minutes(15)));
+        assert_eq!(standups[0].external_id.as_str(), "standup@example.com_20240304T140000Z");
+        assert!(feed.events.iter().any(|e| e.external_id.as_str() == "once@example.com"));
+    }
+
+    #[test]
+    fn test_exdate_and_rdate() {
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            BEGIN:VEVENT\r\nUID:standup@example.com\r\nSUMMARY:Standup\r\n\
+            DTSTART;TZID=America/New_York:20240226T090000\r\nRRULE:FREQ=WEEKLY;BYDAY=MO\r\n\
+            EXDATE;TZID=America/New_York:20240311T090000\r\nRDATE:20240313T170000Z\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:workshop@example.com\r\nSUMMARY:Workshop\r\n\
+            DTSTART:20240305T150000Z\r\nRDATE:20240307T150000Z,20240312T150000Z\r\nEND:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let options = ParseOptions {
+            window: SyncWindow {
+                start: Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap(),
+                end: Utc.with_ymd_and_hms(2024, 3, 19, 0, 0, 0).unwrap(),
+            },
+            ..ParseOptions::default()
+        };
+
+        let feed = parse_ics_feed(ics, "test", &options).unwrap();
+        let starts = |title: &str| feed.events.iter().filter(|e| e.title == title).map(|e| e.start_time).collect::<Vec<_>>();
+        // The holiday on the 11th is skipped, the extra session on the 13th added
+        assert_eq!(
+            starts("Standup"),
+            vec![
+                Utc.with_ymd_and_hms(2024, 3, 4, 14, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 3, 13, 17, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 3, 18, 13, 0, 0).unwrap(),
+            ]
+        );
+        // RDATEs alone make a series too
+        assert_eq!(starts("Workshop").len(), 3);
+        assert_eq!(feed.series.len(), 2);
+    }
+
+    #[test]
+    fn test_recurrence_id_replaces_occurrence() {
+        // The override comes first, as some servers write them
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            BEGIN:VEVENT\r\nUID:standup@example.com\r\nSUMMARY:Standup (moved)\r\n\
+            RECURRENCE-ID;TZID=America/New_York:20240311T090000\r\n\
+            DTSTART;TZID=America/New_York:20240312T100000\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:standup@example.com\r\nSUMMARY:Standup\r\n\
+            DTSTART;TZID=America/New_York:20240226T090000\r\nRRULE:FREQ=WEEKLY;BYDAY=MO\r\nEND:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let options = ParseOptions {
+            window: SyncWindow {
+                start: Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap(),
+                end: Utc.with_ymd_and_hms(2024, 3, 19, 0, 0, 0).unwrap(),
+            },
+            ..ParseOptions::default()
+        };
+
+        let feed = parse_ics_feed(ics, "test", &options).unwrap();
+        assert_eq!(feed.events.len(), 3);
+        let moved = feed.events.iter().find(|e| e.title == "Standup (moved)").unwrap();
+        assert_eq!(moved.external_id.as_str(), "standup@example.com_20240311T130000Z");
+        assert_eq!(moved.start_time, Utc.with_ymd_and_hms(2024, 3, 12, 14, 0, 0).unwrap());
+        assert!(!feed.events.iter().any(|e| e.start_time == Utc.with_ymd_and_hms(2024, 3, 11, 13, 0, 0).unwrap()));
+
+        // Cancelling that one occurrence drops it altogether
+        let feed = parse_ics_feed(&ics.replace("SUMMARY:Standup (moved)", "STATUS:CANCELLED"), "test", &options).unwrap();
+        assert_eq!(feed.events.len(), 2);
+        assert_eq!(feed.cancelled, vec!["standup@example.com_20240311T130000Z".to_string()]);
+        assert!(feed.events.iter().all(|e| e.title == "Standup"));
+
+        // A floating RECURRENCE-ID is in the series' zone, not the override's
+        let elsewhere = ics
+            .replace("RECURRENCE-ID;TZID=America/New_York:20240311T090000", "RECURRENCE-ID:20240311T090000")
+            .replace("DTSTART;TZID=America/New_York:20240312T100000", "DTSTART:20240312T140000Z");
+        let feed = parse_ics_feed(&elsewhere, "test", &options).unwrap();
+        assert_eq!(feed.events.len(), 3);
+        let moved = feed.events.iter().find(|e| e.title == "Standup (moved)").unwrap();
+        assert_eq!(moved.external_id.as_str(), "standup@example.com_20240311T130000Z");
+    }
+
+    #[test]
+    fn test_attendees_are_parsed() {
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            BEGIN:VEVENT\r\nUID:review@example.com\r\nSUMMARY:Design review\r\n\
+            DTSTART:20240311T150000Z\r\n\
+            ORGANIZER;CN=\"Dana Smith\":mailto:Dana@Example.com\r\n\
+            ATTENDEE;CN=Dana Smith;PARTSTAT=ACCEPTED:mailto:dana@example.com\r\n\
+            ATTENDEE;PARTSTAT=DECLINED:MAILTO:alex@example.com\r\n\
+            ATTENDEE;CN=Sam;ROLE=OPT-PARTICIPANT:mailto:sam@example.com\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:focus@example.com\r\nSUMMARY:Focus time\r\n\
+            DTSTART:20240311T170000Z\r\nEND:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+
+        let feed = parse_ics_feed(ics, "test", &in_2024()).unwrap();
+        assert_eq!(feed.events.len(), 2);
+        assert_eq!(feed.attendees["review@example.com"], vec![
+            Attendee { email: "dana@example.com".to_string(), name: Some("Dana Smith".to_string()), rsvp: Rsvp::Accepted, is_organizer: true },
+            Attendee { email: "alex@example.com".to_string(), name: None, rsvp: Rsvp::Declined, is_organizer: false },
+            Attendee { email: "sam@example.com".to_string(), name: Some("Sam".to_string()), rsvp: Rsvp::NeedsAction, is_organizer: false },
+        ]);
+        // Events without anyone listed have no entry
+        assert!(!feed.attendees.contains_key("focus@example.com"));
+    }
+
+    #[test]
+    fn test_feed_hash_is_stable() {
+        let account = Account::new_proton("Work".to_string(), "https://calendar.proton.me/a.ics".to_string());
+        let settings = crate::models::Settings { floating_timezone: "UTC".to_string(), ..Default::default() };
+        let ics = "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n";
+
+        // It's stored, so the same feed has to hash the same in every build
+        assert_eq!(feed_hash(ics, &settings, &account), "132ed533f3784aa7");
+        let shorter = crate::models::Settings { sync_days_ahead: 30, ..settings.clone() };
+        assert_ne!(feed_hash(ics, &shorter, &account), feed_hash(ics, &settings, &account));
+    }
+
+    #[test]
+    fn test_valarms_become_reminders() {
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            BEGIN:VEVENT\r\nUID:standup@example.com\r\nSUMMARY:Standup\r\n\
+            DTSTART:20240311T150000Z\r\nDTEND:20240311T153000Z\r\nRRULE:FREQ=DAILY;COUNT=2\r\n\
+            BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT5M\r\nEND:VALARM\r\n\
+            BEGIN:VALARM\r\nACTION:AUDIO\r\nTRIGGER;RELATED=END:-PT45M\r\nEND:VALARM\r\n\
+            BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER;VALUE=DATE-TIME:20240311T140000Z\r\nEND:VALARM\r\n\
+            BEGIN:VALARM\r\nACTION:EMAIL\r\nTRIGGER:-P1D\r\nEND:VALARM\r\n\
+            BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:PT10M\r\nEND:VALARM\r\n\
+            BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-P2D\r\nEND:VALARM\r\n\
+            BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT5M\r\nEND:VALARM\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:lunch@example.com\r\nSUMMARY:Lunch\r\nDTSTART:20240311T120000Z\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:review@example.com\r\nSUMMARY:Review\r\nDTSTART:20240311T170000Z\r\n\
+            BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER;VALUE=DATE-TIME:20240311T160000Z\r\nEND:VALARM\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+
+        let feed = parse_ics_feed(ics, "test", &in_2024()).unwrap();
+        let standups: Vec<_> = feed.events.iter().filter(|e| e.title == "Standup").collect();
+        assert_eq!(standups.len(), 2);
+        // Duplicates, email, after-the-start and over-a-day alarms are
+        // dropped, and so is the fixed-time one on a recurring event
+        assert!(standups.iter().all(|e| e.reminder_minutes.as_deref() == Some("15,5")));
+        assert_eq!(standups[0].calendar_reminders(), vec![15, 5]);
+        let lunch = feed.events.iter().find(|e| e.title == "Lunch").unwrap();
+        assert_eq!(lunch.reminder_minutes, None);
+        assert!(lunch.calendar_reminders().is_empty());
+        // A one-off event keeps its fixed-time alarm
+        let review = feed.events.iter().find(|e| e.title == "Review").unwrap();
+        assert_eq!(review.calendar_reminders(), vec![60]);
+    }
+
+    #[tokio::test]
+    async fn test_join_details_are_stored() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = crate::database::Database::open(&dir.path().join("test.db")).await.unwrap();
+        let account_id = sqlx::query("INSERT INTO accounts (provider, account_name, auth_data) VALUES ('proton', 'Test', 'https://example.com/cal.ics')")
+            .execute(&db.pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:zoom@example.com\r\nSUMMARY:Planning\r\n\
+            DTSTART:20240115T150000Z\r\nDTEND:20240115T160000Z\r\n\
+            DESCRIPTION:https://zoom.us/j/85012345678 Passcode: 482910 +16699006833,,85012345678# US\r\n\
+            END:VEVENT\r\nBEGIN:VEVENT\r\nUID:phone@example.com\r\nSUMMARY:Phone only\r\n\
+            DTSTART:20240115T170000Z\r\nDTEND:20240115T173000Z\r\n\
+            DESCRIPTION:Call +44 20 7946 0000 PIN: 4821\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        for mut event in parse_ics_events_with(ics, "test", &in_2024()).unwrap() {
+            event.account_id = account_id;
+            store_event(&event, &db.pool).await.unwrap();
+        }
+
+        let stored = crate::database::events::get_between(&db.pool, Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap(), Utc.with_ymd_and_hms(2024, 1, 16, 0, 0, 0).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 2);
+
+        let zoom = stored[0].video_meeting().unwrap();
+        assert_eq!(zoom.platform, "Zoom");
+        assert_eq!(zoom.meeting_id.as_deref(), Some("85012345678"));
+        assert_eq!(zoom.password.as_deref(), Some("482910"));
+        assert_eq!(zoom.dial_in.unwrap().conference_id.as_deref(), Some("85012345678"));
+
+        let phone = &stored[1];
+        assert!(phone.video_meeting().is_none());
+        assert_eq!(phone.dial_in_number.as_deref(), Some("+44 20 7946 0000"));
+        assert_eq!(phone.meeting_passcode.as_deref(), Some("4821"));
     }
 }
@@ -0,0 +1,248 @@
+// file: src/calendar/connection_test.rs
+//! The add-calendar form's **Test** button. A calendar is checked a step
+//! at a time (URL, server lookup, download, parse) without saving it, and
+//! each step says what went wrong in terms the user can act on: a mistyped
+//! host, a broken certificate, a web page where the feed should be, or a
+//! feed with nothing in the sync window.
+
+use std::time::Duration;
+
+use url::Url;
+
+use crate::calendar::caldav::CalDavSource;
+use crate::calendar::common::{self, ParseOptions};
+use crate::calendar::ics_stream;
+use crate::calendar::source::{CalendarSource, FeedAuth};
+use crate::doctor::CheckResult;
+use crate::http_config::HttpClientFactory;
+use crate::models::{Account, Settings};
+
+/// How long the server lookup may take
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Check `account`, stopping at the first step that fails
+pub async fn run(account: &Account, settings: &Settings) -> Vec<CheckResult> {
+    if account.is_local() {
+        return vec![CheckResult::pass("Calendar", "reminders are kept on this computer; nothing to fetch")];
+    }
+    #[cfg(feature = "google-api")]
+    if crate::calendar::google_api::calendar_id(account).is_some() {
+        let signed_in = crate::keyring::load(account.refresh_token.as_deref().unwrap_or_default()).await.is_ok();
+        return vec![match signed_in {
+            true => CheckResult::pass("Sign-in", "signed in to Google"),
+            false => CheckResult::fail("Sign-in", "no Google sign-in is stored for this calendar; sign in again"),
+        }];
+    }
+
+    let url = common::normalize_feed_url(&account.auth_data);
+    let mut steps = Vec::new();
+    let is_caldav = account.provider == crate::models::CalendarProvider::CalDav.as_str();
+    if let Err(e) = common::validate_ics_url_format(&url) {
+        steps.push(CheckResult::fail("URL", e.to_string()));
+        return steps;
+    }
+    steps.push(CheckResult::pass("URL", "looks like a calendar address"));
+
+    let server = lookup(&url).await;
+    let found = server.status != crate::doctor::CheckStatus::Fail;
+    steps.push(server);
+    if !found {
+        return steps;
+    }
+
+    let downloaded = if is_caldav {
+        match CalDavSource::from_account(account, tokio_util::sync::CancellationToken::new()).await {
+            Ok(source) => source.fetch_ics().await.map_err(|e| format!("{:#}", e)),
+            Err(e) => Err(e.to_string()),
+        }
+    } else {
+        match FeedAuth::from_account(account).await {
+            Ok(auth) => download(&url, auth.as_ref(), settings.max_feed_bytes()).await,
+            Err(e) => Err(e.to_string()),
+        }
+    };
+    let body = match downloaded {
+        Ok(body) => body,
+        Err(problem) => {
+            steps.push(CheckResult::fail("Download", problem));
+            return steps;
+        }
+    };
+    steps.push(CheckResult::pass("Download", format!("received {} KB", body.len().div_ceil(1024))));
+
+    steps.push(check_events(body, ParseOptions::for_account(settings, account), settings).await);
+    steps
+}
+
+/// Whether the feed's server can be found. Through a proxy the proxy does
+/// the lookup, so there's nothing to check here.
+async fn lookup(url: &str) -> CheckResult {
+    let name = "Server";
+    if let Some(proxy) = crate::http_config::configured_proxy() {
+        let host = Url::parse(&proxy).ok().and_then(|u| u.host_str().map(str::to_string)).unwrap_or(proxy);
+        return CheckResult::pass(name, format!("reached through the proxy at {}", host));
+    }
+    let Some((host, port)) = Url::parse(url).ok().and_then(|u| Some((u.host_str()?.to_string(), u.port_or_known_default()?))) else {
+        return CheckResult::fail(name, "the URL has no server name");
+    };
+    let found = tokio::time::timeout(LOOKUP_TIMEOUT, tokio::net::lookup_host((host.as_str(), port)))
+        .await
+        .map(|addresses| addresses.is_ok_and(|mut addresses| addresses.next().is_some()));
+    match found {
+        Ok(true) => CheckResult::pass(name, format!("found {}", host)),
+        Ok(false) => CheckResult::fail(name, format!("no server called {} could be found (DNS lookup failed); check the address for typos", host)),
+        Err(_) => CheckResult::fail(name, format!("looking up {} timed out; check your internet connection", host)),
+    }
+}
+
+/// Download the feed once, without the cache, retries or circuit breaker
+/// a sync goes through, so the first problem is reported as it happened
+async fn download(url: &str, auth: Option<&FeedAuth>, max_bytes: usize) -> Result<String, String> {
+    let client = HttpClientFactory::shared_ics_client().map_err(|e| format!("could not set up the connection: {}", e))?;
+    let mut request = client.get(url);
+    if let Some(auth) = auth {
+        request = auth.apply(request);
+    }
+    let response = request.send().await.map_err(|e| describe_request_error(&e))?;
+
+    let status = response.status();
+    match status.as_u16() {
+        401 | 403 if auth.is_some() => return Err(format!("the server turned down the login (HTTP {})", status.as_u16())),
+        401 | 403 => return Err(format!("the server asks for a username and password (HTTP {})", status.as_u16())),
+        404 | 410 => {
+            return Err(match common::google_public_feed_hint(url) {
+                Some(hint) => format!("Google has no public calendar at that address (HTTP {}); {}", status.as_u16(), hint),
+                None => format!("there's no calendar at that address (HTTP {}); copy the link again", status.as_u16()),
+            })
+        }
+        _ if !status.is_success() => return Err(format!("the server answered HTTP {}", status)),
+        _ => {}
+    }
+
+    let body = common::read_body_limited(response, max_bytes).await.map_err(|e| e.to_string())?;
+    let start = body.trim_start().get(..100).unwrap_or(body.trim_start()).to_ascii_lowercase();
+    if start.starts_with("<!doctype") || start.starts_with("<html") || (start.starts_with("<?xml") && body.contains("<html")) {
+        return Err("the server sent a web page (HTML) instead of a calendar file; use the calendar's ICS or iCal link, not the address of the page showing it".to_string());
+    }
+    if !ics_stream::content_lines(&body).any(|line| line.trim().eq_ignore_ascii_case("BEGIN:VCALENDAR")) {
+        return Err("the file isn't an iCalendar feed (no BEGIN:VCALENDAR)".to_string());
+    }
+    Ok(body)
+}
+
+/// A failed request in plain words, telling certificate and connection
+/// problems apart
+fn describe_request_error(error: &reqwest::Error) -> String {
+    let mut causes = Vec::new();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        causes.push(cause.to_string());
+        source = cause.source();
+    }
+    let cause = causes.last().cloned().unwrap_or_else(|| error.to_string());
+    let all = causes.join(": ").to_ascii_lowercase();
+
+    if ["certificate", "tls", "ssl", "handshake"].iter().any(|word| all.contains(word)) {
+        format!("the secure connection failed; the server's certificate may be expired or not trusted ({})", cause)
+    } else if error.is_timeout() {
+        "the server didn't answer in time".to_string()
+    } else if all.contains("dns") || all.contains("lookup") {
+        format!("the server's name couldn't be looked up ({})", cause)
+    } else if error.is_connect() {
+        format!("couldn't connect to the server ({})", cause)
+    } else {
+        format!("the request failed ({})", cause)
+    }
+}
+
+/// How many events the feed holds, and how many of those fall in the sync
+/// window
+async fn check_events(body: String, options: ParseOptions, settings: &Settings) -> CheckResult {
+    let name = "Events";
+    let total = ics_stream::event_blocks(&body).count();
+    let parsed = tokio::task::spawn_blocking(move || common::parse_ics_feed(&body, "test", &options)).await;
+    let feed = match parsed {
+        Ok(Ok(feed)) => feed,
+        Ok(Err(e)) => return CheckResult::fail(name, format!("the calendar couldn't be read: {}", e)),
+        Err(e) => return CheckResult::fail(name, format!("reading the calendar failed: {}", e)),
+    };
+    match (total, feed.events.len()) {
+        (0, _) => CheckResult::warn(name, "the calendar is reachable but has no events (0 parsed)"),
+        (_, 0) => CheckResult::warn(
+            name,
+            format!(
+                "0 events parsed: none of its {} fall between {} days ago and {} days ahead (sync_days_back, sync_days_ahead)",
+                total, settings.sync_days_back, settings.sync_days_ahead
+            ),
+        ),
+        (_, count) => CheckResult::pass(name, format!("{} events to sync", count)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::doctor::CheckStatus;
+
+    /// Serve one HTTP response with `body` on a local port and return its URL
+    async fn serve_once(status: &str, body: &str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let response = format!("HTTP/1.1 {}\r\ncontent-length: {}\r\n\r\n{}", status, body.len(), body);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket.write_all(response.as_bytes()).await;
+        });
+        format!("http://{}/feed.ics", addr)
+    }
+
+    #[tokio::test]
+    async fn test_download_problems_are_told_apart() {
+        let html = serve_once("200 OK", "<!DOCTYPE html><html><body>My calendar</body></html>").await;
+        assert!(download(&html, None, 1 << 20).await.unwrap_err().contains("web page (HTML)"));
+
+        let missing = serve_once("404 Not Found", "").await;
+        assert!(download(&missing, None, 1 << 20).await.unwrap_err().contains("no calendar at that address"));
+
+        let login = serve_once("401 Unauthorized", "").await;
+        assert!(download(&login, None, 1 << 20).await.unwrap_err().contains("username and password"));
+
+        let text = serve_once("200 OK", "hello").await;
+        assert!(download(&text, None, 1 << 20).await.unwrap_err().contains("isn't an iCalendar feed"));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let closed = format!("http://{}/feed.ics", listener.local_addr().unwrap());
+        drop(listener);
+        assert!(download(&closed, None, 1 << 20).await.unwrap_err().contains("couldn't connect"));
+    }
+
+    #[tokio::test]
+    async fn test_empty_calendars_are_reported() {
+        let settings = Settings::default();
+        let empty = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nEND:VCALENDAR\r\n".to_string();
+        let result = check_events(empty, ParseOptions::from_settings(&settings), &settings).await;
+        assert_eq!(result.status, CheckStatus::Warn);
+        assert!(result.detail.contains("no events"));
+
+        let long_ago = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:a\r\nSUMMARY:Old\r\n\
+            DTSTART:20000101T090000Z\r\nDTEND:20000101T100000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n".to_string();
+        let result = check_events(long_ago, ParseOptions::from_settings(&settings), &settings).await;
+        assert_eq!(result.status, CheckStatus::Warn);
+        assert!(result.detail.starts_with("0 events parsed: none of its 1"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_url_stops_at_the_first_step() {
+        let account = Account::new_generic("Club".to_string(), "not a url".to_string());
+        let steps = run(&account, &Settings::default()).await;
+        assert_eq!(steps.len(), 1);
+        assert_eq!((steps[0].name.as_str(), steps[0].status), ("URL", CheckStatus::Fail));
+
+        let steps = run(&Account::new_local(), &Settings::default()).await;
+        assert_eq!(steps[0].status, CheckStatus::Pass);
+    }
+}
@@ -0,0 +1,157 @@
+// file: src/calendar/discovery.rs
+//! Finding the ICS feed behind a pasted web page URL. Pasting the page a
+//! calendar is shown on, rather than its feed, is the most common mistake
+//! when adding an account; it used to end in "the server returned HTML".
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use url::Url;
+
+use crate::calendar::common::{google_embed_feed_url, normalize_feed_url, read_body_limited, validate_ics_url_format};
+use crate::http_config::{HttpClientFactory, HttpConfig};
+
+lazy_static::lazy_static! {
+    static ref TAG: Regex = Regex::new(r"(?is)<(link|a)\b([^>]*)>").unwrap();
+    static ref ATTRIBUTE: Regex = Regex::new(r#"(?is)([a-z-]+)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#).unwrap();
+}
+
+/// Whether a URL already looks like a calendar feed, so it can be linked
+/// without looking for one
+pub fn looks_like_feed_url(url: &str) -> bool {
+    let Ok(parsed) = Url::parse(url.trim()) else {
+        return false;
+    };
+    let path = parsed.path().to_ascii_lowercase();
+    matches!(parsed.scheme(), "webcal" | "webcals") || path.ends_with(".ics") || path.contains("/ical/")
+}
+
+/// The public feed for a Google Calendar embed link; see
+/// `common::google_embed_feed_url`
+pub fn google_feed_from_url(url: &Url) -> Option<String> {
+    google_embed_feed_url(url.as_str())
+}
+
+/// Calendar feeds a page links to: `<link rel="alternate"
+/// type="text/calendar">` first, then plain links to `.ics` or `webcal://`
+/// URLs. Relative links are resolved against `page`, `webcal://` becomes
+/// `https://`, and duplicates are dropped.
+pub fn feeds_in_html(page: &Url, html: &str) -> Vec<String> {
+    let mut alternates = Vec::new();
+    let mut links = Vec::new();
+
+    for tag in TAG.captures_iter(html) {
+        let attributes: Vec<(String, String)> = ATTRIBUTE
+            .captures_iter(&tag[2])
+            .map(|a| {
+                let value = a.get(2).or(a.get(3)).or(a.get(4)).map_or("", |v| v.as_str());
+                (a[1].to_ascii_lowercase(), value.trim().to_string())
+            })
+            .collect();
+        let attribute = |name: &str| attributes.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str());
+        let Some(href) = attribute("href").filter(|h| !h.is_empty()) else {
+            continue;
+        };
+
+        if tag[1].eq_ignore_ascii_case("link") {
+            let is_alternate = attribute("rel").is_some_and(|rel| rel.split_whitespace().any(|r| r.eq_ignore_ascii_case("alternate")));
+            let is_calendar = attribute("type").is_some_and(|t| t.eq_ignore_ascii_case("text/calendar"));
+            if is_alternate && is_calendar {
+                alternates.extend(resolve(page, href));
+            }
+        } else if let Some(url) = resolve(page, href).filter(|u| looks_like_feed_url(u)) {
+            links.push(url);
+        }
+    }
+
+    let mut feeds: Vec<String> = Vec::new();
+    for feed in alternates.into_iter().chain(links) {
+        if !feeds.contains(&feed) {
+            feeds.push(feed);
+        }
+    }
+    feeds
+}
+
+/// An absolute `https://` URL for a link on `page`
+fn resolve(page: &Url, href: &str) -> Option<String> {
+    let href = html_unescape(href);
+    let url = Url::parse(&normalize_feed_url(page.join(&href).ok()?.as_str())).ok()?;
+    (url.scheme() == "https").then(|| url.to_string())
+}
+
+/// `&amp;` in attribute values, which is all feed links tend to carry
+fn html_unescape(value: &str) -> String {
+    value.replace("&amp;", "&").replace("&#38;", "&")
+}
+
+/// Calendar feeds found at `url`. A URL that already serves a calendar is
+/// returned as is; a web page gives the feeds it links to, which may be none.
+pub async fn discover_feeds(url: &str) -> Result<Vec<String>> {
+    let page = Url::parse(&normalize_feed_url(url)).map_err(|e| anyhow!("Invalid URL: {}", e))?;
+    if let Some(feed) = google_feed_from_url(&page) {
+        return Ok(vec![feed]);
+    }
+    validate_ics_url_format(page.as_str())?;
+
+    let client = HttpClientFactory::shared_ics_client().map_err(|e| anyhow!("Failed to build client: {}", e))?;
+    let response = client.get(page.as_str()).send().await.map_err(|e| anyhow!("Request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(anyhow!("HTTP {} from the page", response.status().as_u16()));
+    }
+    let body = read_body_limited(response, HttpConfig::ics_fetch().max_response_bytes).await?;
+
+    if body.contains("BEGIN:VCALENDAR") {
+        return Ok(vec![page.to_string()]);
+    }
+    Ok(feeds_in_html(&page, &body)
+        .into_iter()
+        .filter(|feed| validate_ics_url_format(feed).is_ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_looks_like_feed_url() {
+        assert!(looks_like_feed_url("https://example.com/team/calendar.ICS"));
+        assert!(looks_like_feed_url("webcal://example.com/feed"));
+        assert!(looks_like_feed_url("https://calendar.google.com/calendar/ical/abc%40group.calendar.google.com/public/basic.ics"));
+        assert!(!looks_like_feed_url("https://example.com/events"));
+        assert!(!looks_like_feed_url("not a url"));
+    }
+
+    #[test]
+    fn test_google_embed_link() {
+        let embed = Url::parse("https://calendar.google.com/calendar/embed?src=team%40group.calendar.google.com&ctz=Europe%2FLondon").unwrap();
+        assert_eq!(
+            google_feed_from_url(&embed).as_deref(),
+            Some("https://calendar.google.com/calendar/ical/team@group.calendar.google.com/public/basic.ics")
+        );
+        assert_eq!(google_feed_from_url(&Url::parse("https://calendar.google.com/calendar/u/0/r").unwrap()), None);
+    }
+
+    #[test]
+    fn test_feeds_in_html() {
+        let page = Url::parse("https://club.example.org/events/").unwrap();
+        let html = r#"<html><head>
+            <link rel="stylesheet" href="/style.css">
+            <LINK TYPE="text/calendar" REL="alternate" HREF="/events/feed.ics?token=a&amp;b=1" title="Club events">
+            </head><body>
+            <a href="webcal://club.example.org/events/feed.ics?token=a&amp;b=1">Subscribe</a>
+            <a href='matches.ics'>Match days</a>
+            <a href="http://insecure.example.org/cal.ics">Old feed</a>
+            <a href="/about">About</a>
+            </body></html>"#;
+
+        assert_eq!(
+            feeds_in_html(&page, html),
+            vec![
+                "https://club.example.org/events/feed.ics?token=a&b=1".to_string(),
+                "https://club.example.org/events/matches.ics".to_string(),
+            ]
+        );
+        assert!(feeds_in_html(&page, "<html><body>Nothing here</body></html>").is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+// file: src/calendar/export.rs
+//! Writing stored events back out as an ICS file, to back up or re-share
+//! the merged agenda. A meeting synced from several calendars is written
+//! once, under the title shown in the app; recurring series are written as
+//! the occurrences that were expanded, each a plain event of its own.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+
+use crate::models::{merge_duplicates, CalendarEvent, MergedEvent};
+
+/// Longest content line, in octets, before it's folded (RFC 5545 3.1)
+const MAX_LINE_OCTETS: usize = 75;
+
+/// A VCALENDAR holding `events`
+pub fn events_to_ics(events: &[CalendarEvent], now: DateTime<Utc>) -> String {
+    meetings_to_ics(&merge_duplicates(events), now)
+}
+
+/// The undismissed events from `now` to the end of the sync window, as ICS
+pub async fn export_upcoming(pool: &SqlitePool, now: DateTime<Utc>) -> Result<(String, usize)> {
+    let events = crate::database::events::get_to_window_end(pool, now).await?;
+    let meetings = merge_duplicates(&events);
+    Ok((meetings_to_ics(&meetings, now), meetings.len()))
+}
+
+/// A VCALENDAR holding each of `meetings` once
+fn meetings_to_ics(meetings: &[MergedEvent], now: DateTime<Utc>) -> String {
+    let mut ics = String::new();
+    for line in ["BEGIN:VCALENDAR", "VERSION:2.0", "PRODID:-//OpenChime//Export//EN", "CALSCALE:GREGORIAN"] {
+        push_line(&mut ics, line);
+    }
+    for merged in meetings {
+        push_event(&mut ics, &merged.event, now);
+    }
+    push_line(&mut ics, "END:VCALENDAR");
+    ics
+}
+
+fn push_event(ics: &mut String, event: &CalendarEvent, now: DateTime<Utc>) {
+    let utc = |t: DateTime<Utc>| t.format("%Y%m%dT%H%M%SZ").to_string();
+
+    push_line(ics, "BEGIN:VEVENT");
+    push_line(ics, &format!("UID:{}", escape_text(event.external_id.as_str())));
+    push_line(ics, &format!("DTSTAMP:{}", utc(now)));
+    if event.is_all_day {
+        // All-day dates are stored as UTC midnight; DTEND is exclusive
+        let end = event.end_time.max(event.start_time + chrono::Duration::days(1));
+        push_line(ics, &format!("DTSTART;VALUE=DATE:{}", event.start_time.format("%Y%m%d")));
+        push_line(ics, &format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")));
+    } else {
+        push_line(ics, &format!("DTSTART:{}", utc(event.start_time)));
+        push_line(ics, &format!("DTEND:{}", utc(event.end_time)));
+    }
+    push_line(ics, &format!("SUMMARY:{}", escape_text(event.display_title())));
+    if let Some(description) = event.description.as_deref().filter(|d| !d.is_empty()) {
+        push_line(ics, &format!("DESCRIPTION:{}", escape_text(description)));
+    }
+    if let Some(location) = event.location.as_deref().filter(|l| !l.is_empty()) {
+        push_line(ics, &format!("LOCATION:{}", escape_text(location)));
+    }
+    if let Some(link) = &event.video_link {
+        push_line(ics, &format!("URL:{}", link));
+    }
+    if event.is_free {
+        push_line(ics, "TRANSP:TRANSPARENT");
+    }
+    for minutes in event.calendar_reminders() {
+        push_line(ics, "BEGIN:VALARM");
+        push_line(ics, "ACTION:DISPLAY");
+        push_line(ics, &format!("TRIGGER:-PT{}M", minutes));
+        push_line(ics, &format!("DESCRIPTION:{}", escape_text(event.display_title())));
+        push_line(ics, "END:VALARM");
+    }
+    push_line(ics, "END:VEVENT");
+}
+
+/// TEXT value escaping (RFC 5545 3.3.11)
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\n', '\r'], "\\n")
+}
+
+/// Append a content line, folded so no line exceeds 75 octets
+fn push_line(ics: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE_OCTETS {
+            ics.push_str("\r\n ");
+            width = 1;
+        }
+        ics.push(c);
+        width += c.len_utf8();
+    }
+    ics.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::common::{parse_ics_events_with, ParseOptions, SyncWindow};
+    use chrono::TimeZone;
+
+    const FEED: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+        BEGIN:VEVENT\r\nUID:review@example.com\r\nSUMMARY:Design review\\, Q3\r\n\
+        DTSTART:20240115T150000Z\r\nDTEND:20240115T160000Z\r\n\
+        DESCRIPTION:Agenda\\nBring the mockups; all of them\r\nLOCATION:Room 4\r\n\
+        URL:https://meet.google.com/abc-defg-hij\r\nTRANSP:TRANSPARENT\r\n\
+        BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT15M\r\nEND:VALARM\r\nEND:VEVENT\r\n\
+        BEGIN:VEVENT\r\nUID:offsite@example.com\r\nSUMMARY:Offsite\r\n\
+        DTSTART;VALUE=DATE:20240117\r\nDTEND;VALUE=DATE:20240119\r\nEND:VEVENT\r\n\
+        END:VCALENDAR\r\n";
+
+    fn in_january() -> ParseOptions {
+        ParseOptions {
+            window: SyncWindow {
+                start: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+                end: Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap(),
+            },
+            ..ParseOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_exported_events_read_back_the_same() {
+        let events = parse_ics_events_with(FEED, "test", &in_january()).unwrap();
+        let ics = events_to_ics(&events, Utc::now());
+        assert!(ics.lines().all(|line| line.len() <= MAX_LINE_OCTETS));
+
+        let exported = parse_ics_events_with(&ics, "test", &in_january()).unwrap();
+        assert_eq!(exported.len(), 2);
+        for (before, after) in events.iter().zip(&exported) {
+            assert_eq!(after.external_id, before.external_id);
+            assert_eq!(after.title, before.title);
+            assert_eq!((after.start_time, after.end_time), (before.start_time, before.end_time));
+            assert_eq!(after.is_all_day, before.is_all_day);
+            assert_eq!(after.description, before.description);
+            assert_eq!(after.location, before.location);
+            assert_eq!(after.video_link, before.video_link);
+            assert_eq!(after.is_free, before.is_free);
+            assert_eq!(after.calendar_reminders(), before.calendar_reminders());
+        }
+    }
+
+    #[test]
+    fn test_duplicates_and_renames() {
+        let mut events = parse_ics_events_with(FEED, "test", &in_january()).unwrap();
+        events[0].title_override = Some("Q3 review".to_string());
+        let mut copy = events[1].clone();
+        copy.account_id = 2;
+        events.push(copy);
+
+        let ics = events_to_ics(&events, Utc::now());
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("SUMMARY:Q3 review\r\n"));
+    }
+
+    #[test]
+    fn test_long_lines_are_folded() {
+        let mut ics = String::new();
+        push_line(&mut ics, &format!("DESCRIPTION:{}", "é".repeat(60)));
+        let lines: Vec<&str> = ics.split("\r\n").filter(|l| !l.is_empty()).collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|l| l.len() <= MAX_LINE_OCTETS));
+        assert!(lines[1].starts_with(' '));
+    }
+}
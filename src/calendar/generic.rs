@@ -0,0 +1,14 @@
+// Any other ICS feed: Meetup groups, school and sports calendars, holiday
+// lists. Synced like the Proton and Google feeds, behind its own circuit
+// breaker so a flaky community feed can't pause those.
+
+use crate::models::{Account, SyncResult};
+use crate::calendar::cache::IcsCache;
+use crate::calendar::common;
+use anyhow::Result;
+use tokio_util::sync::CancellationToken;
+use sqlx::SqlitePool;
+
+pub async fn sync_generic_calendar(account: &Account, pool: &SqlitePool, cache: &IcsCache, cancel: &CancellationToken) -> Result<SyncResult> {
+    common::sync_ics_account(account, pool, &super::account_breaker_name("generic_calendar", account), "generic", cache, cancel).await
+}
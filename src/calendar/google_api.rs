@@ -0,0 +1,543 @@
+// file: src/calendar/google_api.rs
+//! Google Calendar through its API rather than a secret ICS address, for
+//! Workspace domains whose admins turn those addresses off. Built only with
+//! the `google-api` cargo feature.
+//!
+//! Sign-in uses the OAuth device flow (`openchime google-login`), with the
+//! OAuth client read from `OPENCHIME_GOOGLE_CLIENT_ID` and
+//! `OPENCHIME_GOOGLE_CLIENT_SECRET`. The refresh token goes to the OS
+//! keyring; the account's `refresh_token` column only names the keyring
+//! entry. Syncs are incremental through the API's `syncToken`.
+//!
+//! Such accounts are Google accounts whose `auth_data` is
+//! `google-api:<calendar id>` instead of a feed URL.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use tokio_util::sync::CancellationToken;
+
+use crate::calendar::common::{store_event, StoreOutcome};
+use crate::http_config::HttpClientFactory;
+use crate::keyring;
+use crate::models::{Account, CalendarEvent, EventChanges, EventId, SyncResult};
+use crate::utils::retry::OperationCancelled;
+
+const DEVICE_CODE_URL: &str = "https://oauth2.googleapis.com/device/code";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const EVENTS_URL: &str = "https://www.googleapis.com/calendar/v3/calendars";
+const SCOPE: &str = "https://www.googleapis.com/auth/calendar.readonly";
+
+/// `auth_data` prefix marking an API account
+const AUTH_PREFIX: &str = "google-api:";
+
+/// The calendar an API account syncs, or `None` for an ICS account
+pub fn calendar_id(account: &Account) -> Option<&str> {
+    account.auth_data.strip_prefix(AUTH_PREFIX).filter(|id| !id.is_empty())
+}
+
+/// A Google account synced through the API
+pub fn new_account(account_name: String, calendar_id: &str, keyring_key: String) -> Account {
+    Account::new_google(account_name, format!("{}{}", AUTH_PREFIX, calendar_id), Some(keyring_key))
+}
+
+/// The OAuth client OpenChime signs in as
+#[derive(Debug, Clone)]
+pub struct OAuthClient {
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+/// What the user has to do to finish signing in
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceAuthorization {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_url: String,
+    pub expires_in: u64,
+    #[serde(default = "default_poll_interval")]
+    pub interval: u64,
+}
+
+fn default_poll_interval() -> u64 {
+    5
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+impl TokenResponse {
+    fn error_message(&self) -> String {
+        match (&self.error, &self.error_description) {
+            (Some(error), Some(description)) => format!("{}: {}", error, description),
+            (Some(error), None) => error.clone(),
+            _ => "no access token in the response".to_string(),
+        }
+    }
+}
+
+impl OAuthClient {
+    /// The client configured in the environment
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .ok_or_else(|| anyhow!("{} is not set; create a \"TVs and Limited Input devices\" OAuth client in Google Cloud and set it", name))
+        };
+        Ok(Self {
+            client_id: var("OPENCHIME_GOOGLE_CLIENT_ID")?,
+            client_secret: var("OPENCHIME_GOOGLE_CLIENT_SECRET")?,
+        })
+    }
+
+    /// Start the device flow
+    pub async fn request_device_code(&self, http: &Client) -> Result<DeviceAuthorization> {
+        let response = http
+            .post(DEVICE_CODE_URL)
+            .form(&[("client_id", self.client_id.as_str()), ("scope", SCOPE)])
+            .send()
+            .await
+            .context("Failed to reach Google sign-in")?;
+        if !response.status().is_success() {
+            bail!("Google sign-in refused the request (HTTP {})", response.status().as_u16());
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Poll until the user approves (or refuses) the sign-in; returns the
+    /// refresh token
+    pub async fn wait_for_authorization(&self, http: &Client, authorization: &DeviceAuthorization) -> Result<String> {
+        let deadline = Utc::now() + Duration::seconds(authorization.expires_in as i64);
+        let mut interval = authorization.interval.max(1);
+
+        while Utc::now() < deadline {
+            tokio::time::sleep(std::time::Duration::from_secs(interval)).await;
+            let token: TokenResponse = http
+                .post(TOKEN_URL)
+                .form(&[
+                    ("client_id", self.client_id.as_str()),
+                    ("client_secret", self.client_secret.as_str()),
+                    ("device_code", authorization.device_code.as_str()),
+                    ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                ])
+                .send()
+                .await?
+                .json()
+                .await?;
+
+            match token.error.as_deref() {
+                None => return token.refresh_token.ok_or_else(|| anyhow!("Google didn't return a refresh token")),
+                Some("authorization_pending") => {}
+                Some("slow_down") => interval += 5,
+                Some(_) => bail!("Google sign-in failed: {}", token.error_message()),
+            }
+        }
+        bail!("Google sign-in timed out; run the command again")
+    }
+
+    /// A fresh access token for a stored refresh token
+    pub async fn access_token(&self, http: &Client, refresh_token: &str) -> Result<String> {
+        let token: TokenResponse = http
+            .post(TOKEN_URL)
+            .form(&[
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+                ("refresh_token", refresh_token),
+                ("grant_type", "refresh_token"),
+            ])
+            .send()
+            .await
+            .context("Failed to reach Google sign-in")?
+            .json()
+            .await?;
+        token
+            .access_token
+            .clone()
+            .ok_or_else(|| anyhow!("Google refused the stored sign-in ({}); run `openchime google-login` again", token.error_message()))
+    }
+}
+
+/// An event as the Calendar API returns it
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiEvent {
+    pub id: String,
+    pub status: Option<String>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    /// "transparent" for events shown as free
+    pub transparency: Option<String>,
+    pub start: Option<ApiTime>,
+    pub end: Option<ApiTime>,
+    pub hangout_link: Option<String>,
+    pub conference_data: Option<ConferenceData>,
+    #[serde(default)]
+    pub attendees: Vec<ApiAttendee>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTime {
+    pub date_time: Option<DateTime<FixedOffset>>,
+    pub date: Option<NaiveDate>,
+    pub time_zone: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiAttendee {
+    /// Whether this attendee is the signed-in user
+    #[serde(rename = "self", default)]
+    pub is_self: bool,
+    pub response_status: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConferenceData {
+    #[serde(default)]
+    pub entry_points: Vec<EntryPoint>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryPoint {
+    pub entry_point_type: String,
+    pub uri: String,
+}
+
+impl ApiTime {
+    fn to_utc(&self) -> Option<DateTime<Utc>> {
+        match (self.date_time, self.date) {
+            (Some(date_time), _) => Some(date_time.with_timezone(&Utc)),
+            // All-day events are stored at UTC midnight, as from ICS feeds
+            (None, Some(date)) => Some(date.and_time(chrono::NaiveTime::MIN).and_utc()),
+            (None, None) => None,
+        }
+    }
+}
+
+impl ApiEvent {
+    /// Whether the event should be removed: cancelled, or declined by the user
+    pub fn is_gone(&self) -> bool {
+        self.status.as_deref() == Some("cancelled")
+            || self.attendees.iter().any(|a| a.is_self && a.response_status.as_deref() == Some("declined"))
+    }
+
+    /// The event as OpenChime stores it; `None` when it should be removed
+    /// or has no start. `account_id` is left at 0.
+    pub fn to_calendar_event(&self) -> Option<CalendarEvent> {
+        if self.is_gone() {
+            return None;
+        }
+        let start = self.start.as_ref()?;
+        let start_time = start.to_utc()?;
+        let is_all_day = start.date_time.is_none();
+        let end_time = self
+            .end
+            .as_ref()
+            .and_then(ApiTime::to_utc)
+            .unwrap_or(start_time + if is_all_day { Duration::days(1) } else { Duration::hours(1) });
+
+        let description = self.description.as_deref().map(crate::utils::html::html_to_text);
+        let video_entry = self
+            .conference_data
+            .iter()
+            .flat_map(|data| &data.entry_points)
+            .find(|entry| entry.entry_point_type == "video")
+            .map(|entry| entry.uri.as_str())
+            .or(self.hangout_link.as_deref());
+        let link_text = format!("{} {}", self.location.as_deref().unwrap_or(""), video_entry.unwrap_or(""));
+        let video = crate::utils::extract_video_link(description.as_deref(), Some(&link_text));
+        let invite_text = format!("{} {}", description.as_deref().unwrap_or(""), link_text);
+        let (meeting_id, passcode, dial_in) = match &video {
+            Some(info) => (info.meeting_id.clone(), info.password.clone(), info.dial_in.clone()),
+            None => (None, crate::utils::extract_meeting_password(&invite_text), crate::utils::extract_dial_in(&invite_text)),
+        };
+
+        let now = Utc::now();
+        Some(CalendarEvent {
+            id: None,
+            external_id: self.id.clone().into(),
+            account_id: 0,
+            title: self.summary.clone().filter(|s| !s.trim().is_empty()).unwrap_or_else(|| "Untitled Event".to_string()),
+            description,
+            start_time,
+            end_time,
+            video_link: video.as_ref().map(|info| info.url.clone()),
+            video_platform: video.map(|info| info.platform),
+            snooze_count: 0,
+            has_alerted: false,
+            last_alert_threshold: None,
+            is_dismissed: false,
+            last_snoozed_at: None,
+            is_all_day,
+            meeting_id,
+            meeting_passcode: passcode,
+            dial_in_number: dial_in.as_ref().map(|d| d.number.clone()),
+            dial_in_conference_id: dial_in.and_then(|d| d.conference_id),
+            title_override: None,
+            source_timezone: start.time_zone.as_deref().and_then(|tz| tz.parse::<chrono_tz::Tz>().ok()).map(|tz| tz.name().to_string()),
+            snoozed_until: None,
+            location: self.location.clone(),
+            reminder_minutes: None,
+            is_free: self.transparency.as_deref() == Some("transparent"),
+            created_at: now,
+            updated_at: now,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventsPage {
+    #[serde(default)]
+    items: Vec<ApiEvent>,
+    next_page_token: Option<String>,
+    next_sync_token: Option<String>,
+}
+
+/// The saved sync token is too old; Google wants a full sync
+#[derive(Debug)]
+struct SyncTokenExpired;
+
+impl std::fmt::Display for SyncTokenExpired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("sync token expired")
+    }
+}
+
+impl std::error::Error for SyncTokenExpired {}
+
+/// Events changed since `sync_token` (all of them without one), and the
+/// token for next time
+async fn fetch_changes(
+    http: &Client,
+    access_token: &str,
+    calendar_id: &str,
+    sync_token: Option<&str>,
+    cancel: &CancellationToken,
+) -> Result<(Vec<ApiEvent>, String)> {
+    let mut url = url::Url::parse(EVENTS_URL)?;
+    url.path_segments_mut().map_err(|_| anyhow!("Invalid events URL"))?.extend([calendar_id, "events"]);
+
+    let mut events = Vec::new();
+    let mut page_token: Option<String> = None;
+    loop {
+        let mut query = vec![("singleEvents", "true".to_string()), ("maxResults", "2500".to_string())];
+        match sync_token {
+            Some(token) => query.push(("syncToken", token.to_string())),
+            // The first sync skips what's long over; later ones only get changes
+            None => query.push(("timeMin", (Utc::now() - Duration::days(30)).to_rfc3339())),
+        }
+        if let Some(token) = &page_token {
+            query.push(("pageToken", token.clone()));
+        }
+
+        let request = http.get(url.clone()).bearer_auth(access_token).query(&query).send();
+        let response = tokio::select! {
+            response = request => response.context("Failed to reach the Google Calendar API")?,
+            _ = cancel.cancelled() => return Err(OperationCancelled.into()),
+        };
+        match response.status().as_u16() {
+            410 => return Err(SyncTokenExpired.into()),
+            status if !(200..300).contains(&status) => {
+                let body = response.text().await.unwrap_or_default();
+                bail!("Google Calendar API returned HTTP {}: {}", status, body.chars().take(200).collect::<String>());
+            }
+            _ => {}
+        }
+
+        let page: EventsPage = response.json().await?;
+        events.extend(page.items);
+        match (page.next_page_token, page.next_sync_token) {
+            (Some(next), _) => page_token = Some(next),
+            (None, Some(next_sync_token)) => return Ok((events, next_sync_token)),
+            (None, None) => bail!("Google Calendar API returned neither a next page nor a sync token"),
+        }
+    }
+}
+
+/// Sync an API account: only what changed since the last sync, or
+/// everything when there is no sync token yet (or Google expired it)
+pub async fn sync_account(account: &Account, pool: &SqlitePool, cancel: &CancellationToken) -> Result<SyncResult> {
+    let account_id = account.id.unwrap_or(0);
+    let calendar_id = calendar_id(account).ok_or_else(|| anyhow!("{} isn't a Google Calendar API account", account.account_name))?;
+    let keyring_key = account
+        .refresh_token
+        .as_deref()
+        .ok_or_else(|| anyhow!("No Google sign-in stored for {}; run `openchime google-login` again", account.account_name))?;
+    let refresh_token = keyring::load(keyring_key)
+        .await
+        .with_context(|| format!("Google sign-in for {} not found; run `openchime google-login` again", account.account_name))?;
+
+    let oauth = OAuthClient::from_env()?;
+    let http = HttpClientFactory::shared_ics_client().map_err(|e| anyhow!("Failed to build client: {}", e))?;
+    let access_token = oauth.access_token(&http, &refresh_token).await?;
+
+    let saved_token = crate::database::accounts::sync_token(pool, account_id).await?;
+    let (events, next_token, full) = match fetch_changes(&http, &access_token, calendar_id, saved_token.as_deref(), cancel).await {
+        Ok((events, next)) => (events, next, saved_token.is_none()),
+        Err(e) if e.is::<SyncTokenExpired>() => {
+            log::info!("Google sync token for {} expired; doing a full sync", account.account_name);
+            let (events, next) = fetch_changes(&http, &access_token, calendar_id, None, cancel).await?;
+            (events, next, true)
+        }
+        Err(e) => return Err(e),
+    };
+
+    let mut changes = EventChanges::default();
+    let (_turn, mut tx) = crate::database::begin_store(pool).await?;
+    for api_event in &events {
+        match api_event.to_calendar_event() {
+            Some(mut event) => {
+                event.account_id = account_id;
+                match store_event(&event, &mut *tx).await? {
+                    StoreOutcome::Added(stored) => changes.added.push(stored),
+                    StoreOutcome::Updated(stored) => changes.updated.push(stored),
+                    StoreOutcome::Unchanged => {}
+                }
+            }
+            None => {
+                let removed: Option<EventId> = sqlx::query_scalar("SELECT id FROM events WHERE account_id = ? AND external_id = ? AND removed_at IS NULL")
+                    .bind(account_id)
+                    .bind(&api_event.id)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+                if let Some(event_id) = removed {
+                    crate::database::events::remove_from_feed(&mut tx, event_id).await?;
+                    changes.removed.push(event_id);
+                }
+            }
+        }
+    }
+
+    // A full sync lists every live event, so anything else is gone
+    if full {
+        let seen: Vec<&str> = events.iter().filter(|e| !e.is_gone()).map(|e| e.id.as_str()).collect();
+        let stored: Vec<(EventId, String)> = sqlx::query_as("SELECT id, external_id FROM events WHERE account_id = ? AND removed_at IS NULL")
+            .bind(account_id)
+            .fetch_all(&mut *tx)
+            .await?;
+        for (event_id, external_id) in stored.into_iter().filter(|(_, external_id)| !seen.contains(&external_id.as_str())) {
+            crate::database::events::remove_from_feed(&mut tx, event_id).await?;
+            log::debug!("Removed event {} no longer in Google Calendar", external_id);
+            changes.removed.push(event_id);
+        }
+    }
+    tx.commit().await?;
+
+    crate::database::accounts::set_sync_token(pool, account_id, Some(&next_token)).await?;
+    log::info!(
+        "Google Calendar API sync for {}: {} added, {} updated, {} removed",
+        account.account_name,
+        changes.added.len(),
+        changes.updated.len(),
+        changes.removed.len()
+    );
+    Ok(SyncResult::with_changes(account_id, changes))
+}
+
+/// Sign in with the device flow and add an API account for `calendar_id`
+/// (`primary` for the user's main calendar). `prompt` is shown the code to
+/// enter and where.
+pub async fn sign_in(pool: &SqlitePool, account_name: &str, calendar_id: &str, prompt: impl Fn(&DeviceAuthorization)) -> Result<i64> {
+    let oauth = OAuthClient::from_env()?;
+    let http = HttpClientFactory::shared_ics_client().map_err(|e| anyhow!("Failed to build client: {}", e))?;
+
+    let authorization = oauth.request_device_code(&http).await?;
+    prompt(&authorization);
+    let refresh_token = oauth.wait_for_authorization(&http, &authorization).await?;
+
+    let key = keyring::new_key("google-api");
+    keyring::store(&key, "OpenChime Google sign-in", &refresh_token).await?;
+    match crate::database::accounts::add(pool, &new_account(account_name.to_string(), calendar_id, key.clone())).await {
+        Ok(account_id) => Ok(account_id),
+        Err(e) => {
+            keyring::delete(&key).await;
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn parse(json: &str) -> ApiEvent {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_api_account_marker() {
+        let api = new_account("Work".to_string(), "primary", "google-api-1".to_string());
+        assert_eq!(calendar_id(&api), Some("primary"));
+        assert_eq!(api.refresh_token.as_deref(), Some("google-api-1"));
+
+        let ics = Account::new_google("Work".to_string(), "https://calendar.google.com/calendar/ical/x/basic.ics".to_string(), None);
+        assert_eq!(calendar_id(&ics), None);
+    }
+
+    #[test]
+    fn test_timed_event_with_meet_link() {
+        let event = parse(r#"{
+            "id": "abc123_20240304T150000Z",
+            "status": "confirmed",
+            "summary": "Design review",
+            "start": {"dateTime": "2024-03-04T10:00:00-05:00", "timeZone": "America/New_York"},
+            "end": {"dateTime": "2024-03-04T11:00:00-05:00"},
+            "hangoutLink": "https://meet.google.com/abc-defg-hij",
+            "attendees": [{"email": "me@example.com", "self": true, "responseStatus": "accepted"}]
+        }"#);
+
+        let stored = event.to_calendar_event().unwrap();
+        assert_eq!(stored.external_id.as_str(), "abc123_20240304T150000Z");
+        assert_eq!(stored.start_time, Utc.with_ymd_and_hms(2024, 3, 4, 15, 0, 0).unwrap());
+        assert_eq!(stored.end_time - stored.start_time, Duration::hours(1));
+        assert!(!stored.is_all_day);
+        assert_eq!(stored.video_link.as_deref(), Some("https://meet.google.com/abc-defg-hij"));
+        assert_eq!(stored.source_timezone.as_deref(), Some("America/New_York"));
+    }
+
+    #[test]
+    fn test_all_day_event() {
+        let event = parse(r#"{"id": "holiday", "summary": "Offsite", "start": {"date": "2024-03-04"}, "end": {"date": "2024-03-05"}, "transparency": "transparent"}"#);
+        let stored = event.to_calendar_event().unwrap();
+        assert!(stored.is_all_day);
+        assert!(stored.is_free);
+        assert_eq!(stored.start_time, Utc.with_ymd_and_hms(2024, 3, 4, 0, 0, 0).unwrap());
+        assert_eq!(stored.end_time - stored.start_time, Duration::days(1));
+    }
+
+    #[test]
+    fn test_cancelled_and_declined_events_are_removed() {
+        let cancelled = parse(r#"{"id": "gone", "status": "cancelled"}"#);
+        assert!(cancelled.is_gone());
+        assert!(cancelled.to_calendar_event().is_none());
+
+        let declined = parse(r#"{
+            "id": "nope", "summary": "Optional sync",
+            "start": {"dateTime": "2024-03-04T10:00:00Z"},
+            "attendees": [{"self": true, "responseStatus": "declined"}, {"responseStatus": "accepted"}]
+        }"#);
+        assert!(declined.is_gone());
+
+        // Someone else declining doesn't matter
+        let others = parse(r#"{
+            "id": "ok", "start": {"dateTime": "2024-03-04T10:00:00Z"},
+            "attendees": [{"responseStatus": "declined"}]
+        }"#);
+        assert!(!others.is_gone());
+        assert_eq!(others.to_calendar_event().unwrap().title, "Untitled Event");
+    }
+}
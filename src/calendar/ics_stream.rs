@@ -0,0 +1,200 @@
+// file: src/calendar/ics_stream.rs
+//! Reading a feed one content line, and one VEVENT, at a time.
+//!
+//! University and room-booking feeds can hold tens of thousands of events.
+//! Rather than unfolding a copy of the whole feed and building a component
+//! tree for all of it, lines are unfolded as they are read and each VEVENT
+//! is handed to the ICS parser on its own, so memory use beyond the body
+//! itself stays at about one event.
+
+use std::borrow::Cow;
+
+use crate::calendar::recurrence::RawProperty;
+
+/// The unfolded content lines of a feed, without line endings. Lines that
+/// aren't folded are borrowed from the feed.
+#[derive(Debug, Clone)]
+pub struct ContentLines<'a> {
+    rest: &'a str,
+}
+
+/// Iterate over the unfolded content lines of `ics`
+pub fn content_lines(ics: &str) -> ContentLines<'_> {
+    ContentLines { rest: ics }
+}
+
+impl<'a> ContentLines<'a> {
+    /// The next physical line, without its line ending
+    fn physical_line(&mut self) -> Option<&'a str> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let (line, rest) = match self.rest.find('\n') {
+            Some(end) => (&self.rest[..end], &self.rest[end + 1..]),
+            None => (self.rest, ""),
+        };
+        self.rest = rest;
+        Some(line.strip_suffix('\r').unwrap_or(line))
+    }
+
+    fn continues(&self) -> bool {
+        self.rest.starts_with([' ', '\t'])
+    }
+}
+
+impl<'a> Iterator for ContentLines<'a> {
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.physical_line()?;
+        if !self.continues() {
+            return Some(Cow::Borrowed(first));
+        }
+        let mut line = first.to_string();
+        while self.continues() {
+            // Drop the space or tab that marks the fold
+            let continuation = self.physical_line().unwrap_or_default();
+            line.push_str(&continuation[1..]);
+        }
+        Some(Cow::Owned(line))
+    }
+}
+
+/// A top-level VEVENT as read from the feed
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventBlock {
+    /// The unfolded text from `BEGIN:VEVENT` to `END:VEVENT`, nested
+    /// components (VALARM) included
+    pub text: String,
+    /// The event's own properties in feed order, with repeated ones (several
+    /// EXDATE lines, say) all kept
+    pub properties: Vec<RawProperty>,
+    /// The properties of each VALARM inside the event
+    pub alarms: Vec<Vec<RawProperty>>,
+}
+
+impl EventBlock {
+    /// The event as a calendar of its own, for the ICS parser
+    pub fn as_calendar(&self) -> String {
+        format!("BEGIN:VCALENDAR\r\n{}END:VCALENDAR\r\n", self.text)
+    }
+}
+
+/// The top-level VEVENTs of a feed, one at a time and in feed order
+#[derive(Debug, Clone)]
+pub struct EventBlocks<'a> {
+    lines: ContentLines<'a>,
+    depth: usize,
+}
+
+/// Iterate over the VEVENTs of `ics`
+pub fn event_blocks(ics: &str) -> EventBlocks<'_> {
+    EventBlocks { lines: content_lines(ics), depth: 0 }
+}
+
+impl Iterator for EventBlocks<'_> {
+    type Item = EventBlock;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut current: Option<EventBlock> = None;
+        let mut in_alarm = false;
+
+        for line in self.lines.by_ref() {
+            let Some(property) = RawProperty::parse(&line) else { continue };
+            let starts_event = property.name == "BEGIN" && self.depth == 1 && property.value.trim().eq_ignore_ascii_case("VEVENT");
+            match property.name.as_str() {
+                "BEGIN" => self.depth += 1,
+                "END" => self.depth = self.depth.saturating_sub(1),
+                _ => {}
+            }
+            if starts_event {
+                current = Some(EventBlock { text: String::new(), properties: Vec::new(), alarms: Vec::new() });
+            }
+            let Some(event) = current.as_mut() else { continue };
+
+            event.text.push_str(&line);
+            event.text.push_str("\r\n");
+            match property.name.as_str() {
+                "END" if self.depth == 1 => return current,
+                "BEGIN" if self.depth == 3 && property.value.trim().eq_ignore_ascii_case("VALARM") => {
+                    event.alarms.push(Vec::new());
+                    in_alarm = true;
+                }
+                "END" if self.depth == 2 => in_alarm = false,
+                "BEGIN" | "END" => {}
+                _ if self.depth == 2 => event.properties.push(property),
+                _ if self.depth == 3 && in_alarm => {
+                    if let Some(alarm) = event.alarms.last_mut() {
+                        alarm.push(property);
+                    }
+                }
+                _ => {}
+            }
+        }
+        // A feed cut off inside an event
+        None
+    }
+}
+
+/// The VCALENDAR's own properties (X-WR-CALNAME, X-APPLE-CALENDAR-COLOR),
+/// read up to its first VEVENT, where feeds put them
+pub fn calendar_properties(ics: &str) -> Vec<RawProperty> {
+    let mut depth = 0usize;
+    let mut properties = Vec::new();
+    for line in content_lines(ics) {
+        let Some(property) = RawProperty::parse(&line) else { continue };
+        match property.name.as_str() {
+            "BEGIN" if depth == 1 && property.value.trim().eq_ignore_ascii_case("VEVENT") => break,
+            "BEGIN" => depth += 1,
+            "END" => depth = depth.saturating_sub(1),
+            _ if depth == 1 => properties.push(property),
+            _ => {}
+        }
+    }
+    properties
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_lines() {
+        let lines: Vec<Cow<str>> = content_lines("SUMMARY:One line\r\nDESCRIPTION:Fol\r\n ded\n\t and more\r\n\r\nEND:VEVENT").collect();
+        assert_eq!(lines, vec!["SUMMARY:One line", "DESCRIPTION:Folded and more", "", "END:VEVENT"]);
+        assert!(matches!(lines[0], Cow::Borrowed(_)));
+        assert_eq!(content_lines("").count(), 0);
+    }
+
+    #[test]
+    fn test_event_blocks() {
+        let ics = "BEGIN:VCALENDAR\nBEGIN:VTIMEZONE\nTZID:X\nEND:VTIMEZONE\nBEGIN:VEVENT\nUID:a\n\
+            EXDATE;TZID=\"Europe/London\":20240311T090000\nEXDATE;TZID=Europe/London:20240318T090000\n\
+            BEGIN:VALARM\nTRIGGER:-PT5M\nEND:VALARM\nEND:VEVENT\nBEGIN:VEVENT\nUID:b\nEND:VEVENT\nEND:VCALENDAR\n";
+        let events: Vec<EventBlock> = event_blocks(ics).collect();
+        assert_eq!(events.len(), 2);
+        let exdates: Vec<&RawProperty> = events[0].properties.iter().filter(|p| p.name == "EXDATE").collect();
+        assert_eq!(exdates.len(), 2);
+        assert_eq!(exdates[0].param("TZID"), Some("Europe/London"));
+        assert_eq!(exdates[0].value, "20240311T090000");
+        assert!(!events[0].properties.iter().any(|p| p.name == "TRIGGER"));
+        assert!(events[0].text.contains("BEGIN:VALARM\r\nTRIGGER:-PT5M\r\nEND:VALARM\r\n"));
+        assert_eq!(events[0].alarms, vec![vec![RawProperty { name: "TRIGGER".to_string(), params: Vec::new(), value: "-PT5M".to_string() }]]);
+        assert!(events[1].alarms.is_empty());
+        assert_eq!(events[1].properties, vec![RawProperty { name: "UID".to_string(), params: Vec::new(), value: "b".to_string() }]);
+        assert_eq!(events[1].text, "BEGIN:VEVENT\r\nUID:b\r\nEND:VEVENT\r\n");
+
+        // An event the feed breaks off in the middle of is dropped
+        let truncated: Vec<EventBlock> = event_blocks("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:a\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nUID:b").collect();
+        assert_eq!(truncated.len(), 1);
+    }
+
+    #[test]
+    fn test_calendar_properties() {
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nX-WR-CALNAME:Team\r\n\
+            BEGIN:VTIMEZONE\r\nTZID:X\r\nEND:VTIMEZONE\r\nX-APPLE-CALENDAR-COLOR:#1BADF8\r\n\
+            BEGIN:VEVENT\r\nUID:a\r\nEND:VEVENT\r\nX-LATE:ignored\r\nEND:VCALENDAR\r\n";
+        let names: Vec<String> = calendar_properties(ics).into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["VERSION", "X-WR-CALNAME", "X-APPLE-CALENDAR-COLOR"]);
+    }
+}
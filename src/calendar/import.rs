@@ -0,0 +1,70 @@
+// file: src/calendar/import.rs
+//! Adding events from ICS text, such as a meeting invite that arrived as an
+//! `.ics` attachment. Imported events go into a local "Imported" account
+//! that never syncs; importing the same invite again updates its event,
+//! and an imported cancellation removes it.
+
+use anyhow::{anyhow, Result};
+use sqlx::SqlitePool;
+
+use crate::calendar::common::{parse_ics_feed, store_feed_events, ParseOptions};
+use crate::models::{EventChanges, SyncResult};
+
+/// Largest ICS file read for an import
+const MAX_IMPORT_BYTES: u64 = 10 * 1024 * 1024;
+
+/// The ICS text to import: `input` itself when it's pasted calendar text,
+/// otherwise the contents of the file it names
+pub async fn read_input(input: &str) -> Result<String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(anyhow!("Paste the ICS text or the path to an .ics file"));
+    }
+    if is_ics_text(input) {
+        return Ok(input.to_string());
+    }
+
+    let path = match input.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().ok_or_else(|| anyhow!("No home directory"))?.join(rest),
+        None => std::path::PathBuf::from(input.trim_matches(['"', '\''])),
+    };
+    let size = tokio::fs::metadata(&path).await.map_err(|e| anyhow!("Can't open {}: {}", path.display(), e))?.len();
+    if size > MAX_IMPORT_BYTES {
+        return Err(anyhow!("{} is too large to import", path.display()));
+    }
+    let text = tokio::fs::read_to_string(&path).await.map_err(|e| anyhow!("Can't read {}: {}", path.display(), e))?;
+    if !is_ics_text(&text) {
+        return Err(anyhow!("{} isn't an ICS file", path.display()));
+    }
+    Ok(text)
+}
+
+fn is_ics_text(text: &str) -> bool {
+    text.to_ascii_uppercase().contains("BEGIN:VCALENDAR")
+}
+
+/// Store the events in `ics` under the Imported account
+pub async fn import_ics(pool: &SqlitePool, ics: &str) -> Result<SyncResult> {
+    let settings = crate::database::settings::get(pool).await?;
+    let options = ParseOptions::from_settings(&settings);
+    let ics_data = ics.to_string();
+    let feed = tokio::task::spawn_blocking(move || parse_ics_feed(&ics_data, "import", &options))
+        .await
+        .map_err(|e| anyhow!("ICS parsing failed: {}", e))??;
+    if feed.events.is_empty() && feed.cancelled.is_empty() {
+        return Err(anyhow!("No events to import (events outside the sync window are left out)"));
+    }
+
+    let account_id = crate::database::accounts::imported_account_id(pool).await?;
+    let mut changes = EventChanges::default();
+    let (_turn, mut tx) = crate::database::begin_store(pool).await?;
+    store_feed_events(&mut tx, account_id, feed.events, &feed.attendees, &feed.occurrence_series, &feed.cancelled, &mut changes).await?;
+    tx.commit().await?;
+
+    let result = SyncResult::with_changes(account_id, changes);
+    log::info!(
+        "Imported ICS: {} events added, {} updated, {} removed",
+        result.events_added, result.events_updated, result.events_deleted
+    );
+    Ok(result)
+}
@@ -1,77 +1,310 @@
 #![allow(dead_code)]
 // Calendar integration module
-// Handles Google Calendar ICS and Proton ICS feed parsing (ICS-only, OAuth removed)
+// Handles Google Calendar, Proton and other ICS feeds and CalDAV calendars
 
 use crate::models::{Account, SyncResult};
 use anyhow::Result;
+use cache::IcsCache;
+use chrono::{DateTime, Utc};
 use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
+pub mod generic;
 pub mod google;
+#[cfg(feature = "google-api")]
+pub mod google_api;
 pub mod proton;
+pub mod caldav;
 pub mod common;
+pub mod connection_test;
+pub mod discovery;
+pub mod export;
+pub mod import;
+pub mod ics_stream;
+pub mod recurrence;
+pub mod vtimezone;
+pub mod cache;
+pub mod source;
 
-pub async fn sync_account(account: &Account, db: &SqlitePool) -> Result<SyncResult> {
+/// The circuit breaker an account's syncs go through: one per account, so a
+/// feed that keeps failing doesn't hold off the provider's other accounts.
+/// `None` for reminders, which have nothing to fetch.
+pub fn circuit_breaker_name(account: &Account) -> Option<String> {
+    let service_name = match account.provider().ok()? {
+        crate::models::CalendarProvider::Google => "google_calendar",
+        crate::models::CalendarProvider::Proton => "proton_calendar",
+        crate::models::CalendarProvider::Generic => "generic_calendar",
+        crate::models::CalendarProvider::CalDav => "caldav_calendar",
+        crate::models::CalendarProvider::Local => return None,
+    };
+    Some(account_breaker_name(service_name, account))
+}
+
+/// The breaker for `account` among those of `service_name`; an account that
+/// isn't saved yet shares the service's own
+pub fn account_breaker_name(service_name: &str, account: &Account) -> String {
+    match account.id {
+        Some(account_id) => format!("{}:{}", service_name, account_id),
+        None => service_name.to_string(),
+    }
+}
+
+lazy_static::lazy_static! {
+    /// Accounts whose server sent a Retry-After too long to wait out within
+    /// a sync, and when it said to come back
+    static ref RETRY_NOT_BEFORE: Mutex<HashMap<i64, DateTime<Utc>>> = Mutex::new(HashMap::new());
+}
+
+/// When an account's server said it may be synced again, if that's after `now`
+pub fn retry_not_before(account_id: i64, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let mut waits = RETRY_NOT_BEFORE.lock().unwrap();
+    match waits.get(&account_id) {
+        Some(until) if *until > now => Some(*until),
+        Some(_) => {
+            waits.remove(&account_id);
+            None
+        }
+        None => None,
+    }
+}
+
+/// Remember the Retry-After a failed sync came back with, so the next ones
+/// wait for it rather than hitting the rate limit again
+fn note_retry_after(account_id: i64, error: &anyhow::Error, now: DateTime<Utc>) {
+    let Some(wait) = crate::utils::retry::retry_after_hint(error) else {
+        return;
+    };
+    let Ok(wait) = chrono::Duration::from_std(wait) else {
+        return;
+    };
+    RETRY_NOT_BEFORE.lock().unwrap().insert(account_id, now + wait);
+}
+
+/// Sync one account, keeping its downloaded feed in `cache`. Cancelling
+/// `cancel` aborts an in-flight download and returns `OperationCancelled`
+/// without tripping the circuit breaker.
+///
+/// A sync that runs longer than `timeout` is abandoned and counted as a
+/// failure, so one hung server can't stall the accounts queued behind it.
+///
+/// A server that rate-limited the last sync with a Retry-After isn't
+/// contacted again until that time has passed.
+pub async fn sync_account(account: &Account, db: &SqlitePool, cache: &IcsCache, cancel: &CancellationToken, timeout: Duration) -> Result<SyncResult> {
     use crate::utils::circuit_breaker::get_circuit_breaker;
 
     let provider = account.provider().map_err(|e| anyhow::anyhow!("{}", e))?;
-    let service_name = match provider {
-        crate::models::CalendarProvider::Google => "google_calendar",
-        crate::models::CalendarProvider::Proton => "proton_calendar",
+    let Some(breaker_name) = circuit_breaker_name(account) else {
+        // Reminders live only in the database; there is nothing to fetch
+        return Ok(SyncResult::success(account.id.unwrap_or(0)));
     };
+    if let Some(until) = account.id.and_then(|id| retry_not_before(id, Utc::now())) {
+        return Err(anyhow::anyhow!(
+            "The calendar server asked not to be contacted again until {}",
+            until.with_timezone(&chrono::Local).format("%H:%M")
+        ));
+    }
 
-    // Get circuit breaker for this service
-    let breaker = get_circuit_breaker(service_name).await;
+    // Get circuit breaker for this account
+    let breaker = get_circuit_breaker(&breaker_name).await;
 
     // Execute sync through circuit breaker
     let account_clone = account.clone();
     let db_clone = db.clone();
     let provider_clone = provider.clone();
+    let cache_clone = cache.clone();
+    let cancel_clone = cancel.clone();
 
     breaker.execute(move || {
         let account = account_clone.clone();
         let db = db_clone.clone();
         let provider = provider_clone.clone();
+        let cache = cache_clone.clone();
+        let cancel = cancel_clone.clone();
         async move {
-            match provider {
-                crate::models::CalendarProvider::Google => {
-                    google::sync_google_calendar(&account, &db).await
+            let started_at = chrono::Utc::now();
+            let timer = std::time::Instant::now();
+            let sync = async {
+                match provider {
+                    crate::models::CalendarProvider::Google => {
+                        google::sync_google_calendar(&account, &db, &cache, &cancel).await
+                    }
+                    crate::models::CalendarProvider::Proton => {
+                        proton::sync_proton_calendar(&account, &db, &cache, &cancel).await
+                    }
+                    crate::models::CalendarProvider::Generic => {
+                        generic::sync_generic_calendar(&account, &db, &cache, &cancel).await
+                    }
+                    crate::models::CalendarProvider::CalDav => {
+                        caldav::sync_caldav_calendar(&account, &db, &cancel).await
+                    }
+                    crate::models::CalendarProvider::Local => Ok(SyncResult::success(account.id.unwrap_or(0))),
+                }
+            };
+            let result = tokio::time::timeout(timeout, sync)
+                .await
+                .map_err(|_| anyhow::anyhow!("Calendar sync timed out after {}s", timeout.as_secs()))
+                .and_then(|result| result);
+
+            if let (Some(account_id), Err(e)) = (account.id, &result) {
+                note_retry_after(account_id, e, Utc::now());
+            }
+
+            // Shutting down mid-sync says nothing about the feed
+            let cancelled = matches!(&result, Err(e) if e.is::<crate::utils::retry::OperationCancelled>());
+            if let (Some(account_id), false) = (account.id, cancelled) {
+                let error = result.as_ref().err().map(|e| format!("{:#}", e));
+                if let Err(e) = crate::database::sync_history::record(&db, account_id, started_at, timer.elapsed(), error.as_deref()).await {
+                    log::warn!("Failed to record sync history for {}: {}", account.account_name, e);
                 }
-                crate::models::CalendarProvider::Proton => {
-                    proton::sync_proton_calendar(&account, &db).await
+                if let Err(e) = crate::database::accounts::record_sync_error(&db, account_id, error.as_deref()).await {
+                    log::warn!("Failed to record the sync error for {}: {}", account.account_name, e);
                 }
             }
+            result
         }
     }).await
 }
 
-pub async fn test_connection(account: &Account) -> Result<bool> {
-    use crate::utils::circuit_breaker::get_circuit_breaker;
+/// What syncing an account would change, without writing anything (see
+/// `common::SyncMode::Preview`). The account needn't be saved yet, so the
+/// add form can show it before linking. Previews aren't recorded in the
+/// sync history.
+pub async fn preview_account(account: &Account, db: &SqlitePool, cancel: &CancellationToken, timeout: Duration) -> Result<SyncResult> {
+    use common::SyncMode;
 
     let provider = account.provider().map_err(|e| anyhow::anyhow!("{}", e))?;
-    let service_name = match provider {
-        crate::models::CalendarProvider::Google => "google_calendar",
-        crate::models::CalendarProvider::Proton => "proton_calendar",
+    let Some(breaker_name) = circuit_breaker_name(account) else {
+        return Ok(SyncResult::success(account.id.unwrap_or(0)));
+    };
+
+    let preview = async {
+        match provider {
+            crate::models::CalendarProvider::CalDav => {
+                caldav::sync_caldav_calendar_with(account, db, cancel, SyncMode::Preview).await
+            }
+            #[cfg(feature = "google-api")]
+            crate::models::CalendarProvider::Google if google_api::calendar_id(account).is_some() => {
+                Err(anyhow::anyhow!("Google Calendar API accounts can't be previewed"))
+            }
+            // Every other provider is an ICS feed, its IDs prefixed with its name
+            _ => common::sync_ics_account_with(account, db, &breaker_name, provider.as_str(), &IcsCache::default_location(), cancel, SyncMode::Preview).await,
+        }
     };
+    tokio::time::timeout(timeout, preview)
+        .await
+        .map_err(|_| anyhow::anyhow!("Sync preview timed out after {}s", timeout.as_secs()))?
+}
 
-    // Get circuit breaker for this service
-    let breaker = get_circuit_breaker(service_name).await;
+/// How many accounts `sync_accounts` syncs at once
+pub const MAX_CONCURRENT_SYNCS: usize = 4;
 
-    // Execute connection test through circuit breaker
-    let account_clone = account.clone();
-    let provider_clone = provider.clone();
+/// Sync several accounts side by side, at most `MAX_CONCURRENT_SYNCS` at a
+/// time, so one slow server doesn't hold up the rest. Results are in the
+/// order of `accounts`; a sync task that panics is logged and left out.
+pub async fn sync_accounts(accounts: &[Account], db: &SqlitePool, cache: &IcsCache, cancel: &CancellationToken, timeout: Duration) -> Vec<(Account, Result<SyncResult>)> {
+    let permits = std::sync::Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_SYNCS));
+    let mut syncs = tokio::task::JoinSet::new();
 
-    breaker.execute(move || {
-        let account = account_clone.clone();
-        let provider = provider_clone.clone();
-        async move {
-            match provider {
-                crate::models::CalendarProvider::Google => {
-                    google::test_connection(&account).await
-                }
-                crate::models::CalendarProvider::Proton => {
-                    proton::test_connection(&account).await
-                }
-            }
+    for (index, account) in accounts.iter().cloned().enumerate() {
+        let (db, cache, cancel, permits) = (db.clone(), cache.clone(), cancel.clone(), permits.clone());
+        syncs.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let result = sync_account(&account, &db, &cache, &cancel, timeout).await;
+            (index, account, result)
+        });
+    }
+
+    let mut results = Vec::with_capacity(accounts.len());
+    while let Some(joined) = syncs.join_next().await {
+        match joined {
+            Ok(synced) => results.push(synced),
+            Err(e) => log::error!("Account sync task failed: {}", e),
         }
-    }).await
-}
\ No newline at end of file
+    }
+    results.sort_by_key(|(index, _, _)| *index);
+    results.into_iter().map(|(_, account, result)| (account, result)).collect()
+}
+
+/// Sync one account straight away, closing its circuit breaker first so a
+/// run of earlier failures doesn't block the attempt. Other accounts'
+/// breakers are left alone.
+pub async fn retry_account(account: &Account, db: &SqlitePool, cache: &IcsCache, cancel: &CancellationToken, timeout: Duration) -> Result<SyncResult> {
+    if let Some(breaker_name) = circuit_breaker_name(account) {
+        crate::utils::circuit_breaker::get_circuit_breaker(&breaker_name).await.reset().await;
+    }
+
+    let result = sync_account(account, db, cache, cancel, timeout).await?;
+    if let Some(account_id) = account.id {
+        crate::database::accounts::update_sync_time(db, account_id).await?;
+    }
+    Ok(result)
+}
+
+/// Check a calendar step by step before it's linked (see `connection_test`)
+pub async fn test_connection(account: &Account, settings: &crate::models::Settings) -> Vec<crate::doctor::CheckResult> {
+    connection_test::run(account, settings).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::retry::HttpStatusError;
+
+    #[tokio::test]
+    async fn test_rate_limited_account_waits_for_retry_after() {
+        let now = Utc::now();
+        let throttled: anyhow::Error = HttpStatusError {
+            status: 429,
+            retry_after: Some(Duration::from_secs(600)),
+            body: String::new(),
+        }.into();
+        note_retry_after(9001, &throttled, now);
+        note_retry_after(9002, &anyhow::anyhow!("connection refused"), now);
+
+        assert_eq!(retry_not_before(9001, now), Some(now + chrono::Duration::minutes(10)));
+        assert_eq!(retry_not_before(9002, now), None);
+        assert_eq!(retry_not_before(9001, now + chrono::Duration::minutes(11)), None);
+
+        // Including when the retries around the request ran out
+        let config = crate::utils::retry::RetryConfig { max_attempts: 1, ..Default::default() };
+        let exhausted = crate::utils::retry::retry_with_exponential_backoff(&config, || async {
+            Err::<(), _>(anyhow::Error::new(HttpStatusError { status: 429, retry_after: Some(Duration::from_secs(600)), body: String::new() }))
+        })
+        .await
+        .unwrap_err();
+        note_retry_after(9004, &exhausted, now);
+        assert_eq!(retry_not_before(9004, now), Some(now + chrono::Duration::minutes(10)));
+
+        // Throttled accounts fail straight away, without a request
+        note_retry_after(9003, &throttled, now);
+        let mut account = Account::new_proton("Busy".to_string(), "https://calendar.invalid/cal.ics".to_string());
+        account.id = Some(9003);
+        let pool = SqlitePool::connect(":memory:").await.unwrap();
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let error = sync_account(&account, &pool, &IcsCache::new(cache_dir.path()), &CancellationToken::new(), Duration::from_secs(5)).await.unwrap_err();
+        assert!(error.to_string().contains("asked not to be contacted again"));
+    }
+
+    #[tokio::test]
+    async fn test_each_account_has_its_own_breaker() {
+        use crate::utils::circuit_breaker::{get_circuit_breaker, CircuitState};
+
+        let mut failing = Account::new_proton("Flaky".to_string(), "https://calendar.invalid/a.ics".to_string());
+        failing.id = Some(9101);
+        let mut healthy = Account::new_proton("Work".to_string(), "https://calendar.invalid/b.ics".to_string());
+        healthy.id = Some(9102);
+        assert_eq!(circuit_breaker_name(&failing).as_deref(), Some("proton_calendar:9101"));
+        assert_eq!(circuit_breaker_name(&Account::new_local()), None);
+
+        let breaker = get_circuit_breaker(&circuit_breaker_name(&failing).unwrap()).await;
+        for _ in 0..5 {
+            let _ = breaker.execute(|| async { Err::<(), _>(anyhow::anyhow!("connection refused")) }).await;
+        }
+        assert!(matches!(breaker.get_state().await, CircuitState::Open));
+        let other = get_circuit_breaker(&circuit_breaker_name(&healthy).unwrap()).await;
+        assert!(matches!(other.get_state().await, CircuitState::Closed));
+    }
+}
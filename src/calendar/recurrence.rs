@@ -0,0 +1,535 @@
+// file: src/calendar/recurrence.rs
+//! Recurring events: RRULE parsing and expansion into occurrences.
+//!
+//! Occurrences are computed in the series' own wall-clock time, so a 09:00
+//! standup stays at 09:00 across DST changes, and only the ones inside the
+//! sync window are kept. Each occurrence is stored as its own event, keyed
+//! by `occurrence_id`.
+//!
+//! The supported subset covers what calendar apps produce: DAILY, WEEKLY,
+//! MONTHLY and YEARLY rules with INTERVAL, COUNT, UNTIL, BYDAY (with
+//! ordinals for monthly and yearly rules), BYMONTHDAY, BYMONTH, BYSETPOS and
+//! WKST. Sub-daily frequencies aren't expanded. EXDATE and RDATE lists
+//! remove and add single occurrences.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Utc, Weekday};
+use icalendar::{CalendarDateTime, DatePerhapsTime};
+use std::str::FromStr;
+
+/// Upper bound on periods walked for one series, against runaway rules
+const MAX_PERIODS: u32 = 20_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// When a series ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Until {
+    /// A UTC `DATE-TIME` (`...Z`)
+    Utc(DateTime<Utc>),
+    /// A `DATE` or floating `DATE-TIME`, in the series' own time
+    Local(NaiveDateTime),
+}
+
+/// A parsed RRULE
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<Until>,
+    /// Weekdays, with an optional ordinal (`2TU`, `-1FR`)
+    pub by_day: Vec<(Option<i32>, Weekday)>,
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub by_set_pos: Vec<i32>,
+    pub week_start: Weekday,
+}
+
+fn parse_weekday(s: &str) -> Option<Weekday> {
+    Some(match s {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+fn parse_list<T: FromStr>(value: &str) -> Result<Vec<T>, String> {
+    value
+        .split(',')
+        .map(|v| v.trim().parse().map_err(|_| format!("Invalid value '{}'", v)))
+        .collect()
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut frequency = None;
+        let mut rule = RecurrenceRule {
+            frequency: Frequency::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+            by_set_pos: Vec::new(),
+            week_start: Weekday::Mon,
+        };
+
+        for part in s.trim().trim_start_matches("RRULE:").split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part.split_once('=').ok_or_else(|| format!("Invalid RRULE part '{}'", part))?;
+            let value = value.trim().to_ascii_uppercase();
+            match key.trim().to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        other => return Err(format!("Unsupported frequency {}", other)),
+                    })
+                }
+                "INTERVAL" => rule.interval = value.parse().ok().filter(|i| *i > 0).ok_or("Invalid INTERVAL")?,
+                "COUNT" => rule.count = Some(value.parse().map_err(|_| "Invalid COUNT")?),
+                "UNTIL" => rule.until = Some(parse_until(&value).ok_or_else(|| format!("Invalid UNTIL '{}'", value))?),
+                "BYDAY" => {
+                    rule.by_day = value
+                        .split(',')
+                        .map(|day| {
+                            let day = day.trim();
+                            let split = day.len().checked_sub(2).ok_or_else(|| format!("Invalid BYDAY '{}'", day))?;
+                            let weekday = day.get(split..).and_then(parse_weekday).ok_or_else(|| format!("Invalid BYDAY '{}'", day))?;
+                            let ordinal = match &day[..split] {
+                                "" => None,
+                                n => Some(n.trim_start_matches('+').parse().map_err(|_| format!("Invalid BYDAY '{}'", day))?),
+                            };
+                            Ok((ordinal, weekday))
+                        })
+                        .collect::<Result<_, String>>()?
+                }
+                "BYMONTHDAY" => rule.by_month_day = parse_list(&value)?,
+                "BYMONTH" => rule.by_month = parse_list(&value)?,
+                "BYSETPOS" => rule.by_set_pos = parse_list(&value)?,
+                "WKST" => rule.week_start = parse_weekday(&value).ok_or("Invalid WKST")?,
+                // BYHOUR, BYMINUTE, BYWEEKNO, BYYEARDAY, ... aren't produced by
+                // the calendars this is meant for
+                other => log::debug!("Ignoring unsupported RRULE part {}", other),
+            }
+        }
+
+        rule.frequency = frequency.ok_or("RRULE has no FREQ")?;
+        Ok(rule)
+    }
+}
+
+fn parse_until(value: &str) -> Option<Until> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        return NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok().map(|t| Until::Utc(t.and_utc()));
+    }
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(value, "%Y%m%d").ok().and_then(|d| d.and_hms_opt(23, 59, 59)))
+        .map(Until::Local)
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|d| d.pred_opt())
+        .map_or(28, |d| d.day())
+}
+
+/// The first day of the week containing `date`
+fn week_start(date: NaiveDate, week_start: Weekday) -> NaiveDate {
+    let offset = (7 + date.weekday().num_days_from_monday() - week_start.num_days_from_monday()) % 7;
+    date - Duration::days(offset as i64)
+}
+
+impl RecurrenceRule {
+    /// Candidate days in one month, from BYDAY and BYMONTHDAY, or `default_day`
+    fn month_days(&self, year: i32, month: u32, default_day: u32) -> Vec<NaiveDate> {
+        let length = days_in_month(year, month);
+        let date = |day: u32| NaiveDate::from_ymd_opt(year, month, day);
+
+        let by_month_day: Vec<NaiveDate> = self
+            .by_month_day
+            .iter()
+            .filter_map(|&day| match day {
+                d if d > 0 => date(d as u32),
+                d if d < 0 && d.unsigned_abs() <= length => date(length + 1 - d.unsigned_abs()),
+                _ => None,
+            })
+            .collect();
+
+        let by_day: Vec<NaiveDate> = self
+            .by_day
+            .iter()
+            .flat_map(|&(ordinal, weekday)| {
+                let matching: Vec<NaiveDate> = (1..=length).filter_map(date).filter(|d| d.weekday() == weekday).collect();
+                match ordinal {
+                    None => matching,
+                    Some(n) if n > 0 => matching.get(n as usize - 1).copied().into_iter().collect(),
+                    Some(n) if n < 0 => matching.len().checked_sub(n.unsigned_abs() as usize).map(|i| matching[i]).into_iter().collect(),
+                    Some(_) => Vec::new(),
+                }
+            })
+            .collect();
+
+        match (self.by_day.is_empty(), self.by_month_day.is_empty()) {
+            (true, true) => date(default_day).into_iter().collect(),
+            (false, true) => by_day,
+            (true, false) => by_month_day,
+            (false, false) => by_day.into_iter().filter(|d| by_month_day.contains(d)).collect(),
+        }
+    }
+
+    /// The first day of the `index`th period after the one holding `first`
+    fn period_start(&self, first: NaiveDate, index: u32) -> Option<NaiveDate> {
+        let step = index as i64 * self.interval as i64;
+        match self.frequency {
+            Frequency::Daily => first.checked_add_signed(Duration::days(step)),
+            Frequency::Weekly => week_start(first, self.week_start).checked_add_signed(Duration::weeks(step)),
+            Frequency::Monthly => {
+                let months = first.year() as i64 * 12 + first.month0() as i64 + step;
+                NaiveDate::from_ymd_opt(i32::try_from(months / 12).ok()?, (months % 12) as u32 + 1, 1)
+            }
+            Frequency::Yearly => NaiveDate::from_ymd_opt(first.year().checked_add(i32::try_from(step).ok()?)?, 1, 1),
+        }
+    }
+
+    /// Candidate days in the `index`th period after the one holding `first`
+    fn period_days(&self, first: NaiveDate, index: u32) -> Vec<NaiveDate> {
+        let step = index as i64 * self.interval as i64;
+        let in_months = |d: &NaiveDate| self.by_month.is_empty() || self.by_month.contains(&d.month());
+
+        let mut days: Vec<NaiveDate> = match self.frequency {
+            Frequency::Daily => {
+                let day = first + Duration::days(step);
+                let weekday_ok = self.by_day.is_empty() || self.by_day.iter().any(|(_, w)| *w == day.weekday());
+                let month_day_ok = self.by_month_day.is_empty() || self.month_days(day.year(), day.month(), day.day()).contains(&day);
+                if weekday_ok && month_day_ok { vec![day] } else { Vec::new() }
+            }
+            Frequency::Weekly => {
+                let start = week_start(first, self.week_start) + Duration::weeks(step);
+                let weekdays: Vec<Weekday> = match self.by_day.is_empty() {
+                    true => vec![first.weekday()],
+                    false => self.by_day.iter().map(|(_, w)| *w).collect(),
+                };
+                (0..7).map(|i| start + Duration::days(i)).filter(|d| weekdays.contains(&d.weekday())).collect()
+            }
+            Frequency::Monthly => {
+                let months = first.year() as i64 * 12 + first.month0() as i64 + step;
+                let (year, month) = ((months / 12) as i32, (months % 12) as u32 + 1);
+                self.month_days(year, month, first.day())
+            }
+            Frequency::Yearly => {
+                let year = first.year() + step as i32;
+                let months = match self.by_month.is_empty() {
+                    true => vec![first.month()],
+                    false => self.by_month.clone(),
+                };
+                months.into_iter().flat_map(|month| self.month_days(year, month, first.day())).collect()
+            }
+        };
+        days.retain(in_months);
+        days.sort();
+        days.dedup();
+
+        if self.by_set_pos.is_empty() {
+            return days;
+        }
+        let mut picked: Vec<NaiveDate> = self
+            .by_set_pos
+            .iter()
+            .filter_map(|&pos| match pos {
+                p if p > 0 => days.get(p as usize - 1).copied(),
+                p if p < 0 => days.len().checked_sub(p.unsigned_abs() as usize).map(|i| days[i]),
+                _ => None,
+            })
+            .collect();
+        picked.sort();
+        picked.dedup();
+        picked
+    }
+
+    /// Start times of the series' occurrences, in its own wall-clock time,
+    /// from `start` (the DTSTART) up to `window_end`. `to_utc` places a
+    /// wall-clock time on the timeline, for UNTIL and the window.
+    pub fn occurrences(
+        &self,
+        start: NaiveDateTime,
+        to_utc: impl Fn(NaiveDateTime) -> Option<DateTime<Utc>>,
+        window_end: DateTime<Utc>,
+    ) -> Vec<NaiveDateTime> {
+        let mut occurrences = Vec::new();
+        let mut count = 0;
+
+        // A day's slack either way covers any UTC offset
+        let mut last_day = window_end.date_naive() + Duration::days(1);
+        match self.until {
+            Some(Until::Utc(until)) => last_day = last_day.min(until.date_naive() + Duration::days(1)),
+            Some(Until::Local(until)) => last_day = last_day.min(until.date()),
+            None => {}
+        }
+
+        for index in 0..MAX_PERIODS {
+            if self.period_start(start.date(), index).is_none_or(|day| day > last_day) {
+                return occurrences;
+            }
+            for day in self.period_days(start.date(), index) {
+                let local = day.and_time(start.time());
+                if local < start {
+                    continue;
+                }
+                let Some(utc) = to_utc(local) else { continue };
+                let past_until = match self.until {
+                    Some(Until::Utc(until)) => utc > until,
+                    Some(Until::Local(until)) => local > until,
+                    None => false,
+                };
+                if past_until || utc > window_end || self.count.is_some_and(|c| count >= c) {
+                    return occurrences;
+                }
+                count += 1;
+                occurrences.push(local);
+            }
+        }
+        log::warn!("Stopped expanding a recurring event after {} periods", MAX_PERIODS);
+        occurrences
+    }
+}
+
+/// The stored ID of one occurrence of series `uid`: the UID plus the
+/// occurrence's original start in UTC (the form RECURRENCE-ID takes), so
+/// it stays put when other occurrences change
+pub fn occurrence_id(uid: &str, original_start: DateTime<Utc>, is_all_day: bool) -> String {
+    match is_all_day {
+        true => format!("{}_{}", uid, original_start.format("%Y%m%d")),
+        false => format!("{}_{}", uid, original_start.format("%Y%m%dT%H%M%SZ")),
+    }
+}
+
+/// A content line as written in the feed
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawProperty {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    pub value: String,
+}
+
+impl RawProperty {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    pub(crate) fn parse(line: &str) -> Option<Self> {
+        // The value starts at the first colon outside a quoted parameter
+        let mut quoted = false;
+        let colon = line.char_indices().find_map(|(i, c)| match c {
+            '"' => {
+                quoted = !quoted;
+                None
+            }
+            ':' if !quoted => Some(i),
+            _ => None,
+        })?;
+        let (head, value) = (&line[..colon], &line[colon + 1..]);
+        let mut parts = head.split(';');
+        let name = parts.next()?.trim().to_ascii_uppercase();
+        let params = parts
+            .filter_map(|p| p.split_once('='))
+            .map(|(k, v)| (k.trim().to_ascii_uppercase(), v.trim().trim_matches('"').to_string()))
+            .collect();
+        Some(Self { name, params, value: value.trim_end_matches('\r').to_string() })
+    }
+}
+
+/// The dates and times listed in an EXDATE or RDATE property. A PERIOD
+/// counts as its start; values that can't be read are skipped.
+pub fn date_list(property: &RawProperty) -> Vec<DatePerhapsTime> {
+    let is_date = property.param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE"));
+    property
+        .value
+        .split(',')
+        .filter_map(|value| {
+            let value = value.split('/').next().unwrap_or_default().trim();
+            if is_date || value.len() == 8 {
+                return NaiveDate::parse_from_str(value, "%Y%m%d").ok().map(DatePerhapsTime::Date);
+            }
+            let (value, utc) = match value.strip_suffix('Z') {
+                Some(value) => (value, true),
+                None => (value, false),
+            };
+            let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+            let time = match (utc, property.param("TZID")) {
+                (true, _) => CalendarDateTime::Utc(naive.and_utc()),
+                (false, Some(tzid)) => CalendarDateTime::WithTimezone { date_time: naive, tzid: tzid.to_string() },
+                (false, None) => CalendarDateTime::Floating(naive),
+            };
+            Some(DatePerhapsTime::DateTime(time))
+        })
+        .collect()
+}
+
+/// A DURATION value such as `-PT15M`, `P1D` or `-P1DT2H`. Weeks and days
+/// count as 7 and 1 days of 24 hours.
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (negative, value) = match value.as_bytes().first()? {
+        b'-' => (true, &value[1..]),
+        b'+' => (false, &value[1..]),
+        _ => (false, value),
+    };
+    let value = value.strip_prefix(['P', 'p'])?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    let mut any = false;
+    for c in value.chars() {
+        match c.to_ascii_uppercase() {
+            '0'..='9' => number.push(c),
+            'T' if number.is_empty() && !in_time => in_time = true,
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match (unit, in_time) {
+                    ('W', false) => Duration::weeks(n),
+                    ('D', false) => Duration::days(n),
+                    ('H', true) => Duration::hours(n),
+                    ('M', true) => Duration::minutes(n),
+                    ('S', true) => Duration::seconds(n),
+                    _ => return None,
+                };
+                any = true;
+            }
+        }
+    }
+    if !number.is_empty() || !any {
+        return None;
+    }
+    Some(if negative { -total } else { total })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveTime, TimeZone};
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap().and_time(NaiveTime::from_hms_opt(h, min, 0).unwrap())
+    }
+
+    fn expand(rule: &str, start: NaiveDateTime, until: DateTime<Utc>) -> Vec<NaiveDateTime> {
+        rule.parse::<RecurrenceRule>().unwrap().occurrences(start, |t| Some(t.and_utc()), until)
+    }
+
+    #[test]
+    fn test_weekly_and_daily() {
+        let start = at(2024, 3, 4, 9, 0); // a Monday
+        let weekly = expand("FREQ=WEEKLY;COUNT=3", start, Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(weekly, vec![start, at(2024, 3, 11, 9, 0), at(2024, 3, 18, 9, 0)]);
+
+        let weekdays = expand("FREQ=WEEKLY;BYDAY=MO,WE,FR;UNTIL=20240308T235959Z", start, Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(weekdays, vec![start, at(2024, 3, 6, 9, 0), at(2024, 3, 8, 9, 0)]);
+
+        let fortnightly = expand("FREQ=WEEKLY;INTERVAL=2;BYDAY=TU", start, Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap());
+        assert_eq!(fortnightly, vec![at(2024, 3, 5, 9, 0), at(2024, 3, 19, 9, 0)]);
+
+        // Stops at the window when the series doesn't end
+        let daily = expand("FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR", start, Utc.with_ymd_and_hms(2024, 3, 12, 0, 0, 0).unwrap());
+        assert_eq!(daily.len(), 6);
+        assert_eq!(daily.last(), Some(&at(2024, 3, 11, 9, 0)));
+    }
+
+    #[test]
+    fn test_monthly_and_yearly() {
+        let end = Utc.with_ymd_and_hms(2025, 12, 31, 0, 0, 0).unwrap();
+
+        // Second Tuesday, and the last Friday, of each month
+        let second_tuesday = expand("FREQ=MONTHLY;BYDAY=2TU;COUNT=3", at(2024, 1, 9, 14, 0), end);
+        assert_eq!(second_tuesday, vec![at(2024, 1, 9, 14, 0), at(2024, 2, 13, 14, 0), at(2024, 3, 12, 14, 0)]);
+        let last_friday = expand("FREQ=MONTHLY;BYDAY=-1FR;COUNT=2", at(2024, 1, 26, 16, 0), end);
+        assert_eq!(last_friday, vec![at(2024, 1, 26, 16, 0), at(2024, 2, 23, 16, 0)]);
+
+        // Outlook's "last weekday of the month"
+        let last_weekday = expand("FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1;COUNT=2", at(2024, 3, 29, 17, 0), end);
+        assert_eq!(last_weekday, vec![at(2024, 3, 29, 17, 0), at(2024, 4, 30, 17, 0)]);
+
+        // The 31st only happens in months that have one
+        let thirty_first = expand("FREQ=MONTHLY;COUNT=3", at(2024, 1, 31, 10, 0), end);
+        assert_eq!(thirty_first, vec![at(2024, 1, 31, 10, 0), at(2024, 3, 31, 10, 0), at(2024, 5, 31, 10, 0)]);
+
+        let anniversary = expand("FREQ=YEARLY;UNTIL=20251231", at(2024, 2, 29, 0, 0), end);
+        assert_eq!(anniversary, vec![at(2024, 2, 29, 0, 0)]);
+        let review = expand("FREQ=YEARLY;BYMONTH=3,9;BYMONTHDAY=1;COUNT=3", at(2024, 3, 1, 11, 0), end);
+        assert_eq!(review, vec![at(2024, 3, 1, 11, 0), at(2024, 9, 1, 11, 0), at(2025, 3, 1, 11, 0)]);
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!("COUNT=3".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=HOURLY".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=WEEKLY;BYDAY=XX".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=WEEKLY;INTERVAL=0".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=weekly;byday=mo".parse::<RecurrenceRule>().is_ok());
+    }
+
+    #[test]
+    fn test_occurrence_ids() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 4, 14, 0, 0).unwrap();
+        let id = occurrence_id("standup@example.com", start, false);
+        assert_eq!(id, "standup@example.com_20240304T140000Z");
+        assert_eq!(occurrence_id("holiday", start, true), "holiday_20240304");
+    }
+
+    #[test]
+    fn test_date_list() {
+        let property = |line: &str| RawProperty::parse(line).unwrap();
+
+        let listed = date_list(&property("EXDATE;TZID=Europe/London:20240311T090000,20240318T090000"));
+        assert_eq!(
+            listed,
+            vec![
+                DatePerhapsTime::DateTime(CalendarDateTime::WithTimezone { date_time: at(2024, 3, 11, 9, 0), tzid: "Europe/London".to_string() }),
+                DatePerhapsTime::DateTime(CalendarDateTime::WithTimezone { date_time: at(2024, 3, 18, 9, 0), tzid: "Europe/London".to_string() }),
+            ]
+        );
+        assert_eq!(
+            date_list(&property("RDATE;VALUE=PERIOD:20240320T150000Z/PT1H,garbage")),
+            vec![DatePerhapsTime::DateTime(CalendarDateTime::Utc(Utc.with_ymd_and_hms(2024, 3, 20, 15, 0, 0).unwrap()))]
+        );
+        assert_eq!(
+            date_list(&property("EXDATE;VALUE=DATE:20241225")),
+            vec![DatePerhapsTime::Date(NaiveDate::from_ymd_opt(2024, 12, 25).unwrap())]
+        );
+        assert_eq!(
+            date_list(&property("RDATE:20240320T150000")),
+            vec![DatePerhapsTime::DateTime(CalendarDateTime::Floating(at(2024, 3, 20, 15, 0)))]
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("-PT15M"), Some(-Duration::minutes(15)));
+        assert_eq!(parse_duration("PT0S"), Some(Duration::zero()));
+        assert_eq!(parse_duration("-P1DT2H30M"), Some(-(Duration::days(1) + Duration::hours(2) + Duration::minutes(30))));
+        assert_eq!(parse_duration("+P2W"), Some(Duration::weeks(2)));
+        assert_eq!(parse_duration("-PT15"), None);
+        assert_eq!(parse_duration("-P15M"), None);
+        assert_eq!(parse_duration("P"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+}
@@ -0,0 +1,291 @@
+// Calendar sources
+// A CalendarSource produces raw ICS data for one account. Providers use
+// IcsUrlSource; tests use MockCalendarSource to drive the sync pipeline
+// without the network.
+
+use crate::calendar::cache::IcsCache;
+use crate::calendar::common;
+use crate::models::{Account, FeedValidators};
+use anyhow::{anyhow, Result};
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+/// Something that can produce an ICS feed
+pub trait CalendarSource: Send + Sync {
+    /// Prefix for event IDs generated when a VEVENT has no UID
+    fn id_prefix(&self) -> &str;
+
+    /// Fetch the current ICS document
+    fn fetch_ics(&self) -> impl Future<Output = Result<String>> + Send;
+
+    /// Fetch the ICS document unless it is unchanged since the response
+    /// `validators` came from. Sources that can't tell always fetch.
+    fn fetch_ics_if_modified(&self, _validators: Option<&FeedValidators>) -> impl Future<Output = Result<FeedFetch>> + Send {
+        let fetch = self.fetch_ics();
+        async move { Ok(FeedFetch::Modified { body: fetch.await?, validators: FeedValidators::default() }) }
+    }
+}
+
+/// The outcome of a conditional fetch
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeedFetch {
+    /// A new (or first) copy of the feed
+    Modified { body: String, validators: FeedValidators },
+    /// The server confirmed the feed is the one last synced (HTTP 304)
+    NotModified,
+}
+
+impl FeedFetch {
+    /// The body, for callers that never send validators
+    pub fn into_body(self) -> Result<String> {
+        match self {
+            Self::Modified { body, .. } => Ok(body),
+            Self::NotModified => Err(anyhow!("Server answered 304 Not Modified to an unconditional request")),
+        }
+    }
+}
+
+/// Credentials and extra headers sent with every request for a feed
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct FeedAuth {
+    /// HTTP Basic username and password
+    pub basic: Option<(String, String)>,
+    /// Extra request headers (API tokens, cookies) for feeds behind a gateway
+    pub headers: Vec<(String, String)>,
+}
+
+impl FeedAuth {
+    /// What an account stores, if anything, with the password and headers
+    /// read from the keyring. Stored headers that no longer parse are
+    /// skipped.
+    pub async fn from_account(account: &Account) -> Result<Option<Self>> {
+        let secrets = account.secrets().await?;
+        let basic = account
+            .username
+            .as_deref()
+            .filter(|u| !u.trim().is_empty())
+            .map(|username| (username.to_string(), secrets.password.unwrap_or_default()));
+        let headers = secrets
+            .request_headers
+            .as_deref()
+            .unwrap_or_default()
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match parse_header(line) {
+                Ok(header) => Some(header),
+                Err(e) => {
+                    log::warn!("Ignoring request header for {}: {}", account.account_name, e);
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Ok((basic.is_some() || !headers.is_empty()).then_some(Self { basic, headers }))
+    }
+
+    /// Add the credentials and headers to a request
+    pub fn apply(&self, mut request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        if let Some((username, password)) = &self.basic {
+            request = request.basic_auth(username, Some(password));
+        }
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        request
+    }
+}
+
+// Keep the password and header values (tokens, cookies) out of logs
+impl fmt::Debug for FeedAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FeedAuth")
+            .field("username", &self.basic.as_ref().map(|(username, _)| username))
+            .field("headers", &self.headers.iter().map(|(name, _)| name).collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Read a `Name: value` request header, as stored one per line in
+/// `Account::request_headers`
+pub fn parse_header(line: &str) -> Result<(String, String)> {
+    let (name, value) = line.split_once(':').ok_or_else(|| anyhow!("'{}' isn't in the form 'Name: value'", line.trim()))?;
+    let (name, value) = (name.trim(), value.trim());
+    reqwest::header::HeaderName::from_bytes(name.as_bytes()).map_err(|_| anyhow!("'{}' isn't a valid header name", name))?;
+    reqwest::header::HeaderValue::from_str(value).map_err(|_| anyhow!("The value of '{}' has characters a header can't hold", name))?;
+    Ok((name.to_string(), value.to_string()))
+}
+
+/// An ICS feed fetched over HTTPS
+#[derive(Debug, Clone)]
+pub struct IcsUrlSource {
+    pub url: String,
+    pub circuit_breaker_name: String,
+    pub id_prefix: String,
+    pub auth: Option<FeedAuth>,
+    /// Aborts an in-flight fetch (e.g. on shutdown)
+    pub cancel: CancellationToken,
+    /// Fetch straight from the server, past the cache and circuit breaker
+    /// (see `common::fetch_ics_data_uncached`); for previews
+    pub direct: bool,
+    /// Where fetched feeds are kept; the user's cache directory unless
+    /// `with_cache` says otherwise
+    pub cache: IcsCache,
+}
+
+impl IcsUrlSource {
+    pub fn new(url: impl Into<String>, circuit_breaker_name: impl Into<String>, id_prefix: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            circuit_breaker_name: circuit_breaker_name.into(),
+            id_prefix: id_prefix.into(),
+            auth: None,
+            cancel: CancellationToken::new(),
+            direct: false,
+            cache: IcsCache::default_location(),
+        }
+    }
+
+    pub fn with_auth(mut self, auth: Option<FeedAuth>) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    pub fn with_cache(mut self, cache: IcsCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn direct(mut self) -> Self {
+        self.direct = true;
+        self
+    }
+}
+
+impl CalendarSource for IcsUrlSource {
+    fn id_prefix(&self) -> &str {
+        &self.id_prefix
+    }
+
+    fn fetch_ics(&self) -> impl Future<Output = Result<String>> + Send {
+        let direct = self.direct;
+        async move {
+            match direct {
+                true => common::fetch_ics_data_uncached(&self.url, self.auth.as_ref(), &self.cancel).await,
+                false => common::fetch_ics_data_if_modified(&self.url, &self.circuit_breaker_name, self.auth.as_ref(), &self.cache, &self.cancel, None)
+                    .await?
+                    .into_body(),
+            }
+        }
+    }
+
+    fn fetch_ics_if_modified(&self, validators: Option<&FeedValidators>) -> impl Future<Output = Result<FeedFetch>> + Send {
+        let validators = validators.cloned();
+        async move {
+            if self.direct {
+                let body = common::fetch_ics_data_uncached(&self.url, self.auth.as_ref(), &self.cancel).await?;
+                return Ok(FeedFetch::Modified { body, validators: FeedValidators::default() });
+            }
+            common::fetch_ics_data_if_modified(&self.url, &self.circuit_breaker_name, self.auth.as_ref(), &self.cache, &self.cancel, validators.as_ref()).await
+        }
+    }
+}
+
+/// In-memory source for tests: returns whatever feed (or error) it was last given
+#[derive(Debug)]
+pub struct MockCalendarSource {
+    response: Mutex<std::result::Result<String, String>>,
+    etag: Mutex<Option<String>>,
+    fetches: AtomicUsize,
+}
+
+impl MockCalendarSource {
+    pub fn new(ics: impl Into<String>) -> Self {
+        Self {
+            response: Mutex::new(Ok(ics.into())),
+            etag: Mutex::new(None),
+            fetches: AtomicUsize::new(0),
+        }
+    }
+
+    /// Serve the feed with this ETag, answering "not modified" when asked
+    /// with it
+    pub fn set_etag(&self, etag: impl Into<String>) {
+        *self.etag.lock().unwrap() = Some(etag.into());
+    }
+
+    /// Serve a different feed on the next fetch
+    pub fn set_ics(&self, ics: impl Into<String>) {
+        *self.response.lock().unwrap() = Ok(ics.into());
+    }
+
+    /// Make the next fetch fail with `message`
+    pub fn set_error(&self, message: impl Into<String>) {
+        *self.response.lock().unwrap() = Err(message.into());
+    }
+
+    /// Number of times the feed has been fetched
+    pub fn fetch_count(&self) -> usize {
+        self.fetches.load(Ordering::SeqCst)
+    }
+}
+
+impl CalendarSource for MockCalendarSource {
+    fn id_prefix(&self) -> &str {
+        "mock"
+    }
+
+    fn fetch_ics(&self) -> impl Future<Output = Result<String>> + Send {
+        self.fetches.fetch_add(1, Ordering::SeqCst);
+        let response = self.response.lock().unwrap().clone();
+        async move { response.map_err(|e| anyhow!(e)) }
+    }
+
+    fn fetch_ics_if_modified(&self, validators: Option<&FeedValidators>) -> impl Future<Output = Result<FeedFetch>> + Send {
+        let etag = self.etag.lock().unwrap().clone();
+        let unchanged = etag.is_some() && validators.and_then(|v| v.etag.as_ref()) == etag.as_ref();
+        let fetch = self.fetch_ics();
+        async move {
+            let body = fetch.await?;
+            Ok(match unchanged {
+                true => FeedFetch::NotModified,
+                false => FeedFetch::Modified { body, validators: FeedValidators { etag, last_modified: None } },
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(parse_header(" X-Api-Key :  abc123 ").unwrap(), ("X-Api-Key".to_string(), "abc123".to_string()));
+        assert_eq!(parse_header("Cookie: a=1; b=2").unwrap().1, "a=1; b=2");
+        assert!(parse_header("no colon here").is_err());
+        assert!(parse_header("Bad Name: x").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_feed_auth_from_account() {
+        let account = Account::new_proton("Work".to_string(), "https://example.com/cal.ics".to_string());
+        assert_eq!(FeedAuth::from_account(&account).await.unwrap(), None);
+
+        let account = account.with_request_headers("X-Api-Key: abc123\n\nnot a header\n".to_string());
+        let auth = FeedAuth::from_account(&account).await.unwrap().unwrap();
+        assert_eq!(auth.basic, None);
+        assert_eq!(auth.headers, vec![("X-Api-Key".to_string(), "abc123".to_string())]);
+
+        let auth = FeedAuth::from_account(&account.with_basic_auth("alice".to_string(), "s3cret".to_string())).await.unwrap().unwrap();
+        assert_eq!(auth.basic, Some(("alice".to_string(), "s3cret".to_string())));
+        assert!(!format!("{:?}", auth).contains("abc123"));
+    }
+}
@@ -0,0 +1,213 @@
+// file: src/calendar/vtimezone.rs
+//! Timezones a feed defines itself in VTIMEZONE blocks, for TZIDs that
+//! aren't IANA names (Outlook and Exchange write "Pacific Standard Time",
+//! "W. Europe Standard Time" and the like).
+//!
+//! Each STANDARD and DAYLIGHT observance is expanded, with the RRULE reader
+//! from `recurrence`, into the instants the UTC offset changes, up to
+//! `HORIZON_YEAR`.
+
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+use icalendar::{CalendarDateTime, DatePerhapsTime};
+use std::collections::HashMap;
+
+use crate::calendar::ics_stream;
+use crate::calendar::recurrence::{self, RawProperty, RecurrenceRule};
+
+/// Offset changes are worked out up to the start of this year
+const HORIZON_YEAR: i32 = 2100;
+
+/// A timezone defined by a VTIMEZONE
+#[derive(Debug, Clone, PartialEq)]
+pub struct VTimezone {
+    pub tzid: String,
+    /// When the offset changes, and the offset (seconds east of UTC) from then on, in order
+    transitions: Vec<(DateTime<Utc>, i32)>,
+    /// The offset before the first transition
+    initial_offset: i32,
+    /// Every offset the zone uses
+    offsets: Vec<i32>,
+}
+
+impl VTimezone {
+    /// Build a zone from its STANDARD and DAYLIGHT observances. `None` if
+    /// none of them can be read.
+    fn build(tzid: &str, observances: &[Vec<RawProperty>]) -> Option<Self> {
+        let horizon = Utc.with_ymd_and_hms(HORIZON_YEAR, 1, 1, 0, 0, 0).single()?;
+        let mut transitions: Vec<(DateTime<Utc>, i32, i32)> = Vec::new();
+
+        for observance in observances {
+            let value = |name: &str| observance.iter().find(|p| p.name == name).map(|p| p.value.trim());
+            let (Some(from), Some(to)) = (value("TZOFFSETFROM").and_then(parse_offset), value("TZOFFSETTO").and_then(parse_offset)) else {
+                continue;
+            };
+            let Some(start) = value("DTSTART").and_then(|v| NaiveDateTime::parse_from_str(v, "%Y%m%dT%H%M%S").ok()) else {
+                continue;
+            };
+            // Onsets are written in the wall-clock time before the change
+            let to_utc = |local: NaiveDateTime| local.checked_sub_signed(Duration::seconds(from as i64)).map(|t| t.and_utc());
+
+            let mut onsets = vec![start];
+            if let Some(rule) = value("RRULE").and_then(|v| v.parse::<RecurrenceRule>().ok()) {
+                onsets.extend(rule.occurrences(start, to_utc, horizon));
+            }
+            for rdate in observance.iter().filter(|p| p.name == "RDATE") {
+                onsets.extend(recurrence::date_list(rdate).into_iter().filter_map(|listed| match listed {
+                    DatePerhapsTime::DateTime(CalendarDateTime::Floating(local)) => Some(local),
+                    DatePerhapsTime::DateTime(CalendarDateTime::WithTimezone { date_time, .. }) => Some(date_time),
+                    // A UTC onset, put back in local time before the change
+                    DatePerhapsTime::DateTime(CalendarDateTime::Utc(utc)) => {
+                        utc.naive_utc().checked_add_signed(Duration::seconds(from as i64))
+                    }
+                    DatePerhapsTime::Date(date) => Some(date.and_time(chrono::NaiveTime::MIN)),
+                }));
+            }
+            transitions.extend(onsets.into_iter().filter_map(to_utc).map(|onset| (onset, from, to)));
+        }
+
+        transitions.sort_by_key(|(onset, _, _)| *onset);
+        transitions.dedup_by_key(|(onset, _, _)| *onset);
+        let &(_, initial_offset, _) = transitions.first()?;
+        let mut offsets: Vec<i32> = transitions.iter().map(|(_, _, to)| *to).chain([initial_offset]).collect();
+        offsets.sort();
+        offsets.dedup();
+
+        Some(Self {
+            tzid: tzid.to_string(),
+            transitions: transitions.into_iter().map(|(onset, _, to)| (onset, to)).collect(),
+            initial_offset,
+            offsets,
+        })
+    }
+
+    /// The UTC offset, in seconds, in force at `at`
+    pub fn offset_at(&self, at: DateTime<Utc>) -> i32 {
+        match self.transitions.partition_point(|(onset, _)| *onset <= at) {
+            0 => self.initial_offset,
+            index => self.transitions[index - 1].1,
+        }
+    }
+
+    /// Convert a wall-clock time in this zone to UTC, resolving DST gaps and
+    /// overlaps the way `common::resolve_local_time` does
+    pub fn to_utc(&self, naive: &NaiveDateTime) -> Option<DateTime<Utc>> {
+        let at_offset = |offset: i32| naive.checked_sub_signed(Duration::seconds(offset as i64)).map(|t| t.and_utc());
+
+        // A repeated time means its first occurrence
+        let valid = self
+            .offsets
+            .iter()
+            .filter_map(|&offset| at_offset(offset).filter(|utc| self.offset_at(*utc) == offset))
+            .min();
+        // A skipped time is read with the offset before the transition
+        valid.or_else(|| at_offset(self.offset_at(naive.checked_sub_signed(Duration::days(1))?.and_utc())))
+    }
+}
+
+/// A UTC offset as written in TZOFFSETFROM/TZOFFSETTO (`+0100`, `-0330`,
+/// `+053000`), in seconds
+fn parse_offset(value: &str) -> Option<i32> {
+    let (sign, digits) = match value.split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    if !matches!(digits.len(), 4 | 6) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let part = |range: std::ops::Range<usize>| digits.get(range).and_then(|d| d.parse::<i32>().ok()).unwrap_or(0);
+    Some(sign * (part(0..2) * 3600 + part(2..4) * 60 + part(4..6)))
+}
+
+/// The VTIMEZONEs in a feed, by TZID
+pub fn parse_all(ics: &str) -> HashMap<String, VTimezone> {
+    let mut zones = HashMap::new();
+    let mut in_zone = false;
+    let mut tzid: Option<String> = None;
+    let mut observances: Vec<Vec<RawProperty>> = Vec::new();
+    let mut current: Option<Vec<RawProperty>> = None;
+
+    for line in ics_stream::content_lines(ics) {
+        let Some(property) = RawProperty::parse(&line) else { continue };
+        let component = property.value.trim().to_ascii_uppercase();
+        match (property.name.as_str(), component.as_str()) {
+            ("BEGIN", "VTIMEZONE") => {
+                in_zone = true;
+                tzid = None;
+                observances.clear();
+            }
+            ("BEGIN", "STANDARD" | "DAYLIGHT") if in_zone => current = Some(Vec::new()),
+            ("END", "STANDARD" | "DAYLIGHT") if in_zone => observances.extend(current.take()),
+            ("END", "VTIMEZONE") if in_zone => {
+                in_zone = false;
+                let Some(tzid) = tzid.take() else { continue };
+                match VTimezone::build(&tzid, &observances) {
+                    Some(zone) => {
+                        zones.insert(tzid, zone);
+                    }
+                    None => log::debug!("Ignoring VTIMEZONE '{}' without usable observances", tzid),
+                }
+            }
+            ("TZID", _) if in_zone && current.is_none() => tzid = Some(property.value.trim().to_string()),
+            _ => {
+                if let Some(observance) = current.as_mut() {
+                    observance.push(property);
+                }
+            }
+        }
+    }
+    zones
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    const PACIFIC: &str = "BEGIN:VCALENDAR\r\nBEGIN:VTIMEZONE\r\nTZID:Pacific Standard Time\r\n\
+        BEGIN:STANDARD\r\nDTSTART:16010101T020000\r\nTZOFFSETFROM:-0700\r\nTZOFFSETTO:-0800\r\n\
+        RRULE:FREQ=YEARLY;INTERVAL=1;BYDAY=1SU;BYMONTH=11\r\nEND:STANDARD\r\n\
+        BEGIN:DAYLIGHT\r\nDTSTART:16010101T020000\r\nTZOFFSETFROM:-0800\r\nTZOFFSETTO:-0700\r\n\
+        RRULE:FREQ=YEARLY;INTERVAL=1;BYDAY=2SU;BYMONTH=3\r\nEND:DAYLIGHT\r\n\
+        END:VTIMEZONE\r\nEND:VCALENDAR\r\n";
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("+0100"), Some(3600));
+        assert_eq!(parse_offset("-0330"), Some(-(3 * 3600 + 30 * 60)));
+        assert_eq!(parse_offset("+053045"), Some(5 * 3600 + 30 * 60 + 45));
+        assert_eq!(parse_offset("0100"), None);
+        assert_eq!(parse_offset("+1"), None);
+    }
+
+    #[test]
+    fn test_windows_zone_follows_dst() {
+        let zones = parse_all(PACIFIC);
+        let zone = &zones["Pacific Standard Time"];
+
+        let utc = |naive| zone.to_utc(&naive).unwrap();
+        assert_eq!(utc(at(2024, 1, 15, 9, 0)), Utc.with_ymd_and_hms(2024, 1, 15, 17, 0, 0).unwrap());
+        assert_eq!(utc(at(2024, 7, 15, 9, 0)), Utc.with_ymd_and_hms(2024, 7, 15, 16, 0, 0).unwrap());
+        // Clocks go forward on March 10: 02:30 doesn't exist
+        assert_eq!(utc(at(2024, 3, 10, 2, 30)), Utc.with_ymd_and_hms(2024, 3, 10, 10, 30, 0).unwrap());
+        // And back on November 3: 01:30 happens twice, the first is meant
+        assert_eq!(utc(at(2024, 11, 3, 1, 30)), Utc.with_ymd_and_hms(2024, 11, 3, 8, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn test_fixed_and_unusable_zones() {
+        let ics = "BEGIN:VTIMEZONE\r\nTZID:India Standard Time\r\nBEGIN:STANDARD\r\nDTSTART:16010101T000000\r\n\
+            TZOFFSETFROM:+0530\r\nTZOFFSETTO:+0530\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\n\
+            BEGIN:VTIMEZONE\r\nTZID:Broken\r\nBEGIN:STANDARD\r\nTZOFFSETTO:+0100\r\nEND:STANDARD\r\nEND:VTIMEZONE\r\n";
+        let zones = parse_all(ics);
+        assert!(!zones.contains_key("Broken"));
+        assert_eq!(
+            zones["India Standard Time"].to_utc(&at(2024, 6, 1, 9, 0)).unwrap(),
+            Utc.with_ymd_and_hms(2024, 6, 1, 3, 30, 0).unwrap()
+        );
+    }
+}
@@ -0,0 +1,211 @@
+//! Command-line argument parsing
+//!
+//! OpenChime is primarily a GUI app, so the CLI is intentionally small and
+//! parsed by hand rather than pulling in an argument parsing crate.
+
+/// Usage text printed by `openchime help`
+pub const USAGE: &str = "\
+Usage: openchime [--profile NAME] [COMMAND]
+
+Commands:
+  (none)               Launch the OpenChime app
+  doctor [--no-sound]  Check the database, calendar feeds and audio, then print a report
+  profile list         List configuration profiles (* marks the active one)
+  profile use NAME     Make NAME the active profile, creating it if needed
+  register-url-handler Register openchime:// links with the desktop
+  google-login NAME [CALENDAR_ID]
+                       Sign in to the Google Calendar API and add calendar
+                       CALENDAR_ID (default: primary) as account NAME
+                       (builds with the google-api feature only)
+  openchime://add?url=URL[&name=NAME]
+                       Launch with the Add Calendar form pre-filled
+  help                 Show this message
+  version              Show the OpenChime version
+
+Options:
+  --profile NAME       Use profile NAME for this run (its own database and settings)
+";
+
+/// What the binary should do, as selected on the command line
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    /// Launch the GUI (default)
+    RunApp,
+    /// Run diagnostics and print a report
+    Doctor {
+        /// Skip playing the test tone
+        no_sound: bool,
+    },
+    /// List configured profiles
+    ProfileList,
+    /// Switch the active profile
+    ProfileUse(String),
+    /// Launch the GUI to handle an `openchime://` link
+    OpenLink(String),
+    /// Register the `openchime://` URL scheme
+    RegisterUrlHandler,
+    /// Sign in to the Google Calendar API and add an account
+    GoogleLogin {
+        account_name: String,
+        calendar_id: String,
+    },
+    /// Print usage
+    Help,
+    /// Print version
+    Version,
+}
+
+/// Parsed command line: global options plus the selected command
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliArgs {
+    /// Profile selected with `--profile`, overriding the active profile
+    pub profile: Option<String>,
+    pub command: CliCommand,
+}
+
+/// Parse command-line arguments (excluding the program name)
+pub fn parse_args<I, S>(args: I) -> Result<CliArgs, String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut profile = None;
+    let mut positional = Vec::new();
+
+    let mut args = args.into_iter().map(|a| a.as_ref().to_string());
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            let name = args.next().ok_or("--profile requires a profile name")?;
+            profile = Some(name);
+        } else if let Some(name) = arg.strip_prefix("--profile=") {
+            profile = Some(name.to_string());
+        } else {
+            positional.push(arg);
+        }
+    }
+
+    let command = parse_command(&positional)?;
+    Ok(CliArgs { profile, command })
+}
+
+fn parse_command(args: &[String]) -> Result<CliCommand, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Ok(CliCommand::RunApp);
+    };
+
+    if crate::protocol::is_protocol_url(command) {
+        return match rest {
+            [] => Ok(CliCommand::OpenLink(command.clone())),
+            _ => Err("Unexpected arguments after link".to_string()),
+        };
+    }
+
+    match command.as_str() {
+        "doctor" => {
+            let mut no_sound = false;
+            for arg in rest {
+                match arg.as_str() {
+                    "--no-sound" => no_sound = true,
+                    other => return Err(format!("Unknown option for doctor: {}", other)),
+                }
+            }
+            Ok(CliCommand::Doctor { no_sound })
+        }
+        "profile" => match rest {
+            [sub] if sub == "list" => Ok(CliCommand::ProfileList),
+            [sub, name] if sub == "use" => Ok(CliCommand::ProfileUse(name.clone())),
+            [sub] if sub == "use" => Err("profile use requires a profile name".to_string()),
+            _ => Err("Expected `profile list` or `profile use NAME`".to_string()),
+        },
+        "register-url-handler" => Ok(CliCommand::RegisterUrlHandler),
+        "google-login" => match rest {
+            [name] => Ok(CliCommand::GoogleLogin { account_name: name.clone(), calendar_id: "primary".to_string() }),
+            [name, calendar_id] => Ok(CliCommand::GoogleLogin { account_name: name.clone(), calendar_id: calendar_id.clone() }),
+            _ => Err("Expected `google-login NAME [CALENDAR_ID]`".to_string()),
+        },
+        "help" | "--help" | "-h" => Ok(CliCommand::Help),
+        "version" | "--version" | "-V" => Ok(CliCommand::Version),
+        other => Err(format!("Unknown command: {}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(args: &[&str]) -> Result<CliCommand, String> {
+        parse_args(args).map(|parsed| parsed.command)
+    }
+
+    #[test]
+    fn test_no_args_runs_app() {
+        let parsed = parse_args(Vec::<String>::new()).unwrap();
+        assert_eq!(parsed, CliArgs { profile: None, command: CliCommand::RunApp });
+    }
+
+    #[test]
+    fn test_doctor() {
+        assert_eq!(command(&["doctor"]), Ok(CliCommand::Doctor { no_sound: false }));
+        assert_eq!(command(&["doctor", "--no-sound"]), Ok(CliCommand::Doctor { no_sound: true }));
+        assert!(command(&["doctor", "--bogus"]).is_err());
+    }
+
+    #[test]
+    fn test_help_and_version() {
+        assert_eq!(command(&["--help"]), Ok(CliCommand::Help));
+        assert_eq!(command(&["help"]), Ok(CliCommand::Help));
+        assert_eq!(command(&["-V"]), Ok(CliCommand::Version));
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        let err = command(&["frobnicate"]).unwrap_err();
+        assert!(err.contains("frobnicate"));
+    }
+
+    #[test]
+    fn test_profile_flag_anywhere() {
+        let parsed = parse_args(["--profile", "work"]).unwrap();
+        assert_eq!(parsed.profile.as_deref(), Some("work"));
+        assert_eq!(parsed.command, CliCommand::RunApp);
+
+        let parsed = parse_args(["doctor", "--profile=client-a"]).unwrap();
+        assert_eq!(parsed.profile.as_deref(), Some("client-a"));
+        assert_eq!(parsed.command, CliCommand::Doctor { no_sound: false });
+
+        assert!(parse_args(["--profile"]).is_err());
+    }
+
+    #[test]
+    fn test_profile_subcommands() {
+        assert_eq!(command(&["profile", "list"]), Ok(CliCommand::ProfileList));
+        assert_eq!(command(&["profile", "use", "work"]), Ok(CliCommand::ProfileUse("work".to_string())));
+        assert!(command(&["profile", "use"]).is_err());
+        assert!(command(&["profile"]).is_err());
+    }
+
+    #[test]
+    fn test_protocol_link() {
+        let link = "openchime://add?url=https%3A%2F%2Fexample.com%2Fcal.ics";
+        assert_eq!(command(&[link]), Ok(CliCommand::OpenLink(link.to_string())));
+
+        let parsed = parse_args(["--profile", "work", link]).unwrap();
+        assert_eq!(parsed.profile.as_deref(), Some("work"));
+        assert_eq!(parsed.command, CliCommand::OpenLink(link.to_string()));
+
+        assert_eq!(command(&["register-url-handler"]), Ok(CliCommand::RegisterUrlHandler));
+    }
+
+    #[test]
+    fn test_google_login() {
+        assert_eq!(
+            command(&["google-login", "Work"]),
+            Ok(CliCommand::GoogleLogin { account_name: "Work".to_string(), calendar_id: "primary".to_string() })
+        );
+        assert_eq!(
+            command(&["google-login", "Team", "team@group.calendar.google.com"]),
+            Ok(CliCommand::GoogleLogin { account_name: "Team".to_string(), calendar_id: "team@group.calendar.google.com".to_string() })
+        );
+        assert!(command(&["google-login"]).is_err());
+    }
+}
@@ -5,7 +5,7 @@
 //! and testability.
 
 use crate::database::Database;
-use crate::models::{Account, Settings, CalendarEvent};
+use crate::models::{Account, Settings, CalendarEvent, SyncResult};
 use crate::audio::AudioManager;
 use crate::calendar;
 use crate::error::AppError;
@@ -30,16 +30,10 @@ impl DatabaseHandlers {
         info!("Loading events from database");
         let local_now = chrono::Local::now();
         let now_utc = local_now.with_timezone(&chrono::Utc);
-        let six_months_ahead = now_utc + chrono::Duration::days(180);
 
-        let events = sqlx::query_as::<_, CalendarEvent>(
-            "SELECT id, external_id, account_id, title, description, start_time, end_time, video_link, video_platform, snooze_count, has_alerted, last_alert_threshold, is_dismissed, created_at, updated_at FROM events WHERE start_time >= ? AND start_time <= ? AND is_dismissed = 0 ORDER BY start_time ASC"
-        )
-        .bind(now_utc)
-        .bind(six_months_ahead)
-        .fetch_all(&self.db.pool)
-        .await
-        .map_err(|e| AppError::Database(e))?;
+        let events = crate::database::events::get_to_window_end(&self.db.pool, now_utc)
+            .await
+            .map_err(AppError::Anyhow)?;
 
         info!("Loaded {} events from database", events.len());
         Ok(events)
@@ -49,12 +43,9 @@ impl DatabaseHandlers {
     #[allow(dead_code)]
     pub async fn load_accounts(&self) -> Result<Vec<Account>, AppError> {
         info!("Loading accounts from database");
-        let accounts = sqlx::query_as::<_, Account>(
-            "SELECT id, provider, account_name, auth_data, refresh_token, last_synced_at FROM accounts ORDER BY created_at ASC"
-        )
-        .fetch_all(&self.db.pool)
-        .await
-        .map_err(|e| AppError::Database(e))?;
+        let accounts = crate::database::accounts::get_all(&self.db.pool)
+            .await
+            .map_err(AppError::Anyhow)?;
 
         info!("Loaded {} accounts from database", accounts.len());
         Ok(accounts)
@@ -84,11 +75,9 @@ impl DatabaseHandlers {
     pub async fn delete_account(&self, account_id: i64) -> Result<(), AppError> {
         info!("Deleting account ID: {}", account_id);
         
-        sqlx::query("DELETE FROM accounts WHERE id = ?")
-            .bind(account_id)
-            .execute(&self.db.pool)
+        crate::database::accounts::delete(&self.db.pool, account_id)
             .await
-            .map_err(|e| AppError::Database(e))?;
+            .map_err(AppError::Anyhow)?;
 
         info!("Successfully deleted account ID: {}", account_id);
         Ok(())
@@ -122,31 +111,27 @@ impl CalendarHandlers {
         info!("Starting calendar synchronization");
         
         // Get all accounts
-        let accounts = sqlx::query_as::<_, Account>(
-            "SELECT id, provider, account_name, auth_data, refresh_token, last_synced_at FROM accounts"
-        )
-        .fetch_all(&self.db.pool)
-        .await
-        .map_err(|e| AppError::Database(e))?;
+        let accounts = crate::database::accounts::get_syncable(&self.db.pool).await?;
 
         if accounts.is_empty() {
             return Err(AppError::OperationFailed("No accounts configured. Please add an account first.".to_string()));
         }
 
+        let timeout = self.db.get_settings().await.unwrap_or_default().sync_timeout_duration();
         let mut total_events = 0;
         let mut successful_syncs = 0;
 
-        for account in accounts.iter() {
-            info!("Attempting to sync account: {} ({})", account.account_name, account.provider);
-            match calendar::sync_account(account, &self.db.pool).await {
+        info!("Syncing {} accounts", accounts.len());
+        for (account, result) in calendar::sync_accounts(&accounts, &self.db.pool, &calendar::cache::IcsCache::default_location(), &tokio_util::sync::CancellationToken::new(), timeout).await {
+            match result {
                 Ok(sync_result) => {
                     total_events += sync_result.events_added + sync_result.events_updated;
                     successful_syncs += 1;
-                    info!("Synced account {}: {} events added, {} events updated", 
-                          account.account_name, sync_result.events_added, sync_result.events_updated);
+                    info!("Synced account {}: {} events added, {} events updated, {} events deleted", 
+                          account.account_name, sync_result.events_added, sync_result.events_updated, sync_result.events_deleted);
                 }
                 Err(e) => {
-                    error!("Failed to sync account {}: {}", account.account_name, e);
+                    error!("Failed to sync account {}: {:#}", account.account_name, e);
                     // Continue with other accounts even if one fails
                 }
             }
@@ -159,6 +144,25 @@ impl CalendarHandlers {
         info!("Calendar synchronization completed: {} events processed", total_events);
         Ok((total_events, successful_syncs))
     }
+
+    /// Sync one account on demand, whatever its sync interval, and report
+    /// what changed
+    pub async fn sync_account(&self, account_id: i64, cancel: &tokio_util::sync::CancellationToken) -> Result<SyncResult, AppError> {
+        let account = crate::database::accounts::get(&self.db.pool, account_id)
+            .await?
+            .ok_or_else(|| AppError::InvalidInput(format!("No account with ID {}", account_id)))?;
+        info!("Syncing account {} on demand", account.account_name);
+
+        let timeout = self.db.get_settings().await.unwrap_or_default().sync_timeout_duration();
+        let result = calendar::sync_account(&account, &self.db.pool, &calendar::cache::IcsCache::default_location(), cancel, timeout)
+            .await
+            .map_err(|e| AppError::Calendar(format!("Failed to sync {}: {:#}", account.account_name, e)))?;
+        crate::database::accounts::update_sync_time(&self.db.pool, account_id).await?;
+
+        info!("Synced account {}: {} events added, {} events updated, {} events deleted",
+              account.account_name, result.events_added, result.events_updated, result.events_deleted);
+        Ok(result)
+    }
 }
 
 /// Test audio system
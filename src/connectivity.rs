@@ -0,0 +1,166 @@
+// file: src/connectivity.rs
+//! Noticing when the network comes back. Before syncing, a monitor cycle
+//! tries a TCP connection to the calendar servers it's about to sync (or the
+//! proxy, when there is one); while none answers, syncs are held back so the
+//! circuit breakers don't trip, every cycle tries again, and the first one
+//! that gets through syncs every calendar. The `hold_syncs_offline` setting
+//! turns the check off.
+
+use crate::models::Account;
+use log::{debug, info};
+use std::time::Duration;
+
+/// How long one connection attempt may take
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Whether the network was reachable at the last check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Connectivity {
+    online: bool,
+}
+
+impl Default for Connectivity {
+    fn default() -> Self {
+        Self { online: true }
+    }
+}
+
+impl Connectivity {
+    pub fn is_online(&self) -> bool {
+        self.online
+    }
+
+    /// Whether to check now: before a sync, or to notice the network
+    /// coming back
+    pub fn should_check(&self, sync_due: bool) -> bool {
+        sync_due || !self.online
+    }
+
+    /// Record a check; true when it's the network coming back
+    pub fn update(&mut self, online: bool) -> bool {
+        let reconnected = online && !self.online;
+        if online != self.online {
+            info!("Network {}", if online { "reconnected" } else { "unreachable; holding syncs" });
+        }
+        self.online = online;
+        reconnected
+    }
+}
+
+/// Hosts and ports to try: the proxy's when requests go through one, else
+/// those of the accounts' own servers, each once. Only a TCP connection is
+/// made; nothing is sent.
+pub fn probe_targets(proxy: Option<&str>, accounts: &[Account]) -> Vec<(String, u16)> {
+    if let Some(proxy) = proxy.and_then(host_and_port) {
+        return vec![proxy];
+    }
+    let mut targets: Vec<(String, u16)> = Vec::new();
+    for target in accounts.iter().filter_map(|account| host_and_port(&account.auth_data)) {
+        if !targets.contains(&target) {
+            targets.push(target);
+        }
+    }
+    targets
+}
+
+fn host_and_port(url: &str) -> Option<(String, u16)> {
+    let url = url::Url::parse(url.trim()).ok()?;
+    let port = url.port_or_known_default().or(match url.scheme() {
+        "webcal" | "webcals" => Some(443),
+        "socks5" | "socks5h" => Some(1080),
+        _ => None,
+    })?;
+    Some((url.host_str()?.to_string(), port))
+}
+
+/// Whether the network is up: the proxy, or any of `accounts`' servers,
+/// accepts a connection. With nothing to try (local calendars only), it's
+/// taken to be.
+pub async fn check(accounts: &[Account]) -> bool {
+    let proxy = crate::http_config::configured_proxy();
+    let targets = probe_targets(proxy.as_deref(), accounts);
+    if targets.is_empty() {
+        return true;
+    }
+
+    // One server being down doesn't mean the network is
+    let mut probes = tokio::task::JoinSet::new();
+    for target in targets {
+        probes.spawn(async move { reachable(&target).await });
+    }
+    while let Some(probe) = probes.join_next().await {
+        if probe.unwrap_or(false) {
+            return true;
+        }
+    }
+    false
+}
+
+async fn reachable((host, port): &(String, u16)) -> bool {
+    match tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect((host.as_str(), *port))).await {
+        Ok(Ok(_)) => true,
+        Ok(Err(e)) => {
+            debug!("Connectivity check: {}:{} unreachable: {}", host, port, e);
+            false
+        }
+        Err(_) => {
+            debug!("Connectivity check: {}:{} timed out", host, port);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_is_reported_once() {
+        let mut connectivity = Connectivity::default();
+        assert!(!connectivity.update(true));
+        assert!(!connectivity.update(false));
+        assert!(!connectivity.is_online());
+        assert!(!connectivity.update(false));
+        assert!(connectivity.update(true));
+        assert!(!connectivity.update(true));
+    }
+
+    #[test]
+    fn test_checks_only_before_a_sync_while_online() {
+        let mut connectivity = Connectivity::default();
+        assert!(!connectivity.should_check(false));
+        assert!(connectivity.should_check(true));
+        connectivity.update(false);
+        assert!(connectivity.should_check(false));
+    }
+
+    #[test]
+    fn test_probe_targets() {
+        let accounts = [
+            Account::new_proton("Work".to_string(), "https://calendar.proton.me/api/a.ics".to_string()),
+            Account::new_proton("Home".to_string(), "https://calendar.proton.me/api/b.ics".to_string()),
+            Account::new_generic("Club".to_string(), "webcal://nextcloud.lan:8443/club.ics".to_string()),
+        ];
+        assert_eq!(
+            probe_targets(None, &accounts),
+            vec![("calendar.proton.me".to_string(), 443), ("nextcloud.lan".to_string(), 8443)]
+        );
+        assert!(probe_targets(None, &[]).is_empty());
+        assert_eq!(probe_targets(Some("socks5h://127.0.0.1"), &accounts), vec![("127.0.0.1".to_string(), 1080)]);
+        assert_eq!(
+            probe_targets(Some("http://proxy.example.com:3128"), &accounts),
+            vec![("proxy.example.com".to_string(), 3128)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reachable_needs_a_listening_server() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let target = ("127.0.0.1".to_string(), port);
+        assert!(reachable(&target).await);
+
+        drop(listener);
+        assert!(!reachable(&target).await);
+    }
+}
@@ -0,0 +1,110 @@
+// file: src/database/account_groups.rs
+//! Account groups ("Work", "Personal") whose accounts are synced, alerted
+//! and shown together
+
+use anyhow::{bail, Result};
+use sqlx::SqlitePool;
+
+use crate::models::AccountGroup;
+
+/// Create a group; all of its switches start on
+pub async fn add(pool: &SqlitePool, name: &str) -> Result<i64> {
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("Group name cannot be empty");
+    }
+
+    let result = sqlx::query("INSERT INTO account_groups (name) VALUES (?)")
+        .bind(name)
+        .execute(pool)
+        .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// All groups, alphabetically
+pub async fn get_all(pool: &SqlitePool) -> Result<Vec<AccountGroup>> {
+    let groups = sqlx::query_as(
+        "SELECT id, name, sync_enabled, alerts_enabled, visible FROM account_groups ORDER BY name COLLATE NOCASE"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(groups)
+}
+
+/// Save a group's switches
+pub async fn update(pool: &SqlitePool, group: &AccountGroup) -> Result<()> {
+    sqlx::query("UPDATE account_groups SET sync_enabled = ?, alerts_enabled = ?, visible = ? WHERE id = ?")
+        .bind(group.sync_enabled)
+        .bind(group.alerts_enabled)
+        .bind(group.visible)
+        .bind(group.id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Delete a group. Its accounts stay, ungrouped.
+pub async fn delete(pool: &SqlitePool, group_id: i64) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("UPDATE accounts SET group_id = NULL WHERE group_id = ?")
+        .bind(group_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM account_groups WHERE id = ?")
+        .bind(group_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Move an account into a group, or out of any with `None`
+pub async fn assign(pool: &SqlitePool, account_id: i64, group_id: Option<i64>) -> Result<()> {
+    sqlx::query("UPDATE accounts SET group_id = ? WHERE id = ?")
+        .bind(group_id)
+        .bind(account_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{accounts, Database};
+    use crate::models::Account;
+
+    #[tokio::test]
+    async fn test_paused_groups_are_not_synced() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = Database::open(&dir.path().join("test.db")).await.unwrap();
+        let work_account = db.add_account(&Account::new_proton("Work".to_string(), "https://example.com/work.ics".to_string())).await.unwrap();
+        db.add_account(&Account::new_proton("Home".to_string(), "https://example.com/home.ics".to_string())).await.unwrap();
+
+        let work = add(&db.pool, "  Work ").await.unwrap();
+        assert!(add(&db.pool, " ").await.is_err());
+        assign(&db.pool, work_account, Some(work)).await.unwrap();
+        assert_eq!(accounts::get_syncable(&db.pool).await.unwrap().len(), 2);
+
+        let mut group = get_all(&db.pool).await.unwrap().remove(0);
+        assert_eq!(group.name, "Work");
+        assert!(group.sync_enabled && group.alerts_enabled && group.visible);
+        group.sync_enabled = false;
+        update(&db.pool, &group).await.unwrap();
+
+        let syncable = accounts::get_syncable(&db.pool).await.unwrap();
+        assert_eq!(syncable.iter().map(|a| a.account_name.as_str()).collect::<Vec<_>>(), vec!["Home"]);
+
+        // Deleting the group frees its accounts
+        delete(&db.pool, work).await.unwrap();
+        assert!(get_all(&db.pool).await.unwrap().is_empty());
+        assert_eq!(accounts::get_syncable(&db.pool).await.unwrap().len(), 2);
+        let all = accounts::get_all(&db.pool).await.unwrap();
+        assert!(all.iter().all(|a| a.group_id.is_none()));
+    }
+}
@@ -1,27 +1,146 @@
 // file: src/database/accounts.rs
 // ICS-only mode - no encryption needed for public ICS URLs
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use sqlx::SqlitePool;
 
+use crate::models::{AccountSecrets, FeedValidators, IMPORTED_ACCOUNT_NAME};
+
+/// The columns an `Account` is read from
+const ACCOUNT_COLUMNS: &str = "id, provider, account_name, auth_data, refresh_token, last_synced_at, group_id, is_shared, is_informational, alerts_enabled, username, sync_interval, last_sync_error, last_sync_error_at, timezone, calendar_name, calendar_color, feed_refresh_interval, secret_key";
+
+/// Save a new account, its secrets going to the keyring
 pub async fn add(pool: &SqlitePool, account: &crate::models::Account) -> Result<i64> {
+    let secrets = account.secrets().await?;
+    let secret_key = match secrets.is_empty() {
+        true => None,
+        false => {
+            let key = crate::keyring::new_key("account");
+            store_secrets(&key, &account.account_name, &secrets).await?;
+            Some(key)
+        }
+    };
+
     // ICS URLs stored as plain text - they're public/semi-public links
     let result = sqlx::query(
-        "INSERT INTO accounts (provider, account_name, auth_data, refresh_token) VALUES (?, ?, ?, ?)"
+        "INSERT INTO accounts (provider, account_name, auth_data, refresh_token, username, secret_key) VALUES (?, ?, ?, ?, ?, ?)"
     )
     .bind(&account.provider)
     .bind(&account.account_name)
     .bind(&account.auth_data)
     .bind(&account.refresh_token)
+    .bind(&account.username)
+    .bind(&secret_key)
     .execute(pool)
+    .await;
+
+    match result {
+        Ok(result) => Ok(result.last_insert_rowid()),
+        Err(e) => {
+            if let Some(key) = &secret_key {
+                crate::keyring::delete(key).await;
+            }
+            Err(e.into())
+        }
+    }
+}
+
+async fn store_secrets(key: &str, account_name: &str, secrets: &AccountSecrets) -> Result<()> {
+    let label = format!("OpenChime: {}", account_name);
+    crate::keyring::store(key, &label, &serde_json::to_string(secrets)?)
+        .await
+        .map_err(|e| anyhow::anyhow!("Couldn't keep the password for {} in the keyring: {}", account_name, e))
+}
+
+/// Save an account's name, provider and URL. A new URL forgets what was known about
+/// the old feed (its validators, content hash and sync position) so the
+/// next sync reads the new one in full; the events, with their snooze and
+/// dismiss state, stay until that sync prunes any the new feed lacks.
+pub async fn update(pool: &SqlitePool, account: &crate::models::Account) -> Result<()> {
+    let account_id = account.id.ok_or_else(|| anyhow::anyhow!("Account has not been saved yet"))?;
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "UPDATE accounts SET feed_etag = NULL, feed_last_modified = NULL, feed_validated_at = NULL, feed_hash = NULL,
+             sync_token = NULL, last_sync_error = NULL, last_sync_error_at = NULL
+         WHERE id = ? AND auth_data != ?",
+    )
+    .bind(account_id)
+    .bind(&account.auth_data)
+    .execute(&mut *tx)
     .await?;
+    sqlx::query("UPDATE accounts SET account_name = ?, provider = ?, auth_data = ? WHERE id = ?")
+        .bind(&account.account_name)
+        .bind(&account.provider)
+        .bind(&account.auth_data)
+        .bind(account_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
 
-    Ok(result.last_insert_rowid())
+    Ok(())
 }
 
+/// Delete an account (its events go with it) and what it keeps in the
+/// keyring: its secrets and, for a Google API account, its sign-in
+pub async fn delete(pool: &SqlitePool, account_id: i64) -> Result<()> {
+    let Some(account) = get(pool, account_id).await? else {
+        return Ok(());
+    };
+    sqlx::query("DELETE FROM accounts WHERE id = ?")
+        .bind(account_id)
+        .execute(pool)
+        .await?;
+
+    if let Some(key) = &account.secret_key {
+        crate::keyring::delete(key).await;
+    }
+    #[cfg(feature = "google-api")]
+    if let Some(key) = account
+        .refresh_token
+        .as_deref()
+        .filter(|_| crate::calendar::google_api::calendar_id(&account).is_some())
+    {
+        crate::keyring::delete(key).await;
+    }
+
+    Ok(())
+}
+
+/// Every account, oldest first
 pub async fn get_all(pool: &SqlitePool) -> Result<Vec<crate::models::Account>> {
     // ICS URLs retrieved as plain text - no decryption needed
     let accounts = sqlx::query_as::<_, crate::models::Account>(
-        "SELECT id, provider, account_name, auth_data, refresh_token, last_synced_at FROM accounts",
+        &format!("SELECT {} FROM accounts ORDER BY id", ACCOUNT_COLUMNS),
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(accounts)
+}
+
+/// One account by id, `None` if it's gone
+pub async fn get(pool: &SqlitePool, account_id: i64) -> Result<Option<crate::models::Account>> {
+    let account = sqlx::query_as::<_, crate::models::Account>(
+        &format!("SELECT {} FROM accounts WHERE id = ?", ACCOUNT_COLUMNS),
+    )
+    .bind(account_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(account)
+}
+
+/// Calendar accounts to sync: everything but the reminders account and
+/// accounts in a group with syncing paused
+pub async fn get_syncable(pool: &SqlitePool) -> Result<Vec<crate::models::Account>> {
+    let accounts = sqlx::query_as::<_, crate::models::Account>(
+        &format!(
+            "SELECT {} FROM accounts
+             WHERE provider != 'local'
+               AND (group_id IS NULL OR group_id NOT IN (SELECT id FROM account_groups WHERE sync_enabled = 0))
+             ORDER BY created_at ASC",
+            ACCOUNT_COLUMNS
+        ),
     )
     .fetch_all(pool)
     .await?;
@@ -29,6 +148,211 @@ pub async fn get_all(pool: &SqlitePool) -> Result<Vec<crate::models::Account>> {
     Ok(accounts)
 }
 
+/// The built-in reminders account, created the first time it's needed
+pub async fn local_account_id(pool: &SqlitePool) -> Result<i64> {
+    let existing: Option<i64> = sqlx::query_scalar("SELECT id FROM accounts WHERE provider = 'local' AND account_name != ? ORDER BY id LIMIT 1")
+        .bind(IMPORTED_ACCOUNT_NAME)
+        .fetch_optional(pool)
+        .await?;
+
+    match existing {
+        Some(id) => Ok(id),
+        None => add(pool, &crate::models::Account::new_local()).await,
+    }
+}
+
+/// The local account imported events go into, created the first time it's
+/// needed
+pub async fn imported_account_id(pool: &SqlitePool) -> Result<i64> {
+    let existing: Option<i64> = sqlx::query_scalar("SELECT id FROM accounts WHERE provider = 'local' AND account_name = ? ORDER BY id LIMIT 1")
+        .bind(IMPORTED_ACCOUNT_NAME)
+        .fetch_optional(pool)
+        .await?;
+
+    match existing {
+        Some(id) => Ok(id),
+        None => add(pool, &crate::models::Account::new_imported()).await,
+    }
+}
+
+/// Ids of the accounts marked as shared team calendars
+pub async fn shared_ids(pool: &SqlitePool) -> Result<Vec<i64>> {
+    let ids = sqlx::query_scalar("SELECT id FROM accounts WHERE is_shared = 1")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(ids)
+}
+
+pub async fn set_shared(pool: &SqlitePool, account_id: i64, is_shared: bool) -> Result<()> {
+    sqlx::query("UPDATE accounts SET is_shared = ? WHERE id = ?")
+        .bind(is_shared)
+        .bind(account_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Mark an account as informational only (holidays, birthdays), so its
+/// events never alert
+pub async fn set_informational(pool: &SqlitePool, account_id: i64, is_informational: bool) -> Result<()> {
+    sqlx::query("UPDATE accounts SET is_informational = ? WHERE id = ?")
+        .bind(is_informational)
+        .bind(account_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Mute an account's alerts, or turn them back on
+pub async fn set_alerts_enabled(pool: &SqlitePool, account_id: i64, alerts_enabled: bool) -> Result<()> {
+    sqlx::query("UPDATE accounts SET alerts_enabled = ? WHERE id = ?")
+        .bind(alerts_enabled)
+        .bind(account_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Where the account's last incremental sync left off (Google Calendar API
+/// accounts only)
+pub async fn sync_token(pool: &SqlitePool, account_id: i64) -> Result<Option<String>> {
+    let token = sqlx::query_scalar("SELECT sync_token FROM accounts WHERE id = ?")
+        .bind(account_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(token.flatten())
+}
+
+pub async fn set_sync_token(pool: &SqlitePool, account_id: i64, sync_token: Option<&str>) -> Result<()> {
+    sqlx::query("UPDATE accounts SET sync_token = ? WHERE id = ?")
+        .bind(sync_token)
+        .bind(account_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// The validators of the feed response an account last synced from, if
+/// that response was parsed after `since`
+pub async fn feed_validators(pool: &SqlitePool, account_id: i64, since: DateTime<Utc>) -> Result<Option<FeedValidators>> {
+    let row: Option<(Option<String>, Option<String>)> = sqlx::query_as(
+        "SELECT feed_etag, feed_last_modified FROM accounts WHERE id = ? AND feed_validated_at > ?",
+    )
+    .bind(account_id)
+    .bind(since)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row
+        .map(|(etag, last_modified)| FeedValidators { etag, last_modified })
+        .filter(|validators| !validators.is_empty()))
+}
+
+/// Remember the validators of the feed response just synced
+pub async fn set_feed_validators(pool: &SqlitePool, account_id: i64, validators: &FeedValidators) -> Result<()> {
+    sqlx::query("UPDATE accounts SET feed_etag = ?, feed_last_modified = ?, feed_validated_at = ? WHERE id = ?")
+        .bind(&validators.etag)
+        .bind(&validators.last_modified)
+        .bind(Utc::now())
+        .bind(account_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// The hash of the feed body last parsed (see `set_feed_hash`), if that
+/// parse was after `since`
+pub async fn feed_hash(pool: &SqlitePool, account_id: i64, since: DateTime<Utc>) -> Result<Option<String>> {
+    let hash: Option<Option<String>> = sqlx::query_scalar(
+        "SELECT feed_hash FROM accounts WHERE id = ? AND feed_validated_at > ?",
+    )
+    .bind(account_id)
+    .bind(since)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(hash.flatten())
+}
+
+/// Remember the hash of the feed body just synced, or forget it with
+/// `None`; set alongside `set_feed_validators`, whose time it shares
+pub async fn set_feed_hash(pool: &SqlitePool, account_id: i64, hash: Option<&str>) -> Result<()> {
+    sqlx::query("UPDATE accounts SET feed_hash = ? WHERE id = ?")
+        .bind(hash)
+        .bind(account_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Sync this account every `sync_interval` seconds, or with the global
+/// interval when `None`
+pub async fn set_sync_interval(pool: &SqlitePool, account_id: i64, sync_interval: Option<i32>) -> Result<()> {
+    sqlx::query("UPDATE accounts SET sync_interval = ? WHERE id = ?")
+        .bind(sync_interval)
+        .bind(account_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Read the account's floating times in `timezone` (an IANA name), or
+/// per the `floating_timezone` setting when `None`. The stored feed
+/// validators are dropped so the next sync parses the feed again.
+pub async fn set_timezone(pool: &SqlitePool, account_id: i64, timezone: Option<&str>) -> Result<()> {
+    sqlx::query("UPDATE accounts SET timezone = ?, feed_validated_at = NULL WHERE id = ?")
+        .bind(timezone)
+        .bind(account_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Drop every account's feed validators, so the next sync parses each
+/// feed again even if it hasn't changed
+pub async fn forget_parsed_feeds(pool: &SqlitePool) -> Result<()> {
+    sqlx::query("UPDATE accounts SET feed_validated_at = NULL")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Remember the name and color a feed gives its calendar; a `None` keeps
+/// the one stored, since a feed that stops sending them hasn't renamed it
+pub async fn set_calendar_metadata(pool: &SqlitePool, account_id: i64, name: Option<&str>, color: Option<&str>) -> Result<()> {
+    sqlx::query("UPDATE accounts SET calendar_name = COALESCE(?, calendar_name), calendar_color = COALESCE(?, calendar_color) WHERE id = ?")
+        .bind(name)
+        .bind(color)
+        .bind(account_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Remember how often the feed asks to be fetched; a `None` (the feed
+/// stopped saying) goes back to the global interval
+pub async fn set_feed_refresh_interval(pool: &SqlitePool, account_id: i64, interval: Option<i32>) -> Result<()> {
+    sqlx::query("UPDATE accounts SET feed_refresh_interval = ? WHERE id = ?")
+        .bind(interval)
+        .bind(account_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
 pub async fn update_sync_time(pool: &SqlitePool, account_id: i64) -> Result<()> {
     let now = chrono::Utc::now();
     sqlx::query("UPDATE accounts SET last_synced_at = ? WHERE id = ?")
@@ -40,6 +364,19 @@ pub async fn update_sync_time(pool: &SqlitePool, account_id: i64) -> Result<()>
     Ok(())
 }
 
+/// Remember why an account's sync failed, or clear the error once it
+/// syncs again (`error` is `None`)
+pub async fn record_sync_error(pool: &SqlitePool, account_id: i64, error: Option<&str>) -> Result<()> {
+    sqlx::query("UPDATE accounts SET last_sync_error = ?, last_sync_error_at = ? WHERE id = ?")
+        .bind(error.map(crate::database::sync_history::redact_urls))
+        .bind(error.map(|_| Utc::now()))
+        .bind(account_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -57,7 +394,25 @@ mod tests {
                 account_name TEXT NOT NULL,
                 auth_data TEXT NOT NULL,
                 refresh_token TEXT,
-                last_synced_at DATETIME
+                last_synced_at DATETIME,
+                group_id INTEGER,
+                is_shared BOOLEAN NOT NULL DEFAULT 0,
+                is_informational BOOLEAN NOT NULL DEFAULT 0,
+                alerts_enabled BOOLEAN NOT NULL DEFAULT 1,
+                sync_token TEXT,
+                feed_etag TEXT,
+                feed_last_modified TEXT,
+                feed_validated_at DATETIME,
+                feed_hash TEXT,
+                username TEXT,
+                sync_interval INTEGER,
+                last_sync_error TEXT,
+                last_sync_error_at DATETIME,
+                timezone TEXT,
+                calendar_name TEXT,
+                calendar_color TEXT,
+                feed_refresh_interval INTEGER,
+                secret_key TEXT
             )
             "#,
         )
@@ -121,6 +476,27 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_get_by_id() {
+        let pool = setup_test_db().await;
+        let id = add(&pool, &Account::new_proton("Work".to_string(), "https://example.com/cal.ics".to_string())).await.unwrap();
+
+        assert_eq!(get(&pool, id).await.unwrap().map(|a| a.account_name).as_deref(), Some("Work"));
+        assert!(get(&pool, id + 1).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_local_accounts() {
+        let pool = setup_test_db().await;
+        let imported = imported_account_id(&pool).await.unwrap();
+        let reminders = local_account_id(&pool).await.unwrap();
+
+        assert_ne!(imported, reminders);
+        assert_eq!(imported_account_id(&pool).await.unwrap(), imported);
+        assert_eq!(local_account_id(&pool).await.unwrap(), reminders);
+        assert_eq!(get(&pool, imported).await.unwrap().unwrap().account_name, IMPORTED_ACCOUNT_NAME);
+    }
+
     #[tokio::test]
     async fn test_proton_account_persistence() {
         let pool = setup_test_db().await;
@@ -141,5 +517,162 @@ mod tests {
             "https://calendar.proton.me/ics/secret"
         );
         assert_eq!(retrieved.refresh_token, None);
+        assert_eq!(retrieved.username, None);
+    }
+
+    #[tokio::test]
+    async fn test_feed_auth_persistence() {
+        let pool = setup_test_db().await;
+        let account = Account::new_proton("Baikal".to_string(), "https://dav.example.com/cal.ics".to_string())
+            .with_basic_auth("alice".to_string(), "s3cret".to_string())
+            .with_request_headers("X-Api-Key: abc123\nCookie: session=1; theme=dark".to_string());
+
+        let id = add(&pool, &account).await.unwrap();
+        let retrieved = &get_all(&pool).await.unwrap()[0];
+        assert_eq!(retrieved.username.as_deref(), Some("alice"));
+        // The password and headers are in the keyring, not the database
+        assert_eq!((retrieved.password.as_deref(), retrieved.request_headers.as_deref()), (None, None));
+        let key = retrieved.secret_key.clone().unwrap();
+        let secrets = retrieved.secrets().await.unwrap();
+        assert_eq!(secrets.password.as_deref(), Some("s3cret"));
+        assert_eq!(secrets.request_headers.as_deref(), Some("X-Api-Key: abc123\nCookie: session=1; theme=dark"));
+
+        delete(&pool, id).await.unwrap();
+        assert!(crate::keyring::load(&key).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sync_interval() {
+        let pool = setup_test_db().await;
+        let id = add(&pool, &Account::new_proton("Holidays".to_string(), "https://example.com/holidays.ics".to_string())).await.unwrap();
+        assert_eq!(get_all(&pool).await.unwrap()[0].sync_interval, None);
+
+        set_sync_interval(&pool, id, Some(3600)).await.unwrap();
+        assert_eq!(get_all(&pool).await.unwrap()[0].sync_interval, Some(3600));
+        set_sync_interval(&pool, id, None).await.unwrap();
+        assert_eq!(get_all(&pool).await.unwrap()[0].sync_interval, None);
+    }
+
+    #[tokio::test]
+    async fn test_delete() {
+        let pool = setup_test_db().await;
+        let id = add(&pool, &Account::new_proton("Work".to_string(), "https://example.com/cal.ics".to_string())).await.unwrap();
+
+        delete(&pool, id).await.unwrap();
+        assert!(get(&pool, id).await.unwrap().is_none());
+        // Already gone is fine
+        delete(&pool, id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_feed_validators() {
+        let pool = setup_test_db().await;
+        let id = add(&pool, &Account::new_proton("Work".to_string(), "https://example.com/cal.ics".to_string())).await.unwrap();
+        let an_hour_ago = Utc::now() - chrono::Duration::hours(1);
+        assert_eq!(feed_validators(&pool, id, an_hour_ago).await.unwrap(), None);
+
+        let validators = FeedValidators { etag: Some("\"v1\"".to_string()), last_modified: None };
+        set_feed_validators(&pool, id, &validators).await.unwrap();
+        assert_eq!(feed_validators(&pool, id, an_hour_ago).await.unwrap(), Some(validators));
+        // Too old to skip a re-parse
+        assert_eq!(feed_validators(&pool, id, Utc::now() + chrono::Duration::seconds(1)).await.unwrap(), None);
+
+        set_feed_validators(&pool, id, &FeedValidators::default()).await.unwrap();
+        assert_eq!(feed_validators(&pool, id, an_hour_ago).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_feed_hash_expires_with_the_validators() {
+        let pool = setup_test_db().await;
+        let id = add(&pool, &Account::new_proton("Work".to_string(), "https://example.com/cal.ics".to_string())).await.unwrap();
+        let an_hour_ago = Utc::now() - chrono::Duration::hours(1);
+        assert_eq!(feed_hash(&pool, id, an_hour_ago).await.unwrap(), None);
+
+        set_feed_hash(&pool, id, Some("1f2e3d")).await.unwrap();
+        set_feed_validators(&pool, id, &FeedValidators::default()).await.unwrap();
+        assert_eq!(feed_hash(&pool, id, an_hour_ago).await.unwrap().as_deref(), Some("1f2e3d"));
+        assert_eq!(feed_hash(&pool, id, Utc::now() + chrono::Duration::seconds(1)).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_update_forgets_the_old_feed_only_when_the_url_changes() {
+        let pool = setup_test_db().await;
+        let mut account = Account::new_generic("Wrok".to_string(), "https://example.com/cal.ics".to_string());
+        account.id = Some(add(&pool, &account).await.unwrap());
+        let id = account.id.unwrap();
+        let an_hour_ago = Utc::now() - chrono::Duration::hours(1);
+        let validators = FeedValidators { etag: Some("\"v1\"".to_string()), last_modified: None };
+        set_feed_validators(&pool, id, &validators).await.unwrap();
+        set_feed_hash(&pool, id, Some("1f2e3d")).await.unwrap();
+
+        account.account_name = "Work".to_string();
+        update(&pool, &account).await.unwrap();
+        assert_eq!(get(&pool, id).await.unwrap().unwrap().account_name, "Work");
+        assert_eq!(feed_validators(&pool, id, an_hour_ago).await.unwrap(), Some(validators));
+
+        account.auth_data = "https://example.com/work.ics".to_string();
+        update(&pool, &account).await.unwrap();
+        assert_eq!(get(&pool, id).await.unwrap().unwrap().auth_data, "https://example.com/work.ics");
+        assert_eq!(feed_validators(&pool, id, an_hour_ago).await.unwrap(), None);
+        assert_eq!(feed_hash(&pool, id, an_hour_ago).await.unwrap(), None);
+
+        // A feed moved to Proton goes behind Proton's breaker
+        account.provider = "proton".to_string();
+        account.auth_data = "https://calendar.proton.me/api/calendar/v1/url/abc/calendar.ics".to_string();
+        update(&pool, &account).await.unwrap();
+        assert_eq!(get(&pool, id).await.unwrap().unwrap().provider, "proton");
+
+        account.id = None;
+        assert!(update(&pool, &account).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_timezone_change_forces_a_reparse() {
+        let pool = setup_test_db().await;
+        let id = add(&pool, &Account::new_proton("Team".to_string(), "https://example.com/cal.ics".to_string())).await.unwrap();
+        let an_hour_ago = Utc::now() - chrono::Duration::hours(1);
+        let validators = FeedValidators { etag: Some("\"v1\"".to_string()), last_modified: None };
+        set_feed_validators(&pool, id, &validators).await.unwrap();
+
+        set_timezone(&pool, id, Some("Europe/Paris")).await.unwrap();
+        assert_eq!(get(&pool, id).await.unwrap().unwrap().timezone.as_deref(), Some("Europe/Paris"));
+        assert_eq!(feed_validators(&pool, id, an_hour_ago).await.unwrap(), None);
+
+        set_timezone(&pool, id, None).await.unwrap();
+        assert_eq!(get(&pool, id).await.unwrap().unwrap().timezone, None);
+
+        // The computer's clocks changing does the same for every account
+        set_feed_validators(&pool, id, &validators).await.unwrap();
+        forget_parsed_feeds(&pool).await.unwrap();
+        assert_eq!(feed_validators(&pool, id, an_hour_ago).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_calendar_metadata_is_kept_until_the_feed_changes_it() {
+        let pool = setup_test_db().await;
+        let id = add(&pool, &Account::new_generic("Team".to_string(), "https://example.com/cal.ics".to_string())).await.unwrap();
+
+        set_calendar_metadata(&pool, id, Some("Platform Team"), Some("#1badf8")).await.unwrap();
+        set_calendar_metadata(&pool, id, None, None).await.unwrap();
+        let account = get(&pool, id).await.unwrap().unwrap();
+        assert_eq!((account.calendar_name.as_deref(), account.calendar_color.as_deref()), (Some("Platform Team"), Some("#1badf8")));
+
+        set_calendar_metadata(&pool, id, None, Some("#ff2968")).await.unwrap();
+        assert_eq!(get(&pool, id).await.unwrap().unwrap().calendar_color.as_deref(), Some("#ff2968"));
+    }
+
+    #[tokio::test]
+    async fn test_sync_error_is_kept_until_a_sync_succeeds() {
+        let pool = setup_test_db().await;
+        let id = add(&pool, &Account::new_proton("Work".to_string(), "https://example.com/cal.ics".to_string())).await.unwrap();
+
+        record_sync_error(&pool, id, Some("HTTP 404 from https://example.com/secret/cal.ics")).await.unwrap();
+        let account = get(&pool, id).await.unwrap().unwrap();
+        assert_eq!(account.last_sync_error.as_deref(), Some("HTTP 404 from <example.com>"));
+        assert!(account.last_sync_error_at.is_some());
+
+        record_sync_error(&pool, id, None).await.unwrap();
+        let account = get(&pool, id).await.unwrap().unwrap();
+        assert_eq!((account.last_sync_error, account.last_sync_error_at), (None, None));
     }
 }
@@ -0,0 +1,142 @@
+// file: src/database/attendees.rs
+//! Who's invited to each event, and how they replied. The list comes from
+//! the feed and is replaced whenever a sync brings a different one.
+
+use anyhow::Result;
+use sqlx::{FromRow, SqliteConnection, SqlitePool};
+use std::collections::HashMap;
+
+use crate::models::{Attendee, EventId};
+
+const SELECT: &str =
+    "SELECT email, name, rsvp, is_organizer FROM event_attendees WHERE event_id = ? ORDER BY is_organizer DESC, email";
+
+/// Replace an event's attendees. Returns whether anything changed.
+pub async fn set_for_event(conn: &mut SqliteConnection, event_id: EventId, attendees: &[Attendee]) -> Result<bool> {
+    let stored: Vec<Attendee> = sqlx::query_as(SELECT).bind(event_id).fetch_all(&mut *conn).await?;
+    if stored == attendees {
+        return Ok(false);
+    }
+
+    sqlx::query("DELETE FROM event_attendees WHERE event_id = ?")
+        .bind(event_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for attendee in attendees {
+        sqlx::query("INSERT OR IGNORE INTO event_attendees (event_id, email, name, rsvp, is_organizer) VALUES (?, ?, ?, ?, ?)")
+            .bind(event_id)
+            .bind(&attendee.email)
+            .bind(&attendee.name)
+            .bind(attendee.rsvp)
+            .bind(attendee.is_organizer)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(true)
+}
+
+#[derive(FromRow)]
+struct EventAttendee {
+    external_id: String,
+    #[sqlx(flatten)]
+    attendee: Attendee,
+}
+
+/// Every stored attendee list of an account, by the event's external ID,
+/// so a sync can tell which lists changed without a query per event
+pub async fn get_for_account(conn: &mut SqliteConnection, account_id: i64) -> Result<HashMap<String, Vec<Attendee>>> {
+    let rows: Vec<EventAttendee> = sqlx::query_as(
+        "SELECT e.external_id, a.email, a.name, a.rsvp, a.is_organizer
+         FROM event_attendees a JOIN events e ON e.id = a.event_id
+         WHERE e.account_id = ?
+         ORDER BY e.external_id, a.is_organizer DESC, a.email"
+    )
+    .bind(account_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut lists: HashMap<String, Vec<Attendee>> = HashMap::new();
+    for row in rows {
+        lists.entry(row.external_id).or_default().push(row.attendee);
+    }
+    Ok(lists)
+}
+
+/// Events that any of `addresses` declined
+pub async fn declined_by(pool: &SqlitePool, addresses: &[String]) -> Result<Vec<EventId>> {
+    if addresses.is_empty() {
+        return Ok(Vec::new());
+    }
+    let events = sqlx::query_scalar(
+        "SELECT DISTINCT event_id FROM event_attendees
+         WHERE rsvp = 'DECLINED' AND email IN (SELECT value FROM json_each(?))"
+    )
+    .bind(serde_json::to_string(addresses)?)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(events)
+}
+
+/// An event's attendees, organizer first
+pub async fn get_for_event(pool: &SqlitePool, event_id: EventId) -> Result<Vec<Attendee>> {
+    let attendees = sqlx::query_as(SELECT).bind(event_id).fetch_all(pool).await?;
+
+    Ok(attendees)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Rsvp;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect(":memory:").await.unwrap();
+        sqlx::query(
+            r#"
+            CREATE TABLE event_attendees (
+                event_id INTEGER NOT NULL,
+                email TEXT NOT NULL,
+                name TEXT,
+                rsvp TEXT NOT NULL DEFAULT 'NEEDS-ACTION',
+                is_organizer BOOLEAN NOT NULL DEFAULT 0,
+                PRIMARY KEY(event_id, email)
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_set_for_event_replaces_the_list() {
+        let pool = setup_test_db().await;
+        let event_id = EventId(7);
+        let mut attendees = vec![
+            Attendee { email: "dana@example.com".to_string(), name: Some("Dana".to_string()), rsvp: Rsvp::Accepted, is_organizer: true },
+            Attendee { email: "sam@example.com".to_string(), name: None, rsvp: Rsvp::NeedsAction, is_organizer: false },
+        ];
+
+        let mut conn = pool.acquire().await.unwrap();
+        assert!(set_for_event(&mut conn, event_id, &attendees).await.unwrap());
+        assert!(!set_for_event(&mut conn, event_id, &attendees).await.unwrap());
+        drop(conn);
+        assert_eq!(get_for_event(&pool, event_id).await.unwrap(), attendees);
+
+        attendees[1].rsvp = Rsvp::Declined;
+        let mut conn = pool.acquire().await.unwrap();
+        assert!(set_for_event(&mut conn, event_id, &attendees[1..]).await.unwrap());
+        drop(conn);
+        assert_eq!(get_for_event(&pool, event_id).await.unwrap(), attendees[1..]);
+        assert!(get_for_event(&pool, EventId(8)).await.unwrap().is_empty());
+
+        assert_eq!(declined_by(&pool, &["sam@example.com".to_string()]).await.unwrap(), vec![event_id]);
+        assert!(declined_by(&pool, &["dana@example.com".to_string()]).await.unwrap().is_empty());
+        assert!(declined_by(&pool, &[]).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+// file: src/database/checklists.rs
+//! Per-event preparation checklists. Items hang off the event row, so they
+//! survive resyncs and go away with the event.
+
+use anyhow::{bail, Result};
+use sqlx::SqlitePool;
+
+use crate::models::{ChecklistItem, EventId};
+
+/// Add an item to the end of an event's checklist
+pub async fn add(pool: &SqlitePool, event_id: EventId, text: &str) -> Result<ChecklistItem> {
+    let text = text.trim();
+    if text.is_empty() {
+        bail!("Checklist item is empty");
+    }
+
+    let result = sqlx::query("INSERT INTO event_checklist_items (event_id, text) VALUES (?, ?)")
+        .bind(event_id)
+        .bind(text)
+        .execute(pool)
+        .await?;
+
+    Ok(ChecklistItem {
+        id: result.last_insert_rowid(),
+        event_id,
+        text: text.to_string(),
+        is_done: false,
+    })
+}
+
+/// Tick or untick an item
+pub async fn set_done(pool: &SqlitePool, item_id: i64, done: bool) -> Result<()> {
+    sqlx::query("UPDATE event_checklist_items SET is_done = ? WHERE id = ?")
+        .bind(done)
+        .bind(item_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn remove(pool: &SqlitePool, item_id: i64) -> Result<()> {
+    sqlx::query("DELETE FROM event_checklist_items WHERE id = ?")
+        .bind(item_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// An event's checklist, in the order it was written
+pub async fn get_for_event(pool: &SqlitePool, event_id: EventId) -> Result<Vec<ChecklistItem>> {
+    let items = sqlx::query_as(
+        "SELECT id, event_id, text, is_done FROM event_checklist_items WHERE event_id = ? ORDER BY id"
+    )
+    .bind(event_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(items)
+}
+
+/// Items still to do across `event_ids` (the copies of one meeting in
+/// several calendars), without repeats
+pub async fn unchecked(pool: &SqlitePool, event_ids: &[EventId]) -> Result<Vec<String>> {
+    let mut texts: Vec<String> = Vec::new();
+    for &event_id in event_ids {
+        for item in get_for_event(pool, event_id).await? {
+            if !item.is_done && !texts.contains(&item.text) {
+                texts.push(item.text);
+            }
+        }
+    }
+
+    Ok(texts)
+}
@@ -1,65 +1,218 @@
 // file: src/database/events.rs
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use sqlx::SqlitePool;
 
-pub async fn get_upcoming(pool: &SqlitePool) -> Result<Vec<crate::models::CalendarEvent>> {
+use crate::models::{CalendarEvent, EventId, ExternalEventId, DUPLICATE_START_TOLERANCE_MINUTES};
+
+/// Rows that are the same meeting as `id`, including itself, by the rule in
+/// `CalendarEvent::is_same_meeting`. Bind `TOLERANCE_SECONDS`, then the id.
+const SAME_MEETING: &str = "SELECT d.id FROM events e
+    JOIN events d ON (d.external_id = e.external_id AND d.start_time = e.start_time)
+        OR (d.account_id != e.account_id
+            AND lower(trim(d.title)) = lower(trim(e.title))
+            AND abs(strftime('%s', d.start_time) - strftime('%s', e.start_time)) <= ?)
+    WHERE e.id = ?";
+
+/// `DUPLICATE_START_TOLERANCE_MINUTES`, for `SAME_MEETING`
+const TOLERANCE_SECONDS: i64 = DUPLICATE_START_TOLERANCE_MINUTES * 60;
+
+/// The columns a `CalendarEvent` is read from, for queries put together
+/// at run time that `query_as!` can't check
+pub(crate) const EVENT_COLUMNS: &str = "id, external_id, account_id, title, description, start_time, end_time, video_link, video_platform, snooze_count, has_alerted, last_alert_threshold, is_dismissed, last_snoozed_at, is_all_day, meeting_id, meeting_passcode, dial_in_number, dial_in_conference_id, title_override, source_timezone, snoozed_until, location, reminder_minutes, is_free, created_at, updated_at";
+
+/// Snoozes allowed per event
+const MAX_SNOOZES: i32 = 3;
+
+pub async fn get_upcoming(pool: &SqlitePool) -> Result<Vec<CalendarEvent>> {
     // This function is used for the alerts page, showing events in the next few days
-    // NOT used for the main calendar view (which loads 6 months of events)
+    // NOT used for the main calendar view (see `get_to_window_end`)
     let now = chrono::Utc::now();
     let days_ahead = now + chrono::Duration::days(3); // Show next 3 days
 
-    let events = sqlx::query_as::<_, crate::models::CalendarEvent>(
+    get_between(pool, now, days_ahead).await
+}
+
+/// Undismissed events from `now` to the end of the sync window, for the
+/// main calendar view
+pub async fn get_to_window_end(pool: &SqlitePool, now: DateTime<Utc>) -> Result<Vec<CalendarEvent>> {
+    let settings = crate::database::settings::get(pool).await?;
+    get_between(pool, now, now + settings.sync_days_ahead_duration()).await
+}
+
+/// Undismissed events starting between `from` and `to`, earliest first
+pub async fn get_between(pool: &SqlitePool, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<CalendarEvent>> {
+    let events = sqlx::query_as!(
+        CalendarEvent,
         r#"
         SELECT
-            id, external_id, account_id, title, description, start_time, end_time,
-            video_link, video_platform, snooze_count, has_alerted, last_alert_threshold,
-            is_dismissed, created_at, updated_at
+            id as "id?: EventId", external_id as "external_id!: ExternalEventId", account_id, title, description,
+            start_time as "start_time!: DateTime<Utc>", end_time as "end_time!: DateTime<Utc>",
+            video_link, video_platform, snooze_count as "snooze_count!: i32", has_alerted as "has_alerted!: bool",
+            last_alert_threshold as "last_alert_threshold: i32", is_dismissed as "is_dismissed!: bool",
+            last_snoozed_at as "last_snoozed_at: DateTime<Utc>", is_all_day as "is_all_day!: bool",
+            meeting_id, meeting_passcode, dial_in_number, dial_in_conference_id, title_override, source_timezone,
+            snoozed_until as "snoozed_until: DateTime<Utc>",
+            location,
+            reminder_minutes,
+            is_free as "is_free!: bool",
+            created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
         FROM events
         WHERE start_time >= ?
             AND start_time <= ?
             AND is_dismissed = 0
+            AND removed_at IS NULL
         ORDER BY start_time ASC
         "#,
+        from,
+        to,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(events)
+}
+
+/// Undismissed events that may start within `from..=to` once all-day
+/// events are moved to local midnight: all-day rows are matched with
+/// `all_day_margin` of slack on either side, so callers should filter on
+/// `CalendarEvent::effective_start`. Events of informational accounts, and
+/// of accounts with alerts off themselves or in their group, are left out.
+pub async fn get_alert_candidates(
+    pool: &SqlitePool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    all_day_margin: chrono::Duration,
+) -> Result<Vec<CalendarEvent>> {
+    let all_day_from = from - all_day_margin;
+    let all_day_to = to + all_day_margin;
+    let events = sqlx::query_as!(
+        CalendarEvent,
+        r#"
+        SELECT
+            id as "id?: EventId", external_id as "external_id!: ExternalEventId", account_id, title, description,
+            start_time as "start_time!: DateTime<Utc>", end_time as "end_time!: DateTime<Utc>",
+            video_link, video_platform, snooze_count as "snooze_count!: i32", has_alerted as "has_alerted!: bool",
+            last_alert_threshold as "last_alert_threshold: i32", is_dismissed as "is_dismissed!: bool",
+            last_snoozed_at as "last_snoozed_at: DateTime<Utc>", is_all_day as "is_all_day!: bool",
+            meeting_id, meeting_passcode, dial_in_number, dial_in_conference_id, title_override, source_timezone,
+            snoozed_until as "snoozed_until: DateTime<Utc>",
+            location,
+            reminder_minutes,
+            is_free as "is_free!: bool",
+            created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+        FROM events
+        WHERE (start_time BETWEEN ? AND ? OR (is_all_day = 1 AND start_time BETWEEN ? AND ?))
+            AND is_dismissed = 0
+            AND removed_at IS NULL
+            AND account_id NOT IN (
+                SELECT a.id FROM accounts a LEFT JOIN account_groups g ON g.id = a.group_id
+                WHERE a.is_informational = 1 OR a.alerts_enabled = 0 OR g.alerts_enabled = 0
+            )
+        ORDER BY start_time ASC
+        "#,
+        from,
+        to,
+        all_day_from,
+        all_day_to,
     )
-    .bind(now)
-    .bind(days_ahead)
     .fetch_all(pool)
     .await?;
 
     Ok(events)
 }
 
-pub async fn get_needing_alert(pool: &SqlitePool) -> Result<Vec<crate::models::CalendarEvent>> {
+pub async fn get_by_id(pool: &SqlitePool, event_id: EventId) -> Result<Option<CalendarEvent>> {
+    let event = sqlx::query_as!(
+        CalendarEvent,
+        r#"
+        SELECT
+            id as "id?: EventId", external_id as "external_id!: ExternalEventId", account_id, title, description,
+            start_time as "start_time!: DateTime<Utc>", end_time as "end_time!: DateTime<Utc>",
+            video_link, video_platform, snooze_count as "snooze_count!: i32", has_alerted as "has_alerted!: bool",
+            last_alert_threshold as "last_alert_threshold: i32", is_dismissed as "is_dismissed!: bool",
+            last_snoozed_at as "last_snoozed_at: DateTime<Utc>", is_all_day as "is_all_day!: bool",
+            meeting_id, meeting_passcode, dial_in_number, dial_in_conference_id, title_override, source_timezone,
+            snoozed_until as "snoozed_until: DateTime<Utc>",
+            location,
+            reminder_minutes,
+            is_free as "is_free!: bool",
+            created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+        FROM events
+        WHERE id = ?
+        "#,
+        event_id,
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(event)
+}
+
+pub async fn get_needing_alert(pool: &SqlitePool) -> Result<Vec<CalendarEvent>> {
     let now = chrono::Utc::now();
     let video_threshold = now + chrono::Duration::minutes(3);
     let regular_threshold = now + chrono::Duration::minutes(1);
 
-    let events = sqlx::query_as::<_, crate::models::CalendarEvent>(
+    let events = sqlx::query_as!(
+        CalendarEvent,
         r#"
-        SELECT 
-            id, external_id, account_id, title, description, start_time, end_time,
-            video_link, video_platform, snooze_count, has_alerted, last_alert_threshold,
-            is_dismissed, created_at, updated_at
-        FROM events 
-        WHERE has_alerted = 0 
+        SELECT
+            id as "id?: EventId", external_id as "external_id!: ExternalEventId", account_id, title, description,
+            start_time as "start_time!: DateTime<Utc>", end_time as "end_time!: DateTime<Utc>",
+            video_link, video_platform, snooze_count as "snooze_count!: i32", has_alerted as "has_alerted!: bool",
+            last_alert_threshold as "last_alert_threshold: i32", is_dismissed as "is_dismissed!: bool",
+            last_snoozed_at as "last_snoozed_at: DateTime<Utc>", is_all_day as "is_all_day!: bool",
+            meeting_id, meeting_passcode, dial_in_number, dial_in_conference_id, title_override, source_timezone,
+            snoozed_until as "snoozed_until: DateTime<Utc>",
+            location,
+            reminder_minutes,
+            is_free as "is_free!: bool",
+            created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+        FROM events
+        WHERE has_alerted = 0
             AND is_dismissed = 0
+            AND removed_at IS NULL
             AND (
                 (video_link IS NOT NULL AND start_time <= ?)
                 OR (video_link IS NULL AND start_time <= ?)
             )
         ORDER BY start_time ASC
         "#,
+        video_threshold,
+        regular_threshold,
     )
-    .bind(video_threshold)
-    .bind(regular_threshold)
     .fetch_all(pool)
     .await?;
 
     Ok(events)
 }
 
-pub async fn mark_alerted(pool: &SqlitePool, event_id: &str) -> Result<()> {
-    sqlx::query("UPDATE events SET has_alerted = 1 WHERE id = ?")
+/// Take an event that left its feed out of the calendar. One with local
+/// state (a rename, tags the user added, a checklist) is only marked
+/// removed, so that state is back if the event returns; others are deleted.
+pub(crate) async fn remove_from_feed(conn: &mut sqlx::SqliteConnection, event_id: EventId) -> Result<()> {
+    let kept = sqlx::query(
+        "UPDATE events SET removed_at = ? WHERE id = ? AND (title_override IS NOT NULL
+            OR EXISTS (SELECT 1 FROM event_tags WHERE event_id = events.id AND is_auto = 0)
+            OR EXISTS (SELECT 1 FROM event_checklist_items WHERE event_id = events.id))",
+    )
+    .bind(Utc::now())
+    .bind(event_id)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected()
+        > 0;
+    if !kept {
+        sqlx::query("DELETE FROM events WHERE id = ?").bind(event_id).execute(&mut *conn).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn mark_alerted(pool: &SqlitePool, event_id: EventId) -> Result<()> {
+    sqlx::query(&format!("UPDATE events SET has_alerted = 1 WHERE id IN ({})", SAME_MEETING))
+        .bind(TOLERANCE_SECONDS)
         .bind(event_id)
         .execute(pool)
         .await?;
@@ -67,7 +220,7 @@ pub async fn mark_alerted(pool: &SqlitePool, event_id: &str) -> Result<()> {
     Ok(())
 }
 
-pub async fn snooze(pool: &SqlitePool, event_id: &str) -> Result<()> {
+pub async fn snooze(pool: &SqlitePool, event_id: EventId) -> Result<()> {
     // Check current snooze count
     let snooze_count: i32 =
         sqlx::query_scalar("SELECT snooze_count FROM events WHERE id = ?")
@@ -75,16 +228,18 @@ pub async fn snooze(pool: &SqlitePool, event_id: &str) -> Result<()> {
             .fetch_one(pool)
             .await?;
 
-    if snooze_count >= 3 {
+    if snooze_count >= MAX_SNOOZES {
         return Err(anyhow::anyhow!("Maximum snooze limit reached"));
     }
 
     // Update snooze count and timestamp
     let now = chrono::Utc::now();
-    sqlx::query(
-        "UPDATE events SET snooze_count = snooze_count + 1, last_snoozed_at = ?, has_alerted = 0 WHERE id = ?"
-    )
+    sqlx::query(&format!(
+        "UPDATE events SET snooze_count = snooze_count + 1, last_snoozed_at = ?, snoozed_until = NULL, has_alerted = 0 WHERE id IN ({})",
+        SAME_MEETING
+    ))
     .bind(now)
+    .bind(TOLERANCE_SECONDS)
     .bind(event_id)
     .execute(pool)
     .await?;
@@ -92,8 +247,102 @@ pub async fn snooze(pool: &SqlitePool, event_id: &str) -> Result<()> {
     Ok(())
 }
 
-pub async fn dismiss(pool: &SqlitePool, event_id: &str) -> Result<()> {
-    sqlx::query("UPDATE events SET is_dismissed = 1 WHERE id = ?")
+/// Snooze several alerts at once until `until`, all or none. Events already
+/// at the snooze limit are left alone; returns how many were snoozed.
+pub async fn snooze_all(pool: &SqlitePool, event_ids: &[EventId], until: DateTime<Utc>) -> Result<usize> {
+    let now = chrono::Utc::now();
+    let mut snoozed = 0;
+    let mut tx = pool.begin().await?;
+    for &event_id in event_ids {
+        let updated = sqlx::query(&format!(
+            "UPDATE events SET snooze_count = snooze_count + 1, last_snoozed_at = ?, snoozed_until = ?, has_alerted = 0
+             WHERE id IN ({}) AND snooze_count < ?",
+            SAME_MEETING
+        ))
+        .bind(now)
+        .bind(until)
+        .bind(TOLERANCE_SECONDS)
+        .bind(event_id)
+        .bind(MAX_SNOOZES)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if updated > 0 {
+            snoozed += 1;
+        }
+    }
+    tx.commit().await?;
+
+    Ok(snoozed)
+}
+
+/// Dismiss several alerts at once, all or none
+pub async fn dismiss_all(pool: &SqlitePool, event_ids: &[EventId]) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    for &event_id in event_ids {
+        sqlx::query(&format!("UPDATE events SET is_dismissed = 1 WHERE id IN ({})", SAME_MEETING))
+            .bind(TOLERANCE_SECONDS)
+            .bind(event_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Create a reminder under the built-in local account. It alerts like any
+/// other event; a meeting link, if given, gets a Join button.
+pub async fn add_reminder(pool: &SqlitePool, title: &str, start_time: DateTime<Utc>, link: Option<&str>) -> Result<CalendarEvent> {
+    let account_id = super::accounts::local_account_id(pool).await?;
+    let link = link.map(str::trim).filter(|l| !l.is_empty());
+    let video = link.and_then(|l| crate::utils::extract_video_link(None, Some(l)));
+    let external_id = format!("local-{}-{:08x}", start_time.timestamp(), rand::random::<u32>());
+
+    let mut tx = pool.begin().await?;
+    let event_id = EventId(
+        sqlx::query(
+            "INSERT INTO events (external_id, account_id, title, start_time, end_time, video_link, video_platform, meeting_id, meeting_passcode)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&external_id)
+        .bind(account_id)
+        .bind(title.trim())
+        .bind(start_time)
+        .bind(start_time)
+        .bind(video.as_ref().map(|v| v.url.as_str()).or(link))
+        .bind(video.as_ref().map(|v| v.platform.as_str()))
+        .bind(video.as_ref().and_then(|v| v.meeting_id.as_deref()))
+        .bind(video.as_ref().and_then(|v| v.password.as_deref()))
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid(),
+    );
+    super::tags::set_auto_tags(&mut tx, event_id, &crate::utils::categorize_event(title, None)).await?;
+    tx.commit().await?;
+
+    get_by_id(pool, event_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Reminder {} vanished after insert", event_id))
+}
+
+/// Rename an event locally, or restore the feed's title with `None`.
+/// Applies to every calendar's copy of the meeting and survives syncs.
+pub async fn set_title_override(pool: &SqlitePool, event_id: EventId, title: Option<&str>) -> Result<()> {
+    let title = title.map(str::trim).filter(|t| !t.is_empty());
+    sqlx::query(&format!("UPDATE events SET title_override = ? WHERE id IN ({})", SAME_MEETING))
+        .bind(title)
+        .bind(TOLERANCE_SECONDS)
+        .bind(event_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn dismiss(pool: &SqlitePool, event_id: EventId) -> Result<()> {
+    sqlx::query(&format!("UPDATE events SET is_dismissed = 1 WHERE id IN ({})", SAME_MEETING))
+        .bind(TOLERANCE_SECONDS)
         .bind(event_id)
         .execute(pool)
         .await?;
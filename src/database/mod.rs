@@ -2,14 +2,54 @@
 
 use anyhow::{Context, Result};
 use log::{info, debug, warn};
-use sqlx::{migrate::MigrateDatabase, sqlite::{SqlitePool, SqlitePoolOptions, SqliteConnectOptions}, Sqlite, Row};
+use sqlx::{migrate::MigrateDatabase, sqlite::{SqlitePool, SqlitePoolOptions, SqliteConnectOptions}, Connection, Sqlite, Row};
 use std::time::Duration;
 use std::str::FromStr;
 
 // Declare submodules
+pub mod account_groups;
 pub mod accounts;
+pub mod attendees;
+pub mod checklists;
 pub mod events;
 pub mod settings;
+pub mod stats;
+pub mod sync_history;
+pub mod tags;
+
+/// Held by transactions that read before they write (see `begin_store`)
+static STORE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Begin a transaction that reads before it writes, such as a sync storing
+/// a feed. Under WAL, one that read while another connection committed
+/// would find its snapshot stale when it came to write and fail with
+/// SQLITE_BUSY_SNAPSHOT, which `busy_timeout` doesn't retry. So the write
+/// lock is taken up front, as `BEGIN IMMEDIATE` would (sqlx only issues a
+/// plain `BEGIN`), and writers elsewhere wait for the commit instead.
+/// These transactions also take turns; keep the guard until it's done.
+pub async fn begin_store(pool: &SqlitePool) -> Result<(tokio::sync::MutexGuard<'static, ()>, sqlx::Transaction<'static, Sqlite>)> {
+    let turn = STORE_LOCK.lock().await;
+    let mut tx = pool.begin().await?;
+    sqlx::query("UPDATE accounts SET id = id WHERE 0").execute(&mut *tx).await?;
+    Ok((turn, tx))
+}
+
+/// Whether `error` means the transaction it happened in can't go on (the
+/// database is locked, full, unreadable or gone), as opposed to one
+/// statement being refused
+pub fn ends_transaction(error: &anyhow::Error) -> bool {
+    // Primary result codes: BUSY, LOCKED, NOMEM, READONLY, IOERR, CORRUPT,
+    // FULL, CANTOPEN, NOTADB
+    const FATAL: [i64; 9] = [5, 6, 7, 8, 10, 11, 13, 14, 26];
+    match error.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::Database(e)) => e
+            .code()
+            .and_then(|code| code.parse::<i64>().ok())
+            .is_none_or(|code| FATAL.contains(&(code & 0xff))),
+        Some(sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::WorkerCrashed) => true,
+        _ => false,
+    }
+}
 
 /// Connection pool statistics for monitoring
 #[derive(Debug, Clone)]
@@ -30,7 +70,21 @@ impl Database {
     }
 
     pub async fn new_with_retries(max_retries: u32) -> Result<Self> {
-        let db_path = "sqlite:openchime.db?mode=rwc";
+        Self::connect("sqlite:openchime.db?mode=rwc", max_retries).await
+    }
+
+    /// Open (creating if needed) the database file at `path`, e.g. for a named profile
+    pub async fn open(path: &std::path::Path) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create database directory {}", parent.display()))?;
+        }
+
+        let db_path = format!("sqlite:{}?mode=rwc", path.display());
+        Self::connect(&db_path, 3).await
+    }
+
+    async fn connect(db_path: &str, max_retries: u32) -> Result<Self> {
 
         // Create database if it doesn't exist
         let db_exists = Sqlite::database_exists(db_path)
@@ -98,6 +152,7 @@ impl Database {
         );
 
         // Run schema migrations
+        collapse_duplicate_events(&pool).await.context("Failed to remove duplicate events")?;
         run_schema(&pool).await.context("Failed to run database schema")?;
 
         // Ensure specific migrations for existing databases
@@ -137,18 +192,26 @@ impl Database {
         events::get_needing_alert(&self.pool).await
     }
 
-    pub async fn mark_event_alerted(&self, event_id: &str) -> Result<()> {
+    pub async fn mark_event_alerted(&self, event_id: crate::models::EventId) -> Result<()> {
         events::mark_alerted(&self.pool, event_id).await
     }
 
-    pub async fn snooze_event(&self, event_id: &str) -> Result<()> {
+    pub async fn snooze_event(&self, event_id: crate::models::EventId) -> Result<()> {
         events::snooze(&self.pool, event_id).await
     }
 
-    pub async fn dismiss_event(&self, event_id: &str) -> Result<()> {
+    pub async fn dismiss_event(&self, event_id: crate::models::EventId) -> Result<()> {
         events::dismiss(&self.pool, event_id).await
     }
 
+    pub async fn snooze_events(&self, event_ids: &[crate::models::EventId], until: chrono::DateTime<chrono::Utc>) -> Result<usize> {
+        events::snooze_all(&self.pool, event_ids, until).await
+    }
+
+    pub async fn dismiss_events(&self, event_ids: &[crate::models::EventId]) -> Result<()> {
+        events::dismiss_all(&self.pool, event_ids).await
+    }
+
     // --- Settings Delegates ---
 
     pub async fn get_settings(&self) -> Result<crate::models::Settings> {
@@ -165,6 +228,10 @@ impl Database {
         accounts::add(&self.pool, account).await
     }
 
+    pub async fn update_account(&self, account: &crate::models::Account) -> Result<()> {
+        accounts::update(&self.pool, account).await
+    }
+
     pub async fn get_accounts(&self) -> Result<Vec<crate::models::Account>> {
         accounts::get_all(&self.pool).await
     }
@@ -174,6 +241,25 @@ impl Database {
     }
 }
 
+/// Keep only the newest row of each feed event in databases from before
+/// schema.sql's unique index on (external_id, account_id), which couldn't be
+/// created over the duplicates they may hold. Runs until that index exists.
+async fn collapse_duplicate_events(pool: &SqlitePool) -> Result<()> {
+    let needed: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'events')
+            AND NOT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'index' AND name = 'idx_events_external_account')",
+    )
+    .fetch_one(pool)
+    .await?;
+    if needed {
+        info!("Migrating: Removing duplicate events");
+        sqlx::query("DELETE FROM events WHERE id NOT IN (SELECT MAX(id) FROM events GROUP BY external_id, account_id)")
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
 async fn run_schema(pool: &SqlitePool) -> Result<()> {
     let schema = include_str!("schema.sql");
     
@@ -210,6 +296,153 @@ async fn run_schema(pool: &SqlitePool) -> Result<()> {
 }
 
 async fn ensure_migrations(pool: &SqlitePool) -> Result<()> {
+    // Older databases don't allow every provider; the CHECK has to be rebuilt
+    let accounts_sql: String = sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'accounts'")
+        .fetch_one(pool)
+        .await
+        .context("Failed to read accounts table definition")?;
+    if !accounts_sql.contains("'local'") || !accounts_sql.contains("'caldav'") || !accounts_sql.contains("'generic'") {
+        info!("Migrating: Allowing local reminders, CalDAV and generic ICS accounts in accounts table");
+        rebuild_accounts_table(pool).await.context("Failed to rebuild accounts table")?;
+    }
+    if !accounts_sql.contains("'generic'") {
+        // Before generic feeds had a provider of their own, every non-Google
+        // feed was filed under Proton and shared its circuit breaker
+        info!("Migrating: Moving non-Proton feeds to the generic provider");
+        sqlx::query("UPDATE accounts SET provider = 'generic' WHERE provider = 'proton' AND auth_data NOT LIKE '%proton.me%'")
+            .execute(pool)
+            .await
+            .context("Failed to move non-Proton feeds to the generic provider")?;
+    }
+
+    let account_columns: Vec<String> = sqlx::query("PRAGMA table_info(accounts)")
+        .fetch_all(pool)
+        .await
+        .context("Failed to fetch table info")?
+        .iter()
+        .map(|row| row.get::<String, _>("name"))
+        .collect();
+    if !account_columns.contains(&"group_id".to_string()) {
+        info!("Migrating: Adding group_id column to accounts table");
+        sqlx::query("ALTER TABLE accounts ADD COLUMN group_id INTEGER REFERENCES account_groups(id) ON DELETE SET NULL")
+            .execute(pool)
+            .await
+            .context("Failed to add group_id column")?;
+    }
+    if !account_columns.contains(&"is_shared".to_string()) {
+        info!("Migrating: Adding is_shared column to accounts table");
+        sqlx::query("ALTER TABLE accounts ADD COLUMN is_shared BOOLEAN NOT NULL DEFAULT 0")
+            .execute(pool)
+            .await
+            .context("Failed to add is_shared column")?;
+    }
+    if !account_columns.contains(&"sync_token".to_string()) {
+        info!("Migrating: Adding sync_token column to accounts table");
+        sqlx::query("ALTER TABLE accounts ADD COLUMN sync_token TEXT")
+            .execute(pool)
+            .await
+            .context("Failed to add sync_token column")?;
+    }
+    if !account_columns.contains(&"feed_etag".to_string()) {
+        info!("Migrating: Adding feed_etag column to accounts table");
+        sqlx::query("ALTER TABLE accounts ADD COLUMN feed_etag TEXT")
+            .execute(pool)
+            .await
+            .context("Failed to add feed_etag column")?;
+    }
+    if !account_columns.contains(&"feed_last_modified".to_string()) {
+        info!("Migrating: Adding feed_last_modified column to accounts table");
+        sqlx::query("ALTER TABLE accounts ADD COLUMN feed_last_modified TEXT")
+            .execute(pool)
+            .await
+            .context("Failed to add feed_last_modified column")?;
+    }
+    if !account_columns.contains(&"feed_validated_at".to_string()) {
+        info!("Migrating: Adding feed_validated_at column to accounts table");
+        sqlx::query("ALTER TABLE accounts ADD COLUMN feed_validated_at DATETIME")
+            .execute(pool)
+            .await
+            .context("Failed to add feed_validated_at column")?;
+    }
+    if !account_columns.contains(&"feed_hash".to_string()) {
+        info!("Migrating: Adding feed_hash column to accounts table");
+        sqlx::query("ALTER TABLE accounts ADD COLUMN feed_hash TEXT")
+            .execute(pool)
+            .await
+            .context("Failed to add feed_hash column")?;
+    }
+    if !account_columns.contains(&"username".to_string()) {
+        info!("Migrating: Adding username column to accounts table");
+        sqlx::query("ALTER TABLE accounts ADD COLUMN username TEXT")
+            .execute(pool)
+            .await
+            .context("Failed to add username column")?;
+    }
+    if !account_columns.contains(&"sync_interval".to_string()) {
+        info!("Migrating: Adding sync_interval column to accounts table");
+        sqlx::query("ALTER TABLE accounts ADD COLUMN sync_interval INTEGER")
+            .execute(pool)
+            .await
+            .context("Failed to add sync_interval column")?;
+    }
+    if !account_columns.contains(&"last_sync_error".to_string()) {
+        info!("Migrating: Adding last_sync_error columns to accounts table");
+        sqlx::query("ALTER TABLE accounts ADD COLUMN last_sync_error TEXT")
+            .execute(pool)
+            .await
+            .context("Failed to add last_sync_error column")?;
+        sqlx::query("ALTER TABLE accounts ADD COLUMN last_sync_error_at DATETIME")
+            .execute(pool)
+            .await
+            .context("Failed to add last_sync_error_at column")?;
+    }
+    if !account_columns.contains(&"timezone".to_string()) {
+        info!("Migrating: Adding timezone column to accounts table");
+        sqlx::query("ALTER TABLE accounts ADD COLUMN timezone TEXT")
+            .execute(pool)
+            .await
+            .context("Failed to add timezone column")?;
+    }
+    if !account_columns.contains(&"calendar_color".to_string()) {
+        info!("Migrating: Adding calendar name and color columns to accounts table");
+        sqlx::query("ALTER TABLE accounts ADD COLUMN calendar_name TEXT")
+            .execute(pool)
+            .await
+            .context("Failed to add calendar_name column")?;
+        sqlx::query("ALTER TABLE accounts ADD COLUMN calendar_color TEXT")
+            .execute(pool)
+            .await
+            .context("Failed to add calendar_color column")?;
+    }
+    if !account_columns.contains(&"is_informational".to_string()) {
+        info!("Migrating: Adding is_informational column to accounts table");
+        sqlx::query("ALTER TABLE accounts ADD COLUMN is_informational BOOLEAN NOT NULL DEFAULT 0")
+            .execute(pool)
+            .await
+            .context("Failed to add is_informational column")?;
+    }
+    if !account_columns.contains(&"alerts_enabled".to_string()) {
+        info!("Migrating: Adding alerts_enabled column to accounts table");
+        sqlx::query("ALTER TABLE accounts ADD COLUMN alerts_enabled BOOLEAN NOT NULL DEFAULT 1")
+            .execute(pool)
+            .await
+            .context("Failed to add alerts_enabled column")?;
+    }
+    if !account_columns.contains(&"feed_refresh_interval".to_string()) {
+        info!("Migrating: Adding feed_refresh_interval column to accounts table");
+        sqlx::query("ALTER TABLE accounts ADD COLUMN feed_refresh_interval INTEGER")
+            .execute(pool)
+            .await
+            .context("Failed to add feed_refresh_interval column")?;
+    }
+    if !account_columns.contains(&"secret_key".to_string()) {
+        info!("Migrating: Adding secret_key column to accounts table");
+        sqlx::query("ALTER TABLE accounts ADD COLUMN secret_key TEXT")
+            .execute(pool)
+            .await
+            .context("Failed to add secret_key column")?;
+    }
+
     // Check columns in events table
     let rows = sqlx::query("PRAGMA table_info(events)")
         .fetch_all(pool)
@@ -268,10 +501,147 @@ async fn ensure_migrations(pool: &SqlitePool) -> Result<()> {
             .await
             .context("Failed to add last_snoozed_at column")?;
     }
+
+    if !columns.contains(&"is_all_day".to_string()) {
+        info!("Migrating: Adding is_all_day column to events table");
+        sqlx::query("ALTER TABLE events ADD COLUMN is_all_day BOOLEAN DEFAULT 0")
+            .execute(pool)
+            .await
+            .context("Failed to add is_all_day column")?;
+    }
+
+    // Join details extracted from the invite (see `CalendarEvent::video_meeting`)
+    for column in ["meeting_id", "meeting_passcode", "dial_in_number", "dial_in_conference_id"] {
+        if !columns.contains(&column.to_string()) {
+            info!("Migrating: Adding {} column to events table", column);
+            sqlx::query(&format!("ALTER TABLE events ADD COLUMN {} TEXT", column))
+                .execute(pool)
+                .await
+                .with_context(|| format!("Failed to add {} column", column))?;
+        }
+    }
+
+    if !columns.contains(&"title_override".to_string()) {
+        info!("Migrating: Adding title_override column to events table");
+        sqlx::query("ALTER TABLE events ADD COLUMN title_override TEXT")
+            .execute(pool)
+            .await
+            .context("Failed to add title_override column")?;
+    }
+
+    if !columns.contains(&"source_timezone".to_string()) {
+        info!("Migrating: Adding source_timezone column to events table");
+        sqlx::query("ALTER TABLE events ADD COLUMN source_timezone TEXT")
+            .execute(pool)
+            .await
+            .context("Failed to add source_timezone column")?;
+    }
+
+    if !columns.contains(&"snoozed_until".to_string()) {
+        info!("Migrating: Adding snoozed_until column to events table");
+        sqlx::query("ALTER TABLE events ADD COLUMN snoozed_until DATETIME")
+            .execute(pool)
+            .await
+            .context("Failed to add snoozed_until column")?;
+    }
+    if !columns.contains(&"location".to_string()) {
+        info!("Migrating: Adding location column to events table");
+        sqlx::query("ALTER TABLE events ADD COLUMN location TEXT")
+            .execute(pool)
+            .await
+            .context("Failed to add location column")?;
+    }
+    if !columns.contains(&"reminder_minutes".to_string()) {
+        info!("Migrating: Adding reminder_minutes column to events table");
+        sqlx::query("ALTER TABLE events ADD COLUMN reminder_minutes TEXT")
+            .execute(pool)
+            .await
+            .context("Failed to add reminder_minutes column")?;
+    }
+    if !columns.contains(&"is_free".to_string()) {
+        info!("Migrating: Adding is_free column to events table");
+        sqlx::query("ALTER TABLE events ADD COLUMN is_free BOOLEAN NOT NULL DEFAULT 0")
+            .execute(pool)
+            .await
+            .context("Failed to add is_free column")?;
+    }
+    if !columns.contains(&"removed_at".to_string()) {
+        info!("Migrating: Adding removed_at column to events table");
+        sqlx::query("ALTER TABLE events ADD COLUMN removed_at DATETIME")
+            .execute(pool)
+            .await
+            .context("Failed to add removed_at column")?;
+    }
+    if !columns.contains(&"series_uid".to_string()) {
+        info!("Migrating: Adding series_uid column to events table");
+        sqlx::query("ALTER TABLE events ADD COLUMN series_uid TEXT")
+            .execute(pool)
+            .await
+            .context("Failed to add series_uid column")?;
+    }
+
+    // Events stored before automatic tagging get their tags once
+    let tagging_added = sqlx::query("INSERT OR IGNORE INTO schema_migrations (version, name) VALUES (2, 'auto_tags')")
+        .execute(pool)
+        .await
+        .context("Failed to record auto_tags migration")?
+        .rows_affected() > 0;
+    if tagging_added {
+        info!("Migrating: Tagging existing events");
+        tags::retag_all(pool).await.context("Failed to tag existing events")?;
+    }
     
     Ok(())
 }
 
+/// Recreate the accounts table from schema.sql, keeping its rows and ids.
+///
+/// SQLite can't alter a CHECK constraint in place. Foreign keys are off on
+/// this connection while the old table is dropped, or its events would be
+/// deleted with it.
+async fn rebuild_accounts_table(pool: &SqlitePool) -> Result<()> {
+    let schema = include_str!("schema.sql");
+    let start = schema
+        .find("CREATE TABLE IF NOT EXISTS accounts (")
+        .context("accounts table missing from schema.sql")?;
+    let end = start + schema[start..].find(");").context("accounts table definition is unterminated")? + 2;
+    let create_new = schema[start..end].replace("IF NOT EXISTS accounts (", "accounts_new (");
+
+    let mut conn = pool.acquire().await?;
+    sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut *conn).await?;
+    let result = copy_into_new_accounts_table(&mut conn, &create_new).await;
+    sqlx::query("PRAGMA foreign_keys = ON").execute(&mut *conn).await?;
+    result
+}
+
+async fn copy_into_new_accounts_table(conn: &mut sqlx::SqliteConnection, create_new: &str) -> Result<()> {
+    let mut tx = conn.begin().await?;
+    sqlx::query(create_new).execute(&mut *tx).await?;
+
+    let column_names = |rows: Vec<sqlx::sqlite::SqliteRow>| -> Vec<String> {
+        rows.iter().map(|row| row.get::<String, _>("name")).collect()
+    };
+    let old_columns = column_names(sqlx::query("PRAGMA table_info(accounts)").fetch_all(&mut *tx).await?);
+    let new_columns = column_names(sqlx::query("PRAGMA table_info(accounts_new)").fetch_all(&mut *tx).await?);
+    let shared = new_columns
+        .into_iter()
+        .filter(|c| old_columns.contains(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    sqlx::query(&format!("INSERT INTO accounts_new ({0}) SELECT {0} FROM accounts", shared))
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DROP TABLE accounts").execute(&mut *tx).await?;
+    sqlx::query("ALTER TABLE accounts_new RENAME TO accounts").execute(&mut *tx).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_accounts_provider ON accounts(provider)")
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -297,6 +667,50 @@ mod tests {
         assert!(db.pool.is_closed() == false);
     }
 
+    #[tokio::test]
+    async fn test_open_creates_profile_database() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("profiles").join("work").join("openchime.db");
+
+        let db = Database::open(&path).await.unwrap();
+        assert!(path.exists());
+
+        let settings = db.get_settings().await.unwrap();
+        assert_eq!(settings.volume, 0.7);
+    }
+
+    #[tokio::test]
+    async fn test_store_transaction_holds_off_other_writers() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = Database::open(&dir.path().join("store.db")).await.unwrap();
+        let id = db.add_account(&Account::new_proton("Work".to_string(), "https://example.com/a.ics".to_string())).await.unwrap();
+
+        // A write landing between the store's read and its write would leave
+        // it on a stale snapshot; it waits for the commit instead
+        let (turn, mut tx) = begin_store(&db.pool).await.unwrap();
+        let _: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM accounts").fetch_one(&mut *tx).await.unwrap();
+        let writer = tokio::spawn({
+            let pool = db.pool.clone();
+            async move { accounts::set_feed_hash(&pool, id, Some("1f2e3d")).await }
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!writer.is_finished());
+
+        sqlx::query("UPDATE accounts SET account_name = 'Office' WHERE id = ?").bind(id).execute(&mut *tx).await.unwrap();
+        tx.commit().await.unwrap();
+        drop(turn);
+        writer.await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_ends_transaction() {
+        let locked = anyhow::Error::from(sqlx::Error::PoolTimedOut);
+        assert!(ends_transaction(&locked));
+        let decode = anyhow::Error::from(sqlx::Error::ColumnNotFound("title".to_string()));
+        assert!(!ends_transaction(&decode));
+        assert!(!ends_transaction(&anyhow::anyhow!("Event has no start time")));
+    }
+
     #[tokio::test]
     async fn test_add_account() {
         let db = create_test_database().await;
@@ -391,21 +805,186 @@ mod tests {
     #[tokio::test]
     async fn test_snooze_event_not_found() {
         let db = create_test_database().await;
-        let result = db.snooze_event("nonexistent").await;
+        let result = db.snooze_event(crate::models::EventId(999_999)).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_dismiss_event_not_found() {
         let db = create_test_database().await;
-        let result = db.dismiss_event("nonexistent").await;
+        let result = db.dismiss_event(crate::models::EventId(999_999)).await;
         assert!(result.is_ok()); // Updating 0 rows is not an error in SQL
     }
 
     #[tokio::test]
     async fn test_mark_event_alerted_not_found() {
         let db = create_test_database().await;
-        let result = db.mark_event_alerted("nonexistent").await;
+        let result = db.mark_event_alerted(crate::models::EventId(999_999)).await;
         assert!(result.is_ok()); // Updating 0 rows is not an error in SQL
     }
+
+    #[tokio::test]
+    async fn test_duplicate_events_are_collapsed_before_indexing() {
+        let db = create_test_database().await;
+        let account_id = db
+            .add_account(&Account::new_proton("cal".to_string(), "https://example.com/cal.ics".to_string()))
+            .await
+            .unwrap();
+
+        // Simulate a database from before the unique index existed
+        sqlx::query("DROP INDEX idx_events_external_account").execute(&db.pool).await.unwrap();
+        for title in ["Old", "New"] {
+            sqlx::query("INSERT INTO events (external_id, account_id, title, start_time, end_time) VALUES ('dup', ?, ?, '2024-01-01', '2024-01-01')")
+                .bind(account_id)
+                .bind(title)
+                .execute(&db.pool)
+                .await
+                .unwrap();
+        }
+
+        collapse_duplicate_events(&db.pool).await.unwrap();
+        run_schema(&db.pool).await.unwrap();
+        let titles: Vec<String> = sqlx::query_scalar("SELECT title FROM events")
+            .fetch_all(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(titles, vec!["New".to_string()]);
+
+        // From then on the index keeps them apart
+        let duplicate = sqlx::query("INSERT INTO events (external_id, account_id, title, start_time, end_time) VALUES ('dup', ?, 'Again', '2024-01-01', '2024-01-01')")
+            .bind(account_id)
+            .execute(&db.pool)
+            .await;
+        assert!(duplicate.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dismiss_applies_to_copies_in_other_accounts() {
+        let db = create_test_database().await;
+        let mut event_ids = Vec::new();
+        for name in ["Work", "Team"] {
+            let account_id = db
+                .add_account(&Account::new_proton(name.to_string(), "https://example.com/cal.ics".to_string()))
+                .await
+                .unwrap();
+            let id = sqlx::query("INSERT INTO events (external_id, account_id, title, start_time, end_time) VALUES ('sync@example.com', ?, 'Sync', '2030-01-01 09:00:00', '2030-01-01 10:00:00')")
+                .bind(account_id)
+                .execute(&db.pool)
+                .await
+                .unwrap()
+                .last_insert_rowid();
+            event_ids.push(id);
+        }
+
+        db.dismiss_event(crate::models::EventId(event_ids[0])).await.unwrap();
+
+        let dismissed: Vec<bool> = sqlx::query_scalar("SELECT is_dismissed FROM events ORDER BY id")
+            .fetch_all(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(dismissed, vec![true, true]);
+    }
+
+    #[tokio::test]
+    async fn test_dismiss_applies_to_copies_under_other_uids() {
+        let db = create_test_database().await;
+        let work = db
+            .add_account(&Account::new_proton("Work".to_string(), "https://example.com/work.ics".to_string()))
+            .await
+            .unwrap();
+        let team = db
+            .add_account(&Account::new_proton("Team".to_string(), "https://example.com/team.ics".to_string()))
+            .await
+            .unwrap();
+        let mut event_ids = Vec::new();
+        for (uid, account_id, title, start) in [
+            ("a@google.com", work, "Sync", "2030-01-01 09:00:00+00:00"),
+            ("b@proton.me", team, "sync", "2030-01-01 09:01:00+00:00"),
+            // Same title in the same calendar, and a later one elsewhere: other meetings
+            ("c@google.com", work, "Sync", "2030-01-01 09:00:00+00:00"),
+            ("d@proton.me", team, "Sync", "2030-01-01 09:30:00+00:00"),
+        ] {
+            let id = sqlx::query("INSERT INTO events (external_id, account_id, title, start_time, end_time) VALUES (?, ?, ?, ?, ?)")
+                .bind(uid)
+                .bind(account_id)
+                .bind(title)
+                .bind(start)
+                .bind(start)
+                .execute(&db.pool)
+                .await
+                .unwrap()
+                .last_insert_rowid();
+            event_ids.push(id);
+        }
+
+        db.dismiss_event(crate::models::EventId(event_ids[0])).await.unwrap();
+
+        let dismissed: Vec<bool> = sqlx::query_scalar("SELECT is_dismissed FROM events ORDER BY id")
+            .fetch_all(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(dismissed, vec![true, true, false, false]);
+    }
+
+    #[tokio::test]
+    async fn test_open_allows_local_accounts_in_older_databases() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("old.db");
+
+        // An accounts table from before local reminders, with an event hanging off it
+        {
+            let pool = SqlitePool::connect(&format!("sqlite:{}?mode=rwc", path.display())).await.unwrap();
+            run_schema(&pool).await.unwrap();
+            sqlx::query("DROP TABLE accounts").execute(&pool).await.unwrap();
+            sqlx::query(
+                "CREATE TABLE accounts (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    provider TEXT NOT NULL CHECK (provider IN ('google', 'proton')),
+                    account_name TEXT NOT NULL,
+                    auth_data TEXT NOT NULL,
+                    refresh_token TEXT,
+                    last_synced_at DATETIME
+                )"
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query("INSERT INTO accounts (id, provider, account_name, auth_data) VALUES (7, 'proton', 'Work', 'https://example.com/cal.ics')")
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO accounts (id, provider, account_name, auth_data) VALUES (8, 'proton', 'Home', 'https://calendar.proton.me/api/calendar/v1/url/abc/calendar.ics')")
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO events (external_id, account_id, title, start_time, end_time) VALUES ('sync@example.com', 7, 'Sync', '2030-01-01 09:00:00', '2030-01-01 10:00:00')")
+                .execute(&pool)
+                .await
+                .unwrap();
+            pool.close().await;
+        }
+
+        let db = Database::open(&path).await.unwrap();
+        let accounts = db.get_accounts().await.unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].id, Some(7));
+
+        // Only feeds that really are Proton's stay behind its breaker
+        let provider = |id| accounts.iter().find(|a| a.id == Some(id)).map(|a| a.provider.as_str());
+        assert_eq!((provider(7), provider(8)), (Some("generic"), Some("proton")));
+
+        // Rebuilding the table didn't cascade to the events
+        let events: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM events").fetch_one(&db.pool).await.unwrap();
+        assert_eq!(events, 1);
+
+        let local_id = accounts::local_account_id(&db.pool).await.unwrap();
+        assert_eq!(accounts::local_account_id(&db.pool).await.unwrap(), local_id);
+        let generic = crate::models::Account::new_generic("Club".to_string(), "https://club.example.org/events.ics".to_string());
+        accounts::add(&db.pool, &generic).await.unwrap();
+
+        // Foreign keys are enforced again afterwards
+        sqlx::query("DELETE FROM accounts WHERE id = 7").execute(&db.pool).await.unwrap();
+        let events: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM events").fetch_one(&db.pool).await.unwrap();
+        assert_eq!(events, 0);
+    }
 }
@@ -1,17 +1,47 @@
 -- OpenChime Database Schema
 -- Local-first SQLite database for calendar events and settings
 
+-- Account groups ("Work", "Personal"): switch syncing, alerts and
+-- visibility for all of their accounts at once
+CREATE TABLE IF NOT EXISTS account_groups (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    sync_enabled BOOLEAN NOT NULL DEFAULT 1,
+    alerts_enabled BOOLEAN NOT NULL DEFAULT 1,
+    visible BOOLEAN NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
 -- Accounts table: Stores authentication info for calendar providers
--- Note: auth_data and refresh_token are encrypted at rest using AES-256-GCM
+-- Note: passwords and tokens aren't stored here but in the OS keyring;
+-- refresh_token and secret_key only name the keyring entry
 CREATE TABLE IF NOT EXISTS accounts (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
-    provider TEXT NOT NULL CHECK (provider IN ('google', 'proton')),
+    provider TEXT NOT NULL CHECK (provider IN ('google', 'proton', 'generic', 'caldav', 'local')),
     account_name TEXT NOT NULL,
-    auth_data TEXT NOT NULL, -- Encrypted: OAuth tokens for Google, ICS URL for Proton
-    refresh_token TEXT,      -- Encrypted: OAuth refresh token (Google only)
+    auth_data TEXT NOT NULL, -- Feed URL (calendar collection URL for CalDAV), empty for local
+    refresh_token TEXT,      -- The keyring entry for Google API accounts
     last_synced_at DATETIME,
-    encryption_version INTEGER DEFAULT 1, -- Tracks encryption algorithm version (1 = AES-256-GCM)
-    encrypted_at DATETIME DEFAULT CURRENT_TIMESTAMP, -- When tokens were encrypted
+    group_id INTEGER REFERENCES account_groups(id) ON DELETE SET NULL,
+    is_shared BOOLEAN NOT NULL DEFAULT 0,
+    is_informational BOOLEAN NOT NULL DEFAULT 0, -- Holiday/birthday feed: listed, never alerts
+    alerts_enabled BOOLEAN NOT NULL DEFAULT 1,   -- Off mutes the account's alerts
+    sync_token TEXT,         -- Google Calendar API incremental sync position
+    feed_etag TEXT,          -- ETag of the feed response last synced
+    feed_last_modified TEXT, -- Last-Modified of the feed response last synced
+    feed_validated_at DATETIME, -- When that response was parsed
+    feed_hash TEXT,          -- Hash of that response's body and the settings it was parsed with
+    username TEXT,           -- HTTP Basic sign-in for a feed behind a login, or for CalDAV
+    sync_interval INTEGER,   -- Seconds between syncs; NULL follows the global setting
+    last_sync_error TEXT,    -- Why the last sync failed, URLs cut to their host; NULL once one succeeds
+    last_sync_error_at DATETIME,
+    timezone TEXT,           -- IANA zone for the feed's floating times; NULL follows floating_timezone
+    calendar_name TEXT,      -- X-WR-CALNAME of the feed
+    calendar_color TEXT,     -- X-APPLE-CALENDAR-COLOR of the feed, as #rrggbb
+    feed_refresh_interval INTEGER, -- Seconds between syncs the feed asks for (REFRESH-INTERVAL or X-PUBLISHED-TTL)
+    secret_key TEXT,         -- The keyring entry holding the password and extra request headers, as JSON
+    encryption_version INTEGER DEFAULT 1, -- Unused; nothing in this table is encrypted
+    encrypted_at DATETIME DEFAULT CURRENT_TIMESTAMP, -- Unused
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
 );
@@ -32,12 +62,78 @@ CREATE TABLE IF NOT EXISTS events (
     last_alert_threshold INTEGER, -- Closest minute-threshold alerted (e.g. 30, 10, 5, 1, 0)
     is_dismissed BOOLEAN DEFAULT 0,
     last_snoozed_at DATETIME,
+    is_all_day BOOLEAN DEFAULT 0, -- DATE event; times are UTC midnight of the dates
+    meeting_id TEXT, -- From the video link (e.g. Zoom meeting number)
+    meeting_passcode TEXT,
+    dial_in_number TEXT,
+    dial_in_conference_id TEXT,
+    -- Set by the user to rename the event locally; sync never touches it
+    title_override TEXT,
+    source_timezone TEXT, -- DTSTART's TZID, when the feed gave a known one
+    snoozed_until DATETIME, -- End of a pending snooze that isn't the usual interval
+    location TEXT,
+    reminder_minutes TEXT, -- VALARM offsets, minutes before start, largest first ("15,5")
+    is_free BOOLEAN NOT NULL DEFAULT 0, -- TRANSP:TRANSPARENT, shown as free time
+    series_uid TEXT, -- UID of the recurring series an occurrence was expanded from
+    -- Set when the event left its feed while it had local state (a rename,
+    -- tags or a checklist); the row is hidden, and shown again if it returns
+    removed_at DATETIME,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     
     FOREIGN KEY(account_id) REFERENCES accounts(id) ON DELETE CASCADE
 );
 
+-- Event tags: categories such as "standup" or "1:1". Tags with is_auto = 1
+-- come from the event's text during sync and are recomputed when it changes;
+-- others were added by the user and are left alone.
+CREATE TABLE IF NOT EXISTS event_tags (
+    event_id INTEGER NOT NULL,
+    tag TEXT NOT NULL,
+    is_auto BOOLEAN NOT NULL DEFAULT 1,
+
+    PRIMARY KEY(event_id, tag),
+    FOREIGN KEY(event_id) REFERENCES events(id) ON DELETE CASCADE
+);
+
+-- Checklist items: things to prepare before a meeting ("prepare slides").
+-- Unchecked ones are repeated in the event's 30 and 10 minute alerts.
+CREATE TABLE IF NOT EXISTS event_checklist_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_id INTEGER NOT NULL,
+    text TEXT NOT NULL,
+    is_done BOOLEAN NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY(event_id) REFERENCES events(id) ON DELETE CASCADE
+);
+
+-- Event attendees: the ORGANIZER and ATTENDEEs of the feed event, with their
+-- reply (PARTSTAT). Replaced from the feed on every sync that changes them.
+CREATE TABLE IF NOT EXISTS event_attendees (
+    event_id INTEGER NOT NULL,
+    email TEXT NOT NULL,
+    name TEXT,
+    rsvp TEXT NOT NULL DEFAULT 'NEEDS-ACTION',
+    is_organizer BOOLEAN NOT NULL DEFAULT 0,
+
+    PRIMARY KEY(event_id, email),
+    FOREIGN KEY(event_id) REFERENCES events(id) ON DELETE CASCADE
+);
+
+-- Sync history: one row per sync attempt, kept for 30 days, for the
+-- per-account reliability report in `openchime doctor` and the Stats view
+CREATE TABLE IF NOT EXISTS sync_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    account_id INTEGER NOT NULL,
+    started_at DATETIME NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    success BOOLEAN NOT NULL,
+    error TEXT, -- URLs reduced to their host
+
+    FOREIGN KEY(account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
 -- Settings table: User preferences and application configuration
 CREATE TABLE IF NOT EXISTS settings (
     key TEXT PRIMARY KEY,
@@ -55,20 +151,46 @@ INSERT OR IGNORE INTO settings (key, value) VALUES
 ('snooze_interval', '2'),
 ('max_snoozes', '3'),
 ('sync_interval', '300'),
+('sync_timeout', '180'),
+('sync_days_back', '30'),
+('sync_days_ahead', '365'),
+('floating_timezone', 'local'),
 ('auto_join_enabled', 'false'),
 ('theme', 'dark'),
 ('alert_30m', 'false'),
 ('alert_10m', 'false'),
 ('alert_5m', 'true'),
 ('alert_1m', 'true'),
-('alert_default', 'true');
+('alert_default', 'true'),
+('calendar_reminders', 'false'),
+('custom_alert_minutes', ''),
+('alert_free_events', 'false'),
+('skip_all_day_events', 'true'),
+('check_for_updates', 'false'),
+('hold_syncs_offline', 'true'),
+('focus_work_minutes', '25'),
+('focus_break_minutes', '5'),
+('world_clocks', ''),
+('sound_profiles', ''),
+('keep_awake_minutes', '0'),
+('proxy_url', ''),
+('max_feed_size_mb', '25'),
+('my_addresses', '');
 
 -- Indexes for performance
 CREATE INDEX IF NOT EXISTS idx_events_start_time ON events(start_time);
 CREATE INDEX IF NOT EXISTS idx_events_account_id ON events(account_id);
 CREATE INDEX IF NOT EXISTS idx_events_external_id ON events(external_id);
+
+-- Sync upserts on (external_id, account_id). Older databases could hold
+-- duplicate rows for the same feed event; collapse_duplicate_events keeps
+-- the newest before this runs.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_events_external_account ON events(external_id, account_id);
 CREATE INDEX IF NOT EXISTS idx_events_alert ON events(has_alerted, is_dismissed, start_time);
 CREATE INDEX IF NOT EXISTS idx_accounts_provider ON accounts(provider);
+CREATE INDEX IF NOT EXISTS idx_event_tags_tag ON event_tags(tag);
+CREATE INDEX IF NOT EXISTS idx_event_checklist_items_event ON event_checklist_items(event_id);
+CREATE INDEX IF NOT EXISTS idx_sync_history_account ON sync_history(account_id, started_at);
 
 -- Schema Migrations table: Tracks applied database migrations
 -- Used by the migration system to ensure idempotent migrations
@@ -22,6 +22,10 @@ pub async fn get(pool: &SqlitePool) -> Result<crate::models::Settings> {
             "snooze_interval" => app_settings.snooze_interval = setting.value.parse().unwrap_or(2),
             "max_snoozes" => app_settings.max_snoozes = setting.value.parse().unwrap_or(3),
             "sync_interval" => app_settings.sync_interval = setting.value.parse().unwrap_or(300),
+            "sync_timeout" => app_settings.sync_timeout = setting.value.parse().unwrap_or(180),
+            "sync_days_back" => app_settings.sync_days_back = setting.value.parse().unwrap_or(30),
+            "sync_days_ahead" => app_settings.sync_days_ahead = setting.value.parse().unwrap_or(365),
+            "floating_timezone" => app_settings.floating_timezone = setting.value,
             "auto_join_enabled" => {
                 app_settings.auto_join_enabled = setting.value.parse().unwrap_or(false)
             }
@@ -31,6 +35,22 @@ pub async fn get(pool: &SqlitePool) -> Result<crate::models::Settings> {
             "alert_5m" => app_settings.alert_5m = setting.value.parse().unwrap_or(true),
             "alert_1m" => app_settings.alert_1m = setting.value.parse().unwrap_or(true),
             "alert_default" => app_settings.alert_default = setting.value.parse().unwrap_or(true),
+            "calendar_reminders" => app_settings.calendar_reminders = setting.value.parse().unwrap_or(false),
+            "custom_alert_minutes" => app_settings.custom_alert_minutes = setting.value,
+            "alert_free_events" => app_settings.alert_free_events = setting.value.parse().unwrap_or(false),
+            "skip_all_day_events" => app_settings.skip_all_day_events = setting.value.parse().unwrap_or(true),
+            "check_for_updates" => {
+                app_settings.check_for_updates = setting.value.parse().unwrap_or(false)
+            }
+            "hold_syncs_offline" => app_settings.hold_syncs_offline = setting.value.parse().unwrap_or(true),
+            "focus_work_minutes" => app_settings.focus_work_minutes = setting.value.parse().unwrap_or(25),
+            "focus_break_minutes" => app_settings.focus_break_minutes = setting.value.parse().unwrap_or(5),
+            "world_clocks" => app_settings.world_clocks = setting.value,
+            "sound_profiles" => app_settings.sound_profiles = setting.value,
+            "keep_awake_minutes" => app_settings.keep_awake_minutes = setting.value.parse().unwrap_or(0),
+            "proxy_url" => app_settings.proxy_url = setting.value,
+            "max_feed_size_mb" => app_settings.max_feed_size_mb = setting.value.parse().unwrap_or(25),
+            "my_addresses" => app_settings.my_addresses = setting.value,
             _ => {}
         }
     }
@@ -46,6 +66,9 @@ pub async fn update(pool: &SqlitePool, settings: &crate::models::Settings) -> Re
     let snooze_interval_str = settings.snooze_interval.to_string();
     let max_snoozes_str = settings.max_snoozes.to_string();
     let sync_interval_str = settings.sync_interval.to_string();
+    let sync_timeout_str = settings.sync_timeout.to_string();
+    let sync_days_back_str = settings.sync_days_back.to_string();
+    let sync_days_ahead_str = settings.sync_days_ahead.to_string();
     let auto_join_enabled_str = settings.auto_join_enabled.to_string();
     let theme_str = settings.theme.clone();
     let alert_30m_str = settings.alert_30m.to_string();
@@ -53,6 +76,15 @@ pub async fn update(pool: &SqlitePool, settings: &crate::models::Settings) -> Re
     let alert_5m_str = settings.alert_5m.to_string();
     let alert_1m_str = settings.alert_1m.to_string();
     let alert_default_str = settings.alert_default.to_string();
+    let calendar_reminders_str = settings.calendar_reminders.to_string();
+    let alert_free_events_str = settings.alert_free_events.to_string();
+    let skip_all_day_events_str = settings.skip_all_day_events.to_string();
+    let check_for_updates_str = settings.check_for_updates.to_string();
+    let hold_syncs_offline_str = settings.hold_syncs_offline.to_string();
+    let focus_work_minutes_str = settings.focus_work_minutes.to_string();
+    let focus_break_minutes_str = settings.focus_break_minutes.to_string();
+    let keep_awake_minutes_str = settings.keep_awake_minutes.to_string();
+    let max_feed_size_mb_str = settings.max_feed_size_mb.to_string();
 
     let updates = vec![
         ("sound", sound_str.as_str()),
@@ -62,6 +94,10 @@ pub async fn update(pool: &SqlitePool, settings: &crate::models::Settings) -> Re
         ("snooze_interval", snooze_interval_str.as_str()),
         ("max_snoozes", max_snoozes_str.as_str()),
         ("sync_interval", sync_interval_str.as_str()),
+        ("sync_timeout", sync_timeout_str.as_str()),
+        ("sync_days_back", sync_days_back_str.as_str()),
+        ("sync_days_ahead", sync_days_ahead_str.as_str()),
+        ("floating_timezone", settings.floating_timezone.as_str()),
         ("auto_join_enabled", auto_join_enabled_str.as_str()),
         ("theme", theme_str.as_str()),
         ("alert_30m", alert_30m_str.as_str()),
@@ -69,6 +105,20 @@ pub async fn update(pool: &SqlitePool, settings: &crate::models::Settings) -> Re
         ("alert_5m", alert_5m_str.as_str()),
         ("alert_1m", alert_1m_str.as_str()),
         ("alert_default", alert_default_str.as_str()),
+        ("calendar_reminders", calendar_reminders_str.as_str()),
+        ("custom_alert_minutes", settings.custom_alert_minutes.as_str()),
+        ("alert_free_events", alert_free_events_str.as_str()),
+        ("skip_all_day_events", skip_all_day_events_str.as_str()),
+        ("check_for_updates", check_for_updates_str.as_str()),
+        ("hold_syncs_offline", hold_syncs_offline_str.as_str()),
+        ("focus_work_minutes", focus_work_minutes_str.as_str()),
+        ("focus_break_minutes", focus_break_minutes_str.as_str()),
+        ("world_clocks", settings.world_clocks.as_str()),
+        ("sound_profiles", settings.sound_profiles.as_str()),
+        ("keep_awake_minutes", keep_awake_minutes_str.as_str()),
+        ("proxy_url", settings.proxy_url.as_str()),
+        ("max_feed_size_mb", max_feed_size_mb_str.as_str()),
+        ("my_addresses", settings.my_addresses.as_str()),
     ];
 
     for (key, value) in updates {
@@ -0,0 +1,241 @@
+// file: src/database/stats.rs
+//! Meeting statistics for the Stats view
+
+use anyhow::Result;
+use chrono::{Datelike, Duration, Local, NaiveDate, TimeZone};
+use sqlx::SqlitePool;
+
+use crate::database::events::EVENT_COLUMNS;
+use crate::models::{merge_duplicates, CalendarEvent};
+
+/// Weeks covered by the Stats view, including the current one
+pub const STATS_WEEKS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeetingStats {
+    /// Monday of each week with its hours in meetings, oldest first
+    pub hours_per_week: Vec<(NaiveDate, f64)>,
+    /// Meetings on each day of the week, Monday first
+    pub meetings_per_weekday: [u32; 7],
+    pub video_meetings: u32,
+    pub in_person_meetings: u32,
+    /// Snoozes per meeting
+    pub average_snoozes: f64,
+}
+
+impl MeetingStats {
+    /// Tally timed events starting in the `weeks` weeks from `first_monday`,
+    /// bucketed by their date in `tz`. All-day events aren't meetings and
+    /// are skipped.
+    pub fn from_events<Tz: TimeZone>(events: &[CalendarEvent], first_monday: NaiveDate, weeks: usize, tz: &Tz) -> Self {
+        let mut stats = Self {
+            hours_per_week: (0..weeks)
+                .map(|week| (first_monday + Duration::weeks(week as i64), 0.0))
+                .collect(),
+            meetings_per_weekday: [0; 7],
+            video_meetings: 0,
+            in_person_meetings: 0,
+            average_snoozes: 0.0,
+        };
+
+        let mut snoozes = 0;
+        for event in events.iter().filter(|e| !e.is_all_day) {
+            let date = event.start_time.with_timezone(tz).date_naive();
+            let days_in = (date - first_monday).num_days();
+            if days_in < 0 {
+                continue;
+            }
+            let Some((_, hours)) = stats.hours_per_week.get_mut((days_in / 7) as usize) else {
+                continue;
+            };
+
+            *hours += (event.end_time - event.start_time).num_minutes().max(0) as f64 / 60.0;
+            stats.meetings_per_weekday[date.weekday().num_days_from_monday() as usize] += 1;
+            if event.is_video_meeting() {
+                stats.video_meetings += 1;
+            } else {
+                stats.in_person_meetings += 1;
+            }
+            snoozes += event.snooze_count.max(0);
+        }
+
+        if stats.total_meetings() > 0 {
+            stats.average_snoozes = snoozes as f64 / stats.total_meetings() as f64;
+        }
+        stats
+    }
+
+    pub fn total_meetings(&self) -> u32 {
+        self.video_meetings + self.in_person_meetings
+    }
+}
+
+/// Statistics for the last `STATS_WEEKS` weeks, this one included, in the
+/// system timezone. Reminders don't count, nor do team calendar events
+/// that don't invite one of the user's `my_addresses` (or that they
+/// declined), and a meeting that appears in several calendars counts once.
+pub async fn meeting_stats(pool: &SqlitePool) -> Result<MeetingStats> {
+    let today = Local::now().date_naive();
+    let this_monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let first_monday = this_monday - Duration::weeks(STATS_WEEKS as i64 - 1);
+
+    // Padded by a day either side; `from_events` buckets by local date
+    let from = first_monday.and_time(chrono::NaiveTime::MIN).and_utc() - Duration::days(1);
+    let to = (this_monday + Duration::weeks(1)).and_time(chrono::NaiveTime::MIN).and_utc() + Duration::days(1);
+
+    let my_addresses = crate::database::settings::get(pool).await?.my_addresses();
+    let events: Vec<CalendarEvent> = sqlx::query_as(&format!(
+        "SELECT {} FROM events
+         WHERE (account_id IN (SELECT id FROM accounts WHERE provider != 'local' AND is_shared = 0)
+                OR (account_id IN (SELECT id FROM accounts WHERE is_shared = 1)
+                    AND id IN (SELECT event_id FROM event_attendees
+                               WHERE rsvp != 'DECLINED' AND email IN (SELECT value FROM json_each(?)))))
+           AND is_all_day = 0 AND removed_at IS NULL AND start_time >= ? AND start_time < ?",
+        EVENT_COLUMNS
+    ))
+    .bind(serde_json::to_string(&my_addresses)?)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await?;
+
+    let merged: Vec<CalendarEvent> = merge_duplicates(&events).into_iter().map(|m| m.event).collect();
+    Ok(MeetingStats::from_events(&merged, first_monday, STATS_WEEKS, &Local))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn meeting(start: chrono::DateTime<Utc>, minutes: i64, video: bool, snoozes: i32) -> CalendarEvent {
+        CalendarEvent {
+            external_id: format!("m-{}", start.timestamp()).into(),
+            end_time: start + Duration::minutes(minutes),
+            video_link: video.then(|| "https://zoom.us/j/123456789".to_string()),
+            snooze_count: snoozes,
+            ..CalendarEvent::test_at(start)
+        }
+    }
+
+    #[test]
+    fn test_stats_from_events() {
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let at = |day: i64, hour: u32| {
+            Utc.from_utc_datetime(&(monday + Duration::days(day)).and_hms_opt(hour, 0, 0).unwrap())
+        };
+
+        let events = vec![
+            meeting(at(0, 9), 30, true, 2),   // week 1, Monday
+            meeting(at(0, 14), 90, false, 0), // week 1, Monday
+            meeting(at(8, 10), 60, true, 1),  // week 2, Tuesday
+            meeting(at(-1, 10), 60, true, 5), // before the range
+            meeting(at(14, 10), 60, true, 5), // after the range
+            CalendarEvent { is_all_day: true, ..meeting(at(2, 0), 1440, false, 0) },
+        ];
+
+        let stats = MeetingStats::from_events(&events, monday, 2, &Utc);
+        assert_eq!(stats.hours_per_week, vec![(monday, 2.0), (monday + Duration::weeks(1), 1.0)]);
+        assert_eq!(stats.meetings_per_weekday, [2, 1, 0, 0, 0, 0, 0]);
+        assert_eq!((stats.video_meetings, stats.in_person_meetings), (2, 1));
+        assert_eq!(stats.total_meetings(), 3);
+        assert!((stats.average_snoozes - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_stats_buckets_by_local_date() {
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        // Sunday 23:30 UTC is already Monday in Tokyo
+        let start = Utc.with_ymd_and_hms(2024, 3, 3, 23, 30, 0).unwrap();
+        let events = vec![meeting(start, 60, false, 0)];
+
+        assert_eq!(MeetingStats::from_events(&events, monday, 1, &Utc).total_meetings(), 0);
+        let tokyo = MeetingStats::from_events(&events, monday, 1, &chrono_tz::Asia::Tokyo);
+        assert_eq!(tokyo.meetings_per_weekday[0], 1);
+        assert_eq!(tokyo.average_snoozes, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_meeting_stats_skips_reminders_and_duplicates() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = crate::database::Database::open(&dir.path().join("test.db")).await.unwrap();
+        let now = Utc::now();
+
+        for (external_id, provider) in [("standup", "proton"), ("standup", "google")] {
+            let account_id = sqlx::query("INSERT INTO accounts (provider, account_name, auth_data) VALUES (?, ?, 'https://example.com/cal.ics')")
+                .bind(provider)
+                .bind(provider)
+                .execute(&db.pool)
+                .await
+                .unwrap()
+                .last_insert_rowid();
+            sqlx::query("INSERT INTO events (external_id, account_id, title, start_time, end_time) VALUES (?, ?, 'Standup', ?, ?)")
+                .bind(external_id)
+                .bind(account_id)
+                .bind(now)
+                .bind(now + Duration::minutes(15))
+                .execute(&db.pool)
+                .await
+                .unwrap();
+        }
+        crate::database::events::add_reminder(&db.pool, "Call the dentist", now, None).await.unwrap();
+
+        let team_id = sqlx::query("INSERT INTO accounts (provider, account_name, auth_data, is_shared) VALUES ('proton', 'Team', 'https://example.com/team.ics', 1)")
+            .execute(&db.pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+        sqlx::query("INSERT INTO events (external_id, account_id, title, start_time, end_time) VALUES ('offsite', ?, 'Team offsite', ?, ?)")
+            .bind(team_id)
+            .bind(now)
+            .bind(now + Duration::hours(3))
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        let stats = meeting_stats(&db.pool).await.unwrap();
+        assert_eq!(stats.total_meetings(), 1);
+        assert_eq!(stats.hours_per_week.len(), STATS_WEEKS);
+        assert_eq!(stats.hours_per_week.last().unwrap().1, 0.25);
+    }
+
+    #[tokio::test]
+    async fn test_meeting_stats_counts_team_meetings_you_attend() {
+        use crate::models::{Attendee, Rsvp};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = crate::database::Database::open(&dir.path().join("test.db")).await.unwrap();
+        let settings = crate::models::Settings { my_addresses: "Me@Example.com".to_string(), ..Default::default() };
+        crate::database::settings::update(&db.pool, &settings).await.unwrap();
+        let now = Utc::now();
+
+        let team_id = sqlx::query("INSERT INTO accounts (provider, account_name, auth_data, is_shared) VALUES ('proton', 'Team', 'https://example.com/team.ics', 1)")
+            .execute(&db.pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+        let attendee = |email: &str, rsvp| Attendee { email: email.to_string(), name: None, rsvp, is_organizer: false };
+        for (external_id, attendees) in [
+            ("review", vec![attendee("me@example.com", Rsvp::Accepted), attendee("alex@example.com", Rsvp::Accepted)]),
+            ("offsite", vec![attendee("alex@example.com", Rsvp::Accepted)]),
+            ("retro", vec![attendee("me@example.com", Rsvp::Declined)]),
+        ] {
+            let event_id = sqlx::query("INSERT INTO events (external_id, account_id, title, start_time, end_time) VALUES (?, ?, ?, ?, ?)")
+                .bind(external_id)
+                .bind(team_id)
+                .bind(external_id)
+                .bind(now)
+                .bind(now + Duration::minutes(30))
+                .execute(&db.pool)
+                .await
+                .unwrap()
+                .last_insert_rowid();
+            let mut conn = db.pool.acquire().await.unwrap();
+            crate::database::attendees::set_for_event(&mut conn, crate::models::EventId(event_id), &attendees).await.unwrap();
+        }
+
+        let stats = meeting_stats(&db.pool).await.unwrap();
+        assert_eq!(stats.total_meetings(), 1);
+        assert_eq!(stats.hours_per_week.last().unwrap().1, 0.5);
+    }
+}
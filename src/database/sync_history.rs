@@ -0,0 +1,212 @@
+// file: src/database/sync_history.rs
+//! One row per sync attempt, so `openchime doctor` and the Stats view can
+//! show which feeds are flaky. Errors are stored with URLs cut down to their host: feed
+//! URLs are secrets.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use regex::{Captures, Regex};
+use sqlx::{FromRow, SqlitePool};
+
+/// Attempts older than this are pruned as new ones are recorded
+const KEEP_DAYS: i64 = 30;
+/// How far back the reliability report looks
+pub const REPORT_DAYS: i64 = 7;
+/// Below this success rate a feed is reported as flaky
+pub const FLAKY_SUCCESS_RATE: f64 = 0.9;
+
+lazy_static::lazy_static! {
+    static ref URL: Regex = Regex::new(r#"(?i)\b(?:https?|webcal)://([^/\s?#"'<>]+)[^\s"'<>)]*"#).unwrap();
+}
+
+/// An account's sync attempts over a period
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct AccountReliability {
+    pub account_id: i64,
+    pub account_name: String,
+    pub attempts: i64,
+    pub successes: i64,
+    pub average_duration_ms: f64,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+}
+
+impl AccountReliability {
+    /// Share of attempts that succeeded, 0.0 to 1.0
+    pub fn success_rate(&self) -> f64 {
+        if self.attempts == 0 {
+            return 0.0;
+        }
+        self.successes as f64 / self.attempts as f64
+    }
+
+    pub fn average_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.average_duration_ms.max(0.0) as u64)
+    }
+
+    /// Whether syncs fail often enough to look into
+    pub fn is_flaky(&self) -> bool {
+        self.success_rate() < FLAKY_SUCCESS_RATE
+    }
+
+    /// One line on how the account's syncs went, for the report
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{:.0}% of {} syncs succeeded in the past week, {:.1}s on average",
+            self.success_rate() * 100.0,
+            self.attempts,
+            self.average_duration().as_secs_f64()
+        );
+        if let Some(error) = &self.last_error {
+            match self.last_error_at {
+                Some(at) => summary.push_str(&format!("; last error {}: {}", at.with_timezone(&chrono::Local).format("%a %H:%M"), error)),
+                None => summary.push_str(&format!("; last error: {}", error)),
+            }
+        }
+        summary
+    }
+}
+
+/// Record a sync attempt; `error` is `None` when it succeeded
+pub async fn record(
+    pool: &SqlitePool,
+    account_id: i64,
+    started_at: DateTime<Utc>,
+    duration: std::time::Duration,
+    error: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO sync_history (account_id, started_at, duration_ms, success, error) VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(account_id)
+    .bind(started_at)
+    .bind(duration.as_millis() as i64)
+    .bind(error.is_none())
+    .bind(error.map(redact_urls))
+    .execute(pool)
+    .await?;
+
+    sqlx::query("DELETE FROM sync_history WHERE started_at < ?")
+        .bind(started_at - Duration::days(KEEP_DAYS))
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// The reliability report: each account over the past `REPORT_DAYS`
+pub async fn recent_reliability(pool: &SqlitePool) -> Result<Vec<AccountReliability>> {
+    reliability_since(pool, Utc::now() - Duration::days(REPORT_DAYS)).await
+}
+
+/// Per-account reliability for attempts since `since`. Accounts that didn't
+/// try to sync in that time are left out.
+pub async fn reliability_since(pool: &SqlitePool, since: DateTime<Utc>) -> Result<Vec<AccountReliability>> {
+    let report = sqlx::query_as(
+        "SELECT a.id AS account_id, a.account_name,
+                COUNT(*) AS attempts,
+                SUM(h.success) AS successes,
+                AVG(h.duration_ms) AS average_duration_ms,
+                (SELECT error FROM sync_history f WHERE f.account_id = a.id AND f.success = 0 AND f.started_at >= ?
+                 ORDER BY f.started_at DESC LIMIT 1) AS last_error,
+                (SELECT MAX(started_at) FROM sync_history f WHERE f.account_id = a.id AND f.success = 0 AND f.started_at >= ?) AS last_error_at
+         FROM sync_history h JOIN accounts a ON a.id = h.account_id
+         WHERE h.started_at >= ?
+         GROUP BY a.id
+         ORDER BY a.account_name"
+    )
+    .bind(since)
+    .bind(since)
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(report)
+}
+
+/// Accounts whose last `failures` or more sync attempts all failed
+pub async fn failing_accounts(pool: &SqlitePool, failures: i64) -> Result<Vec<i64>> {
+    let accounts = sqlx::query_scalar(
+        "SELECT h.account_id FROM sync_history h
+         WHERE h.success = 0
+           AND h.started_at > COALESCE(
+               (SELECT MAX(s.started_at) FROM sync_history s WHERE s.account_id = h.account_id AND s.success = 1), '')
+         GROUP BY h.account_id
+         HAVING COUNT(*) >= ?"
+    )
+    .bind(failures)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(accounts)
+}
+
+/// Replace each URL with its host
+pub(crate) fn redact_urls(error: &str) -> String {
+    URL.replace_all(error, |caps: &Captures| format!("<{}>", &caps[1])).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_urls_keeps_only_the_host() {
+        let error = "error sending request for url (https://calendar.proton.me/api/calendar/v1/url/SECRET/calendar.ics?CacheKey=abc): timed out";
+        assert_eq!(redact_urls(error), "error sending request for url (<calendar.proton.me>): timed out");
+        assert_eq!(redact_urls("HTTP 404"), "HTTP 404");
+    }
+
+    #[tokio::test]
+    async fn test_reliability_since() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = crate::database::Database::open(&dir.path().join("test.db")).await.unwrap();
+        let account = crate::models::Account::new_proton("Work".to_string(), "https://calendar.proton.me/a.ics".to_string());
+        let account_id = db.add_account(&account).await.unwrap();
+        let now = Utc::now();
+        let ms = std::time::Duration::from_millis;
+
+        // An old failure outside the window
+        record(&db.pool, account_id, now - Duration::days(10), ms(100), Some("old")).await.unwrap();
+        record(&db.pool, account_id, now - Duration::hours(3), ms(1000), None).await.unwrap();
+        record(&db.pool, account_id, now - Duration::hours(2), ms(3000), Some("HTTP 503 from https://calendar.proton.me/secret")).await.unwrap();
+        record(&db.pool, account_id, now - Duration::hours(1), ms(2000), None).await.unwrap();
+
+        let report = reliability_since(&db.pool, now - Duration::days(7)).await.unwrap();
+        assert_eq!(report.len(), 1);
+        let work = &report[0];
+        assert_eq!(work.account_name, "Work");
+        assert_eq!((work.attempts, work.successes), (3, 2));
+        assert!((work.success_rate() - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(work.average_duration(), ms(2000));
+        assert_eq!(work.last_error.as_deref(), Some("HTTP 503 from <calendar.proton.me>"));
+        assert!(work.last_error_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_failing_accounts_counts_failures_since_the_last_success() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = crate::database::Database::open(&dir.path().join("test.db")).await.unwrap();
+        let flaky = crate::models::Account::new_proton("Flaky".to_string(), "https://calendar.proton.me/a.ics".to_string());
+        let broken = crate::models::Account::new_proton("Broken".to_string(), "https://calendar.proton.me/b.ics".to_string());
+        let flaky_id = db.add_account(&flaky).await.unwrap();
+        let broken_id = db.add_account(&broken).await.unwrap();
+        let now = Utc::now();
+        let ms = std::time::Duration::from_millis;
+
+        for hours_ago in [5, 4, 3] {
+            record(&db.pool, flaky_id, now - Duration::hours(hours_ago), ms(100), Some("HTTP 503")).await.unwrap();
+        }
+        record(&db.pool, flaky_id, now - Duration::hours(2), ms(100), None).await.unwrap();
+        record(&db.pool, flaky_id, now - Duration::hours(1), ms(100), Some("HTTP 503")).await.unwrap();
+        record(&db.pool, broken_id, now - Duration::hours(3), ms(100), None).await.unwrap();
+        for hours_ago in [2, 1, 0] {
+            record(&db.pool, broken_id, now - Duration::hours(hours_ago), ms(100), Some("HTTP 404")).await.unwrap();
+        }
+
+        assert_eq!(failing_accounts(&db.pool, 3).await.unwrap(), vec![broken_id]);
+        let mut either = failing_accounts(&db.pool, 1).await.unwrap();
+        either.sort();
+        assert_eq!(either, vec![flaky_id, broken_id]);
+    }
+}
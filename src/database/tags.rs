@@ -0,0 +1,66 @@
+// file: src/database/tags.rs
+//! Event tags: categories such as "standup", "interview" or "1:1" that
+//! alert rules and statistics can group events by
+
+use anyhow::Result;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+
+use crate::models::EventId;
+
+/// Replace an event's automatic tags. Tags the user added are kept.
+pub async fn set_auto_tags(conn: &mut SqliteConnection, event_id: EventId, tags: &[&str]) -> Result<()> {
+    sqlx::query("DELETE FROM event_tags WHERE event_id = ? AND is_auto = 1")
+        .bind(event_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for tag in tags {
+        sqlx::query("INSERT OR IGNORE INTO event_tags (event_id, tag, is_auto) VALUES (?, ?, 1)")
+            .bind(event_id)
+            .bind(tag)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Recompute automatic tags for every stored event
+pub async fn retag_all(pool: &SqlitePool) -> Result<()> {
+    let rows = sqlx::query("SELECT id, title, description FROM events")
+        .fetch_all(pool)
+        .await?;
+
+    let mut tx = pool.begin().await?;
+    for row in rows {
+        let event_id: EventId = row.try_get("id")?;
+        let title: String = row.try_get("title")?;
+        let description: Option<String> = row.try_get("description")?;
+        let tags = crate::utils::categorize_event(&title, description.as_deref());
+        set_auto_tags(&mut tx, event_id, &tags).await?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// An event's tags, alphabetically
+pub async fn get_for_event(pool: &SqlitePool, event_id: EventId) -> Result<Vec<String>> {
+    let tags = sqlx::query_scalar("SELECT tag FROM event_tags WHERE event_id = ? ORDER BY tag")
+        .bind(event_id)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(tags)
+}
+
+/// How many events carry each tag, most common first
+pub async fn count_by_tag(pool: &SqlitePool) -> Result<Vec<(String, i64)>> {
+    let counts = sqlx::query_as(
+        "SELECT tag, COUNT(*) AS events FROM event_tags GROUP BY tag ORDER BY events DESC, tag ASC"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(counts)
+}
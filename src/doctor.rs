@@ -0,0 +1,357 @@
+//! `openchime doctor` health check
+//!
+//! Runs the same startup steps the app does (database, calendar feeds,
+//! audio) and collects the outcome of each into a plain-text report that
+//! users can paste into a support thread. Feed URLs are secret, so the
+//! report only ever shows the host name.
+
+use std::fmt;
+use std::str::FromStr;
+
+use icalendar::Calendar as IcsCalendar;
+use url::Url;
+
+use crate::audio::AudioManager;
+use crate::calendar::caldav::CalDavSource;
+use crate::calendar::common;
+use crate::calendar::source::{CalendarSource, FeedAuth};
+use crate::database::sync_history::{self, AccountReliability};
+use crate::database::Database;
+use crate::http_config::HttpClientFactory;
+use crate::models::Account;
+use crate::profiles::ResolvedProfile;
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    fn label(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+        }
+    }
+}
+
+/// A single line in the doctor report
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl CheckResult {
+    pub fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self { name: name.into(), status: CheckStatus::Pass, detail: detail.into() }
+    }
+
+    pub fn warn(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self { name: name.into(), status: CheckStatus::Warn, detail: detail.into() }
+    }
+
+    pub fn fail(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self { name: name.into(), status: CheckStatus::Fail, detail: detail.into() }
+    }
+}
+
+/// Full diagnostic report
+#[derive(Debug, Clone, Default)]
+pub struct DoctorReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    pub fn push(&mut self, check: CheckResult) {
+        self.checks.push(check);
+    }
+
+    fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+
+    /// True if any check failed outright
+    pub fn has_failures(&self) -> bool {
+        self.count(CheckStatus::Fail) > 0
+    }
+}
+
+impl fmt::Display for DoctorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "OpenChime doctor report")?;
+        writeln!(f, "=======================")?;
+        writeln!(f, "Version:  {}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(f, "Platform: {} ({})", std::env::consts::OS, std::env::consts::ARCH)?;
+        writeln!(f)?;
+
+        for check in &self.checks {
+            writeln!(f, "[{}] {}: {}", check.status.label(), check.name, check.detail)?;
+        }
+
+        writeln!(f)?;
+        write!(
+            f,
+            "Summary: {} passed, {} warnings, {} failed",
+            self.count(CheckStatus::Pass),
+            self.count(CheckStatus::Warn),
+            self.count(CheckStatus::Fail)
+        )
+    }
+}
+
+/// Apply the network settings before any feed is fetched, and say which proxy
+/// that is
+async fn check_proxy(db: &Database) -> CheckResult {
+    let name = "Proxy";
+    match crate::database::settings::get(&db.pool).await {
+        Ok(settings) => crate::http_config::apply_settings(&settings),
+        Err(e) => return CheckResult::warn(name, format!("could not read the proxy setting: {}", e)),
+    }
+    match crate::http_config::configured_proxy() {
+        Some(proxy) => {
+            // A proxy URL can carry a password
+            let shown = Url::parse(&proxy)
+                .map(|mut url| {
+                    if url.password().is_some() {
+                        let _ = url.set_password(Some("****"));
+                    }
+                    url.to_string()
+                })
+                .unwrap_or(proxy);
+            CheckResult::pass(name, format!("requests go through {}", shown))
+        }
+        None => CheckResult::pass(name, "none, connecting directly"),
+    }
+}
+
+/// Run every check against `profile` and build the report
+pub async fn run(profile: &ResolvedProfile, play_sound: bool) -> DoctorReport {
+    let mut report = DoctorReport::default();
+
+    report.push(CheckResult::pass(
+        "Profile",
+        format!("{} (database: {})", profile.name, profile.database_path.display()),
+    ));
+
+    match Database::open(&profile.database_path).await {
+        Ok(db) => {
+            report.push(check_database(&db).await);
+            report.push(check_proxy(&db).await);
+
+            match db.get_accounts().await {
+                Ok(accounts) if accounts.is_empty() => {
+                    report.push(CheckResult::warn("Accounts", "no calendar accounts configured"));
+                }
+                Ok(accounts) => {
+                    for account in &accounts {
+                        report.push(check_account(account).await);
+                    }
+                    for check in check_sync_history(&db).await {
+                        report.push(check);
+                    }
+                }
+                Err(e) => report.push(CheckResult::fail("Accounts", format!("could not load accounts: {}", e))),
+            }
+        }
+        Err(e) => {
+            report.push(CheckResult::fail("Database", format!("{:#}", e)));
+        }
+    }
+
+    report.push(check_audio(play_sound).await);
+
+    report
+}
+
+async fn check_database(db: &Database) -> CheckResult {
+    let integrity: Result<String, sqlx::Error> = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_one(&db.pool)
+        .await;
+
+    match integrity {
+        Ok(result) if result == "ok" => {}
+        Ok(result) => return CheckResult::fail("Database", format!("integrity check reported: {}", result)),
+        Err(e) => return CheckResult::fail("Database", format!("integrity check failed: {}", e)),
+    }
+
+    let counts: Result<(i64, i64), sqlx::Error> = sqlx::query_as(
+        "SELECT (SELECT COUNT(*) FROM accounts), (SELECT COUNT(*) FROM events)"
+    )
+    .fetch_one(&db.pool)
+    .await;
+
+    match counts {
+        Ok((accounts, events)) => CheckResult::pass(
+            "Database",
+            format!("opened and migrated ({} accounts, {} events)", accounts, events),
+        ),
+        Err(e) => CheckResult::fail("Database", format!("schema query failed: {}", e)),
+    }
+}
+
+async fn check_account(account: &Account) -> CheckResult {
+    let name = format!("Account \"{}\" ({}, {})", account.account_name, account.provider, redacted_host(&account.auth_data));
+
+    let breaker_name = match account.provider() {
+        Ok(crate::models::CalendarProvider::Google) => "google_calendar",
+        Ok(crate::models::CalendarProvider::Proton) => "proton_calendar",
+        Ok(crate::models::CalendarProvider::Generic) => "generic_calendar",
+        Ok(crate::models::CalendarProvider::CalDav) => "caldav_calendar",
+        Ok(crate::models::CalendarProvider::Local) => {
+            return CheckResult::pass(format!("Account \"{}\" (local)", account.account_name), "reminders are stored locally; nothing to fetch");
+        }
+        Err(e) => return CheckResult::fail(name, e.to_string()),
+    };
+
+    if let Err(e) = common::validate_ics_url_format(&account.auth_data) {
+        return CheckResult::fail(name, format!("invalid feed URL: {}", e));
+    }
+
+    // Always hit the network here; a cached copy would hide a broken feed
+    let client = match HttpClientFactory::shared_ics_client() {
+        Ok(client) => client,
+        Err(e) => return CheckResult::fail(name, format!("could not build HTTP client: {}", e)),
+    };
+
+    let fetched = if account.provider == crate::models::CalendarProvider::CalDav.as_str() {
+        match CalDavSource::from_account(account, tokio_util::sync::CancellationToken::new()).await {
+            Ok(source) => source.fetch_ics().await,
+            Err(e) => Err(e),
+        }
+    } else {
+        match FeedAuth::from_account(account).await {
+            Ok(auth) => common::fetch_ics_data_with_client(&client, &account.auth_data, breaker_name, auth.as_ref()).await,
+            Err(e) => Err(e),
+        }
+    };
+    let ics_data = match fetched {
+        Ok(data) => data,
+        Err(e) => return CheckResult::fail(name, format!("fetch failed: {:#}", e)),
+    };
+
+    match IcsCalendar::from_str(&ics_data) {
+        Ok(calendar) => {
+            let event_count = calendar.components.iter().filter(|c| c.as_event().is_some()).count();
+            let detail = format!("fetched {} bytes, parsed {} events", ics_data.len(), event_count);
+            if event_count == 0 {
+                CheckResult::warn(name, detail)
+            } else {
+                CheckResult::pass(name, detail)
+            }
+        }
+        Err(e) => CheckResult::fail(name, format!("fetched {} bytes but parsing failed: {}", ics_data.len(), e)),
+    }
+}
+
+/// How each account's background syncs went over the past week
+async fn check_sync_history(db: &Database) -> Vec<CheckResult> {
+    match sync_history::recent_reliability(&db.pool).await {
+        Ok(report) if report.is_empty() => vec![CheckResult::warn("Sync history", "no syncs recorded in the past week")],
+        Ok(report) => report.iter().map(reliability_check).collect(),
+        Err(e) => vec![CheckResult::fail("Sync history", format!("could not read sync history: {}", e))],
+    }
+}
+
+fn reliability_check(reliability: &AccountReliability) -> CheckResult {
+    let name = format!("Sync history \"{}\"", reliability.account_name);
+    let detail = reliability.summary();
+
+    if reliability.successes == 0 {
+        CheckResult::fail(name, detail)
+    } else if reliability.is_flaky() {
+        CheckResult::warn(name, detail)
+    } else {
+        CheckResult::pass(name, detail)
+    }
+}
+
+async fn check_audio(play_sound: bool) -> CheckResult {
+    let audio = match AudioManager::new() {
+        Ok(audio) => audio,
+        Err(e) => return CheckResult::fail("Audio", format!("initialization failed: {}", e)),
+    };
+
+    if !play_sound {
+        return CheckResult::pass("Audio", "initialized (test tone skipped)");
+    }
+
+    match tokio::task::spawn_blocking(move || audio.play_test_tone()).await {
+        Ok(Ok(())) => CheckResult::pass("Audio", "initialized and played test tone"),
+        Ok(Err(e)) => CheckResult::fail("Audio", format!("{:#}", e)),
+        Err(e) => CheckResult::fail("Audio", format!("test tone task failed: {}", e)),
+    }
+}
+
+/// Host part of a feed URL; the full URL is a secret and never printed
+fn redacted_host(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_string()))
+        .unwrap_or_else(|| "unknown host".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacted_host_hides_secret_path() {
+        let host = redacted_host("https://calendar.proton.me/api/calendar/v1/url/SECRET/calendar.ics?CacheKey=abc");
+        assert_eq!(host, "calendar.proton.me");
+        assert_eq!(redacted_host("not a url"), "unknown host");
+    }
+
+    #[test]
+    fn test_report_summary_and_failures() {
+        let mut report = DoctorReport::default();
+        report.push(CheckResult::pass("Database", "ok"));
+        report.push(CheckResult::warn("Accounts", "none"));
+        assert!(!report.has_failures());
+
+        report.push(CheckResult::fail("Audio", "no device"));
+        assert!(report.has_failures());
+
+        let rendered = report.to_string();
+        assert!(rendered.contains("[PASS] Database: ok"));
+        assert!(rendered.contains("[WARN] Accounts: none"));
+        assert!(rendered.contains("[FAIL] Audio: no device"));
+        assert!(rendered.ends_with("Summary: 1 passed, 1 warnings, 1 failed"));
+    }
+
+    #[test]
+    fn test_reliability_check_flags_flaky_feeds() {
+        let reliability = AccountReliability {
+            account_id: 1,
+            account_name: "Work".to_string(),
+            attempts: 20,
+            successes: 19,
+            average_duration_ms: 1250.0,
+            last_error: Some("HTTP 503".to_string()),
+            last_error_at: None,
+        };
+        let check = reliability_check(&reliability);
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert_eq!(check.name, "Sync history \"Work\"");
+        assert_eq!(check.detail, "95% of 20 syncs succeeded in the past week, 1.2s on average; last error: HTTP 503");
+
+        let flaky = AccountReliability { successes: 12, ..reliability.clone() };
+        assert_eq!(reliability_check(&flaky).status, CheckStatus::Warn);
+        let broken = AccountReliability { successes: 0, ..reliability };
+        assert_eq!(reliability_check(&broken).status, CheckStatus::Fail);
+    }
+
+    #[tokio::test]
+    async fn test_check_account_rejects_bad_url_without_network() {
+        let account = Account::new_proton("Work".to_string(), "http://example.com/cal.ics".to_string());
+        let result = check_account(&account).await;
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.name.contains("example.com"));
+        assert!(result.detail.contains("HTTPS"));
+    }
+}
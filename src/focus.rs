@@ -0,0 +1,241 @@
+// Focus timer
+// Pomodoro-style work/break cycle. The timer is a plain state machine driven
+// by the caller's clock: the app ticks it once a second and chimes when a
+// phase ends. A meeting alert pauses it until the meeting is over.
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Work session length offered in the focus view (minutes)
+pub const WORK_MINUTE_CHOICES: [i32; 4] = [15, 25, 45, 50];
+/// Break length offered in the focus view (minutes)
+pub const BREAK_MINUTE_CHOICES: [i32; 3] = [5, 10, 15];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusPhase {
+    Work,
+    Break,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum FocusState {
+    Idle,
+    Running { ends_at: DateTime<Utc> },
+    Paused {
+        remaining: Duration,
+        /// Set when a meeting paused the timer: resume once it has ended
+        resume_at: Option<DateTime<Utc>>,
+    },
+}
+
+/// What a tick changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusEvent {
+    /// The previous phase ran out and this one started
+    PhaseStarted(FocusPhase),
+    /// The meeting that paused the timer is over
+    Resumed,
+}
+
+#[derive(Debug, Clone)]
+pub struct FocusTimer {
+    phase: FocusPhase,
+    state: FocusState,
+    work: Duration,
+    rest: Duration,
+    completed_sessions: u32,
+}
+
+impl FocusTimer {
+    pub fn new(work: Duration, rest: Duration) -> Self {
+        Self {
+            phase: FocusPhase::Work,
+            state: FocusState::Idle,
+            work,
+            rest,
+            completed_sessions: 0,
+        }
+    }
+
+    /// Change phase lengths. Takes effect from the next phase.
+    pub fn set_durations(&mut self, work: Duration, rest: Duration) {
+        self.work = work;
+        self.rest = rest;
+    }
+
+    pub fn phase(&self) -> FocusPhase {
+        self.phase
+    }
+
+    /// Work sessions finished since the timer was started
+    pub fn completed_sessions(&self) -> u32 {
+        self.completed_sessions
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.state == FocusState::Idle
+    }
+
+    pub fn is_running(&self) -> bool {
+        matches!(self.state, FocusState::Running { .. })
+    }
+
+    /// Whether a meeting alert paused the timer
+    pub fn is_paused_for_meeting(&self) -> bool {
+        matches!(self.state, FocusState::Paused { resume_at: Some(_), .. })
+    }
+
+    /// Time left in the current phase
+    pub fn remaining(&self, now: DateTime<Utc>) -> Option<Duration> {
+        match self.state {
+            FocusState::Idle => None,
+            FocusState::Running { ends_at } => Some((ends_at - now).max(Duration::zero())),
+            FocusState::Paused { remaining, .. } => Some(remaining),
+        }
+    }
+
+    /// Begin a work session
+    pub fn start(&mut self, now: DateTime<Utc>) {
+        self.phase = FocusPhase::Work;
+        self.completed_sessions = 0;
+        self.state = FocusState::Running { ends_at: now + self.work };
+    }
+
+    pub fn stop(&mut self) {
+        self.phase = FocusPhase::Work;
+        self.state = FocusState::Idle;
+    }
+
+    pub fn pause(&mut self, now: DateTime<Utc>) {
+        if let FocusState::Running { ends_at } = self.state {
+            self.state = FocusState::Paused { remaining: (ends_at - now).max(Duration::zero()), resume_at: None };
+        }
+    }
+
+    /// Pause for a meeting; `tick` resumes the timer once `meeting_end` has passed.
+    /// A timer the user paused stays paused.
+    pub fn pause_for_meeting(&mut self, now: DateTime<Utc>, meeting_end: DateTime<Utc>) {
+        match &mut self.state {
+            FocusState::Running { ends_at } => {
+                self.state = FocusState::Paused {
+                    remaining: (*ends_at - now).max(Duration::zero()),
+                    resume_at: Some(meeting_end),
+                };
+            }
+            // Back-to-back meetings: wait for the later one
+            FocusState::Paused { resume_at: Some(resume_at), .. } => {
+                *resume_at = (*resume_at).max(meeting_end);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn resume(&mut self, now: DateTime<Utc>) {
+        if let FocusState::Paused { remaining, .. } = self.state {
+            self.state = FocusState::Running { ends_at: now + remaining };
+        }
+    }
+
+    /// Advance to `now`, returning what changed
+    pub fn tick(&mut self, now: DateTime<Utc>) -> Option<FocusEvent> {
+        match self.state {
+            FocusState::Paused { resume_at: Some(resume_at), .. } if now >= resume_at => {
+                self.resume(now);
+                Some(FocusEvent::Resumed)
+            }
+            FocusState::Running { ends_at } if now >= ends_at => {
+                let (next, length) = match self.phase {
+                    FocusPhase::Work => {
+                        self.completed_sessions += 1;
+                        (FocusPhase::Break, self.rest)
+                    }
+                    FocusPhase::Break => (FocusPhase::Work, self.work),
+                };
+                self.phase = next;
+                self.state = FocusState::Running { ends_at: now + length };
+                Some(FocusEvent::PhaseStarted(next))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(minute: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap() + Duration::minutes(minute)
+    }
+
+    fn timer() -> FocusTimer {
+        FocusTimer::new(Duration::minutes(25), Duration::minutes(5))
+    }
+
+    #[test]
+    fn test_cycles_between_work_and_break() {
+        let mut timer = timer();
+        assert!(timer.is_idle());
+        assert_eq!(timer.tick(at(0)), None);
+
+        timer.start(at(0));
+        assert_eq!(timer.remaining(at(10)), Some(Duration::minutes(15)));
+        assert_eq!(timer.tick(at(24)), None);
+
+        assert_eq!(timer.tick(at(25)), Some(FocusEvent::PhaseStarted(FocusPhase::Break)));
+        assert_eq!(timer.completed_sessions(), 1);
+        assert_eq!(timer.remaining(at(25)), Some(Duration::minutes(5)));
+
+        assert_eq!(timer.tick(at(30)), Some(FocusEvent::PhaseStarted(FocusPhase::Work)));
+        assert_eq!(timer.phase(), FocusPhase::Work);
+
+        timer.stop();
+        assert!(timer.is_idle());
+        assert_eq!(timer.remaining(at(31)), None);
+    }
+
+    #[test]
+    fn test_pause_keeps_the_time_left() {
+        let mut timer = timer();
+        timer.start(at(0));
+        timer.pause(at(10));
+
+        // Time spent paused doesn't count
+        assert_eq!(timer.tick(at(60)), None);
+        assert_eq!(timer.remaining(at(60)), Some(Duration::minutes(15)));
+
+        timer.resume(at(60));
+        assert_eq!(timer.tick(at(74)), None);
+        assert_eq!(timer.tick(at(75)), Some(FocusEvent::PhaseStarted(FocusPhase::Break)));
+    }
+
+    #[test]
+    fn test_meeting_pauses_until_it_ends() {
+        let mut timer = timer();
+        timer.start(at(0));
+
+        timer.pause_for_meeting(at(10), at(40));
+        assert!(timer.is_paused_for_meeting());
+        // A second, later meeting extends the pause
+        timer.pause_for_meeting(at(12), at(45));
+
+        assert_eq!(timer.tick(at(40)), None);
+        assert_eq!(timer.tick(at(45)), Some(FocusEvent::Resumed));
+        assert!(timer.is_running());
+        assert_eq!(timer.remaining(at(45)), Some(Duration::minutes(15)));
+    }
+
+    #[test]
+    fn test_meeting_leaves_idle_and_user_paused_timers_alone() {
+        let mut idle = timer();
+        idle.pause_for_meeting(at(0), at(30));
+        assert!(idle.is_idle());
+
+        let mut paused = timer();
+        paused.start(at(0));
+        paused.pause(at(5));
+        paused.pause_for_meeting(at(6), at(30));
+        assert_eq!(paused.tick(at(31)), None);
+        assert!(!paused.is_running());
+    }
+}
@@ -1,11 +1,84 @@
 //! HTTP client configuration module
 //! 
 //! This module provides centralized configuration for HTTP clients,
-//! including timeouts, retry policies, and connection settings.
+//! including timeouts, retry policies, proxies and connection settings.
 
 use reqwest::{Client, ClientBuilder};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, RwLock};
 use std::time::Duration;
 
+/// User agent sent with every request
+pub const USER_AGENT: &str = concat!("OpenChime/", env!("CARGO_PKG_VERSION"));
+
+/// Process-wide ICS client and the proxy it was built for; built on first
+/// use and again after the proxy changes
+static SHARED_ICS_CLIENT: Mutex<Option<(Option<String>, Client)>> = Mutex::new(None);
+
+/// The `proxy_url` setting, see `set_proxy_setting`
+static PROXY_SETTING: RwLock<String> = RwLock::new(String::new());
+
+/// The `max_feed_size_mb` setting in bytes, see `apply_settings`
+static MAX_FEED_BYTES: AtomicUsize = AtomicUsize::new(25 * 1024 * 1024);
+
+/// Environment variables a proxy is read from when the setting is empty
+const PROXY_ENV_VARS: [&str; 4] = ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"];
+
+/// Use the `proxy_url` setting for clients built from now on; the shared ICS
+/// client is rebuilt on its next use
+pub fn set_proxy_setting(setting: &str) {
+    // Never hold both locks: `shared_ics_client` reads the setting too
+    let changed = {
+        let mut current = PROXY_SETTING.write().unwrap();
+        let changed = *current != setting.trim();
+        if changed {
+            *current = setting.trim().to_string();
+        }
+        changed
+    };
+    if changed {
+        *SHARED_ICS_CLIENT.lock().unwrap() = None;
+    }
+}
+
+/// Use the network settings (proxy and feed size limit) for requests from now on
+pub fn apply_settings(settings: &crate::models::Settings) {
+    set_proxy_setting(&settings.proxy_url);
+    MAX_FEED_BYTES.store(settings.max_feed_bytes(), Ordering::Relaxed);
+}
+
+/// The proxy requests go through: the `proxy_url` setting, or else
+/// `HTTPS_PROXY` (then `ALL_PROXY`) from the environment. A setting of
+/// `none` goes direct whatever the environment says.
+pub fn configured_proxy() -> Option<String> {
+    resolve_proxy(&PROXY_SETTING.read().unwrap(), |name| std::env::var(name).ok())
+}
+
+fn resolve_proxy(setting: &str, env: impl Fn(&str) -> Option<String>) -> Option<String> {
+    match setting.trim() {
+        "" => PROXY_ENV_VARS.iter().find_map(|name| env(name).filter(|value| !value.trim().is_empty())),
+        none if none.eq_ignore_ascii_case("none") => None,
+        proxy => Some(proxy.to_string()),
+    }
+}
+
+/// Check a `proxy_url` setting: empty, `none`, or an `http://`, `https://`,
+/// `socks5://` or `socks5h://` URL
+pub fn validate_proxy_setting(setting: &str) -> Result<(), String> {
+    let setting = setting.trim();
+    if setting.is_empty() || setting.eq_ignore_ascii_case("none") {
+        return Ok(());
+    }
+    let url = url::Url::parse(setting).map_err(|e| format!("'{}' isn't a proxy URL: {}", setting, e))?;
+    if !matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+        return Err(format!("Unsupported proxy type '{}' (use http, https, socks5 or socks5h)", url.scheme()));
+    }
+    if url.host_str().is_none() {
+        return Err(format!("'{}' has no proxy host", setting));
+    }
+    Ok(())
+}
+
 /// HTTP client configuration
 #[derive(Debug, Clone)]
 pub struct HttpConfig {
@@ -23,6 +96,11 @@ pub struct HttpConfig {
     pub max_retry_delay: Duration,
     /// Backoff multiplier for exponential backoff
     pub backoff_multiplier: f64,
+    /// Largest (decompressed) response body that will be read into memory
+    pub max_response_bytes: usize,
+    /// Proxy every request goes through (`http://`, `https://`, `socks5://`
+    /// or `socks5h://` URL); `None` connects directly
+    pub proxy: Option<String>,
 }
 
 impl Default for HttpConfig {
@@ -35,6 +113,8 @@ impl Default for HttpConfig {
             base_retry_delay: Duration::from_millis(500),
             max_retry_delay: Duration::from_secs(30),
             backoff_multiplier: 2.0,
+            max_response_bytes: 10 * 1024 * 1024,
+            proxy: configured_proxy(),
         }
     }
 }
@@ -55,6 +135,8 @@ impl HttpConfig {
             base_retry_delay: Duration::from_millis(1000),
             max_retry_delay: Duration::from_secs(20),
             backoff_multiplier: 2.0,
+            max_response_bytes: 10 * 1024 * 1024,
+            proxy: configured_proxy(),
         }
     }
     
@@ -68,6 +150,8 @@ impl HttpConfig {
             base_retry_delay: Duration::from_millis(2000),
             max_retry_delay: Duration::from_secs(30),
             backoff_multiplier: 1.5,
+            max_response_bytes: MAX_FEED_BYTES.load(Ordering::Relaxed), // `max_feed_size_mb`
+            proxy: configured_proxy(),
         }
     }
     
@@ -81,12 +165,24 @@ impl HttpConfig {
             base_retry_delay: Duration::from_millis(500),
             max_retry_delay: Duration::from_secs(10),
             backoff_multiplier: 2.0,
+            max_response_bytes: 1024 * 1024,
+            proxy: configured_proxy(),
         }
     }
     
     /// Build a reqwest client with this configuration
     pub fn build_client(&self) -> Result<Client, Box<dyn std::error::Error + Send + Sync>> {
-        Ok(ClientBuilder::new()
+        // The proxy is always the configured one, never reqwest's own pick
+        let mut builder = ClientBuilder::new().no_proxy();
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?.no_proxy(reqwest::NoProxy::from_env()));
+        }
+        Ok(builder
+            .user_agent(USER_AGENT)
+            // Advertise Accept-Encoding and transparently decompress; large ICS
+            // feeds shrink to a fraction of their size on every sync
+            .gzip(true)
+            .brotli(true)
             .connect_timeout(self.connect_timeout)
             .timeout(self.timeout) // Use unified timeout instead of separate read_timeout
             .tcp_keepalive(Duration::from_secs(30))
@@ -103,6 +199,7 @@ impl HttpConfig {
             base_delay: self.base_retry_delay,
             max_delay: self.max_retry_delay,
             backoff_multiplier: self.backoff_multiplier,
+            ..Default::default()
         }
     }
 }
@@ -139,6 +236,27 @@ impl HttpClientFactory {
     pub fn oauth_client(&self) -> Result<Client, Box<dyn std::error::Error + Send + Sync>> {
         HttpConfig::oauth().build_client()
     }
+
+    /// Get the shared, pooled client for ICS fetching
+    ///
+    /// Built once from `HttpConfig::ics_fetch()` and reused by every sync, so
+    /// connections and TLS sessions are kept alive between fetches. `Client`
+    /// is reference-counted, so the returned clone shares the same pool.
+    pub fn shared_ics_client() -> Result<Client, Box<dyn std::error::Error + Send + Sync>> {
+        // The proxy is resolved before taking the client lock, and a client
+        // built for another proxy (the setting changed in between) isn't reused
+        let config = HttpConfig::ics_fetch();
+        let mut shared = SHARED_ICS_CLIENT.lock().unwrap();
+        if let Some((proxy, client)) = shared.as_ref() {
+            if *proxy == config.proxy {
+                return Ok(client.clone());
+            }
+        }
+
+        let client = config.build_client()?;
+        *shared = Some((config.proxy, client.clone()));
+        Ok(client)
+    }
 }
 
 impl Default for HttpClientFactory {
@@ -146,3 +264,107 @@ impl Default for HttpClientFactory {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_build_clients() {
+        assert!(HttpConfig::default().build_client().is_ok());
+        assert!(HttpConfig::ics_fetch().build_client().is_ok());
+    }
+
+    #[test]
+    fn test_ics_retry_config_comes_from_preset() {
+        let config = HttpConfig::ics_fetch();
+        let retry = config.to_retry_config();
+        assert_eq!(retry.max_attempts, config.max_retries);
+        assert_eq!(retry.base_delay, config.base_retry_delay);
+        assert_eq!(retry.max_delay, config.max_retry_delay);
+    }
+
+    #[test]
+    fn test_shared_ics_client_is_reused() {
+        HttpClientFactory::shared_ics_client().unwrap();
+        assert!(SHARED_ICS_CLIENT.lock().unwrap().is_some());
+
+        // Later calls hand out the cached client instead of building a new one
+        assert!(HttpClientFactory::shared_ics_client().is_ok());
+    }
+
+    #[test]
+    fn test_proxy_changes_while_clients_are_built_dont_deadlock() {
+        let builders: Vec<_> = (0..4)
+            .map(|_| std::thread::spawn(|| for _ in 0..50 { HttpClientFactory::shared_ics_client().unwrap(); }))
+            .collect();
+        for i in 0..50 {
+            set_proxy_setting(if i % 2 == 0 { "none" } else { "" });
+        }
+        for builder in builders {
+            builder.join().unwrap();
+        }
+        set_proxy_setting("");
+    }
+
+    #[test]
+    fn test_resolve_proxy() {
+        let env = |name: &str| match name {
+            "https_proxy" => Some("http://proxy.corp.example:3128".to_string()),
+            "HTTPS_PROXY" => Some(" ".to_string()),
+            _ => None,
+        };
+        assert_eq!(resolve_proxy("", env).as_deref(), Some("http://proxy.corp.example:3128"));
+        assert_eq!(resolve_proxy(" socks5h://127.0.0.1:1080 ", env).as_deref(), Some("socks5h://127.0.0.1:1080"));
+        assert_eq!(resolve_proxy("None", env), None);
+        assert_eq!(resolve_proxy("", |_| None), None);
+    }
+
+    #[test]
+    fn test_validate_proxy_setting() {
+        assert!(validate_proxy_setting("").is_ok());
+        assert!(validate_proxy_setting("none").is_ok());
+        assert!(validate_proxy_setting("http://proxy.corp.example:3128").is_ok());
+        assert!(validate_proxy_setting("socks5://127.0.0.1:1080").is_ok());
+        assert!(validate_proxy_setting("ftp://proxy.corp.example").is_err());
+        assert!(validate_proxy_setting("proxy.corp.example:3128").is_err());
+    }
+
+    #[test]
+    fn test_clients_build_with_a_proxy() {
+        for proxy in ["http://proxy.corp.example:3128", "socks5h://127.0.0.1:1080"] {
+            let config = HttpConfig { proxy: Some(proxy.to_string()), ..HttpConfig::ics_fetch() };
+            assert!(config.build_client().is_ok(), "{}", proxy);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_requests_compressed_responses() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let n = socket.read(&mut buf).await.unwrap();
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_lowercase()
+        });
+
+        let client = HttpConfig::ics_fetch().build_client().unwrap();
+        client.get(format!("http://{}/feed.ics", addr)).send().await.unwrap();
+
+        let request = server.await.unwrap();
+        let accept_encoding = request
+            .lines()
+            .find(|line| line.starts_with("accept-encoding:"))
+            .expect("client should advertise Accept-Encoding");
+        assert!(accept_encoding.contains("gzip"));
+        assert!(accept_encoding.contains("br"));
+    }
+}
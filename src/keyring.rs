@@ -0,0 +1,234 @@
+// file: src/keyring.rs
+//! Secrets in the OS keyring, through the platform's own tool:
+//! `secret-tool` (libsecret) on Linux, `security` on macOS and the
+//! PasswordVault through PowerShell on Windows.
+//!
+//! The database only ever holds the name of an entry: Google API sign-ins
+//! in `accounts.refresh_token`, feed and CalDAV passwords in
+//! `accounts.secret_key`. Tests use an in-memory store instead.
+//!
+//! The tools can block (on an unlock prompt, say), so they run on the
+//! blocking thread pool rather than an async worker.
+
+use anyhow::{anyhow, bail, Result};
+#[cfg(not(test))]
+use std::io::Write;
+#[cfg(not(test))]
+use std::process::{Command, Stdio};
+
+/// Service name for keyring entries
+#[cfg_attr(test, allow(dead_code))]
+const KEYRING_SERVICE: &str = "openchime";
+
+/// A new, unique name for a keyring entry, starting with `kind`
+pub fn new_key(kind: &str) -> String {
+    format!("{}-{}", kind, uuid::Uuid::new_v4())
+}
+
+/// Keep `secret` under `key`, replacing what was there. `label` is what
+/// keyring managers show for the entry.
+pub async fn store(key: &str, label: &str, secret: &str) -> Result<()> {
+    let (key, label, secret) = (key.to_string(), label.to_string(), secret.to_string());
+    blocking(move || store_now(&key, &label, &secret)).await
+}
+
+/// The secret kept under `key`
+pub async fn load(key: &str) -> Result<String> {
+    let key = key.to_string();
+    blocking(move || load_now(&key)).await
+}
+
+/// Remove an entry; a missing one is fine
+pub async fn delete(key: &str) {
+    let key = key.to_string();
+    let _ = blocking(move || {
+        delete_now(&key);
+        Ok(())
+    })
+    .await;
+}
+
+async fn blocking<T: Send + 'static>(task: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    tokio::task::spawn_blocking(task)
+        .await
+        .map_err(|e| anyhow!("Keyring lookup didn't finish: {}", e))?
+}
+
+fn store_now(key: &str, label: &str, secret: &str) -> Result<()> {
+    #[cfg(test)]
+    {
+        let _ = label;
+        memory::store(key, secret);
+        Ok(())
+    }
+    #[cfg(all(not(test), target_os = "linux"))]
+    {
+        let mut command = Command::new("secret-tool");
+        command.args(["store", &format!("--label={}", label), "service", KEYRING_SERVICE, "account", key]);
+        run(command, Some(secret)).map(|_| ())
+    }
+    #[cfg(all(not(test), target_os = "macos"))]
+    {
+        // A trailing `-w` with no value has `security` prompt for the
+        // secret (and again to confirm) on stdin, keeping it out of argv
+        let mut command = Command::new("security");
+        command.args(["add-generic-password", "-U", "-s", KEYRING_SERVICE, "-a", key, "-l", label, "-w"]);
+        run(command, Some(&format!("{0}\n{0}\n", secret))).map(|_| ())
+    }
+    #[cfg(all(not(test), target_os = "windows"))]
+    {
+        let _ = label;
+        let script = format!(
+            "$secret = [Console]::In.ReadToEnd(); \
+             $vault.Add((New-Object Windows.Security.Credentials.PasswordCredential('{}', '{}', $secret)))",
+            KEYRING_SERVICE, key
+        );
+        run(powershell(&script), Some(secret)).map(|_| ())
+    }
+    #[cfg(all(not(test), not(any(target_os = "linux", target_os = "macos", target_os = "windows"))))]
+    {
+        let _ = (key, label, secret);
+        bail!("No supported keyring on this platform")
+    }
+}
+
+fn load_now(key: &str) -> Result<String> {
+    #[cfg(test)]
+    let secret = memory::load(key);
+    #[cfg(all(not(test), target_os = "linux"))]
+    let secret = {
+        let mut command = Command::new("secret-tool");
+        command.args(["lookup", "service", KEYRING_SERVICE, "account", key]);
+        run(command, None)?
+    };
+    #[cfg(all(not(test), target_os = "macos"))]
+    let secret = {
+        let mut command = Command::new("security");
+        command.args(["find-generic-password", "-s", KEYRING_SERVICE, "-a", key, "-w"]);
+        run(command, None)?
+    };
+    #[cfg(all(not(test), target_os = "windows"))]
+    let secret = {
+        let script = format!(
+            "$credential = $vault.Retrieve('{}', '{}'); $credential.RetrievePassword(); $credential.Password",
+            KEYRING_SERVICE, key
+        );
+        run(powershell(&script), None)?
+    };
+    #[cfg(all(not(test), not(any(target_os = "linux", target_os = "macos", target_os = "windows"))))]
+    let secret = {
+        let _ = key;
+        String::new()
+    };
+
+    if secret.is_empty() {
+        bail!("{} isn't in the keyring", key);
+    }
+    Ok(secret)
+}
+
+fn delete_now(key: &str) {
+    #[cfg(test)]
+    memory::delete(key);
+    #[cfg(all(not(test), target_os = "linux"))]
+    let command = {
+        let mut command = Command::new("secret-tool");
+        command.args(["clear", "service", KEYRING_SERVICE, "account", key]);
+        Some(command)
+    };
+    #[cfg(all(not(test), target_os = "macos"))]
+    let command = {
+        let mut command = Command::new("security");
+        command.args(["delete-generic-password", "-s", KEYRING_SERVICE, "-a", key]);
+        Some(command)
+    };
+    #[cfg(all(not(test), target_os = "windows"))]
+    let command = Some(powershell(&format!("$vault.Remove($vault.Retrieve('{}', '{}'))", KEYRING_SERVICE, key)));
+    #[cfg(all(not(test), not(any(target_os = "linux", target_os = "macos", target_os = "windows"))))]
+    let command: Option<Command> = None;
+
+    #[cfg(not(test))]
+    if let Some(command) = command {
+        if let Err(e) = run(command, None) {
+            log::debug!("Keyring entry {} not removed: {}", key, e);
+        }
+    }
+}
+
+/// Run `command`, writing `input` to its stdin; returns its stdout
+#[cfg(not(test))]
+fn run(mut command: Command, input: Option<&str>) -> Result<String> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("Couldn't run {} to reach the keyring: {}", program, e))?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end_matches(['\r', '\n']).to_string())
+}
+
+#[cfg(all(not(test), target_os = "windows"))]
+fn powershell(script: &str) -> Command {
+    let mut command = Command::new("powershell");
+    command.args([
+        "-NoProfile",
+        "-NonInteractive",
+        "-Command",
+        &format!(
+            "[void][Windows.Security.Credentials.PasswordVault,Windows.Security.Credentials,ContentType=WindowsRuntime]; \
+             $vault = New-Object Windows.Security.Credentials.PasswordVault; {}",
+            script
+        ),
+    ]);
+    command
+}
+
+/// The keyring tests see: a map for the whole test process
+#[cfg(test)]
+mod memory {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    static ENTRIES: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+    pub fn store(key: &str, secret: &str) {
+        ENTRIES.lock().unwrap().get_or_insert_with(HashMap::new).insert(key.to_string(), secret.to_string());
+    }
+
+    pub fn load(key: &str) -> String {
+        ENTRIES.lock().unwrap().as_ref().and_then(|entries| entries.get(key).cloned()).unwrap_or_default()
+    }
+
+    pub fn delete(key: &str) {
+        if let Some(entries) = ENTRIES.lock().unwrap().as_mut() {
+            entries.remove(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_store_load_delete() {
+        let key = new_key("test");
+        assert!(key.starts_with("test-"));
+        assert!(load(&key).await.is_err());
+
+        store(&key, "OpenChime test", "s3cret").await.unwrap();
+        assert_eq!(load(&key).await.unwrap(), "s3cret");
+        delete(&key).await;
+        assert!(load(&key).await.is_err());
+        // Already gone is fine
+        delete(&key).await;
+    }
+}
@@ -11,11 +11,23 @@ pub mod utils;
 pub mod error;
 pub mod command_handlers;
 pub mod http_config;
+pub mod keyring;
 pub mod config;
+pub mod connectivity;
+pub mod profiles;
+pub mod tray;
+pub mod protocol;
 pub mod app;
+pub mod cli;
+pub mod doctor;
+pub mod focus;
 pub mod messages;
+pub mod notify;
 pub mod ui;
 pub mod ui_state;
+pub mod updates;
+pub mod wake_lock;
+pub mod zone_watch;
 
 // Re-export commonly used types
 pub use models::*;
@@ -32,4 +44,6 @@ pub struct AppState {
     pub db: Arc<Database>,
     pub audio: Arc<AudioManager>,
     pub shutdown: tokio_util::sync::CancellationToken,
+    /// Time source for alert scheduling (a `MockClock` in tests)
+    pub clock: Arc<dyn utils::clock::Clock>,
 }
\ No newline at end of file
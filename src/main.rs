@@ -7,14 +7,54 @@ use iced::{Application, Settings as IcedSettings};
 
 use openchime::database::Database;
 use openchime::audio::AudioManager;
-use openchime::app::OpenChimeApp;
+use openchime::app::{AppFlags, OpenChimeApp};
+use openchime::cli::{self, CliCommand};
 use openchime::config;
+use openchime::profiles::{ProfilesConfig, ResolvedProfile};
 
 fn main() -> iced::Result {
+    let args = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    match args.command {
+        CliCommand::RunApp | CliCommand::Doctor { .. } | CliCommand::OpenLink(_) | CliCommand::GoogleLogin { .. } => {}
+        CliCommand::Help => {
+            print!("{}", cli::USAGE);
+            return Ok(());
+        }
+        CliCommand::Version => {
+            println!("openchime {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        CliCommand::ProfileList => run_profile_list(),
+        CliCommand::ProfileUse(name) => run_profile_use(&name),
+        CliCommand::RegisterUrlHandler => run_register_url_handler(),
+    }
+
+    let profile = match ProfilesConfig::load().and_then(|config| config.resolve(args.profile.as_deref())) {
+        Ok(profile) => profile,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if let CliCommand::Doctor { no_sound } = args.command {
+        run_doctor(&profile, !no_sound);
+    }
+    if let CliCommand::GoogleLogin { account_name, calendar_id } = &args.command {
+        run_google_login(&profile, account_name, calendar_id);
+    }
+
     // Initialize logging
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
-    info!("Starting OpenChime with iced UI");
+    info!("Starting OpenChime with iced UI (profile: {})", profile.name);
 
     // Create a Tokio runtime for async operations
     let rt = tokio::runtime::Builder::new_multi_thread()
@@ -34,7 +74,7 @@ fn main() -> iced::Result {
     }
 
         // Initialize core components
-        let db = match Database::new().await {
+        let db = match Database::open(&profile.database_path).await {
             Ok(database) => Arc::new(database),
             Err(e) => {
                 error!("Failed to initialize database: {}", e);
@@ -54,16 +94,32 @@ fn main() -> iced::Result {
             }
         };
 
+        // Before the first sync goes out
+        match db.get_settings().await {
+            Ok(settings) => openchime::http_config::apply_settings(&settings),
+            Err(e) => warn!("Failed to load the network settings: {}", e),
+        }
+
         (db, audio)
     });
 
     // Run iced application
     // The runtime 'rt' stays alive here, allowing background tasks (like DB pool) to function.
     let result = OpenChimeApp::run(IcedSettings {
-        flags: (db, audio),
+        flags: AppFlags {
+            db,
+            audio,
+            open_link: match args.command {
+                CliCommand::OpenLink(link) => Some(link),
+                _ => None,
+            },
+        },
         window: iced::window::Settings {
             size: iced::Size::new(800.0, 600.0),
             resizable: true,
+            // Closing only hides the window while there's a tray icon to
+            // bring it back from
+            exit_on_close_request: false,
             ..Default::default()
         },
         id: None,
@@ -77,4 +133,101 @@ fn main() -> iced::Result {
     drop(rt);
 
     result
-}
\ No newline at end of file
+}
+
+/// Run `openchime doctor`, print the report and exit
+fn run_doctor(profile: &ResolvedProfile, play_sound: bool) -> ! {
+    // Keep the report readable: only surface warnings and errors from the log
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to create Tokio runtime");
+
+    let report = rt.block_on(openchime::doctor::run(profile, play_sound));
+    println!("{}", report);
+
+    std::process::exit(if report.has_failures() { 1 } else { 0 });
+}
+
+/// Run `openchime google-login`: sign in with the device flow and add the account
+#[cfg(feature = "google-api")]
+fn run_google_login(profile: &ResolvedProfile, account_name: &str, calendar_id: &str) -> ! {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to create Tokio runtime");
+
+    let result = rt.block_on(async {
+        let db = Database::open(&profile.database_path).await?;
+        openchime::calendar::google_api::sign_in(&db.pool, account_name, calendar_id, |authorization| {
+            println!("To sign in, open {} and enter the code {}", authorization.verification_url, authorization.user_code);
+            println!("Waiting for you to approve OpenChime...");
+        })
+        .await
+    });
+    match result {
+        Ok(_) => {
+            println!("Added '{}'; it syncs the next time OpenChime runs", account_name);
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(not(feature = "google-api"))]
+fn run_google_login(_profile: &ResolvedProfile, _account_name: &str, _calendar_id: &str) -> ! {
+    eprintln!("❌ This build of OpenChime doesn't include the Google Calendar API; rebuild with `--features google-api`");
+    std::process::exit(1);
+}
+
+/// Print known profiles, marking the active one
+fn run_profile_list() -> ! {
+    let config = load_profiles_or_exit();
+    for name in config.names() {
+        let marker = if name == config.active_name() { "*" } else { " " };
+        match config.resolve(Some(&name)) {
+            Ok(profile) => println!("{} {} ({})", marker, name, profile.database_path.display()),
+            Err(_) => println!("{} {}", marker, name),
+        }
+    }
+    std::process::exit(0);
+}
+
+/// Switch the active profile and persist it
+fn run_profile_use(name: &str) -> ! {
+    let mut config = load_profiles_or_exit();
+    if let Err(e) = config.set_active(name).and_then(|_| config.save()) {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    }
+    println!("Active profile is now '{}'", name);
+    std::process::exit(0);
+}
+
+/// Register `openchime://` links with the desktop environment
+fn run_register_url_handler() -> ! {
+    match openchime::protocol::register_handler() {
+        Ok(message) => {
+            println!("{}", message);
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn load_profiles_or_exit() -> ProfilesConfig {
+    ProfilesConfig::load().unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    })
+}
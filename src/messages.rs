@@ -1,5 +1,11 @@
-use crate::models::{Account, CalendarEvent, Settings};
+use crate::models::{Account, AccountGroup, Attendee, CalendarEvent, ChecklistItem, EventId, Settings, SyncResult};
 use crate::alerts::MonitorEvent;
+use crate::calendar::caldav::DiscoveredCalendar;
+use crate::database::stats::MeetingStats;
+use crate::database::sync_history::AccountReliability;
+use crate::doctor::CheckResult;
+use crate::tray::TrayEvent;
+use crate::updates::UpdateInfo;
 
 /// Unified application message type
 /// 
@@ -14,6 +20,10 @@ pub enum Message {
     ShowSettings,
     /// Switch to alerts view
     ShowAlerts,
+    /// Switch to focus timer view
+    ShowFocus,
+    /// Switch to statistics view
+    ShowStats,
     
     // ===== UI Action Messages =====
     /// Toggle theme (Light/Dark)
@@ -27,19 +37,113 @@ pub enum Message {
     /// Stop any playing sound
     StopSound,
     /// Snooze an alert
-    SnoozeAlert(i64), // event_id
+    SnoozeAlert(EventId),
     /// Dismiss an alert
-    DismissAlert(i64), // event_id
+    DismissAlert(EventId),
+    /// Reset an account's circuit breaker and sync it now
+    RetrySync(i64),
+    /// Snooze every alert in the Alerts view for `SNOOZE_ALL_MINUTES`
+    SnoozeAll(Vec<EventId>),
+    /// Dismiss every alert in the Alerts view
+    DismissAll(Vec<EventId>),
+    /// Start renaming an event
+    EditEventTitle(EventId),
+    /// Stop renaming without saving
+    CancelEditEventTitle,
+    /// Save the new name (an empty name restores the feed's title)
+    SaveEventTitle,
+    /// Open the new reminder form
+    NewReminder,
+    /// Close the new reminder form without saving
+    CancelReminder,
+    /// Create the reminder from the form
+    SaveReminder,
+    /// Open the form for renaming an account or fixing its URL
+    EditAccount(i64),
+    /// Close the account form without saving
+    CancelEditAccount,
+    /// Save the account form
+    SaveAccountEdit,
+    /// Open an event's preparation checklist
+    OpenChecklist(EventId),
+    /// Close the open checklist
+    CloseChecklist,
+    /// Add the typed item to the open checklist
+    AddChecklistItem,
+    /// Tick or untick a checklist item
+    ToggleChecklistItem(i64, bool),
+    /// Remove a checklist item
+    RemoveChecklistItem(i64),
+    /// Show who's invited to an event
+    OpenAttendees(EventId),
+    /// Hide the attendee list
+    CloseAttendees,
+    /// Start a focus work session
+    FocusStart,
+    /// Pause the focus timer
+    FocusPause,
+    /// Resume the paused focus timer
+    FocusResume,
+    /// Stop the focus timer
+    FocusStop,
+    /// One-second focus timer tick
+    FocusTick,
+    /// Periodic tick that refreshes the title countdown and world clocks
+    ClockTick,
     
     // ===== Form Input Messages =====
     /// Update account name input field
     AccountNameChanged(String),
     /// Update ICS URL input field
     IcsUrlChanged(String),
+    /// Update the CalDAV username input field
+    CalDavUsernameChanged(String),
+    /// Update the CalDAV app password input field
+    CalDavPasswordChanged(String),
+    /// Update the request header input field
+    RequestHeaderChanged(String),
+    /// Add the typed request header to the new calendar
+    AddRequestHeader,
+    /// Drop a request header from the new calendar
+    RemoveRequestHeader(usize),
     /// Update auth data input field (token or URL) - kept for compatibility if needed
     AuthDataChanged(String),
     /// Update refresh token input field
     RefreshTokenChanged(String),
+    /// Update the event rename input field
+    EventTitleInputChanged(String),
+    /// Update the reminder title field
+    ReminderTitleChanged(String),
+    /// Update the reminder time field
+    ReminderTimeChanged(String),
+    /// Update the reminder link field
+    ReminderLinkChanged(String),
+    /// Update the account form's name field
+    EditAccountNameChanged(String),
+    /// Update the account form's URL field
+    EditAccountUrlChanged(String),
+    /// Update the world clock zones field
+    WorldClocksChanged(String),
+    /// Update the extra alert times field
+    CustomAlertsChanged(String),
+    /// Save the extra alert times
+    SaveCustomAlerts,
+    /// Update the user's email addresses field
+    MyAddressesChanged(String),
+    /// Save the user's email addresses
+    SaveMyAddresses,
+    /// Update the new checklist item field
+    ChecklistInputChanged(String),
+    /// Save the world clock zones
+    SaveWorldClocks,
+    /// Update the sound profiles field
+    SoundProfilesChanged(String),
+    /// Save the sound profiles
+    SaveSoundProfiles,
+    /// Update the proxy URL field
+    ProxyUrlChanged(String),
+    /// Save the proxy URL
+    SaveProxyUrl,
     /// Update alert timing preference (30m)
     ToggleAlert30m(bool),
     /// Update alert timing preference (10m)
@@ -50,26 +154,124 @@ pub enum Message {
     ToggleAlert1m(bool),
     /// Update alert timing preference (At start)
     ToggleAlertDefault(bool),
+    /// Also alert at the reminders set in the calendar app
+    ToggleCalendarReminders(bool),
+    /// Alert for events marked as free time
+    ToggleAlertFreeEvents(bool),
+    /// Never alert for all-day events
+    ToggleSkipAllDayEvents(bool),
+    /// Hold background syncs while no calendar server can be reached
+    ToggleHoldSyncsOffline(bool),
+    /// Enable or disable the background update check
+    ToggleUpdateCheck(bool),
+    /// Change how often calendars sync in the background (seconds)
+    SyncIntervalChanged(i32),
+    /// Change how many days of past events are imported
+    SyncDaysBackChanged(i32),
+    /// Change how many days of upcoming events are imported
+    SyncDaysAheadChanged(i32),
+    /// Change the focus work session length (minutes)
+    FocusWorkMinutesChanged(i32),
+    /// Change the focus break length (minutes)
+    FocusBreakMinutesChanged(i32),
+    /// Change how long before a meeting suspend is blocked (minutes; 0 is off)
+    KeepAwakeMinutesChanged(i32),
+    /// Change the largest calendar feed downloaded (MB)
+    MaxFeedSizeChanged(i32),
     
     // ===== Account Management Messages =====
     /// Request to add a new Proton/ICS account
     AddProtonAccount,
+    /// Link the account with a feed found on the pasted page
+    UseDiscoveredFeed(String),
+    /// Link the CalDAV calendar picked from those found on the server
+    UseDiscoveredCalendar(String),
+    /// Fetch the calendar in the add form and show what linking it would bring in
+    PreviewAccount,
+    /// Check the calendar in the add form step by step, without linking it
+    TestAccount,
     /// Request to delete an account
     DeleteAccount(i64),
     /// Request to sync an account manually
     SyncAccount(i64),
     /// Request to sync all accounts
     SyncCalendars,
+    /// Save the upcoming events to an ICS file
+    ExportEvents,
+    /// Update the Import ICS field
+    ImportInputChanged(String),
+    /// Import the events in the pasted ICS text or file
+    ImportIcs,
+    /// Update the new group name field
+    GroupNameChanged(String),
+    /// Create a group from the name field
+    AddGroup,
+    /// Switch one of a group's toggles
+    SetGroupToggle(i64, GroupToggle, bool),
+    /// Delete a group, leaving its accounts ungrouped
+    DeleteGroup(i64),
+    /// Move an account into a group, or out of any
+    AssignAccountGroup(i64, Option<i64>),
+    /// Mark an account as a shared team calendar, or as the user's own
+    SetAccountShared(i64, bool),
+    /// Mark an account as informational only: listed, never alerting
+    SetAccountInformational(i64, bool),
+    /// Turn an account's alerts off for a while, or back on
+    SetAccountAlertsEnabled(i64, bool),
+    /// Give an account its own sync interval in seconds, or `None` to follow
+    /// the global one
+    SetAccountSyncInterval(i64, Option<i32>),
+    /// Read an account's floating times in an IANA zone, or `None` to
+    /// follow the `floating_timezone` setting
+    SetAccountTimezone(i64, Option<String>),
     
     // ===== Async Operation Results =====
     /// Account addition completed
     AccountAdded(Result<Account, String>),
     /// Account deletion completed
     AccountDeleted(Result<(), String>),
+    /// Feeds found at a pasted URL that didn't look like one
+    FeedsDiscovered(String, Result<Vec<String>, String>),
+    /// Calendars found on a CalDAV server given by hostname
+    CalendarsDiscovered(String, Result<Vec<DiscoveredCalendar>, String>),
+    /// A sync preview of the URL in the add form finished
+    AccountPreviewed(String, Result<SyncResult, String>),
+    /// The steps of testing the calendar at a URL
+    AccountTested(String, Vec<CheckResult>),
+    /// A manual sync of one account finished
+    AccountSynced(i64, Result<SyncResult, String>),
+    /// Events were written to this file (with how many there were)
+    EventsExported(Result<(std::path::PathBuf, usize), String>),
+    /// An ICS import finished
+    IcsImported(Result<SyncResult, String>),
+    /// A change to an account's options was saved
+    AccountSaved(Result<(), String>),
     /// Calendar sync completed
     CalendarSyncResult(Result<(), String>),
     /// Audio test completed
     AudioTestResult(Result<(), String>),
+    /// Focus timer chime finished playing
+    FocusChimePlayed(Result<(), String>),
+    /// Event rename saved
+    EventTitleSaved(Result<(), String>),
+    /// "Snooze all" saved; carries how many alerts were snoozed
+    AlertsSnoozed(Result<usize, String>),
+    /// "Dismiss all" saved
+    AlertsDismissed(Result<(), String>),
+    /// Reminder created
+    ReminderAdded(Result<CalendarEvent, String>),
+    /// Account edit saved; carries the account as saved
+    AccountUpdated(Result<Account, String>),
+    /// Meeting statistics computed
+    StatsLoaded(Result<MeetingStats, String>),
+    /// Each account's sync record over the past week, for Stats
+    SyncReliabilityLoaded(Result<Vec<AccountReliability>, String>),
+    /// An event's checklist was loaded or changed
+    ChecklistLoaded(EventId, Result<Vec<ChecklistItem>, String>),
+    /// An event's attendees were loaded
+    AttendeesLoaded(EventId, Result<Vec<Attendee>, String>),
+    /// Update check completed (None when already up to date)
+    UpdateCheckResult(Result<Option<UpdateInfo>, String>),
     
     // ===== Data Update Messages =====
     /// Events data has been updated
@@ -78,8 +280,30 @@ pub enum Message {
     SettingsUpdated(Settings),
     /// Initial data loading completed
     DataLoaded(Vec<CalendarEvent>, Vec<Account>),
+    /// Accounts re-read after a sync, to show its errors
+    AccountsLoaded(Result<Vec<Account>, String>),
+    /// Account groups loaded or changed
+    GroupsLoaded(Result<Vec<AccountGroup>, String>),
     
     // ===== Monitor System Messages =====
     /// Background monitor event received
     MonitorEventReceived(MonitorEvent),
+
+    // ===== System Tray =====
+    /// The tray icon came up, or one of its items was clicked
+    Tray(TrayEvent),
+    /// The tray icon shows the latest countdown
+    TrayUpdated,
+    /// The window's close button was clicked
+    WindowCloseRequested,
+    /// A desktop notification was shown (or couldn't be)
+    NotificationShown,
+}
+
+/// A switch that applies to every account in a group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupToggle {
+    Sync,
+    Alerts,
+    Visible,
 }
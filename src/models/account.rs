@@ -4,10 +4,23 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::models::settings::{MAX_SYNC_INTERVAL_SECS, MIN_SYNC_INTERVAL_SECS};
+
+/// Name of the local account holding imported events, which tells it
+/// apart from the reminders account
+pub const IMPORTED_ACCOUNT_NAME: &str = "Imported";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum CalendarProvider {
     Google,
     Proton,
+    /// Any other ICS feed (Meetup, school, sports and holiday calendars)
+    Generic,
+    /// A CalDAV server, signed in with a username and app password
+    CalDav,
+    /// Built-in account holding reminders created in the app, or events
+    /// imported from ICS text (see `Account::new_imported`); never synced
+    Local,
 }
 
 impl CalendarProvider {
@@ -15,6 +28,9 @@ impl CalendarProvider {
         match self {
             CalendarProvider::Google => "google",
             CalendarProvider::Proton => "proton",
+            CalendarProvider::Generic => "generic",
+            CalendarProvider::CalDav => "caldav",
+            CalendarProvider::Local => "local",
         }
     }
 }
@@ -24,9 +40,111 @@ pub struct Account {
     pub id: Option<i64>,
     pub provider: String,
     pub account_name: String,
-    pub auth_data: String, // JSON: OAuth tokens for Google, ICS URL for Proton
+    pub auth_data: String, // ICS URL for Google and Proton, calendar collection URL for CalDAV
     pub refresh_token: Option<String>,
     pub last_synced_at: Option<DateTime<Utc>>,
+    /// See `AccountGroup`
+    #[sqlx(default)]
+    #[serde(default)]
+    pub group_id: Option<i64>,
+    /// A shared team calendar rather than the user's own: its events only
+    /// chime at the start time, are dimmed and don't count in statistics
+    #[sqlx(default)]
+    #[serde(default)]
+    pub is_shared: bool,
+    /// A holiday or birthday feed: its events are listed but never alert
+    #[sqlx(default)]
+    #[serde(default)]
+    pub is_informational: bool,
+    /// Whether the account's events alert; off mutes it for a while
+    /// without unlinking it
+    #[sqlx(default)]
+    #[serde(default = "default_alerts_enabled")]
+    pub alerts_enabled: bool,
+    /// HTTP Basic credentials for an ICS feed behind a login (Baikal, SOGo),
+    /// or the sign-in of a CalDAV account. The password is never read from
+    /// the database: `accounts::add` puts it in the keyring (see
+    /// `secret_key`).
+    #[sqlx(default)]
+    #[serde(default)]
+    pub username: Option<String>,
+    #[sqlx(skip)]
+    #[serde(skip)]
+    pub password: Option<String>,
+    /// Extra request headers for the feed (API tokens, cookies), one
+    /// `Name: value` per line. Like the password, only set on an account
+    /// that hasn't been saved yet.
+    #[sqlx(skip)]
+    #[serde(skip)]
+    pub request_headers: Option<String>,
+    /// How often to sync this account, in seconds; `None` follows the
+    /// global sync interval
+    #[sqlx(default)]
+    #[serde(default)]
+    pub sync_interval: Option<i32>,
+    /// Why the last sync failed, with URLs cut down to their host; cleared
+    /// by the next successful sync
+    #[sqlx(default)]
+    #[serde(default)]
+    pub last_sync_error: Option<String>,
+    #[sqlx(default)]
+    #[serde(default)]
+    pub last_sync_error_at: Option<DateTime<Utc>>,
+    /// IANA zone the feed's floating times are read in; `None` follows the
+    /// `floating_timezone` setting
+    #[sqlx(default)]
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// The name and `#rrggbb` color the feed gives its calendar, kept from
+    /// the last feed that stated them
+    #[sqlx(default)]
+    #[serde(default)]
+    pub calendar_name: Option<String>,
+    #[sqlx(default)]
+    #[serde(default)]
+    pub calendar_color: Option<String>,
+    /// Seconds between syncs the feed asks for (REFRESH-INTERVAL or
+    /// X-PUBLISHED-TTL); used when the account has no interval of its own
+    #[sqlx(default)]
+    #[serde(default)]
+    pub feed_refresh_interval: Option<i32>,
+    /// The keyring entry holding the account's secrets, once it's saved
+    #[sqlx(default)]
+    #[serde(default)]
+    pub secret_key: Option<String>,
+}
+
+/// What an account keeps in the keyring rather than the database, stored
+/// there as JSON
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccountSecrets {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_headers: Option<String>,
+}
+
+impl AccountSecrets {
+    pub fn is_empty(&self) -> bool {
+        self.password.is_none() && self.request_headers.is_none()
+    }
+}
+
+fn default_alerts_enabled() -> bool {
+    true
+}
+
+/// Accounts switched on and off together ("Work", "Personal")
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct AccountGroup {
+    pub id: i64,
+    pub name: String,
+    /// Whether the group's accounts are synced
+    pub sync_enabled: bool,
+    /// Whether the group's events chime
+    pub alerts_enabled: bool,
+    /// Whether the group's events show in the calendar
+    pub visible: bool,
 }
 
 impl Account {
@@ -42,6 +160,21 @@ impl Account {
             auth_data,
             refresh_token,
             last_synced_at: None,
+            group_id: None,
+            is_shared: false,
+            is_informational: false,
+            alerts_enabled: true,
+            username: None,
+            password: None,
+            request_headers: None,
+            sync_interval: None,
+            last_sync_error: None,
+            last_sync_error_at: None,
+            timezone: None,
+            calendar_name: None,
+            calendar_color: None,
+            feed_refresh_interval: None,
+            secret_key: None,
         }
     }
 
@@ -53,13 +186,150 @@ impl Account {
             auth_data: ics_url,
             refresh_token: None,
             last_synced_at: None,
+            group_id: None,
+            is_shared: false,
+            is_informational: false,
+            alerts_enabled: true,
+            username: None,
+            password: None,
+            request_headers: None,
+            sync_interval: None,
+            last_sync_error: None,
+            last_sync_error_at: None,
+            timezone: None,
+            calendar_name: None,
+            calendar_color: None,
+            feed_refresh_interval: None,
+            secret_key: None,
+        }
+    }
+
+    /// An ICS feed from anywhere else
+    pub fn new_generic(account_name: String, ics_url: String) -> Self {
+        Self {
+            provider: CalendarProvider::Generic.as_str().to_string(),
+            ..Self::new_proton(account_name, ics_url)
+        }
+    }
+
+    /// A CalDAV calendar collection, signed in to like a feed behind HTTP
+    /// Basic auth
+    pub fn new_caldav(account_name: String, calendar_url: String, credentials: &crate::calendar::caldav::CalDavCredentials) -> Self {
+        let account = Self {
+            id: None,
+            provider: CalendarProvider::CalDav.as_str().to_string(),
+            account_name,
+            auth_data: calendar_url,
+            refresh_token: None,
+            last_synced_at: None,
+            group_id: None,
+            is_shared: false,
+            is_informational: false,
+            alerts_enabled: true,
+            username: None,
+            password: None,
+            request_headers: None,
+            sync_interval: None,
+            last_sync_error: None,
+            last_sync_error_at: None,
+            timezone: None,
+            calendar_name: None,
+            calendar_color: None,
+            feed_refresh_interval: None,
+            secret_key: None,
+        };
+        account.with_basic_auth(credentials.username.clone(), credentials.password.clone())
+    }
+
+    /// The local account events imported from ICS text go into
+    pub fn new_imported() -> Self {
+        Self { account_name: IMPORTED_ACCOUNT_NAME.to_string(), ..Self::new_local() }
+    }
+
+    /// The built-in account for local reminders
+    pub fn new_local() -> Self {
+        Self {
+            id: None,
+            provider: CalendarProvider::Local.as_str().to_string(),
+            account_name: "Reminders".to_string(),
+            auth_data: String::new(),
+            refresh_token: None,
+            last_synced_at: None,
+            group_id: None,
+            is_shared: false,
+            is_informational: false,
+            alerts_enabled: true,
+            username: None,
+            password: None,
+            request_headers: None,
+            sync_interval: None,
+            last_sync_error: None,
+            last_sync_error_at: None,
+            timezone: None,
+            calendar_name: None,
+            calendar_color: None,
+            feed_refresh_interval: None,
+            secret_key: None,
+        }
+    }
+
+    /// The account's secrets: from the keyring once it's saved, else the
+    /// ones it was built with
+    pub async fn secrets(&self) -> anyhow::Result<AccountSecrets> {
+        use anyhow::Context;
+        let Some(key) = &self.secret_key else {
+            return Ok(AccountSecrets { password: self.password.clone(), request_headers: self.request_headers.clone() });
+        };
+        let stored = crate::keyring::load(key)
+            .await
+            .with_context(|| format!("The password for {} isn't in the keyring; add the calendar again", self.account_name))?;
+        serde_json::from_str(&stored).with_context(|| format!("Unreadable keyring entry for {}", self.account_name))
+    }
+
+    /// Send these HTTP Basic credentials with every feed request
+    pub fn with_basic_auth(mut self, username: String, password: String) -> Self {
+        self.username = Some(username);
+        self.password = Some(password);
+        self
+    }
+
+    /// Send these headers with every feed request, one `Name: value` per line
+    pub fn with_request_headers(mut self, headers: String) -> Self {
+        self.request_headers = Some(headers).filter(|h| !h.trim().is_empty());
+        self
+    }
+
+    /// How long to wait between syncs of this account, given the global
+    /// interval: its own, else the one its feed asks for (never more often
+    /// than the global one), else the global one
+    pub fn sync_interval_duration(&self, default: chrono::Duration) -> chrono::Duration {
+        let clamped = |secs: i32| chrono::Duration::seconds(secs.clamp(MIN_SYNC_INTERVAL_SECS, MAX_SYNC_INTERVAL_SECS) as i64);
+        match (self.sync_interval, self.feed_refresh_interval) {
+            (Some(secs), _) => clamped(secs),
+            (None, Some(secs)) => clamped(secs).max(default),
+            (None, None) => default,
         }
     }
 
+    /// Whether this is the built-in reminders account rather than a calendar feed
+    pub fn is_local(&self) -> bool {
+        self.provider == CalendarProvider::Local.as_str()
+    }
+
+    /// The feed's calendar color as red, green and blue
+    pub fn calendar_rgb(&self) -> Option<[u8; 3]> {
+        let hex = self.calendar_color.as_deref()?.strip_prefix('#')?;
+        let channel = |i: usize| hex.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok());
+        Some([channel(0)?, channel(2)?, channel(4)?])
+    }
+
     pub fn provider(&self) -> Result<CalendarProvider, String> {
         match self.provider.as_str() {
             "google" => Ok(CalendarProvider::Google),
             "proton" => Ok(CalendarProvider::Proton),
+            "generic" => Ok(CalendarProvider::Generic),
+            "caldav" => Ok(CalendarProvider::CalDav),
+            "local" => Ok(CalendarProvider::Local),
             _ => Err(format!("Unknown provider: {}", self.provider)),
         }
     }
@@ -76,6 +346,27 @@ mod tests {
     fn test_calendar_provider_as_str() {
         assert_eq!(CalendarProvider::Google.as_str(), "google");
         assert_eq!(CalendarProvider::Proton.as_str(), "proton");
+        assert_eq!(CalendarProvider::Generic.as_str(), "generic");
+        assert_eq!(CalendarProvider::CalDav.as_str(), "caldav");
+        assert_eq!(CalendarProvider::Local.as_str(), "local");
+    }
+
+    #[test]
+    fn test_account_new_local() {
+        let account = Account::new_local();
+        assert!(account.is_local());
+        assert!(matches!(account.provider().unwrap(), CalendarProvider::Local));
+        assert!(!Account::new_proton("Work".to_string(), "https://example.com/cal.ics".to_string()).is_local());
+    }
+
+    #[test]
+    fn test_calendar_rgb() {
+        let mut account = Account::new_generic("Team".to_string(), "https://example.com/cal.ics".to_string());
+        assert_eq!(account.calendar_rgb(), None);
+        account.calendar_color = Some("#1badf8".to_string());
+        assert_eq!(account.calendar_rgb(), Some([0x1b, 0xad, 0xf8]));
+        account.calendar_color = Some("teal".to_string());
+        assert_eq!(account.calendar_rgb(), None);
     }
 
     #[test]
@@ -120,5 +411,33 @@ mod tests {
             proton_account.provider().unwrap(),
             CalendarProvider::Proton
         ));
+        let meetup = Account::new_generic("Meetup".to_string(), "https://www.meetup.com/events/ical/123/abc/going".to_string());
+        assert_eq!(meetup.provider, "generic");
+        assert!(matches!(meetup.provider().unwrap(), CalendarProvider::Generic));
+    }
+
+    #[test]
+    fn test_account_sync_interval_duration() {
+        let default = chrono::Duration::minutes(5);
+        let mut account = Account::new_proton("Holidays".to_string(), "https://example.com/holidays.ics".to_string());
+        assert_eq!(account.sync_interval_duration(default), default);
+
+        account.sync_interval = Some(3600);
+        assert_eq!(account.sync_interval_duration(default), chrono::Duration::hours(1));
+        // Kept within the same bounds as the global interval
+        account.sync_interval = Some(1);
+        assert_eq!(account.sync_interval_duration(default), chrono::Duration::seconds(MIN_SYNC_INTERVAL_SECS as i64));
+
+        // The feed's own refresh interval stands in for the global one
+        account.sync_interval = None;
+        account.feed_refresh_interval = Some(4 * 3600);
+        assert_eq!(account.sync_interval_duration(default), chrono::Duration::hours(4));
+        account.feed_refresh_interval = Some(7 * 86_400);
+        assert_eq!(account.sync_interval_duration(default), chrono::Duration::seconds(MAX_SYNC_INTERVAL_SECS as i64));
+        // but a feed asking for more often than that doesn't get it
+        account.feed_refresh_interval = Some(60);
+        assert_eq!(account.sync_interval_duration(default), default);
+        account.sync_interval = Some(900);
+        assert_eq!(account.sync_interval_duration(default), chrono::Duration::minutes(15));
     }
 }
@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 // file: src/alert.rs
 use super::event::CalendarEvent;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,11 +21,30 @@ pub enum AlertType {
     Warning10m,
     Warning5m,
     Warning1m,
+    /// A custom threshold (see `Settings::custom_alert_minutes`), which has
+    /// no spoken warning of its own
+    Warning,
+    /// Focus timer: a work session ended, take a break
+    FocusBreak,
+    /// Focus timer: the break is over
+    FocusWork,
+}
+
+impl AlertType {
+    /// The alert at the meeting's start, rather than a warning ahead of it
+    pub fn is_start(&self) -> bool {
+        matches!(self, AlertType::VideoMeeting | AlertType::Meeting)
+    }
 }
 
 impl AlertInfo {
     pub fn new(event: CalendarEvent) -> Self {
-        let minutes_remaining = event.minutes_until_start();
+        Self::new_at(event, Utc::now())
+    }
+
+    /// Build alert info relative to a given time
+    pub fn new_at(event: CalendarEvent, now: DateTime<Utc>) -> Self {
+        let minutes_remaining = event.minutes_until_start_at(now);
         let alert_type = if event.is_video_meeting() {
             AlertType::VideoMeeting
         } else {
@@ -48,30 +68,26 @@ mod tests {
     fn test_alert_info_new() {
         let now = Utc::now();
         let video_event = CalendarEvent {
-            id: None,
-            external_id: "test-5".to_string(),
-            account_id: 1,
+            external_id: "test-5".into(),
             title: "Video Call".to_string(),
-            description: None,
-            start_time: now + Duration::minutes(5),
             end_time: now + Duration::hours(1),
             video_link: Some("https://meet.google.com/abc-def".to_string()),
             video_platform: Some("Google Meet".to_string()),
-            snooze_count: 0,
-            has_alerted: false,
-            last_alert_threshold: None,
-            is_dismissed: false,
-            created_at: now,
-            updated_at: now,
+            ..CalendarEvent::test_at(now + Duration::minutes(5))
         };
 
-        let alert_info = AlertInfo::new(video_event.clone());
+        let alert_info = AlertInfo::new_at(video_event.clone(), now);
         assert!(matches!(alert_info.alert_type, AlertType::VideoMeeting));
-        let minutes = alert_info.minutes_remaining;
-        assert!(
-            minutes >= 4 && minutes <= 6,
-            "Expected ~5 minutes, got {}",
-            minutes
-        );
+        assert_eq!(alert_info.minutes_remaining, 5);
+    }
+
+    #[test]
+    fn test_only_meeting_alerts_are_at_the_start() {
+        assert!(AlertType::Meeting.is_start());
+        assert!(AlertType::VideoMeeting.is_start());
+        assert!(!AlertType::Warning30m.is_start());
+        assert!(!AlertType::Warning1m.is_start());
+        assert!(!AlertType::Warning.is_start());
+        assert!(!AlertType::SnoozeReminder.is_start());
     }
 }
@@ -0,0 +1,66 @@
+// file: src/attendee.rs
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// An attendee's reply to the invitation (the feed's PARTSTAT)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT")]
+pub enum Rsvp {
+    #[default]
+    #[sqlx(rename = "NEEDS-ACTION")]
+    NeedsAction,
+    #[sqlx(rename = "ACCEPTED")]
+    Accepted,
+    #[sqlx(rename = "TENTATIVE")]
+    Tentative,
+    #[sqlx(rename = "DECLINED")]
+    Declined,
+    #[sqlx(rename = "DELEGATED")]
+    Delegated,
+}
+
+impl Rsvp {
+    /// Read a PARTSTAT value; anything unknown counts as not yet answered
+    pub fn from_partstat(partstat: &str) -> Self {
+        match partstat.trim().to_ascii_uppercase().as_str() {
+            "ACCEPTED" => Self::Accepted,
+            "TENTATIVE" => Self::Tentative,
+            "DECLINED" => Self::Declined,
+            "DELEGATED" => Self::Delegated,
+            _ => Self::NeedsAction,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::NeedsAction => "no reply",
+            Self::Accepted => "accepted",
+            Self::Tentative => "maybe",
+            Self::Declined => "declined",
+            Self::Delegated => "delegated",
+        }
+    }
+}
+
+/// Someone invited to an event, or its organizer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct Attendee {
+    /// Lowercased address from the `mailto:` value
+    pub email: String,
+    /// Display name (the CN parameter)
+    pub name: Option<String>,
+    pub rsvp: Rsvp,
+    pub is_organizer: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rsvp_from_partstat() {
+        assert_eq!(Rsvp::from_partstat("ACCEPTED"), Rsvp::Accepted);
+        assert_eq!(Rsvp::from_partstat("declined"), Rsvp::Declined);
+        assert_eq!(Rsvp::from_partstat("X-UNKNOWN"), Rsvp::NeedsAction);
+    }
+}
@@ -0,0 +1,14 @@
+// file: src/checklist.rs
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::ids::EventId;
+
+/// Something to prepare before a meeting
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct ChecklistItem {
+    pub id: i64,
+    pub event_id: EventId,
+    pub text: String,
+    pub is_done: bool,
+}
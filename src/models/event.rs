@@ -1,12 +1,19 @@
 // file: src/event.rs
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use super::ids::{EventId, ExternalEventId};
+use super::meeting::{DialIn, VideoMeetingInfo};
+
+/// Calendar-app reminders further ahead of the start than this (a day) are
+/// ignored
+pub const MAX_CALENDAR_REMINDER_MINUTES: i32 = 24 * 60;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct CalendarEvent {
-    pub id: Option<i64>,
-    pub external_id: String,
+    pub id: Option<EventId>,
+    pub external_id: ExternalEventId,
     pub account_id: i64,
     pub title: String,
     pub description: Option<String>,
@@ -18,6 +25,55 @@ pub struct CalendarEvent {
     pub has_alerted: bool,
     pub last_alert_threshold: Option<i32>,
     pub is_dismissed: bool,
+    /// Set while a snooze is pending; cleared once the event alerts again
+    #[sqlx(default)]
+    #[serde(default)]
+    pub last_snoozed_at: Option<DateTime<Utc>>,
+    /// A DATE (not DATE-TIME) event. Its start and end are stored as UTC
+    /// midnight of the calendar dates so they read the same in every timezone.
+    #[sqlx(default)]
+    #[serde(default)]
+    pub is_all_day: bool,
+    /// Join details from the invite; see `video_meeting` and `dial_in`
+    #[sqlx(default)]
+    #[serde(default)]
+    pub meeting_id: Option<String>,
+    #[sqlx(default)]
+    #[serde(default)]
+    pub meeting_passcode: Option<String>,
+    #[sqlx(default)]
+    #[serde(default)]
+    pub dial_in_number: Option<String>,
+    #[sqlx(default)]
+    #[serde(default)]
+    pub dial_in_conference_id: Option<String>,
+    /// The user's local name for the event; see `display_title`
+    #[sqlx(default)]
+    #[serde(default)]
+    pub title_override: Option<String>,
+    /// IANA zone the feed gave the start time in; see `origin_start`
+    #[sqlx(default)]
+    #[serde(default)]
+    pub source_timezone: Option<String>,
+    /// When a snooze of its own length runs out; a pending snooze without
+    /// one lasts `snooze_interval`
+    #[sqlx(default)]
+    #[serde(default)]
+    pub snoozed_until: Option<DateTime<Utc>>,
+    /// Where the meeting is held (the feed's LOCATION)
+    #[sqlx(default)]
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Reminders set in the calendar app (VALARMs), as minutes before the
+    /// start, largest first and comma-separated; see `calendar_reminders`
+    #[sqlx(default)]
+    #[serde(default)]
+    pub reminder_minutes: Option<String>,
+    /// Marked as free time rather than busy (TRANSP:TRANSPARENT); these
+    /// don't alert unless `Settings::alert_free_events` is on
+    #[sqlx(default)]
+    #[serde(default)]
+    pub is_free: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -27,8 +83,106 @@ impl CalendarEvent {
         self.video_link.is_some()
     }
 
+    /// The title to show: the user's override if set, otherwise the feed's
+    pub fn display_title(&self) -> &str {
+        self.title_override.as_deref().unwrap_or(&self.title)
+    }
+
+    /// Minutes before the start of the reminders set in the calendar app,
+    /// largest first
+    pub fn calendar_reminders(&self) -> Vec<i32> {
+        self.reminder_minutes
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|minutes| minutes.trim().parse().ok())
+            .filter(|minutes| (0..=MAX_CALENDAR_REMINDER_MINUTES).contains(minutes))
+            .collect()
+    }
+
+    /// The location to show; nothing when it only repeats the video link
+    pub fn display_location(&self) -> Option<&str> {
+        let location = self.location.as_deref()?.trim();
+        let rest = match &self.video_link {
+            Some(link) => location.replace(link.as_str(), ""),
+            None => location.to_string(),
+        };
+        rest.chars().any(char::is_alphanumeric).then_some(location)
+    }
+
+    /// Everything known about joining this meeting online
+    pub fn video_meeting(&self) -> Option<VideoMeetingInfo> {
+        Some(VideoMeetingInfo {
+            platform: self.video_platform.clone().unwrap_or_else(|| "Meeting".to_string()),
+            url: self.video_link.clone()?,
+            meeting_id: self.meeting_id.clone(),
+            password: self.meeting_passcode.clone(),
+            dial_in: self.dial_in(),
+        })
+    }
+
+    /// The number to join this meeting by phone
+    pub fn dial_in(&self) -> Option<DialIn> {
+        Some(DialIn {
+            number: self.dial_in_number.clone()?,
+            conference_id: self.dial_in_conference_id.clone(),
+        })
+    }
+
+    /// The calendar date of an all-day event
+    pub fn all_day_date(&self) -> Option<NaiveDate> {
+        self.is_all_day.then(|| self.start_time.date_naive())
+    }
+
+    /// The local date this event is listed under
+    pub fn local_date(&self) -> NaiveDate {
+        self.all_day_date()
+            .unwrap_or_else(|| self.start_time.with_timezone(&Local).date_naive())
+    }
+
+    /// The instant the event starts for a viewer in `tz`: all-day events
+    /// begin at that viewer's midnight, everything else at `start_time`
+    pub fn effective_start_in<Tz: TimeZone>(&self, tz: &Tz) -> DateTime<Utc> {
+        match self.all_day_date() {
+            Some(date) => tz
+                .from_local_datetime(&date.and_time(chrono::NaiveTime::MIN))
+                .earliest()
+                .map(|start| start.with_timezone(&Utc))
+                .unwrap_or(self.start_time),
+            None => self.start_time,
+        }
+    }
+
+    /// `effective_start_in` for the system timezone
+    pub fn effective_start(&self) -> DateTime<Utc> {
+        self.effective_start_in(&Local)
+    }
+
+    /// The start time on the organizer's clock, when the feed's timezone
+    /// reads differently from `tz` at that moment (a New York standup seen
+    /// from London). `None` for floating, UTC and all-day events.
+    pub fn origin_start_in<Tz: TimeZone>(&self, tz: &Tz) -> Option<DateTime<chrono_tz::Tz>> {
+        use chrono::Offset;
+
+        if self.is_all_day {
+            return None;
+        }
+        let origin: chrono_tz::Tz = self.source_timezone.as_deref()?.parse().ok()?;
+        let there = self.start_time.with_timezone(&origin);
+        let here = self.start_time.with_timezone(tz);
+        (there.offset().fix() != here.offset().fix()).then_some(there)
+    }
+
+    /// `origin_start_in` for the system timezone
+    pub fn origin_start(&self) -> Option<DateTime<chrono_tz::Tz>> {
+        self.origin_start_in(&Local)
+    }
+
     pub fn minutes_until_start(&self) -> i64 {
-        let now = Utc::now();
+        self.minutes_until_start_at(Utc::now())
+    }
+
+    pub fn minutes_until_start_at(&self, now: DateTime<Utc>) -> i64 {
         (self.start_time - now).num_minutes()
     }
 
@@ -40,6 +194,171 @@ impl CalendarEvent {
         let now = Utc::now();
         now >= self.start_time && now <= self.end_time
     }
+
+    /// Whether the fields a sync writes match `other`'s; the same comparison
+    /// `store_event` makes before updating a row
+    pub fn same_synced_fields(&self, other: &CalendarEvent) -> bool {
+        self.title == other.title
+            && self.description == other.description
+            && self.start_time == other.start_time
+            && self.end_time == other.end_time
+            && self.video_link == other.video_link
+            && self.video_platform == other.video_platform
+            && self.is_all_day == other.is_all_day
+            && self.meeting_id == other.meeting_id
+            && self.meeting_passcode == other.meeting_passcode
+            && self.dial_in_number == other.dial_in_number
+            && self.dial_in_conference_id == other.dial_in_conference_id
+            && self.source_timezone == other.source_timezone
+            && self.location == other.location
+            && self.reminder_minutes == other.reminder_minutes
+            && self.is_free == other.is_free
+    }
+}
+
+#[cfg(test)]
+impl CalendarEvent {
+    /// A plain hour-long busy event starting at `start`, with no alert state,
+    /// for tests to fill in the rest with `..CalendarEvent::test_at(start)`
+    pub fn test_at(start: DateTime<Utc>) -> Self {
+        CalendarEvent {
+            id: None,
+            external_id: "test".into(),
+            account_id: 1,
+            title: "Meeting".to_string(),
+            description: None,
+            start_time: start,
+            end_time: start + chrono::Duration::hours(1),
+            video_link: None,
+            video_platform: None,
+            snooze_count: 0,
+            has_alerted: false,
+            last_alert_threshold: None,
+            is_dismissed: false,
+            last_snoozed_at: None,
+            is_all_day: false,
+            meeting_id: None,
+            meeting_passcode: None,
+            dial_in_number: None,
+            dial_in_conference_id: None,
+            title_override: None,
+            source_timezone: None,
+            snoozed_until: None,
+            location: None,
+            reminder_minutes: None,
+            is_free: false,
+            created_at: start,
+            updated_at: start,
+        }
+    }
+}
+
+/// How far apart, in minutes, the starts of same-titled events in different
+/// accounts can be for them to count as one meeting
+pub const DUPLICATE_START_TOLERANCE_MINUTES: i64 = 2;
+
+/// One meeting as the user sees it: rows from one or more accounts that
+/// share a UID and start time, or a title and (nearly) the same start
+#[derive(Debug, Clone)]
+pub struct MergedEvent {
+    /// The first row seen, carrying the combined alert state of all of them
+    pub event: CalendarEvent,
+    /// Every row that makes up this meeting
+    pub ids: Vec<EventId>,
+    /// Accounts the meeting was synced from
+    pub account_ids: Vec<i64>,
+}
+
+impl CalendarEvent {
+    /// Whether `other` is this meeting's copy in another calendar (or this event).
+    ///
+    /// Copies share a UID and start time. Providers that re-issue invites
+    /// under their own UIDs are caught by title instead: the same title, ignoring
+    /// case, in a different account, starting within
+    /// `DUPLICATE_START_TOLERANCE_MINUTES`.
+    pub fn is_same_meeting(&self, other: &CalendarEvent) -> bool {
+        let same_uid = self.external_id == other.external_id && self.start_time == other.start_time;
+        let same_title = self.account_id != other.account_id
+            && self.title.trim().eq_ignore_ascii_case(other.title.trim())
+            && (self.start_time - other.start_time).num_seconds().abs() <= DUPLICATE_START_TOLERANCE_MINUTES * 60;
+        same_uid || same_title
+    }
+}
+
+/// Collapse events that appear in several calendars into one entry each,
+/// keeping the order of first appearance.
+///
+/// Alert state is combined so the meeting chimes once: the most advanced
+/// threshold wins, the latest pending snooze wins, and dismissing any copy
+/// dismisses the meeting.
+pub fn merge_duplicates(events: &[CalendarEvent]) -> Vec<MergedEvent> {
+    let mut merged: Vec<MergedEvent> = Vec::new();
+    for event in events {
+        let existing = merged
+            .iter_mut()
+            .find(|m| m.event.is_same_meeting(event));
+        match existing {
+            Some(m) => {
+                m.ids.extend(event.id);
+                if !m.account_ids.contains(&event.account_id) {
+                    m.account_ids.push(event.account_id);
+                }
+                let primary = &mut m.event;
+                primary.last_alert_threshold = match (primary.last_alert_threshold, event.last_alert_threshold) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+                // A snooze's length goes with it; the copy snoozed last has both
+                if event.last_snoozed_at > primary.last_snoozed_at {
+                    primary.last_snoozed_at = event.last_snoozed_at;
+                    primary.snoozed_until = event.snoozed_until;
+                }
+                primary.is_dismissed |= event.is_dismissed;
+                if primary.video_link.is_none() {
+                    primary.video_link = event.video_link.clone();
+                    primary.video_platform = event.video_platform.clone();
+                    primary.meeting_id = event.meeting_id.clone();
+                }
+                if primary.meeting_passcode.is_none() {
+                    primary.meeting_passcode = event.meeting_passcode.clone();
+                }
+                if primary.dial_in_number.is_none() {
+                    primary.dial_in_number = event.dial_in_number.clone();
+                    primary.dial_in_conference_id = event.dial_in_conference_id.clone();
+                }
+                if primary.title_override.is_none() {
+                    primary.title_override = event.title_override.clone();
+                }
+            }
+            None => merged.push(MergedEvent {
+                event: event.clone(),
+                ids: event.id.into_iter().collect(),
+                account_ids: vec![event.account_id],
+            }),
+        }
+    }
+    merged
+}
+
+/// The earliest timed meeting that hasn't started yet at `now`, counting
+/// one that started within the last minute
+pub fn next_meeting(events: &[CalendarEvent], now: DateTime<Utc>) -> Option<&CalendarEvent> {
+    events
+        .iter()
+        .filter(|e| !e.is_all_day && !e.is_dismissed && e.start_time > now - chrono::Duration::minutes(1))
+        .min_by_key(|e| e.start_time)
+}
+
+/// Timed meetings on `now`'s date in `tz` that haven't ended yet, counting
+/// a meeting found in several calendars once
+pub fn remaining_today<Tz: TimeZone>(events: &[CalendarEvent], now: DateTime<Utc>, tz: &Tz) -> usize {
+    let today = now.with_timezone(tz).date_naive();
+    merge_duplicates(events)
+        .iter()
+        .map(|merged| &merged.event)
+        .filter(|e| !e.is_all_day && !e.is_dismissed && e.end_time > now)
+        .filter(|e| e.start_time.with_timezone(tz).date_naive() == today)
+        .count()
 }
 
 #[cfg(test)]
@@ -50,21 +369,11 @@ mod tests {
     #[test]
     fn test_calendar_event_is_video_meeting() {
         let event_with_video = CalendarEvent {
-            id: None,
-            external_id: "test-1".to_string(),
-            account_id: 1,
+            external_id: "test-1".into(),
             title: "Video Meeting".to_string(),
-            description: None,
-            start_time: Utc::now(),
-            end_time: Utc::now() + Duration::hours(1),
             video_link: Some("https://zoom.us/j/123456".to_string()),
             video_platform: Some("Zoom".to_string()),
-            snooze_count: 0,
-            has_alerted: false,
-            last_alert_threshold: None,
-            is_dismissed: false,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
+            ..CalendarEvent::test_at(Utc::now())
         };
 
         let event_without_video = CalendarEvent {
@@ -80,21 +389,10 @@ mod tests {
     fn test_calendar_event_minutes_until_start() {
         let now = Utc::now();
         let future_event = CalendarEvent {
-            id: None,
-            external_id: "test-2".to_string(),
-            account_id: 1,
+            external_id: "test-2".into(),
             title: "Future Meeting".to_string(),
-            description: None,
-            start_time: now + Duration::minutes(30),
             end_time: now + Duration::minutes(90),
-            video_link: None,
-            video_platform: None,
-            snooze_count: 0,
-            has_alerted: false,
-            last_alert_threshold: None,
-            is_dismissed: false,
-            created_at: now,
-            updated_at: now,
+            ..CalendarEvent::test_at(now + Duration::minutes(30))
         };
 
         let minutes = future_event.minutes_until_start();
@@ -109,21 +407,12 @@ mod tests {
     fn test_calendar_event_is_past() {
         let now = Utc::now();
         let past_event = CalendarEvent {
-            id: None,
-            external_id: "test-3".to_string(),
-            account_id: 1,
+            external_id: "test-3".into(),
             title: "Past Meeting".to_string(),
-            description: None,
-            start_time: now - Duration::hours(1),
             end_time: now - Duration::minutes(30),
-            video_link: None,
-            video_platform: None,
-            snooze_count: 0,
-            has_alerted: false,
-            last_alert_threshold: None,
-            is_dismissed: false,
             created_at: now - Duration::hours(2),
             updated_at: now - Duration::hours(2),
+            ..CalendarEvent::test_at(now - Duration::hours(1))
         };
 
         assert!(past_event.is_past());
@@ -133,23 +422,238 @@ mod tests {
     fn test_calendar_event_is_happening_now() {
         let now = Utc::now();
         let ongoing_event = CalendarEvent {
-            id: None,
-            external_id: "test-4".to_string(),
-            account_id: 1,
+            external_id: "test-4".into(),
             title: "Ongoing Meeting".to_string(),
-            description: None,
-            start_time: now - Duration::minutes(15),
             end_time: now + Duration::minutes(45),
-            video_link: None,
-            video_platform: None,
-            snooze_count: 0,
-            has_alerted: false,
-            last_alert_threshold: None,
-            is_dismissed: false,
             created_at: now - Duration::hours(1),
             updated_at: now - Duration::hours(1),
+            ..CalendarEvent::test_at(now - Duration::minutes(15))
         };
 
         assert!(ongoing_event.is_happening_now());
     }
+
+    #[test]
+    fn test_all_day_event_dates() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let midnight_utc = date.and_time(chrono::NaiveTime::MIN).and_utc();
+        let event = CalendarEvent {
+            external_id: "birthday".into(),
+            title: "Birthday".to_string(),
+            end_time: midnight_utc + Duration::days(1),
+            is_all_day: true,
+            ..CalendarEvent::test_at(midnight_utc)
+        };
+
+        // The date never shifts, whatever the viewer's timezone
+        assert_eq!(event.all_day_date(), Some(date));
+        assert_eq!(event.local_date(), date);
+
+        // It starts at each viewer's own midnight
+        let tokyo = event.effective_start_in(&chrono_tz::Asia::Tokyo);
+        assert_eq!(tokyo, Utc.with_ymd_and_hms(2024, 3, 14, 15, 0, 0).unwrap());
+        let new_york = event.effective_start_in(&chrono_tz::America::New_York);
+        assert_eq!(new_york, Utc.with_ymd_and_hms(2024, 3, 15, 4, 0, 0).unwrap());
+
+        let timed = CalendarEvent { is_all_day: false, ..event };
+        assert_eq!(timed.all_day_date(), None);
+        assert_eq!(timed.effective_start_in(&chrono_tz::Asia::Tokyo), midnight_utc);
+    }
+
+    #[test]
+    fn test_origin_start_only_when_clocks_differ() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 15, 13, 0, 0).unwrap();
+        let event = CalendarEvent {
+            external_id: "standup".into(),
+            title: "Standup".to_string(),
+            end_time: start + Duration::minutes(15),
+            source_timezone: Some("America/New_York".to_string()),
+            ..CalendarEvent::test_at(start)
+        };
+
+        let origin = event.origin_start_in(&chrono_tz::Europe::London).unwrap();
+        assert_eq!(origin.format("%H:%M %Z").to_string(), "09:00 EDT");
+
+        // Same clock, different zone name: nothing to point out
+        assert!(event.origin_start_in(&chrono_tz::America::Toronto).is_none());
+
+        let floating = CalendarEvent { source_timezone: None, ..event.clone() };
+        assert!(floating.origin_start_in(&chrono_tz::Europe::London).is_none());
+        let all_day = CalendarEvent { is_all_day: true, ..event };
+        assert!(all_day.origin_start_in(&chrono_tz::Europe::London).is_none());
+    }
+
+    #[test]
+    fn test_display_location() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 15, 13, 0, 0).unwrap();
+        let mut event = CalendarEvent {
+            external_id: "review".into(),
+            title: "Design review".to_string(),
+            end_time: start + Duration::minutes(30),
+            ..CalendarEvent::test_at(start)
+        };
+        assert_eq!(event.display_location(), None);
+
+        event.location = Some(" Room 4, Building B ".to_string());
+        assert_eq!(event.display_location(), Some("Room 4, Building B"));
+
+        // A location that's just the meeting link adds nothing
+        event.video_link = Some("https://zoom.us/j/123456789".to_string());
+        event.location = Some("https://zoom.us/j/123456789".to_string());
+        assert_eq!(event.display_location(), None);
+        event.location = Some("Room 4; https://zoom.us/j/123456789".to_string());
+        assert_eq!(event.display_location(), Some("Room 4; https://zoom.us/j/123456789"));
+    }
+
+    #[test]
+    fn test_next_meeting() {
+        let now = Utc::now();
+        let at = |title: &str, minutes: i64| CalendarEvent {
+            external_id: title.into(),
+            title: title.to_string(),
+            end_time: now + Duration::minutes(minutes + 30),
+            ..CalendarEvent::test_at(now + Duration::minutes(minutes))
+        };
+        let events = vec![
+            at("Later", 90),
+            at("Started", -10),
+            CalendarEvent { is_all_day: true, ..at("Holiday", 5) },
+            CalendarEvent { is_dismissed: true, ..at("Skipped", 10) },
+            at("Standup", 20),
+        ];
+
+        assert_eq!(next_meeting(&events, now).unwrap().title, "Standup");
+        assert_eq!(next_meeting(&events, now + Duration::minutes(20)).unwrap().title, "Standup");
+        assert_eq!(next_meeting(&events, now + Duration::minutes(22)).unwrap().title, "Later");
+        assert!(next_meeting(&events, now + Duration::hours(2)).is_none());
+    }
+
+    #[test]
+    fn test_remaining_today() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 4, 15, 0, 0).unwrap();
+        let at = |uid: &str, hour: u32, account_id: i64| CalendarEvent {
+            external_id: uid.into(),
+            account_id,
+            title: uid.to_string(),
+            end_time: Utc.with_ymd_and_hms(2024, 3, 4, hour, 30, 0).unwrap(),
+            ..CalendarEvent::test_at(Utc.with_ymd_and_hms(2024, 3, 4, hour, 0, 0).unwrap())
+        };
+        let events = vec![
+            at("done", 9, 1),
+            CalendarEvent { end_time: now + Duration::minutes(10), ..at("ongoing", 14, 1) },
+            at("review", 16, 1),
+            at("review", 16, 2), // the same meeting in another calendar
+            CalendarEvent { is_dismissed: true, ..at("skipped", 17, 1) },
+            CalendarEvent { is_all_day: true, ..at("holiday", 0, 1) },
+            at("late", 23, 1),
+        ];
+
+        assert_eq!(remaining_today(&events, now, &Utc), 3);
+        // It's already the 5th in Tokyo, where "ongoing" started yesterday
+        assert_eq!(remaining_today(&events, now, &chrono_tz::Asia::Tokyo), 2);
+    }
+
+    #[test]
+    fn test_merge_duplicates_across_accounts() {
+        let now = Utc::now();
+        let base = CalendarEvent {
+            id: Some(EventId(1)),
+            external_id: "weekly@example.com".into(),
+            title: "Weekly Sync".to_string(),
+            last_alert_threshold: Some(5),
+            ..CalendarEvent::test_at(now)
+        };
+        let copy = CalendarEvent {
+            id: Some(EventId(2)),
+            account_id: 2,
+            video_link: Some("https://meet.google.com/abc-defg-hij".to_string()),
+            video_platform: Some("Google Meet".to_string()),
+            last_alert_threshold: Some(1),
+            title_override: Some("Sync w/ Alex".to_string()),
+            source_timezone: None,
+            snoozed_until: None,
+            location: None,
+            reminder_minutes: None,
+            is_free: false,
+            ..base.clone()
+        };
+        // Same UID but moved: a different occurrence, not a duplicate
+        let other_time = CalendarEvent {
+            id: Some(EventId(3)),
+            start_time: now + Duration::days(7),
+            ..base.clone()
+        };
+
+        let merged = merge_duplicates(&[base, copy, other_time]);
+        assert_eq!(merged.len(), 2);
+
+        let meeting = &merged[0];
+        assert_eq!(meeting.ids, vec![EventId(1), EventId(2)]);
+        assert_eq!(meeting.account_ids, vec![1, 2]);
+        assert_eq!(meeting.event.last_alert_threshold, Some(1));
+        assert_eq!(meeting.event.video_link.as_deref(), Some("https://meet.google.com/abc-defg-hij"));
+        assert_eq!(meeting.event.display_title(), "Sync w/ Alex");
+        assert_eq!(merged[1].ids, vec![EventId(3)]);
+        assert_eq!(merged[1].event.display_title(), "Weekly Sync");
+    }
+
+    #[test]
+    fn test_merge_duplicates_takes_the_latest_snooze() {
+        let now = Utc::now();
+        let early = CalendarEvent {
+            id: Some(EventId(1)),
+            last_snoozed_at: Some(now - Duration::minutes(10)),
+            snoozed_until: Some(now + Duration::minutes(30)),
+            ..CalendarEvent::test_at(now + Duration::hours(1))
+        };
+        let late = CalendarEvent {
+            id: Some(EventId(2)),
+            account_id: 2,
+            last_snoozed_at: Some(now - Duration::minutes(1)),
+            snoozed_until: Some(now + Duration::minutes(4)),
+            ..early.clone()
+        };
+
+        for events in [[early.clone(), late.clone()], [late.clone(), early.clone()]] {
+            let merged = merge_duplicates(&events);
+            assert_eq!(merged.len(), 1);
+            assert_eq!(merged[0].event.last_snoozed_at, late.last_snoozed_at);
+            assert_eq!(merged[0].event.snoozed_until, late.snoozed_until);
+        }
+
+        // A later plain snooze (the usual interval) clears an earlier custom length
+        let plain = CalendarEvent { snoozed_until: None, ..late };
+        let merged = merge_duplicates(&[early, plain]);
+        assert_eq!(merged[0].event.snoozed_until, None);
+    }
+
+    #[test]
+    fn test_same_meeting_under_different_uids() {
+        let now = Utc::now();
+        let google = CalendarEvent {
+            id: Some(EventId(1)),
+            external_id: "abc123@google.com".into(),
+            title: "Weekly Sync".to_string(),
+            ..CalendarEvent::test_at(now)
+        };
+        let proton = CalendarEvent {
+            external_id: "xyz789@proton.me".into(),
+            account_id: 2,
+            id: Some(EventId(2)),
+            title: "weekly sync ".to_string(),
+            start_time: now + Duration::minutes(1),
+            ..google.clone()
+        };
+        assert!(google.is_same_meeting(&proton));
+
+        // Too far apart, a different meeting, or two events in one calendar
+        let later = CalendarEvent { start_time: now + Duration::minutes(30), ..proton.clone() };
+        let other = CalendarEvent { title: "1:1".to_string(), ..proton.clone() };
+        let same_account = CalendarEvent { account_id: 1, ..proton.clone() };
+        assert!(!google.is_same_meeting(&later));
+        assert!(!google.is_same_meeting(&other));
+        assert!(!google.is_same_meeting(&same_account));
+
+        assert_eq!(merge_duplicates(&[google, proton, later]).len(), 2);
+    }
 }
@@ -0,0 +1,102 @@
+// file: src/ids.rs
+//! Typed event identifiers
+//!
+//! Events have two identifiers that are easy to mix up: the local database
+//! row id and the UID the calendar feed assigned. Wrapping them keeps a
+//! feed UID from being bound where a row id is expected (which silently
+//! matches nothing in SQL).
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Local database id of an event (`events.id`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type)]
+#[serde(transparent)]
+#[sqlx(transparent)]
+pub struct EventId(pub i64);
+
+impl EventId {
+    pub fn get(self) -> i64 {
+        self.0
+    }
+}
+
+impl From<i64> for EventId {
+    fn from(id: i64) -> Self {
+        Self(id)
+    }
+}
+
+impl fmt::Display for EventId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Identifier assigned by the calendar provider (`events.external_id`, the ICS UID)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type)]
+#[serde(transparent)]
+#[sqlx(transparent)]
+pub struct ExternalEventId(pub String);
+
+impl ExternalEventId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for ExternalEventId {
+    fn from(id: String) -> Self {
+        Self(id)
+    }
+}
+
+impl From<&str> for ExternalEventId {
+    fn from(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+impl fmt::Display for ExternalEventId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_serialize_transparently() {
+        assert_eq!(serde_json::to_string(&EventId(42)).unwrap(), "42");
+        assert_eq!(serde_json::to_string(&ExternalEventId::from("uid@example.com")).unwrap(), "\"uid@example.com\"");
+        assert_eq!(serde_json::from_str::<EventId>("7").unwrap(), EventId(7));
+    }
+
+    #[tokio::test]
+    async fn test_ids_roundtrip_through_sqlite() {
+        let pool = sqlx::SqlitePool::connect(":memory:").await.unwrap();
+        sqlx::query("CREATE TABLE t (id INTEGER PRIMARY KEY, external_id TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let external_id = ExternalEventId::from("abc-123");
+        let id = sqlx::query("INSERT INTO t (external_id) VALUES (?)")
+            .bind(&external_id)
+            .execute(&pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+
+        let (row_id, row_external): (EventId, ExternalEventId) =
+            sqlx::query_as("SELECT id, external_id FROM t WHERE id = ?")
+                .bind(EventId(id))
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(row_id, EventId(id));
+        assert_eq!(row_external, external_id);
+    }
+}
@@ -8,6 +8,28 @@ pub struct VideoMeetingInfo {
     pub url: String,
     pub meeting_id: Option<String>,
     pub password: Option<String>,
+    #[serde(default)]
+    pub dial_in: Option<DialIn>,
+}
+
+/// A telephone number for joining by phone, with the conference ID to
+/// enter once connected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DialIn {
+    pub number: String,
+    pub conference_id: Option<String>,
+}
+
+impl DialIn {
+    /// A `tel:` link that dials the number and then the conference ID
+    /// (each `,` is a pause)
+    pub fn tel_uri(&self) -> String {
+        let number: String = self.number.chars().filter(|c| c.is_ascii_digit() || *c == '+').collect();
+        match &self.conference_id {
+            Some(id) => format!("tel:{},,{}%23", number, id),
+            None => format!("tel:{}", number),
+        }
+    }
 }
 
 impl VideoMeetingInfo {
@@ -17,6 +39,7 @@ impl VideoMeetingInfo {
             url,
             meeting_id: None,
             password: None,
+            dial_in: None,
         }
     }
 }
@@ -37,4 +60,16 @@ mod tests {
         assert!(info.meeting_id.is_none());
         assert!(info.password.is_none());
     }
+
+    #[test]
+    fn test_dial_in_tel_uri() {
+        let dial_in = DialIn {
+            number: "+1 323-555-0100".to_string(),
+            conference_id: Some("123456789".to_string()),
+        };
+        assert_eq!(dial_in.tel_uri(), "tel:+13235550100,,123456789%23");
+
+        let number_only = DialIn { number: "+44 20 7946 0000".to_string(), conference_id: None };
+        assert_eq!(number_only.tel_uri(), "tel:+442079460000");
+    }
 }
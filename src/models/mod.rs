@@ -4,16 +4,24 @@
 // Declare modules
 pub mod account;
 pub mod alert;
+pub mod attendee;
+pub mod checklist;
 pub mod event;
+pub mod ids;
 pub mod meeting;
 pub mod settings;
+pub mod sound_profile;
 pub mod sync;
 
 // Re-export all public types to ensure no breaking changes for external callers.
 // This flattens the structure so imports like `use crate::CalendarEvent` still work.
-pub use account::{Account, CalendarProvider};
+pub use account::{Account, AccountGroup, AccountSecrets, CalendarProvider, IMPORTED_ACCOUNT_NAME};
 pub use alert::{AlertInfo, AlertType};
-pub use event::CalendarEvent;
-pub use meeting::VideoMeetingInfo;
+pub use attendee::{Attendee, Rsvp};
+pub use checklist::ChecklistItem;
+pub use event::{merge_duplicates, next_meeting, remaining_today, CalendarEvent, MergedEvent, DUPLICATE_START_TOLERANCE_MINUTES, MAX_CALENDAR_REMINDER_MINUTES};
+pub use ids::{EventId, ExternalEventId};
+pub use meeting::{DialIn, VideoMeetingInfo};
 pub use settings::{Setting, Settings};
-pub use sync::SyncResult;
+pub use sound_profile::{SoundProfile, SoundSchedule};
+pub use sync::{EventChanges, FeedValidators, SyncResult};
@@ -8,6 +8,21 @@ pub struct Setting {
     pub value: String,
 }
 
+/// Shortest allowed background sync interval, in seconds
+pub const MIN_SYNC_INTERVAL_SECS: i32 = 30;
+/// Longest allowed background sync interval, in seconds (one day)
+pub const MAX_SYNC_INTERVAL_SECS: i32 = 86_400;
+/// Shortest allowed per-account sync timeout, in seconds
+pub const MIN_SYNC_TIMEOUT_SECS: i32 = 10;
+/// Longest allowed per-account sync timeout, in seconds
+pub const MAX_SYNC_TIMEOUT_SECS: i32 = 1_800;
+/// Furthest back or ahead events are imported, in days (ten years)
+pub const MAX_SYNC_WINDOW_DAYS: i32 = 3_650;
+/// Longest focus timer phase, in minutes
+pub const MAX_FOCUS_MINUTES: i32 = 180;
+/// Largest allowed calendar feed download, in MB
+pub const MAX_FEED_SIZE_MB: i32 = 500;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     pub sound: String,
@@ -17,6 +32,10 @@ pub struct Settings {
     pub snooze_interval: i32,      // minutes
     pub max_snoozes: i32,
     pub sync_interval: i32,        // seconds
+    pub sync_timeout: i32,         // seconds allowed for one account's sync
+    pub sync_days_back: i32,       // days of past events to import
+    pub sync_days_ahead: i32,      // days of upcoming events to import
+    pub floating_timezone: String, // "local" or an IANA zone for times without a TZID
     pub auto_join_enabled: bool,
     pub theme: String,
     pub alert_30m: bool,
@@ -24,6 +43,20 @@ pub struct Settings {
     pub alert_5m: bool,
     pub alert_1m: bool,
     pub alert_default: bool, // At start time
+    pub calendar_reminders: bool, // also alert at the event's own VALARM reminders
+    pub custom_alert_minutes: String, // comma-separated extra alerts, in minutes before the start
+    pub alert_free_events: bool,  // alert for events marked free (TRANSP:TRANSPARENT)
+    pub skip_all_day_events: bool, // never alert for all-day events
+    pub check_for_updates: bool, // Opt-in GitHub release check
+    pub hold_syncs_offline: bool, // skip background syncs while no calendar server answers
+    pub focus_work_minutes: i32,  // focus timer work session
+    pub focus_break_minutes: i32, // focus timer break
+    pub world_clocks: String,     // comma-separated IANA zones shown above the calendar
+    pub sound_profiles: String,   // time-of-day sound profiles; see `SoundSchedule`
+    pub keep_awake_minutes: i32,  // block suspend this long before a meeting; 0 is off
+    pub proxy_url: String,        // proxy URL, "none", or empty for HTTPS_PROXY from the environment
+    pub max_feed_size_mb: i32,    // largest (decompressed) calendar feed downloaded
+    pub my_addresses: String,     // comma-separated email addresses that are you in attendee lists
 }
 
 impl Default for Settings {
@@ -36,6 +69,10 @@ impl Default for Settings {
             snooze_interval: 2,
             max_snoozes: 3,
             sync_interval: 300, // 5 minutes
+            sync_timeout: 180,  // 3 minutes
+            sync_days_back: 30,
+            sync_days_ahead: 365,
+            floating_timezone: "local".to_string(),
             auto_join_enabled: false,
             theme: "dark".to_string(),
             alert_30m: false,
@@ -43,6 +80,113 @@ impl Default for Settings {
             alert_5m: true,
             alert_1m: true,
             alert_default: true,
+            calendar_reminders: false,
+            custom_alert_minutes: String::new(),
+            alert_free_events: false,
+            skip_all_day_events: true,
+            check_for_updates: false,
+            hold_syncs_offline: true,
+            focus_work_minutes: 25,
+            focus_break_minutes: 5,
+            world_clocks: String::new(),
+            sound_profiles: String::new(),
+            keep_awake_minutes: 0,
+            proxy_url: String::new(),
+            max_feed_size_mb: 25,
+            my_addresses: String::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Background sync interval, clamped to a sane range so a bad stored
+    /// value can't make the monitor hammer providers or never sync
+    pub fn sync_interval_duration(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.sync_interval.clamp(MIN_SYNC_INTERVAL_SECS, MAX_SYNC_INTERVAL_SECS) as i64)
+    }
+
+    /// How long one account's sync may run before it is abandoned, so a hung
+    /// server can't hold up the monitor cycle (and every alert behind it)
+    pub fn sync_timeout_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.sync_timeout.clamp(MIN_SYNC_TIMEOUT_SECS, MAX_SYNC_TIMEOUT_SECS) as u64)
+    }
+
+    /// How far back events are imported; none from before today is allowed
+    pub fn sync_days_back_duration(&self) -> chrono::Duration {
+        chrono::Duration::days(self.sync_days_back.clamp(0, MAX_SYNC_WINDOW_DAYS) as i64)
+    }
+
+    /// How far ahead events are imported, at least a day
+    pub fn sync_days_ahead_duration(&self) -> chrono::Duration {
+        chrono::Duration::days(self.sync_days_ahead.clamp(1, MAX_SYNC_WINDOW_DAYS) as i64)
+    }
+
+    /// Largest calendar feed body that will be downloaded, at least 1 MB
+    pub fn max_feed_bytes(&self) -> usize {
+        self.max_feed_size_mb.clamp(1, MAX_FEED_SIZE_MB) as usize * 1024 * 1024
+    }
+
+    /// Focus timer work session length
+    pub fn focus_work_duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.focus_work_minutes.clamp(1, MAX_FOCUS_MINUTES) as i64)
+    }
+
+    /// Focus timer break length
+    pub fn focus_break_duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.focus_break_minutes.clamp(1, MAX_FOCUS_MINUTES) as i64)
+    }
+
+    /// Zones for the world clock strip, in the order given. Unknown names are skipped.
+    pub fn world_clock_zones(&self) -> Vec<chrono_tz::Tz> {
+        self.world_clocks
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .filter_map(|name| name.parse().ok())
+            .collect()
+    }
+
+    /// The extra alert thresholds, in minutes before the start, largest
+    /// first. Entries that aren't a whole number of minutes from 1 up to a
+    /// day ahead are skipped.
+    pub fn custom_alert_thresholds(&self) -> Vec<i32> {
+        let mut minutes: Vec<i32> = self
+            .custom_alert_minutes
+            .split(',')
+            .filter_map(|entry| entry.trim().parse().ok())
+            .filter(|minutes| (1..=super::MAX_CALENDAR_REMINDER_MINUTES).contains(minutes))
+            .collect();
+        minutes.sort_by_key(|minutes| std::cmp::Reverse(*minutes));
+        minutes.dedup();
+        minutes
+    }
+
+    /// The user's own email addresses, lowercased like attendee addresses
+    pub fn my_addresses(&self) -> Vec<String> {
+        self.my_addresses
+            .split(',')
+            .map(|address| address.trim().to_lowercase())
+            .filter(|address| !address.is_empty())
+            .collect()
+    }
+
+    /// Alert sound profiles by time of day. A schedule that doesn't parse
+    /// counts as empty, so alerts keep sounding normally.
+    pub fn sound_schedule(&self) -> super::SoundSchedule {
+        self.sound_profiles.parse().unwrap_or_default()
+    }
+
+    /// These settings as they apply to a shared team calendar: only the
+    /// start-time alert, if that's enabled
+    pub fn for_team_calendar(&self) -> Settings {
+        Settings {
+            alert_30m: false,
+            alert_10m: false,
+            alert_5m: false,
+            alert_1m: false,
+            calendar_reminders: false,
+            custom_alert_minutes: String::new(),
+            ..self.clone()
         }
     }
 }
@@ -51,6 +195,27 @@ impl Default for Settings {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_team_calendar_settings_keep_only_the_start_alert() {
+        let settings = Settings { alert_30m: true, snooze_interval: 7, ..Settings::default() };
+        let settings = Settings { custom_alert_minutes: "45, 15".to_string(), ..settings };
+        let team = settings.for_team_calendar();
+        assert!(!team.alert_30m && !team.alert_10m && !team.alert_5m && !team.alert_1m);
+        assert!(team.custom_alert_thresholds().is_empty());
+        assert!(team.alert_default);
+        assert_eq!(team.snooze_interval, 7);
+
+        let quiet = Settings { alert_default: false, ..settings };
+        assert!(!quiet.for_team_calendar().alert_default);
+    }
+
+    #[test]
+    fn test_custom_alert_thresholds() {
+        let settings = Settings { custom_alert_minutes: " 2, 45,15 ,45, soon, -5, 2000".to_string(), ..Settings::default() };
+        assert_eq!(settings.custom_alert_thresholds(), vec![45, 15, 2]);
+        assert!(Settings::default().custom_alert_thresholds().is_empty());
+    }
+
     #[test]
     fn test_settings_default() {
         let settings = Settings::default();
@@ -61,6 +226,10 @@ mod tests {
         assert_eq!(settings.snooze_interval, 2);
         assert_eq!(settings.max_snoozes, 3);
         assert_eq!(settings.sync_interval, 300);
+        assert_eq!(settings.sync_timeout, 180);
+        assert_eq!(settings.sync_days_back, 30);
+        assert_eq!(settings.sync_days_ahead, 365);
+        assert_eq!(settings.floating_timezone, "local");
         assert!(!settings.auto_join_enabled);
         assert_eq!(settings.theme, "dark");
         assert!(!settings.alert_30m);
@@ -68,5 +237,92 @@ mod tests {
         assert!(settings.alert_5m);
         assert!(settings.alert_1m);
         assert!(settings.alert_default);
+        assert!(!settings.calendar_reminders);
+        assert!(!settings.alert_free_events);
+        assert!(settings.skip_all_day_events);
+        assert!(!settings.check_for_updates);
+        assert!(settings.hold_syncs_offline);
+        assert_eq!(settings.focus_work_minutes, 25);
+        assert_eq!(settings.focus_break_minutes, 5);
+        assert_eq!(settings.keep_awake_minutes, 0);
+        assert!(settings.proxy_url.is_empty());
+        assert_eq!(settings.max_feed_bytes(), 25 * 1024 * 1024);
+        assert!(settings.world_clock_zones().is_empty());
+        assert!(settings.sound_schedule().is_empty());
+    }
+
+    #[test]
+    fn test_world_clock_zones() {
+        let settings = Settings {
+            world_clocks: "America/New_York, Asia/Tokyo,,Mars/Olympus_Mons ,Europe/London".to_string(),
+            ..Settings::default()
+        };
+        assert_eq!(
+            settings.world_clock_zones(),
+            vec![chrono_tz::America::New_York, chrono_tz::Asia::Tokyo, chrono_tz::Europe::London]
+        );
+    }
+
+    #[test]
+    fn test_focus_durations_are_clamped() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.focus_work_duration(), chrono::Duration::minutes(25));
+        assert_eq!(settings.focus_break_duration(), chrono::Duration::minutes(5));
+
+        settings.focus_work_minutes = 0;
+        settings.focus_break_minutes = 10_000;
+        assert_eq!(settings.focus_work_duration(), chrono::Duration::minutes(1));
+        assert_eq!(settings.focus_break_duration(), chrono::Duration::minutes(180));
+    }
+
+    #[test]
+    fn test_sync_interval_duration_is_clamped() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.sync_interval_duration(), chrono::Duration::minutes(5));
+
+        settings.sync_interval = 1800;
+        assert_eq!(settings.sync_interval_duration(), chrono::Duration::minutes(30));
+
+        settings.sync_interval = 0;
+        assert_eq!(settings.sync_interval_duration(), chrono::Duration::seconds(30));
+
+        settings.sync_interval = i32::MAX;
+        assert_eq!(settings.sync_interval_duration(), chrono::Duration::days(1));
+    }
+
+    #[test]
+    fn test_sync_timeout_duration_is_clamped() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.sync_timeout_duration(), std::time::Duration::from_secs(180));
+
+        settings.sync_timeout = 0;
+        assert_eq!(settings.sync_timeout_duration(), std::time::Duration::from_secs(10));
+
+        settings.sync_timeout = 1_000_000;
+        assert_eq!(settings.sync_timeout_duration(), std::time::Duration::from_secs(1_800));
+    }
+
+    #[test]
+    fn test_max_feed_bytes_is_clamped() {
+        let mut settings = Settings { max_feed_size_mb: 0, ..Settings::default() };
+        assert_eq!(settings.max_feed_bytes(), 1024 * 1024);
+
+        settings.max_feed_size_mb = i32::MAX;
+        assert_eq!(settings.max_feed_bytes(), 500 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_sync_window_days_are_clamped() {
+        let mut settings = Settings { sync_days_back: 1, sync_days_ahead: 30, ..Settings::default() };
+        assert_eq!(settings.sync_days_back_duration(), chrono::Duration::days(1));
+        assert_eq!(settings.sync_days_ahead_duration(), chrono::Duration::days(30));
+
+        settings.sync_days_back = -5;
+        settings.sync_days_ahead = 0;
+        assert_eq!(settings.sync_days_back_duration(), chrono::Duration::zero());
+        assert_eq!(settings.sync_days_ahead_duration(), chrono::Duration::days(1));
+
+        settings.sync_days_ahead = i32::MAX;
+        assert_eq!(settings.sync_days_ahead_duration(), chrono::Duration::days(3_650));
     }
 }
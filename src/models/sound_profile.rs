@@ -0,0 +1,145 @@
+// file: src/models/sound_profile.rs
+//! Time-of-day sound profiles: how loud alerts are at a given hour
+
+use chrono::NaiveTime;
+use std::fmt;
+use std::str::FromStr;
+
+/// How an alert sounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SoundProfile {
+    /// Quieter chimes
+    Gentle,
+    /// The configured volume
+    #[default]
+    Standard,
+    /// No sound; the alert still shows in the app
+    Silent,
+}
+
+impl SoundProfile {
+    /// Multiplier on the alert volume
+    pub fn volume_factor(self) -> f32 {
+        match self {
+            SoundProfile::Gentle => 0.4,
+            SoundProfile::Standard => 1.0,
+            SoundProfile::Silent => 0.0,
+        }
+    }
+}
+
+impl FromStr for SoundProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "gentle" => Ok(SoundProfile::Gentle),
+            "standard" => Ok(SoundProfile::Standard),
+            "silent" => Ok(SoundProfile::Silent),
+            other => Err(format!("Unknown sound profile '{}' (use gentle, standard or silent)", other)),
+        }
+    }
+}
+
+impl fmt::Display for SoundProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SoundProfile::Gentle => "gentle",
+            SoundProfile::Standard => "standard",
+            SoundProfile::Silent => "silent",
+        })
+    }
+}
+
+/// Profiles by time of day, e.g. `"07:00-10:00 gentle, 18:00-07:00 silent"`.
+/// Ranges include their start, exclude their end and may wrap past
+/// midnight (one that ends where it starts covers the whole day); the
+/// first match wins and other times are standard.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SoundSchedule {
+    ranges: Vec<(NaiveTime, NaiveTime, SoundProfile)>,
+}
+
+impl SoundSchedule {
+    /// Profile in effect at `time` (local wall-clock time)
+    pub fn profile_at(&self, time: NaiveTime) -> SoundProfile {
+        self.ranges
+            .iter()
+            .find(|(start, end, _)| match start.cmp(end) {
+                std::cmp::Ordering::Less => *start <= time && time < *end,
+                std::cmp::Ordering::Greater => time >= *start || time < *end,
+                std::cmp::Ordering::Equal => true,
+            })
+            .map(|(_, _, profile)| *profile)
+            .unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+impl FromStr for SoundSchedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_time = |t: &str| {
+            NaiveTime::parse_from_str(t.trim(), "%H:%M").map_err(|_| format!("Invalid time '{}' (use HH:MM)", t.trim()))
+        };
+
+        let mut ranges = Vec::new();
+        for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (times, profile) = entry
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| format!("'{}' needs a time range and a profile, like 18:00-07:00 silent", entry))?;
+            let (start, end) = times
+                .split_once('-')
+                .ok_or_else(|| format!("Invalid time range '{}' (use HH:MM-HH:MM)", times))?;
+            ranges.push((parse_time(start)?, parse_time(end)?, profile.parse()?));
+        }
+        Ok(Self { ranges })
+    }
+}
+
+impl fmt::Display for SoundSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries: Vec<String> = self
+            .ranges
+            .iter()
+            .map(|(start, end, profile)| format!("{}-{} {}", start.format("%H:%M"), end.format("%H:%M"), profile))
+            .collect();
+        f.write_str(&entries.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_profile_at() {
+        let schedule: SoundSchedule = "07:00-10:00 gentle, 18:00-07:00 Silent".parse().unwrap();
+        assert_eq!(schedule.profile_at(at(6, 59)), SoundProfile::Silent);
+        assert_eq!(schedule.profile_at(at(7, 0)), SoundProfile::Gentle);
+        assert_eq!(schedule.profile_at(at(9, 59)), SoundProfile::Gentle);
+        assert_eq!(schedule.profile_at(at(10, 0)), SoundProfile::Standard);
+        assert_eq!(schedule.profile_at(at(18, 0)), SoundProfile::Silent);
+        assert_eq!(schedule.profile_at(at(23, 30)), SoundProfile::Silent);
+
+        assert_eq!(SoundSchedule::default().profile_at(at(3, 0)), SoundProfile::Standard);
+        assert_eq!(schedule.to_string(), "07:00-10:00 gentle, 18:00-07:00 silent");
+    }
+
+    #[test]
+    fn test_invalid_schedules() {
+        assert!("".parse::<SoundSchedule>().unwrap().is_empty());
+        assert!("18:00-07:00".parse::<SoundSchedule>().is_err());
+        assert!("18:00 silent".parse::<SoundSchedule>().is_err());
+        assert!("25:00-07:00 silent".parse::<SoundSchedule>().is_err());
+        assert!("18:00-07:00 loud".parse::<SoundSchedule>().unwrap_err().contains("loud"));
+    }
+}
@@ -3,14 +3,81 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::event::CalendarEvent;
+use super::ids::EventId;
+
+/// Rows a sync touched, so views can patch their event lists in place
+/// instead of reloading them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventChanges {
+    pub added: Vec<CalendarEvent>,
+    pub updated: Vec<CalendarEvent>,
+    pub removed: Vec<EventId>,
+    /// Events the feed had that couldn't be stored
+    #[serde(default)]
+    pub failed: usize,
+}
+
+impl EventChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+
+    /// Fold another account's changes into this one
+    pub fn merge(&mut self, other: EventChanges) {
+        self.added.extend(other.added);
+        self.updated.extend(other.updated);
+        self.removed.extend(other.removed);
+        self.failed += other.failed;
+    }
+
+    /// Apply to a list showing undismissed events starting within
+    /// `[window_start, window_end]`, keeping it ordered by start time
+    pub fn apply(&self, events: &mut Vec<CalendarEvent>, window_start: DateTime<Utc>, window_end: DateTime<Utc>) {
+        let touched: Vec<EventId> = self
+            .updated
+            .iter()
+            .chain(&self.added)
+            .filter_map(|e| e.id)
+            .chain(self.removed.iter().copied())
+            .collect();
+        events.retain(|e| !e.id.is_some_and(|id| touched.contains(&id)));
+
+        let visible = |e: &&CalendarEvent| {
+            !e.is_dismissed && e.start_time >= window_start && e.start_time <= window_end
+        };
+        events.extend(self.added.iter().chain(&self.updated).filter(visible).cloned());
+        events.sort_by_key(|e| e.start_time);
+    }
+}
+
+/// The `ETag` and `Last-Modified` a feed response came with, sent back on
+/// the next fetch so an unchanged feed costs a 304 instead of a download
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl FeedValidators {
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncResult {
     pub account_id: i64,
     pub success: bool,
     pub events_added: usize,
     pub events_updated: usize,
+    /// Rows removed because the event is gone from the source
+    #[serde(default)]
+    pub events_deleted: usize,
     pub error_message: Option<String>,
     pub sync_time: DateTime<Utc>,
+    #[serde(default)]
+    pub changes: EventChanges,
 }
 
 impl SyncResult {
@@ -20,8 +87,10 @@ impl SyncResult {
             success: true,
             events_added: 0,
             events_updated: 0,
+            events_deleted: 0,
             error_message: None,
             sync_time: Utc::now(),
+            changes: EventChanges::default(),
         }
     }
 
@@ -31,8 +100,20 @@ impl SyncResult {
             success: true,
             events_added: added,
             events_updated: updated,
+            events_deleted: 0,
             error_message: None,
             sync_time: Utc::now(),
+            changes: EventChanges::default(),
+        }
+    }
+
+    pub fn with_changes(account_id: i64, changes: EventChanges) -> Self {
+        Self {
+            events_added: changes.added.len(),
+            events_updated: changes.updated.len(),
+            events_deleted: changes.removed.len(),
+            changes,
+            ..Self::success(account_id)
         }
     }
 
@@ -42,8 +123,10 @@ impl SyncResult {
             success: false,
             events_added: 0,
             events_updated: 0,
+            events_deleted: 0,
             error_message: Some(error),
             sync_time: Utc::now(),
+            changes: EventChanges::default(),
         }
     }
 }
@@ -51,6 +134,16 @@ impl SyncResult {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Duration;
+
+    fn event(id: i64, title: &str, start: DateTime<Utc>) -> CalendarEvent {
+        CalendarEvent {
+            id: Some(EventId(id)),
+            external_id: format!("uid-{}", id).into(),
+            title: title.to_string(),
+            ..CalendarEvent::test_at(start)
+        }
+    }
 
     #[test]
     fn test_sync_result_success() {
@@ -78,4 +171,47 @@ mod tests {
         assert_eq!(result.account_id, 1);
         assert_eq!(result.error_message, Some("Network error".to_string()));
     }
+
+    #[test]
+    fn test_sync_result_with_changes() {
+        let now = Utc::now();
+        let changes = EventChanges {
+            added: vec![event(1, "A", now), event(2, "B", now)],
+            updated: vec![event(3, "C", now)],
+            removed: vec![EventId(4)],
+            failed: 0,
+        };
+        let result = SyncResult::with_changes(7, changes);
+        assert!(result.success);
+        assert_eq!(result.events_added, 2);
+        assert_eq!(result.events_updated, 1);
+        assert_eq!(result.events_deleted, 1);
+    }
+
+    #[test]
+    fn test_event_changes_apply() {
+        let now = Utc::now();
+        let window_end = now + Duration::days(180);
+        let mut events = vec![
+            event(1, "Standup", now + Duration::hours(1)),
+            event(2, "Lunch", now + Duration::hours(3)),
+            event(3, "Retro", now + Duration::hours(5)),
+        ];
+
+        let mut dismissed = event(3, "Retro", now + Duration::hours(5));
+        dismissed.is_dismissed = true;
+        let changes = EventChanges {
+            added: vec![
+                event(4, "Planning", now + Duration::hours(2)),
+                event(5, "Next year", now + Duration::days(400)),
+            ],
+            updated: vec![event(1, "Standup (moved)", now + Duration::hours(4)), dismissed],
+            removed: vec![EventId(2)],
+            failed: 0,
+        };
+        changes.apply(&mut events, now, window_end);
+
+        let titles: Vec<&str> = events.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["Planning", "Standup (moved)"]);
+    }
 }
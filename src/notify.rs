@@ -0,0 +1,96 @@
+// file: src/notify.rs
+//! Desktop notifications, for problems worth seeing while the window is
+//! hidden. The platform's own tool shows them (`notify-send`, `osascript`,
+//! or a tray balloon through PowerShell). The text goes to macOS and
+//! Windows in environment variables, so it needs no quoting.
+
+use log::debug;
+use std::process::{Command, Stdio};
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+const SUMMARY_VAR: &str = "OPENCHIME_NOTIFY_SUMMARY";
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+const BODY_VAR: &str = "OPENCHIME_NOTIFY_BODY";
+
+/// Show a notification; failures are only logged
+pub async fn show(summary: String, body: String) {
+    let Some(mut command) = notification_command(&summary, &body) else {
+        return;
+    };
+    let shown = tokio::task::spawn_blocking(move || {
+        command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null()).status()
+    })
+    .await;
+    match shown {
+        Ok(Ok(status)) if status.success() => {}
+        Ok(Ok(status)) => debug!("Notification \"{}\" not shown: {}", summary, status),
+        Ok(Err(e)) => debug!("Notification \"{}\" not shown: {}", summary, e),
+        Err(e) => debug!("Notification \"{}\" not shown: {}", summary, e),
+    }
+}
+
+/// A command that shows `summary` and `body` as a notification
+fn notification_command(summary: &str, body: &str) -> Option<Command> {
+    #[cfg(target_os = "linux")]
+    {
+        let mut command = Command::new("notify-send");
+        command.args(["--app-name=OpenChime", summary, body]);
+        Some(command)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let mut command = Command::new("osascript");
+        command
+            .args([
+                "-e",
+                &format!(
+                    "display notification (system attribute \"{}\") with title (system attribute \"{}\")",
+                    BODY_VAR, SUMMARY_VAR
+                ),
+            ])
+            .env(SUMMARY_VAR, summary)
+            .env(BODY_VAR, body);
+        Some(command)
+    }
+    #[cfg(target_os = "windows")]
+    {
+        // A balloon from a short-lived tray icon; Windows 10 and later show it as a toast
+        let script = format!(
+            "Add-Type -AssemblyName System.Windows.Forms, System.Drawing; \
+             $icon = New-Object System.Windows.Forms.NotifyIcon; \
+             $icon.Icon = [System.Drawing.SystemIcons]::Warning; $icon.Visible = $true; \
+             $icon.ShowBalloonTip(10000, $env:{}, $env:{}, 'Warning'); \
+             Start-Sleep -Seconds 10; $icon.Dispose()",
+            SUMMARY_VAR, BODY_VAR
+        );
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+        let mut command = Command::new("powershell");
+        command
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            .env(SUMMARY_VAR, summary)
+            .env(BODY_VAR, body)
+            .creation_flags(CREATE_NO_WINDOW);
+        Some(command)
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        let _ = (summary, body);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_notification_command_passes_the_text_as_arguments() {
+        let command = notification_command("Work calendar isn't syncing", "HTTP 404").unwrap();
+        assert_eq!(command.get_program(), "notify-send");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, ["--app-name=OpenChime", "Work calendar isn't syncing", "HTTP 404"]);
+    }
+}
@@ -0,0 +1,228 @@
+//! Named configuration profiles
+//!
+//! A profile is a named section in `profiles.json` that selects its own
+//! database, so e.g. `openchime --profile client-a` keeps that client's
+//! accounts, events and settings separate from everything else. The
+//! `default` profile always exists and uses the original `openchime.db`
+//! location, so installs without a config file behave exactly as before.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, AppResult};
+
+/// Name of the implicit profile used when none is selected
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Database file used by the default profile (relative to the working directory)
+pub const DEFAULT_DATABASE: &str = "openchime.db";
+
+const CONFIG_FILE: &str = "profiles.json";
+
+/// One `[profile]` section of the config file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    /// Database file for this profile; defaults to the per-profile data directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub database: Option<PathBuf>,
+}
+
+/// Contents of `profiles.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfilesConfig {
+    /// Profile used when `--profile` is not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// A profile with its database location worked out
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedProfile {
+    pub name: String,
+    pub database_path: PathBuf,
+}
+
+impl ResolvedProfile {
+    pub fn is_default(&self) -> bool {
+        self.name == DEFAULT_PROFILE
+    }
+}
+
+/// Directory holding `profiles.json` (`$OPENCHIME_CONFIG_DIR` overrides the platform default)
+pub fn config_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("OPENCHIME_CONFIG_DIR") {
+        return PathBuf::from(dir);
+    }
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("openchime")
+}
+
+/// Profile names end up in file paths, so keep them simple
+pub fn validate_profile_name(name: &str) -> AppResult<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::invalid_input(format!(
+            "Invalid profile name '{}'. Use letters, digits, '-' or '_' (max 64 characters).",
+            name
+        )))
+    }
+}
+
+impl ProfilesConfig {
+    /// Load the config from `dir`, returning an empty config if the file doesn't exist
+    pub fn load_from(dir: &Path) -> AppResult<Self> {
+        let path = dir.join(CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| AppError::config(format!("Failed to read {}: {}", path.display(), e)))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| AppError::config(format!("Failed to parse {}: {}", path.display(), e)))
+    }
+
+    pub fn load() -> AppResult<Self> {
+        Self::load_from(&config_dir())
+    }
+
+    pub fn save_to(&self, dir: &Path) -> AppResult<()> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| AppError::config(format!("Failed to create {}: {}", dir.display(), e)))?;
+
+        let path = dir.join(CONFIG_FILE);
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| AppError::config(format!("Failed to serialize profiles: {}", e)))?;
+        std::fs::write(&path, contents)
+            .map_err(|e| AppError::config(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    pub fn save(&self) -> AppResult<()> {
+        self.save_to(&config_dir())
+    }
+
+    /// Name of the active profile
+    pub fn active_name(&self) -> &str {
+        self.active.as_deref().unwrap_or(DEFAULT_PROFILE)
+    }
+
+    /// All known profile names, always including `default`
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.profiles.keys().cloned().collect();
+        if !self.profiles.contains_key(DEFAULT_PROFILE) {
+            names.insert(0, DEFAULT_PROFILE.to_string());
+        }
+        names
+    }
+
+    /// Make `name` the active profile, creating an empty section for it if needed
+    pub fn set_active(&mut self, name: &str) -> AppResult<()> {
+        validate_profile_name(name)?;
+        if name != DEFAULT_PROFILE {
+            self.profiles.entry(name.to_string()).or_default();
+        }
+        self.active = Some(name.to_string());
+        info!("Active profile set to '{}'", name);
+        Ok(())
+    }
+
+    /// Resolve `requested` (or the active profile) to a database path
+    pub fn resolve(&self, requested: Option<&str>) -> AppResult<ResolvedProfile> {
+        let name = requested.unwrap_or_else(|| self.active_name());
+        validate_profile_name(name)?;
+
+        let configured = self.profiles.get(name).and_then(|p| p.database.clone());
+        let database_path = match configured {
+            Some(path) => path,
+            None if name == DEFAULT_PROFILE => PathBuf::from(DEFAULT_DATABASE),
+            None => profile_data_dir(name).join(DEFAULT_DATABASE),
+        };
+
+        Ok(ResolvedProfile {
+            name: name.to_string(),
+            database_path,
+        })
+    }
+}
+
+fn profile_data_dir(name: &str) -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("openchime")
+        .join("profiles")
+        .join(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert!(validate_profile_name("work").is_ok());
+        assert!(validate_profile_name("client_a-2").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("../etc").is_err());
+        assert!(validate_profile_name("has space").is_err());
+    }
+
+    #[test]
+    fn test_default_profile_keeps_legacy_database() {
+        let config = ProfilesConfig::default();
+        let resolved = config.resolve(None).unwrap();
+        assert!(resolved.is_default());
+        assert_eq!(resolved.database_path, PathBuf::from(DEFAULT_DATABASE));
+        assert_eq!(config.names(), vec!["default".to_string()]);
+    }
+
+    #[test]
+    fn test_named_profile_gets_its_own_database() {
+        let mut config = ProfilesConfig::default();
+        config.set_active("work").unwrap();
+
+        let active = config.resolve(None).unwrap();
+        assert_eq!(active.name, "work");
+        assert!(active.database_path.ends_with("profiles/work/openchime.db"));
+
+        // --profile overrides the active profile
+        let default = config.resolve(Some("default")).unwrap();
+        assert_eq!(default.database_path, PathBuf::from(DEFAULT_DATABASE));
+    }
+
+    #[test]
+    fn test_configured_database_path_wins() {
+        let mut config = ProfilesConfig::default();
+        config.profiles.insert(
+            "client".to_string(),
+            Profile { database: Some(PathBuf::from("/tmp/client.db")) },
+        );
+        let resolved = config.resolve(Some("client")).unwrap();
+        assert_eq!(resolved.database_path, PathBuf::from("/tmp/client.db"));
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(ProfilesConfig::load_from(dir.path()).unwrap(), ProfilesConfig::default());
+
+        let mut config = ProfilesConfig::default();
+        config.set_active("personal").unwrap();
+        config.save_to(dir.path()).unwrap();
+
+        let loaded = ProfilesConfig::load_from(dir.path()).unwrap();
+        assert_eq!(loaded.active_name(), "personal");
+        assert_eq!(loaded.names(), vec!["default".to_string(), "personal".to_string()]);
+    }
+}
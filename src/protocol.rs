@@ -0,0 +1,207 @@
+//! `openchime://` URL scheme handling
+//!
+//! Links like `openchime://add?url=https%3A%2F%2F...&name=Team` (e.g. from
+//! docs or a company wiki) launch the app with the Add Calendar form
+//! pre-filled. The OS passes the link as the first argument; this module
+//! parses it and registers the scheme with the desktop environment.
+
+use url::Url;
+
+use crate::error::{AppError, AppResult};
+
+/// URL scheme handled by OpenChime
+pub const SCHEME: &str = "openchime";
+
+/// Something a protocol link asks the app to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProtocolAction {
+    /// Open the Add Calendar form with these values filled in
+    AddCalendar {
+        ics_url: String,
+        name: Option<String>,
+    },
+}
+
+/// Returns true if `arg` looks like an `openchime://` link
+pub fn is_protocol_url(arg: &str) -> bool {
+    arg.len() > SCHEME.len() + 1
+        && arg
+            .get(..SCHEME.len() + 1)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&format!("{}:", SCHEME)))
+}
+
+/// Parse an `openchime://` link into the action it requests
+pub fn parse_protocol_url(link: &str) -> AppResult<ProtocolAction> {
+    let parsed = Url::parse(link)
+        .map_err(|e| AppError::invalid_input(format!("Invalid {} link: {}", SCHEME, e)))?;
+
+    if parsed.scheme() != SCHEME {
+        return Err(AppError::invalid_input(format!(
+            "Expected an {}:// link, got '{}://'",
+            SCHEME,
+            parsed.scheme()
+        )));
+    }
+
+    // `openchime://add?...` puts "add" in the host; `openchime:add?...` in the path
+    let action = parsed
+        .host_str()
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| parsed.path().trim_matches('/'));
+
+    match action.to_ascii_lowercase().as_str() {
+        "add" => {
+            let mut ics_url = None;
+            let mut name = None;
+            for (key, value) in parsed.query_pairs() {
+                match key.as_ref() {
+                    "url" => ics_url = Some(value.trim().to_string()),
+                    "name" => name = Some(value.trim().to_string()).filter(|n| !n.is_empty()),
+                    _ => {}
+                }
+            }
+
+            let ics_url = ics_url
+                .filter(|u| !u.is_empty())
+                .ok_or_else(|| AppError::invalid_input("Link is missing the calendar 'url' parameter"))?;
+
+            Ok(ProtocolAction::AddCalendar { ics_url, name })
+        }
+        other => Err(AppError::invalid_input(format!(
+            "Unsupported {} link action '{}'",
+            SCHEME, other
+        ))),
+    }
+}
+
+/// Register the running executable as the `openchime://` handler for the current user
+#[cfg(target_os = "linux")]
+pub fn register_handler() -> AppResult<String> {
+    let exe = std::env::current_exe()
+        .map_err(|e| AppError::config(format!("Could not locate the OpenChime executable: {}", e)))?;
+
+    let applications_dir = dirs::data_dir()
+        .ok_or_else(|| AppError::config("Could not determine the user data directory"))?
+        .join("applications");
+    std::fs::create_dir_all(&applications_dir)
+        .map_err(|e| AppError::config(format!("Failed to create {}: {}", applications_dir.display(), e)))?;
+
+    let desktop_file = "openchime-url-handler.desktop";
+    let entry = format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=OpenChime\n\
+         Exec=\"{}\" %u\n\
+         Terminal=false\n\
+         NoDisplay=true\n\
+         MimeType=x-scheme-handler/{};\n",
+        exe.display(),
+        SCHEME
+    );
+    let path = applications_dir.join(desktop_file);
+    std::fs::write(&path, entry)
+        .map_err(|e| AppError::config(format!("Failed to write {}: {}", path.display(), e)))?;
+
+    let status = std::process::Command::new("xdg-mime")
+        .args(["default", desktop_file, &format!("x-scheme-handler/{}", SCHEME)])
+        .status()
+        .map_err(|e| AppError::config(format!("Failed to run xdg-mime: {}", e)))?;
+    if !status.success() {
+        return Err(AppError::config(format!("xdg-mime exited with {}", status)));
+    }
+
+    Ok(format!("Registered {}:// links via {}", SCHEME, path.display()))
+}
+
+/// Register the running executable as the `openchime://` handler for the current user
+#[cfg(target_os = "windows")]
+pub fn register_handler() -> AppResult<String> {
+    let exe = std::env::current_exe()
+        .map_err(|e| AppError::config(format!("Could not locate the OpenChime executable: {}", e)))?;
+    let key = format!(r"HKCU\Software\Classes\{}", SCHEME);
+    let command = format!("\"{}\" \"%1\"", exe.display());
+
+    let entries: [(String, Option<&str>, String); 3] = [
+        (key.clone(), None, "URL:OpenChime Protocol".to_string()),
+        (key.clone(), Some("URL Protocol"), String::new()),
+        (format!(r"{}\shell\open\command", key), None, command),
+    ];
+
+    for (path, value_name, data) in entries.iter() {
+        let mut reg = std::process::Command::new("reg");
+        reg.args(["add", path]);
+        match value_name {
+            Some(name) => reg.args(["/v", name]),
+            None => reg.arg("/ve"),
+        };
+        let status = reg
+            .args(["/d", data, "/f"])
+            .status()
+            .map_err(|e| AppError::config(format!("Failed to run reg: {}", e)))?;
+        if !status.success() {
+            return Err(AppError::config(format!("reg add {} exited with {}", path, status)));
+        }
+    }
+
+    Ok(format!("Registered {}:// links in {}", SCHEME, key))
+}
+
+/// URL schemes are declared in the app bundle's Info.plist on macOS and
+/// can't be registered from a bare binary.
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub fn register_handler() -> AppResult<String> {
+    Err(AppError::config(format!(
+        "On this platform {}:// links are registered by the installed app bundle (CFBundleURLTypes), not from the command line",
+        SCHEME
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_protocol_url() {
+        assert!(is_protocol_url("openchime://add?url=x"));
+        assert!(is_protocol_url("OpenChime://add"));
+        assert!(!is_protocol_url("openchime"));
+        assert!(!is_protocol_url("doctor"));
+        assert!(!is_protocol_url("https://example.com"));
+    }
+
+    #[test]
+    fn test_parse_add_link() {
+        let action = parse_protocol_url(
+            "openchime://add?url=https%3A%2F%2Fcalendar.proton.me%2Fapi%2Fcal.ics&name=Team%20Calendar",
+        )
+        .unwrap();
+
+        assert_eq!(
+            action,
+            ProtocolAction::AddCalendar {
+                ics_url: "https://calendar.proton.me/api/cal.ics".to_string(),
+                name: Some("Team Calendar".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_add_link_without_name() {
+        let action = parse_protocol_url("openchime:add?url=https://example.com/cal.ics").unwrap();
+        assert_eq!(
+            action,
+            ProtocolAction::AddCalendar {
+                ics_url: "https://example.com/cal.ics".to_string(),
+                name: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_links() {
+        assert!(parse_protocol_url("openchime://add").is_err());
+        assert!(parse_protocol_url("openchime://add?url=").is_err());
+        assert!(parse_protocol_url("openchime://delete?url=https://example.com/a.ics").is_err());
+        assert!(parse_protocol_url("https://example.com/?url=x").is_err());
+    }
+}
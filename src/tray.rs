@@ -0,0 +1,190 @@
+// file: src/tray.rs
+//! The system tray icon, so OpenChime can sit in the panel rather than a
+//! window left open all day. The icon carries the same meeting count badge
+//! as the window icon, its tooltip counts down to the next meeting
+//! ("Standup in 12m"), and its menu offers Snooze all, Dismiss all (the
+//! Alerts Center's batch actions), Sync Now and Quit; clicking it
+//! brings the window forward. While it's there, closing the window only
+//! hides it.
+//!
+//! On Linux the icon is a StatusNotifierItem (KDE, GNOME with the
+//! AppIndicator extension, most other panels). Elsewhere there's no tray
+//! yet and `spawn` gives `None`.
+
+use tokio::sync::mpsc::UnboundedSender;
+
+/// What the tray asks the app to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayAction {
+    /// The icon was clicked
+    Show,
+    /// Snooze every alert in the Alerts Center
+    SnoozeAll,
+    /// Dismiss every alert in the Alerts Center
+    DismissAll,
+    SyncNow,
+    Quit,
+}
+
+/// What the tray tells the app: that it's up, and then what was clicked
+#[derive(Debug, Clone)]
+pub enum TrayEvent {
+    Ready(TrayHandle),
+    Action(TrayAction),
+}
+
+/// Shown when there's no meeting to count down to
+#[cfg(target_os = "linux")]
+const IDLE_STATUS: &str = "OpenChime";
+
+/// A running tray icon
+#[derive(Clone)]
+pub struct TrayHandle {
+    #[cfg(target_os = "linux")]
+    handle: ksni::Handle<linux::StatusIcon>,
+}
+
+impl std::fmt::Debug for TrayHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TrayHandle")
+    }
+}
+
+impl TrayHandle {
+    /// Show `status` (the next meeting's countdown, or `None` for nothing
+    /// coming up) and a badge for `remaining` meetings today
+    pub async fn update(&self, status: Option<String>, remaining: usize) {
+        #[cfg(target_os = "linux")]
+        self.handle
+            .update(move |icon| {
+                icon.status = status.unwrap_or_else(|| IDLE_STATUS.to_string());
+                icon.remaining = remaining;
+            })
+            .await;
+        #[cfg(not(target_os = "linux"))]
+        let _ = (status, remaining);
+    }
+}
+
+/// Put the icon in the tray, sending clicks on it to `events`. `None` when
+/// there's no tray to put it in.
+pub async fn spawn(events: UnboundedSender<TrayEvent>) -> Option<TrayHandle> {
+    #[cfg(target_os = "linux")]
+    {
+        use ksni::TrayMethods;
+
+        let icon = linux::StatusIcon { status: IDLE_STATUS.to_string(), remaining: 0, events };
+        match icon.spawn().await {
+            Ok(handle) => Some(TrayHandle { handle }),
+            Err(e) => {
+                log::info!("No system tray available: {}", e);
+                None
+            }
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = events;
+        None
+    }
+}
+
+/// The badge icon in the tray's pixel format: ARGB, most significant byte
+/// first
+#[cfg(target_os = "linux")]
+fn argb_icon(remaining: usize) -> Vec<u8> {
+    crate::ui::badge::icon_rgba(remaining)
+        .chunks_exact(4)
+        .flat_map(|rgba| [rgba[3], rgba[0], rgba[1], rgba[2]])
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::{argb_icon, TrayAction, TrayEvent};
+    use tokio::sync::mpsc::UnboundedSender;
+
+    pub struct StatusIcon {
+        pub status: String,
+        pub remaining: usize,
+        pub events: UnboundedSender<TrayEvent>,
+    }
+
+    impl StatusIcon {
+        fn send(&self, action: TrayAction) {
+            let _ = self.events.send(TrayEvent::Action(action));
+        }
+    }
+
+    impl ksni::Tray for StatusIcon {
+        fn id(&self) -> String {
+            env!("CARGO_PKG_NAME").into()
+        }
+
+        fn title(&self) -> String {
+            self.status.clone()
+        }
+
+        fn icon_pixmap(&self) -> Vec<ksni::Icon> {
+            let size = crate::ui::badge::ICON_SIZE as i32;
+            vec![ksni::Icon { width: size, height: size, data: argb_icon(self.remaining) }]
+        }
+
+        fn tool_tip(&self) -> ksni::ToolTip {
+            ksni::ToolTip { title: self.status.clone(), ..Default::default() }
+        }
+
+        fn activate(&mut self, _x: i32, _y: i32) {
+            self.send(TrayAction::Show);
+        }
+
+        fn menu(&self) -> Vec<ksni::MenuItem<Self>> {
+            use ksni::menu::StandardItem;
+
+            vec![
+                StandardItem {
+                    label: format!("Snooze All {}m", crate::alerts::SNOOZE_ALL_MINUTES),
+                    activate: Box::new(|icon: &mut Self| icon.send(TrayAction::SnoozeAll)),
+                    ..Default::default()
+                }
+                .into(),
+                StandardItem {
+                    label: "Dismiss All".into(),
+                    activate: Box::new(|icon: &mut Self| icon.send(TrayAction::DismissAll)),
+                    ..Default::default()
+                }
+                .into(),
+                ksni::MenuItem::Separator,
+                StandardItem {
+                    label: "Sync Now".into(),
+                    activate: Box::new(|icon: &mut Self| icon.send(TrayAction::SyncNow)),
+                    ..Default::default()
+                }
+                .into(),
+                ksni::MenuItem::Separator,
+                StandardItem {
+                    label: "Quit".into(),
+                    icon_name: "application-exit".into(),
+                    activate: Box::new(|icon: &mut Self| icon.send(TrayAction::Quit)),
+                    ..Default::default()
+                }
+                .into(),
+            ]
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icon_is_argb() {
+        let rgba = crate::ui::badge::icon_rgba(3);
+        let argb = argb_icon(3);
+        assert_eq!(argb.len(), rgba.len());
+        for (rgba, argb) in rgba.chunks_exact(4).zip(argb.chunks_exact(4)) {
+            assert_eq!([argb[1], argb[2], argb[3], argb[0]], [rgba[0], rgba[1], rgba[2], rgba[3]]);
+        }
+    }
+}
@@ -3,7 +3,7 @@
 use iced::widget::{button, column, container, row, text, scrollable};
 use iced::{Element, Length, Alignment};
 
-use crate::models::{AlertInfo, AlertType};
+use crate::models::{AlertInfo, AlertType, EventId};
 use crate::ui::{palette, card_style, section_header};
 
 pub struct AlertsView {
@@ -13,8 +13,8 @@ pub struct AlertsView {
 
 #[derive(Debug, Clone)]
 pub enum AlertsMessage {
-    SnoozeAlert(String),
-    DismissAlert(String),
+    SnoozeAlert(EventId),
+    DismissAlert(EventId),
     ClearHistory,
     TestAlert(AlertType),
 }
@@ -30,7 +30,7 @@ impl AlertsView {
     pub fn update(&mut self, message: AlertsMessage) {
         match message {
             AlertsMessage::SnoozeAlert(_) => {},
-            AlertsMessage::DismissAlert(id) => { self.active_alerts.retain(|a| a.event.external_id != id); },
+            AlertsMessage::DismissAlert(id) => { self.active_alerts.retain(|a| a.event.id != Some(id)); },
             AlertsMessage::ClearHistory => { self.alert_history.clear(); },
             AlertsMessage::TestAlert(_) => {},
         }
@@ -111,13 +111,17 @@ impl AlertsView {
                     .size(14)
                     .style(palette::TEXT_MAIN),
 
+                text(alert.event.display_location().map(|location| format!("📍 {}", location)).unwrap_or_default())
+                    .size(12)
+                    .style(palette::TEXT_MUTED),
+
                 row![
                     button("Snooze 5m")
-                        .on_press(AlertsMessage::SnoozeAlert(alert.event.external_id.clone()))
+                        .on_press_maybe(alert.event.id.map(AlertsMessage::SnoozeAlert))
                         .style(iced::theme::Button::Secondary)
                         .padding([8, 16]),
                     button("Dismiss")
-                        .on_press(AlertsMessage::DismissAlert(alert.event.external_id.clone()))
+                        .on_press_maybe(alert.event.id.map(AlertsMessage::DismissAlert))
                         .style(iced::theme::Button::Destructive)
                         .padding([8, 16]),
                 ].spacing(10).width(Length::Fill).align_items(Alignment::Center)
@@ -0,0 +1,139 @@
+// src/ui/badge.rs
+//! The app icon with a count badge for today's remaining meetings
+
+use iced::Color;
+
+use super::styles::ZEN_ACCENT;
+
+/// Width and height of the icon in pixels
+pub const ICON_SIZE: u32 = 32;
+
+/// Counts above this show as "9+"
+const MAX_SHOWN: usize = 9;
+
+/// Darker than `ZEN_DESTRUCTIVE` so white digits stay readable at icon size
+const BADGE_COLOR: Color = Color::from_rgb(0.78, 0.27, 0.27);
+
+/// Badge centre and radius, in the top-right corner
+const BADGE_CENTER: (f32, f32) = (22.5, 9.5);
+const BADGE_RADIUS: f32 = 9.5;
+
+/// Each glyph pixel is drawn as a square this many pixels wide
+const GLYPH_SCALE: u32 = 2;
+
+/// 3x5 glyphs, one row per byte with the leftmost pixel in bit 2
+fn glyph(c: char) -> Option<[u8; 5]> {
+    Some(match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        _ => return None,
+    })
+}
+
+/// The badge's text; `None` when there's nothing left today
+pub fn badge_label(count: usize) -> Option<String> {
+    match count {
+        0 => None,
+        n if n > MAX_SHOWN => Some(format!("{}+", MAX_SHOWN)),
+        n => Some(n.to_string()),
+    }
+}
+
+/// RGBA pixels of the icon: a sage disc, with a red count badge when
+/// `count` is above zero
+pub fn icon_rgba(count: usize) -> Vec<u8> {
+    let size = ICON_SIZE as usize;
+    let mut pixels = vec![[0u8; 4]; size * size];
+
+    let center = ICON_SIZE as f32 / 2.0;
+    fill_circle(&mut pixels, (center, center), center - 1.0, ZEN_ACCENT);
+
+    if let Some(label) = badge_label(count) {
+        fill_circle(&mut pixels, BADGE_CENTER, BADGE_RADIUS, BADGE_COLOR);
+
+        // Glyphs are 3 pixels wide with a 1 pixel gap, centred on the badge
+        let glyph_width = 3 * GLYPH_SCALE;
+        let width = label.len() as u32 * (glyph_width + GLYPH_SCALE) - GLYPH_SCALE;
+        let left = (BADGE_CENTER.0 - width as f32 / 2.0).round() as u32;
+        let top = (BADGE_CENTER.1 - (5 * GLYPH_SCALE) as f32 / 2.0).round() as u32;
+        for (i, c) in label.chars().enumerate() {
+            let Some(rows) = glyph(c) else { continue };
+            let x0 = left + i as u32 * (glyph_width + GLYPH_SCALE);
+            for (row, bits) in rows.iter().enumerate() {
+                for col in 0..3 {
+                    if bits & (0b100 >> col) == 0 {
+                        continue;
+                    }
+                    for dy in 0..GLYPH_SCALE {
+                        for dx in 0..GLYPH_SCALE {
+                            let x = (x0 + col * GLYPH_SCALE + dx) as usize;
+                            let y = (top + row as u32 * GLYPH_SCALE + dy) as usize;
+                            if x < size && y < size {
+                                pixels[y * size + x] = Color::WHITE.into_rgba8();
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pixels.concat()
+}
+
+fn fill_circle(pixels: &mut [[u8; 4]], center: (f32, f32), radius: f32, color: Color) {
+    let size = ICON_SIZE as usize;
+    for y in 0..size {
+        for x in 0..size {
+            let dx = x as f32 + 0.5 - center.0;
+            let dy = y as f32 + 0.5 - center.1;
+            if dx * dx + dy * dy <= radius * radius {
+                pixels[y * size + x] = color.into_rgba8();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(rgba: &[u8], x: usize, y: usize) -> [u8; 4] {
+        let i = (y * ICON_SIZE as usize + x) * 4;
+        [rgba[i], rgba[i + 1], rgba[i + 2], rgba[i + 3]]
+    }
+
+    #[test]
+    fn test_badge_label() {
+        assert_eq!(badge_label(0), None);
+        assert_eq!(badge_label(3).as_deref(), Some("3"));
+        assert_eq!(badge_label(9).as_deref(), Some("9"));
+        assert_eq!(badge_label(14).as_deref(), Some("9+"));
+    }
+
+    #[test]
+    fn test_icon_rgba() {
+        let plain = icon_rgba(0);
+        assert_eq!(plain.len(), (ICON_SIZE * ICON_SIZE * 4) as usize);
+        // Corners stay transparent, the middle is the app colour
+        assert_eq!(pixel(&plain, 0, 0)[3], 0);
+        assert_eq!(pixel(&plain, 16, 16), ZEN_ACCENT.into_rgba8());
+        assert_eq!(pixel(&plain, 29, 9), ZEN_ACCENT.into_rgba8());
+
+        let badged = icon_rgba(3);
+        assert_eq!(pixel(&badged, 16, 24), ZEN_ACCENT.into_rgba8());
+        assert_eq!(pixel(&badged, 29, 9), BADGE_COLOR.into_rgba8());
+        assert!(badged.chunks(4).any(|p| p == Color::WHITE.into_rgba8()));
+        assert_ne!(icon_rgba(1), badged);
+        assert_ne!(icon_rgba(10), icon_rgba(9));
+    }
+}
@@ -3,7 +3,7 @@
 use iced::widget::{button, column, container, row, text, scrollable};
 use iced::{Element, Length, Alignment};
 use chrono::TimeZone;
-use crate::models::CalendarEvent;
+use crate::models::{CalendarEvent, ExternalEventId};
 use crate::ui::{view_event, palette};
 
 pub struct CalendarView {
@@ -15,7 +15,7 @@ pub struct CalendarView {
 pub enum CalendarMessage {
     DateSelected(chrono::NaiveDate),
     RefreshEvents,
-    EventClicked(String),
+    EventClicked(ExternalEventId),
 }
 
 impl CalendarView {
@@ -0,0 +1,112 @@
+// src/ui/charts.rs
+//! Small canvas charts for the Stats view
+
+use iced::mouse;
+use iced::widget::canvas::{self, Frame, Geometry, Text};
+use iced::{alignment, Color, Pixels, Point, Rectangle, Renderer, Size, Theme};
+
+use super::styles::{ZEN_ACCENT, ZEN_DESTRUCTIVE, ZEN_SUBTEXT, ZEN_SURFACE, ZEN_TEXT};
+
+/// Space under the bars for their labels
+const LABEL_HEIGHT: f32 = 18.0;
+/// Space above the tallest bar for its value
+const VALUE_HEIGHT: f32 = 16.0;
+
+/// Vertical bars with a label under each and the value above it
+#[derive(Debug, Clone)]
+pub struct BarChart {
+    pub bars: Vec<(String, f64)>,
+    /// Formats a bar's value for display
+    pub format: fn(f64) -> String,
+}
+
+impl<Message> canvas::Program<Message> for BarChart {
+    type State = ();
+
+    fn draw(&self, _state: &(), renderer: &Renderer, _theme: &Theme, bounds: Rectangle, _cursor: mouse::Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        if self.bars.is_empty() {
+            return vec![frame.into_geometry()];
+        }
+
+        let slot = frame.width() / self.bars.len() as f32;
+        let bar_width = (slot * 0.6).max(1.0);
+        let plot_height = (frame.height() - LABEL_HEIGHT - VALUE_HEIGHT).max(1.0);
+        let max = self.bars.iter().map(|(_, value)| *value).fold(0.0, f64::max);
+
+        for (i, (label, value)) in self.bars.iter().enumerate() {
+            let center = slot * (i as f32 + 0.5);
+            let height = if max > 0.0 { (value / max) as f32 * plot_height } else { 0.0 };
+            let top = VALUE_HEIGHT + plot_height - height;
+
+            // A faint track keeps empty weeks visible
+            frame.fill_rectangle(
+                Point::new(center - bar_width / 2.0, VALUE_HEIGHT),
+                Size::new(bar_width, plot_height),
+                ZEN_SURFACE,
+            );
+            frame.fill_rectangle(Point::new(center - bar_width / 2.0, top), Size::new(bar_width, height), ZEN_ACCENT);
+
+            frame.fill_text(label_text(label.clone(), Point::new(center, frame.height() - LABEL_HEIGHT / 2.0), ZEN_SUBTEXT));
+            if *value > 0.0 {
+                frame.fill_text(label_text((self.format)(*value), Point::new(center, top - VALUE_HEIGHT / 2.0), ZEN_TEXT));
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+/// One bar split in two shares, labelled with their percentages
+#[derive(Debug, Clone)]
+pub struct RatioBar {
+    pub left: (String, u32),
+    pub right: (String, u32),
+}
+
+impl<Message> canvas::Program<Message> for RatioBar {
+    type State = ();
+
+    fn draw(&self, _state: &(), renderer: &Renderer, _theme: &Theme, bounds: Rectangle, _cursor: mouse::Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let total = self.left.1 + self.right.1;
+        let bar_height = (frame.height() - LABEL_HEIGHT).max(1.0);
+
+        if total == 0 {
+            frame.fill_rectangle(Point::ORIGIN, Size::new(frame.width(), bar_height), ZEN_SURFACE);
+            return vec![frame.into_geometry()];
+        }
+
+        let share = self.left.1 as f32 / total as f32;
+        let split = frame.width() * share;
+        frame.fill_rectangle(Point::ORIGIN, Size::new(split, bar_height), ZEN_ACCENT);
+        frame.fill_rectangle(Point::new(split, 0.0), Size::new(frame.width() - split, bar_height), ZEN_DESTRUCTIVE);
+
+        let y = bar_height + LABEL_HEIGHT / 2.0;
+        let left = format!("{} {:.0}%", self.left.0, share * 100.0);
+        let right = format!("{} {:.0}%", self.right.0, (1.0 - share) * 100.0);
+        frame.fill_text(Text {
+            horizontal_alignment: alignment::Horizontal::Left,
+            ..label_text(left, Point::new(0.0, y), ZEN_TEXT)
+        });
+        frame.fill_text(Text {
+            horizontal_alignment: alignment::Horizontal::Right,
+            ..label_text(right, Point::new(frame.width(), y), ZEN_TEXT)
+        });
+
+        vec![frame.into_geometry()]
+    }
+}
+
+/// Small text centred on `position`
+fn label_text(content: String, position: Point, color: Color) -> Text {
+    Text {
+        content,
+        position,
+        color,
+        size: Pixels(11.0),
+        horizontal_alignment: alignment::Horizontal::Center,
+        vertical_alignment: alignment::Vertical::Center,
+        ..Text::default()
+    }
+}
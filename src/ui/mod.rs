@@ -6,6 +6,8 @@ use iced::{Element, Color, Background, Border, Shadow, Vector, Theme};
 use crate::models::{CalendarEvent, Account};
 
 
+pub mod badge;
+pub mod charts;
 pub mod styles;
 
 // --- ZEN THEME PALETTE ---
@@ -51,11 +53,15 @@ pub fn view_event(event: &CalendarEvent) -> Element<'_, crate::messages::Message
     let local_start = event.start_time.with_timezone(&chrono::Local);
     let local_end = event.end_time.with_timezone(&chrono::Local);
 
-    let time_str = format!(
-        "{} - {}",
-        local_start.format("%H:%M"),
-        local_end.format("%H:%M")
-    );
+    let time_str = if event.is_all_day {
+        "All day".to_string()
+    } else {
+        format!(
+            "{} - {}",
+            local_start.format("%H:%M"),
+            local_end.format("%H:%M")
+        )
+    };
 
     // A visual strip on the left to indicate event type
     let indicator_color = if is_video { palette::ACCENT } else { palette::TEXT_MUTED };
@@ -120,6 +120,19 @@ impl container::StyleSheet for CardStyle {
     }
 }
 
+/// A strip or swatch in a calendar's own color (see `Account::calendar_rgb`)
+pub struct CalendarColorStyle(pub Color);
+impl container::StyleSheet for CalendarColorStyle {
+    type Style = Theme;
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            background: Some(Background::Color(self.0)),
+            border: Border { radius: 2.0.into(), ..Border::default() },
+            ..Default::default()
+        }
+    }
+}
+
 pub struct InputStyle;
 impl iced::widget::text_input::StyleSheet for InputStyle {
     type Style = Theme;
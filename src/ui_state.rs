@@ -9,6 +9,38 @@ pub enum View {
     Calendar,
     Settings,
     Alerts,
+    Focus,
+    Stats,
+}
+
+/// The "New reminder" form
+#[derive(Debug, Clone, Default)]
+pub struct ReminderForm {
+    pub title: String,
+    /// As typed, e.g. `15:00`, `3pm` or `in 20m`
+    pub time: String,
+    pub link: String,
+    /// Why the last save was rejected
+    pub error: Option<String>,
+}
+
+/// The form for renaming a linked account or fixing its URL
+#[derive(Debug, Clone)]
+pub struct AccountForm {
+    pub account_id: i64,
+    pub name: String,
+    pub url: String,
+    /// Why the last save was rejected
+    pub error: Option<String>,
+}
+
+/// An event's checklist, open for editing in the calendar list
+#[derive(Debug, Clone)]
+pub struct ChecklistEditor {
+    pub event_id: crate::models::EventId,
+    pub items: Vec<crate::models::ChecklistItem>,
+    /// The item being typed
+    pub new_item: String,
 }
 
 /// Application UI state
@@ -28,6 +60,18 @@ pub struct UiState {
     
     /// ICS URL input field
     pub ics_url: String,
+
+    /// CalDAV username input field; a calendar linked with one is a CalDAV account
+    pub caldav_username: String,
+
+    /// CalDAV app password input field
+    pub caldav_password: String,
+
+    /// Request header being typed in the Add New Calendar form
+    pub header_input: String,
+
+    /// Request headers (`Name: value`) to send with the new calendar's feed
+    pub request_headers: Vec<String>,
     
     /// Current sync status message
     pub sync_status: String,
@@ -37,6 +81,66 @@ pub struct UiState {
     
     /// Timestamp of last successful sync
     pub last_sync_time: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Newer release found by the update check, if any
+    pub available_update: Option<crate::updates::UpdateInfo>,
+
+    /// Whether the update check has already run this session
+    pub update_checked: bool,
+
+    /// Event being renamed, and the name typed so far
+    pub editing_title: Option<(crate::models::EventId, String)>,
+
+    /// Open while a reminder is being created
+    pub reminder_form: Option<ReminderForm>,
+
+    /// Open while a linked account is being edited
+    pub account_form: Option<AccountForm>,
+
+    /// World clock zones typed in Settings but not saved yet
+    pub world_clocks_draft: Option<String>,
+
+    /// Extra alert times typed in Settings but not saved yet
+    pub custom_alerts_draft: Option<String>,
+
+    /// Sound profiles typed in Settings but not saved yet
+    pub sound_profiles_draft: Option<String>,
+
+    /// Proxy URL typed in Settings but not saved yet
+    pub proxy_url_draft: Option<String>,
+
+    /// The user's email addresses typed in Settings but not saved yet
+    pub my_addresses_draft: Option<String>,
+
+    /// Name of the account group being added in Settings
+    pub group_name: String,
+
+    /// Calendar feeds found on a web page pasted as an ICS URL
+    pub discovered_feeds: Vec<String>,
+
+    /// Calendars found on a CalDAV server entered by hostname
+    pub discovered_calendars: Vec<crate::calendar::caldav::DiscoveredCalendar>,
+
+    /// What linking the calendar in the add form would bring in
+    pub account_preview: Option<crate::models::SyncResult>,
+
+    /// How each step of testing the calendar in the add form went
+    pub connection_test: Vec<crate::doctor::CheckResult>,
+
+    /// ICS text or file path in the Import ICS field
+    pub import_input: String,
+
+    /// Accounts whose syncs keep failing, shown with a retry button
+    pub stalled_syncs: Vec<crate::alerts::StalledSync>,
+
+    /// Checklist open for editing
+    pub checklist: Option<ChecklistEditor>,
+
+    /// Attendee list open for an event
+    pub attendees: Option<(crate::models::EventId, Vec<crate::models::Attendee>)>,
+
+    /// Unchecked items from each event's latest early-warning alert
+    pub to_prepare: std::collections::HashMap<crate::models::EventId, Vec<String>>,
 }
 
 impl UiState {
@@ -46,9 +150,33 @@ impl UiState {
             current_view: View::Calendar,
             account_name: String::new(),
             ics_url: String::new(),
+            caldav_username: String::new(),
+            caldav_password: String::new(),
+            header_input: String::new(),
+            request_headers: Vec::new(),
             sync_status: "Ready".to_string(),
             loading: false,
             last_sync_time: None,
+            available_update: None,
+            update_checked: false,
+            editing_title: None,
+            reminder_form: None,
+            account_form: None,
+            world_clocks_draft: None,
+            custom_alerts_draft: None,
+            sound_profiles_draft: None,
+            proxy_url_draft: None,
+            my_addresses_draft: None,
+            group_name: String::new(),
+            discovered_feeds: Vec::new(),
+            discovered_calendars: Vec::new(),
+            account_preview: None,
+            connection_test: Vec::new(),
+            import_input: String::new(),
+            stalled_syncs: Vec::new(),
+            checklist: None,
+            attendees: None,
+            to_prepare: std::collections::HashMap::new(),
         }
     }
 }
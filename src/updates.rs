@@ -0,0 +1,173 @@
+//! Update check module
+//!
+//! Opt-in check against the GitHub releases API. This only reports that a
+//! newer version exists and where to read about it; nothing is downloaded
+//! or installed.
+
+use log::{debug, info};
+use serde::Deserialize;
+
+use crate::error::{AppError, AppResult};
+use crate::http_config::HttpConfig;
+
+/// Latest release endpoint for the OpenChime repository
+pub const RELEASES_API_URL: &str =
+    "https://api.github.com/repos/socrates8300/openchime/releases/latest";
+
+/// Version of the running binary
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A release that is newer than the running version
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateInfo {
+    /// Version without the leading `v`, e.g. "0.2.0"
+    pub version: String,
+    /// Release page with the release notes
+    pub release_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+}
+
+/// Parse a version string like "v1.2.3", "1.2" or "1.2.3-beta.1" into
+/// `(major, minor, patch)`. Pre-release and build suffixes are ignored.
+pub fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim();
+    let version = version
+        .strip_prefix('v')
+        .or_else(|| version.strip_prefix('V'))
+        .unwrap_or(version);
+    let core = version.split(['-', '+']).next()?;
+
+    let mut parts = core.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = match parts.next() {
+        Some(part) => part.parse().ok()?,
+        None => 0,
+    };
+    let patch = match parts.next() {
+        Some(part) => part.parse().ok()?,
+        None => 0,
+    };
+
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Some((major, minor, patch))
+}
+
+/// Returns true if `candidate` is a strictly newer version than `current`.
+/// Unparseable versions are never considered newer.
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    match (parse_version(candidate), parse_version(current)) {
+        (Some(candidate), Some(current)) => candidate > current,
+        _ => false,
+    }
+}
+
+/// Query GitHub for the latest release and return it if it is newer than
+/// the running version.
+pub async fn check_for_update() -> AppResult<Option<UpdateInfo>> {
+    let client = HttpConfig::default()
+        .build_client()
+        .map_err(|e| AppError::config(format!("Failed to build HTTP client: {}", e)))?;
+
+    let response = client
+        .get(RELEASES_API_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(AppError::operation_failed(format!(
+            "Update check failed with HTTP {}",
+            response.status()
+        )));
+    }
+
+    let release: GitHubRelease = response.json().await?;
+    Ok(evaluate_release(release, CURRENT_VERSION))
+}
+
+fn evaluate_release(release: GitHubRelease, current: &str) -> Option<UpdateInfo> {
+    if release.draft || release.prerelease {
+        debug!("Ignoring draft/pre-release {}", release.tag_name);
+        return None;
+    }
+
+    if !is_newer(&release.tag_name, current) {
+        debug!("Running version {} is up to date (latest {})", current, release.tag_name);
+        return None;
+    }
+
+    let version = release.tag_name.trim_start_matches(['v', 'V']).to_string();
+    info!("New version available: {} (running {})", version, current);
+
+    Some(UpdateInfo {
+        version,
+        release_url: release.html_url,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str) -> GitHubRelease {
+        GitHubRelease {
+            tag_name: tag.to_string(),
+            html_url: format!("https://github.com/socrates8300/openchime/releases/tag/{}", tag),
+            draft: false,
+            prerelease: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("1.2.3"), Some((1, 2, 3)));
+        assert_eq!(parse_version("v0.1.0"), Some((0, 1, 0)));
+        assert_eq!(parse_version("1.4"), Some((1, 4, 0)));
+        assert_eq!(parse_version("2"), Some((2, 0, 0)));
+        assert_eq!(parse_version("1.0.0-beta.2"), Some((1, 0, 0)));
+        assert_eq!(parse_version("1.0.0+build.5"), Some((1, 0, 0)));
+        assert_eq!(parse_version("nightly"), None);
+        assert_eq!(parse_version("1.2.3.4"), None);
+        assert_eq!(parse_version(""), None);
+    }
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("v0.2.0", "0.1.0"));
+        assert!(is_newer("0.1.1", "0.1.0"));
+        assert!(is_newer("1.0.0", "0.9.9"));
+        assert!(is_newer("0.10.0", "0.9.0"));
+        assert!(!is_newer("0.1.0", "0.1.0"));
+        assert!(!is_newer("v0.0.9", "0.1.0"));
+        assert!(!is_newer("garbage", "0.1.0"));
+    }
+
+    #[test]
+    fn test_evaluate_release() {
+        let update = evaluate_release(release("v0.2.0"), "0.1.0").unwrap();
+        assert_eq!(update.version, "0.2.0");
+        assert!(update.release_url.ends_with("/v0.2.0"));
+
+        assert!(evaluate_release(release("v0.1.0"), "0.1.0").is_none());
+
+        let mut pre = release("v0.3.0-rc.1");
+        pre.prerelease = true;
+        assert!(evaluate_release(pre, "0.1.0").is_none());
+
+        let mut draft = release("v0.3.0");
+        draft.draft = true;
+        assert!(evaluate_release(draft, "0.1.0").is_none());
+    }
+}
@@ -95,10 +95,14 @@ impl CircuitBreaker {
         let result = operation().await;
         
         // Update circuit state based on result
-        match result {
+        match &result {
             Ok(_) => {
                 self.on_success().await;
             }
+            // Shutting down says nothing about the health of the service
+            Err(e) if e.is::<crate::utils::retry::OperationCancelled>() => {}
+            // A local database error says nothing about the remote service either
+            Err(e) if e.is::<sqlx::Error>() => {}
             Err(_) => {
                 self.on_failure().await;
             }
@@ -153,6 +157,16 @@ impl CircuitBreaker {
         }
     }
 
+    /// Close the circuit and forget past failures, e.g. when the user asks
+    /// to retry now rather than wait out the timeout
+    pub async fn reset(&self) {
+        *self.state.write().await = CircuitState::Closed;
+        *self.failure_count.write().await = 0;
+        *self.success_count.write().await = 0;
+        *self.last_failure_time.write().await = None;
+        info!("Circuit breaker reset");
+    }
+
     pub async fn get_state(&self) -> CircuitState {
         self.state.read().await.clone()
     }
@@ -202,7 +216,8 @@ impl CircuitBreakerRegistry {
         let mut breakers = self.breakers.write().await;
         
         if !breakers.contains_key(service_name) {
-            let config = match service_name {
+            // Per-account breakers ("proton_calendar:3") take their service's settings
+            let config = match service_name.split(':').next().unwrap_or(service_name) {
                 "google_calendar" => CircuitBreakerConfig {
                     failure_threshold: 3,
                     success_threshold: 2,
@@ -223,6 +238,14 @@ impl CircuitBreakerRegistry {
         breakers.get(service_name).unwrap().clone()
     }
 
+    /// Close every breaker, e.g. once the network is back
+    pub async fn reset_all(&self) {
+        let breakers = self.breakers.read().await;
+        for breaker in breakers.values() {
+            breaker.reset().await;
+        }
+    }
+
     pub async fn get_all_stats(&self) -> HashMap<String, CircuitBreakerStats> {
         let breakers = self.breakers.read().await;
         let mut stats = HashMap::new();
@@ -244,6 +267,10 @@ pub async fn get_circuit_breaker(service_name: &str) -> Arc<CircuitBreaker> {
     CIRCUIT_BREAKER_REGISTRY.get_breaker(service_name).await
 }
 
+pub async fn reset_all_circuit_breakers() {
+    CIRCUIT_BREAKER_REGISTRY.reset_all().await
+}
+
 pub async fn get_all_circuit_breaker_stats() -> HashMap<String, CircuitBreakerStats> {
     CIRCUIT_BREAKER_REGISTRY.get_all_stats().await
 }
@@ -285,6 +312,62 @@ mod tests {
         assert!(matches!(breaker.get_state().await, CircuitState::Open));
     }
 
+    #[tokio::test]
+    async fn test_cancellation_is_not_a_failure() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            success_threshold: 1,
+            timeout: Duration::from_secs(60),
+        };
+
+        let breaker = CircuitBreaker::new(config);
+
+        let result: Result<&str, _> = breaker.execute(|| async {
+            Err(crate::utils::retry::OperationCancelled.into())
+        }).await;
+        assert!(result.is_err());
+        assert!(matches!(breaker.get_state().await, CircuitState::Closed));
+        assert_eq!(breaker.get_stats().await.failure_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_database_error_is_not_a_failure() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            success_threshold: 1,
+            timeout: Duration::from_secs(60),
+        };
+
+        let breaker = CircuitBreaker::new(config);
+
+        let result: Result<&str, _> = breaker.execute(|| async {
+            Err(sqlx::Error::PoolTimedOut.into())
+        }).await;
+        assert!(result.is_err());
+        assert!(matches!(breaker.get_state().await, CircuitState::Closed));
+    }
+
+    #[tokio::test]
+    async fn test_reset_closes_the_circuit() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            success_threshold: 1,
+            timeout: Duration::from_secs(60),
+        };
+
+        let breaker = CircuitBreaker::new(config);
+        let _: Result<&str, _> = breaker.execute(|| async {
+            Err(anyhow::anyhow!("Test failure"))
+        }).await;
+        assert!(matches!(breaker.get_state().await, CircuitState::Open));
+
+        breaker.reset().await;
+        assert!(matches!(breaker.get_state().await, CircuitState::Closed));
+        assert_eq!(breaker.get_stats().await.failure_count, 0);
+        let result = breaker.execute(|| async { Ok("success") }).await;
+        assert!(result.is_ok());
+    }
+
     #[tokio::test]
     async fn test_circuit_breaker_half_open_state() {
         let config = CircuitBreakerConfig {
@@ -0,0 +1,66 @@
+// Time source abstraction
+// Alert logic reads the time through a Clock so tests can freeze and advance it
+
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Mutex;
+
+/// Source of the current time
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The real wall clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that only moves when told to
+#[derive(Debug)]
+pub struct MockClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl MockClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self { now: Mutex::new(now) }
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock().unwrap();
+        *now += by;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_mock_clock_set_and_advance() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let clock = MockClock::new(start);
+        assert_eq!(clock.now(), start);
+
+        clock.advance(Duration::minutes(5));
+        assert_eq!(clock.now(), start + Duration::minutes(5));
+
+        clock.set(start);
+        assert_eq!(clock.now(), start);
+    }
+}
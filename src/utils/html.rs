@@ -0,0 +1,146 @@
+// HTML descriptions
+// Outlook and Google often send event descriptions as HTML. They are turned
+// into plain text before storing, keeping link targets so the event card
+// stays readable and video-link extraction still finds the meeting URL.
+
+use regex::{Captures, Regex};
+
+lazy_static::lazy_static! {
+    /// Tags that mark a description as HTML rather than plain text that
+    /// happens to contain `<...>` (e.g. Teams' `Join<https://...>`)
+    static ref HTML_MARKER: Regex = Regex::new(
+        r"(?i)<(?:html|body|p|br|div|span|a|b|i|u|em|strong|font|ul|ol|li|table|tr|td|h[1-6])\b[^<>]*>"
+    ).unwrap();
+    static ref HIDDEN: Regex = Regex::new(r"(?is)<!--.*?-->|<(script|style|head|title)\b[^>]*>.*?</(?:script|style|head|title)>").unwrap();
+    static ref ANCHOR: Regex = Regex::new(r#"(?is)<a\b[^>]*?\bhref\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))[^>]*>(.*?)</a\s*>"#).unwrap();
+    static ref LINE_BREAK: Regex = Regex::new(r"(?i)<br\b[^>]*>|</(?:p|div|tr|h[1-6]|table|ul|ol|blockquote)\s*>|<(?:p|div|hr|table|blockquote)\b[^>]*>").unwrap();
+    static ref LIST_ITEM: Regex = Regex::new(r"(?i)<li\b[^>]*>").unwrap();
+    static ref TAG: Regex = Regex::new(r"</?[a-zA-Z][\w:-]*(?:\s[^<>]*)?/?>").unwrap();
+    static ref ENTITY: Regex = Regex::new(r"&(#[0-9]{1,7}|#[xX][0-9a-fA-F]{1,6}|[a-zA-Z]+);").unwrap();
+    static ref SPACES: Regex = Regex::new(r"[ \t\u{a0}]+").unwrap();
+    static ref BLANK_LINES: Regex = Regex::new(r"\n{3,}").unwrap();
+}
+
+/// Whether `text` looks like HTML
+pub fn is_html(text: &str) -> bool {
+    HTML_MARKER.is_match(text)
+}
+
+/// Plain text for an HTML description. Links keep their target, as
+/// `text (url)` or just `url` when the text is the URL itself. Text that
+/// isn't HTML is returned unchanged.
+pub fn html_to_text(html: &str) -> String {
+    if !is_html(html) {
+        return html.to_string();
+    }
+
+    let text = HIDDEN.replace_all(html, "");
+    let text = ANCHOR.replace_all(&text, |caps: &Captures| {
+        let href = decode_entities(caps.get(1).or(caps.get(2)).or(caps.get(3)).map_or("", |m| m.as_str()));
+        let label = decode_entities(TAG.replace_all(&caps[4], "").trim());
+        let label = SPACES.replace_all(&label, " ");
+        let is_web = href.starts_with("http://") || href.starts_with("https://");
+
+        if !is_web || label == href {
+            label.into_owned()
+        } else if label.is_empty() {
+            href
+        } else {
+            format!("{} ({})", label, href)
+        }
+    });
+    let text = LINE_BREAK.replace_all(&text, "\n");
+    let text = LIST_ITEM.replace_all(&text, "\n• ");
+    let text = TAG.replace_all(&text, "");
+    let text = decode_entities(&text);
+
+    let lines: Vec<String> = text
+        .lines()
+        .map(|line| SPACES.replace_all(line, " ").trim().to_string())
+        .collect();
+    BLANK_LINES.replace_all(lines.join("\n").trim(), "\n\n").into_owned()
+}
+
+/// Decode named and numeric character references. Unknown names are kept as written.
+fn decode_entities(text: &str) -> String {
+    ENTITY
+        .replace_all(text, |caps: &Captures| {
+            let entity = &caps[1];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                "ndash" => Some('–'),
+                "mdash" => Some('—'),
+                "hellip" => Some('…'),
+                "rsquo" => Some('’'),
+                "lsquo" => Some('‘'),
+                "rdquo" => Some('”'),
+                "ldquo" => Some('“'),
+                "bull" => Some('•'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(str::parse))
+                    .and_then(Result::ok)
+                    .and_then(char::from_u32),
+            };
+            decoded.map_or_else(|| caps[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text_is_untouched() {
+        let text = "Join Microsoft Teams Meeting<https://teams.microsoft.com/l/meetup-join/abc>\nA < B & C";
+        assert!(!is_html(text));
+        assert_eq!(html_to_text(text), text);
+    }
+
+    #[test]
+    fn test_google_description() {
+        let html = "Weekly planning<br><br>Agenda:<ul><li>Roadmap &amp; hiring</li><li>Q&amp;A</li></ul>\
+            Join: <a href=\"https://meet.google.com/abc-defg-hij\">https://meet.google.com/abc-defg-hij</a>";
+        assert_eq!(
+            html_to_text(html),
+            "Weekly planning\n\nAgenda:\n• Roadmap & hiring\n• Q&A\nJoin: https://meet.google.com/abc-defg-hij"
+        );
+    }
+
+    #[test]
+    fn test_outlook_description() {
+        let html = "<html><head><style>p.MsoNormal { margin: 0 }</style></head><body>\
+            <p class=\"MsoNormal\">Hi&nbsp;all,<o:p></o:p></p>\
+            <!-- converted from text -->\
+            <p><a href=\"https://us02web.zoom.us/j/85012345678?pwd=abc&amp;from=addon\" target=\"_blank\">\
+            <span style=\"color:blue\">Join Zoom Meeting</span></a></p>\
+            <p>Questions? <a href=\"mailto:alex@example.com\">Alex</a> &#8211; thanks&#x21;</p></body></html>";
+        assert_eq!(
+            html_to_text(html),
+            "Hi all,\n\nJoin Zoom Meeting (https://us02web.zoom.us/j/85012345678?pwd=abc&from=addon)\n\nQuestions? Alex – thanks!"
+        );
+    }
+
+    #[test]
+    fn test_links_survive_for_extraction() {
+        let html = "<p>Click <a href='https://zoom.us/j/123456789'>here</a> to join</p>";
+        let text = html_to_text(html);
+        assert_eq!(text, "Click here (https://zoom.us/j/123456789) to join");
+
+        let info = crate::utils::extract_video_link(Some(&text), None).unwrap();
+        assert_eq!(info.url, "https://zoom.us/j/123456789");
+    }
+
+    #[test]
+    fn test_unknown_entities_are_kept() {
+        assert_eq!(decode_entities("&bogus; &#xD800; &amp;"), "&bogus; &#xD800; &");
+    }
+}
@@ -1,113 +1,206 @@
 #![allow(dead_code)]
-use crate::models::VideoMeetingInfo;
+use crate::models::{DialIn, VideoMeetingInfo};
 use regex::Regex;
 
 pub mod retry;
 pub mod logging;
 pub mod circuit_breaker;
+pub mod clock;
+pub mod rate_limiter;
+pub mod html;
+pub mod fnv;
 
-pub fn extract_video_link(description: Option<&str>, location: Option<&str>) -> Option<VideoMeetingInfo> {
-    let combined_text = format!("{} {}", description.unwrap_or(""), location.unwrap_or(""));
-    
-    // Video platform patterns
-    let patterns = vec![
+lazy_static::lazy_static! {
+    /// Meeting link patterns, most specific first. Hosts only match their
+    /// own subdomains; the first capture group is the meeting id.
+    static ref VIDEO_PATTERNS: Vec<(Regex, &'static str)> = [
         // Zoom
-        (r"https://.*zoom\.us/j/(\d+)", "Zoom"),
-        (r"https://.*zoom\.us/my/([^\\s]+)", "Zoom"),
-        (r"https://.*zoom\.us/s/([^\\s]+)", "Zoom"),
-        
+        (r"https://(?:[\w-]+\.)*zoom\.us/j/(\d+)", "Zoom"),
+        (r#"https://(?:[\w-]+\.)*zoom\.us/my/([^\s<>"']+)"#, "Zoom"),
+        (r#"https://(?:[\w-]+\.)*zoom\.us/s/([^\s<>"']+)"#, "Zoom"),
+
         // Google Meet
         (r"https://meet\.google\.com/([a-z-]+)", "Google Meet"),
-        
+
         // Microsoft Teams
-        (r"https://teams\.microsoft\.com/l/meetup-join/([^\\s]+)", "Teams"),
-        (r"https://teams\.live\.com/([^\\s]+)", "Teams"),
-        
-        // Webex
-        (r"https://.*webex\.com/([^\\s]+)", "Webex"),
-        (r"https://.*webex\.com/join/([^\\s]+)", "Webex"),
-        
+        (r#"https://teams\.microsoft\.com/l/meetup-join/([^\s<>"']+)"#, "Teams"),
+        (r#"https://teams\.live\.com/([^\s<>"']+)"#, "Teams"),
+
+        // Webex, including personal-room short links (site.webex.com/meet/name)
+        (r#"https://(?:[\w-]+\.)*webex\.com/(?:meet|join)/([^\s<>"']+)"#, "Webex"),
+        (r#"https://(?:[\w-]+\.)*webex\.com/([^\s<>"']+)"#, "Webex"),
+
         // Skype
-        (r"https://join\.skype\.com/([^\\s]+)", "Skype"),
-        
+        (r#"https://join\.skype\.com/([^\s<>"']+)"#, "Skype"),
+
         // GoToMeeting
-        (r"https://.*gotomeeting\.com/([^\\s]+)", "GoToMeeting"),
-        
+        (r#"https://(?:[\w-]+\.)*gotomeeting\.com/([^\s<>"']+)"#, "GoToMeeting"),
+
         // BlueJeans
-        (r"https://.*bluejeans\.com/([^\\s]+)", "BlueJeans"),
-        
+        (r#"https://(?:[\w-]+\.)*bluejeans\.com/([^\s<>"']+)"#, "BlueJeans"),
+
         // RingCentral
-        (r"https://.*ringcentral\.com/([^\\s]+)", "RingCentral"),
-        
+        (r#"https://(?:[\w-]+\.)*ringcentral\.com/([^\s<>"']+)"#, "RingCentral"),
+
         // Whereby
-        (r"https://.*whereby\.com/([^\\s]+)", "Whereby"),
-        
+        (r#"https://(?:[\w-]+\.)*whereby\.com/([^\s<>"']+)"#, "Whereby"),
+
         // Jitsi
-        (r"https://.*jitsi\.org/([^\\s]+)", "Jitsi"),
-        (r"https://meet\.jit\.si/([^\\s]+)", "Jitsi"),
-        
+        (r#"https://(?:[\w-]+\.)*jitsi\.org/([^\s<>"']+)"#, "Jitsi"),
+        (r#"https://meet\.jit\.si/([^\s<>"']+)"#, "Jitsi"),
+
+        // Around
+        (r#"https://(?:[\w-]+\.)*around\.co/r/([^\s<>"']+)"#, "Around"),
+
+        // Gather
+        (r#"https://(?:app\.)?gather\.town/app/([^\s<>"']+)"#, "Gather"),
+
+        // Amazon Chime
+        (r"https://chime\.aws/(\d+)", "Chime"),
+        (r#"https://app\.chime\.aws/meetings/([^\s<>"']+)"#, "Chime"),
+
+        // Vonage
+        (r#"https://meetings\.vonage\.com/([^\s<>"']+)"#, "Vonage"),
+
+        // Lifesize
+        (r#"https://(?:[\w-]+\.)*lifesizecloud\.com/([^\s<>"']+)"#, "Lifesize"),
+
         // Discord
-        (r"https://discord\.gg/([^\\s]+)", "Discord"),
-        (r"https://.*discord\.com/channels/([^\\s]+)", "Discord"),
-        
+        (r#"https://discord\.gg/([^\s<>"']+)"#, "Discord"),
+        (r#"https://(?:[\w-]+\.)*discord\.com/channels/([^\s<>"']+)"#, "Discord"),
+
         // Slack
-        (r"https://.*slack\.com/archives/([^\\s]+)", "Slack"),
-        (r"https://app\.slack\.com/meet/([^\\s]+)", "Slack"),
-        
+        (r#"https://(?:[\w-]+\.)*slack\.com/archives/([^\s<>"']+)"#, "Slack"),
+        (r#"https://app\.slack\.com/meet/([^\s<>"']+)"#, "Slack"),
+
         // FaceTime (iOS links)
-        (r"facetime://([^\\s]+)", "FaceTime"),
-        (r"facetime-audio://([^\\s]+)", "FaceTime"),
-        
+        (r#"facetime://([^\s<>"']+)"#, "FaceTime"),
+        (r#"facetime-audio://([^\s<>"']+)"#, "FaceTime"),
+
         // Zoom alternative patterns
         (r"zoom\.us/j/(\d+)", "Zoom"),
-        (r"zoom\.us/my/([^\\s]+)", "Zoom"),
-        
+        (r#"zoom\.us/my/([^\s<>"']+)"#, "Zoom"),
+
         // Generic patterns
-        (r"https://([^\\s]*meet[^\\s]*)", "Meeting"),
-        (r"https://([^\\s]*call[^\\s]*)", "Meeting"),
-        (r"https://([^\\s]*video[^\\s]*)", "Meeting"),
-    ];
-    
-    for (pattern, platform) in patterns {
-        if let Ok(regex) = Regex::new(pattern) {
-            if let Some(captures) = regex.captures(&combined_text) {
-                let full_match = captures.get(0).unwrap().as_str().to_string();
-                let meeting_id = captures.get(1).map(|m| m.as_str().to_string());
-                
-                return Some(VideoMeetingInfo {
-                    platform: platform.to_string(),
-                    url: full_match,
-                    meeting_id,
-                    password: None, // Could be enhanced to extract passwords
-                });
-            }
+        (r#"https://([^\s<>"']*meet[^\s<>"']*)"#, "Meeting"),
+        (r#"https://([^\s<>"']*call[^\s<>"']*)"#, "Meeting"),
+        (r#"https://([^\s<>"']*video[^\s<>"']*)"#, "Meeting"),
+    ]
+    .into_iter()
+    .map(|(pattern, platform)| (Regex::new(pattern).expect("invalid video link pattern"), platform))
+    .collect();
+
+    /// Google and Outlook (Safe Links) redirectors that wrap links in
+    /// forwarded or security-scanned invites
+    static ref REDIRECTOR: Regex = Regex::new(
+        r#"https://(?:www\.)?google\.com/url\?[^\s<>"']+|https://(?:[\w-]+\.)*safelinks\.protection\.outlook\.com/\?[^\s<>"']+"#
+    )
+    .expect("invalid redirector pattern");
+
+    /// Passcode patterns, most specific first. A label needs a `:` or `=`
+    /// after it so prose like "password protected" isn't read as one.
+    /// Numeric codes may be printed in space-separated groups ("2634 123").
+    static ref PASSWORD_PATTERNS: Vec<Regex> = [
+        r"(?i)\b(?:meeting\s+)?pass(?:code|word)\s*[:=]\s*([A-Za-z0-9.@*_-]+)",
+        r"[?&]pwd=([A-Za-z0-9._-]+)",
+        r"(?i)\bpwd\s*[:=]\s*([A-Za-z0-9._-]+)",
+        r"(?i)\bpass\s*[:=]\s*([A-Za-z0-9._-]+)",
+        r"(?i)\b(?:numeric\s+)?(?:pin|access\s+code|code)\s*[:=]\s*(\d+(?: \d+)*)",
+    ]
+    .into_iter()
+    .map(|pattern| Regex::new(pattern).expect("invalid password pattern"))
+    .collect();
+
+    /// A one-tap dial string: number, pause, conference ID and `#`
+    /// (`+16699006833,,85012345678#`, `+1 323-555-0100,,123456789#`)
+    static ref ONE_TAP_DIAL_IN: Regex =
+        Regex::new(r"(\+\d[\d ().-]{6,20}\d),,+(\d+)#").expect("invalid dial-in pattern");
+
+    /// A phone number on its own (not across lines)
+    static ref PHONE_NUMBER: Regex =
+        Regex::new(r"\+\d[\d ().-]{6,20}\d").expect("invalid phone number pattern");
+
+    /// The ID to enter once connected (Teams "Phone Conference ID", Zoom "Meeting ID")
+    static ref CONFERENCE_ID: Regex =
+        Regex::new(r"(?i)(?:phone\s+)?(?:conference|meeting)\s+id\s*:\s*(\d[\d ]*\d)\s*#?")
+            .expect("invalid conference id pattern");
+}
+
+/// Replace redirector links with the link they point to
+fn unwrap_redirects(text: &str) -> std::borrow::Cow<'_, str> {
+    REDIRECTOR.replace_all(text, |caps: &regex::Captures| {
+        let wrapped = caps[0].replace("&amp;", "&");
+        url::Url::parse(&wrapped)
+            .ok()
+            .and_then(|url| {
+                url.query_pairs()
+                    .find(|(key, _)| key == "q" || key == "url")
+                    .map(|(_, target)| target.into_owned())
+            })
+            .unwrap_or_else(|| caps[0].to_string())
+    })
+}
+
+pub fn extract_video_link(description: Option<&str>, location: Option<&str>) -> Option<VideoMeetingInfo> {
+    let combined_text = format!("{} {}", description.unwrap_or(""), location.unwrap_or(""));
+    let combined_text = unwrap_redirects(&combined_text);
+
+    for (regex, platform) in VIDEO_PATTERNS.iter() {
+        if let Some(captures) = regex.captures(&combined_text) {
+            // Sentence punctuation after a link isn't part of it
+            let full_match = trim_link_punctuation(captures.get(0).unwrap().as_str()).to_string();
+            let meeting_id = captures
+                .get(1)
+                .map(|m| trim_link_punctuation(m.as_str()).to_string());
+
+            return Some(VideoMeetingInfo {
+                platform: platform.to_string(),
+                url: full_match,
+                meeting_id,
+                password: extract_meeting_password(&combined_text),
+                dial_in: extract_dial_in(&combined_text),
+            });
         }
     }
-    
+
     None
 }
 
+fn trim_link_punctuation(link: &str) -> &str {
+    link.trim_end_matches(['.', ',', ';', ':', '!', '?', ')'])
+}
+
 pub fn extract_meeting_password(text: &str) -> Option<String> {
-    // Common password patterns
-    let password_patterns = vec![
-        r"password[:\s]+([A-Za-z0-9]+)",
-        r"pwd[:\s]+([A-Za-z0-9]+)",
-        r"pass[:\s]+([A-Za-z0-9]+)",
-        r"code[:\s]+([A-Za-z0-9]+)",
-        r"pin[:\s]+([A-Za-z0-9]+)",
-    ];
-    
-    for pattern in password_patterns {
-        if let Ok(regex) = Regex::new(pattern) {
-            if let Some(captures) = regex.captures(text) {
-                if let Some(password) = captures.get(1) {
-                    return Some(password.as_str().to_string());
-                }
-            }
-        }
+    PASSWORD_PATTERNS
+        .iter()
+        .find_map(|regex| regex.captures(text))
+        .and_then(|captures| captures.get(1))
+        .map(|password| password.as_str().replace(' ', ""))
+}
+
+/// Find a telephone dial-in in an invite: a one-tap dial string if there is
+/// one, otherwise the first international number and the conference ID
+/// printed near it
+pub fn extract_dial_in(text: &str) -> Option<DialIn> {
+    if let Some(captures) = ONE_TAP_DIAL_IN.captures(text) {
+        return Some(DialIn {
+            number: captures[1].trim().to_string(),
+            conference_id: Some(captures[2].to_string()),
+        });
     }
-    
-    None
+
+    let number = PHONE_NUMBER
+        .find_iter(text)
+        .map(|m| m.as_str().trim())
+        .find(|number| number.chars().filter(char::is_ascii_digit).count() >= 8)?;
+    let conference_id = CONFERENCE_ID
+        .captures(text)
+        .map(|captures| captures[1].chars().filter(char::is_ascii_digit).collect());
+
+    Some(DialIn {
+        number: number.to_string(),
+        conference_id,
+    })
 }
 
 pub fn is_all_day_event(start_time: chrono::DateTime<chrono::Utc>, end_time: chrono::DateTime<chrono::Utc>) -> bool {
@@ -124,15 +217,16 @@ pub fn extract_meeting_keywords(title: &str, description: Option<&str>) -> Vec<S
     let mut keywords = Vec::new();
     
     let meeting_keywords = vec![
-        "meeting", "call", "conference", "sync", "standup", "review",
+        "meeting", "call", "conference", "sync", "standup", "stand-up", "review",
         "demo", "presentation", "interview", "check-in", "checkin",
         "retrospective", "retro", "planning", "grooming", "refinement",
         "kickoff", "kick-off", "training", "workshop", "webinar",
         "all-hands", "all hands", "townhall", "town hall", "q&a", "qa",
+        "1:1", "1-1", "1on1", "one-on-one", "one on one",
     ];
     
     for keyword in meeting_keywords {
-        if combined.contains(keyword) {
+        if contains_word(&combined, keyword) {
             keywords.push(keyword.to_string());
         }
     }
@@ -140,6 +234,88 @@ pub fn extract_meeting_keywords(title: &str, description: Option<&str>) -> Vec<S
     keywords
 }
 
+/// When a reminder typed as `15:00`, `3:30pm`, `tomorrow 9am`, `in 20m` or
+/// `2024-03-01 15:00` should fire. A bare time that has already passed today
+/// means tomorrow.
+pub fn parse_reminder_time<Tz: chrono::TimeZone>(input: &str, now: &chrono::DateTime<Tz>) -> Option<chrono::DateTime<Tz>> {
+    use chrono::{Duration, NaiveDateTime, NaiveTime};
+
+    let input = input.trim().to_lowercase();
+
+    if let Some(offset) = input.strip_prefix("in ") {
+        let split = offset.find(|c: char| !c.is_ascii_digit())?;
+        let (amount, unit) = offset.split_at(split);
+        let amount: i64 = amount.parse().ok()?;
+        let duration = match unit.trim() {
+            "m" | "min" | "mins" | "minute" | "minutes" => Duration::minutes(amount),
+            "h" | "hr" | "hrs" | "hour" | "hours" => Duration::hours(amount),
+            _ => return None,
+        };
+        return now.clone().checked_add_signed(duration);
+    }
+
+    let at_local = |naive: NaiveDateTime| now.timezone().from_local_datetime(&naive).earliest();
+
+    if let Ok(naive) = NaiveDateTime::parse_from_str(&input, "%Y-%m-%d %H:%M") {
+        return at_local(naive);
+    }
+
+    let (tomorrow, time) = match input.strip_prefix("tomorrow") {
+        Some(rest) => (true, rest.trim()),
+        None => (false, input.as_str()),
+    };
+    // chrono wants minutes, so "3pm" is read as "3:00pm"
+    let mut compact = time.replace(' ', "");
+    if !compact.contains(':') && (compact.ends_with("am") || compact.ends_with("pm")) {
+        compact.insert_str(compact.len() - 2, ":00");
+    }
+    let time = ["%H:%M", "%I:%M%p"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(&compact, format).ok())?;
+
+    let today = now.naive_local().date();
+    let mut at = at_local(today.and_time(time))?;
+    if tomorrow || at <= *now {
+        at = at_local(today.succ_opt()?.and_time(time))?;
+    }
+    Some(at)
+}
+
+/// Categories an event falls into, from the keywords in its title and description
+pub fn categorize_event(title: &str, description: Option<&str>) -> Vec<&'static str> {
+    let keywords = extract_meeting_keywords(title, description);
+    let mut categories = Vec::new();
+
+    for keyword in &keywords {
+        let category = match keyword.as_str() {
+            "standup" | "stand-up" => "standup",
+            "interview" => "interview",
+            "1:1" | "1-1" | "1on1" | "one-on-one" | "one on one" => "1:1",
+            "all-hands" | "all hands" | "townhall" | "town hall" => "all-hands",
+            "retrospective" | "retro" => "retro",
+            "planning" | "grooming" | "refinement" => "planning",
+            "demo" => "demo",
+            "training" | "workshop" | "webinar" => "training",
+            _ => continue,
+        };
+        if !categories.contains(&category) {
+            categories.push(category);
+        }
+    }
+
+    categories
+}
+
+/// Whether `word` appears in `text` without letters or digits either side,
+/// so "1:1" doesn't match "11:15" and "qa" doesn't match "aqua"
+fn contains_word(text: &str, word: &str) -> bool {
+    text.match_indices(word).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + word.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -185,6 +361,61 @@ mod tests {
         assert_eq!(result.unwrap().platform, "Webex");
     }
     
+    #[test]
+    fn test_link_stops_at_whitespace() {
+        // Links used to run to the end of the text, and `s` ended them early
+        let description = Some("Personal room: https://zoom.us/my/jsmith\nDial in: +1 555 0100");
+
+        let result = extract_video_link(description, None).unwrap();
+        assert_eq!(result.url, "https://zoom.us/my/jsmith");
+        assert_eq!(result.meeting_id.as_deref(), Some("jsmith"));
+    }
+
+    #[test]
+    fn test_link_in_html_description() {
+        let description = Some(r#"<p>Join: <a href="https://meet.jit.si/sprint-review">https://meet.jit.si/sprint-review</a></p>"#);
+
+        let result = extract_video_link(description, None).unwrap();
+        assert_eq!(result.platform, "Jitsi");
+        assert_eq!(result.url, "https://meet.jit.si/sprint-review");
+    }
+
+    #[test]
+    fn test_extract_newer_platforms() {
+        let cases = [
+            ("https://meet.around.co/r/design-crit", "Around", "design-crit"),
+            ("https://app.gather.town/app/aBc123/office", "Gather", "aBc123/office"),
+            ("https://chime.aws/4829103756", "Chime", "4829103756"),
+            ("https://meetings.vonage.com/482910375", "Vonage", "482910375"),
+            ("https://call.lifesizecloud.com/1234567", "Lifesize", "1234567"),
+            ("https://acme.webex.com/meet/jdoe", "Webex", "jdoe"),
+        ];
+
+        for (link, platform, id) in cases {
+            let description = format!("Join here: {}\nSee you there", link);
+            let result = extract_video_link(Some(&description), None).unwrap();
+            assert_eq!(result.platform, platform, "{}", link);
+            assert_eq!(result.url, link);
+            assert_eq!(result.meeting_id.as_deref(), Some(id));
+        }
+    }
+
+    #[test]
+    fn test_extract_link_behind_redirector() {
+        let google = Some("Join: https://www.google.com/url?q=https%3A%2F%2Fzoom.us%2Fj%2F123456789&sa=D&ust=1700000000");
+        let result = extract_video_link(google, None).unwrap();
+        assert_eq!(result.platform, "Zoom");
+        assert_eq!(result.url, "https://zoom.us/j/123456789");
+
+        let safelinks = Some(r#"<a href="https://nam12.safelinks.protection.outlook.com/?url=https%3A%2F%2Fteams.microsoft.com%2Fl%2Fmeetup-join%2F19%253ameeting_abc&amp;data=05%7C01&amp;reserved=0">Join</a>"#);
+        let result = extract_video_link(safelinks, None).unwrap();
+        assert_eq!(result.platform, "Teams");
+        assert_eq!(result.url, "https://teams.microsoft.com/l/meetup-join/19%3ameeting_abc");
+
+        // A Google search link isn't a redirect to a meeting
+        assert!(extract_video_link(Some("https://www.google.com/url?sa=D"), None).is_none());
+    }
+
     #[test]
     fn test_no_video_link() {
         let description = Some("Regular team meeting");
@@ -205,6 +436,50 @@ mod tests {
         assert_eq!(result_pwd.unwrap(), "abcdef");
     }
 
+    #[test]
+    fn test_extract_passcodes() {
+        assert_eq!(extract_meeting_password("Meeting ID: 850 1234 5678\nPasscode: 482910").as_deref(), Some("482910"));
+        assert_eq!(extract_meeting_password("Meeting password: xK9-2bq").as_deref(), Some("xK9-2bq"));
+        assert_eq!(extract_meeting_password("https://us02web.zoom.us/j/85012345678?pwd=aBcD1234.1").as_deref(), Some("aBcD1234.1"));
+        assert_eq!(extract_meeting_password("Access code: 2634 123 4567").as_deref(), Some("26341234567"));
+        assert_eq!(extract_meeting_password("PIN: 4821\nHost key: 112233").as_deref(), Some("4821"));
+        assert_eq!(extract_meeting_password("This room is password protected"), None);
+    }
+
+    #[test]
+    fn test_extract_zoom_dial_in() {
+        let invite = "Join Zoom Meeting\nhttps://us02web.zoom.us/j/85012345678?pwd=abc\n\n\
+            Meeting ID: 850 1234 5678\nPasscode: 482910\n\n\
+            One tap mobile\n+16699006833,,85012345678#,,,,*482910# US (San Jose)\n\
+            +13462487799,,85012345678#,,,,*482910# US (Houston)";
+
+        let dial_in = extract_dial_in(invite).unwrap();
+        assert_eq!(dial_in.number, "+16699006833");
+        assert_eq!(dial_in.conference_id.as_deref(), Some("85012345678"));
+
+        let info = extract_video_link(Some(invite), None).unwrap();
+        assert_eq!(info.password.as_deref(), Some("482910"));
+        assert_eq!(info.dial_in, Some(dial_in));
+    }
+
+    #[test]
+    fn test_extract_teams_dial_in() {
+        let invite = "Microsoft Teams meeting\nJoin on your computer\n\
+            Or call in (audio only)\n+1 323-555-0100,,123456789# United States, Los Angeles\n\
+            Phone Conference ID: 123 456 789#";
+        let dial_in = extract_dial_in(invite).unwrap();
+        assert_eq!(dial_in.number, "+1 323-555-0100");
+        assert_eq!(dial_in.conference_id.as_deref(), Some("123456789"));
+
+        // Number and ID on separate lines
+        let invite = "Dial in: +44 20 7946 0000\nPhone Conference ID: 987 654 321#";
+        let dial_in = extract_dial_in(invite).unwrap();
+        assert_eq!(dial_in.number, "+44 20 7946 0000");
+        assert_eq!(dial_in.conference_id.as_deref(), Some("987654321"));
+
+        assert!(extract_dial_in("Room 4B, extension +1234").is_none());
+    }
+
     #[test]
     fn test_is_all_day_event() {
         let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
@@ -225,4 +500,138 @@ mod tests {
         assert!(keywords.contains(&"standup".to_string()));
         assert!(!keywords.contains(&"demo".to_string()));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_meeting_keywords_match_whole_words() {
+        let keywords = extract_meeting_keywords("Aqua recall at 11:15", Some("Resync the demos"));
+        assert!(keywords.is_empty(), "unexpected keywords: {:?}", keywords);
+
+        let keywords = extract_meeting_keywords("Alex / Sam 1:1", None);
+        assert_eq!(keywords, vec!["1:1".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_reminder_time() {
+        let tz = chrono_tz::Europe::London;
+        let now = tz.with_ymd_and_hms(2024, 3, 1, 10, 0, 0).unwrap();
+        let at = |d, h, m| tz.with_ymd_and_hms(2024, 3, d, h, m, 0).unwrap();
+
+        assert_eq!(parse_reminder_time("15:00", &now), Some(at(1, 15, 0)));
+        assert_eq!(parse_reminder_time("3pm", &now), Some(at(1, 15, 0)));
+        assert_eq!(parse_reminder_time("3:30 PM", &now), Some(at(1, 15, 30)));
+        assert_eq!(parse_reminder_time("9:00", &now), Some(at(2, 9, 0)), "past times mean tomorrow");
+        assert_eq!(parse_reminder_time("tomorrow 11am", &now), Some(at(2, 11, 0)));
+        assert_eq!(parse_reminder_time("in 20m", &now), Some(at(1, 10, 20)));
+        assert_eq!(parse_reminder_time("in 2 hours", &now), Some(at(1, 12, 0)));
+        assert_eq!(parse_reminder_time("2024-03-05 08:15", &now), Some(at(5, 8, 15)));
+
+        assert_eq!(parse_reminder_time("", &now), None);
+        assert_eq!(parse_reminder_time("teatime", &now), None);
+        assert_eq!(parse_reminder_time("25:00", &now), None);
+        assert_eq!(parse_reminder_time("in 5 fortnights", &now), None);
+    }
+
+    #[test]
+    fn test_categorize_event() {
+        assert_eq!(categorize_event("Daily Stand-up", None), vec!["standup"]);
+        assert_eq!(categorize_event("Alex <> Sam 1on1", None), vec!["1:1"]);
+        assert_eq!(categorize_event("Q3 All Hands", Some("Town hall with Q&A")), vec!["all-hands"]);
+        assert_eq!(
+            categorize_event("Interview: Senior Engineer", Some("Panel interview, then a short demo")),
+            vec!["demo", "interview"]
+        );
+        assert!(categorize_event("Lunch", Some("Burritos at 12:30")).is_empty());
+    }
+
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        /// A meeting link, and the platform it belongs to
+        fn meeting_link() -> impl Strategy<Value = (String, &'static str)> {
+            let token = "[A-Za-z0-9]{4,24}";
+            prop_oneof![
+                ("(us02web\\.|acme\\.)?", "[1-9][0-9]{8,10}")
+                    .prop_map(|(sub, id)| (format!("https://{}zoom.us/j/{}", sub, id), "Zoom")),
+                "[a-z]{3}-[a-z]{4}-[a-z]{3}"
+                    .prop_map(|code| (format!("https://meet.google.com/{}", code), "Google Meet")),
+                "19%3ameeting_[A-Za-z0-9]{10,40}"
+                    .prop_map(|id| (format!("https://teams.microsoft.com/l/meetup-join/{}", id), "Teams")),
+                ("[a-z]{3,10}", token)
+                    .prop_map(|(company, user)| (format!("https://{}.webex.com/join/{}", company, user), "Webex")),
+                token.prop_map(|room| (format!("https://meet.jit.si/{}", room), "Jitsi")),
+                token.prop_map(|room| (format!("https://whereby.com/{}", room), "Whereby")),
+                token.prop_map(|invite| (format!("https://discord.gg/{}", invite), "Discord")),
+                token.prop_map(|room| (format!("https://meet.around.co/r/{}", room), "Around")),
+                "[0-9]{10}".prop_map(|id| (format!("https://chime.aws/{}", id), "Chime")),
+                token.prop_map(|id| (format!("https://meetings.vonage.com/{}", id), "Vonage")),
+                "[0-9]{5,9}".prop_map(|id| (format!("https://call.lifesizecloud.com/{}", id), "Lifesize")),
+            ]
+        }
+
+        /// Tracking parameters mail clients and calendar invites append
+        fn tracking() -> impl Strategy<Value = String> {
+            prop_oneof![
+                Just(String::new()),
+                Just("?utm_source=calendar&utm_medium=email".to_string()),
+                "[A-Za-z0-9]{4,12}".prop_map(|pwd| format!("?pwd={}", pwd)),
+                "[a-f0-9]{8}".prop_map(|id| format!("?context=%7b%22Tid%22%3a%22{}%22%7d", id)),
+            ]
+        }
+
+        fn whitespace() -> impl Strategy<Value = &'static str> {
+            prop::sample::select(vec![" ", "  ", "\n", "\r\n", "\t", "\n\n"])
+        }
+
+        fn encode(link: &str) -> String {
+            url::form_urlencoded::byte_serialize(link.as_bytes()).collect()
+        }
+
+        /// Surround a link the ways descriptions do
+        fn wrap(link: &str, style: usize) -> String {
+            match style {
+                0 => link.to_string(),
+                1 => format!(r#"<a href="{0}">{0}</a>"#, link),
+                2 => format!("<{}>", link),
+                3 => format!("({})", link),
+                4 => format!("'{}'", link),
+                5 => format!("{}.", link),
+                6 => format!("https://www.google.com/url?q={}&sa=D", encode(link)),
+                _ => format!("https://eur01.safelinks.protection.outlook.com/?url={}&data=05%7C01", encode(link)),
+            }
+        }
+
+        proptest! {
+            #[test]
+            fn finds_the_link_and_platform(
+                (link, platform) in meeting_link(),
+                params in tracking(),
+                style in 0..8usize,
+                before in "[A-Za-z ]{0,40}",
+                after in "[A-Za-z ]{0,40}",
+                gap in whitespace(),
+                in_location in any::<bool>(),
+            ) {
+                let link = format!("{}{}", link, params);
+                let text = format!("{}{}{}{}{}", before, gap, wrap(&link, style), gap, after);
+                let result = if in_location {
+                    extract_video_link(Some("Agenda to follow"), Some(&text))
+                } else {
+                    extract_video_link(Some(&text), None)
+                };
+
+                let info = result.expect("no link found");
+                prop_assert_eq!(info.platform.as_str(), platform);
+                prop_assert!(link.starts_with(&info.url), "{:?} is not a prefix of {:?}", info.url, link);
+                prop_assert!(info.meeting_id.is_some_and(|id| !id.is_empty()));
+            }
+
+            #[test]
+            fn never_panics(description in any::<Option<String>>(), location in any::<Option<String>>()) {
+                if let Some(info) = extract_video_link(description.as_deref(), location.as_deref()) {
+                    prop_assert!(!info.url.chars().any(char::is_whitespace));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+use log::debug;
+
+/// Token bucket settings: `capacity` requests may go out back to back, after
+/// which one more is allowed every `refill_interval`
+#[derive(Debug, Clone)]
+pub struct RateLimiterConfig {
+    pub capacity: u32,
+    pub refill_interval: Duration,
+}
+
+impl Default for RateLimiterConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 6,
+            refill_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+#[derive(Debug)]
+pub struct TokenBucket {
+    config: RateLimiterConfig,
+    state: Mutex<BucketState>,
+}
+
+impl TokenBucket {
+    pub fn new(config: RateLimiterConfig) -> Self {
+        let tokens = config.capacity as f64;
+        Self {
+            config,
+            state: Mutex::new(BucketState {
+                tokens,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take a token if one is available, otherwise return how long until one is
+    pub fn try_acquire(&self) -> Result<(), Duration> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+
+        let refill_secs = self.config.refill_interval.as_secs_f64();
+        if refill_secs > 0.0 {
+            let refilled = now.duration_since(state.last_refill).as_secs_f64() / refill_secs;
+            state.tokens = (state.tokens + refilled).min(self.config.capacity as f64);
+        } else {
+            state.tokens = self.config.capacity as f64;
+        }
+        state.last_refill = now;
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(self.config.refill_interval.mul_f64(1.0 - state.tokens))
+        }
+    }
+
+    /// Wait until a token is available and take it
+    pub async fn acquire(&self) {
+        while let Err(wait) = self.try_acquire() {
+            debug!("Rate limit reached, waiting {:?}", wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// One token bucket per host
+pub struct RateLimiterRegistry {
+    buckets: RwLock<HashMap<String, Arc<TokenBucket>>>,
+    config: RateLimiterConfig,
+}
+
+impl RateLimiterRegistry {
+    pub fn new(config: RateLimiterConfig) -> Self {
+        Self {
+            buckets: RwLock::new(HashMap::new()),
+            config,
+        }
+    }
+
+    pub async fn get_bucket(&self, host: &str) -> Arc<TokenBucket> {
+        let key = host.to_ascii_lowercase();
+        {
+            let buckets = self.buckets.read().await;
+            if let Some(bucket) = buckets.get(&key) {
+                return bucket.clone();
+            }
+        }
+
+        let mut buckets = self.buckets.write().await;
+        buckets
+            .entry(key)
+            .or_insert_with(|| Arc::new(TokenBucket::new(self.config.clone())))
+            .clone()
+    }
+}
+
+lazy_static::lazy_static! {
+    pub static ref RATE_LIMITER_REGISTRY: RateLimiterRegistry = RateLimiterRegistry::new(RateLimiterConfig::default());
+}
+
+/// Wait for the rate limiter of `url`'s host before sending a request
+pub async fn acquire_for_url(url: &str) {
+    let host = match url::Url::parse(url) {
+        Ok(parsed) => parsed.host_str().unwrap_or_default().to_string(),
+        Err(_) => return,
+    };
+    RATE_LIMITER_REGISTRY.get_bucket(&host).await.acquire().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(capacity: u32, refill_secs: u64) -> RateLimiterConfig {
+        RateLimiterConfig {
+            capacity,
+            refill_interval: Duration::from_secs(refill_secs),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bucket_allows_burst_then_limits() {
+        let bucket = TokenBucket::new(config(3, 10));
+
+        assert!(bucket.try_acquire().is_ok());
+        assert!(bucket.try_acquire().is_ok());
+        assert!(bucket.try_acquire().is_ok());
+
+        let wait = bucket.try_acquire().unwrap_err();
+        assert_eq!(wait, Duration::from_secs(10));
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert!(bucket.try_acquire().is_ok());
+        assert!(bucket.try_acquire().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_acquire_waits_for_refill() {
+        let bucket = TokenBucket::new(config(1, 4));
+        let started = Instant::now();
+
+        bucket.acquire().await;
+        bucket.acquire().await;
+
+        assert_eq!(started.elapsed(), Duration::from_secs(4));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_registry_keys_by_host() {
+        let registry = RateLimiterRegistry::new(config(1, 60));
+
+        let a = registry.get_bucket("calendar.example.com").await;
+        assert!(a.try_acquire().is_ok());
+        assert!(registry.get_bucket("CALENDAR.example.com").await.try_acquire().is_err());
+
+        // Another host has its own budget
+        assert!(registry.get_bucket("other.example.com").await.try_acquire().is_ok());
+    }
+}
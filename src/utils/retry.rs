@@ -1,6 +1,10 @@
+use std::fmt;
 use std::time::Duration;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use log::{warn, info, debug};
+use rand::Rng;
+use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, Clone)]
 pub struct RetryConfig {
@@ -8,6 +12,9 @@ pub struct RetryConfig {
     pub base_delay: Duration,
     pub max_delay: Duration,
     pub backoff_multiplier: f64,
+    /// Randomize each delay by up to this fraction (0.25 = ±25%) so clients
+    /// that failed together don't all retry at the same instant
+    pub jitter: f64,
 }
 
 impl Default for RetryConfig {
@@ -17,14 +24,88 @@ impl Default for RetryConfig {
             base_delay: Duration::from_millis(1000),
             max_delay: Duration::from_secs(30),
             backoff_multiplier: 2.0,
+            jitter: 0.25,
         }
     }
 }
 
+/// A non-success HTTP response, carrying the server's Retry-After hint
+#[derive(Debug, Clone)]
+pub struct HttpStatusError {
+    pub status: u16,
+    pub retry_after: Option<Duration>,
+    pub body: String,
+}
+
+impl fmt::Display for HttpStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = reqwest::StatusCode::from_u16(self.status)
+            .ok()
+            .and_then(|s| s.canonical_reason())
+            .unwrap_or("");
+        write!(f, "HTTP {} {}: {}", self.status, reason, self.body)
+    }
+}
+
+impl std::error::Error for HttpStatusError {}
+
+/// Returned when an operation is abandoned because shutdown was requested.
+/// Not a failure of the remote service, so circuit breakers ignore it.
+#[derive(Debug, Clone, Copy)]
+pub struct OperationCancelled;
+
+impl fmt::Display for OperationCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Operation cancelled")
+    }
+}
+
+impl std::error::Error for OperationCancelled {}
+
+/// Parse a Retry-After header value: either delay-seconds or an HTTP date
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let at = DateTime::parse_from_rfc2822(value).ok()?.with_timezone(&Utc);
+    Some((at - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Apply random jitter of ±`jitter` (as a fraction) to `delay`
+pub fn with_jitter(delay: Duration, jitter: f64) -> Duration {
+    let jitter = jitter.clamp(0.0, 1.0);
+    if jitter == 0.0 || delay.is_zero() {
+        return delay;
+    }
+    let factor = rand::thread_rng().gen_range((1.0 - jitter)..=(1.0 + jitter));
+    delay.mul_f64(factor)
+}
+
+/// Retry-After requested by the server, if the error carries one
+pub fn retry_after_hint(error: &anyhow::Error) -> Option<Duration> {
+    error.downcast_ref::<HttpStatusError>().and_then(|e| e.retry_after)
+}
+
 pub async fn retry_with_exponential_backoff<T, F, Fut>(
     config: &RetryConfig,
     operation: F,
 ) -> Result<T>
+where
+    F: Fn() -> Fut,
+    Fut: std::future::Future<Output = Result<T>> + Send + 'static,
+{
+    retry_with_cancellation(config, &CancellationToken::new(), operation).await
+}
+
+/// Like `retry_with_exponential_backoff`, but gives up with `OperationCancelled`
+/// as soon as `cancel` fires, whether mid-attempt or while backing off
+pub async fn retry_with_cancellation<T, F, Fut>(
+    config: &RetryConfig,
+    cancel: &CancellationToken,
+    operation: F,
+) -> Result<T>
 where
     F: Fn() -> Fut,
     Fut: std::future::Future<Output = Result<T>> + Send + 'static,
@@ -32,7 +113,10 @@ where
     let mut delay = config.base_delay;
     
     for attempt in 1..=config.max_attempts {
-        let result = operation().await;
+        let result = tokio::select! {
+            result = operation() => result,
+            _ = cancel.cancelled() => return Err(OperationCancelled.into()),
+        };
         match result {
             Ok(value) => {
                 if attempt > 1 {
@@ -43,12 +127,26 @@ where
             Err(e) => {
                 if attempt == config.max_attempts {
                     warn!("Operation failed after {} attempts: {}", config.max_attempts, e);
-                    return Err(anyhow::anyhow!("Failed after {} retry attempts: {}", config.max_attempts, e));
+                    // As context, so callers can still downcast to the cause
+                    return Err(e.context(format!("Failed after {} retry attempts", config.max_attempts)));
                 }
                 
                 if is_transient_error(&e) {
-                    debug!("Attempt {} failed transiently, retrying in {:?}: {}", attempt, delay, e);
-                    tokio::time::sleep(delay).await;
+                    let wait = match retry_after_hint(&e) {
+                        // Honor the server's Retry-After, but don't stall a sync
+                        // for longer than we'd ever back off ourselves
+                        Some(retry_after) if retry_after > config.max_delay => {
+                            warn!("Server asked to retry after {:?}, giving up for now: {}", retry_after, e);
+                            return Err(e);
+                        }
+                        Some(retry_after) => retry_after,
+                        None => std::cmp::min(with_jitter(delay, config.jitter), config.max_delay),
+                    };
+                    debug!("Attempt {} failed transiently, retrying in {:?}: {}", attempt, wait, e);
+                    tokio::select! {
+                        _ = tokio::time::sleep(wait) => {}
+                        _ = cancel.cancelled() => return Err(OperationCancelled.into()),
+                    }
                     delay = std::cmp::min(
                         Duration::from_millis((delay.as_millis() as f64 * config.backoff_multiplier) as u64),
                         config.max_delay,
@@ -131,4 +229,112 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(attempt_count.load(Ordering::SeqCst), 1);
     }
+
+    #[tokio::test]
+    async fn test_retry_stops_when_cancelled() {
+        let cancel = CancellationToken::new();
+        let config = RetryConfig::default();
+
+        let cancel_clone = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            cancel_clone.cancel();
+        });
+
+        // An attempt that would hang for a minute is abandoned promptly
+        let started = std::time::Instant::now();
+        let result: Result<&str> = retry_with_cancellation(&config, &cancel, || {
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok("too late")
+            })
+        }).await;
+
+        assert!(result.unwrap_err().is::<OperationCancelled>());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_with_jitter_stays_in_range() {
+        let delay = Duration::from_millis(1000);
+        for _ in 0..100 {
+            let jittered = with_jitter(delay, 0.25);
+            assert!(jittered >= Duration::from_millis(750) && jittered <= Duration::from_millis(1250));
+        }
+        assert_eq!(with_jitter(delay, 0.0), delay);
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc3339("2015-10-21T07:28:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now), Some(Duration::from_secs(30)));
+        // Dates in the past mean "now"
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    fn rate_limited(retry_after: Duration) -> anyhow::Error {
+        anyhow::Error::new(HttpStatusError {
+            status: 429,
+            retry_after: Some(retry_after),
+            body: "Too Many Requests".to_string(),
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_honors_retry_after() {
+        let attempt_count = Arc::new(AtomicU32::new(0));
+        let config = RetryConfig::default();
+        let attempt_count_clone = attempt_count.clone();
+        let started = tokio::time::Instant::now();
+
+        let result = retry_with_exponential_backoff(&config, || {
+            let count_clone = attempt_count_clone.clone();
+            Box::pin(async move {
+                if count_clone.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Err(rate_limited(Duration::from_secs(7)))
+                } else {
+                    Ok("success")
+                }
+            })
+        }).await;
+
+        assert_eq!(result.unwrap(), "success");
+        assert_eq!(started.elapsed(), Duration::from_secs(7));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_gives_up_on_long_retry_after() {
+        let attempt_count = Arc::new(AtomicU32::new(0));
+        let config = RetryConfig::default();
+        let attempt_count_clone = attempt_count.clone();
+
+        let result: Result<&str> = retry_with_exponential_backoff(&config, || {
+            let count_clone = attempt_count_clone.clone();
+            Box::pin(async move {
+                count_clone.fetch_add(1, Ordering::SeqCst);
+                Err(rate_limited(Duration::from_secs(3600)))
+            })
+        }).await;
+
+        let err = result.unwrap_err();
+        assert_eq!(attempt_count.load(Ordering::SeqCst), 1);
+        assert_eq!(err.downcast_ref::<HttpStatusError>().unwrap().status, 429);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_exhausted_retries_keep_the_status_error() {
+        let config = RetryConfig::default();
+
+        let result: Result<&str> = retry_with_exponential_backoff(&config, || {
+            Box::pin(async { Err(rate_limited(Duration::from_secs(2))) })
+        }).await;
+
+        let err = result.unwrap_err();
+        assert_eq!(err.to_string(), format!("Failed after {} retry attempts", config.max_attempts));
+        assert!(format!("{:#}", err).ends_with("Too Many Requests"));
+        assert_eq!(err.downcast_ref::<HttpStatusError>().unwrap().status, 429);
+        assert_eq!(retry_after_hint(&err), Some(Duration::from_secs(2)));
+    }
 }
\ No newline at end of file
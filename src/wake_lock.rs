@@ -0,0 +1,156 @@
+// file: src/wake_lock.rs
+//! Keeps the machine from suspending shortly before a meeting, so the
+//! start-time alert isn't swallowed by sleep. The platform's own tool holds
+//! the lock (`systemd-inhibit`, `caffeinate`, or `SetThreadExecutionState`
+//! through PowerShell) and exits by itself when its time is up, so a crash
+//! can't leave the machine awake for good.
+
+use chrono::{DateTime, Duration, Utc};
+use log::{debug, info, warn};
+use std::process::{Child, Command, Stdio};
+
+use crate::models::{next_meeting, CalendarEvent};
+
+/// The lock is kept this long past the meeting's start, for the alert itself
+const GRACE_MINUTES: i64 = 2;
+
+/// Keep-awake windows offered in Settings (minutes before a meeting; 0 is off)
+pub const KEEP_AWAKE_MINUTE_CHOICES: [i32; 4] = [0, 5, 10, 15];
+
+/// When the machine has to stay awake until, if the next meeting starts
+/// within `minutes` of `now`
+pub fn keep_awake_until(events: &[CalendarEvent], now: DateTime<Utc>, minutes: i32) -> Option<DateTime<Utc>> {
+    if minutes <= 0 {
+        return None;
+    }
+    let start = next_meeting(events, now)?.effective_start();
+    (start - now <= Duration::minutes(minutes as i64)).then(|| start.max(now) + Duration::minutes(GRACE_MINUTES))
+}
+
+/// A sleep inhibitor held until a deadline
+#[derive(Debug, Default)]
+pub struct WakeLock {
+    held: Option<(Child, DateTime<Utc>)>,
+}
+
+impl WakeLock {
+    /// Keep the machine awake until `until`. A lock that already lasts that
+    /// long is kept; a shorter one is replaced.
+    pub fn hold_until(&mut self, until: DateTime<Utc>, now: DateTime<Utc>) {
+        if self.is_held() && self.held.as_ref().is_some_and(|(_, held_until)| *held_until >= until) {
+            return;
+        }
+        self.release();
+
+        let seconds = (until - now).num_seconds().max(1) as u64;
+        let Some(mut command) = inhibit_command(seconds) else {
+            debug!("Keeping the system awake isn't supported on this platform");
+            return;
+        };
+        match command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null()).spawn() {
+            Ok(child) => {
+                info!("Keeping the system awake for {}s for the next meeting", seconds);
+                self.held = Some((child, until));
+            }
+            Err(e) => warn!("Failed to keep the system awake: {}", e),
+        }
+    }
+
+    /// Let the machine sleep again
+    pub fn release(&mut self) {
+        if let Some((mut child, _)) = self.held.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    /// Whether the inhibitor is still running
+    pub fn is_held(&mut self) -> bool {
+        match &mut self.held {
+            Some((child, _)) => matches!(child.try_wait(), Ok(None)),
+            None => false,
+        }
+    }
+}
+
+impl Drop for WakeLock {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// A command that blocks suspend for `seconds` and then exits
+fn inhibit_command(seconds: u64) -> Option<Command> {
+    #[cfg(target_os = "linux")]
+    {
+        let mut command = Command::new("systemd-inhibit");
+        command.args([
+            "--what=idle:sleep",
+            "--who=OpenChime",
+            "--why=A meeting is about to start",
+            "--mode=block",
+            "sleep",
+            &seconds.to_string(),
+        ]);
+        Some(command)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let mut command = Command::new("caffeinate");
+        command.args(["-i", "-t", &seconds.to_string()]);
+        Some(command)
+    }
+    #[cfg(target_os = "windows")]
+    {
+        // ES_CONTINUOUS | ES_SYSTEM_REQUIRED, held for as long as PowerShell runs
+        let script = format!(
+            "$k = Add-Type -Name Power -Namespace OpenChime -PassThru -MemberDefinition '[DllImport(\"kernel32.dll\")] public static extern uint SetThreadExecutionState(uint f);'; \
+             $k::SetThreadExecutionState(0x80000001) | Out-Null; Start-Sleep -Seconds {}",
+            seconds
+        );
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+        let mut command = Command::new("powershell");
+        command.args(["-NoProfile", "-NonInteractive", "-Command", &script]).creation_flags(CREATE_NO_WINDOW);
+        Some(command)
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        let _ = seconds;
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn meeting(start: DateTime<Utc>) -> CalendarEvent {
+        CalendarEvent {
+            external_id: "standup".into(),
+            title: "Standup".to_string(),
+            end_time: start + Duration::minutes(15),
+            ..CalendarEvent::test_at(start)
+        }
+    }
+
+    #[test]
+    fn test_keep_awake_until() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+        let events = vec![meeting(now + Duration::minutes(8))];
+
+        assert_eq!(keep_awake_until(&events, now, 10), Some(now + Duration::minutes(10)));
+        assert_eq!(keep_awake_until(&events, now, 5), None);
+        assert_eq!(keep_awake_until(&events, now, 0), None);
+        assert_eq!(keep_awake_until(&[], now, 10), None);
+
+        // A meeting that just started keeps the lock for the grace period
+        let started = vec![meeting(now - Duration::seconds(30))];
+        assert_eq!(keep_awake_until(&started, now, 5), Some(now + Duration::minutes(GRACE_MINUTES)));
+
+        let dismissed = vec![CalendarEvent { is_dismissed: true, ..meeting(now + Duration::minutes(3)) }];
+        assert_eq!(keep_awake_until(&dismissed, now, 10), None);
+    }
+}
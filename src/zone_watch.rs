@@ -0,0 +1,90 @@
+// file: src/zone_watch.rs
+//! Noticing when the computer's clock moves against UTC: a daylight saving
+//! transition, or the timezone being changed (after a flight, say). Events
+//! whose times are floating are stored in UTC as read in the local zone at
+//! the last parse, so when that zone's offset or rules change every feed is
+//! parsed and its recurring events expanded again on the next sync.
+
+use chrono::{DateTime, Datelike, Offset, TimeZone, Utc};
+use log::info;
+
+use crate::utils::fnv::Fnv1a;
+
+/// The local zone's UTC offset now, and a digest of its offsets through the
+/// year, as of the last check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZoneWatch {
+    offset: i32,
+    rules: u64,
+}
+
+impl ZoneWatch {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self::in_zone(&chrono::Local, now)
+    }
+
+    fn in_zone<Tz: TimeZone>(tz: &Tz, now: DateTime<Utc>) -> Self {
+        Self { offset: offset_at(tz, now), rules: rules(tz, now) }
+    }
+
+    /// Record a check; true when the offset or the zone's rules changed
+    /// since the last one
+    pub fn update(&mut self, now: DateTime<Utc>) -> bool {
+        self.update_in(&chrono::Local, now)
+    }
+
+    fn update_in<Tz: TimeZone>(&mut self, tz: &Tz, now: DateTime<Utc>) -> bool {
+        let current = Self::in_zone(tz, now);
+        let changed = current != *self;
+        if current.rules != self.rules {
+            info!("Local timezone changed; expanding recurring events again");
+        } else if changed {
+            info!("Clocks changed (UTC{:+} to UTC{:+}); expanding recurring events again", self.offset / 3600, current.offset / 3600);
+        }
+        *self = current;
+        changed
+    }
+}
+
+/// A digest of the local zone's offsets through `now`'s year, for telling
+/// zones apart in feed hashes
+pub fn local_rules(now: DateTime<Utc>) -> u64 {
+    rules(&chrono::Local, now)
+}
+
+fn offset_at<Tz: TimeZone>(tz: &Tz, at: DateTime<Utc>) -> i32 {
+    tz.offset_from_utc_datetime(&at.naive_utc()).fix().local_minus_utc()
+}
+
+/// Noon UTC on the first of each month tells any two zones with different
+/// offsets or DST dates apart, to within a month
+fn rules<Tz: TimeZone>(tz: &Tz, now: DateTime<Utc>) -> u64 {
+    let mut hasher = Fnv1a::default();
+    for month in 1..=12 {
+        if let Some(at) = Utc.with_ymd_and_hms(now.year(), month, 1, 12, 0, 0).single() {
+            hasher.write(&offset_at(tz, at).to_le_bytes());
+        }
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::{America, Europe};
+
+    #[test]
+    fn test_dst_transitions_and_zone_changes_are_noticed() {
+        let before = Utc.with_ymd_and_hms(2024, 3, 31, 0, 0, 0).unwrap();
+        let mut watch = ZoneWatch::in_zone(&Europe::Berlin, before);
+        assert!(!watch.update_in(&Europe::Berlin, before + chrono::Duration::minutes(30)));
+
+        // Clocks go forward at 01:00 UTC
+        assert!(watch.update_in(&Europe::Berlin, before + chrono::Duration::hours(2)));
+        assert!(!watch.update_in(&Europe::Berlin, before + chrono::Duration::hours(3)));
+
+        // Paris keeps the same clocks as Berlin; New York doesn't
+        assert!(!watch.update_in(&Europe::Paris, before + chrono::Duration::hours(2)));
+        assert!(watch.update_in(&America::New_York, before + chrono::Duration::hours(2)));
+    }
+}
@@ -1,4 +1,6 @@
-use openchime::{CalendarEvent, Account, AlertInfo};
+use openchime::{CalendarEvent, Account, AlertInfo, AppError};
+use openchime::models::EventId;
+use openchime::utils::clock::SystemClock;
 use chrono::{Duration, Utc};
 use tempfile::NamedTempFile;
 use sqlx::SqlitePool;
@@ -21,8 +23,8 @@ async fn create_test_database() -> openchime::Database {
 fn create_test_event(minutes_from_now: i64, has_video: bool) -> CalendarEvent {
     let now = Utc::now();
     CalendarEvent {
-        id: Some(1),
-        external_id: "test-event".to_string(),
+        id: Some(EventId(1)),
+        external_id: "test-event".into(),
         account_id: 1,
         title: "Test Meeting".to_string(),
         description: Some("Test description".to_string()),
@@ -42,6 +44,18 @@ fn create_test_event(minutes_from_now: i64, has_video: bool) -> CalendarEvent {
         has_alerted: false,
         last_alert_threshold: None,
         is_dismissed: false,
+        last_snoozed_at: None,
+        is_all_day: false,
+        meeting_id: None,
+        meeting_passcode: None,
+        dial_in_number: None,
+        dial_in_conference_id: None,
+        title_override: None,
+        source_timezone: None,
+        snoozed_until: None,
+        location: None,
+        reminder_minutes: None,
+        is_free: false,
         created_at: now,
         updated_at: now,
     }
@@ -52,7 +66,7 @@ async fn test_alert_workflow_integration() {
     let db = create_test_database().await;
     let audio = Arc::new(openchime::AudioManager::new().unwrap());
     let shutdown = tokio_util::sync::CancellationToken::new();
-    let state = Arc::new(openchime::AppState { db: Arc::new(db), audio, shutdown });
+    let state = Arc::new(openchime::AppState { db: Arc::new(db), audio, shutdown, clock: Arc::new(SystemClock) });
     
     // Create test events
     let video_event = create_test_event(2, true); // 2 minutes away, has video
@@ -101,7 +115,7 @@ async fn test_alert_workflow_integration() {
     .unwrap();
     
     // Get the inserted event ID
-    let event_id: i64 = sqlx::query_scalar("SELECT last_insert_rowid()")
+    let event_id: EventId = sqlx::query_scalar("SELECT last_insert_rowid()")
         .fetch_one(&db_clone.pool)
         .await
         .unwrap();
@@ -116,10 +130,10 @@ async fn test_monitor_cycle_components() {
     let db = create_test_database().await;
     let audio = Arc::new(openchime::AudioManager::new().unwrap());
     let shutdown = tokio_util::sync::CancellationToken::new();
-    let state = Arc::new(openchime::AppState { db: Arc::new(db), audio, shutdown });
+    let state = Arc::new(openchime::AppState { db: Arc::new(db), audio, shutdown, clock: Arc::new(SystemClock) });
     
     // Test getting upcoming events (should be empty initially)
-    let events = openchime::get_upcoming_events(&state.db.pool).await.unwrap();
+    let events = openchime::get_upcoming_events(&state.db.pool, state.clock.as_ref(), Duration::minutes(60)).await.unwrap();
     assert!(events.is_empty());
     
     // Add a test account
@@ -172,7 +186,7 @@ async fn test_concurrent_alert_operations() {
     let db = create_test_database().await;
     let audio = Arc::new(openchime::AudioManager::new().unwrap());
     let shutdown = tokio_util::sync::CancellationToken::new();
-    let state = Arc::new(openchime::AppState { db: Arc::new(db), audio, shutdown });
+    let state = Arc::new(openchime::AppState { db: Arc::new(db), audio, shutdown, clock: Arc::new(SystemClock) });
     
     // Test concurrent access to alert functions
     let mut handles = vec![];
@@ -209,26 +223,70 @@ async fn test_alert_error_handling() {
     let db = create_test_database().await;
     let audio = Arc::new(openchime::AudioManager::new().unwrap());
     let shutdown = tokio_util::sync::CancellationToken::new();
-    let state = Arc::new(openchime::AppState { db: Arc::new(db), audio, shutdown });
+    let state = Arc::new(openchime::AppState { db: Arc::new(db), audio, shutdown, clock: Arc::new(SystemClock) });
     
     // Test manual alert with non-existent event
-    let result = openchime::alerts::trigger_manual_alert(99999, &state).await;
-    assert!(result.is_err());
-    assert!(result.unwrap_err().to_string().contains("Event not found"));
+    let result = openchime::alerts::trigger_manual_alert(EventId(99999), &state).await;
+    let err = result.unwrap_err();
+    assert!(matches!(err, AppError::NotFound(_)));
+    assert!(err.to_string().contains("Event 99999"));
     
     // Test getting upcoming events with empty database
-    let events = openchime::get_upcoming_events(&state.db.pool).await.unwrap();
+    let events = openchime::get_upcoming_events(&state.db.pool, state.clock.as_ref(), Duration::minutes(60)).await.unwrap();
     assert!(events.is_empty());
 }
 
+#[tokio::test]
+async fn test_snooze_and_dismiss_all() {
+    let db = create_test_database().await;
+    let account_id = db.add_account(&Account::new_proton("Work".to_string(), "https://example.com/cal.ics".to_string())).await.unwrap();
+    let start = Utc::now() + Duration::minutes(5);
+    let mut ids = vec![];
+    for (uid, snoozes) in [("standup", 0), ("review", 3), ("planning", 1)] {
+        let id = sqlx::query("INSERT INTO events (external_id, account_id, title, start_time, end_time, snooze_count) VALUES (?, ?, ?, ?, ?, ?)")
+            .bind(uid)
+            .bind(account_id)
+            .bind(uid)
+            .bind(start)
+            .bind(start + Duration::hours(1))
+            .bind(snoozes)
+            .execute(&db.pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+        ids.push(EventId(id));
+    }
+    let load = |id: EventId| {
+        let pool = db.pool.clone();
+        async move { openchime::database::events::get_by_id(&pool, id).await.unwrap().unwrap() }
+    };
+
+    // The event already at the snooze limit is skipped
+    let until = Utc::now() + Duration::minutes(10);
+    assert_eq!(db.snooze_events(&ids, until).await.unwrap(), 2);
+    let standup = load(ids[0]).await;
+    assert_eq!(standup.snooze_count, 1);
+    assert!(standup.last_snoozed_at.is_some());
+    assert_eq!(standup.snoozed_until.map(|u| u.timestamp()), Some(until.timestamp()));
+    let review = load(ids[1]).await;
+    assert_eq!(review.snooze_count, 3);
+    assert!(review.snoozed_until.is_none());
+    assert_eq!(load(ids[2]).await.snooze_count, 2);
+
+    db.dismiss_events(&ids[..2]).await.unwrap();
+    let remaining = openchime::database::events::get_between(&db.pool, start - Duration::minutes(1), start + Duration::minutes(1)).await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].id, Some(ids[2]));
+}
+
 #[test]
 fn test_alert_info_edge_cases() {
     let now = Utc::now();
     
     // Test event exactly at alert threshold
     let video_event_at_threshold = CalendarEvent {
-        id: Some(1),
-        external_id: "video-threshold".to_string(),
+        id: Some(EventId(1)),
+        external_id: "video-threshold".into(),
         account_id: 1,
         title: "Video Meeting at Threshold".to_string(),
         description: None,
@@ -240,15 +298,25 @@ fn test_alert_info_edge_cases() {
         has_alerted: false,
         last_alert_threshold: None,
         is_dismissed: false,
+        last_snoozed_at: None,
+        is_all_day: false,
+        meeting_id: None,
+        meeting_passcode: None,
+        dial_in_number: None,
+        dial_in_conference_id: None,
+        title_override: None,
+        source_timezone: None,
+        snoozed_until: None,
+        location: None,
+        reminder_minutes: None,
+        is_free: false,
         created_at: now,
         updated_at: now,
     };
     
-    let alert_info = AlertInfo::new(video_event_at_threshold.clone());
+    let alert_info = AlertInfo::new_at(video_event_at_threshold.clone(), now);
     assert!(matches!(alert_info.alert_type, openchime::models::AlertType::VideoMeeting));
-    // Allow for slight timing difference (2 or 3)
-    assert!(alert_info.minutes_remaining >= 2 && alert_info.minutes_remaining <= 3, 
-            "Expected ~3 minutes, got {}", alert_info.minutes_remaining);
+    assert_eq!(alert_info.minutes_remaining, 3);
     
     // Test regular event at threshold
     let regular_event_at_threshold = CalendarEvent {
@@ -257,9 +325,7 @@ fn test_alert_info_edge_cases() {
         ..video_event_at_threshold.clone()
     };
     
-    let alert_info = AlertInfo::new(regular_event_at_threshold);
+    let alert_info = AlertInfo::new_at(regular_event_at_threshold, now);
     assert!(matches!(alert_info.alert_type, openchime::models::AlertType::Meeting));
-    // Allow for slight timing difference (0 or 1)
-    assert!(alert_info.minutes_remaining >= 0 && alert_info.minutes_remaining <= 1,
-            "Expected ~1 minute, got {}", alert_info.minutes_remaining);
+    assert_eq!(alert_info.minutes_remaining, 1);
 }
\ No newline at end of file
@@ -120,24 +120,33 @@ fn test_sound_files_struct() {
 }
 
 #[test]
-fn test_alert_type_matching() {
-    let meeting_type = openchime::audio::AlertType::Meeting;
-    let video_type = openchime::audio::AlertType::VideoMeeting;
-    let test_type = openchime::audio::AlertType::Test;
-    
-    // Test that all variants can be matched
-    let all_types = vec![meeting_type, video_type, test_type];
-    for alert_type in all_types {
-        match alert_type {
-            AlertType::Meeting => assert!(true),
-            AlertType::VideoMeeting => assert!(true),
-            AlertType::SnoozeReminder => assert!(true),
-            AlertType::Test => assert!(true),
-            AlertType::Warning30m => assert!(true),
-            AlertType::Warning10m => assert!(true),
-            AlertType::Warning5m => assert!(true),
-            AlertType::Warning1m => assert!(true),
-        }
+fn test_alert_type_sounds() {
+    let temp_dir = TempDir::new().unwrap();
+    let sound_files = openchime::SoundFiles {
+        meeting_alert: temp_dir.path().join("meeting.wav"),
+        video_meeting_alert: temp_dir.path().join("video.wav"),
+        test_sound: temp_dir.path().join("test.wav"),
+        alert_30m: temp_dir.path().join("alert_30m.wav"),
+        alert_10m: temp_dir.path().join("alert_10m.wav"),
+        alert_5m: temp_dir.path().join("alert_5m.wav"),
+        alert_1m: temp_dir.path().join("alert_1m.wav"),
+    };
+
+    let expected = [
+        (AlertType::Meeting, "meeting.wav"),
+        (AlertType::VideoMeeting, "video.wav"),
+        (AlertType::SnoozeReminder, "meeting.wav"),
+        (AlertType::Test, "test.wav"),
+        (AlertType::Warning30m, "alert_30m.wav"),
+        (AlertType::Warning10m, "alert_10m.wav"),
+        (AlertType::Warning5m, "alert_5m.wav"),
+        (AlertType::Warning1m, "alert_1m.wav"),
+        (AlertType::Warning, "test.wav"),
+        (AlertType::FocusBreak, "alert_5m.wav"),
+        (AlertType::FocusWork, "alert_1m.wav"),
+    ];
+    for (alert_type, file) in expected {
+        assert_eq!(sound_files.for_alert(&alert_type), temp_dir.path().join(file), "{:?}", alert_type);
     }
 }
 
@@ -0,0 +1,851 @@
+use openchime::calendar::cache::IcsCache;
+use openchime::calendar::common::{sync_from_source, sync_from_source_with, SyncMode};
+use openchime::calendar::import;
+use openchime::calendar::source::MockCalendarSource;
+use openchime::database::{account_groups, accounts, attendees, checklists, events, tags};
+use openchime::{Account, CalendarEvent, Database, Rsvp};
+use chrono::{TimeZone, Utc};
+use sqlx::SqlitePool;
+use tempfile::NamedTempFile;
+
+const RECURRING: &str = include_str!("fixtures/recurring.ics");
+const CANCELLED: &str = include_str!("fixtures/cancelled.ics");
+const TIMEZONES: &str = include_str!("fixtures/timezones.ics");
+
+async fn create_test_database() -> Database {
+    let temp_file = NamedTempFile::new().unwrap();
+    let (_, path) = temp_file.keep().unwrap();
+    let db_path = format!("sqlite:{}", path.to_str().unwrap());
+
+    let pool = SqlitePool::connect(&db_path).await.unwrap();
+
+    let schema = include_str!("../src/database/schema.sql");
+    sqlx::query(schema).execute(&pool).await.unwrap();
+
+    let db = Database { pool };
+    // The fixtures are dated 2024; import them whatever today's date
+    set_sync_days_back(&db, 3650).await;
+    db
+}
+
+async fn set_sync_days_back(db: &Database, days: i32) {
+    let mut settings = db.get_settings().await.unwrap();
+    settings.sync_days_back = days;
+    db.update_settings(&settings).await.unwrap();
+}
+
+async fn create_test_account(db: &Database) -> Account {
+    let mut account = Account::new_proton(
+        "Fixture Calendar".to_string(),
+        "https://calendar.example.com/fixture.ics".to_string(),
+    );
+    account.id = Some(db.add_account(&account).await.unwrap());
+    account
+}
+
+async fn stored_events(db: &Database) -> Vec<CalendarEvent> {
+    sqlx::query_as::<_, CalendarEvent>(
+        "SELECT id, external_id, account_id, title, description, start_time, end_time, video_link, video_platform, snooze_count, has_alerted, last_alert_threshold, is_dismissed, is_all_day, created_at, updated_at FROM events ORDER BY start_time ASC"
+    )
+    .fetch_all(&db.pool)
+    .await
+    .unwrap()
+}
+
+fn find<'a>(events: &'a [CalendarEvent], uid: &str) -> &'a CalendarEvent {
+    events
+        .iter()
+        .find(|e| e.external_id.as_str() == uid)
+        .unwrap_or_else(|| panic!("event {} not stored", uid))
+}
+
+#[tokio::test]
+async fn test_sync_timezone_heavy_feed() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let source = MockCalendarSource::new(TIMEZONES);
+
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert!(result.success);
+    assert_eq!(result.events_added, 4);
+    assert_eq!(source.fetch_count(), 1);
+
+    let events = stored_events(&db).await;
+    assert_eq!(find(&events, "ny-winter@example.com").start_time, Utc.with_ymd_and_hms(2024, 1, 15, 14, 0, 0).unwrap());
+    assert_eq!(find(&events, "ny-summer@example.com").start_time, Utc.with_ymd_and_hms(2024, 7, 15, 13, 0, 0).unwrap());
+    assert_eq!(find(&events, "tokyo@example.com").start_time, Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap());
+    assert_eq!(find(&events, "utc@example.com").start_time, Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap());
+    assert!(events.iter().all(|e| e.account_id == account.id.unwrap()));
+}
+
+#[tokio::test]
+async fn test_resync_is_idempotent_and_picks_up_changes() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let source = MockCalendarSource::new(TIMEZONES);
+
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+
+    // Same feed again: nothing new, nothing changed
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.events_added, 0);
+    assert_eq!(result.events_updated, 0);
+
+    // A renamed event is updated in place
+    source.set_ics(TIMEZONES.replace("SUMMARY:Tokyo", "SUMMARY:Tokyo Standup"));
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.events_added, 0);
+    assert_eq!(result.events_updated, 1);
+    assert_eq!(result.changes.updated[0].title, "Tokyo Standup");
+
+    let events = stored_events(&db).await;
+    assert_eq!(events.len(), 4);
+    assert_eq!(find(&events, "tokyo@example.com").title, "Tokyo Standup");
+    assert_eq!(source.fetch_count(), 3);
+}
+
+#[tokio::test]
+async fn test_sync_preview_writes_nothing() {
+    let db = create_test_database().await;
+    let source = MockCalendarSource::new(TIMEZONES);
+
+    // An account that hasn't been added yet
+    let unsaved = Account::new_proton("New Calendar".to_string(), "https://calendar.example.com/new.ics".to_string());
+    let preview = sync_from_source_with(&source, &unsaved, &db.pool, SyncMode::Preview).await.unwrap();
+    assert_eq!(preview.events_added, 4);
+    assert!(preview.changes.added.iter().any(|e| e.title == "Tokyo"));
+    assert!(stored_events(&db).await.is_empty());
+    assert!(db.get_accounts().await.unwrap().is_empty());
+
+    // One that has: the preview counts changes against what's stored
+    let account = create_test_account(&db).await;
+    source.set_etag("\"v1\"");
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    source.set_ics(TIMEZONES.replace("SUMMARY:Tokyo", "SUMMARY:Tokyo Standup"));
+    let preview = sync_from_source_with(&source, &account, &db.pool, SyncMode::Preview).await.unwrap();
+    assert_eq!((preview.events_added, preview.events_updated), (0, 1));
+    assert_eq!(find(&stored_events(&db).await, "tokyo@example.com").title, "Tokyo");
+
+    // It only reads, so it doesn't wait on a sync holding the write lock
+    let mut writer = db.pool.begin().await.unwrap();
+    sqlx::query("UPDATE events SET title = 'Busy'").execute(&mut *writer).await.unwrap();
+    let preview = tokio::time::timeout(
+        std::time::Duration::from_secs(2),
+        sync_from_source_with(&source, &account, &db.pool, SyncMode::Preview),
+    )
+    .await
+    .expect("preview waited for the write lock")
+    .unwrap();
+    assert_eq!(preview.events_updated, 1);
+    writer.rollback().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_unchanged_feed_is_not_reparsed() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let source = MockCalendarSource::new(TIMEZONES);
+    source.set_etag("\"v1\"");
+
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.events_added, 4);
+
+    // The server says nothing changed, so whatever it would send is ignored
+    source.set_ics(TIMEZONES.replace("SUMMARY:Tokyo", "SUMMARY:Tokyo Standup"));
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert!(result.success);
+    assert_eq!(result.events_updated, 0);
+
+    source.set_etag("\"v2\"");
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.events_updated, 1);
+    assert_eq!(source.fetch_count(), 3);
+}
+
+#[tokio::test]
+async fn test_same_feed_content_is_not_reparsed() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    // No ETag or Last-Modified: the feed is downloaded every time
+    let source = MockCalendarSource::new(TIMEZONES);
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+
+    // Had the same body been parsed again, it would have put the title back
+    sqlx::query("UPDATE events SET title = 'Edited' WHERE external_id = 'tokyo@example.com'")
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert!(result.success);
+    assert_eq!(result.events_updated, 0);
+    assert_eq!(find(&stored_events(&db).await, "tokyo@example.com").title, "Edited");
+
+    source.set_ics(TIMEZONES.replace("SUMMARY:Tokyo", "SUMMARY:Tokyo Standup"));
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.events_updated, 1);
+    assert_eq!(source.fetch_count(), 3);
+}
+
+#[tokio::test]
+async fn test_feed_with_unstored_events_is_reparsed() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let source = MockCalendarSource::new(TIMEZONES);
+    source.set_etag("\"v1\"");
+
+    // One event can't be written; the rest of the feed still lands
+    sqlx::query(
+        "CREATE TRIGGER refuse_tokyo BEFORE INSERT ON events WHEN NEW.external_id = 'tokyo@example.com'
+            BEGIN SELECT RAISE(ABORT, 'disk I/O error'); END",
+    )
+    .execute(&db.pool)
+    .await
+    .unwrap();
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.events_added, 3);
+    assert_eq!(result.changes.failed, 1);
+
+    // Neither the ETag nor the body hash lets the same feed be skipped
+    sqlx::query("DROP TRIGGER refuse_tokyo").execute(&db.pool).await.unwrap();
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.events_added, 1);
+    find(&stored_events(&db).await, "tokyo@example.com");
+
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.events_added + result.events_updated, 0);
+    assert_eq!(source.fetch_count(), 3);
+}
+
+#[tokio::test]
+async fn test_floating_times_use_the_account_timezone() {
+    const FLOATING: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+        BEGIN:VEVENT\r\nUID:standup@example.com\r\nSUMMARY:Standup\r\n\
+        DTSTART:20240115T090000\r\nDTEND:20240115T091500\r\nEND:VEVENT\r\n\
+        BEGIN:VEVENT\r\nUID:offsite@example.com\r\nSUMMARY:Offsite\r\n\
+        DTSTART;VALUE=DATE:20240117\r\nEND:VEVENT\r\n\
+        END:VCALENDAR\r\n";
+    let db = create_test_database().await;
+    let mut account = create_test_account(&db).await;
+    let source = MockCalendarSource::new(FLOATING);
+
+    accounts::set_timezone(&db.pool, account.id.unwrap(), Some("Europe/Paris")).await.unwrap();
+    account.timezone = Some("Europe/Paris".to_string());
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    let events = stored_events(&db).await;
+    assert_eq!(find(&events, "standup@example.com").start_time, Utc.with_ymd_and_hms(2024, 1, 15, 8, 0, 0).unwrap());
+    // All-day events stay on their date whatever the zone
+    assert_eq!(find(&events, "offsite@example.com").start_time, Utc.with_ymd_and_hms(2024, 1, 17, 0, 0, 0).unwrap());
+
+    // A new zone re-parses the same feed
+    accounts::set_timezone(&db.pool, account.id.unwrap(), Some("America/New_York")).await.unwrap();
+    account.timezone = Some("America/New_York".to_string());
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.events_updated, 1);
+    let events = stored_events(&db).await;
+    assert_eq!(find(&events, "standup@example.com").start_time, Utc.with_ymd_and_hms(2024, 1, 15, 14, 0, 0).unwrap());
+}
+
+#[tokio::test]
+async fn test_sync_stores_the_calendar_name_and_color() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let feed = TIMEZONES.replacen("VERSION:2.0", "VERSION:2.0\r\nX-WR-CALNAME:Team CET\r\nX-APPLE-CALENDAR-COLOR:#FF2968", 1);
+    let source = MockCalendarSource::new(&feed);
+
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    let stored = accounts::get(&db.pool, account.id.unwrap()).await.unwrap().unwrap();
+    assert_eq!(stored.calendar_name.as_deref(), Some("Team CET"));
+    assert_eq!(stored.calendar_rgb(), Some([0xff, 0x29, 0x68]));
+}
+
+#[tokio::test]
+async fn test_feed_refresh_interval_replaces_the_global_one() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let default = chrono::Duration::minutes(5);
+
+    let feed = TIMEZONES.replacen("VERSION:2.0", "VERSION:2.0\r\nX-PUBLISHED-TTL:PT6H", 1);
+    sync_from_source(&MockCalendarSource::new(&feed), &account, &db.pool).await.unwrap();
+    let stored = accounts::get(&db.pool, account.id.unwrap()).await.unwrap().unwrap();
+    assert_eq!(stored.feed_refresh_interval, Some(6 * 3600));
+    assert_eq!(stored.sync_interval_duration(default), chrono::Duration::hours(6));
+
+    // A feed that stops asking goes back to the global interval
+    sync_from_source(&MockCalendarSource::new(TIMEZONES), &account, &db.pool).await.unwrap();
+    let stored = accounts::get(&db.pool, account.id.unwrap()).await.unwrap().unwrap();
+    assert_eq!(stored.sync_interval_duration(default), default);
+}
+
+#[tokio::test]
+async fn test_sync_recurring_feed() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let source = MockCalendarSource::new(RECURRING);
+
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+
+    let events = stored_events(&db).await;
+    let planning = find(&events, "one-off@example.com");
+    assert_eq!(planning.title, "Planning");
+
+    // The series and its override share a UID and never produce duplicate rows:
+    // the moved occurrence takes the place of the generated one
+    let series_rows: Vec<_> = events.iter().filter(|e| e.external_id.as_str().starts_with("weekly-sync@example.com")).collect();
+    assert_eq!(series_rows.len(), 4);
+    assert_eq!(series_rows.iter().filter(|e| e.external_id.as_str() == "weekly-sync@example.com_20240115T160000Z").count(), 1);
+    let moved = find(&events, "weekly-sync@example.com_20240115T160000Z");
+    assert_eq!(moved.title, "Weekly Sync (moved)");
+    assert_eq!(moved.start_time, Utc.with_ymd_and_hms(2024, 1, 16, 16, 0, 0).unwrap());
+}
+
+#[tokio::test]
+async fn test_sync_expands_and_prunes_series_occurrences() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let first = (Utc::now() - chrono::Duration::days(14)).format("%Y%m%d");
+    let feed = |count: u32| {
+        format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:standup@example.com\r\nSUMMARY:Standup\r\n\
+            DTSTART:{first}T160000Z\r\nDTEND:{first}T161500Z\r\nRRULE:FREQ=WEEKLY;COUNT={count}\r\n\
+            END:VEVENT\r\nEND:VCALENDAR\r\n"
+        )
+    };
+    let source = MockCalendarSource::new(feed(6));
+
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.events_added, 6);
+    let events = stored_events(&db).await;
+    assert_eq!(events.len(), 6);
+    assert!(events.iter().all(|e| e.external_id.as_str().starts_with("standup@example.com_")));
+
+    // The series is cut short: its later occurrences go away
+    source.set_ics(feed(3));
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.events_added, 0);
+    assert_eq!(result.changes.removed.len(), 3);
+    assert_eq!(stored_events(&db).await.len(), 3);
+}
+
+#[tokio::test]
+async fn test_events_deleted_upstream_are_removed() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let day = |offset: i64| (Utc::now() + chrono::Duration::days(offset)).format("%Y%m%d");
+    let event = |uid: &str, date| format!("BEGIN:VEVENT\r\nUID:{uid}\r\nSUMMARY:{uid}\r\nDTSTART:{date}T150000Z\r\nEND:VEVENT\r\n");
+    let feed = |events: &[String]| format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}END:VCALENDAR\r\n", events.concat());
+
+    let past = event("past@example.com", day(-60));
+    let kept = event("kept@example.com", day(2));
+    let gone = event("gone@example.com", day(3));
+    let source = MockCalendarSource::new(feed(&[past, kept.clone(), gone]));
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    let gone_id = find(&stored_events(&db).await, "gone@example.com").id.unwrap();
+
+    // Upcoming events that vanish go; ones from before the sync window stay for history
+    set_sync_days_back(&db, 30).await;
+    source.set_ics(feed(&[kept]));
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.events_deleted, 1);
+    assert_eq!(result.changes.removed, vec![gone_id]);
+    let events = stored_events(&db).await;
+    assert_eq!(events.len(), 2);
+    find(&events, "past@example.com");
+
+    // A feed that suddenly comes back empty deletes nothing
+    source.set_ics(feed(&[]));
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.events_deleted, 0);
+    assert_eq!(stored_events(&db).await.len(), 2);
+}
+
+#[tokio::test]
+async fn test_cancelled_events_keep_their_local_state() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let source = MockCalendarSource::new(CANCELLED.replace("STATUS:CANCELLED", "STATUS:CONFIRMED"));
+    let alerting = || async {
+        let events = events::get_needing_alert(&db.pool).await.unwrap();
+        events.into_iter().filter_map(|e| e.id).collect::<Vec<_>>()
+    };
+
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    let offsite_id = find(&stored_events(&db).await, "called-off@example.com").id.unwrap();
+    events::set_title_override(&db.pool, offsite_id, Some("Offsite")).await.unwrap();
+    assert!(alerting().await.contains(&offsite_id));
+
+    // Called off upstream, the renamed event is hidden rather than deleted
+    source.set_ics(CANCELLED);
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.changes.removed, vec![offsite_id]);
+    let removed_at: Option<chrono::DateTime<Utc>> = sqlx::query_scalar("SELECT removed_at FROM events WHERE id = ?")
+        .bind(offsite_id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    assert!(removed_at.is_some());
+    assert!(!alerting().await.contains(&offsite_id));
+}
+
+#[tokio::test]
+async fn test_unreadable_events_are_not_taken_for_deleted() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let day = |offset: i64| (Utc::now() + chrono::Duration::days(offset)).format("%Y%m%d");
+    let event = |uid: &str, date| format!("BEGIN:VEVENT\r\nUID:{uid}\r\nSUMMARY:{uid}\r\nDTSTART:{date}T150000Z\r\nEND:VEVENT\r\n");
+    let feed = |events: &[String]| format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}END:VCALENDAR\r\n", events.concat());
+
+    let kept = event("kept@example.com", day(2));
+    let series = |start: &str| format!("BEGIN:VEVENT\r\nUID:standup\r\nSUMMARY:Standup\r\nDTSTART:{start}\r\nRRULE:FREQ=DAILY;COUNT=2\r\nEND:VEVENT\r\n");
+    // Its own event, though its UID reads like one of the series' occurrences
+    let lookalike = format!("standup_{}", day(5));
+    let source = MockCalendarSource::new(feed(&[
+        kept.clone(),
+        event("garbled@example.com", day(3)),
+        series(&format!("{}T090000Z", day(2))),
+        event(&lookalike, day(5)),
+    ]));
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(stored_events(&db).await.len(), 5);
+
+    // Their start times got mangled upstream: still in the feed, so still
+    // here, occurrences and all. The lookalike really is gone.
+    let garbled = "BEGIN:VEVENT\r\nUID:garbled@example.com\r\nSUMMARY:Garbled\r\nDTSTART:soon\r\nEND:VEVENT\r\n".to_string();
+    source.set_ics(feed(&[kept, garbled, series("soon")]));
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.events_deleted, 1);
+    let events = stored_events(&db).await;
+    find(&events, "garbled@example.com");
+    assert_eq!(events.iter().filter(|e| e.title == "Standup").count(), 2);
+    assert!(events.iter().all(|e| e.external_id.as_str() != lookalike));
+}
+
+#[tokio::test]
+async fn test_removed_events_keep_their_local_state() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let day = |offset: i64| (Utc::now() + chrono::Duration::days(offset)).format("%Y%m%d");
+    let event = |uid: &str, date| format!("BEGIN:VEVENT\r\nUID:{uid}\r\nSUMMARY:{uid}\r\nDTSTART:{date}T150000Z\r\nEND:VEVENT\r\n");
+    let feed = |events: &[String]| format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}END:VCALENDAR\r\n", events.concat());
+    let visible = || async {
+        let events = events::get_between(&db.pool, Utc::now(), Utc::now() + chrono::Duration::days(365)).await.unwrap();
+        events.into_iter().map(|e| e.display_title().to_string()).collect::<Vec<_>>()
+    };
+
+    let kept = event("kept@example.com", day(2));
+    let renamed = event("renamed@example.com", day(3));
+    let far = event("far@example.com", day(200));
+    let source = MockCalendarSource::new(feed(&[kept.clone(), renamed.clone(), far]));
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    let renamed_id = find(&stored_events(&db).await, "renamed@example.com").id.unwrap();
+    events::set_title_override(&db.pool, renamed_id, Some("Renamed")).await.unwrap();
+    checklists::add(&db.pool, renamed_id, "Bring notes").await.unwrap();
+
+    // Gone from the feed, the renamed event is hidden rather than deleted.
+    // Events past a sync window that shrank weren't looked for, so they stay.
+    let mut settings = db.get_settings().await.unwrap();
+    settings.sync_days_ahead = 30;
+    db.update_settings(&settings).await.unwrap();
+    source.set_ics(feed(std::slice::from_ref(&kept)));
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.changes.removed, vec![renamed_id]);
+    assert_eq!(stored_events(&db).await.len(), 3);
+    assert_eq!(visible().await, vec!["kept@example.com", "far@example.com"]);
+
+    // Back in the feed, it's shown again as the user left it
+    source.set_ics(feed(&[kept, renamed]));
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(visible().await, vec!["kept@example.com", "Renamed", "far@example.com"]);
+    assert_eq!(checklists::get_for_event(&db.pool, renamed_id).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_sync_feed_with_cancelled_event() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let source = MockCalendarSource::new(CANCELLED);
+
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert!(result.success);
+
+    let events = stored_events(&db).await;
+    assert_eq!(find(&events, "still-on@example.com").title, "Design Review");
+    assert!(!events.iter().any(|e| e.external_id.as_str() == "called-off@example.com"));
+}
+
+#[tokio::test]
+async fn test_cancelling_an_event_removes_it() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let source = MockCalendarSource::new(CANCELLED.replace("STATUS:CANCELLED", "STATUS:CONFIRMED"));
+
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    let offsite_id = find(&stored_events(&db).await, "called-off@example.com").id.unwrap();
+
+    source.set_ics(CANCELLED);
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.changes.removed, vec![offsite_id]);
+    assert!(events::get_by_id(&db.pool, offsite_id).await.unwrap().is_none());
+    assert_eq!(stored_events(&db).await.len(), 1);
+}
+
+#[tokio::test]
+async fn test_sync_fetch_failure_leaves_events_untouched() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let source = MockCalendarSource::new(TIMEZONES);
+
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+
+    source.set_error("connection reset by peer");
+    let err = sync_from_source(&source, &account, &db.pool).await.unwrap_err();
+    assert!(err.to_string().contains("connection reset"));
+
+    assert_eq!(stored_events(&db).await.len(), 4);
+}
+
+#[tokio::test]
+async fn test_sync_truncated_feed_stores_nothing() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let source = MockCalendarSource::new("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nSUMMARY:Truncated");
+
+    // Whether the parser rejects the feed or finds no usable events, nothing is stored
+    let _ = sync_from_source(&source, &account, &db.pool).await;
+    assert!(stored_events(&db).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_sync_tags_events_by_keyword() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let source = MockCalendarSource::new(TIMEZONES);
+
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    let tokyo_id = find(&stored_events(&db).await, "tokyo@example.com").id.unwrap();
+    assert!(tags::get_for_event(&db.pool, tokyo_id).await.unwrap().is_empty());
+
+    // A tag the user added survives the automatic ones being recomputed
+    sqlx::query("INSERT INTO event_tags (event_id, tag, is_auto) VALUES (?, 'customer', 0)")
+        .bind(tokyo_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+    source.set_ics(TIMEZONES.replace("SUMMARY:Tokyo", "SUMMARY:Tokyo Standup"));
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(tags::get_for_event(&db.pool, tokyo_id).await.unwrap(), vec!["customer", "standup"]);
+
+    source.set_ics(TIMEZONES.replace("SUMMARY:Tokyo", "SUMMARY:Tokyo 1:1"));
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(tags::get_for_event(&db.pool, tokyo_id).await.unwrap(), vec!["1:1", "customer"]);
+
+    let counts = tags::count_by_tag(&db.pool).await.unwrap();
+    assert_eq!(counts, vec![("1:1".to_string(), 1), ("customer".to_string(), 1)]);
+}
+
+#[tokio::test]
+async fn test_title_override_survives_resync() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let source = MockCalendarSource::new(TIMEZONES);
+
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    let tokyo_id = find(&stored_events(&db).await, "tokyo@example.com").id.unwrap();
+    events::set_title_override(&db.pool, tokyo_id, Some("  Weekly sync w/ Kenji ")).await.unwrap();
+
+    // The feed renames the event; the local name stays
+    source.set_ics(TIMEZONES.replace("SUMMARY:Tokyo", "SUMMARY:Tokyo (moved rooms)"));
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.changes.updated[0].title_override.as_deref(), Some("Weekly sync w/ Kenji"));
+
+    let tokyo = events::get_by_id(&db.pool, tokyo_id).await.unwrap().unwrap();
+    assert_eq!(tokyo.title, "Tokyo (moved rooms)");
+    assert_eq!(tokyo.display_title(), "Weekly sync w/ Kenji");
+
+    // Clearing the name shows the feed's title again
+    events::set_title_override(&db.pool, tokyo_id, Some("")).await.unwrap();
+    let tokyo = events::get_by_id(&db.pool, tokyo_id).await.unwrap().unwrap();
+    assert_eq!(tokyo.display_title(), "Tokyo (moved rooms)");
+}
+
+#[tokio::test]
+async fn test_checklist_survives_resync() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let source = MockCalendarSource::new(TIMEZONES);
+
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    let tokyo_id = find(&stored_events(&db).await, "tokyo@example.com").id.unwrap();
+    let slides = checklists::add(&db.pool, tokyo_id, " Prepare slides ").await.unwrap();
+    checklists::add(&db.pool, tokyo_id, "Read the design doc").await.unwrap();
+    assert!(checklists::add(&db.pool, tokyo_id, "   ").await.is_err());
+
+    source.set_ics(TIMEZONES.replace("SUMMARY:Tokyo", "SUMMARY:Tokyo (moved rooms)"));
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+
+    let items = checklists::get_for_event(&db.pool, tokyo_id).await.unwrap();
+    let texts: Vec<_> = items.iter().map(|item| item.text.as_str()).collect();
+    assert_eq!(texts, vec!["Prepare slides", "Read the design doc"]);
+
+    checklists::set_done(&db.pool, slides.id, true).await.unwrap();
+    assert_eq!(checklists::unchecked(&db.pool, &[tokyo_id]).await.unwrap(), vec!["Read the design doc"]);
+
+    checklists::remove(&db.pool, slides.id).await.unwrap();
+    assert_eq!(checklists::get_for_event(&db.pool, tokyo_id).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_local_reminders_alert_but_never_sync() {
+    let db = create_test_database().await;
+    let start = Utc.with_ymd_and_hms(2030, 1, 1, 15, 0, 0).unwrap();
+
+    let reminder = events::add_reminder(&db.pool, " Call the dentist ", start, Some("https://zoom.us/j/123456789")).await.unwrap();
+    assert_eq!(reminder.title, "Call the dentist");
+    assert_eq!(reminder.video_platform.as_deref(), Some("Zoom"));
+    events::add_reminder(&db.pool, "Water the plants", start, None).await.unwrap();
+
+    // Both live under one built-in account
+    let accounts = db.get_accounts().await.unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert!(accounts[0].is_local());
+
+    let candidates = events::get_alert_candidates(&db.pool, start - chrono::Duration::hours(1), start, chrono::Duration::zero())
+        .await
+        .unwrap();
+    assert_eq!(candidates.len(), 2);
+
+    let cache_dir = tempfile::TempDir::new().unwrap();
+    let cache = IcsCache::new(cache_dir.path());
+    let result = openchime::calendar::sync_account(&accounts[0], &db.pool, &cache, &Default::default(), std::time::Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(result.events_added + result.events_updated, 0);
+    assert_eq!(stored_events(&db).await.len(), 2);
+}
+
+#[tokio::test]
+async fn test_group_with_alerts_off_stays_quiet() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let source = MockCalendarSource::new(CANCELLED);
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+
+    let (from, to) = (Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(), Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap());
+    let candidates = || events::get_alert_candidates(&db.pool, from, to, chrono::Duration::zero());
+    let alerting = candidates().await.unwrap().len();
+    assert!(alerting > 0);
+
+    let group_id = account_groups::add(&db.pool, "Volunteering").await.unwrap();
+    account_groups::assign(&db.pool, account.id.unwrap(), Some(group_id)).await.unwrap();
+    let mut group = account_groups::get_all(&db.pool).await.unwrap().remove(0);
+    group.alerts_enabled = false;
+    account_groups::update(&db.pool, &group).await.unwrap();
+    assert!(candidates().await.unwrap().is_empty());
+
+    // Events are still stored and come back once alerts are on again
+    assert!(!stored_events(&db).await.is_empty());
+    group.alerts_enabled = true;
+    account_groups::update(&db.pool, &group).await.unwrap();
+    assert_eq!(candidates().await.unwrap().len(), alerting);
+}
+
+#[tokio::test]
+async fn test_muted_account_stays_quiet_until_alerts_are_back_on() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    assert!(account.alerts_enabled);
+    let source = MockCalendarSource::new(CANCELLED);
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+
+    let (from, to) = (Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(), Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap());
+    let candidates = || events::get_alert_candidates(&db.pool, from, to, chrono::Duration::zero());
+    let alerting = candidates().await.unwrap().len();
+    assert!(alerting > 0);
+
+    accounts::set_alerts_enabled(&db.pool, account.id.unwrap(), false).await.unwrap();
+    assert!(!accounts::get(&db.pool, account.id.unwrap()).await.unwrap().unwrap().alerts_enabled);
+    assert!(candidates().await.unwrap().is_empty());
+
+    accounts::set_alerts_enabled(&db.pool, account.id.unwrap(), true).await.unwrap();
+    assert_eq!(candidates().await.unwrap().len(), alerting);
+}
+
+#[tokio::test]
+async fn test_informational_account_is_listed_but_never_alerts() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let source = MockCalendarSource::new(CANCELLED);
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+
+    let (from, to) = (Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(), Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap());
+    let candidates = || events::get_alert_candidates(&db.pool, from, to, chrono::Duration::zero());
+    let alerting = candidates().await.unwrap().len();
+    assert!(alerting > 0);
+
+    accounts::set_informational(&db.pool, account.id.unwrap(), true).await.unwrap();
+    assert!(candidates().await.unwrap().is_empty());
+    assert!(!stored_events(&db).await.is_empty());
+
+    accounts::set_informational(&db.pool, account.id.unwrap(), false).await.unwrap();
+    assert_eq!(candidates().await.unwrap().len(), alerting);
+}
+
+/// Serve `body` as a calendar feed on a local port, to any number of
+/// requests, and return its URL
+async fn serve_feed(body: String) -> String {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/feed.ics", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let response = format!("HTTP/1.1 200 OK\r\ncontent-type: text/calendar\r\ncontent-length: {}\r\n\r\n{}", body.len(), body);
+            tokio::spawn(async move {
+                let mut buf = vec![0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                let _ = socket.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+    url
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_sync_accounts_reports_every_account_in_order() {
+    // The app's own setup (WAL), so the syncs really do write side by side
+    let dir = tempfile::TempDir::new().unwrap();
+    let db = Database::open(&dir.path().join("parallel.db")).await.unwrap();
+    let day = |offset: i64| (Utc::now() + chrono::Duration::days(offset)).format("%Y%m%d");
+    let mut accounts = Vec::new();
+    for name in ["Work", "Holidays", "Team", "Gym", "Family", "Rota"] {
+        let events: String = (1..=20)
+            .map(|n| format!("BEGIN:VEVENT\r\nUID:{name}-{n}\r\nSUMMARY:{name} {n}\r\nDTSTART:{}T150000Z\r\nEND:VEVENT\r\n", day(n)))
+            .collect();
+        let url = serve_feed(format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{events}END:VCALENDAR\r\n")).await;
+        let mut account = Account::new_generic(name.to_string(), url);
+        account.id = Some(db.add_account(&account).await.unwrap());
+        accounts.push(account);
+    }
+
+    // More accounts than run at once; each still gets a result, in order.
+    // The timeout is generous so a loaded machine can't fail a sync for it.
+    assert!(accounts.len() > openchime::calendar::MAX_CONCURRENT_SYNCS);
+    // Feeds are cached beside the database, not in the user's cache
+    let cache = IcsCache::new(dir.path().join("ics"));
+    let results = openchime::calendar::sync_accounts(&accounts, &db.pool, &cache, &Default::default(), std::time::Duration::from_secs(120)).await;
+    let names: Vec<_> = results.iter().map(|(account, _)| account.account_name.as_str()).collect();
+    assert_eq!(names, vec!["Work", "Holidays", "Team", "Gym", "Family", "Rota"]);
+    for (account, result) in &results {
+        assert_eq!(result.as_ref().map(|r| r.events_added).ok(), Some(20), "{}", account.account_name);
+    }
+    let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM events").fetch_one(&db.pool).await.unwrap();
+    assert_eq!(stored, 120);
+    for account in &accounts {
+        assert!(cache.get(&account.auth_data, None).is_some(), "{}", account.account_name);
+    }
+}
+
+#[tokio::test]
+async fn test_sync_window_limits_what_is_imported() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let day = |offset: i64| (Utc::now() + chrono::Duration::days(offset)).format("%Y%m%d");
+    let event = |uid: &str, date| format!("BEGIN:VEVENT\r\nUID:{uid}\r\nSUMMARY:{uid}\r\nDTSTART:{date}T150000Z\r\nEND:VEVENT\r\n");
+    let source = MockCalendarSource::new(format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}{}{}END:VCALENDAR\r\n",
+        event("last-week@example.com", day(-7)),
+        event("next-week@example.com", day(7)),
+        event("next-year@example.com", day(300)),
+    ));
+
+    let mut settings = db.get_settings().await.unwrap();
+    settings.sync_days_back = 1;
+    settings.sync_days_ahead = 30;
+    db.update_settings(&settings).await.unwrap();
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    let uids: Vec<_> = stored_events(&db).await.iter().map(|e| e.external_id.as_str().to_string()).collect();
+    assert_eq!(uids, vec!["next-week@example.com"]);
+
+    // Widening the window brings the rest in; narrowing it again hides the
+    // far-off one, which stays stored since it wasn't looked for
+    settings.sync_days_ahead = 365;
+    db.update_settings(&settings).await.unwrap();
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(stored_events(&db).await.len(), 2);
+    assert_eq!(events::get_to_window_end(&db.pool, Utc::now()).await.unwrap().len(), 2);
+
+    settings.sync_days_ahead = 30;
+    db.update_settings(&settings).await.unwrap();
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.events_deleted, 0);
+    assert_eq!(stored_events(&db).await.len(), 2);
+    assert_eq!(events::get_to_window_end(&db.pool, Utc::now()).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_sync_stores_attendees_and_their_replies() {
+    let db = create_test_database().await;
+    let account = create_test_account(&db).await;
+    let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+        BEGIN:VEVENT\r\nUID:review@example.com\r\nSUMMARY:Design review\r\nDTSTART:20240311T150000Z\r\n\
+        ORGANIZER;CN=Dana:mailto:dana@example.com\r\n\
+        ATTENDEE;CN=Sam;PARTSTAT=NEEDS-ACTION:mailto:sam@example.com\r\nEND:VEVENT\r\n\
+        END:VCALENDAR\r\n";
+    let source = MockCalendarSource::new(ics);
+
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    let review_id = find(&stored_events(&db).await, "review@example.com").id.unwrap();
+    let people = attendees::get_for_event(&db.pool, review_id).await.unwrap();
+    let names: Vec<_> = people.iter().map(|a| (a.name.as_deref().unwrap(), a.is_organizer, a.rsvp)).collect();
+    assert_eq!(names, vec![("Dana", true, Rsvp::NeedsAction), ("Sam", false, Rsvp::NeedsAction)]);
+
+    // A reply alone doesn't change the event, but is picked up
+    source.set_ics(ics.replace("PARTSTAT=NEEDS-ACTION", "PARTSTAT=DECLINED"));
+    let result = sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert_eq!(result.events_updated, 0);
+    assert_eq!(attendees::get_for_event(&db.pool, review_id).await.unwrap()[1].rsvp, Rsvp::Declined);
+
+    // Deleting the event upstream takes its attendees with it
+    source.set_ics(ics.replace("UID:review@example.com", "UID:other@example.com"));
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+    assert!(attendees::get_for_event(&db.pool, review_id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_imported_invites_go_into_their_own_account() {
+    let db = create_test_database().await;
+    let invite = |summary: &str, method: &str, status: &str| {
+        format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nMETHOD:{method}\r\nBEGIN:VEVENT\r\nUID:offsite-invite@example.com\r\n\
+             SUMMARY:{summary}\r\nSTATUS:{status}\r\nDTSTART:20240304T090000Z\r\nDTEND:20240304T170000Z\r\n\
+             ORGANIZER;CN=Dana:mailto:dana@example.com\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n"
+        )
+    };
+
+    let result = import::import_ics(&db.pool, &invite("Offsite", "REQUEST", "CONFIRMED")).await.unwrap();
+    assert_eq!(result.events_added, 1);
+    let event_id = result.changes.added[0].id.unwrap();
+    assert_eq!(attendees::get_for_event(&db.pool, event_id).await.unwrap()[0].email, "dana@example.com");
+
+    // Reminders keep their own account
+    events::add_reminder(&db.pool, "Book train", Utc.with_ymd_and_hms(2030, 1, 1, 8, 0, 0).unwrap(), None).await.unwrap();
+    let accounts = db.get_accounts().await.unwrap();
+    assert_eq!(accounts.iter().map(|a| a.account_name.as_str()).collect::<Vec<_>>(), vec!["Imported", "Reminders"]);
+    assert!(accounts.iter().all(|a| a.is_local()));
+
+    // An updated invite, read from a file this time
+    let file = NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), invite("Offsite (new venue)", "REQUEST", "CONFIRMED")).unwrap();
+    let ics = import::read_input(file.path().to_str().unwrap()).await.unwrap();
+    let result = import::import_ics(&db.pool, &ics).await.unwrap();
+    assert_eq!((result.events_added, result.events_updated), (0, 1));
+    assert_eq!(find(&stored_events(&db).await, "offsite-invite@example.com").title, "Offsite (new venue)");
+
+    // Then its cancellation
+    let result = import::import_ics(&db.pool, &invite("Offsite (new venue)", "CANCEL", "CANCELLED")).await.unwrap();
+    assert_eq!(result.changes.removed, vec![event_id]);
+    assert_eq!(stored_events(&db).await.len(), 1);
+
+    assert!(import::read_input("not an ics file.txt").await.is_err());
+    assert!(import::import_ics(&db.pool, "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nEND:VCALENDAR\r\n").await.is_err());
+}
@@ -0,0 +1,124 @@
+use openchime::alerts::check_alert_thresholds;
+use openchime::calendar::common::sync_from_source;
+use openchime::calendar::source::MockCalendarSource;
+use openchime::utils::clock::{Clock, MockClock};
+use openchime::{Account, CalendarEvent, Database, Settings};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use chrono_tz::Tz;
+use sqlx::SqlitePool;
+use tempfile::NamedTempFile;
+
+const DST: &str = include_str!("fixtures/dst.ics");
+
+async fn create_test_database() -> Database {
+    let temp_file = NamedTempFile::new().unwrap();
+    let (_, path) = temp_file.keep().unwrap();
+    let db_path = format!("sqlite:{}", path.to_str().unwrap());
+
+    let pool = SqlitePool::connect(&db_path).await.unwrap();
+
+    let schema = include_str!("../src/database/schema.sql");
+    sqlx::query(schema).execute(&pool).await.unwrap();
+
+    let db = Database { pool };
+    // The fixture is dated 2024; import it whatever today's date
+    let settings = Settings { sync_days_back: 3650, ..db.get_settings().await.unwrap() };
+    db.update_settings(&settings).await.unwrap();
+    db
+}
+
+/// Sync the DST fixture and return the stored events
+async fn synced_events() -> Vec<CalendarEvent> {
+    let db = create_test_database().await;
+    let mut account = Account::new_proton(
+        "DST Calendar".to_string(),
+        "https://calendar.example.com/dst.ics".to_string(),
+    );
+    account.id = Some(db.add_account(&account).await.unwrap());
+
+    let source = MockCalendarSource::new(DST);
+    sync_from_source(&source, &account, &db.pool).await.unwrap();
+
+    sqlx::query_as::<_, CalendarEvent>(
+        "SELECT id, external_id, account_id, title, description, start_time, end_time, video_link, video_platform, snooze_count, has_alerted, last_alert_threshold, is_dismissed, is_all_day, created_at, updated_at FROM events ORDER BY start_time ASC"
+    )
+    .fetch_all(&db.pool)
+    .await
+    .unwrap()
+}
+
+fn find<'a>(events: &'a [CalendarEvent], uid: &str) -> &'a CalendarEvent {
+    events
+        .iter()
+        .find(|e| e.external_id.as_str() == uid)
+        .unwrap_or_else(|| panic!("event {} not stored", uid))
+}
+
+fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+}
+
+fn wall_clock(at: DateTime<Utc>, tz: Tz) -> String {
+    at.with_timezone(&tz).format("%Y-%m-%d %H:%M").to_string()
+}
+
+#[tokio::test]
+async fn test_events_keep_their_wall_clock_time_across_dst() {
+    let events = synced_events().await;
+    assert_eq!(events.len(), 5);
+
+    let ny = find(&events, "ny-after-spring@example.com");
+    assert_eq!(ny.start_time, utc(2024, 3, 11, 13, 0));
+    assert_eq!(wall_clock(ny.start_time, chrono_tz::America::New_York), "2024-03-11 09:00");
+
+    let london = find(&events, "london-after-spring@example.com");
+    assert_eq!(london.start_time, utc(2024, 4, 2, 8, 0));
+    assert_eq!(wall_clock(london.start_time, chrono_tz::Europe::London), "2024-04-02 09:00");
+
+    let sydney = find(&events, "sydney-after-autumn@example.com");
+    assert_eq!(sydney.start_time, utc(2024, 4, 7, 23, 0));
+    assert_eq!(wall_clock(sydney.start_time, chrono_tz::Australia::Sydney), "2024-04-08 09:00");
+}
+
+#[tokio::test]
+async fn test_skipped_and_repeated_local_times_are_kept() {
+    let events = synced_events().await;
+
+    // 02:30 doesn't exist on spring-forward night; it lands at 03:30 EDT
+    let gap = find(&events, "ny-gap@example.com");
+    assert_eq!(gap.start_time, utc(2024, 3, 10, 7, 30));
+    assert_eq!(wall_clock(gap.start_time, chrono_tz::America::New_York), "2024-03-10 03:30");
+
+    // 01:30 happens twice on fall-back night; the first (EDT) one is used
+    let repeated = find(&events, "ny-repeated@example.com");
+    assert_eq!(repeated.start_time, utc(2024, 11, 3, 5, 30));
+}
+
+#[tokio::test]
+async fn test_alerts_fire_at_wall_clock_time_after_a_dst_switch() {
+    let events = synced_events().await;
+    let settings = Settings::default();
+
+    let cases = [
+        ("ny-after-spring@example.com", utc(2024, 3, 9, 12, 0)),
+        ("london-after-spring@example.com", utc(2024, 3, 30, 12, 0)),
+        ("sydney-after-autumn@example.com", utc(2024, 4, 6, 0, 0)),
+    ];
+
+    for (uid, synced_at) in cases {
+        let event = find(&events, uid);
+
+        // Synced before the switch, alerted after it
+        let clock = MockClock::new(synced_at);
+        assert!(check_alert_thresholds(event, &settings, &clock).is_none(), "{} alerted early", uid);
+
+        clock.set(event.start_time - Duration::minutes(6));
+        assert!(check_alert_thresholds(event, &settings, &clock).is_none(), "{} alerted an hour off", uid);
+
+        clock.advance(Duration::minutes(1));
+        let (threshold, _) = check_alert_thresholds(event, &settings, &clock)
+            .unwrap_or_else(|| panic!("{} did not alert 5 minutes before start", uid));
+        assert_eq!(threshold, 5);
+        assert_eq!((event.start_time - clock.now()).num_minutes(), 5);
+    }
+}